@@ -35,6 +35,8 @@ pub fn execute(
         MemoryLoop {} => execute_memory_loop(),
         MessageLoop {} => execute_message_loop(env),
         AllocateLargeMemory { pages } => execute_allocate_large_memory(pages),
+        AllocateLargeVec { bytes } => execute_allocate_large_vec(bytes),
+        GrowMemoryToLimit {} => execute_grow_memory_to_limit(),
         Panic {} => execute_panic(),
         Unreachable {} => execute_unreachable(),
         MirrorEnv {} => execute_mirror_env(env),
@@ -120,6 +122,38 @@ fn execute_allocate_large_memory(pages: u32) -> Result<Response, ContractError>
     Err(StdError::generic_err("Unsupported architecture").into())
 }
 
+fn execute_allocate_large_vec(bytes: u32) -> Result<Response, ContractError> {
+    // The content depends on the index and is read again below. This ensures the
+    // allocation is not optimized away.
+    let data: Vec<u8> = (0..bytes).map(|i| (i % 251) as u8).collect();
+    let checksum = data
+        .iter()
+        .fold(0u32, |acc, byte| acc.rotate_left(5) ^ u32::from(*byte));
+    Ok(Response::new().set_data(checksum.to_be_bytes()))
+}
+
+fn execute_grow_memory_to_limit() -> Result<Response, ContractError> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use core::arch::wasm32;
+
+        // Once memory.grow fails, the allocator cannot get new pages anymore. So we allocate
+        // some heap memory upfront, which is freed for creating the response.
+        let reserve = vec![1u8; 256 * 1024];
+        // The volatile read ensures the reserve is not optimized away
+        unsafe { core::ptr::read_volatile(reserve.as_ptr()) };
+
+        while wasm32::memory_grow(0, 1) != usize::max_value() {}
+        let pages = wasm32::memory_size(0);
+
+        drop(reserve);
+        Ok(Response::new().set_data((pages as u32).to_be_bytes()))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    Err(StdError::generic_err("Unsupported architecture").into())
+}
+
 fn execute_panic() -> Result<Response, ContractError> {
     // Uncomment your favourite panic case
 
@@ -220,4 +254,23 @@ mod tests {
         let msg = ExecuteMsg::Debug {};
         execute(deps.as_mut(), mock_env(), mock_info("caller", &[]), msg).unwrap();
     }
+
+    #[test]
+    fn allocate_large_vec_works() {
+        let mut deps = setup();
+
+        let msg = ExecuteMsg::AllocateLargeVec { bytes: 0 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("caller", &[]), msg).unwrap();
+        assert_eq!(res.data.unwrap(), [0, 0, 0, 0]);
+
+        let msg = ExecuteMsg::AllocateLargeVec { bytes: 2 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("caller", &[]), msg).unwrap();
+        assert_eq!(res.data.unwrap(), [0, 0, 0, 1]);
+
+        let msg = ExecuteMsg::AllocateLargeVec {
+            bytes: 3 * 1024 * 1024,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("caller", &[]), msg).unwrap();
+        assert_eq!(res.data.unwrap().len(), 4);
+    }
 }
@@ -19,6 +19,11 @@ pub enum ExecuteMsg {
     MessageLoop {},
     /// Allocate large amounts of memory without consuming much gas
     AllocateLargeMemory { pages: u32 },
+    /// Allocate a vector of the given size and fill it, such that the allocator has to grow the memory
+    AllocateLargeVec { bytes: u32 },
+    /// Grow memory page by page until memory.grow fails at the limit of the instance.
+    /// Returns the number of pages reached.
+    GrowMemoryToLimit {},
     /// Trigger a panic to ensure framework handles gracefully
     Panic {},
     /// In contrast to Panic, this does not use the panic handler.
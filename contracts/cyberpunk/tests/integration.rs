@@ -17,10 +17,11 @@
 //!      });
 //! 4. Anywhere you see query(&deps, ...) you must replace it with query(&mut deps, ...)
 
-use cosmwasm_std::{from_binary, Empty, Env, Response};
+use cosmwasm_std::{from_binary, to_vec, ContractResult, Empty, Env, Response};
 use cosmwasm_vm::testing::{
     execute, instantiate, mock_env, mock_info, mock_instance, mock_instance_with_gas_limit, query,
 };
+use cosmwasm_vm::{call_execute, VmError};
 use std::io::Write;
 use std::time::SystemTime;
 use tempfile::NamedTempFile;
@@ -162,3 +163,51 @@ fn test_env() {
 
     assert_eq!(received_env, env);
 }
+
+#[test]
+fn grow_memory_to_limit() {
+    // 16 MiB, the memory limit of mock_instance
+    const LIMIT_PAGES: u32 = 256;
+
+    let mut deps = mock_instance(WASM, &[]);
+
+    let _res: Response =
+        instantiate(&mut deps, mock_env(), mock_info("admin", &[]), Empty {}).unwrap();
+
+    let msg = ExecuteMsg::GrowMemoryToLimit {};
+    let res: Response = execute(&mut deps, mock_env(), mock_info("caller", &[]), msg).unwrap();
+    assert_eq!(res.data.unwrap(), LIMIT_PAGES.to_be_bytes());
+    assert_eq!(deps.memory_pages(), LIMIT_PAGES as usize);
+
+    // further growth fails in the contract
+    let msg = ExecuteMsg::AllocateLargeMemory { pages: 1 };
+    let res: ContractResult<Response> =
+        execute(&mut deps, mock_env(), mock_info("caller", &[]), msg);
+    assert_eq!(res.unwrap_err(), "Generic error: memory.grow failed");
+}
+
+#[test]
+fn allocate_large_vec() {
+    let mut deps = mock_instance(WASM, &[]);
+
+    let _res: Response =
+        instantiate(&mut deps, mock_env(), mock_info("admin", &[]), Empty {}).unwrap();
+
+    // fits in the memory limit of 16 MiB
+    let msg = ExecuteMsg::AllocateLargeVec {
+        bytes: 8 * 1024 * 1024,
+    };
+    let _res: Response = execute(&mut deps, mock_env(), mock_info("caller", &[]), msg).unwrap();
+    assert!(deps.memory_pages() > 128);
+
+    // exceeding the memory limit aborts the execution
+    let msg = to_vec(&ExecuteMsg::AllocateLargeVec {
+        bytes: 32 * 1024 * 1024,
+    })
+    .unwrap();
+    let err =
+        call_execute::<_, _, _, Empty>(&mut deps, &mock_env(), &mock_info("caller", &[]), &msg)
+            .unwrap_err();
+    assert!(matches!(err, VmError::RuntimeErr { .. }), "{err:?}");
+    assert!(deps.memory_pages() <= 256);
+}
@@ -0,0 +1,128 @@
+// Code generated by cosmwasm-schema. DO NOT EDIT.
+
+export interface InstantiateMsg {
+  beneficiary: string;
+  verifier: string;
+}
+
+export type ExecuteMsg =
+  /** Releasing all funds in the contract to the beneficiary. This is the only "proper" action of this demo contract. */
+  | {
+    release: {};
+  }
+  /** Infinite loop to burn cpu cycles (only run when metering is enabled) */
+  | {
+    cpu_loop: {};
+  }
+  /** Infinite loop making storage calls (to test when their limit hits) */
+  | {
+    storage_loop: {};
+  }
+  /** Infinite loop reading and writing memory */
+  | {
+    memory_loop: {};
+  }
+  /** Infinite loop sending message to itself */
+  | {
+    message_loop: {};
+  }
+  /** Allocate large amounts of memory without consuming much gas */
+  | {
+    allocate_large_memory: {
+      pages: number;
+    };
+  }
+  /** Trigger a panic to ensure framework handles gracefully */
+  | {
+    panic: {};
+  }
+  /** Starting with CosmWasm 0.10, some API calls return user errors back to the contract. This triggers such user errors, ensuring the transaction does not fail in the backend. */
+  | {
+    user_errors_in_api_calls: {};
+  };
+
+export type QueryMsg =
+  /** returns a human-readable representation of the verifier use to ensure query path works in integration tests */
+  | {
+    verifier: {};
+  }
+  /** This returns cosmwasm_std::AllBalanceResponse to demo use of the querier */
+  | {
+    other_balance: {
+      address: string;
+    };
+  }
+  /** Recurse will execute a query into itself up to depth-times and return Each step of the recursion may perform some extra work to test gas metering (`work` rounds of sha256 on contract). Now that we have Env, we can auto-calculate the address to recurse into */
+  | {
+    recurse: {
+      depth: number;
+      work: number;
+    };
+  }
+  /** GetInt returns a hardcoded u32 value */
+  | {
+    get_int: {};
+  };
+
+/**
+ * MigrateMsg allows a privileged contract administrator to run a migration on the contract. In this (demo) case it is just migrating from one hackatom code to the same code, but taking advantage of the migration step to set a new validator.
+ *
+ * Note that the contract doesn't enforce permissions here, this is done by blockchain logic (in the future by blockchain governance)
+ */
+export interface MigrateMsg {
+  verifier: string;
+}
+
+/** SudoMsg is only exposed for internal Cosmos SDK modules to call. This is showing how we can expose "admin" functionality than can not be called by external users or contracts, but only trusted (native/Go) code in the blockchain */
+export type SudoMsg =
+  | {
+    steal_funds: {
+      amount: Coin[];
+      recipient: string;
+    };
+  };
+
+export interface IntResponse {
+  int: number;
+}
+
+export interface AllBalanceResponse {
+  /** Returns all non-zero coins held by this account. */
+  amount: Coin[];
+}
+
+export interface RecurseResponse {
+  /** hashed is the result of running sha256 "work+1" times on the contract's human address */
+  hashed: Binary;
+}
+
+export interface VerifierResponse {
+  verifier: string;
+}
+
+export interface Coin {
+  amount: Uint128;
+  denom: string;
+}
+
+/**
+ * A thin wrapper around u128 that is using strings for JSON encoding/decoding, such that the full u128 range can be used for clients that convert JSON numbers to floats, like JavaScript and jq.
+ *
+ * # Examples
+ *
+ * Use `from` to create instances of this and `u128` to get the value out:
+ *
+ * ``` # use cosmwasm_std::Uint128; let a = Uint128::from(123u128); assert_eq!(a.u128(), 123);
+ *
+ * let b = Uint128::from(42u64); assert_eq!(b.u128(), 42);
+ *
+ * let c = Uint128::from(70u32); assert_eq!(c.u128(), 70); ```
+ */
+export type Uint128 = string;
+
+/**
+ * Binary is a wrapper around Vec<u8> to add base64 de/serialization with serde. It also adds some helper methods to help encode inline.
+ *
+ * This is only needed as serde-json-{core,wasm} has a horrible encoding for Vec<u8>. See also <https://github.com/CosmWasm/cosmwasm/blob/main/docs/MESSAGE_TYPES.md>.
+ */
+export type Binary = string;
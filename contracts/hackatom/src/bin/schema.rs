@@ -9,5 +9,6 @@ fn main() {
         execute: ExecuteMsg,
         sudo: SudoMsg,
         migrate: MigrateMsg,
+        typescript: true,
     }
 }
@@ -0,0 +1,142 @@
+use cosmwasm_std::{
+    entry_point, to_binary, Binary, Deps, DepsMut, Env, IbcMsg, MessageInfo, QueryResponse,
+    Response, StdResult,
+};
+
+use crate::ibc::PACKET_LIFETIME;
+use crate::ibc_msg::PacketMsg;
+use crate::msg::{ChannelInfo, ExecuteMsg, InstantiateMsg, ListChannelsResponse, QueryMsg};
+use crate::state::{load_channel, range_channels, save_channel};
+
+#[entry_point]
+pub fn instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Send {
+            channel_id,
+            payload,
+            timeout,
+        } => handle_send(deps, env, channel_id, payload, timeout),
+        ExecuteMsg::Close { channel_id } => handle_close(deps, channel_id),
+    }
+}
+
+pub fn handle_send(
+    deps: DepsMut,
+    env: Env,
+    channel_id: String,
+    payload: Binary,
+    timeout: Option<u64>,
+) -> StdResult<Response> {
+    // ensure the channel exists (not found if not connected)
+    let mut channel = load_channel(deps.storage, &channel_id)?;
+    channel.sent += 1;
+    save_channel(deps.storage, &channel_id, &channel)?;
+
+    // construct a packet to send
+    let packet = PacketMsg::Echo { payload };
+    let timeout = timeout.unwrap_or(PACKET_LIFETIME);
+    let msg = IbcMsg::SendPacket {
+        channel_id: channel_id.clone(),
+        data: to_binary(&packet)?,
+        timeout: env.block.time.plus_seconds(timeout).into(),
+    };
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "handle_send")
+        .add_attribute("channel_id", channel_id))
+}
+
+pub fn handle_close(deps: DepsMut, channel_id: String) -> StdResult<Response> {
+    // ensure the channel exists (not found if not connected)
+    load_channel(deps.storage, &channel_id)?;
+
+    // the channel is removed from state once the closing completes in ibc_channel_close
+    let msg = IbcMsg::CloseChannel {
+        channel_id: channel_id.clone(),
+    };
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "handle_close")
+        .add_attribute("channel_id", channel_id))
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
+    match msg {
+        QueryMsg::ListChannels {} => to_binary(&query_list_channels(deps)?),
+        QueryMsg::Channel { channel_id } => to_binary(&query_channel(deps, channel_id)?),
+    }
+}
+
+fn query_channel(deps: Deps, channel_id: String) -> StdResult<ChannelInfo> {
+    let channel = load_channel(deps.storage, &channel_id)?;
+    Ok(ChannelInfo::convert(channel_id, channel))
+}
+
+fn query_list_channels(deps: Deps) -> StdResult<ListChannelsResponse> {
+    let channels = range_channels(deps.storage)
+        .map(|r| r.map(|(channel_id, channel)| ChannelInfo::convert(channel_id, channel)))
+        .collect::<StdResult<_>>()?;
+    Ok(ListChannelsResponse { channels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_binary, StdError};
+
+    const CREATOR: &str = "creator";
+
+    #[test]
+    fn instantiate_works() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let info = mock_info(CREATOR, &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // no channels are connected yet
+        let r = query(deps.as_ref(), mock_env(), QueryMsg::ListChannels {}).unwrap();
+        let channels: ListChannelsResponse = from_binary(&r).unwrap();
+        assert_eq!(0, channels.channels.len());
+    }
+
+    #[test]
+    fn send_requires_connected_channel() {
+        let mut deps = mock_dependencies();
+        let info = mock_info(CREATOR, &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), InstantiateMsg {}).unwrap();
+
+        let msg = ExecuteMsg::Send {
+            channel_id: "channel-7".into(),
+            payload: b"hello".into(),
+            timeout: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, StdError::NotFound { .. }));
+
+        let msg = ExecuteMsg::Close {
+            channel_id: "channel-7".into(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, StdError::NotFound { .. }));
+    }
+}
@@ -0,0 +1,431 @@
+use cosmwasm_std::{
+    entry_point, from_slice, to_binary, Binary, DepsMut, Env, Ibc3ChannelOpenResponse,
+    IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcChannelOpenResponse, IbcOrder, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, Never, StdError, StdResult, Storage,
+};
+
+use crate::ibc_msg::{AcknowledgementMsg, EchoResponse, PacketMsg};
+use crate::state::{load_channel, may_load_channel, remove_channel, save_channel, ChannelData};
+
+pub const IBC_APP_VERSION: &str = "ibc-echo-v1";
+
+/// packets live one hour unless the sender sets a different timeout
+pub const PACKET_LIFETIME: u64 = 60 * 60;
+
+#[entry_point]
+/// enforces ordering and versioning constraints
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> StdResult<IbcChannelOpenResponse> {
+    let channel = msg.channel();
+
+    // packets are independent of each other and a timeout must not close the channel
+    if channel.order != IbcOrder::Unordered {
+        return Err(StdError::generic_err("Only supports unordered channels"));
+    }
+
+    // An empty version in OpenInit lets us choose the version
+    if !channel.version.is_empty() && channel.version != IBC_APP_VERSION {
+        return Err(StdError::generic_err(format!(
+            "Must set version to `{}`",
+            IBC_APP_VERSION
+        )));
+    }
+
+    if let Some(counter_version) = msg.counterparty_version() {
+        if counter_version != IBC_APP_VERSION {
+            return Err(StdError::generic_err(format!(
+                "Counterparty version must be `{}`",
+                IBC_APP_VERSION
+            )));
+        }
+    }
+
+    Ok(Some(Ibc3ChannelOpenResponse {
+        version: IBC_APP_VERSION.to_string(),
+    }))
+}
+
+#[entry_point]
+/// once it's established, we start counting packets on the channel
+pub fn ibc_channel_connect(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> StdResult<IbcBasicResponse> {
+    if let Some(counter_version) = msg.counterparty_version() {
+        if counter_version != IBC_APP_VERSION {
+            return Err(StdError::generic_err(format!(
+                "Counterparty version must be `{}`",
+                IBC_APP_VERSION
+            )));
+        }
+    }
+
+    let channel_id = &msg.channel().endpoint.channel_id;
+    save_channel(deps.storage, channel_id, &ChannelData::default())?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_connect")
+        .add_attribute("channel_id", channel_id))
+}
+
+#[entry_point]
+/// On closed channel, simply delete the statistics from our local store
+pub fn ibc_channel_close(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> StdResult<IbcBasicResponse> {
+    let channel_id = &msg.channel().endpoint.channel_id;
+    remove_channel(deps.storage, channel_id);
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_close")
+        .add_attribute("channel_id", channel_id))
+}
+
+// this encode an error or error message into a proper acknowledgement to the recevier
+fn encode_ibc_error(msg: impl Into<String>) -> Binary {
+    // this cannot error, unwrap to keep the interface simple
+    to_binary(&AcknowledgementMsg::<()>::Err(msg.into())).unwrap()
+}
+
+#[entry_point]
+/// we send the payload of the packet back in the acknowledgement.
+/// Invalid packets are acknowledged with an error.
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, Never> {
+    // put this in a closure so we can convert all error responses into acknowledgements
+    (|| {
+        let packet = msg.packet;
+        // which local channel did this packet come on
+        let caller = packet.dest.channel_id;
+        let msg: PacketMsg = from_slice(&packet.data)?;
+        match msg {
+            PacketMsg::Echo { payload } => receive_echo(deps, caller, payload),
+        }
+    })()
+    .or_else(|e| {
+        // we try to capture all app-level errors and convert them into
+        // acknowledgement packets that contain an error code.
+        let acknowledgement = encode_ibc_error(format!("invalid packet: {}", e));
+        Ok(IbcReceiveResponse::new()
+            .set_ack(acknowledgement)
+            .add_attribute("action", "receive_error"))
+    })
+}
+
+// processes PacketMsg::Echo variant
+fn receive_echo(deps: DepsMut, caller: String, payload: Binary) -> StdResult<IbcReceiveResponse> {
+    let mut channel = load_channel(deps.storage, &caller)?;
+    channel.received += 1;
+    save_channel(deps.storage, &caller, &channel)?;
+
+    let acknowledgement = to_binary(&AcknowledgementMsg::Ok(EchoResponse { payload }))?;
+    Ok(IbcReceiveResponse::new()
+        .set_ack(acknowledgement)
+        .add_attribute("action", "receive_echo"))
+}
+
+#[entry_point]
+pub fn ibc_packet_ack(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketAckMsg,
+) -> StdResult<IbcBasicResponse> {
+    // which local channel was this packet send from
+    let caller = msg.original_packet.src.channel_id;
+    // we need to parse the ack based on our request
+    let packet: PacketMsg = from_slice(&msg.original_packet.data)?;
+    match packet {
+        PacketMsg::Echo { .. } => {
+            let res: AcknowledgementMsg<EchoResponse> = from_slice(&msg.acknowledgement.data)?;
+            acknowledge_echo(deps.storage, &caller, res)
+        }
+    }
+}
+
+// receive PacketMsg::Echo response
+fn acknowledge_echo(
+    storage: &mut dyn Storage,
+    caller: &str,
+    ack: AcknowledgementMsg<EchoResponse>,
+) -> StdResult<IbcBasicResponse> {
+    let res = IbcBasicResponse::new().add_attribute("action", "acknowledge_echo");
+    // the channel may have been closed in the meantime
+    let mut channel = match may_load_channel(storage, caller)? {
+        Some(channel) => channel,
+        None => return Ok(res),
+    };
+    let res = match ack {
+        AcknowledgementMsg::Ok(EchoResponse { payload }) => {
+            channel.acknowledged += 1;
+            channel.last_echo = Some(payload);
+            res
+        }
+        AcknowledgementMsg::Err(e) => {
+            channel.failed += 1;
+            res.add_attribute("error", e)
+        }
+    };
+    save_channel(storage, caller, &channel)?;
+    Ok(res)
+}
+
+#[entry_point]
+/// we count timed out packets to show them in queries
+pub fn ibc_packet_timeout(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> StdResult<IbcBasicResponse> {
+    let caller = msg.packet.src.channel_id;
+    // the channel may have been closed in the meantime
+    if let Some(mut channel) = may_load_channel(deps.storage, &caller)? {
+        channel.timed_out += 1;
+        save_channel(deps.storage, &caller, &channel)?;
+    }
+    Ok(IbcBasicResponse::new().add_attribute("action", "ibc_packet_timeout"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ChannelInfo, ExecuteMsg, InstantiateMsg, ListChannelsResponse, QueryMsg};
+
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_ibc_channel_close_init, mock_ibc_channel_connect_ack,
+        mock_ibc_channel_open_init, mock_ibc_channel_open_try, mock_ibc_packet_ack,
+        mock_ibc_packet_recv, mock_ibc_packet_timeout, mock_info, MockApi, MockQuerier,
+        MockStorage,
+    };
+    use cosmwasm_std::{CosmosMsg, IbcAcknowledgement, IbcMsg, OwnedDeps};
+
+    const CREATOR: &str = "creator";
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {};
+        let info = mock_info(CREATOR, &[]);
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        deps
+    }
+
+    // connect will run through the entire handshake to set up a proper connect and
+    // save the channel (tested in detail in `proper_handshake_flow`)
+    fn connect(mut deps: DepsMut, channel_id: &str) {
+        let handshake_open = mock_ibc_channel_open_init(channel_id, IbcOrder::Unordered, "");
+        // first we try to open with a valid handshake
+        let res = ibc_channel_open(deps.branch(), mock_env(), handshake_open).unwrap();
+        assert_eq!(res.unwrap().version, IBC_APP_VERSION);
+
+        // then we connect (with counter-party version set)
+        let handshake_connect =
+            mock_ibc_channel_connect_ack(channel_id, IbcOrder::Unordered, IBC_APP_VERSION);
+        let res = ibc_channel_connect(deps.branch(), mock_env(), handshake_connect).unwrap();
+        assert_eq!(0, res.messages.len());
+    }
+
+    fn get_channel(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, id: &str) -> ChannelInfo {
+        let q = QueryMsg::Channel {
+            channel_id: id.into(),
+        };
+        let r = query(deps.as_ref(), mock_env(), q).unwrap();
+        from_slice(&r).unwrap()
+    }
+
+    #[test]
+    fn enforce_version_in_handshake() {
+        let mut deps = setup();
+
+        let wrong_order =
+            mock_ibc_channel_open_try("channel-12", IbcOrder::Ordered, IBC_APP_VERSION);
+        ibc_channel_open(deps.as_mut(), mock_env(), wrong_order).unwrap_err();
+
+        let wrong_version = mock_ibc_channel_open_try("channel-12", IbcOrder::Unordered, "echo");
+        ibc_channel_open(deps.as_mut(), mock_env(), wrong_version).unwrap_err();
+
+        let wrong_version = mock_ibc_channel_open_init("channel-12", IbcOrder::Unordered, "echo");
+        ibc_channel_open(deps.as_mut(), mock_env(), wrong_version).unwrap_err();
+
+        let valid_handshake =
+            mock_ibc_channel_open_try("channel-12", IbcOrder::Unordered, IBC_APP_VERSION);
+        ibc_channel_open(deps.as_mut(), mock_env(), valid_handshake).unwrap();
+
+        let wrong_version = mock_ibc_channel_connect_ack("channel-12", IbcOrder::Unordered, "echo");
+        ibc_channel_connect(deps.as_mut(), mock_env(), wrong_version).unwrap_err();
+    }
+
+    #[test]
+    fn proper_handshake_flow() {
+        let mut deps = setup();
+        let channel_id = "channel-1234";
+        connect(deps.as_mut(), channel_id);
+
+        let channel = get_channel(&deps, channel_id);
+        assert_eq!(
+            channel,
+            ChannelInfo::convert(channel_id.into(), ChannelData::default())
+        );
+
+        let r = query(deps.as_ref(), mock_env(), QueryMsg::ListChannels {}).unwrap();
+        let res: ListChannelsResponse = from_slice(&r).unwrap();
+        assert_eq!(res.channels, vec![channel]);
+    }
+
+    #[test]
+    fn receive_echoes_payload() {
+        let mut deps = setup();
+        let channel_id = "channel-1234";
+        connect(deps.as_mut(), channel_id);
+
+        let packet = PacketMsg::Echo {
+            payload: b"ping".into(),
+        };
+        let msg = mock_ibc_packet_recv(channel_id, &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: AcknowledgementMsg<EchoResponse> = from_slice(&res.acknowledgement).unwrap();
+        assert_eq!(
+            ack.unwrap(),
+            EchoResponse {
+                payload: b"ping".into()
+            }
+        );
+        assert_eq!(get_channel(&deps, channel_id).received, 1);
+
+        // invalid packets are acknowledged with an error
+        let msg = mock_ibc_packet_recv(channel_id, &"foo").unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: AcknowledgementMsg<EchoResponse> = from_slice(&res.acknowledgement).unwrap();
+        assert!(ack.unwrap_err().starts_with("invalid packet: "));
+        assert_eq!(get_channel(&deps, channel_id).received, 1);
+
+        // as well as packets on unknown channels
+        let msg = mock_ibc_packet_recv("channel-7", &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: AcknowledgementMsg<EchoResponse> = from_slice(&res.acknowledgement).unwrap();
+        assert!(ack.unwrap_err().starts_with("invalid packet: "));
+    }
+
+    #[test]
+    fn send_and_acknowledge() {
+        let mut deps = setup();
+        let channel_id = "channel-1234";
+        connect(deps.as_mut(), channel_id);
+
+        // send a packet
+        let msg = ExecuteMsg::Send {
+            channel_id: channel_id.into(),
+            payload: b"ping".into(),
+            timeout: Some(60),
+        };
+        let info = mock_info(CREATOR, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+        let packet = match &res.messages[0].msg {
+            CosmosMsg::Ibc(IbcMsg::SendPacket {
+                channel_id: packet_channel,
+                data,
+                timeout,
+            }) => {
+                assert_eq!(packet_channel.as_str(), channel_id);
+                assert_eq!(
+                    timeout.timestamp(),
+                    Some(mock_env().block.time.plus_seconds(60))
+                );
+                from_slice::<PacketMsg>(data).unwrap()
+            }
+            o => panic!("Unexpected message: {:?}", o),
+        };
+        assert_eq!(get_channel(&deps, channel_id).sent, 1);
+
+        // the counterparty echoes it
+        let response = AcknowledgementMsg::Ok(EchoResponse {
+            payload: b"ping".into(),
+        });
+        let ack = IbcAcknowledgement::encode_json(&response).unwrap();
+        let msg = mock_ibc_packet_ack(channel_id, &packet, ack).unwrap();
+        let res = ibc_packet_ack(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let channel = get_channel(&deps, channel_id);
+        assert_eq!(channel.acknowledged, 1);
+        assert_eq!(channel.failed, 0);
+        assert_eq!(channel.last_echo, Some(b"ping".into()));
+
+        // error acknowledgements are counted separately
+        let response = AcknowledgementMsg::<EchoResponse>::Err("broken".to_string());
+        let ack = IbcAcknowledgement::encode_json(&response).unwrap();
+        let msg = mock_ibc_packet_ack(channel_id, &packet, ack).unwrap();
+        let res = ibc_packet_ack(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(res.attributes[1], ("error", "broken"));
+
+        let channel = get_channel(&deps, channel_id);
+        assert_eq!(channel.acknowledged, 1);
+        assert_eq!(channel.failed, 1);
+        assert_eq!(channel.last_echo, Some(b"ping".into()));
+    }
+
+    #[test]
+    fn send_and_timeout() {
+        let mut deps = setup();
+        let channel_id = "channel-1234";
+        connect(deps.as_mut(), channel_id);
+
+        let packet = PacketMsg::Echo {
+            payload: b"ping".into(),
+        };
+        let msg = mock_ibc_packet_timeout(channel_id, &packet).unwrap();
+        ibc_packet_timeout(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let channel = get_channel(&deps, channel_id);
+        assert_eq!(channel.timed_out, 1);
+        assert_eq!(channel.acknowledged, 0);
+    }
+
+    #[test]
+    fn close_channel() {
+        let mut deps = setup();
+        let channel_id = "channel-1234";
+        connect(deps.as_mut(), channel_id);
+
+        // start closing from our side
+        let msg = ExecuteMsg::Close {
+            channel_id: channel_id.into(),
+        };
+        let info = mock_info(CREATOR, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Ibc(IbcMsg::CloseChannel {
+                channel_id: channel_id.into()
+            })
+        );
+
+        let close = mock_ibc_channel_close_init(channel_id, IbcOrder::Unordered, IBC_APP_VERSION);
+        ibc_channel_close(deps.as_mut(), mock_env(), close).unwrap();
+
+        let r = query(deps.as_ref(), mock_env(), QueryMsg::ListChannels {}).unwrap();
+        let res: ListChannelsResponse = from_slice(&r).unwrap();
+        assert_eq!(res.channels.len(), 0);
+
+        // late timeouts of the closed channel are ignored
+        let packet = PacketMsg::Echo {
+            payload: b"ping".into(),
+        };
+        let msg = mock_ibc_packet_timeout(channel_id, &packet).unwrap();
+        ibc_packet_timeout(deps.as_mut(), mock_env(), msg).unwrap();
+        let r = query(deps.as_ref(), mock_env(), QueryMsg::ListChannels {}).unwrap();
+        let res: ListChannelsResponse = from_slice(&r).unwrap();
+        assert_eq!(res.channels.len(), 0);
+    }
+}
@@ -0,0 +1,22 @@
+use cosmwasm_std::{Binary, ContractResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This is the message we send over the IBC channel
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PacketMsg {
+    /// Asks the receiver to send the payload back in the acknowledgement
+    Echo { payload: Binary },
+}
+
+/// All IBC acknowledgements are wrapped in `ContractResult`.
+/// The success value depends on the PacketMsg variant.
+pub type AcknowledgementMsg<T> = ContractResult<T>;
+
+/// This is the success response we send on ack for PacketMsg::Echo.
+/// It contains the payload of the received packet.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct EchoResponse {
+    pub payload: Binary,
+}
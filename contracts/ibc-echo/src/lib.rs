@@ -0,0 +1,5 @@
+pub mod contract;
+pub mod ibc;
+pub mod ibc_msg;
+pub mod msg;
+pub mod state;
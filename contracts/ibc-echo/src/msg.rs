@@ -0,0 +1,69 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Binary;
+
+use crate::state::ChannelData;
+
+/// This needs no info. Channels are opened by relayers.
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Sends a packet with the given payload over the channel.
+    /// The counterparty echoes the payload in the acknowledgement.
+    Send {
+        channel_id: String,
+        payload: Binary,
+        /// Seconds until the packet times out. Defaults to one hour.
+        timeout: Option<u64>,
+    },
+    /// Starts closing the channel from this side
+    Close { channel_id: String },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    // Shows all connected channels with their statistics
+    #[returns(ListChannelsResponse)]
+    ListChannels {},
+    // Get the statistics of one channel
+    #[returns(ChannelInfo)]
+    Channel { channel_id: String },
+}
+
+#[cw_serde]
+pub struct ListChannelsResponse {
+    pub channels: Vec<ChannelInfo>,
+}
+
+#[cw_serde]
+pub struct ChannelInfo {
+    pub channel_id: String,
+    /// number of packets sent over this channel
+    pub sent: u64,
+    /// number of packets received over this channel
+    pub received: u64,
+    /// number of sent packets acknowledged with success
+    pub acknowledged: u64,
+    /// number of sent packets acknowledged with an error
+    pub failed: u64,
+    /// number of sent packets that timed out
+    pub timed_out: u64,
+    /// the payload echoed in the last successful acknowledgement
+    pub last_echo: Option<Binary>,
+}
+
+impl ChannelInfo {
+    pub fn convert(channel_id: String, input: ChannelData) -> Self {
+        ChannelInfo {
+            channel_id,
+            sent: input.sent,
+            received: input.received,
+            acknowledged: input.acknowledged,
+            failed: input.failed,
+            timed_out: input.timed_out,
+            last_echo: input.last_echo,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    from_slice,
+    storage_keys::{namespace_with_key, to_length_prefixed},
+    to_vec, Binary, Order, StdError, StdResult, Storage,
+};
+
+/// channels is lookup of channel_id to the packet statistics of the channel
+pub const PREFIX_CHANNELS: &[u8] = b"channels";
+/// Upper bound for ranging over channels
+const PREFIX_CHANNELS_UPPER_BOUND: &[u8] = b"channelt";
+
+/// The packet statistics of a connected channel
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+pub struct ChannelData {
+    /// number of packets sent over this channel
+    pub sent: u64,
+    /// number of packets received over this channel
+    pub received: u64,
+    /// number of sent packets acknowledged with success
+    pub acknowledged: u64,
+    /// number of sent packets acknowledged with an error
+    pub failed: u64,
+    /// number of sent packets that timed out
+    pub timed_out: u64,
+    /// the payload echoed in the last successful acknowledgement
+    pub last_echo: Option<Binary>,
+}
+
+pub fn may_load_channel(storage: &dyn Storage, id: &str) -> StdResult<Option<ChannelData>> {
+    storage
+        .get(&namespace_with_key(&[PREFIX_CHANNELS], id.as_bytes()))
+        .map(|v| from_slice(&v))
+        .transpose()
+}
+
+pub fn load_channel(storage: &dyn Storage, id: &str) -> StdResult<ChannelData> {
+    may_load_channel(storage, id)?.ok_or_else(|| StdError::not_found(format!("channel {}", id)))
+}
+
+pub fn save_channel(storage: &mut dyn Storage, id: &str, channel: &ChannelData) -> StdResult<()> {
+    storage.set(
+        &namespace_with_key(&[PREFIX_CHANNELS], id.as_bytes()),
+        &to_vec(channel)?,
+    );
+    Ok(())
+}
+
+pub fn remove_channel(storage: &mut dyn Storage, id: &str) {
+    storage.remove(&namespace_with_key(&[PREFIX_CHANNELS], id.as_bytes()));
+}
+
+pub fn range_channels(
+    storage: &dyn Storage,
+) -> impl Iterator<Item = StdResult<(String, ChannelData)>> + '_ {
+    let prefix = to_length_prefixed(PREFIX_CHANNELS);
+    let upper_bound = to_length_prefixed(PREFIX_CHANNELS_UPPER_BOUND);
+    storage
+        .range(Some(&prefix), Some(&upper_bound), Order::Ascending)
+        .map(|(key, val)| {
+            Ok((
+                String::from_utf8(key[PREFIX_CHANNELS.len() + 2..].to_vec())?,
+                from_slice(&val)?,
+            ))
+        })
+}
@@ -0,0 +1,229 @@
+//! This integration test tries to run and call the generated wasm.
+//! It depends on a Wasm build being available, which you can create with `cargo wasm`.
+//! Then running `cargo integration-test` will validate we can properly call into that generated Wasm.
+//!
+//! This contract is the integration test target for all IBC entry points of cosmwasm-vm.
+//! The channel handshake and packets are simulated with `cosmwasm_vm::testing::IbcHandshake`.
+
+use cosmwasm_std::{
+    ContractResult, CosmosMsg, IbcAcknowledgement, IbcBasicResponse, IbcMsg, IbcOrder,
+    IbcReceiveResponse, Response,
+};
+use cosmwasm_vm::testing::{
+    execute, ibc_channel_close, ibc_packet_ack, ibc_packet_receive, ibc_packet_timeout,
+    instantiate, mock_env, mock_ibc_channel, mock_ibc_channel_close_confirm, mock_info,
+    mock_instance, query, IbcHandshake, IbcHandshakeState, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_vm::{from_slice, Instance};
+
+use ibc_echo::ibc::IBC_APP_VERSION;
+use ibc_echo::ibc_msg::{AcknowledgementMsg, EchoResponse, PacketMsg};
+use ibc_echo::msg::{ChannelInfo, ExecuteMsg, InstantiateMsg, ListChannelsResponse, QueryMsg};
+
+// This line will test the output of cargo wasm
+static WASM: &[u8] = include_bytes!("../target/wasm32-unknown-unknown/release/ibc_echo.wasm");
+
+const CREATOR: &str = "creator";
+
+const DESERIALIZATION_LIMIT: usize = 20_000;
+
+fn setup() -> Instance<MockApi, MockStorage, MockQuerier> {
+    let mut deps = mock_instance(WASM, &[]);
+    let msg = InstantiateMsg {};
+    let info = mock_info(CREATOR, &[]);
+    let res: Response = instantiate(&mut deps, mock_env(), info, msg).unwrap();
+    assert_eq!(0, res.messages.len());
+    deps
+}
+
+// connect runs the handshake started on our chain, leaving the version choice to the contract
+fn connect(
+    deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
+    channel_id: &str,
+) -> IbcHandshake {
+    let mut handshake = IbcHandshake::init(mock_ibc_channel(channel_id, IbcOrder::Unordered, ""));
+    let res: IbcBasicResponse = handshake.run(deps).unwrap();
+    assert_eq!(0, res.messages.len());
+    assert_eq!(handshake.state(), IbcHandshakeState::Connected);
+    assert_eq!(handshake.channel().version, IBC_APP_VERSION);
+    handshake
+}
+
+fn get_channel(deps: &mut Instance<MockApi, MockStorage, MockQuerier>, id: &str) -> ChannelInfo {
+    let q = QueryMsg::Channel {
+        channel_id: id.into(),
+    };
+    let r = query(deps, mock_env(), q).unwrap();
+    from_slice(&r, DESERIALIZATION_LIMIT).unwrap()
+}
+
+fn list_channels(deps: &mut Instance<MockApi, MockStorage, MockQuerier>) -> Vec<ChannelInfo> {
+    let r = query(deps, mock_env(), QueryMsg::ListChannels {}).unwrap();
+    let res: ListChannelsResponse = from_slice(&r, DESERIALIZATION_LIMIT).unwrap();
+    res.channels
+}
+
+// send executes ExecuteMsg::Send and returns the packet the contract emitted
+fn send(
+    deps: &mut Instance<MockApi, MockStorage, MockQuerier>,
+    channel_id: &str,
+    payload: &[u8],
+) -> PacketMsg {
+    let msg = ExecuteMsg::Send {
+        channel_id: channel_id.into(),
+        payload: payload.into(),
+        timeout: None,
+    };
+    let info = mock_info(CREATOR, &[]);
+    let res: Response = execute(deps, mock_env(), info, msg).unwrap();
+    assert_eq!(1, res.messages.len());
+    match &res.messages[0].msg {
+        CosmosMsg::Ibc(IbcMsg::SendPacket {
+            channel_id: packet_channel,
+            data,
+            ..
+        }) => {
+            assert_eq!(packet_channel.as_str(), channel_id);
+            from_slice(data, DESERIALIZATION_LIMIT).unwrap()
+        }
+        o => panic!("Unexpected message: {:?}", o),
+    }
+}
+
+#[test]
+fn handshake_works() {
+    let mut deps = setup();
+    let channel_id = "channel-1234";
+    connect(&mut deps, channel_id);
+
+    let channels = list_channels(&mut deps);
+    assert_eq!(1, channels.len());
+    assert_eq!(channels[0], get_channel(&mut deps, channel_id));
+    assert_eq!(channels[0].sent, 0);
+    assert_eq!(channels[0].received, 0);
+}
+
+#[test]
+fn enforce_version_in_handshake() {
+    let mut deps = setup();
+
+    let wrong_order = mock_ibc_channel("channel-12", IbcOrder::Ordered, IBC_APP_VERSION);
+    let mut handshake = IbcHandshake::try_open(wrong_order);
+    handshake.open(&mut deps).unwrap_err();
+    assert_eq!(handshake.state(), IbcHandshakeState::New);
+
+    let wrong_version = mock_ibc_channel("channel-12", IbcOrder::Unordered, "echo");
+    let mut handshake = IbcHandshake::try_open(wrong_version);
+    handshake.open(&mut deps).unwrap_err();
+
+    let valid = mock_ibc_channel("channel-12", IbcOrder::Unordered, IBC_APP_VERSION);
+    let mut handshake = IbcHandshake::try_open(valid);
+    let _: IbcBasicResponse = handshake.run(&mut deps).unwrap();
+    assert_eq!(handshake.state(), IbcHandshakeState::Connected);
+}
+
+#[test]
+fn receive_echoes_payload() {
+    let mut deps = setup();
+    let channel_id = "channel-1234";
+    let mut handshake = connect(&mut deps, channel_id);
+
+    let packet = PacketMsg::Echo {
+        payload: b"ping".into(),
+    };
+    let msg = handshake.packet_recv(&packet).unwrap();
+    let res: IbcReceiveResponse = ibc_packet_receive(&mut deps, mock_env(), msg).unwrap();
+    let ack: AcknowledgementMsg<EchoResponse> =
+        from_slice(&res.acknowledgement, DESERIALIZATION_LIMIT).unwrap();
+    assert_eq!(
+        ack,
+        ContractResult::Ok(EchoResponse {
+            payload: b"ping".into()
+        })
+    );
+    assert_eq!(get_channel(&mut deps, channel_id).received, 1);
+
+    // invalid packets are acknowledged with an error, the call itself succeeds
+    let msg = handshake.packet_recv(&"foo").unwrap();
+    let res: IbcReceiveResponse = ibc_packet_receive(&mut deps, mock_env(), msg).unwrap();
+    let ack: AcknowledgementMsg<EchoResponse> =
+        from_slice(&res.acknowledgement, DESERIALIZATION_LIMIT).unwrap();
+    assert!(ack.unwrap_err().starts_with("invalid packet: "));
+    assert_eq!(get_channel(&mut deps, channel_id).received, 1);
+}
+
+#[test]
+fn send_with_acknowledgements() {
+    let mut deps = setup();
+    let channel_id = "channel-1234";
+    let mut handshake = connect(&mut deps, channel_id);
+
+    // the first packet is echoed by the counterparty
+    let packet = send(&mut deps, channel_id, b"ping");
+    let response = AcknowledgementMsg::Ok(EchoResponse {
+        payload: b"ping".into(),
+    });
+    let ack = IbcAcknowledgement::encode_json(&response).unwrap();
+    let msg = handshake.packet_ack(&packet, ack).unwrap();
+    assert_eq!(msg.original_packet.sequence, 1);
+    let res: IbcBasicResponse = ibc_packet_ack(&mut deps, mock_env(), msg).unwrap();
+    assert_eq!(0, res.messages.len());
+
+    // the second one fails on the counterparty
+    let packet = send(&mut deps, channel_id, b"pong");
+    let response = AcknowledgementMsg::<EchoResponse>::Err("broken".to_string());
+    let ack = IbcAcknowledgement::encode_json(&response).unwrap();
+    let msg = handshake.packet_ack(&packet, ack).unwrap();
+    assert_eq!(msg.original_packet.sequence, 2);
+    let res: IbcBasicResponse = ibc_packet_ack(&mut deps, mock_env(), msg).unwrap();
+    assert_eq!(res.attributes[1].value, "broken");
+
+    let channel = get_channel(&mut deps, channel_id);
+    assert_eq!(channel.sent, 2);
+    assert_eq!(channel.acknowledged, 1);
+    assert_eq!(channel.failed, 1);
+    assert_eq!(channel.last_echo, Some(b"ping".into()));
+}
+
+#[test]
+fn send_with_timeout() {
+    let mut deps = setup();
+    let channel_id = "channel-1234";
+    let mut handshake = connect(&mut deps, channel_id);
+
+    let packet = send(&mut deps, channel_id, b"ping");
+    let msg = handshake.packet_timeout(&packet).unwrap();
+    let _: IbcBasicResponse = ibc_packet_timeout(&mut deps, mock_env(), msg).unwrap();
+
+    let channel = get_channel(&mut deps, channel_id);
+    assert_eq!(channel.sent, 1);
+    assert_eq!(channel.timed_out, 1);
+    assert_eq!(channel.acknowledged, 0);
+}
+
+#[test]
+fn close_channel() {
+    let mut deps = setup();
+    let channel_id = "channel-1234";
+    let handshake = connect(&mut deps, channel_id);
+
+    // the counterparty closed the channel
+    let close = mock_ibc_channel_close_confirm(
+        channel_id,
+        IbcOrder::Unordered,
+        &handshake.channel().version,
+    );
+    let res: IbcBasicResponse = ibc_channel_close(&mut deps, mock_env(), close).unwrap();
+    assert_eq!(0, res.messages.len());
+    assert_eq!(list_channels(&mut deps).len(), 0);
+
+    // sending is not possible anymore
+    let msg = ExecuteMsg::Send {
+        channel_id: channel_id.into(),
+        payload: b"ping".into(),
+        timeout: None,
+    };
+    let info = mock_info(CREATOR, &[]);
+    let res: ContractResult<Response> = execute(&mut deps, mock_env(), info, msg);
+    res.unwrap_err();
+}
@@ -412,9 +412,11 @@ mod tests {
         // fake a reply and ensure this works
         let response = Reply {
             id,
+            payload: Binary::default(),
             result: SubMsgResult::Ok(SubMsgResponse {
                 events: fake_events(&account),
                 data: None,
+                msg_responses: vec![],
             }),
         };
         reply(deps.branch(), mock_env(), response).unwrap();
@@ -489,9 +491,11 @@ mod tests {
         // fake a reply and ensure this works
         let response = Reply {
             id,
+            payload: Binary::default(),
             result: SubMsgResult::Ok(SubMsgResponse {
                 events: fake_events(REFLECT_ADDR),
                 data: None,
+                msg_responses: vec![],
             }),
         };
         reply(deps.as_mut(), mock_env(), response).unwrap();
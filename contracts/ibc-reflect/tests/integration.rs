@@ -22,7 +22,7 @@ use cosmwasm_std::testing::{
     mock_ibc_packet_recv, mock_wasmd_attr,
 };
 use cosmwasm_std::{
-    attr, coins, BankMsg, ContractResult, CosmosMsg, Event, IbcBasicResponse, IbcOrder,
+    attr, coins, BankMsg, Binary, ContractResult, CosmosMsg, Event, IbcBasicResponse, IbcOrder,
     IbcReceiveResponse, Reply, Response, SubMsgResponse, SubMsgResult, WasmMsg,
 };
 use cosmwasm_vm::testing::{
@@ -95,9 +95,11 @@ fn connect(
     // fake a reply and ensure this works
     let response = Reply {
         id,
+        payload: Binary::default(),
         result: SubMsgResult::Ok(SubMsgResponse {
             events: fake_events(&account),
             data: None,
+            msg_responses: vec![],
         }),
     };
     let _: Response = reply(deps, mock_env(), response).unwrap();
@@ -173,9 +175,11 @@ fn proper_handshake_flow() {
     // we get the callback from reflect
     let response = Reply {
         id,
+        payload: Binary::default(),
         result: SubMsgResult::Ok(SubMsgResponse {
             events: fake_events(REFLECT_ADDR),
             data: None,
+            msg_responses: vec![],
         }),
     };
     let res: Response = reply(&mut deps, mock_env(), response).unwrap();
@@ -439,8 +439,13 @@ mod tests {
         let result = SubMsgResult::Ok(SubMsgResponse {
             events: events.clone(),
             data: Some(data.clone()),
+            msg_responses: vec![],
         });
-        let subcall = Reply { id, result };
+        let subcall = Reply {
+            id,
+            payload: Binary::default(),
+            result,
+        };
         let res = reply(deps.as_mut(), mock_env(), subcall).unwrap();
         assert_eq!(0, res.messages.len());
 
@@ -32,7 +32,7 @@ use cosmwasm_vm::{
 
 use reflect::msg::{
     CapitalizedResponse, ChainResponse, CustomMsg, ExecuteMsg, InstantiateMsg, OwnerResponse,
-    QueryMsg, SpecialQuery,
+    QueryMsg, SpecialQuery, SpecialResponse,
 };
 use reflect::testing::custom_query_execute;
 
@@ -229,6 +229,26 @@ fn dispatch_custom_query() {
     assert_eq!(value.text, "DEMO ONE");
 }
 
+#[test]
+fn dispatch_custom_query_from_chain_request() {
+    let custom = mock_dependencies_with_custom_querier(&[]);
+    let (instance_options, memory_limit) = mock_instance_options();
+    let mut deps = Instance::from_code(WASM, custom, instance_options, memory_limit).unwrap();
+
+    // the custom query is serialized by the contract and executed by the querier of the Backend
+    let res = query(
+        &mut deps,
+        mock_env(),
+        QueryMsg::Chain {
+            request: SpecialQuery::Ping {}.into(),
+        },
+    )
+    .unwrap();
+    let res: ChainResponse = from_binary(&res).unwrap();
+    let res: SpecialResponse = from_binary(&res.data).unwrap();
+    assert_eq!(res.msg, "pong");
+}
+
 #[test]
 fn reflect_subcall() {
     let mut deps = mock_instance(WASM, &[]);
@@ -271,8 +291,13 @@ fn reply_and_query() {
     let result = SubMsgResult::Ok(SubMsgResponse {
         events: events.clone(),
         data: Some(data.clone()),
+        msg_responses: vec![],
     });
-    let subcall = Reply { id, result };
+    let subcall = Reply {
+        id,
+        payload: Binary::default(),
+        result,
+    };
     let res: Response = reply(&mut deps, mock_env(), subcall).unwrap();
     assert_eq!(0, res.messages.len());
 
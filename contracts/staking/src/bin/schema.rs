@@ -1,11 +1,13 @@
 use cosmwasm_schema::write_api;
 
-use staking::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use staking::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         query: QueryMsg,
         execute: ExecuteMsg,
+        sudo: SudoMsg,
+        migrate: MigrateMsg,
     }
 }
@@ -1,21 +1,31 @@
 use cosmwasm_std::{
-    coin, entry_point, to_binary, BankMsg, Decimal, Deps, DepsMut, DistributionMsg, Env,
-    MessageInfo, QuerierWrapper, QueryResponse, Response, StakingMsg, StdError, StdResult, Uint128,
+    coin, entry_point, from_slice, to_binary, Api, BankMsg, Binary, BlockInfo, CanonicalAddr,
+    Decimal, Deps, DepsMut, DistributionMsg, Env, Event, MessageInfo, QuerierWrapper,
+    QueryResponse, Response, StakingMsg, StdError, StdResult, Storage, Timestamp, Uint128, Uint256,
     WasmMsg,
 };
 
 use crate::errors::{StakingError, Unauthorized};
 use crate::msg::{
-    BalanceResponse, ClaimsResponse, ExecuteMsg, InstantiateMsg, InvestmentResponse, QueryMsg,
-    TokenInfoResponse,
+    AddressBalance, AddressClaims, AllBalancesResponse, AllClaimsResponse, AllowanceResponse,
+    BalanceResponse, Claim, ClaimsResponse, Cw20ReceiveMsg, ExecuteMsg, Expiration, InstantiateMsg,
+    InvestmentResponse, MigrateMsg, QueryMsg, SudoMsg, TokenInfoResponse, ValidatorWeight,
 };
 use crate::state::{
-    load_item, may_load_map, save_item, save_map, update_item, InvestmentInfo, Supply, TokenInfo,
-    KEY_INVESTMENT, KEY_TOKEN_INFO, KEY_TOTAL_SUPPLY, PREFIX_BALANCE, PREFIX_CLAIMS,
+    load_allowance, load_claims, load_item, may_load_map, migrate_claims, parse_claims, range_map,
+    save_allowance, save_claims, save_item, save_map, v1, ClaimsMigration, InvestmentInfo, Supply,
+    TokenInfo, KEY_CLAIMS_MIGRATION, KEY_INVESTMENT, KEY_TOKEN_INFO, KEY_TOTAL_SUPPLY,
+    PREFIX_BALANCE, PREFIX_CLAIMS,
 };
 
 const FALLBACK_RATIO: Decimal = Decimal::one();
 
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+const DEFAULT_MIGRATION_LIMIT: u32 = 50;
+const MAX_MIGRATION_LIMIT: u32 = 250;
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
@@ -23,13 +33,21 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
-    // ensure the validator is registered
-    let validator = deps.querier.query_validator(msg.validator.clone())?;
-    if validator.is_none() {
-        return Err(StdError::generic_err(format!(
-            "{} is not in the current validator set",
-            msg.validator
-        )));
+    // ensure the validator set is valid and all validators are registered
+    if msg.validators.is_empty() {
+        return Err(StdError::generic_err("validator set must not be empty"));
+    }
+    for (i, validator) in msg.validators.iter().enumerate() {
+        if msg.validators[..i]
+            .iter()
+            .any(|v| v.address == validator.address)
+        {
+            return Err(StdError::generic_err(format!(
+                "duplicate validator {}",
+                validator.address
+            )));
+        }
+        assert_valid_validator(&deps.querier, validator)?;
     }
 
     let token = TokenInfo {
@@ -44,8 +62,9 @@ pub fn instantiate(
         owner: info.sender,
         exit_tax: msg.exit_tax,
         bond_denom: denom,
-        validator: msg.validator,
+        validators: msg.validators,
         min_withdrawal: msg.min_withdrawal,
+        unbonding_period: msg.unbonding_period,
     };
     save_item(deps.storage, KEY_INVESTMENT, &invest)?;
 
@@ -67,11 +86,48 @@ pub fn execute(
         ExecuteMsg::Transfer { recipient, amount } => {
             Ok(transfer(deps, env, info, recipient, amount)?)
         }
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => Ok(send(deps, env, info, contract, amount, msg)?),
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => Ok(increase_allowance(
+            deps, env, info, spender, amount, expires,
+        )?),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => Ok(decrease_allowance(
+            deps, env, info, spender, amount, expires,
+        )?),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => Ok(transfer_from(deps, env, info, owner, recipient, amount)?),
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => Ok(send_from(deps, env, info, owner, contract, amount, msg)?),
         ExecuteMsg::Bond {} => Ok(bond(deps, env, info)?),
         ExecuteMsg::Unbond { amount } => Ok(unbond(deps, env, info, amount)?),
-        ExecuteMsg::Claim {} => Ok(claim(deps, env, info)?),
-        ExecuteMsg::Reinvest {} => Ok(reinvest(deps, env, info)?),
-        ExecuteMsg::_BondAllTokens {} => _bond_all_tokens(deps, env, info),
+        ExecuteMsg::ProcessClaims {} => Ok(process_claims(deps, env, info)?),
+        ExecuteMsg::Reinvest {} | ExecuteMsg::Compound {} => Ok(compound(deps, env)?),
+        ExecuteMsg::_CompoundRewards {} => _compound_rewards(deps, env, info),
+        ExecuteMsg::CheckSlash {} => Ok(check_slash(deps, env)?),
+        ExecuteMsg::AddValidator { address, weight } => {
+            add_validator(deps, env, info, address, weight)
+        }
+        ExecuteMsg::RemoveValidator { address } => remove_validator(deps, env, info, address),
+        ExecuteMsg::Rebalance {} => rebalance(deps, env, info),
+        ExecuteMsg::MigrateClaims { limit } => Ok(execute_migrate_claims(deps, limit)?),
     }
 }
 
@@ -84,16 +140,7 @@ pub fn transfer(
 ) -> StdResult<Response> {
     let rcpt_raw = deps.api.addr_canonicalize(&recipient)?;
     let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-
-    let balance = may_load_map(deps.storage, PREFIX_BALANCE, &sender_raw)?.unwrap_or_default();
-    save_map(
-        deps.storage,
-        PREFIX_BALANCE,
-        &sender_raw,
-        balance.checked_sub(send)?,
-    )?;
-    let balance = may_load_map(deps.storage, PREFIX_BALANCE, &rcpt_raw)?.unwrap_or_default();
-    save_map(deps.storage, PREFIX_BALANCE, &rcpt_raw, balance + send)?;
+    move_tokens(deps.storage, &sender_raw, &rcpt_raw, send)?;
 
     let res = Response::new()
         .add_attribute("action", "transfer")
@@ -103,25 +150,308 @@ pub fn transfer(
     Ok(res)
 }
 
-// get_bonded returns the total amount of delegations from contract
+fn move_tokens(
+    storage: &mut dyn Storage,
+    from: &CanonicalAddr,
+    to: &CanonicalAddr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let balance = may_load_map(storage, PREFIX_BALANCE, from)?.unwrap_or_default();
+    save_map(storage, PREFIX_BALANCE, from, balance.checked_sub(amount)?)?;
+    let balance = may_load_map(storage, PREFIX_BALANCE, to)?.unwrap_or_default();
+    save_map(storage, PREFIX_BALANCE, to, balance + amount)?;
+    Ok(())
+}
+
+/// send moves tokens to a contract and notifies it via a Cw20ReceiveMsg
+pub fn send(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> StdResult<Response> {
+    let rcpt_raw = deps.api.addr_canonicalize(&contract)?;
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    move_tokens(deps.storage, &sender_raw, &rcpt_raw, amount)?;
+
+    let receive = Cw20ReceiveMsg {
+        sender: info.sender.to_string(),
+        amount,
+        msg,
+    };
+    let res = Response::new()
+        .add_attribute("action", "send")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", &contract)
+        .add_attribute("amount", amount)
+        .add_message(receive.into_cosmos_msg(contract)?);
+    Ok(res)
+}
+
+fn canonicalize_allowance_parties(
+    api: &dyn Api,
+    owner: &str,
+    spender: &str,
+) -> StdResult<(CanonicalAddr, CanonicalAddr)> {
+    let owner_raw = api.addr_canonicalize(owner)?;
+    let spender_raw = api.addr_canonicalize(spender)?;
+    if owner_raw == spender_raw {
+        return Err(StdError::generic_err("cannot set allowance to own account"));
+    }
+    Ok((owner_raw, spender_raw))
+}
+
+fn assert_not_expired(expires: &Expiration, block: &BlockInfo) -> StdResult<()> {
+    if expires.is_expired(block) {
+        return Err(StdError::generic_err("invalid expiration value"));
+    }
+    Ok(())
+}
+
+pub fn increase_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> StdResult<Response> {
+    let (owner_raw, spender_raw) =
+        canonicalize_allowance_parties(deps.api, info.sender.as_str(), &spender)?;
+
+    let mut allowance = load_allowance(deps.storage, &owner_raw, &spender_raw)?;
+    if let Some(expires) = expires {
+        assert_not_expired(&expires, &env.block)?;
+        allowance.expires = expires;
+    }
+    allowance.allowance += amount;
+    save_allowance(deps.storage, &owner_raw, &spender_raw, &allowance)?;
+
+    let res = Response::new()
+        .add_attribute("action", "increase_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount);
+    Ok(res)
+}
+
+pub fn decrease_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> StdResult<Response> {
+    let (owner_raw, spender_raw) =
+        canonicalize_allowance_parties(deps.api, info.sender.as_str(), &spender)?;
+
+    let mut allowance = load_allowance(deps.storage, &owner_raw, &spender_raw)?;
+    if let Some(expires) = expires {
+        assert_not_expired(&expires, &env.block)?;
+        allowance.expires = expires;
+    }
+    // removes the allowance when it reaches zero
+    allowance.allowance = allowance.allowance.saturating_sub(amount);
+    save_allowance(deps.storage, &owner_raw, &spender_raw, &allowance)?;
+
+    let res = Response::new()
+        .add_attribute("action", "decrease_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount);
+    Ok(res)
+}
+
+/// Deducts amount from the allowance of spender for the tokens of owner
+fn spend_allowance(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    owner: &CanonicalAddr,
+    spender: &CanonicalAddr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let mut allowance = load_allowance(storage, owner, spender)?;
+    if allowance.expires.is_expired(block) {
+        return Err(StdError::generic_err("allowance is expired"));
+    }
+    allowance.allowance = allowance
+        .allowance
+        .checked_sub(amount)
+        .map_err(|_| StdError::generic_err("no allowance for this account"))?;
+    save_allowance(storage, owner, spender, &allowance)
+}
+
+pub fn transfer_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let owner_raw = deps.api.addr_canonicalize(&owner)?;
+    let spender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let rcpt_raw = deps.api.addr_canonicalize(&recipient)?;
+    spend_allowance(deps.storage, &env.block, &owner_raw, &spender_raw, amount)?;
+    move_tokens(deps.storage, &owner_raw, &rcpt_raw, amount)?;
+
+    let res = Response::new()
+        .add_attribute("action", "transfer_from")
+        .add_attribute("from", owner)
+        .add_attribute("to", recipient)
+        .add_attribute("by", info.sender)
+        .add_attribute("amount", amount);
+    Ok(res)
+}
+
+pub fn send_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> StdResult<Response> {
+    let owner_raw = deps.api.addr_canonicalize(&owner)?;
+    let spender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let rcpt_raw = deps.api.addr_canonicalize(&contract)?;
+    spend_allowance(deps.storage, &env.block, &owner_raw, &spender_raw, amount)?;
+    move_tokens(deps.storage, &owner_raw, &rcpt_raw, amount)?;
+
+    let receive = Cw20ReceiveMsg {
+        sender: info.sender.to_string(),
+        amount,
+        msg,
+    };
+    let res = Response::new()
+        .add_attribute("action", "send_from")
+        .add_attribute("from", owner)
+        .add_attribute("to", &contract)
+        .add_attribute("by", info.sender)
+        .add_attribute("amount", amount)
+        .add_message(receive.into_cosmos_msg(contract)?);
+    Ok(res)
+}
+
+// get_delegations returns the amount delegated from contract to each validator
 // it ensures they are all the same denom
-fn get_bonded(querier: &QuerierWrapper, contract_addr: impl Into<String>) -> StdResult<Uint128> {
+fn get_delegations(
+    querier: &QuerierWrapper,
+    contract_addr: impl Into<String>,
+) -> StdResult<Vec<(String, Uint128)>> {
     let bonds = querier.query_all_delegations(contract_addr)?;
-    if bonds.is_empty() {
-        return Ok(Uint128::new(0));
-    }
-    let denom = bonds[0].amount.denom.as_str();
-    bonds.iter().fold(Ok(Uint128::new(0)), |racc, d| {
-        let acc = racc?;
-        if d.amount.denom.as_str() != denom {
-            Err(StdError::generic_err(format!(
+    if let Some(first) = bonds.first() {
+        let denom = first.amount.denom.as_str();
+        if let Some(d) = bonds.iter().find(|d| d.amount.denom.as_str() != denom) {
+            return Err(StdError::generic_err(format!(
                 "different denoms in bonds: '{}' vs '{}'",
                 denom, &d.amount.denom
-            )))
-        } else {
-            Ok(acc + d.amount.amount)
+            )));
         }
-    })
+    }
+    Ok(bonds
+        .into_iter()
+        .map(|d| (d.validator, d.amount.amount))
+        .collect())
+}
+
+// get_bonded returns the total amount of delegations from contract
+// it ensures they are all the same denom
+fn get_bonded(querier: &QuerierWrapper, contract_addr: impl Into<String>) -> StdResult<Uint128> {
+    Ok(get_delegations(querier, contract_addr)?
+        .into_iter()
+        .map(|(_, amount)| amount)
+        .sum())
+}
+
+/// Splits amount into parts proportional to the given shares.
+/// The rounding remainder is distributed one unit at a time to the parts with the largest
+/// fractional remainders, such that the parts add up to amount. A part only gets a unit if its
+/// exact value is not an integer, so no part exceeds its share if amount does not exceed the
+/// sum of the shares. Returns no parts if all shares are zero.
+fn split_proportionally(amount: Uint128, shares: &[(String, Uint128)]) -> Vec<(String, Uint128)> {
+    let total: Uint128 = shares.iter().map(|(_, share)| *share).sum();
+    if total.is_zero() {
+        return vec![];
+    }
+    let mut parts: Vec<(String, Uint128)> = shares
+        .iter()
+        .map(|(addr, share)| (addr.clone(), amount.multiply_ratio(*share, total)))
+        .collect();
+    let distributed: Uint128 = parts.iter().map(|(_, part)| *part).sum();
+
+    // The fractional remainders of the parts, scaled by total
+    let mut remainders: Vec<(usize, Uint256)> = shares
+        .iter()
+        .enumerate()
+        .map(|(i, (_, share))| (i, amount.full_mul(share.u128()) % Uint256::from(total)))
+        .filter(|(_, remainder)| !remainder.is_zero())
+        .collect();
+    // stable, so equal remainders are served in order
+    remainders.sort_by(|(_, a), (_, b)| b.cmp(a));
+    // The sum of the fractional remainders is smaller than the number of non-integer parts
+    let missing = (amount - distributed).u128() as usize;
+    for (i, _) in remainders.into_iter().take(missing) {
+        parts[i].1 += Uint128::one();
+    }
+    parts
+}
+
+/// Splits amount between the validators in proportion to their weights
+fn split_by_weight(amount: Uint128, validators: &[ValidatorWeight]) -> Vec<(String, Uint128)> {
+    let weights: Vec<(String, Uint128)> = validators
+        .iter()
+        .map(|v| (v.address.clone(), Uint128::from(v.weight)))
+        .collect();
+    split_proportionally(amount, &weights)
+}
+
+/// Delegates amount to the validators in proportion to their weights
+fn delegate_by_weight(invest: &InvestmentInfo, amount: Uint128) -> Vec<StakingMsg> {
+    split_by_weight(amount, &invest.validators)
+        .into_iter()
+        .filter(|(_, part)| !part.is_zero())
+        .map(|(validator, part)| StakingMsg::Delegate {
+            validator,
+            amount: coin(part.u128(), &invest.bond_denom),
+        })
+        .collect()
+}
+
+fn assert_valid_validator(querier: &QuerierWrapper, validator: &ValidatorWeight) -> StdResult<()> {
+    if validator.weight == 0 {
+        return Err(StdError::generic_err(format!(
+            "weight of {} must not be zero",
+            validator.address
+        )));
+    }
+    if querier.query_validator(&validator.address)?.is_none() {
+        return Err(StdError::generic_err(format!(
+            "{} is not in the current validator set",
+            validator.address
+        )));
+    }
+    Ok(())
+}
+
+fn assert_owner(invest: &InvestmentInfo, info: &MessageInfo) -> Result<(), StakingError> {
+    if info.sender != invest.owner {
+        return Err(Unauthorized {}.build());
+    }
+    Ok(())
+}
+
+// nominal_value is how many native tokens one derivative token is worth
+fn nominal_value(supply: &Supply) -> Decimal {
+    if supply.issued.is_zero() {
+        FALLBACK_RATIO
+    } else {
+        Decimal::from_ratio(supply.bonded, supply.issued)
+    }
 }
 
 fn assert_bonds(supply: &Supply, bonded: Uint128) -> StdResult<()> {
@@ -167,16 +497,13 @@ pub fn bond(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
     let balance = may_load_map(deps.storage, PREFIX_BALANCE, &sender_raw)?.unwrap_or_default();
     save_map(deps.storage, PREFIX_BALANCE, &sender_raw, balance + to_mint)?;
 
-    // bond them to the validator
+    // bond them to the validators
     let res = Response::new()
         .add_attribute("action", "bond")
         .add_attribute("from", info.sender)
         .add_attribute("bonded", payment.amount)
         .add_attribute("minted", to_mint)
-        .add_message(StakingMsg::Delegate {
-            validator: invest.validator,
-            amount: payment.clone(),
-        });
+        .add_messages(delegate_by_weight(&invest, payment.amount));
     Ok(res)
 }
 
@@ -212,7 +539,8 @@ pub fn unbond(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> St
 
     // re-calculate bonded to ensure we have real values
     // bonded is the total number of tokens we have delegated from this address
-    let bonded = get_bonded(&deps.querier, env.contract.address)?;
+    let delegations = get_delegations(&deps.querier, env.contract.address)?;
+    let bonded = delegations.iter().map(|(_, amount)| *amount).sum();
 
     // calculate how many native tokens this is worth and update supply
     let remainder = amount.checked_sub(tax)?;
@@ -225,46 +553,63 @@ pub fn unbond(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> St
     supply.claims += unbond;
     save_item(deps.storage, KEY_TOTAL_SUPPLY, &supply)?;
 
-    // add a claim to this user to get their tokens after the unbonding period
-    let claim = may_load_map(deps.storage, PREFIX_CLAIMS, &sender_raw)?.unwrap_or_default();
-    save_map(deps.storage, PREFIX_CLAIMS, &sender_raw, claim + unbond)?;
-
-    // unbond them
+    // add a claim to this user to get their tokens after the unbonding period.
+    // release times never decrease, so the queue stays ordered and claims released
+    // at the same time are merged
+    let release_at = env.block.time.plus_seconds(invest.unbonding_period);
+    let mut claims = load_claims(deps.storage, &sender_raw)?;
+    match claims.last_mut() {
+        Some(last) if last.release_at == release_at => last.amount += unbond,
+        _ => claims.push(Claim {
+            amount: unbond,
+            release_at,
+        }),
+    }
+    save_claims(deps.storage, &sender_raw, &claims)?;
+
+    // unbond them in proportion to the current delegations, so we never unbond more
+    // than delegated to a validator, even if the delegations do not match the weights
+    let undelegations = split_proportionally(unbond, &delegations)
+        .into_iter()
+        .filter(|(_, part)| !part.is_zero())
+        .map(|(validator, part)| StakingMsg::Undelegate {
+            validator,
+            amount: coin(part.u128(), &invest.bond_denom),
+        });
     let res = Response::new()
         .add_attribute("action", "unbond")
         .add_attribute("to", info.sender)
         .add_attribute("unbonded", unbond)
         .add_attribute("burnt", amount)
-        .add_message(StakingMsg::Undelegate {
-            validator: invest.validator,
-            amount: coin(unbond.u128(), &invest.bond_denom),
-        });
+        .add_messages(undelegations);
     Ok(res)
 }
 
-pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
-    // find how many tokens the contract has
+/// process_claims pays out all claims of the sender that were released
+pub fn process_claims(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
+    // split the claims into the released and the pending ones
+    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut claims = load_claims(deps.storage, &sender_raw)?;
+    let released = claims
+        .iter()
+        .position(|c| c.release_at > env.block.time)
+        .unwrap_or(claims.len());
+    let to_send: Uint128 = claims.drain(..released).map(|c| c.amount).sum();
+    if to_send.is_zero() {
+        return Err(StdError::generic_err("no released claims for this address"));
+    }
+
+    // ensure the contract received the unbonded tokens
     let invest: InvestmentInfo = load_item(deps.storage, KEY_INVESTMENT)?;
     let mut balance = deps
         .querier
         .query_balance(env.contract.address, invest.bond_denom)?;
-    if balance.amount < invest.min_withdrawal {
+    if balance.amount < to_send {
         return Err(StdError::generic_err(
-            "Insufficient balance in contract to process claim",
+            "Insufficient balance in contract to process claims",
         ));
     }
-
-    // check how much to send - min(balance, claims[sender]), and reduce the claim
-    let sender_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
-    let claim = may_load_map(deps.storage, PREFIX_CLAIMS, &sender_raw)?
-        .ok_or_else(|| StdError::generic_err("no claim for this address"))?;
-    let to_send = balance.amount.min(claim);
-    save_map(
-        deps.storage,
-        PREFIX_CLAIMS,
-        &sender_raw,
-        claim.checked_sub(to_send)?,
-    )?;
+    save_claims(deps.storage, &sender_raw, &claims)?;
 
     // update total supply (lower claim)
     let mut supply: Supply = load_item(deps.storage, KEY_TOTAL_SUPPLY)?;
@@ -274,7 +619,7 @@ pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response>
     // transfer tokens to the sender
     balance.amount = to_send;
     let res = Response::new()
-        .add_attribute("action", "claim")
+        .add_attribute("action", "process_claims")
         .add_attribute("from", &info.sender)
         .add_attribute("amount", to_send)
         .add_message(BankMsg::Send {
@@ -284,19 +629,76 @@ pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response>
     Ok(res)
 }
 
-/// reinvest will withdraw all pending rewards,
-/// then issue a callback to itself via _bond_all_tokens
-/// to reinvest the new earnings (and anything else that accumulated)
-pub fn reinvest(deps: DepsMut, env: Env, _info: MessageInfo) -> StdResult<Response> {
+#[entry_point]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    // only the layout of the first version can be migrated
+    if load_item::<InvestmentInfo>(deps.storage, KEY_INVESTMENT).is_ok() {
+        return Err(StdError::generic_err("contract is migrated already"));
+    }
+    let old: v1::InvestmentInfo = load_item(deps.storage, KEY_INVESTMENT)?;
+    let invest = InvestmentInfo {
+        owner: old.owner,
+        bond_denom: old.bond_denom,
+        exit_tax: old.exit_tax,
+        validators: vec![ValidatorWeight {
+            address: old.validator,
+            weight: 1,
+        }],
+        min_withdrawal: old.min_withdrawal,
+        unbonding_period: msg.unbonding_period,
+    };
+    save_item(deps.storage, KEY_INVESTMENT, &invest)?;
+
+    // converting all claims at once could exceed the gas limit, so they are converted
+    // in chunks via MigrateClaims. Until then, they are converted when loaded.
+    let migration = ClaimsMigration {
+        release_at: env.block.time.plus_seconds(msg.unbonding_period),
+        last_key: None,
+    };
+    save_item(deps.storage, KEY_CLAIMS_MIGRATION, &migration)?;
+
+    Ok(Response::new().add_attribute("action", "migrate"))
+}
+
+/// execute_migrate_claims converts the next chunk of claims after a migration
+pub fn execute_migrate_claims(deps: DepsMut, limit: Option<u32>) -> StdResult<Response> {
+    let limit = limit
+        .unwrap_or(DEFAULT_MIGRATION_LIMIT)
+        .clamp(1, MAX_MIGRATION_LIMIT) as usize;
+    let (checked, done) = migrate_claims(deps.storage, limit)?;
+
+    let res = Response::new()
+        .add_attribute("action", "migrate_claims")
+        .add_attribute("checked", checked.to_string())
+        .add_attribute("done", done.to_string());
+    Ok(res)
+}
+
+#[entry_point]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> StdResult<Response> {
+    match msg {
+        SudoMsg::Compound {} => compound(deps, env),
+        SudoMsg::CheckSlash {} => check_slash(deps, env),
+    }
+}
+
+/// compound will withdraw all pending rewards,
+/// then issue a callback to itself via _compound_rewards
+/// to bond them and pay the owner's cut
+pub fn compound(deps: DepsMut, env: Env) -> StdResult<Response> {
     let contract_addr = env.contract.address;
     let invest: InvestmentInfo = load_item(deps.storage, KEY_INVESTMENT)?;
-    let msg = to_binary(&ExecuteMsg::_BondAllTokens {})?;
-
-    // and bond them to the validator
+    let msg = to_binary(&ExecuteMsg::_CompoundRewards {})?;
+
+    let withdrawals =
+        invest
+            .validators
+            .into_iter()
+            .map(|v| DistributionMsg::WithdrawDelegatorReward {
+                validator: v.address,
+            });
     let res = Response::new()
-        .add_message(DistributionMsg::WithdrawDelegatorReward {
-            validator: invest.validator,
-        })
+        .add_messages(withdrawals)
         .add_message(WasmMsg::Execute {
             contract_addr: contract_addr.into(),
             msg,
@@ -305,7 +707,7 @@ pub fn reinvest(deps: DepsMut, env: Env, _info: MessageInfo) -> StdResult<Respon
     Ok(res)
 }
 
-pub fn _bond_all_tokens(
+pub fn _compound_rewards(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
@@ -315,35 +717,207 @@ pub fn _bond_all_tokens(
         return Err(Unauthorized {}.build());
     }
 
-    // find how many tokens we have to bond
+    // the rewards are all liquid tokens not reserved for claims. Besides the ones we just
+    // withdrew, this includes the rewards the chain withdraws whenever a delegation changes.
+    // Tokens of pending unbondings are reserved as well, so rewards are held back until those
+    // arrive. If the rewards are below min_withdrawal, we just return a no-op
     let invest: InvestmentInfo = load_item(deps.storage, KEY_INVESTMENT)?;
-    let mut balance = deps
+    let balance = deps
         .querier
         .query_balance(env.contract.address, &invest.bond_denom)?;
+    let mut supply: Supply = load_item(deps.storage, KEY_TOTAL_SUPPLY)?;
+    let rewards = balance.amount.saturating_sub(supply.claims);
+    if rewards.is_zero() || rewards < invest.min_withdrawal {
+        return Ok(Response::default());
+    }
+
+    // all rewards are bonded, which raises the value of the derivative tokens.
+    // the owner takes the exit tax from the rewards by getting newly minted derivative tokens
+    // worth that many native tokens. Rounding down favours the existing token holders.
+    let tax = rewards * invest.exit_tax;
+    supply.bonded += rewards;
+    let to_mint = tax
+        .checked_multiply_ratio(supply.issued, supply.bonded - tax)
+        .unwrap_or_default();
+    supply.issued += to_mint;
+    save_item(deps.storage, KEY_TOTAL_SUPPLY, &supply)?;
+
+    if !to_mint.is_zero() {
+        let owner_raw = deps.api.addr_canonicalize(invest.owner.as_str())?;
+        let balance = may_load_map(deps.storage, PREFIX_BALANCE, &owner_raw)?.unwrap_or_default();
+        save_map(deps.storage, PREFIX_BALANCE, &owner_raw, balance + to_mint)?;
+    }
 
-    // we deduct pending claims from our account balance before reinvesting.
-    // if there is not enough funds, we just return a no-op
-    match update_item(deps.storage, KEY_TOTAL_SUPPLY, |mut supply: Supply| {
-        balance.amount = balance.amount.checked_sub(supply.claims)?;
-        // this just triggers the "no op" case if we don't have min_withdrawal left to reinvest
-        balance.amount.checked_sub(invest.min_withdrawal)?;
-        supply.bonded += balance.amount;
-        Ok(supply)
-    }) {
-        Ok(_) => {}
-        // if it is below the minimum, we do a no-op (do not revert other state from withdrawal)
-        Err(StdError::Overflow { .. }) => return Ok(Response::default()),
-        Err(e) => return Err(e.into()),
-    }
-
-    // and bond them to the validator
     let res = Response::new()
-        .add_attribute("action", "reinvest")
-        .add_attribute("bonded", balance.amount)
-        .add_message(StakingMsg::Delegate {
-            validator: invest.validator,
-            amount: balance,
-        });
+        .add_attribute("action", "compound")
+        .add_attribute("bonded", rewards)
+        .add_attribute("tax", tax)
+        .add_attribute("minted", to_mint)
+        .add_messages(delegate_by_weight(&invest, rewards));
+    Ok(res)
+}
+
+/// check_slash reconciles the stored bonded amount with the actual delegations,
+/// which are lower after a validator was slashed. The loss is shared by all
+/// derivative token holders through a lower nominal value.
+/// Pending claims are not reduced, as the contract cannot query its unbonding delegations.
+pub fn check_slash(deps: DepsMut, env: Env) -> StdResult<Response> {
+    let bonded = get_bonded(&deps.querier, env.contract.address)?;
+    let mut supply: Supply = load_item(deps.storage, KEY_TOTAL_SUPPLY)?;
+
+    let res = Response::new().add_attribute("action", "check_slash");
+    let loss = match supply.bonded.checked_sub(bonded) {
+        Ok(loss) if !loss.is_zero() => loss,
+        // nothing was slashed
+        _ => return Ok(res),
+    };
+    supply.bonded = bonded;
+    save_item(deps.storage, KEY_TOTAL_SUPPLY, &supply)?;
+
+    let event = Event::new("slash")
+        .add_attribute("loss", loss)
+        .add_attribute("bonded", bonded)
+        .add_attribute("nominal_value", nominal_value(&supply).to_string());
+    Ok(res.add_event(event))
+}
+
+/// add_validator adds a validator to the set. Tokens are only delegated to it
+/// with the next bond or reinvest, or when the owner calls rebalance.
+pub fn add_validator(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: String,
+    weight: u64,
+) -> Result<Response, StakingError> {
+    let mut invest: InvestmentInfo = load_item(deps.storage, KEY_INVESTMENT)?;
+    assert_owner(&invest, &info)?;
+
+    if invest.validators.iter().any(|v| v.address == address) {
+        return Err(StdError::generic_err(format!("duplicate validator {}", address)).into());
+    }
+    let validator = ValidatorWeight { address, weight };
+    assert_valid_validator(&deps.querier, &validator)?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_validator")
+        .add_attribute("validator", &validator.address)
+        .add_attribute("weight", validator.weight.to_string());
+    invest.validators.push(validator);
+    save_item(deps.storage, KEY_INVESTMENT, &invest)?;
+    Ok(res)
+}
+
+/// remove_validator removes a validator from the set and redelegates everything
+/// bonded to it to the remaining validators
+pub fn remove_validator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, StakingError> {
+    let mut invest: InvestmentInfo = load_item(deps.storage, KEY_INVESTMENT)?;
+    assert_owner(&invest, &info)?;
+
+    let index = invest
+        .validators
+        .iter()
+        .position(|v| v.address == address)
+        .ok_or_else(|| StdError::generic_err(format!("{} is not in the validator set", address)))?;
+    if invest.validators.len() == 1 {
+        return Err(StdError::generic_err("cannot remove the last validator").into());
+    }
+    invest.validators.remove(index);
+    save_item(deps.storage, KEY_INVESTMENT, &invest)?;
+
+    let delegated = deps
+        .querier
+        .query_delegation(env.contract.address, &address)?
+        .map(|d| d.amount.amount)
+        .unwrap_or_default();
+    let redelegations = split_by_weight(delegated, &invest.validators)
+        .into_iter()
+        .filter(|(_, part)| !part.is_zero())
+        .map(|(dst_validator, part)| StakingMsg::Redelegate {
+            src_validator: address.clone(),
+            dst_validator,
+            amount: coin(part.u128(), &invest.bond_denom),
+        })
+        .collect::<Vec<_>>();
+
+    let res = Response::new()
+        .add_attribute("action", "remove_validator")
+        .add_attribute("validator", address)
+        .add_attribute("redelegated", delegated)
+        .add_messages(redelegations);
+    Ok(res)
+}
+
+/// rebalance redelegates tokens from validators holding more than their share
+/// to validators holding less, such that the delegations match the weights
+pub fn rebalance(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, StakingError> {
+    let invest: InvestmentInfo = load_item(deps.storage, KEY_INVESTMENT)?;
+    assert_owner(&invest, &info)?;
+
+    let delegations = get_delegations(&deps.querier, env.contract.address)?;
+    let bonded = delegations.iter().map(|(_, amount)| *amount).sum();
+    let targets = split_by_weight(bonded, &invest.validators);
+
+    let delegated_to = |validator: &str| {
+        delegations
+            .iter()
+            .find(|(addr, _)| addr == validator)
+            .map(|(_, amount)| *amount)
+            .unwrap_or_default()
+    };
+    let target_of = |validator: &str| {
+        targets
+            .iter()
+            .find(|(addr, _)| addr == validator)
+            .map(|(_, amount)| *amount)
+            .unwrap_or_default()
+    };
+    // validators no longer in the set have a target of zero
+    let surpluses = delegations.iter().filter_map(|(addr, amount)| {
+        amount
+            .checked_sub(target_of(addr))
+            .ok()
+            .filter(|surplus| !surplus.is_zero())
+            .map(|surplus| (addr.clone(), surplus))
+    });
+    let mut deficits = targets.iter().filter_map(|(addr, target)| {
+        target
+            .checked_sub(delegated_to(addr))
+            .ok()
+            .filter(|deficit| !deficit.is_zero())
+            .map(|deficit| (addr.clone(), deficit))
+    });
+
+    // surpluses and deficits add up to the same amount, so we can match them greedily
+    let mut redelegations = vec![];
+    let mut deficit = deficits.next();
+    for (src_validator, mut surplus) in surpluses {
+        while let Some((dst_validator, missing)) = deficit.as_mut() {
+            if surplus.is_zero() {
+                break;
+            }
+            let amount = surplus.min(*missing);
+            redelegations.push(StakingMsg::Redelegate {
+                src_validator: src_validator.clone(),
+                dst_validator: dst_validator.clone(),
+                amount: coin(amount.u128(), &invest.bond_denom),
+            });
+            surplus -= amount;
+            *missing -= amount;
+            if missing.is_zero() {
+                deficit = deficits.next();
+            }
+        }
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "rebalance")
+        .add_messages(redelegations);
     Ok(res)
 }
 
@@ -351,9 +925,22 @@ pub fn _bond_all_tokens(
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
     match msg {
         QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
+        QueryMsg::AllBalances { start_after, limit } => {
+            to_binary(&query_all_balances(deps, start_after, limit)?)
+        }
+        QueryMsg::AllClaims { start_after, limit } => {
+            to_binary(&query_all_claims(deps, start_after, limit)?)
+        }
+        QueryMsg::Allowance { owner, spender } => {
+            to_binary(&query_allowance(deps, &owner, &spender)?)
+        }
         QueryMsg::Investment {} => to_binary(&query_investment(deps)?),
         QueryMsg::Balance { address } => to_binary(&query_balance(deps, &address)?),
-        QueryMsg::Claims { address } => to_binary(&query_claims(deps, &address)?),
+        QueryMsg::Claims {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&query_claims(deps, &address, start_after, limit)?),
     }
 }
 
@@ -363,24 +950,108 @@ pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
         symbol,
         decimals,
     } = load_item(deps.storage, KEY_TOKEN_INFO)?;
+    let supply: Supply = load_item(deps.storage, KEY_TOTAL_SUPPLY)?;
 
     Ok(TokenInfoResponse {
         name,
         symbol,
         decimals,
+        total_supply: supply.issued,
     })
 }
 
+pub fn query_allowance(deps: Deps, owner: &str, spender: &str) -> StdResult<AllowanceResponse> {
+    let owner_raw = deps.api.addr_canonicalize(owner)?;
+    let spender_raw = deps.api.addr_canonicalize(spender)?;
+    load_allowance(deps.storage, &owner_raw, &spender_raw)
+}
+
 pub fn query_balance(deps: Deps, address: &str) -> StdResult<BalanceResponse> {
     let address_raw = deps.api.addr_canonicalize(address)?;
     let balance = may_load_map(deps.storage, PREFIX_BALANCE, &address_raw)?.unwrap_or_default();
     Ok(BalanceResponse { balance })
 }
 
-pub fn query_claims(deps: Deps, address: &str) -> StdResult<ClaimsResponse> {
+pub fn query_claims(
+    deps: Deps,
+    address: &str,
+    start_after: Option<Timestamp>,
+    limit: Option<u32>,
+) -> StdResult<ClaimsResponse> {
     let address_raw = deps.api.addr_canonicalize(address)?;
-    let claims = may_load_map(deps.storage, PREFIX_CLAIMS, &address_raw)?.unwrap_or_default();
-    Ok(ClaimsResponse { claims })
+    let claims = load_claims(deps.storage, &address_raw)?;
+    let total = claims.iter().map(|c| c.amount).sum();
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // claims are ordered by release time
+    let start = match start_after {
+        Some(start) => claims
+            .iter()
+            .position(|c| c.release_at > start)
+            .unwrap_or(claims.len()),
+        None => 0,
+    };
+    let claims = claims.into_iter().skip(start).take(limit).collect();
+    Ok(ClaimsResponse { claims, total })
+}
+
+/// Returns up to limit entries of the map with the given prefix, starting after the given address
+fn range_addresses(
+    deps: Deps,
+    prefix: &[u8],
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Vec<u8>)>> {
+    let start = start_after
+        .map(|addr| deps.api.addr_canonicalize(&addr))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    range_map(
+        deps.storage,
+        prefix,
+        start.as_ref().map(|a| a.as_slice()),
+        limit,
+    )
+    .into_iter()
+    .map(|(key, value)| {
+        let address = deps.api.addr_humanize(&key.into())?;
+        Ok((address.into(), value))
+    })
+    .collect()
+}
+
+pub fn query_all_balances(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllBalancesResponse> {
+    let balances = range_addresses(deps, PREFIX_BALANCE, start_after, limit)?
+        .into_iter()
+        .map(|(address, value)| {
+            Ok(AddressBalance {
+                address,
+                balance: from_slice(&value)?,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(AllBalancesResponse { balances })
+}
+
+pub fn query_all_claims(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllClaimsResponse> {
+    let claims = range_addresses(deps, PREFIX_CLAIMS, start_after, limit)?
+        .into_iter()
+        .map(|(address, value)| {
+            Ok(AddressClaims {
+                address,
+                claims: parse_claims(deps.storage, &value)?,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(AllClaimsResponse { claims })
 }
 
 pub fn query_investment(deps: Deps) -> StdResult<InvestmentResponse> {
@@ -390,15 +1061,12 @@ pub fn query_investment(deps: Deps) -> StdResult<InvestmentResponse> {
     let res = InvestmentResponse {
         owner: invest.owner.into(),
         exit_tax: invest.exit_tax,
-        validator: invest.validator,
+        validators: invest.validators,
         min_withdrawal: invest.min_withdrawal,
+        unbonding_period: invest.unbonding_period,
         token_supply: supply.issued,
         staked_tokens: coin(supply.bonded.u128(), &invest.bond_denom),
-        nominal_value: if supply.issued.is_zero() {
-            FALLBACK_RATIO
-        } else {
-            Decimal::from_ratio(supply.bonded, supply.issued)
-        },
+        nominal_value: nominal_value(&supply),
     };
     Ok(res)
 }
@@ -406,6 +1074,8 @@ pub fn query_investment(deps: Deps) -> StdResult<InvestmentResponse> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::PREFIX_CLAIMS;
+    use cosmwasm_std::storage_keys::namespace_with_key;
     use cosmwasm_std::testing::{
         mock_dependencies, mock_env, mock_info, MockQuerier, MOCK_CONTRACT_ADDR,
     };
@@ -432,37 +1102,86 @@ mod tests {
         }
     }
 
+    fn all_validators() -> Vec<Validator> {
+        vec![
+            sample_validator(DEFAULT_VALIDATOR),
+            sample_validator(OTHER_VALIDATOR),
+            sample_validator(THIRD_VALIDATOR),
+        ]
+    }
+
     fn set_validator(querier: &mut MockQuerier) {
-        querier.update_staking("ustake", &[sample_validator(DEFAULT_VALIDATOR)], &[]);
+        querier.update_staking("ustake", &all_validators(), &[]);
     }
 
     fn set_delegation(querier: &mut MockQuerier, amount: u128, denom: &str) {
         querier.update_staking(
             "ustake",
-            &[sample_validator(DEFAULT_VALIDATOR)],
+            &all_validators(),
             &[sample_delegation(DEFAULT_VALIDATOR, coin(amount, denom))],
         );
     }
 
+    fn set_delegations(querier: &mut MockQuerier, delegations: &[(&str, u128)]) {
+        let delegations: Vec<FullDelegation> = delegations
+            .iter()
+            .map(|(validator, amount)| sample_delegation(validator, coin(*amount, "ustake")))
+            .collect();
+        querier.update_staking("ustake", &all_validators(), &delegations);
+    }
+
     const DEFAULT_VALIDATOR: &str = "default-validator";
+    const OTHER_VALIDATOR: &str = "other-validator";
+    const THIRD_VALIDATOR: &str = "third-validator";
+
+    const UNBONDING_PERIOD: u64 = 21 * 24 * 60 * 60;
+
+    fn weighted(validators: &[(&str, u64)]) -> Vec<ValidatorWeight> {
+        validators
+            .iter()
+            .map(|(address, weight)| ValidatorWeight {
+                address: address.to_string(),
+                weight: *weight,
+            })
+            .collect()
+    }
 
     fn default_init(tax_percent: u64, min_withdrawal: u128) -> InstantiateMsg {
+        weighted_init(tax_percent, min_withdrawal, &[(DEFAULT_VALIDATOR, 1)])
+    }
+
+    fn weighted_init(
+        tax_percent: u64,
+        min_withdrawal: u128,
+        validators: &[(&str, u64)],
+    ) -> InstantiateMsg {
         InstantiateMsg {
             name: "Cool Derivative".to_string(),
             symbol: "DRV".to_string(),
             decimals: 9,
-            validator: String::from(DEFAULT_VALIDATOR),
+            validators: weighted(validators),
             exit_tax: Decimal::percent(tax_percent),
             min_withdrawal: Uint128::new(min_withdrawal),
+            unbonding_period: UNBONDING_PERIOD,
         }
     }
 
+    fn staking_msgs(res: &Response) -> Vec<StakingMsg> {
+        res.messages
+            .iter()
+            .map(|sub| match &sub.msg {
+                CosmosMsg::Staking(msg) => msg.clone(),
+                msg => panic!("Unexpected message: {:?}", msg),
+            })
+            .collect()
+    }
+
     fn get_balance(deps: Deps, addr: &str) -> Uint128 {
         query_balance(deps, addr).unwrap().balance
     }
 
     fn get_claims(deps: Deps, addr: &str) -> Uint128 {
-        query_claims(deps, addr).unwrap().claims
+        query_claims(deps, addr, None, None).unwrap().total
     }
 
     #[test]
@@ -476,9 +1195,13 @@ mod tests {
             name: "Cool Derivative".to_string(),
             symbol: "DRV".to_string(),
             decimals: 9,
-            validator: String::from("my-validator"),
+            validators: vec![ValidatorWeight {
+                address: String::from("my-validator"),
+                weight: 1,
+            }],
             exit_tax: Decimal::percent(2),
             min_withdrawal: Uint128::new(50),
+            unbonding_period: UNBONDING_PERIOD,
         };
         let info = mock_info(&creator, &[]);
 
@@ -510,9 +1233,13 @@ mod tests {
             name: "Cool Derivative".to_string(),
             symbol: "DRV".to_string(),
             decimals: 0,
-            validator: String::from("my-validator"),
+            validators: vec![ValidatorWeight {
+                address: String::from("my-validator"),
+                weight: 1,
+            }],
             exit_tax: Decimal::percent(2),
             min_withdrawal: Uint128::new(50),
+            unbonding_period: UNBONDING_PERIOD,
         };
         let info = mock_info(&creator, &[]);
 
@@ -534,9 +1261,10 @@ mod tests {
         // investment info correct
         let invest = query_investment(deps.as_ref()).unwrap();
         assert_eq!(&invest.owner, &creator);
-        assert_eq!(&invest.validator, &msg.validator);
+        assert_eq!(&invest.validators, &msg.validators);
         assert_eq!(invest.exit_tax, msg.exit_tax);
         assert_eq!(invest.min_withdrawal, msg.min_withdrawal);
+        assert_eq!(invest.unbonding_period, UNBONDING_PERIOD);
 
         assert_eq!(invest.token_supply, Uint128::new(0));
         assert_eq!(invest.staked_tokens, coin(0, "ustake"));
@@ -589,7 +1317,8 @@ mod tests {
         set_validator(&mut deps.querier);
 
         let creator = String::from("creator");
-        let instantiate_msg = default_init(2, 50);
+        // no exit tax, such that all rewards go to the token holders
+        let instantiate_msg = default_init(0, 50);
         let info = mock_info(&creator, &[]);
 
         // make sure we can instantiate with this
@@ -607,7 +1336,7 @@ mod tests {
         set_delegation(&mut deps.querier, 1000, "ustake");
 
         // fake a reinvestment (this must be sent by the contract itself)
-        let rebond_msg = ExecuteMsg::_BondAllTokens {};
+        let rebond_msg = ExecuteMsg::_CompoundRewards {};
         let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
         deps.querier
             .update_balance(MOCK_CONTRACT_ADDR, coins(500, "ustake"));
@@ -686,23 +1415,25 @@ mod tests {
         // let's bond some tokens now
         let bob = String::from("bob");
         let bond_msg = ExecuteMsg::Bond {};
-        let info = mock_info(&bob, &[coin(10, "random"), coin(1000, "ustake")]);
+        let info = mock_info(&bob, &[coin(10, "random"), coin(1080, "ustake")]);
         let res = execute(deps.as_mut(), mock_env(), info, bond_msg).unwrap();
         assert_eq!(1, res.messages.len());
 
         // update the querier with new bond
-        set_delegation(&mut deps.querier, 1000, "ustake");
+        set_delegation(&mut deps.querier, 1080, "ustake");
 
         // fake a reinvestment (this must be sent by the contract itself)
-        // after this, we see 1000 issues and 1500 bonded (and a price of 1.5)
-        let rebond_msg = ExecuteMsg::_BondAllTokens {};
+        // the owner takes 60 of the 600 rewards as 60 / 1.5 = 40 newly minted tokens.
+        // after this, we see 1120 issues and 1680 bonded (and a price of 1.5)
+        let rebond_msg = ExecuteMsg::_CompoundRewards {};
         let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
         deps.querier
-            .update_balance(MOCK_CONTRACT_ADDR, coins(500, "ustake"));
+            .update_balance(MOCK_CONTRACT_ADDR, coins(600, "ustake"));
         let _ = execute(deps.as_mut(), mock_env(), info, rebond_msg).unwrap();
+        assert_eq!(get_balance(deps.as_ref(), &creator), Uint128::new(40));
 
         // update the querier with new bond, lower balance
-        set_delegation(&mut deps.querier, 1500, "ustake");
+        set_delegation(&mut deps.querier, 1680, "ustake");
         deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![]);
 
         // creator now tries to unbond these tokens - this must fail
@@ -724,9 +1455,9 @@ mod tests {
         let unbond_msg = ExecuteMsg::Unbond {
             amount: Uint128::new(600),
         };
-        let owner_cut = Uint128::new(60);
+        let owner_cut = Uint128::new(40 + 60);
         let bobs_claim = Uint128::new(810);
-        let bobs_balance = Uint128::new(400);
+        let bobs_balance = Uint128::new(480);
         let info = mock_info(&bob, &[]);
         let res = execute(deps.as_mut(), mock_env(), info, unbond_msg).unwrap();
         assert_eq!(1, res.messages.len());
@@ -740,7 +1471,7 @@ mod tests {
         }
 
         // update the querier with new bond, lower balance
-        set_delegation(&mut deps.querier, 690, "ustake");
+        set_delegation(&mut deps.querier, 870, "ustake");
 
         // check balances
         assert_eq!(get_balance(deps.as_ref(), &bob), bobs_balance);
@@ -753,7 +1484,1191 @@ mod tests {
 
         let invest = query_investment(deps.as_ref()).unwrap();
         assert_eq!(invest.token_supply, bobs_balance + owner_cut);
-        assert_eq!(invest.staked_tokens, coin(690, "ustake")); // 1500 - 810
+        assert_eq!(invest.staked_tokens, coin(870, "ustake")); // 1680 - 810
         assert_eq!(invest.nominal_value, ratio);
     }
+
+    #[test]
+    fn initialization_with_invalid_validator_set() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+        let info = mock_info("creator", &[]);
+
+        let msg = weighted_init(2, 50, &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: validator set must not be empty"
+        );
+
+        let msg = weighted_init(2, 50, &[(DEFAULT_VALIDATOR, 1), (OTHER_VALIDATOR, 0)]);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: weight of other-validator must not be zero"
+        );
+
+        let msg = weighted_init(2, 50, &[(DEFAULT_VALIDATOR, 1), (DEFAULT_VALIDATOR, 2)]);
+        let err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: duplicate validator default-validator"
+        );
+    }
+
+    #[test]
+    fn bonding_delegates_by_weight() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let instantiate_msg = weighted_init(2, 50, &[(DEFAULT_VALIDATOR, 1), (OTHER_VALIDATOR, 3)]);
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        // the rounding remainder goes to the validator with the highest weight
+        let info = mock_info("bob", &[coin(1001, "ustake")]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![
+                StakingMsg::Delegate {
+                    validator: DEFAULT_VALIDATOR.to_string(),
+                    amount: coin(250, "ustake"),
+                },
+                StakingMsg::Delegate {
+                    validator: OTHER_VALIDATOR.to_string(),
+                    amount: coin(751, "ustake"),
+                },
+            ]
+        );
+        assert_eq!(get_balance(deps.as_ref(), "bob"), Uint128::new(1001));
+
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(1001, "ustake"));
+        assert_eq!(
+            invest.validators,
+            weighted(&[(DEFAULT_VALIDATOR, 1), (OTHER_VALIDATOR, 3)])
+        );
+    }
+
+    #[test]
+    fn split_proportionally_works() {
+        let shares = |amounts: &[u128]| -> Vec<(String, Uint128)> {
+            amounts
+                .iter()
+                .enumerate()
+                .map(|(i, amount)| (format!("val{}", i), Uint128::new(*amount)))
+                .collect()
+        };
+        let amounts = |parts: Vec<(String, Uint128)>| -> Vec<u128> {
+            parts.into_iter().map(|(_, part)| part.u128()).collect()
+        };
+
+        // exact split
+        let parts = split_proportionally(Uint128::new(60), &shares(&[1, 2, 3]));
+        assert_eq!(amounts(parts), [10, 20, 30]);
+
+        // the remainder is spread over multiple parts instead of exceeding a share
+        let parts = split_proportionally(Uint128::new(2), &shares(&[1, 1, 1]));
+        assert_eq!(amounts(parts), [1, 1, 0]);
+        let parts = split_proportionally(Uint128::new(3), &shares(&[1, 1, 1]));
+        assert_eq!(amounts(parts), [1, 1, 1]);
+
+        // largest fractional remainders first: 7 * [5, 3, 2] / 10 = [3.5, 2.1, 1.4]
+        let parts = split_proportionally(Uint128::new(7), &shares(&[5, 3, 2]));
+        assert_eq!(amounts(parts), [4, 2, 1]);
+
+        // amounts larger than the total of the shares are split as well
+        let parts = split_proportionally(Uint128::new(100), &shares(&[1, 1, 1]));
+        assert_eq!(amounts(parts), [34, 33, 33]);
+
+        // zero shares get nothing
+        let parts = split_proportionally(Uint128::new(5), &shares(&[0, 2, 0, 3]));
+        assert_eq!(amounts(parts), [0, 2, 0, 3]);
+        let parts = split_proportionally(Uint128::new(5), &shares(&[0, 0]));
+        assert!(parts.is_empty());
+    }
+
+    #[test]
+    fn unbonding_follows_delegations() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let instantiate_msg = weighted_init(0, 50, &[(DEFAULT_VALIDATOR, 1), (OTHER_VALIDATOR, 3)]);
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+
+        // the delegations do not match the weights (e.g. after a validator was added)
+        set_delegations(
+            &mut deps.querier,
+            &[(DEFAULT_VALIDATOR, 500), (OTHER_VALIDATOR, 500)],
+        );
+
+        let unbond_msg = ExecuteMsg::Unbond {
+            amount: Uint128::new(400),
+        };
+        let info = mock_info("bob", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, unbond_msg).unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![
+                StakingMsg::Undelegate {
+                    validator: DEFAULT_VALIDATOR.to_string(),
+                    amount: coin(200, "ustake"),
+                },
+                StakingMsg::Undelegate {
+                    validator: OTHER_VALIDATOR.to_string(),
+                    amount: coin(200, "ustake"),
+                },
+            ]
+        );
+        assert_eq!(get_claims(deps.as_ref(), "bob"), Uint128::new(400));
+    }
+
+    #[test]
+    fn validator_set_can_only_be_changed_by_owner() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(2, 50)).unwrap();
+
+        let msgs = [
+            ExecuteMsg::AddValidator {
+                address: OTHER_VALIDATOR.to_string(),
+                weight: 1,
+            },
+            ExecuteMsg::RemoveValidator {
+                address: DEFAULT_VALIDATOR.to_string(),
+            },
+            ExecuteMsg::Rebalance {},
+        ];
+        for msg in msgs {
+            let info = mock_info("bob", &[]);
+            match execute(deps.as_mut(), mock_env(), info, msg).unwrap_err() {
+                StakingError::Unauthorized { .. } => {}
+                err => panic!("Unexpected error: {:?}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn add_validator_works() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), default_init(2, 50)).unwrap();
+
+        let msg = ExecuteMsg::AddValidator {
+            address: OTHER_VALIDATOR.to_string(),
+            weight: 2,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(res.messages.len(), 0);
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(
+            invest.validators,
+            weighted(&[(DEFAULT_VALIDATOR, 1), (OTHER_VALIDATOR, 2)])
+        );
+
+        // cannot add twice
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: duplicate validator other-validator"
+        );
+
+        // must be registered
+        let msg = ExecuteMsg::AddValidator {
+            address: "unknown".to_string(),
+            weight: 2,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: unknown is not in the current validator set"
+        );
+
+        // weight must not be zero
+        let msg = ExecuteMsg::AddValidator {
+            address: THIRD_VALIDATOR.to_string(),
+            weight: 0,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: weight of third-validator must not be zero"
+        );
+    }
+
+    #[test]
+    fn remove_validator_redelegates() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let instantiate_msg = weighted_init(
+            2,
+            50,
+            &[
+                (DEFAULT_VALIDATOR, 1),
+                (OTHER_VALIDATOR, 3),
+                (THIRD_VALIDATOR, 2),
+            ],
+        );
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg).unwrap();
+        set_delegations(
+            &mut deps.querier,
+            &[
+                (DEFAULT_VALIDATOR, 100),
+                (OTHER_VALIDATOR, 300),
+                (THIRD_VALIDATOR, 200),
+            ],
+        );
+
+        let msg = ExecuteMsg::RemoveValidator {
+            address: THIRD_VALIDATOR.to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![
+                StakingMsg::Redelegate {
+                    src_validator: THIRD_VALIDATOR.to_string(),
+                    dst_validator: DEFAULT_VALIDATOR.to_string(),
+                    amount: coin(50, "ustake"),
+                },
+                StakingMsg::Redelegate {
+                    src_validator: THIRD_VALIDATOR.to_string(),
+                    dst_validator: OTHER_VALIDATOR.to_string(),
+                    amount: coin(150, "ustake"),
+                },
+            ]
+        );
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(
+            invest.validators,
+            weighted(&[(DEFAULT_VALIDATOR, 1), (OTHER_VALIDATOR, 3)])
+        );
+
+        // cannot remove unknown validators
+        let msg = ExecuteMsg::RemoveValidator {
+            address: THIRD_VALIDATOR.to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: third-validator is not in the validator set"
+        );
+
+        // cannot remove the last validator
+        let msg = ExecuteMsg::RemoveValidator {
+            address: OTHER_VALIDATOR.to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::RemoveValidator {
+            address: DEFAULT_VALIDATOR.to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: cannot remove the last validator"
+        );
+    }
+
+    #[test]
+    fn rebalance_matches_weights() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), default_init(2, 50)).unwrap();
+        let info_bob = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info_bob, ExecuteMsg::Bond {}).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        // already balanced
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Rebalance {},
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        for (address, weight) in [(OTHER_VALIDATOR, 1), (THIRD_VALIDATOR, 2)] {
+            let msg = ExecuteMsg::AddValidator {
+                address: address.to_string(),
+                weight,
+            };
+            execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Rebalance {}).unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![
+                StakingMsg::Redelegate {
+                    src_validator: DEFAULT_VALIDATOR.to_string(),
+                    dst_validator: OTHER_VALIDATOR.to_string(),
+                    amount: coin(250, "ustake"),
+                },
+                StakingMsg::Redelegate {
+                    src_validator: DEFAULT_VALIDATOR.to_string(),
+                    dst_validator: THIRD_VALIDATOR.to_string(),
+                    amount: coin(500, "ustake"),
+                },
+            ]
+        );
+
+        // redelegating does not change the bonded amount
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(1000, "ustake"));
+    }
+
+    #[test]
+    fn compound_withdraws_rewards() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let instantiate_msg =
+            weighted_init(10, 50, &[(DEFAULT_VALIDATOR, 1), (OTHER_VALIDATOR, 1)]);
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        // anyone can compound
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Compound {}).unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                validator: DEFAULT_VALIDATOR.to_string(),
+            })
+        );
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::_CompoundRewards {}).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // the chain can do the same via sudo
+        let sudo_res = sudo(deps.as_mut(), mock_env(), SudoMsg::Compound {}).unwrap();
+        assert_eq!(sudo_res, res);
+
+        // the callback can only be called by the contract itself
+        let info = mock_info("anyone", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::_CompoundRewards {},
+        );
+        match res.unwrap_err() {
+            StakingError::Unauthorized { .. } => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn reinvest_pays_owner_cut_like_compound() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let creator = String::from("creator");
+        let instantiate_msg =
+            weighted_init(10, 50, &[(DEFAULT_VALIDATOR, 1), (OTHER_VALIDATOR, 1)]);
+        let info = mock_info(&creator, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, instantiate_msg).unwrap();
+
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        set_delegations(
+            &mut deps.querier,
+            &[(DEFAULT_VALIDATOR, 500), (OTHER_VALIDATOR, 500)],
+        );
+
+        // reinvest withdraws the rewards from all validators, just like compound
+        let info = mock_info("anyone", &[]);
+        let reinvest_res =
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Reinvest {}).unwrap();
+        let info = mock_info("anyone", &[]);
+        let compound_res =
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Compound {}).unwrap();
+        assert_eq!(reinvest_res, compound_res);
+        assert_eq!(
+            reinvest_res.messages[1].msg,
+            CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                validator: OTHER_VALIDATOR.to_string(),
+            })
+        );
+
+        // the withdrawn rewards are bonded by weight and the owner gets their cut
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(500, "ustake"));
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![
+                StakingMsg::Delegate {
+                    validator: DEFAULT_VALIDATOR.to_string(),
+                    amount: coin(250, "ustake"),
+                },
+                StakingMsg::Delegate {
+                    validator: OTHER_VALIDATOR.to_string(),
+                    amount: coin(250, "ustake"),
+                },
+            ]
+        );
+        assert_eq!(get_balance(deps.as_ref(), &creator), Uint128::new(34));
+        set_delegations(
+            &mut deps.querier,
+            &[(DEFAULT_VALIDATOR, 750), (OTHER_VALIDATOR, 750)],
+        );
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![]);
+
+        // the callback of the compound call finds no rewards left, so the cut is not paid twice
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+        assert_eq!(res, Response::default());
+        assert_eq!(get_balance(deps.as_ref(), &creator), Uint128::new(34));
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(1034));
+        assert_eq!(invest.staked_tokens, coin(1500, "ustake"));
+    }
+
+    #[test]
+    fn compound_rewards_pays_owner_cut() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let creator = String::from("creator");
+        let info = mock_info(&creator, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(10, 50)).unwrap();
+
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        // 500 tokens of rewards were withdrawn
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(500, "ustake"));
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![StakingMsg::Delegate {
+                validator: DEFAULT_VALIDATOR.to_string(),
+                amount: coin(500, "ustake"),
+            }]
+        );
+
+        // the owner's cut of 50 tokens is worth 50 * 1000 / 1450 = 34.48 derivative tokens,
+        // which is rounded down in favour of the existing holders
+        assert_eq!(get_balance(deps.as_ref(), &creator), Uint128::new(34));
+        assert_eq!(get_balance(deps.as_ref(), "bob"), Uint128::new(1000));
+
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(1034));
+        assert_eq!(invest.staked_tokens, coin(1500, "ustake"));
+        assert_eq!(
+            invest.nominal_value,
+            Decimal::from_ratio(1500u128, 1034u128)
+        );
+        // bob's tokens are worth at least the rewards minus the owner's cut
+        assert!(invest.nominal_value * Uint128::new(1000) >= Uint128::new(1450));
+    }
+
+    #[test]
+    fn compound_rewards_without_tax() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let creator = String::from("creator");
+        let info = mock_info(&creator, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(0, 50)).unwrap();
+
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(333, "ustake"));
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+
+        assert_eq!(get_balance(deps.as_ref(), &creator), Uint128::zero());
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(1000));
+        assert_eq!(invest.staked_tokens, coin(1333, "ustake"));
+        assert_eq!(invest.nominal_value, Decimal::from_str("1.333").unwrap());
+    }
+
+    #[test]
+    fn compound_while_claims_are_pending() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(10, 50)).unwrap();
+
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        // bob unbonds 100 tokens, 90 after tax, which are claimed once the unbonding finished
+        let info = mock_info("bob", &[]);
+        let unbond_msg = ExecuteMsg::Unbond {
+            amount: Uint128::new(100),
+        };
+        execute(deps.as_mut(), mock_env(), info, unbond_msg).unwrap();
+        set_delegation(&mut deps.querier, 910, "ustake");
+        assert_eq!(get_claims(deps.as_ref(), "bob"), Uint128::new(90));
+
+        // the callback finds the balance on its own, so it needs no arguments
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::Compound {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::_CompoundRewards {}).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // while the unbonding is pending, the withdrawn rewards are held back for the claims
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(60, "ustake"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+        assert_eq!(res, Response::default());
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(910, "ustake"));
+
+        // once the unbonding finished, everything above the claims is compounded
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(150, "ustake"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![StakingMsg::Delegate {
+                validator: DEFAULT_VALIDATOR.to_string(),
+                amount: coin(60, "ustake"),
+            }]
+        );
+        set_delegation(&mut deps.querier, 970, "ustake");
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(970, "ustake"));
+        assert_eq!(get_claims(deps.as_ref(), "bob"), Uint128::new(90));
+
+        // rewards below min_withdrawal are not compounded
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(139, "ustake"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+        assert_eq!(res, Response::default());
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(970, "ustake"));
+
+        // only the rewards are compounded, the unbonded tokens are kept for the claims
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(190, "ustake"));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![StakingMsg::Delegate {
+                validator: DEFAULT_VALIDATOR.to_string(),
+                amount: coin(100, "ustake"),
+            }]
+        );
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(1070, "ustake"));
+        assert_eq!(get_claims(deps.as_ref(), "bob"), Uint128::new(90));
+    }
+
+    #[test]
+    fn reinvest_compounds_rewards_withdrawn_by_bond() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let creator = String::from("creator");
+        let info = mock_info(&creator, &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(10, 50)).unwrap();
+
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        // changing the delegation makes the chain withdraw the 100 pending rewards
+        let info = mock_info("alice", &[coin(500, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        set_delegation(&mut deps.querier, 1500, "ustake");
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(100, "ustake"));
+
+        // reinvest withdraws nothing new, but the callback still compounds those rewards
+        let info = mock_info("anyone", &[]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Reinvest {}).unwrap();
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::_CompoundRewards {},
+        )
+        .unwrap();
+        assert_eq!(
+            staking_msgs(&res),
+            vec![StakingMsg::Delegate {
+                validator: DEFAULT_VALIDATOR.to_string(),
+                amount: coin(100, "ustake"),
+            }]
+        );
+
+        // the owner's cut of 10 tokens is worth 10 * 1500 / 1590 = 9.43 derivative tokens
+        assert_eq!(get_balance(deps.as_ref(), &creator), Uint128::new(9));
+        set_delegation(&mut deps.querier, 1600, "ustake");
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(1509));
+        assert_eq!(invest.staked_tokens, coin(1600, "ustake"));
+    }
+
+    #[test]
+    fn check_slash_applies_loss() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(2, 50)).unwrap();
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        // nothing slashed
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CheckSlash {}).unwrap();
+        assert_eq!(res.events.len(), 0);
+
+        // the validator lost 10% of its stake, so bonding fails until the loss is applied
+        set_delegation(&mut deps.querier, 900, "ustake");
+        let info = mock_info("alice", &[coin(900, "ustake")]);
+        let err =
+            execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::Bond {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: Stored bonded 1000, but query bonded: 900"
+        );
+
+        let res = sudo(deps.as_mut(), mock_env(), SudoMsg::CheckSlash {}).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("slash")
+                .add_attribute("loss", "100")
+                .add_attribute("bonded", "900")
+                .add_attribute("nominal_value", "0.9")]
+        );
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.token_supply, Uint128::new(1000));
+        assert_eq!(invest.staked_tokens, coin(900, "ustake"));
+        assert_eq!(invest.nominal_value, Decimal::percent(90));
+
+        // new tokens are issued at the lower price
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        assert_eq!(get_balance(deps.as_ref(), "alice"), Uint128::new(1000));
+
+        // checking again does not apply the loss twice
+        set_delegation(&mut deps.querier, 1800, "ustake");
+        let res = sudo(deps.as_mut(), mock_env(), SudoMsg::CheckSlash {}).unwrap();
+        assert_eq!(res.events.len(), 0);
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.staked_tokens, coin(1800, "ustake"));
+    }
+
+    #[test]
+    fn claims_are_released_after_unbonding_period() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(0, 50)).unwrap();
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+        set_delegation(&mut deps.querier, 1000, "ustake");
+
+        let unbond_msg = ExecuteMsg::Unbond {
+            amount: Uint128::new(100),
+        };
+        let info = mock_info("bob", &[]);
+
+        // claims created in the same block are merged
+        let env = mock_env();
+        let first_release = env.block.time.plus_seconds(UNBONDING_PERIOD);
+        execute(deps.as_mut(), env.clone(), info.clone(), unbond_msg.clone()).unwrap();
+        set_delegation(&mut deps.querier, 900, "ustake");
+        execute(deps.as_mut(), env.clone(), info.clone(), unbond_msg.clone()).unwrap();
+        set_delegation(&mut deps.querier, 800, "ustake");
+
+        let mut later_env = mock_env();
+        later_env.block.time = env.block.time.plus_seconds(24 * 60 * 60);
+        let second_release = later_env.block.time.plus_seconds(UNBONDING_PERIOD);
+        execute(deps.as_mut(), later_env.clone(), info.clone(), unbond_msg).unwrap();
+        set_delegation(&mut deps.querier, 700, "ustake");
+
+        let first = Claim {
+            amount: Uint128::new(200),
+            release_at: first_release,
+        };
+        let second = Claim {
+            amount: Uint128::new(100),
+            release_at: second_release,
+        };
+        let res = query_claims(deps.as_ref(), "bob", None, None).unwrap();
+        assert_eq!(res.claims, vec![first.clone(), second.clone()]);
+        assert_eq!(res.total, Uint128::new(300));
+
+        // claims can be paginated
+        let res = query_claims(deps.as_ref(), "bob", None, Some(1)).unwrap();
+        assert_eq!(res.claims, vec![first.clone()]);
+        assert_eq!(res.total, Uint128::new(300));
+        let res = query_claims(deps.as_ref(), "bob", Some(first_release), Some(1)).unwrap();
+        assert_eq!(res.claims, vec![second.clone()]);
+        let res = query_claims(deps.as_ref(), "bob", Some(second_release), None).unwrap();
+        assert_eq!(res.claims, vec![]);
+
+        // nothing is released before the unbonding period is over
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(300, "ustake"));
+        let err = execute(
+            deps.as_mut(),
+            later_env,
+            info.clone(),
+            ExecuteMsg::ProcessClaims {},
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: no released claims for this address"
+        );
+
+        // the first claim is paid out once released
+        let mut env = mock_env();
+        env.block.time = first_release;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ProcessClaims {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: coins(200, "ustake"),
+            })
+        );
+        let res = query_claims(deps.as_ref(), "bob", None, None).unwrap();
+        assert_eq!(res.claims, vec![second]);
+        let supply: Supply = load_item(&deps.storage, KEY_TOTAL_SUPPLY).unwrap();
+        assert_eq!(supply.claims, Uint128::new(100));
+
+        // the contract must have received the unbonded tokens
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(50, "ustake"));
+        env.block.time = second_release;
+        let err = execute(deps.as_mut(), env, info, ExecuteMsg::ProcessClaims {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: Insufficient balance in contract to process claims"
+        );
+        assert_eq!(get_claims(deps.as_ref(), "bob"), Uint128::new(100));
+    }
+
+    #[test]
+    fn send_calls_receiver() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(2, 50)).unwrap();
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+
+        let token = query_token_info(deps.as_ref()).unwrap();
+        assert_eq!(token.total_supply, Uint128::new(1000));
+
+        let send_msg = ExecuteMsg::Send {
+            contract: "amm".to_string(),
+            amount: Uint128::new(100),
+            msg: Binary::from(br#"{"swap":{}}"#),
+        };
+        let info = mock_info("bob", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, send_msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => {
+                assert_eq!(contract_addr, "amm");
+                assert_eq!(
+                    msg.as_slice(),
+                    br#"{"receive":{"sender":"bob","amount":"100","msg":"eyJzd2FwIjp7fX0="}}"#
+                );
+                assert_eq!(funds, &[]);
+            }
+            msg => panic!("Unexpected message: {:?}", msg),
+        }
+        assert_eq!(get_balance(deps.as_ref(), "bob"), Uint128::new(900));
+        assert_eq!(get_balance(deps.as_ref(), "amm"), Uint128::new(100));
+    }
+
+    #[test]
+    fn allowances_work() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(2, 50)).unwrap();
+        let info = mock_info("bob", &[coin(1000, "ustake")]);
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+
+        let env = mock_env();
+        let bob = mock_info("bob", &[]);
+        let amm = mock_info("amm", &[]);
+        let expires = Expiration::AtHeight(env.block.height + 10);
+
+        // cannot approve yourself or with an expired expiration
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "bob".to_string(),
+            amount: Uint128::new(300),
+            expires: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), bob.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: cannot set allowance to own account"
+        );
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "amm".to_string(),
+            amount: Uint128::new(300),
+            expires: Some(Expiration::AtHeight(env.block.height)),
+        };
+        let err = execute(deps.as_mut(), env.clone(), bob.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: invalid expiration value"
+        );
+
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "amm".to_string(),
+            amount: Uint128::new(300),
+            expires: Some(expires),
+        };
+        execute(deps.as_mut(), env.clone(), bob.clone(), msg).unwrap();
+        let msg = ExecuteMsg::DecreaseAllowance {
+            spender: "amm".to_string(),
+            amount: Uint128::new(100),
+            expires: None,
+        };
+        execute(deps.as_mut(), env.clone(), bob.clone(), msg).unwrap();
+        assert_eq!(
+            query_allowance(deps.as_ref(), "bob", "amm").unwrap(),
+            AllowanceResponse {
+                allowance: Uint128::new(200),
+                expires,
+            }
+        );
+
+        // the spender moves tokens of the owner
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "bob".to_string(),
+            recipient: "carol".to_string(),
+            amount: Uint128::new(150),
+        };
+        execute(deps.as_mut(), env.clone(), amm.clone(), msg).unwrap();
+        assert_eq!(get_balance(deps.as_ref(), "bob"), Uint128::new(850));
+        assert_eq!(get_balance(deps.as_ref(), "carol"), Uint128::new(150));
+
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "bob".to_string(),
+            recipient: "carol".to_string(),
+            amount: Uint128::new(51),
+        };
+        let err = execute(deps.as_mut(), env.clone(), amm.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: no allowance for this account"
+        );
+
+        // send from notifies the receiving contract about the spender
+        let msg = ExecuteMsg::SendFrom {
+            owner: "bob".to_string(),
+            contract: "pool".to_string(),
+            amount: Uint128::new(50),
+            msg: Binary::from(b"{}"),
+        };
+        let res = execute(deps.as_mut(), env.clone(), amm.clone(), msg).unwrap();
+        let receive = Cw20ReceiveMsg {
+            sender: "amm".to_string(),
+            amount: Uint128::new(50),
+            msg: Binary::from(b"{}"),
+        };
+        assert_eq!(
+            res.messages[0].msg,
+            receive.into_cosmos_msg("pool").unwrap()
+        );
+        assert_eq!(get_balance(deps.as_ref(), "pool"), Uint128::new(50));
+
+        // the used up allowance is removed
+        assert_eq!(
+            query_allowance(deps.as_ref(), "bob", "amm").unwrap(),
+            AllowanceResponse::default()
+        );
+
+        // expired allowances cannot be used
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "amm".to_string(),
+            amount: Uint128::new(100),
+            expires: Some(expires),
+        };
+        execute(deps.as_mut(), env.clone(), bob, msg).unwrap();
+        let mut later_env = env;
+        later_env.block.height += 10;
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "bob".to_string(),
+            recipient: "carol".to_string(),
+            amount: Uint128::new(1),
+        };
+        let err = execute(deps.as_mut(), later_env, amm, msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: allowance is expired"
+        );
+    }
+
+    #[test]
+    fn migrate_converts_v1_layout() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        // the state of the first version
+        let old = v1::InvestmentInfo {
+            owner: Addr::unchecked("creator"),
+            bond_denom: "ustake".to_string(),
+            exit_tax: Decimal::percent(2),
+            validator: DEFAULT_VALIDATOR.to_string(),
+            min_withdrawal: Uint128::new(50),
+        };
+        save_item(&mut deps.storage, KEY_INVESTMENT, &old).unwrap();
+        let supply = Supply {
+            issued: Uint128::new(1000),
+            bonded: Uint128::new(1000),
+            claims: Uint128::new(300),
+        };
+        save_item(&mut deps.storage, KEY_TOTAL_SUPPLY, &supply).unwrap();
+        for addr in ["alice", "bob", "carol"] {
+            let addr_raw = deps.api.addr_canonicalize(addr).unwrap();
+            save_map(
+                &mut deps.storage,
+                PREFIX_CLAIMS,
+                &addr_raw,
+                Uint128::new(100),
+            )
+            .unwrap();
+        }
+
+        let env = mock_env();
+        let release_at = env.block.time.plus_seconds(UNBONDING_PERIOD);
+        let msg = MigrateMsg {
+            unbonding_period: UNBONDING_PERIOD,
+        };
+        migrate(deps.as_mut(), env.clone(), msg.clone()).unwrap();
+        let invest = query_investment(deps.as_ref()).unwrap();
+        assert_eq!(invest.validators, weighted(&[(DEFAULT_VALIDATOR, 1)]));
+        assert_eq!(invest.unbonding_period, UNBONDING_PERIOD);
+        assert_eq!(invest.owner, "creator");
+
+        let err = migrate(deps.as_mut(), env, msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: contract is migrated already"
+        );
+
+        // claims not converted yet can be used as usual
+        let old_claim = Claim {
+            amount: Uint128::new(100),
+            release_at,
+        };
+        let res = query_claims(deps.as_ref(), "alice", None, None).unwrap();
+        assert_eq!(res.claims, vec![old_claim.clone()]);
+
+        // new claims are stored in the new layout
+        let carol_raw = deps.api.addr_canonicalize("carol").unwrap();
+        let carol_claims = vec![
+            old_claim.clone(),
+            Claim {
+                amount: Uint128::new(5),
+                release_at: release_at.plus_seconds(1),
+            },
+        ];
+        save_claims(&mut deps.storage, &carol_raw, &carol_claims).unwrap();
+
+        // claims are converted in chunks by anyone
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::MigrateClaims { limit: Some(2) };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(res.attributes[1].value, "2");
+        assert_eq!(res.attributes[2].value, "false");
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(res.attributes[2].value, "true");
+
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "StdError: Generic error: no claims migration in progress"
+        );
+
+        // all claims are in the new layout now
+        for addr in ["alice", "bob"] {
+            let addr_raw = deps.api.addr_canonicalize(addr).unwrap();
+            let stored = deps
+                .storage
+                .get(&namespace_with_key(&[PREFIX_CLAIMS], &addr_raw))
+                .unwrap();
+            let claims: Vec<Claim> = from_slice(&stored).unwrap();
+            assert_eq!(claims, vec![old_claim.clone()]);
+        }
+        let res = query_claims(deps.as_ref(), "carol", None, None).unwrap();
+        assert_eq!(res.claims, carol_claims);
+    }
+
+    #[test]
+    fn balances_and_claims_can_be_enumerated() {
+        let mut deps = mock_dependencies();
+        set_validator(&mut deps.querier);
+
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, default_init(0, 50)).unwrap();
+        let mut bonded = 0;
+        for (addr, amount) in [("alice", 100), ("bob", 200), ("carol", 300)] {
+            let info = mock_info(addr, &[coin(amount, "ustake")]);
+            execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Bond {}).unwrap();
+            bonded += amount;
+            set_delegation(&mut deps.querier, bonded, "ustake");
+        }
+        let unbond_msg = ExecuteMsg::Unbond {
+            amount: Uint128::new(50),
+        };
+        for addr in ["alice", "carol"] {
+            let info = mock_info(addr, &[]);
+            execute(deps.as_mut(), mock_env(), info, unbond_msg.clone()).unwrap();
+            bonded -= 50;
+            set_delegation(&mut deps.querier, bonded, "ustake");
+        }
+
+        // page through all balances
+        let first = query_all_balances(deps.as_ref(), None, Some(2)).unwrap();
+        assert_eq!(first.balances.len(), 2);
+        let start_after = first.balances[1].address.clone();
+        let rest = query_all_balances(deps.as_ref(), Some(start_after), Some(2)).unwrap();
+        assert_eq!(rest.balances.len(), 1);
+        let mut balances: Vec<_> = first
+            .balances
+            .into_iter()
+            .chain(rest.balances)
+            .map(|b| (b.address, b.balance.u128()))
+            .collect();
+        balances.sort();
+        assert_eq!(
+            balances,
+            vec![
+                ("alice".to_string(), 50),
+                ("bob".to_string(), 200),
+                ("carol".to_string(), 250)
+            ]
+        );
+
+        // only addresses with claims are listed
+        let res = query_all_claims(deps.as_ref(), None, None).unwrap();
+        let mut addresses: Vec<_> = res.claims.iter().map(|c| c.address.as_str()).collect();
+        addresses.sort_unstable();
+        assert_eq!(addresses, ["alice", "carol"]);
+        let release_at = mock_env().block.time.plus_seconds(UNBONDING_PERIOD);
+        for claims in &res.claims {
+            assert_eq!(
+                claims.claims,
+                vec![Claim {
+                    amount: Uint128::new(50),
+                    release_at,
+                }]
+            );
+        }
+        let start_after = res.claims[1].address.clone();
+        let res = query_all_claims(deps.as_ref(), Some(start_after), None).unwrap();
+        assert_eq!(res.claims, vec![]);
+    }
 }
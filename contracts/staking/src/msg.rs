@@ -1,6 +1,8 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use cosmwasm_std::{Coin, Decimal, Uint128};
+use cosmwasm_std::{
+    to_binary, Binary, BlockInfo, Coin, CosmosMsg, Decimal, StdResult, Timestamp, Uint128, WasmMsg,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -13,8 +15,8 @@ pub struct InstantiateMsg {
     /// We don't even know the decimals of the native token
     pub decimals: u8,
 
-    /// This is the validator that all tokens will be bonded to
-    pub validator: String,
+    /// These are the validators that tokens will be bonded to, in proportion to their weights
+    pub validators: Vec<ValidatorWeight>,
 
     /// this is how much the owner takes as a cut when someone unbonds
     /// TODO
@@ -22,28 +24,116 @@ pub struct InstantiateMsg {
     /// This is the minimum amount we will pull out to reinvest, as well as a minumum
     /// that can be unbonded (to avoid needless staking tx)
     pub min_withdrawal: Uint128,
+    /// The chain's unbonding period in seconds. Unbonded tokens can be claimed after this time.
+    pub unbonding_period: u64,
+}
+
+/// MigrateMsg upgrades the storage layout of the first version of this contract,
+/// which bonded to a single validator and had claims without release time
+#[cw_serde]
+pub struct MigrateMsg {
+    /// The chain's unbonding period in seconds. Existing claims are released after
+    /// this time has passed since the migration.
+    pub unbonding_period: u64,
+}
+
+/// A validator of the set the contract delegates to
+#[cw_serde]
+#[derive(Eq)]
+pub struct ValidatorWeight {
+    /// The validator's operator address (e.g. cosmosvaloper1...)
+    pub address: String,
+    /// The share of the bonded tokens delegated to this validator is
+    /// `weight / sum of all weights`. Must not be zero.
+    pub weight: u64,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     /// Transfer moves the derivative token
     Transfer { recipient: String, amount: Uint128 },
+    /// Send moves the derivative token to a contract and calls its `Receive` handler
+    /// with the given message, like a CW20 token does
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// IncreaseAllowance allows spender to move the given amount more of the sender's tokens.
+    /// If expires is set, it replaces the current expiration.
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// DecreaseAllowance lowers the amount spender may move. The allowance is removed
+    /// once it reaches zero. If expires is set, it replaces the current expiration.
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// TransferFrom moves tokens of owner to recipient, using the allowance of the sender
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// SendFrom moves tokens of owner to a contract like Send, using the allowance of the sender
+    SendFrom {
+        owner: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
     /// Bond will bond all staking tokens sent with the message and release derivative tokens
     Bond {},
     /// Unbond will "burn" the given amount of derivative tokens and send the unbonded
-    /// staking tokens to the message sender (after exit tax is deducted)
+    /// staking tokens to the message sender (after exit tax is deducted).
+    /// Tokens are unbonded from all validators in proportion to the current delegations.
     Unbond { amount: Uint128 },
-    /// Claim is used to claim your native tokens that you previously "unbonded"
-    /// after the chain-defined waiting period (eg. 3 weeks)
-    Claim {},
-    /// Reinvest will check for all accumulated rewards, withdraw them, and
-    /// re-bond them to the same validator. Anyone can call this, which updates
-    /// the value of the token (how much under custody).
+    /// ProcessClaims pays out all of your claims on native tokens that you previously
+    /// "unbonded" and whose chain-defined waiting period (eg. 3 weeks) is over
+    ProcessClaims {},
+    /// Reinvest is the same as Compound. It is kept for backwards compatibility.
     Reinvest {},
-    /// _BondAllTokens can only be called by the contract itself, after all rewards have been
+    /// Compound will check for all accumulated rewards, withdraw them, and
+    /// re-bond them to the validator set, which updates the value of the token
+    /// (how much under custody). The owner takes the exit tax from the rewards.
+    /// The cut is paid in derivative tokens, so all rewards remain bonded.
+    /// Anyone can call this, e.g. on a schedule. It is also available via sudo.
+    Compound {},
+    /// _CompoundRewards can only be called by the contract itself, after all rewards have been
     /// withdrawn. This is an example of using "callbacks" in message flows.
-    /// This can only be invoked by the contract itself as a return from Reinvest
-    _BondAllTokens {},
+    /// It bonds all liquid tokens that are not reserved for claims, which includes the rewards
+    /// the chain withdrew automatically when a delegation changed, and mints the owner's cut.
+    _CompoundRewards {},
+    /// CheckSlash compares the bonded tokens with the actual delegations and applies the loss
+    /// if a validator was slashed, lowering the value of all derivative tokens pro-rata.
+    /// Anyone can call this. It is also available via sudo.
+    CheckSlash {},
+    /// AddValidator adds a validator to the set. Only the owner can call this.
+    /// Existing delegations are not moved before `Rebalance` is called.
+    AddValidator { address: String, weight: u64 },
+    /// RemoveValidator removes a validator from the set and redelegates all tokens bonded to it
+    /// to the remaining validators. Only the owner can call this.
+    RemoveValidator { address: String },
+    /// Rebalance redelegates tokens between the validators such that the delegations
+    /// match the weights again. Only the owner can call this.
+    Rebalance {},
+    /// MigrateClaims converts up to limit claims to the new layout after a migration
+    /// from the first version. Anyone can call this until all claims are converted.
+    /// Claims that are not converted yet can be used as usual.
+    MigrateClaims { limit: Option<u32> },
+}
+
+/// SudoMsg allows the chain to drive the contract, e.g. from a begin blocker
+#[cw_serde]
+pub enum SudoMsg {
+    /// Compound withdraws and re-bonds all rewards, see `ExecuteMsg::Compound`
+    Compound {},
+    /// CheckSlash applies losses from slashing, see `ExecuteMsg::CheckSlash`
+    CheckSlash {},
 }
 
 #[cw_serde]
@@ -52,14 +142,38 @@ pub enum QueryMsg {
     /// Balance shows the number of staking derivatives
     #[returns(BalanceResponse)]
     Balance { address: String },
-    /// Claims shows the number of tokens this address can access when they are done unbonding
+    /// Claims shows the tokens this address can access when they are done unbonding,
+    /// ordered by release time
     #[returns(ClaimsResponse)]
-    Claims { address: String },
+    Claims {
+        address: String,
+        /// Only returns claims released after this time
+        start_after: Option<Timestamp>,
+        limit: Option<u32>,
+    },
     /// TokenInfo shows the metadata of the token for UIs
     #[returns(TokenInfoResponse)]
     TokenInfo {},
+    /// AllBalances lists the balances of all holders, ordered by their canonical address.
+    /// Addresses that held tokens before may be listed with a zero balance.
+    #[returns(AllBalancesResponse)]
+    AllBalances {
+        /// Only returns balances after this address
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// AllClaims lists the claims of all addresses, ordered by their canonical address
+    #[returns(AllClaimsResponse)]
+    AllClaims {
+        /// Only returns claims of addresses after this one
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Allowance shows how many tokens of owner spender may move
+    #[returns(AllowanceResponse)]
+    Allowance { owner: String, spender: String },
     /// Investment shows info on total staking tokens under custody,
-    /// with which validator, as well as how many derivative tokens are lists.
+    /// with which validators, as well as how many derivative tokens are lists.
     /// It also shows with the exit tax.
     #[returns(InvestmentResponse)]
     Investment {},
@@ -72,7 +186,41 @@ pub struct BalanceResponse {
 
 #[cw_serde]
 pub struct ClaimsResponse {
-    pub claims: Uint128,
+    /// The claims of the requested page
+    pub claims: Vec<Claim>,
+    /// The total amount of all claims of the address
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct AllBalancesResponse {
+    pub balances: Vec<AddressBalance>,
+}
+
+#[cw_serde]
+pub struct AddressBalance {
+    pub address: String,
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct AllClaimsResponse {
+    pub claims: Vec<AddressClaims>,
+}
+
+#[cw_serde]
+pub struct AddressClaims {
+    pub address: String,
+    /// The claims of the address, ordered by release time
+    pub claims: Vec<Claim>,
+}
+
+/// Tokens that can be claimed once they are released after the unbonding period
+#[cw_serde]
+#[derive(Eq)]
+pub struct Claim {
+    pub amount: Uint128,
+    pub release_at: Timestamp,
 }
 
 /// TokenInfoResponse is info to display the derivative token in a UI
@@ -84,6 +232,72 @@ pub struct TokenInfoResponse {
     pub symbol: String,
     /// decimal places of the derivative token (for UI)
     pub decimals: u8,
+    /// the number of derivative tokens issued
+    pub total_supply: Uint128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct AllowanceResponse {
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+/// Expiration of an allowance, compatible with CW20
+#[cw_serde]
+#[derive(Copy)]
+pub enum Expiration {
+    /// expires when the block height reaches this value
+    AtHeight(u64),
+    /// expires when the block time reaches this value
+    AtTime(Timestamp),
+    /// never expires
+    Never {},
+}
+
+impl Default for Expiration {
+    fn default() -> Self {
+        Expiration::Never {}
+    }
+}
+
+impl Expiration {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        match self {
+            Expiration::AtHeight(height) => block.height >= *height,
+            Expiration::AtTime(time) => block.time >= *time,
+            Expiration::Never {} => false,
+        }
+    }
+}
+
+/// Cw20ReceiveMsg is sent to the receiving contract by Send and SendFrom
+#[cw_serde]
+pub struct Cw20ReceiveMsg {
+    /// the account that sent the tokens
+    pub sender: String,
+    pub amount: Uint128,
+    /// the message passed to Send or SendFrom
+    pub msg: Binary,
+}
+
+impl Cw20ReceiveMsg {
+    /// Creates the message calling the `Receive` handler of the given contract
+    pub fn into_cosmos_msg(self, contract_addr: impl Into<String>) -> StdResult<CosmosMsg> {
+        let msg = to_binary(&ReceiverExecuteMsg::Receive(self))?;
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+/// The execute message a contract must accept to receive tokens via Send and SendFrom
+#[cw_serde]
+pub enum ReceiverExecuteMsg {
+    Receive(Cw20ReceiveMsg),
 }
 
 #[cw_serde]
@@ -97,9 +311,11 @@ pub struct InvestmentResponse {
     pub owner: String,
     /// this is how much the owner takes as a cut when someone unbonds
     pub exit_tax: Decimal,
-    /// All tokens are bonded to this validator
-    pub validator: String,
+    /// Tokens are bonded to these validators, in proportion to their weights
+    pub validators: Vec<ValidatorWeight>,
     /// This is the minimum amount we will pull out to reinvest, as well as a minumum
     /// that can be unbonded (to avoid needless staking tx)
     pub min_withdrawal: Uint128,
+    /// The chain's unbonding period in seconds
+    pub unbonding_period: u64,
 }
@@ -6,15 +6,20 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use cosmwasm_std::{
     from_slice,
     storage_keys::{namespace_with_key, to_length_prefixed},
-    to_vec, Addr, CanonicalAddr, Decimal, StdError, StdResult, Storage, Uint128,
+    to_vec, Addr, Binary, CanonicalAddr, Decimal, Order, StdError, StdResult, Storage, Timestamp,
+    Uint128,
 };
 
+use crate::msg::{AllowanceResponse, Claim, ValidatorWeight};
+
 pub const KEY_INVESTMENT: &[u8] = b"invest";
 pub const KEY_TOKEN_INFO: &[u8] = b"token";
 pub const KEY_TOTAL_SUPPLY: &[u8] = b"total_supply";
+pub const KEY_CLAIMS_MIGRATION: &[u8] = b"claims_migration";
 
 pub const PREFIX_BALANCE: &[u8] = b"balance";
 pub const PREFIX_CLAIMS: &[u8] = b"claim";
+pub const PREFIX_ALLOWANCE: &[u8] = b"allowance";
 
 pub fn may_load_map(
     storage: &dyn Storage,
@@ -37,6 +42,92 @@ pub fn save_map(
     Ok(())
 }
 
+/// Loads the claims of the given address, ordered by release time.
+/// Claims of the v1 layout are converted while the claims migration is in progress.
+pub fn load_claims(storage: &dyn Storage, key: &CanonicalAddr) -> StdResult<Vec<Claim>> {
+    match storage.get(&namespace_with_key(&[PREFIX_CLAIMS], key)) {
+        Some(data) => parse_claims(storage, &data),
+        None => Ok(vec![]),
+    }
+}
+
+/// Parses stored claims, converting claims of the v1 layout while the claims
+/// migration is in progress
+pub fn parse_claims(storage: &dyn Storage, data: &[u8]) -> StdResult<Vec<Claim>> {
+    match from_slice(data) {
+        Ok(claims) => Ok(claims),
+        Err(err) => match may_load_item::<ClaimsMigration>(storage, KEY_CLAIMS_MIGRATION)? {
+            Some(migration) => Ok(vec![migration.convert(from_slice(data)?)]),
+            None => Err(err),
+        },
+    }
+}
+
+pub fn save_claims(
+    storage: &mut dyn Storage,
+    key: &CanonicalAddr,
+    claims: &[Claim],
+) -> StdResult<()> {
+    let key = namespace_with_key(&[PREFIX_CLAIMS], key);
+    if claims.is_empty() {
+        storage.remove(&key);
+    } else {
+        storage.set(&key, &to_vec(claims)?);
+    }
+    Ok(())
+}
+
+/// Loads the allowance of spender for the tokens of owner. Missing allowances are zero.
+pub fn load_allowance(
+    storage: &dyn Storage,
+    owner: &CanonicalAddr,
+    spender: &CanonicalAddr,
+) -> StdResult<AllowanceResponse> {
+    storage
+        .get(&namespace_with_key(&[PREFIX_ALLOWANCE, owner], spender))
+        .map(|v| from_slice(&v))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// Stores the allowance of spender for the tokens of owner. Zero allowances are removed.
+pub fn save_allowance(
+    storage: &mut dyn Storage,
+    owner: &CanonicalAddr,
+    spender: &CanonicalAddr,
+    allowance: &AllowanceResponse,
+) -> StdResult<()> {
+    let key = namespace_with_key(&[PREFIX_ALLOWANCE, owner], spender);
+    if allowance.allowance.is_zero() {
+        storage.remove(&key);
+    } else {
+        storage.set(&key, &to_vec(allowance)?);
+    }
+    Ok(())
+}
+
+/// Returns up to limit entries of the map with the given prefix, ordered by key and
+/// starting after the given key. The keys are returned without the prefix.
+pub fn range_map(
+    storage: &dyn Storage,
+    prefix: &[u8],
+    start_after: Option<&[u8]>,
+    limit: usize,
+) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let namespace = to_length_prefixed(prefix);
+    let start = match start_after {
+        // start right after the given key
+        Some(key) => [namespace.as_slice(), key, &[0]].concat(),
+        None => namespace.clone(),
+    };
+    let end = prefix_end(&namespace);
+    storage
+        .range_bounded(Some(&start), Some(&end), Order::Ascending, limit)
+        .into_iter()
+        .map(|(key, value)| (key[namespace.len()..].to_vec(), value))
+        .collect()
+}
+
 pub fn load_map(storage: &dyn Storage, prefix: &[u8], key: &CanonicalAddr) -> StdResult<Uint128> {
     may_load_map(storage, prefix, key)?
         .ok_or_else(|| StdError::not_found(format!("map value for {}", key)))
@@ -51,12 +142,21 @@ pub struct InvestmentInfo {
     pub bond_denom: String,
     /// this is how much the owner takes as a cut when someone unbonds
     pub exit_tax: Decimal,
-    /// All tokens are bonded to this validator
+    /// Tokens are bonded to these validators, in proportion to their weights.
+    /// This is never empty.
     /// addr_humanize/addr_canonicalize doesn't work for validator addrresses (e.g. cosmosvaloper1...)
-    pub validator: String,
+    pub validators: Vec<ValidatorWeight>,
     /// This is the minimum amount we will pull out to reinvest, as well as a minumum
     /// that can be unbonded (to avoid needless staking tx)
     pub min_withdrawal: Uint128,
+    /// The chain's unbonding period in seconds
+    pub unbonding_period: u64,
+}
+
+impl InvestmentInfo {
+    pub fn total_weight(&self) -> u64 {
+        self.validators.iter().map(|v| v.weight).sum()
+    }
 }
 
 /// Info to display the derivative token in a UI
@@ -75,12 +175,22 @@ pub struct TokenInfo {
 pub struct Supply {
     /// issued is how many derivative tokens this contract has issued
     pub issued: Uint128,
-    /// bonded is how many native tokens exist bonded to the validator
+    /// bonded is how many native tokens exist bonded to the validators
     pub bonded: Uint128,
     /// claims is how many tokens need to be reserved paying back those who unbonded
     pub claims: Uint128,
 }
 
+pub fn may_load_item<T: DeserializeOwned>(
+    storage: &dyn Storage,
+    key: &[u8],
+) -> StdResult<Option<T>> {
+    storage
+        .get(&to_length_prefixed(key))
+        .map(|v| from_slice(&v))
+        .transpose()
+}
+
 pub fn load_item<T: DeserializeOwned>(storage: &dyn Storage, key: &[u8]) -> StdResult<T> {
     storage
         .get(&to_length_prefixed(key))
@@ -104,3 +214,79 @@ where
     save_item(storage, key, &output)?;
     Ok(output)
 }
+
+/// Tracks the conversion of the claims from the v1 layout, where every address had a
+/// single amount without release time, to the queue of claims
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ClaimsMigration {
+    /// the time at which converted claims are released
+    pub release_at: Timestamp,
+    /// the key of the last converted claim, relative to the claims prefix
+    pub last_key: Option<Binary>,
+}
+
+impl ClaimsMigration {
+    pub fn convert(&self, amount: Uint128) -> Claim {
+        Claim {
+            amount,
+            release_at: self.release_at,
+        }
+    }
+}
+
+/// Converts up to limit claims of the v1 layout, continuing after the last converted one.
+/// Returns the number of claims checked and whether the migration is done.
+///
+/// Doing this in chunks keeps the gas usage of each call bounded, no matter how many
+/// addresses have claims.
+pub fn migrate_claims(storage: &mut dyn Storage, limit: usize) -> StdResult<(usize, bool)> {
+    let mut migration: ClaimsMigration = may_load_item(storage, KEY_CLAIMS_MIGRATION)?
+        .ok_or_else(|| StdError::generic_err("no claims migration in progress"))?;
+
+    let start_after = migration.last_key.as_ref().map(Binary::as_slice);
+    let records = range_map(storage, PREFIX_CLAIMS, start_after, limit);
+
+    for (key, value) in &records {
+        // claims that were saved since the migration started are in the new layout already
+        if let Ok(amount) = from_slice::<Uint128>(value) {
+            let claims = vec![migration.convert(amount)];
+            storage.set(
+                &namespace_with_key(&[PREFIX_CLAIMS], key),
+                &to_vec(&claims)?,
+            );
+        }
+    }
+
+    let done = records.len() < limit;
+    if done {
+        storage.remove(&to_length_prefixed(KEY_CLAIMS_MIGRATION));
+    } else if let Some((key, _)) = records.last() {
+        migration.last_key = Some(Binary::from(key.as_slice()));
+        save_item(storage, KEY_CLAIMS_MIGRATION, &migration)?;
+    }
+    Ok((records.len(), done))
+}
+
+/// Returns the first key after all keys starting with prefix
+fn prefix_end(prefix: &[u8]) -> Vec<u8> {
+    let mut end = prefix.to_vec();
+    // the length prefix ensures the last byte is part of the namespace name, which is no 0xff
+    *end.last_mut().unwrap() += 1;
+    end
+}
+
+/// The storage layout of the first version of this contract
+pub mod v1 {
+    use super::*;
+
+    /// Investment info with a single validator
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+    pub struct InvestmentInfo {
+        pub owner: Addr,
+        pub bond_denom: String,
+        pub exit_tax: Decimal,
+        /// All tokens are bonded to this validator
+        pub validator: String,
+        pub min_withdrawal: Uint128,
+    }
+}
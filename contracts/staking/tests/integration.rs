@@ -25,7 +25,7 @@ use cosmwasm_vm::Instance;
 
 use staking::msg::{
     BalanceResponse, ClaimsResponse, InstantiateMsg, InvestmentResponse, QueryMsg,
-    TokenInfoResponse,
+    TokenInfoResponse, ValidatorWeight,
 };
 
 // This line will test the output of cargo wasm
@@ -56,9 +56,13 @@ fn initialization_with_missing_validator() {
         name: "Cool Derivative".to_string(),
         symbol: "DRV".to_string(),
         decimals: 9,
-        validator: String::from("my-validator"),
+        validators: vec![ValidatorWeight {
+            address: String::from("my-validator"),
+            weight: 1,
+        }],
         exit_tax: Decimal::percent(2),
         min_withdrawal: Uint128::new(50),
+        unbonding_period: 21 * 24 * 60 * 60,
     };
     let info = mock_info(&creator, &[]);
 
@@ -86,7 +90,8 @@ fn proper_initialization() {
     );
     let (instance_options, memory_limit) = mock_instance_options();
     let mut deps = Instance::from_code(WASM, backend, instance_options, memory_limit).unwrap();
-    assert_eq!(deps.required_capabilities().len(), 1);
+    assert_eq!(deps.required_capabilities().len(), 2);
+    assert!(deps.required_capabilities().contains("iterator"));
     assert!(deps.required_capabilities().contains("staking"));
 
     let creator = String::from("creator");
@@ -94,9 +99,13 @@ fn proper_initialization() {
         name: "Cool Derivative".to_string(),
         symbol: "DRV".to_string(),
         decimals: 9,
-        validator: String::from("my-validator"),
+        validators: vec![ValidatorWeight {
+            address: String::from("my-validator"),
+            weight: 1,
+        }],
         exit_tax: Decimal::percent(2),
         min_withdrawal: Uint128::new(50),
+        unbonding_period: 21 * 24 * 60 * 60,
     };
     let info = mock_info(&creator, &[]);
 
@@ -129,17 +138,20 @@ fn proper_initialization() {
         mock_env(),
         QueryMsg::Claims {
             address: creator.clone(),
+            start_after: None,
+            limit: None,
         },
     )
     .unwrap();
     let claim: ClaimsResponse = from_binary(&res).unwrap();
-    assert_eq!(claim.claims, Uint128::new(0));
+    assert_eq!(claim.total, Uint128::new(0));
+    assert_eq!(claim.claims, vec![]);
 
     // investment info correct
     let res = query(&mut deps, mock_env(), QueryMsg::Investment {}).unwrap();
     let invest: InvestmentResponse = from_binary(&res).unwrap();
     assert_eq!(&invest.owner, &creator);
-    assert_eq!(&invest.validator, &msg.validator);
+    assert_eq!(&invest.validators, &msg.validators);
     assert_eq!(invest.exit_tax, msg.exit_tax);
     assert_eq!(invest.min_withdrawal, msg.min_withdrawal);
 
@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+/// Expands the inputs of the command line into the paths of the Wasm files to check.
+///
+/// Directories are searched recursively for `.wasm` files. Inputs containing one of the
+/// glob characters `*`, `?` or `[` are expanded as glob pattern, such that patterns
+/// also work in shells that don't expand them. All other inputs are used as they are.
+pub fn wasm_paths<'a>(inputs: impl IntoIterator<Item = &'a str>) -> anyhow::Result<Vec<String>> {
+    let mut paths = vec![];
+    for input in inputs {
+        if is_glob_pattern(input) {
+            let matches = glob::glob(input)
+                .with_context(|| format!("Invalid glob pattern {}", input))?
+                .collect::<Result<Vec<_>, _>>()?;
+            if matches.is_empty() {
+                bail!("No files match {}", input);
+            }
+            for path in matches {
+                push_path(&mut paths, path)?;
+            }
+        } else {
+            push_path(&mut paths, PathBuf::from(input))?;
+        }
+    }
+    Ok(paths)
+}
+
+fn is_glob_pattern(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+fn push_path(paths: &mut Vec<String>, path: PathBuf) -> anyhow::Result<()> {
+    if path.is_dir() {
+        let mut files = vec![];
+        find_wasm_files(&path, &mut files)
+            .with_context(|| format!("Could not read directory {}", path.display()))?;
+        files.sort();
+        paths.extend(files.iter().map(|file| file.display().to_string()));
+    } else {
+        paths.push(path.display().to_string());
+    }
+    Ok(())
+}
+
+fn find_wasm_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_wasm_files(&path, files)?;
+        } else if matches!(path.extension(), Some(extension) if extension == "wasm") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
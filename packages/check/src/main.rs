@@ -1,16 +1,28 @@
-use std::collections::HashSet;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 use std::process::exit;
 
+use anyhow::Context;
 use clap::{Arg, ArgAction, Command};
 use colored::Colorize;
+use rayon::prelude::*;
+use serde::Serialize;
 
-use cosmwasm_vm::capabilities_from_csv;
-use cosmwasm_vm::internals::{check_wasm, compile};
+use cosmwasm_vm::{
+    capabilities_from_csv, parse_contract_metadata, validate_wasm, wasm_stats, ContractMetadata,
+    ValidationReport, WasmLimits, WasmStats,
+};
 
-const DEFAULT_AVAILABLE_CAPABILITIES: &str = "iterator,staking,stargate,cosmwasm_1_1,cosmwasm_1_2";
+mod inputs;
+mod profile;
+mod report;
+
+use profile::{Profile, Target, Version};
+use report::{print_stats, JsonStats};
+
+const DEFAULT_AVAILABLE_CAPABILITIES: &str =
+    "iterator,staking,stargate,feegrant,authz,cosmwasm_1_1,cosmwasm_1_2,cosmwasm_1_3,cosmwasm_1_4,cosmwasm_1_5";
 
 pub fn main() {
     let matches = Command::new("Contract checking")
@@ -27,9 +39,43 @@ pub fn main() {
                 .num_args(1)
                 .action(ArgAction::Set),
         )
+        .arg(
+            Arg::new("PROFILE")
+                .long("profile")
+                .value_name("FILE")
+                .help("Sets the target chain configuration from a TOML profile file. `--available-capabilities` takes precedence over the capabilities of the profile.")
+                .num_args(1)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("OUTPUT")
+                .long("output")
+                .value_name("FORMAT")
+                .help("Sets the output format. `json` prints a single JSON document for use in scripts.")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .num_args(1)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("REPORT")
+                .long("report")
+                .help("Prints the section sizes, import, function and data segment counts and a gas estimate of every contract")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("JOBS")
+                .long("jobs")
+                .short('j')
+                .value_name("N")
+                .help("Sets the number of contracts checked in parallel. Defaults to the number of CPUs.")
+                .value_parser(clap::value_parser!(usize))
+                .num_args(1)
+                .action(ArgAction::Set),
+        )
         .arg(
             Arg::new("WASM")
-                .help("Wasm file to read and compile")
+                .help("Wasm files, directories or glob patterns to check. Directories are searched recursively for .wasm files.")
                 .required(true)
                 .index(1)
                 .num_args(0..)
@@ -37,68 +83,370 @@ pub fn main() {
         )
         .get_matches();
 
+    let with_stats = matches.get_flag("REPORT");
+    let json_output = matches!(
+        matches.get_one::<String>("OUTPUT").map(|s| s.as_str()),
+        Some("json")
+    );
+
+    // Profile
+    let profile_path = matches.get_one::<String>("PROFILE");
+    let profile = match profile_path {
+        Some(path) => Profile::from_file(path).unwrap_or_else(|e| {
+            eprintln!("{:#}", e);
+            exit(2);
+        }),
+        None => Profile::default(),
+    };
+    let mut target = Target::from_profile(
+        profile,
+        capabilities_from_csv(DEFAULT_AVAILABLE_CAPABILITIES),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("{:#}", e);
+        exit(2);
+    });
+
     // Available capabilities
-    let available_capabilities_csv = matches
-        .get_one::<String>("CAPABILITIES")
-        .map(|s| s.as_str())
-        .unwrap_or(DEFAULT_AVAILABLE_CAPABILITIES);
-    let available_capabilities = capabilities_from_csv(available_capabilities_csv);
-    println!("Available capabilities: {:?}", available_capabilities);
-    println!();
+    if let Some(available_capabilities_csv) = matches.get_one::<String>("CAPABILITIES") {
+        target.available_capabilities = capabilities_from_csv(available_capabilities_csv);
+    }
 
-    // File
-    let paths = matches
+    // Files
+    let inputs = matches
         .get_many::<String>("WASM")
         .expect("Error parsing file names");
+    let paths = inputs::wasm_paths(inputs.map(|s| s.as_str())).unwrap_or_else(|e| {
+        eprintln!("{:#}", e);
+        exit(2);
+    });
 
-    let (passes, failures): (Vec<_>, _) = paths
-        .map(|p| {
-            let result = check_contract(p, &available_capabilities);
-            match &result {
-                Ok(_) => println!("{}: {}", p, "pass".green()),
-                Err(e) => {
-                    println!("{}: {}", p, "failure".red());
-                    println!("{}", e);
-                }
-            };
-            result
-        })
-        .partition(|result| result.is_ok());
-    println!();
+    // Check all contracts in parallel. The results keep the order of the paths.
+    let jobs = matches.get_one::<usize>("JOBS").copied().unwrap_or(0);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .expect("Error creating thread pool");
+    let checks: Vec<_> = pool.install(|| {
+        paths
+            .par_iter()
+            .map(|p| check_contract(p, &target, with_stats))
+            .collect()
+    });
 
-    if failures.is_empty() {
+    if json_output {
+        let report = JsonReport::new(&target, checks);
         println!(
-            "All contracts ({}) {} checks!",
-            passes.len(),
-            "passed".green()
+            "{}",
+            serde_json::to_string_pretty(&report).expect("Error serializing report")
         );
+        if report.failures > 0 {
+            exit(1);
+        }
+        return;
+    }
+
+    if let Some(profile_path) = profile_path {
+        println!("Profile: {}", profile_path);
+    }
+    println!(
+        "Available capabilities: {:?}",
+        target.available_capabilities
+    );
+    println!();
+
+    for check in &checks {
+        match &check.result {
+            Ok((_, metadata)) => {
+                println!("{}: {}", check.path, "pass".green());
+                if let Some(metadata) = metadata {
+                    print_metadata(metadata);
+                }
+            }
+            Err(e) => {
+                println!("{}: {}", check.path, "failure".red());
+                println!("{}", e);
+            }
+        };
+        if let Some(stats) = &check.stats {
+            print_stats(stats);
+        }
+    }
+    println!();
+
+    if checks.len() > 1 {
+        print_summary(&checks);
+        println!();
+    }
+
+    let passes = checks.iter().filter(|check| check.result.is_ok()).count();
+    let failures = checks.len() - passes;
+    if failures == 0 {
+        println!("All contracts ({}) {} checks!", passes, "passed".green());
     } else {
         println!(
             "{}: {}, {}: {}",
             "Passes".green(),
-            passes.len(),
+            passes,
             "failures".red(),
-            failures.len()
+            failures
         );
         exit(1);
     }
 }
 
-fn check_contract(
-    path: impl AsRef<Path>,
-    available_capabilities: &HashSet<String>,
-) -> anyhow::Result<()> {
-    let mut file = File::open(path)?;
+/// The outcome of checking a single contract
+struct ContractCheck {
+    path: String,
+    result: anyhow::Result<(ValidationReport, Option<ContractMetadata>)>,
+    /// True if the contract would pass all checks without the limits
+    limit_violation: bool,
+    /// Only set if requested and the Wasm can be deserialized
+    stats: Option<WasmStats>,
+}
+
+fn check_contract(path: &str, target: &Target, with_stats: bool) -> ContractCheck {
+    let wasm = match read_wasm(path) {
+        Ok(wasm) => wasm,
+        Err(e) => {
+            return ContractCheck {
+                path: path.to_string(),
+                result: Err(e),
+                limit_violation: false,
+                stats: None,
+            }
+        }
+    };
+
+    let result = check_wasm(&wasm, target);
+    let limit_violation = result.is_err() && check_wasm(&wasm, &target.without_limits()).is_ok();
+    ContractCheck {
+        path: path.to_string(),
+        result,
+        limit_violation,
+        stats: if with_stats {
+            wasm_stats(&wasm).ok()
+        } else {
+            None
+        },
+    }
+}
+
+fn check_wasm(
+    wasm: &[u8],
+    target: &Target,
+) -> anyhow::Result<(ValidationReport, Option<ContractMetadata>)> {
+    if let Some(max_wasm_size) = target.max_wasm_size {
+        if wasm.len() > max_wasm_size {
+            anyhow::bail!(
+                "Wasm size of {} bytes exceeds the maximum of {} bytes",
+                wasm.len(),
+                max_wasm_size
+            );
+        }
+    }
+
+    // Check wasm and compile module
+    let report = validate_wasm(wasm, &target.limits, &target.available_capabilities)?;
+
+    // The cosmwasm_1_x capabilities tell the minimum VM version even without metadata
+    if let Some(vm_version) = &target.vm_version {
+        let mut unsupported: Vec<_> = report
+            .required_capabilities
+            .iter()
+            .filter(|capability| {
+                Version::from_capability(capability)
+                    .is_some_and(|version| !version.runs_on(vm_version))
+            })
+            .cloned()
+            .collect();
+        if !unsupported.is_empty() {
+            unsupported.sort();
+            anyhow::bail!(
+                "Contract requires capabilities {} which are not supported by cosmwasm-vm {}",
+                unsupported.join(", "),
+                vm_version
+            );
+        }
+    }
+
+    // Read metadata
+    let metadata = parse_contract_metadata(wasm)?;
+    if let (Some(vm_version), Some(metadata)) = (&target.vm_version, &metadata) {
+        let std_version = Version::parse(&metadata.cosmwasm_std_version)
+            .context("Invalid cosmwasm-std version in contract metadata")?;
+        if !std_version.runs_on(vm_version) {
+            anyhow::bail!(
+                "Contract was built with cosmwasm-std {} which is not supported by cosmwasm-vm {}",
+                metadata.cosmwasm_std_version,
+                vm_version
+            );
+        }
+    }
+    Ok((report, metadata))
+}
 
-    // Read wasm
+fn read_wasm(path: impl AsRef<Path>) -> anyhow::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
     let mut wasm = Vec::<u8>::new();
     file.read_to_end(&mut wasm)?;
+    Ok(wasm)
+}
+
+/// Prints one row per contract with the result and the required capabilities
+fn print_summary(checks: &[ContractCheck]) {
+    const CONTRACT: &str = "Contract";
+    const RESULT: &str = "Result";
+    let width = checks
+        .iter()
+        .map(|check| check.path.len())
+        .chain([CONTRACT.len()])
+        .max()
+        .unwrap_or_default();
 
-    // Check wasm
-    check_wasm(&wasm, available_capabilities)?;
+    println!("{:<width$}  {:<7}  Required capabilities", CONTRACT, RESULT);
+    for check in checks {
+        let (result, capabilities) = match &check.result {
+            Ok((report, _)) => (
+                format!("{:<7}", "pass").green(),
+                sorted(report.required_capabilities.iter().cloned()).join(", "),
+            ),
+            Err(_) if check.limit_violation => (
+                format!("{:<7}", "failure").red(),
+                "limit violation".to_string(),
+            ),
+            Err(_) => (format!("{:<7}", "failure").red(), String::new()),
+        };
+        let row = format!("{:<width$}  {}  {}", check.path, result, capabilities);
+        println!("{}", row.trim_end());
+    }
+}
 
-    // Compile module
-    compile(&wasm, &[])?;
+fn print_metadata(metadata: &ContractMetadata) {
+    println!("  name: {}", metadata.name);
+    println!("  version: {}", metadata.version);
+    println!("  cosmwasm-std version: {}", metadata.cosmwasm_std_version);
+    if !metadata.authors.is_empty() {
+        println!("  authors: {}", metadata.authors.join(", "));
+    }
+}
+
+/// The document printed for `--output json`. Fields are only added, never changed or
+/// removed, such that scripts can rely on them.
+#[derive(Serialize)]
+struct JsonReport {
+    available_capabilities: Vec<String>,
+    limits: JsonLimits,
+    max_wasm_size: Option<usize>,
+    vm_version: Option<String>,
+    contracts: Vec<JsonContract>,
+    passes: usize,
+    failures: usize,
+}
+
+impl JsonReport {
+    fn new(target: &Target, checks: Vec<ContractCheck>) -> Self {
+        let contracts: Vec<_> = checks.into_iter().map(JsonContract::from).collect();
+        let passes = contracts.iter().filter(|contract| contract.pass).count();
+        JsonReport {
+            available_capabilities: sorted(target.available_capabilities.iter().cloned()),
+            limits: target.limits.clone().into(),
+            max_wasm_size: target.max_wasm_size,
+            vm_version: target.vm_version.map(|version| version.to_string()),
+            failures: contracts.len() - passes,
+            passes,
+            contracts,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLimits {
+    initial_memory_limit: u32,
+    table_size_limit: u32,
+    max_imports: usize,
+    max_functions: usize,
+    max_function_params: usize,
+}
+
+impl From<WasmLimits> for JsonLimits {
+    fn from(limits: WasmLimits) -> Self {
+        JsonLimits {
+            initial_memory_limit: limits.initial_memory_limit,
+            table_size_limit: limits.table_size_limit,
+            max_imports: limits.max_imports,
+            max_functions: limits.max_functions,
+            max_function_params: limits.max_function_params,
+        }
+    }
+}
+
+/// The result for one contract. All fields but `path`, `pass`, `limit_violation`
+/// and `error` are null if the contract failed the checks.
+#[derive(Serialize)]
+struct JsonContract {
+    path: String,
+    pass: bool,
+    checksum: Option<String>,
+    required_capabilities: Option<Vec<String>>,
+    exports: Option<Vec<String>>,
+    has_ibc_entry_points: Option<bool>,
+    metadata: Option<JsonMetadata>,
+    limit_violation: bool,
+    error: Option<String>,
+    /// Only set with `--report`
+    stats: Option<JsonStats>,
+}
+
+impl From<ContractCheck> for JsonContract {
+    fn from(check: ContractCheck) -> Self {
+        let mut contract = JsonContract {
+            path: check.path,
+            pass: check.result.is_ok(),
+            checksum: None,
+            required_capabilities: None,
+            exports: None,
+            has_ibc_entry_points: None,
+            metadata: None,
+            limit_violation: check.limit_violation,
+            error: None,
+            stats: check.stats.map(JsonStats::from),
+        };
+        match check.result {
+            Ok((report, metadata)) => {
+                contract.checksum = Some(report.checksum.to_hex());
+                contract.required_capabilities = Some(sorted(report.required_capabilities));
+                contract.exports = Some(sorted(report.exports));
+                contract.has_ibc_entry_points = Some(report.has_ibc_entry_points);
+                contract.metadata = metadata.map(JsonMetadata::from);
+            }
+            Err(e) => contract.error = Some(e.to_string()),
+        }
+        contract
+    }
+}
+
+#[derive(Serialize)]
+struct JsonMetadata {
+    name: String,
+    version: String,
+    cosmwasm_std_version: String,
+    authors: Vec<String>,
+}
+
+impl From<ContractMetadata> for JsonMetadata {
+    fn from(metadata: ContractMetadata) -> Self {
+        JsonMetadata {
+            name: metadata.name,
+            version: metadata.version,
+            cosmwasm_std_version: metadata.cosmwasm_std_version,
+            authors: metadata.authors,
+        }
+    }
+}
 
-    Ok(())
+fn sorted(items: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut items: Vec<_> = items.into_iter().collect();
+    items.sort();
+    items
 }
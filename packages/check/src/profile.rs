@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+
+use cosmwasm_vm::WasmLimits;
+
+/// The configuration of a target chain as declared in a profile file, e.g.
+///
+/// ```toml
+/// available_capabilities = ["iterator", "staking", "stargate", "cosmwasm_1_1"]
+/// max_wasm_size = 819200
+/// vm_version = "1.2"
+///
+/// [limits]
+/// initial_memory_limit = 512
+/// ```
+///
+/// All fields are optional. Missing fields fall back to the defaults of cosmwasm-check.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub available_capabilities: Option<Vec<String>>,
+    /// The maximum size of the Wasm blob in bytes
+    pub max_wasm_size: Option<usize>,
+    /// The cosmwasm-vm version the chain runs, as `major.minor` or `major.minor.patch`
+    pub vm_version: Option<String>,
+    #[serde(default)]
+    pub limits: ProfileLimits,
+}
+
+/// Overrides of the static validation limits. See [`WasmLimits`] for the meaning of the fields.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileLimits {
+    pub initial_memory_limit: Option<u32>,
+    pub table_size_limit: Option<u32>,
+    pub max_imports: Option<usize>,
+    pub max_functions: Option<usize>,
+    pub max_function_params: Option<usize>,
+}
+
+impl Profile {
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read profile {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Could not parse profile {}", path.display()))
+    }
+}
+
+/// The chain configuration contracts are checked against
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub available_capabilities: HashSet<String>,
+    pub limits: WasmLimits,
+    pub max_wasm_size: Option<usize>,
+    pub vm_version: Option<Version>,
+}
+
+impl Target {
+    /// Creates the target from a profile. `available_capabilities` is used if the profile
+    /// does not declare any.
+    pub fn from_profile(
+        profile: Profile,
+        available_capabilities: HashSet<String>,
+    ) -> anyhow::Result<Self> {
+        let defaults = WasmLimits::default();
+        let limits = profile.limits;
+        let vm_version = match profile.vm_version {
+            Some(version) => Some(
+                Version::parse(&version)
+                    .with_context(|| format!("Invalid vm_version in profile: {}", version))?,
+            ),
+            None => None,
+        };
+        Ok(Target {
+            available_capabilities: profile
+                .available_capabilities
+                .map(|capabilities| capabilities.into_iter().collect())
+                .unwrap_or(available_capabilities),
+            limits: WasmLimits {
+                initial_memory_limit: limits
+                    .initial_memory_limit
+                    .unwrap_or(defaults.initial_memory_limit),
+                table_size_limit: limits.table_size_limit.unwrap_or(defaults.table_size_limit),
+                max_imports: limits.max_imports.unwrap_or(defaults.max_imports),
+                max_functions: limits.max_functions.unwrap_or(defaults.max_functions),
+                max_function_params: limits
+                    .max_function_params
+                    .unwrap_or(defaults.max_function_params),
+            },
+            max_wasm_size: profile.max_wasm_size,
+            vm_version,
+        })
+    }
+
+    /// The same target without any size and static validation limits. This is used to
+    /// find out if a contract only fails because of the limits.
+    pub fn without_limits(&self) -> Self {
+        Target {
+            limits: WasmLimits {
+                initial_memory_limit: u32::MAX,
+                table_size_limit: u32::MAX,
+                max_imports: usize::MAX,
+                max_functions: usize::MAX,
+                max_function_params: usize::MAX,
+            },
+            max_wasm_size: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// A `major.minor` version. The patch version is ignored since it does not
+/// change the contract interface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+}
+
+impl Version {
+    pub fn parse(version: &str) -> anyhow::Result<Self> {
+        let mut parts = version.trim().split('.');
+        let major = parts.next().unwrap_or_default().parse();
+        let minor = parts.next().unwrap_or_default().parse();
+        match (major, minor, parts.next(), parts.next()) {
+            (Ok(major), Ok(minor), _, None) => Ok(Version { major, minor }),
+            _ => bail!("Expected a version of the form major.minor[.patch]"),
+        }
+    }
+
+    /// Returns the version of a `cosmwasm_<major>_<minor>` capability, which is supported by
+    /// cosmwasm-vm of this version and newer. Returns `None` for all other capabilities.
+    pub fn from_capability(capability: &str) -> Option<Self> {
+        let mut parts = capability.strip_prefix("cosmwasm_")?.split('_');
+        match (parts.next()?.parse(), parts.next()?.parse(), parts.next()) {
+            (Ok(major), Ok(minor), None) => Some(Version { major, minor }),
+            _ => None,
+        }
+    }
+
+    /// Returns true if a contract built with cosmwasm-std of this version can run on
+    /// cosmwasm-vm of the given version
+    pub fn runs_on(&self, vm_version: &Version) -> bool {
+        self.major == vm_version.major && self.minor <= vm_version.minor
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Serialize;
+
+use cosmwasm_vm::WasmStats;
+
+/// The gas wasmd charges per byte of Wasm when storing code (`DefaultCompileCost`)
+const STORE_GAS_PER_BYTE: u64 = 3;
+/// The gas wasmd charges for creating an instance of a contract which is not
+/// pinned (`DefaultInstanceCost`). This is a constant base cost, which does not
+/// depend on the contract, so it is reported as such.
+const INSTANCE_BASE_GAS: u64 = 60_000;
+
+/// An estimate of the gas charged by a chain with the default configuration of wasmd
+/// for storing the contract. Creating an instance costs [`INSTANCE_BASE_GAS`] on top of
+/// the gas used by every entry point call.
+fn estimated_store_gas(stats: &WasmStats) -> u64 {
+    stats.size as u64 * STORE_GAS_PER_BYTE
+}
+
+pub fn print_stats(stats: &WasmStats) {
+    println!("  size: {} bytes", stats.size);
+    println!("  sections:");
+    for section in &stats.sections {
+        println!("    {}: {} bytes", section.name, section.size);
+    }
+    println!("  imports: {}", stats.imports);
+    println!("  functions: {}", stats.functions);
+    println!("  exports: {}", stats.exports);
+    println!(
+        "  data: {} segments, {} bytes",
+        stats.data_segments, stats.data_size
+    );
+    println!("  initial memory: {} pages", stats.initial_memory_pages);
+    println!(
+        "  estimated gas: {} to store, {} per instance (constant base cost)",
+        estimated_store_gas(stats),
+        INSTANCE_BASE_GAS
+    );
+}
+
+#[derive(Serialize)]
+pub struct JsonStats {
+    size: usize,
+    sections: Vec<JsonSection>,
+    imports: usize,
+    functions: usize,
+    exports: usize,
+    data_segments: usize,
+    data_size: usize,
+    initial_memory_pages: u32,
+    estimated_store_gas: u64,
+    instance_base_gas: u64,
+}
+
+#[derive(Serialize)]
+struct JsonSection {
+    name: String,
+    size: usize,
+}
+
+impl From<WasmStats> for JsonStats {
+    fn from(stats: WasmStats) -> Self {
+        JsonStats {
+            estimated_store_gas: estimated_store_gas(&stats),
+            instance_base_gas: INSTANCE_BASE_GAS,
+            size: stats.size,
+            sections: stats
+                .sections
+                .into_iter()
+                .map(|section| JsonSection {
+                    name: section.name,
+                    size: section.size,
+                })
+                .collect(),
+            imports: stats.imports,
+            functions: stats.functions,
+            exports: stats.exports,
+            data_segments: stats.data_segments,
+            data_size: stats.data_size,
+            initial_memory_pages: stats.initial_memory_pages,
+        }
+    }
+}
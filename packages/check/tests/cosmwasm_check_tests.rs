@@ -1,5 +1,6 @@
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
+use std::path::PathBuf;
 use std::process::Command;
 
 #[test]
@@ -72,3 +73,432 @@ fn custom_capabilities_check() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Appends a metadata section to hackatom and writes it to a temporary file
+fn write_hackatom_with_metadata(
+    file_name: &str,
+    std_version: &str,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let payload = format!(
+        "name=hackatom\nversion=0.0.0\ncosmwasm_std_version={}\nauthors=Alice:Bob\n",
+        std_version
+    );
+    let name = "cosmwasm_metadata";
+    let mut section = vec![name.len() as u8];
+    section.extend_from_slice(name.as_bytes());
+    section.extend_from_slice(payload.as_bytes());
+    let mut wasm = std::fs::read("../vm/testdata/hackatom.wasm")?;
+    wasm.push(0); // custom section id
+    wasm.push(section.len() as u8);
+    wasm.extend(section);
+
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(&path, wasm)?;
+    Ok(path)
+}
+
+fn write_profile(file_name: &str, content: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[test]
+fn contract_metadata_check() -> Result<(), Box<dyn std::error::Error>> {
+    let path = write_hackatom_with_metadata("cosmwasm_check_contract_metadata.wasm", "1.2.5")?;
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg(&path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("pass"))
+        .stdout(predicate::str::contains("name: hackatom"))
+        .stdout(predicate::str::contains("version: 0.0.0"))
+        .stdout(predicate::str::contains("cosmwasm-std version: 1.2.5"))
+        .stdout(predicate::str::contains("authors: Alice, Bob"));
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn json_output_check() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+
+    cmd.arg("--output")
+        .arg("json")
+        .arg("../vm/testdata/hackatom.wasm")
+        .arg("../vm/testdata/corrupted.wasm");
+    let output = cmd.output()?;
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["passes"], 1);
+    assert_eq!(report["failures"], 1);
+    assert_eq!(report["limits"]["initial_memory_limit"], 512);
+    let available = report["available_capabilities"].as_array().unwrap();
+    assert!(available.contains(&"iterator".into()));
+
+    let hackatom = &report["contracts"][0];
+    assert_eq!(hackatom["path"], "../vm/testdata/hackatom.wasm");
+    assert_eq!(hackatom["pass"], true);
+    assert_eq!(hackatom["has_ibc_entry_points"], false);
+    assert_eq!(hackatom["limit_violation"], false);
+    assert!(hackatom["error"].is_null());
+    let exports = hackatom["exports"].as_array().unwrap();
+    assert!(exports.contains(&"instantiate".into()));
+    assert!(exports.contains(&"interface_version_8".into()));
+    assert!(hackatom["required_capabilities"].is_array());
+
+    let corrupted = &report["contracts"][1];
+    assert_eq!(corrupted["pass"], false);
+    assert_eq!(corrupted["limit_violation"], false);
+    assert!(corrupted["exports"].is_null());
+    assert!(corrupted["error"]
+        .as_str()
+        .unwrap()
+        .contains("Deserialization error"));
+
+    Ok(())
+}
+
+#[test]
+fn json_output_limit_violation_check() -> Result<(), Box<dyn std::error::Error>> {
+    // A valid contract which requests more initial memory than allowed
+    let wasm = wat::parse_str(
+        r#"(module
+            (memory 600)
+            (export "memory" (memory 0))
+            (type (func))
+            (func (type 0) nop)
+            (export "interface_version_8" (func 0))
+            (export "allocate" (func 0))
+            (export "deallocate" (func 0))
+            (export "instantiate" (func 0))
+        )"#,
+    )?;
+    let path = std::env::temp_dir().join("cosmwasm_check_limit_violation.wasm");
+    std::fs::write(&path, wasm)?;
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--output").arg("json").arg(&path);
+    let output = cmd.output()?;
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let contract = &report["contracts"][0];
+    assert_eq!(contract["pass"], false);
+    assert_eq!(contract["limit_violation"], true);
+    assert!(contract["error"].as_str().unwrap().contains("512 pages"));
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn profile_check() -> Result<(), Box<dyn std::error::Error>> {
+    let profile = write_profile(
+        "cosmwasm_check_profile.toml",
+        r#"
+            available_capabilities = ["iterator", "osmosis"]
+            max_wasm_size = 1000
+
+            [limits]
+            initial_memory_limit = 100
+        "#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--profile")
+        .arg(&profile)
+        .arg("--output")
+        .arg("json")
+        .arg("../vm/testdata/hackatom.wasm");
+    let output = cmd.output()?;
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        report["available_capabilities"],
+        serde_json::json!(["iterator", "osmosis"])
+    );
+    assert_eq!(report["max_wasm_size"], 1000);
+    assert_eq!(report["limits"]["initial_memory_limit"], 100);
+    assert_eq!(report["limits"]["max_imports"], 100);
+    let contract = &report["contracts"][0];
+    assert_eq!(contract["pass"], false);
+    assert_eq!(contract["limit_violation"], true);
+    assert!(contract["error"]
+        .as_str()
+        .unwrap()
+        .contains("exceeds the maximum of 1000 bytes"));
+
+    std::fs::remove_file(profile)?;
+    Ok(())
+}
+
+#[test]
+fn profile_capabilities_are_overridden() -> Result<(), Box<dyn std::error::Error>> {
+    let profile = write_profile(
+        "cosmwasm_check_profile_capabilities.toml",
+        r#"available_capabilities = ["osmosis"]"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--profile")
+        .arg(&profile)
+        .arg("../vm/testdata/hackatom.wasm");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Profile:"))
+        .stdout(predicate::str::contains("osmosis"));
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--profile")
+        .arg(&profile)
+        .arg("--available-capabilities")
+        .arg("friendship")
+        .arg("../vm/testdata/hackatom.wasm");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("friendship"))
+        .stdout(predicate::str::contains("osmosis").not());
+
+    std::fs::remove_file(profile)?;
+    Ok(())
+}
+
+#[test]
+fn profile_vm_version_check() -> Result<(), Box<dyn std::error::Error>> {
+    let path = write_hackatom_with_metadata("cosmwasm_check_vm_version.wasm", "1.2.5")?;
+    let old_chain = write_profile("cosmwasm_check_vm_1_1.toml", r#"vm_version = "1.1.3""#)?;
+    let new_chain = write_profile("cosmwasm_check_vm_1_3.toml", r#"vm_version = "1.3""#)?;
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--profile").arg(&old_chain).arg(&path);
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "built with cosmwasm-std 1.2.5 which is not supported by cosmwasm-vm 1.1",
+    ));
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--profile").arg(&new_chain).arg(&path);
+    cmd.assert().success();
+
+    std::fs::remove_file(path)?;
+    std::fs::remove_file(old_chain)?;
+    std::fs::remove_file(new_chain)?;
+    Ok(())
+}
+
+#[test]
+fn profile_vm_version_checks_capabilities() -> Result<(), Box<dyn std::error::Error>> {
+    // A contract without metadata which requires CosmWasm 1.4
+    let wasm = wat::parse_str(
+        r#"(module
+            (memory 1)
+            (export "memory" (memory 0))
+            (type (func))
+            (func (type 0) nop)
+            (export "interface_version_8" (func 0))
+            (export "allocate" (func 0))
+            (export "deallocate" (func 0))
+            (export "instantiate" (func 0))
+            (export "requires_cosmwasm_1_1" (func 0))
+            (export "requires_cosmwasm_1_4" (func 0))
+        )"#,
+    )?;
+    let path = std::env::temp_dir().join("cosmwasm_check_vm_version_capabilities.wasm");
+    std::fs::write(&path, wasm)?;
+    let old_chain = write_profile(
+        "cosmwasm_check_vm_1_3_capabilities.toml",
+        r#"vm_version = "1.3""#,
+    )?;
+    let new_chain = write_profile(
+        "cosmwasm_check_vm_1_4_capabilities.toml",
+        r#"vm_version = "1.4.1""#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--profile").arg(&old_chain).arg(&path);
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "requires capabilities cosmwasm_1_4 which are not supported by cosmwasm-vm 1.3",
+    ));
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--profile").arg(&new_chain).arg(&path);
+    cmd.assert().success();
+
+    std::fs::remove_file(path)?;
+    std::fs::remove_file(old_chain)?;
+    std::fs::remove_file(new_chain)?;
+    Ok(())
+}
+
+#[test]
+fn invalid_profile_check() -> Result<(), Box<dyn std::error::Error>> {
+    let profile = write_profile("cosmwasm_check_invalid_profile.toml", "max_memory = 1000\n")?;
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--profile")
+        .arg(&profile)
+        .arg("../vm/testdata/hackatom.wasm");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("Could not parse profile"))
+        .stderr(predicate::str::contains("max_memory"));
+
+    std::fs::remove_file(profile)?;
+    Ok(())
+}
+
+#[test]
+fn directory_check() -> Result<(), Box<dyn std::error::Error>> {
+    // Contracts in nested directories next to a non-Wasm file
+    let dir = std::env::temp_dir().join("cosmwasm_check_directory");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("a"))?;
+    std::fs::create_dir_all(dir.join("b").join("c"))?;
+    std::fs::copy("../vm/testdata/hackatom.wasm", dir.join("a/hackatom.wasm"))?;
+    std::fs::copy(
+        "../vm/testdata/ibc_reflect.wasm",
+        dir.join("b/c/ibc_reflect.wasm"),
+    )?;
+    std::fs::write(dir.join("README.md"), "not a contract")?;
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--jobs").arg("2").arg(&dir);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Required capabilities"))
+        .stdout(predicate::str::contains("hackatom.wasm"))
+        .stdout(predicate::str::contains("ibc_reflect.wasm"))
+        .stdout(predicate::str::contains("README.md").not())
+        .stdout(predicate::str::contains("All contracts (2) passed checks!"));
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[test]
+fn glob_check() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+
+    // The pattern is not expanded by a shell here
+    cmd.arg("--output")
+        .arg("json")
+        .arg("../vm/testdata/hackatom*.wasm");
+    let output = cmd.output()?;
+    // Contracts for old VM versions fail
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let contracts = report["contracts"].as_array().unwrap();
+    assert!(contracts.len() > 1);
+    assert_eq!(contracts[0]["path"], "../vm/testdata/hackatom.wasm");
+    assert_eq!(contracts[0]["pass"], true);
+
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("../vm/testdata/nothing*.wasm");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("No files match"));
+
+    Ok(())
+}
+
+#[test]
+fn parallel_check_keeps_order() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+
+    cmd.arg("--jobs")
+        .arg("4")
+        .arg("--output")
+        .arg("json")
+        .arg("../vm/testdata/corrupted.wasm")
+        .arg("../vm/testdata/hackatom.wasm")
+        .arg("../vm/testdata/floaty.wasm")
+        .arg("../vm/testdata/cyberpunk.wasm");
+    let output = cmd.output()?;
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let results: Vec<_> = report["contracts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|contract| {
+            (
+                contract["path"].as_str().unwrap(),
+                contract["pass"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        results,
+        [
+            ("../vm/testdata/corrupted.wasm", false),
+            ("../vm/testdata/hackatom.wasm", true),
+            ("../vm/testdata/floaty.wasm", false),
+            ("../vm/testdata/cyberpunk.wasm", true),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn report_check() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+
+    cmd.arg("--report").arg("../vm/testdata/hackatom.wasm");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("size: 181627 bytes"))
+        .stdout(predicate::str::contains("code: "))
+        .stdout(predicate::str::contains("imports: "))
+        .stdout(predicate::str::contains("functions: "))
+        .stdout(predicate::str::contains("initial memory: 17 pages"))
+        .stdout(predicate::str::contains(
+            "estimated gas: 544881 to store, 60000 per instance (constant base cost)",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn report_json_check() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+
+    cmd.arg("--report")
+        .arg("--output")
+        .arg("json")
+        .arg("../vm/testdata/hackatom.wasm")
+        .arg("../vm/testdata/floaty.wasm")
+        .arg("../vm/testdata/corrupted.wasm");
+    let output = cmd.output()?;
+    assert_eq!(output.status.code(), Some(1));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let stats = &report["contracts"][0]["stats"];
+    assert_eq!(stats["size"], 181627);
+    assert_eq!(stats["estimated_store_gas"], 3 * 181627);
+    assert_eq!(stats["instance_base_gas"], 60000);
+    assert!(stats["functions"].as_u64().unwrap() > 0);
+    assert!(stats["data_size"].as_u64().unwrap() > 0);
+    let sections = stats["sections"].as_array().unwrap();
+    assert!(sections.iter().any(|section| section["name"] == "code"));
+
+    // Stats are available for contracts failing the checks if the Wasm can be read
+    assert!(report["contracts"][1]["stats"]["size"].is_u64());
+    assert!(report["contracts"][2]["stats"].is_null());
+
+    // Stats are only included on request
+    let mut cmd = Command::cargo_bin("cosmwasm-check")?;
+    cmd.arg("--output")
+        .arg("json")
+        .arg("../vm/testdata/hackatom.wasm");
+    let report: serde_json::Value = serde_json::from_slice(&cmd.output()?.stdout)?;
+    assert!(report["contracts"][0]["stats"].is_null());
+
+    Ok(())
+}
@@ -6,23 +6,46 @@ use hex_literal::hex;
 use serde::Deserialize;
 
 // Crypto stuff
-use digest::Digest;
+use digest::{Digest, Update};
+use k256::ecdsa::signature::DigestSigner; // trait
 use k256::ecdsa::SigningKey; // type alias
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 use sha2::Sha256;
 
 use cosmwasm_crypto::{
-    ed25519_batch_verify, ed25519_verify, secp256k1_recover_pubkey, secp256k1_verify,
+    blake2b512, ed25519_batch_verify, ed25519_verify, keccak256, secp256k1_batch_verify,
+    secp256k1_point_add, secp256k1_point_mul, secp256k1_recover_pubkey, secp256k1_verify,
+    secp256r1_recover_pubkey, secp256r1_verify, sha512, sr25519_verify,
 };
+use cosmwasm_crypto::{
+    bls12_381_aggregate_g1, bls12_381_aggregate_g2, bls12_381_hash_to_g1, bls12_381_hash_to_g2,
+    bls12_381_pairing_equality, HashFunction, BLS12_381_G1_GENERATOR, BLS12_381_G2_GENERATOR,
+};
+use cosmwasm_crypto::{groth16_verify_bls12_381, groth16_verify_bn254};
 use std::cmp::min;
 
 const COSMOS_SECP256K1_MSG_HEX: &str = "0a93010a90010a1c2f636f736d6f732e62616e6b2e763162657461312e4d736753656e6412700a2d636f736d6f7331706b707472653766646b6c366766727a6c65736a6a766878686c63337234676d6d6b38727336122d636f736d6f7331717970717870713971637273737a673270767871367273307a716733797963356c7a763778751a100a0575636f736d12073132333435363712650a4e0a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a21034f04181eeba35391b858633a765c4a0c189697b40d216354d50890d350c7029012040a02080112130a0d0a0575636f736d12043230303010c09a0c1a0c73696d642d74657374696e672001";
 const COSMOS_SECP256K1_SIGNATURE_HEX: &str = "c9dd20e07464d3a688ff4b710b1fbc027e495e797cfa0b4804da2ed117959227772de059808f765aa29b8f92edf30f4c2c5a438e30d3fe6897daa7141e3ce6f9";
 const COSMOS_SECP256K1_PUBKEY_BASE64: &str = "A08EGB7ro1ORuFhjOnZcSgwYlpe0DSFjVNUIkNNQxwKQ";
 
+// Generators of the BN254 curve in the EIP-197 encoding
+const BN254_G1_GENERATOR: [u8; 64] = hex!("00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002");
+const BN254_G2_GENERATOR: [u8; 128] = hex!("198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa");
+
+// Test vector from https://tools.ietf.org/html/rfc6979#appendix-A.2.5 (SHA-256)
+const SECP256R1_MSG: &str = "sample";
+const SECP256R1_SIGNATURE_HEX: &str = "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8";
+const SECP256R1_PUBKEY_HEX: &str = "0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299";
+
 // TEST 3 test vector from https://tools.ietf.org/html/rfc8032#section-7.1
 const COSMOS_ED25519_MSG_HEX: &str = "af82";
 const COSMOS_ED25519_SIGNATURE_HEX: &str = "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a";
+
+// Signed with the mini secret key 0x4242…42 (ed25519 expansion) and the `substrate` signing context
+const SR25519_MSG: &[u8] = b"CosmWasm sr25519 test message";
+const SR25519_PUBLIC_KEY_HEX: &str =
+    "a4d007a95b28ce117e4051904503612abdcbd22b60475121f92a8b5dedac375a";
+const SR25519_SIGNATURE_HEX: &str = "ee5ec89ebe97fc4c5f5c3d2ddc4923fd647f287aa06f7c8739b60be2a206e21b1e2bc01f22e13cf04ec92ff40aeb9bf4964c2cf11572159f1bdd346274476585";
 const COSMOS_ED25519_PUBLIC_KEY_HEX: &str =
     "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025";
 
@@ -86,6 +109,37 @@ fn bench_crypto(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("secp256k1_batch_verify_ten", |b| {
+        let message = hex::decode(COSMOS_SECP256K1_MSG_HEX).unwrap();
+        let message_hash = Sha256::digest(&message);
+        let (signatures, public_keys): (Vec<_>, Vec<_>) = (1..=10u8)
+            .map(|i| {
+                let secret_key = SigningKey::from_bytes(&[i; 32]).unwrap();
+                let signature: k256::ecdsa::Signature =
+                    secret_key.sign_digest(Sha256::new().chain(&message));
+                let public_key = secret_key.verifying_key().to_encoded_point(true);
+                (signature.as_ref().to_vec(), public_key.as_bytes().to_vec())
+            })
+            .unzip();
+        let signatures: Vec<&[u8]> = signatures.iter().map(|s| s.as_slice()).collect();
+        let public_keys: Vec<&[u8]> = public_keys.iter().map(|p| p.as_slice()).collect();
+        b.iter(|| {
+            assert!(secp256k1_batch_verify(&[&message_hash], &signatures, &public_keys).unwrap());
+        });
+    });
+
+    group.bench_function("secp256k1_batch_verify_one_pubkey_ten", |b| {
+        let message = hex::decode(COSMOS_SECP256K1_MSG_HEX).unwrap();
+        let message_hash = Sha256::digest(message);
+        let signature = hex::decode(COSMOS_SECP256K1_SIGNATURE_HEX).unwrap();
+        let public_key = base64::decode(COSMOS_SECP256K1_PUBKEY_BASE64).unwrap();
+        let message_hashes: Vec<&[u8]> = vec![&message_hash; 10];
+        let signatures: Vec<&[u8]> = vec![&signature; 10];
+        b.iter(|| {
+            assert!(secp256k1_batch_verify(&message_hashes, &signatures, &[&public_key]).unwrap());
+        });
+    });
+
     group.bench_function("secp256k1_recover_pubkey", |b| {
         let message_hash =
             hex!("82ff40c0a986c6a5cfad4ddf4c3aa6996f1a7837f9c398e17e5de5cbd5a12b28");
@@ -107,6 +161,45 @@ fn bench_crypto(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("secp256k1_point_add", |b| {
+        let p = hex!("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        let q = hex!("02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5");
+        let expected = hex!("02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+        b.iter(|| {
+            assert_eq!(secp256k1_point_add(&p, &q).unwrap(), expected);
+        });
+    });
+
+    group.bench_function("secp256k1_point_mul", |b| {
+        let point = hex!("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        let scalar = hex!("3c9229289a6125f7fdf1885a77bb12c37a8d3b4962d936f7e3084dece32a3ca1");
+        b.iter(|| {
+            secp256k1_point_mul(&point, &scalar).unwrap();
+        });
+    });
+
+    group.bench_function("secp256r1_verify", |b| {
+        let message_hash = Sha256::digest(SECP256R1_MSG);
+        let signature = hex::decode(SECP256R1_SIGNATURE_HEX).unwrap();
+        let public_key = hex::decode(SECP256R1_PUBKEY_HEX).unwrap();
+        b.iter(|| {
+            assert!(secp256r1_verify(&message_hash, &signature, &public_key).unwrap());
+        });
+    });
+
+    group.bench_function("secp256r1_recover_pubkey", |b| {
+        let message_hash = Sha256::digest(SECP256R1_MSG);
+        let signature = hex::decode(SECP256R1_SIGNATURE_HEX).unwrap();
+        let expected = hex::decode(SECP256R1_PUBKEY_HEX).unwrap();
+        let recovery_param: u8 = 0;
+
+        b.iter(|| {
+            let pubkey =
+                secp256r1_recover_pubkey(&message_hash, &signature, recovery_param).unwrap();
+            assert_eq!(pubkey, expected);
+        });
+    });
+
     group.bench_function("ed25519_verify", |b| {
         let message = hex::decode(COSMOS_ED25519_MSG_HEX).unwrap();
         let signature = hex::decode(COSMOS_ED25519_SIGNATURE_HEX).unwrap();
@@ -116,6 +209,14 @@ fn bench_crypto(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("sr25519_verify", |b| {
+        let signature = hex::decode(SR25519_SIGNATURE_HEX).unwrap();
+        let public_key = hex::decode(SR25519_PUBLIC_KEY_HEX).unwrap();
+        b.iter(|| {
+            assert!(sr25519_verify(SR25519_MSG, &signature, &public_key).unwrap());
+        });
+    });
+
     // Ed25519 batch verification of different batch lengths
     {
         let (messages, signatures, public_keys) = read_decode_cosmos_sigs();
@@ -171,6 +272,123 @@ fn bench_crypto(c: &mut Criterion) {
         }
     }
 
+    group.bench_function("bls12_381_hash_to_g1", |b| {
+        b.iter(|| {
+            bls12_381_hash_to_g1(HashFunction::Sha256, b"message", b"dst");
+        });
+    });
+
+    group.bench_function("bls12_381_hash_to_g2", |b| {
+        b.iter(|| {
+            bls12_381_hash_to_g2(HashFunction::Sha256, b"message", b"dst");
+        });
+    });
+
+    for n in [1, 10, 100] {
+        let g1s = BLS12_381_G1_GENERATOR.repeat(n);
+        group.bench_function(
+            format!("bls12_381_aggregate_g1_{}", convert_no_fmt(n as i64)),
+            |b| {
+                b.iter(|| {
+                    bls12_381_aggregate_g1(&g1s).unwrap();
+                });
+            },
+        );
+        let g2s = BLS12_381_G2_GENERATOR.repeat(n);
+        group.bench_function(
+            format!("bls12_381_aggregate_g2_{}", convert_no_fmt(n as i64)),
+            |b| {
+                b.iter(|| {
+                    bls12_381_aggregate_g2(&g2s).unwrap();
+                });
+            },
+        );
+    }
+
+    for n in [1, 5, 10] {
+        let ps = BLS12_381_G1_GENERATOR.repeat(n);
+        let qs = BLS12_381_G2_GENERATOR.repeat(n);
+        group.bench_function(
+            format!("bls12_381_pairing_equality_{}", convert_no_fmt(n as i64)),
+            |b| {
+                b.iter(|| {
+                    bls12_381_pairing_equality(
+                        &ps,
+                        &qs,
+                        &BLS12_381_G1_GENERATOR,
+                        &BLS12_381_G2_GENERATOR,
+                    )
+                    .unwrap();
+                });
+            },
+        );
+    }
+
+    // The proofs are well-formed but do not verify, which takes the same time as a valid proof
+    for n in [0, 5, 20] {
+        let mut input = [0u8; 32];
+        input[31] = 1;
+        let inputs = input.repeat(n);
+
+        let vk = [
+            &BN254_G1_GENERATOR[..],
+            &BN254_G2_GENERATOR.repeat(3),
+            &BN254_G1_GENERATOR.repeat(n + 1),
+        ]
+        .concat();
+        let proof = [
+            &BN254_G1_GENERATOR[..],
+            &BN254_G2_GENERATOR,
+            &BN254_G1_GENERATOR,
+        ]
+        .concat();
+        group.bench_function(
+            format!("groth16_verify_bn254_{}_inputs", convert_no_fmt(n as i64)),
+            |b| {
+                b.iter(|| {
+                    groth16_verify_bn254(&vk, &proof, &inputs).unwrap();
+                });
+            },
+        );
+
+        let vk = [
+            &BLS12_381_G1_GENERATOR[..],
+            &BLS12_381_G2_GENERATOR.repeat(3),
+            &BLS12_381_G1_GENERATOR.repeat(n + 1),
+        ]
+        .concat();
+        let proof = [
+            &BLS12_381_G1_GENERATOR[..],
+            &BLS12_381_G2_GENERATOR,
+            &BLS12_381_G1_GENERATOR,
+        ]
+        .concat();
+        group.bench_function(
+            format!(
+                "groth16_verify_bls12_381_{}_inputs",
+                convert_no_fmt(n as i64)
+            ),
+            |b| {
+                b.iter(|| {
+                    groth16_verify_bls12_381(&vk, &proof, &inputs).unwrap();
+                });
+            },
+        );
+    }
+
+    for n in [32, 1024] {
+        let data = vec![0xab; n];
+        group.bench_function(format!("keccak256_{}_bytes", n), |b| {
+            b.iter(|| keccak256(&data));
+        });
+        group.bench_function(format!("sha512_{}_bytes", n), |b| {
+            b.iter(|| sha512(&data));
+        });
+        group.bench_function(format!("blake2b512_{}_bytes", n), |b| {
+            b.iter(|| blake2b512(&data));
+        });
+    }
+
     group.finish();
 }
 
@@ -0,0 +1,416 @@
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+};
+
+use crate::errors::{CryptoError, CryptoResult};
+
+/// Length of a compressed BLS12-381 G1 point
+pub const BLS12_381_G1_POINT_LEN: usize = 48;
+
+/// Length of a compressed BLS12-381 G2 point
+pub const BLS12_381_G2_POINT_LEN: usize = 96;
+
+/// The generator of the G1 group in compressed form
+pub const BLS12_381_G1_GENERATOR: [u8; BLS12_381_G1_POINT_LEN] = [
+    151, 241, 211, 167, 49, 151, 215, 148, 38, 149, 99, 140, 79, 169, 172, 15, 195, 104, 140, 79,
+    151, 116, 185, 5, 161, 78, 58, 63, 23, 27, 172, 88, 108, 85, 232, 63, 249, 122, 26, 239, 251,
+    58, 240, 10, 219, 34, 198, 187,
+];
+
+/// The generator of the G2 group in compressed form
+pub const BLS12_381_G2_GENERATOR: [u8; BLS12_381_G2_POINT_LEN] = [
+    147, 224, 43, 96, 82, 113, 159, 96, 125, 172, 211, 160, 136, 39, 79, 101, 89, 107, 208, 208,
+    153, 32, 182, 26, 181, 218, 97, 187, 220, 127, 80, 73, 51, 76, 241, 18, 19, 148, 93, 87, 229,
+    172, 125, 5, 93, 4, 43, 126, 2, 74, 162, 178, 240, 143, 10, 145, 38, 8, 5, 39, 45, 197, 16, 81,
+    198, 228, 122, 212, 250, 64, 59, 2, 180, 81, 11, 100, 122, 227, 209, 119, 11, 172, 3, 38, 168,
+    5, 187, 239, 212, 128, 86, 200, 193, 33, 189, 184,
+];
+
+/// The hash function used in the `expand_message_xmd` step of hash-to-curve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashFunction {
+    Sha256 = 0,
+}
+
+impl HashFunction {
+    /// Converts the numeric representation used at the VM boundary
+    pub fn from_u32(value: u32) -> CryptoResult<Self> {
+        match value {
+            0 => Ok(HashFunction::Sha256),
+            _ => Err(CryptoError::unknown_hash_function()),
+        }
+    }
+
+    pub fn to_u32(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Adds up a list of compressed G1 points, concatenated into a single byte slice.
+///
+/// This is used to aggregate BLS signatures or public keys.
+/// An empty input results in the identity element.
+pub fn bls12_381_aggregate_g1(points: &[u8]) -> CryptoResult<[u8; BLS12_381_G1_POINT_LEN]> {
+    let sum = g1s_from_bytes(points)?
+        .iter()
+        .fold(G1Projective::identity(), |sum, point| sum + point);
+    Ok(G1Affine::from(sum).to_compressed())
+}
+
+/// Adds up a list of compressed G2 points, concatenated into a single byte slice.
+///
+/// This is used to aggregate BLS signatures or public keys.
+/// An empty input results in the identity element.
+pub fn bls12_381_aggregate_g2(points: &[u8]) -> CryptoResult<[u8; BLS12_381_G2_POINT_LEN]> {
+    let sum = g2s_from_bytes(points)?
+        .iter()
+        .fold(G2Projective::identity(), |sum, point| sum + point);
+    Ok(G2Affine::from(sum).to_compressed())
+}
+
+/// Checks if the product of the pairings `e(p_1, q_1) * … * e(p_n, q_n)` equals `e(r, s)`.
+///
+/// `ps` is a list of compressed G1 points and `qs` a list of compressed G2 points of the same
+/// length, each concatenated into a single byte slice. `r` is a compressed G1 point
+/// and `s` a compressed G2 point.
+///
+/// For a single pair this verifies a BLS signature: with the signature in G1 and the public key
+/// in G2, `e(signature, G2 generator) == e(hash_to_g1(message), public_key)` is checked via
+/// `ps = signature`, `qs = G2 generator`, `r = hash_to_g1(message)` and `s = public_key`.
+pub fn bls12_381_pairing_equality(ps: &[u8], qs: &[u8], r: &[u8], s: &[u8]) -> CryptoResult<bool> {
+    // Check the shape of the input before the expensive point decoding
+    if !ps.len().is_multiple_of(BLS12_381_G1_POINT_LEN)
+        || !qs.len().is_multiple_of(BLS12_381_G2_POINT_LEN)
+    {
+        return Err(CryptoError::invalid_point());
+    }
+    let (p_count, q_count) = (
+        ps.len() / BLS12_381_G1_POINT_LEN,
+        qs.len() / BLS12_381_G2_POINT_LEN,
+    );
+    if p_count != q_count {
+        return Err(CryptoError::unequal_point_amount(p_count, q_count));
+    }
+    let ps = g1s_from_bytes(ps)?;
+    let qs = g2s_from_bytes(qs)?;
+    let r = g1_from_bytes(r)?;
+    let s = g2_from_bytes(s)?;
+
+    // e(p_1, q_1) * … * e(p_n, q_n) * e(-r, s) == 1
+    let neg_r = -r;
+    let qs: Vec<G2Prepared> = qs.into_iter().map(G2Prepared::from).collect();
+    let s = G2Prepared::from(s);
+    let mut terms: Vec<(&G1Affine, &G2Prepared)> = ps.iter().zip(qs.iter()).collect();
+    terms.push((&neg_r, &s));
+
+    Ok(multi_miller_loop(&terms).final_exponentiation() == Gt::identity())
+}
+
+/// Hashes a message to a G1 point using the `hash_to_curve` construction of
+/// [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380), with the domain separation tag `dst`.
+/// For SHA-256 this is the `BLS12381G1_XMD:SHA-256_SSWU_RO_` suite.
+///
+/// Returns the compressed point.
+pub fn bls12_381_hash_to_g1(
+    hash_function: HashFunction,
+    msg: &[u8],
+    dst: &[u8],
+) -> [u8; BLS12_381_G1_POINT_LEN] {
+    let point = match hash_function {
+        HashFunction::Sha256 => {
+            <G1Projective as HashToCurve<ExpandMsgXmd<sha2_v9::Sha256>>>::hash_to_curve(msg, dst)
+        }
+    };
+    G1Affine::from(point).to_compressed()
+}
+
+/// Hashes a message to a G2 point using the `hash_to_curve` construction of
+/// [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380), with the domain separation tag `dst`.
+/// For SHA-256 this is the `BLS12381G2_XMD:SHA-256_SSWU_RO_` suite.
+///
+/// Returns the compressed point.
+pub fn bls12_381_hash_to_g2(
+    hash_function: HashFunction,
+    msg: &[u8],
+    dst: &[u8],
+) -> [u8; BLS12_381_G2_POINT_LEN] {
+    let point = match hash_function {
+        HashFunction::Sha256 => {
+            <G2Projective as HashToCurve<ExpandMsgXmd<sha2_v9::Sha256>>>::hash_to_curve(msg, dst)
+        }
+    };
+    G2Affine::from(point).to_compressed()
+}
+
+/// Parses a compressed G1 point. This checks that the point is on the curve
+/// and in the correct subgroup.
+pub(crate) fn g1_from_bytes(data: &[u8]) -> CryptoResult<G1Affine> {
+    let data: &[u8; BLS12_381_G1_POINT_LEN] =
+        data.try_into().map_err(|_| CryptoError::invalid_point())?;
+    Option::from(G1Affine::from_compressed(data)).ok_or_else(CryptoError::invalid_point)
+}
+
+/// Parses a compressed G2 point. This checks that the point is on the curve
+/// and in the correct subgroup.
+pub(crate) fn g2_from_bytes(data: &[u8]) -> CryptoResult<G2Affine> {
+    let data: &[u8; BLS12_381_G2_POINT_LEN] =
+        data.try_into().map_err(|_| CryptoError::invalid_point())?;
+    Option::from(G2Affine::from_compressed(data)).ok_or_else(CryptoError::invalid_point)
+}
+
+fn g1s_from_bytes(data: &[u8]) -> CryptoResult<Vec<G1Affine>> {
+    let chunks = data.chunks_exact(BLS12_381_G1_POINT_LEN);
+    if !chunks.remainder().is_empty() {
+        return Err(CryptoError::invalid_point());
+    }
+    chunks.map(g1_from_bytes).collect()
+}
+
+fn g2s_from_bytes(data: &[u8]) -> CryptoResult<Vec<G2Affine>> {
+    let chunks = data.chunks_exact(BLS12_381_G2_POINT_LEN);
+    if !chunks.remainder().is_empty() {
+        return Err(CryptoError::invalid_point());
+    }
+    chunks.map(g2_from_bytes).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bls12_381::Scalar;
+    use hex_literal::hex;
+
+    const DST_G1: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+    const DST_G2: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+    fn secret_key(seed: u64) -> Scalar {
+        Scalar::from(seed) * Scalar::from(0x1234_5678_9abc_def0)
+    }
+
+    fn g1_mul(point: &[u8], scalar: Scalar) -> [u8; BLS12_381_G1_POINT_LEN] {
+        G1Affine::from(g1_from_bytes(point).unwrap() * scalar).to_compressed()
+    }
+
+    fn g2_mul(point: &[u8], scalar: Scalar) -> [u8; BLS12_381_G2_POINT_LEN] {
+        G2Affine::from(g2_from_bytes(point).unwrap() * scalar).to_compressed()
+    }
+
+    #[test]
+    fn generators_are_correct() {
+        assert_eq!(
+            BLS12_381_G1_GENERATOR,
+            G1Affine::generator().to_compressed()
+        );
+        assert_eq!(
+            BLS12_381_G2_GENERATOR,
+            G2Affine::generator().to_compressed()
+        );
+    }
+
+    #[test]
+    fn hash_function_from_u32_works() {
+        assert_eq!(HashFunction::from_u32(0).unwrap(), HashFunction::Sha256);
+        assert_eq!(HashFunction::Sha256.to_u32(), 0);
+        match HashFunction::from_u32(1).unwrap_err() {
+            CryptoError::UnknownHashFunction { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn bls12_381_hash_to_g1_works() {
+        // https://www.rfc-editor.org/rfc/rfc9380#appendix-J.9.1 (msg = "")
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        let point = bls12_381_hash_to_g1(HashFunction::Sha256, b"", dst);
+        assert_eq!(point, hex!("852926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1"));
+    }
+
+    #[test]
+    fn bls12_381_hash_to_g2_works() {
+        let point = bls12_381_hash_to_g2(HashFunction::Sha256, b"abc", DST_G2);
+        assert!(g2_from_bytes(&point).is_ok());
+        assert_ne!(
+            point,
+            bls12_381_hash_to_g2(HashFunction::Sha256, b"abd", DST_G2)
+        );
+        assert_ne!(
+            point,
+            bls12_381_hash_to_g2(HashFunction::Sha256, b"abc", DST_G1)
+        );
+    }
+
+    #[test]
+    fn bls12_381_aggregate_g1_works() {
+        let g = BLS12_381_G1_GENERATOR;
+        let points = [g, g, g].concat();
+        assert_eq!(
+            bls12_381_aggregate_g1(&points).unwrap(),
+            g1_mul(&g, Scalar::from(3))
+        );
+        assert_eq!(bls12_381_aggregate_g1(&g).unwrap(), g);
+
+        // empty input results in the identity
+        assert_eq!(
+            bls12_381_aggregate_g1(&[]).unwrap(),
+            G1Affine::identity().to_compressed()
+        );
+    }
+
+    #[test]
+    fn bls12_381_aggregate_g2_works() {
+        let g = BLS12_381_G2_GENERATOR;
+        let points = [g, g].concat();
+        assert_eq!(
+            bls12_381_aggregate_g2(&points).unwrap(),
+            g2_mul(&g, Scalar::from(2))
+        );
+        assert_eq!(
+            bls12_381_aggregate_g2(&[]).unwrap(),
+            G2Affine::identity().to_compressed()
+        );
+    }
+
+    #[test]
+    fn bls12_381_aggregate_fails_for_invalid_points() {
+        // wrong length
+        let points = [&BLS12_381_G1_GENERATOR[..], &[0u8; 5]].concat();
+        match bls12_381_aggregate_g1(&points).unwrap_err() {
+            CryptoError::InvalidPoint { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // not on the curve
+        let mut point = BLS12_381_G1_GENERATOR;
+        point[47] ^= 0x01;
+        match bls12_381_aggregate_g1(&point).unwrap_err() {
+            CryptoError::InvalidPoint { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        let mut point = BLS12_381_G2_GENERATOR;
+        point[95] ^= 0x01;
+        match bls12_381_aggregate_g2(&point).unwrap_err() {
+            CryptoError::InvalidPoint { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn bls12_381_pairing_equality_verifies_signatures() {
+        let msg = b"the message";
+
+        // Signature in G1, public key in G2 (e.g. drand's quicknet)
+        let sk = secret_key(1);
+        let pubkey = g2_mul(&BLS12_381_G2_GENERATOR, sk);
+        let msg_point = bls12_381_hash_to_g1(HashFunction::Sha256, msg, DST_G1);
+        let signature = g1_mul(&msg_point, sk);
+        assert!(bls12_381_pairing_equality(
+            &signature,
+            &BLS12_381_G2_GENERATOR,
+            &msg_point,
+            &pubkey
+        )
+        .unwrap());
+
+        // Wrong message
+        let other_msg_point = bls12_381_hash_to_g1(HashFunction::Sha256, b"other", DST_G1);
+        assert!(!bls12_381_pairing_equality(
+            &signature,
+            &BLS12_381_G2_GENERATOR,
+            &other_msg_point,
+            &pubkey
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn bls12_381_pairing_equality_verifies_aggregated_signatures() {
+        // Signatures in G2, public keys in G1 (e.g. Ethereum sync committees).
+        // For the same message e(G1, sig_1 + … + sig_n) == e(pk_1 + … + pk_n, H(msg)).
+        let msg_point = bls12_381_hash_to_g2(HashFunction::Sha256, b"block root", DST_G2);
+        let sks: Vec<Scalar> = (1..=5).map(secret_key).collect();
+        let pubkeys: Vec<u8> = sks
+            .iter()
+            .flat_map(|sk| g1_mul(&BLS12_381_G1_GENERATOR, *sk))
+            .collect();
+        let signatures: Vec<u8> = sks.iter().flat_map(|sk| g2_mul(&msg_point, *sk)).collect();
+
+        let pubkey = bls12_381_aggregate_g1(&pubkeys).unwrap();
+        let signature = bls12_381_aggregate_g2(&signatures).unwrap();
+        assert!(bls12_381_pairing_equality(
+            &BLS12_381_G1_GENERATOR,
+            &signature,
+            &pubkey,
+            &msg_point
+        )
+        .unwrap());
+
+        // Missing signer
+        let pubkey = bls12_381_aggregate_g1(&pubkeys[BLS12_381_G1_POINT_LEN..]).unwrap();
+        assert!(!bls12_381_pairing_equality(
+            &BLS12_381_G1_GENERATOR,
+            &signature,
+            &pubkey,
+            &msg_point
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn bls12_381_pairing_equality_works_for_multiple_pairs() {
+        // e(2·G1, 3·G2) * e(G1, G2) == e(7·G1, G2)
+        let ps = [
+            g1_mul(&BLS12_381_G1_GENERATOR, Scalar::from(2)),
+            BLS12_381_G1_GENERATOR,
+        ]
+        .concat();
+        let qs = [
+            g2_mul(&BLS12_381_G2_GENERATOR, Scalar::from(3)),
+            BLS12_381_G2_GENERATOR,
+        ]
+        .concat();
+        let r = g1_mul(&BLS12_381_G1_GENERATOR, Scalar::from(7));
+        assert!(bls12_381_pairing_equality(&ps, &qs, &r, &BLS12_381_G2_GENERATOR).unwrap());
+
+        let r = g1_mul(&BLS12_381_G1_GENERATOR, Scalar::from(6));
+        assert!(!bls12_381_pairing_equality(&ps, &qs, &r, &BLS12_381_G2_GENERATOR).unwrap());
+    }
+
+    #[test]
+    fn bls12_381_pairing_equality_fails_for_invalid_input() {
+        let g1 = BLS12_381_G1_GENERATOR;
+        let g2 = BLS12_381_G2_GENERATOR;
+
+        match bls12_381_pairing_equality(&[g1, g1].concat(), &g2, &g1, &g2).unwrap_err() {
+            CryptoError::UnequalPointAmount { left, right, .. } => {
+                assert_eq!(left, 2);
+                assert_eq!(right, 1);
+            }
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // point amounts are compared before the points are decoded
+        match bls12_381_pairing_equality(&[], &[0u8; 3 * 96], &g1, &g2).unwrap_err() {
+            CryptoError::UnequalPointAmount { left, right, .. } => {
+                assert_eq!(left, 0);
+                assert_eq!(right, 3);
+            }
+            err => panic!("Unexpected error: {}", err),
+        }
+        match bls12_381_pairing_equality(&g1, &g2[1..], &g1, &g2).unwrap_err() {
+            CryptoError::InvalidPoint { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        match bls12_381_pairing_equality(&g1, &g2, &g1[1..], &g2).unwrap_err() {
+            CryptoError::InvalidPoint { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+        match bls12_381_pairing_equality(&g1, &g2, &g1, &g1).unwrap_err() {
+            CryptoError::InvalidPoint { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+}
@@ -39,6 +39,28 @@ pub enum CryptoError {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
+    #[error("Invalid point")]
+    InvalidPoint {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Unequal number of points: {left} != {right}")]
+    UnequalPointAmount {
+        left: usize,
+        right: usize,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Unknown hash function")]
+    UnknownHashFunction {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Invalid scalar")]
+    InvalidScalar {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
 }
 
 impl CryptoError {
@@ -86,6 +108,36 @@ impl CryptoError {
         }
     }
 
+    pub fn invalid_point() -> Self {
+        CryptoError::InvalidPoint {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub fn unequal_point_amount(left: usize, right: usize) -> Self {
+        CryptoError::UnequalPointAmount {
+            left,
+            right,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub fn unknown_hash_function() -> Self {
+        CryptoError::UnknownHashFunction {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub fn invalid_scalar() -> Self {
+        CryptoError::InvalidScalar {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
     /// Numeric error code that can easily be passed over the
     /// contract VM boundary.
     pub fn code(&self) -> u32 {
@@ -96,6 +148,10 @@ impl CryptoError {
             CryptoError::InvalidRecoveryParam { .. } => 6,
             CryptoError::BatchErr { .. } => 7,
             CryptoError::GenericErr { .. } => 10,
+            CryptoError::InvalidPoint { .. } => 11,
+            CryptoError::UnequalPointAmount { .. } => 12,
+            CryptoError::UnknownHashFunction { .. } => 13,
+            CryptoError::InvalidScalar { .. } => 14,
         }
     }
 }
@@ -153,4 +209,43 @@ mod tests {
             _ => panic!("wrong error type!"),
         }
     }
+
+    #[test]
+    fn invalid_point_works() {
+        let error = CryptoError::invalid_point();
+        match error {
+            CryptoError::InvalidPoint { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn unequal_point_amount_works() {
+        let error = CryptoError::unequal_point_amount(2, 3);
+        match error {
+            CryptoError::UnequalPointAmount { left, right, .. } => {
+                assert_eq!(left, 2);
+                assert_eq!(right, 3);
+            }
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn unknown_hash_function_works() {
+        let error = CryptoError::unknown_hash_function();
+        match error {
+            CryptoError::UnknownHashFunction { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
+    #[test]
+    fn invalid_scalar_works() {
+        let error = CryptoError::invalid_scalar();
+        match error {
+            CryptoError::InvalidScalar { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
 }
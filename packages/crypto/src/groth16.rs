@@ -0,0 +1,421 @@
+use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Prepared, Gt, Scalar};
+use bn::{AffineG1, AffineG2, Fq, Fq2, Fr, Group, G1, G2};
+
+use crate::bls12_381::{
+    g1_from_bytes, g2_from_bytes, BLS12_381_G1_POINT_LEN, BLS12_381_G2_POINT_LEN,
+};
+use crate::errors::{CryptoError, CryptoResult};
+
+/// Length of a public input, which is a big-endian encoded scalar
+pub const GROTH16_PUBLIC_INPUT_LEN: usize = 32;
+
+/// Length of an uncompressed BN254 G1 point (x ‖ y) as used by the EIP-197 precompile
+pub const BN254_G1_POINT_LEN: usize = 64;
+
+/// Length of an uncompressed BN254 G2 point (x.im ‖ x.re ‖ y.im ‖ y.re) as used by the EIP-197 precompile
+pub const BN254_G2_POINT_LEN: usize = 128;
+
+const BN254_FIELD_ELEMENT_LEN: usize = 32;
+
+/// Verifies a Groth16 proof over the BN254 (alt_bn128) curve.
+///
+/// All points use the uncompressed big-endian encoding of the EIP-196/EIP-197 precompiles,
+/// where the all-zero encoding is the point at infinity.
+///
+/// - `verifying_key` is `alpha_g1 ‖ beta_g2 ‖ gamma_g2 ‖ delta_g2 ‖ ic[0] ‖ … ‖ ic[n]`
+/// - `proof` is `a_g1 ‖ b_g2 ‖ c_g1`
+/// - `public_inputs` is the concatenation of the `n` big-endian scalars
+///
+/// Returns `Ok(false)` if the proof does not verify and an error if any of the inputs is malformed.
+pub fn groth16_verify_bn254(
+    verifying_key: &[u8],
+    proof: &[u8],
+    public_inputs: &[u8],
+) -> CryptoResult<bool> {
+    let (alpha, rest) = split(verifying_key, BN254_G1_POINT_LEN)?;
+    let (beta, rest) = split(rest, BN254_G2_POINT_LEN)?;
+    let (gamma, rest) = split(rest, BN254_G2_POINT_LEN)?;
+    let (delta, ic) = split(rest, BN254_G2_POINT_LEN)?;
+    let ic = chunks(ic, BN254_G1_POINT_LEN)?
+        .map(bn254_g1_from_bytes)
+        .collect::<CryptoResult<Vec<_>>>()?;
+    let inputs = chunks(public_inputs, GROTH16_PUBLIC_INPUT_LEN)?
+        .map(bn254_scalar_from_bytes)
+        .collect::<CryptoResult<Vec<_>>>()?;
+    check_input_count(inputs.len(), ic.len())?;
+
+    if proof.len() != 2 * BN254_G1_POINT_LEN + BN254_G2_POINT_LEN {
+        return Err(CryptoError::generic_err("Invalid proof length"));
+    }
+    let (a, rest) = proof.split_at(BN254_G1_POINT_LEN);
+    let (b, c) = rest.split_at(BN254_G2_POINT_LEN);
+
+    let l = inputs
+        .iter()
+        .zip(&ic[1..])
+        .fold(ic[0], |acc, (input, point)| acc + *point * *input);
+
+    let result = bn::pairing_batch(&[
+        (-bn254_g1_from_bytes(a)?, bn254_g2_from_bytes(b)?),
+        (bn254_g1_from_bytes(alpha)?, bn254_g2_from_bytes(beta)?),
+        (l, bn254_g2_from_bytes(gamma)?),
+        (bn254_g1_from_bytes(c)?, bn254_g2_from_bytes(delta)?),
+    ]);
+    Ok(result == bn::Gt::one())
+}
+
+/// Verifies a Groth16 proof over the BLS12-381 curve.
+///
+/// All points use the compressed encoding of the Zcash serialization format and
+/// are checked to be in the correct subgroup.
+///
+/// - `verifying_key` is `alpha_g1 ‖ beta_g2 ‖ gamma_g2 ‖ delta_g2 ‖ ic[0] ‖ … ‖ ic[n]`
+/// - `proof` is `a_g1 ‖ b_g2 ‖ c_g1`
+/// - `public_inputs` is the concatenation of the `n` big-endian scalars
+///
+/// Returns `Ok(false)` if the proof does not verify and an error if any of the inputs is malformed.
+pub fn groth16_verify_bls12_381(
+    verifying_key: &[u8],
+    proof: &[u8],
+    public_inputs: &[u8],
+) -> CryptoResult<bool> {
+    let (alpha, rest) = split(verifying_key, BLS12_381_G1_POINT_LEN)?;
+    let (beta, rest) = split(rest, BLS12_381_G2_POINT_LEN)?;
+    let (gamma, rest) = split(rest, BLS12_381_G2_POINT_LEN)?;
+    let (delta, ic) = split(rest, BLS12_381_G2_POINT_LEN)?;
+    let ic = chunks(ic, BLS12_381_G1_POINT_LEN)?
+        .map(g1_from_bytes)
+        .collect::<CryptoResult<Vec<_>>>()?;
+    let inputs = chunks(public_inputs, GROTH16_PUBLIC_INPUT_LEN)?
+        .map(bls12_381_scalar_from_bytes)
+        .collect::<CryptoResult<Vec<_>>>()?;
+    check_input_count(inputs.len(), ic.len())?;
+
+    if proof.len() != 2 * BLS12_381_G1_POINT_LEN + BLS12_381_G2_POINT_LEN {
+        return Err(CryptoError::generic_err("Invalid proof length"));
+    }
+    let (a, rest) = proof.split_at(BLS12_381_G1_POINT_LEN);
+    let (b, c) = rest.split_at(BLS12_381_G2_POINT_LEN);
+
+    let l = inputs
+        .iter()
+        .zip(&ic[1..])
+        .fold(G1Projective::from(ic[0]), |acc, (input, point)| {
+            acc + point * input
+        });
+
+    let neg_a = -g1_from_bytes(a)?;
+    let b = G2Prepared::from(g2_from_bytes(b)?);
+    let alpha = g1_from_bytes(alpha)?;
+    let beta = G2Prepared::from(g2_from_bytes(beta)?);
+    let l = G1Affine::from(l);
+    let gamma = G2Prepared::from(g2_from_bytes(gamma)?);
+    let c = g1_from_bytes(c)?;
+    let delta = G2Prepared::from(g2_from_bytes(delta)?);
+
+    let result = multi_miller_loop(&[(&neg_a, &b), (&alpha, &beta), (&l, &gamma), (&c, &delta)])
+        .final_exponentiation();
+    Ok(result == Gt::identity())
+}
+
+/// Splits off the first `len` bytes, failing if the verifying key is too short
+fn split(data: &[u8], len: usize) -> CryptoResult<(&[u8], &[u8])> {
+    if data.len() < len {
+        return Err(CryptoError::generic_err("Invalid verifying key length"));
+    }
+    Ok(data.split_at(len))
+}
+
+fn chunks(data: &[u8], len: usize) -> CryptoResult<std::slice::ChunksExact<'_, u8>> {
+    let chunks = data.chunks_exact(len);
+    if !chunks.remainder().is_empty() {
+        return Err(CryptoError::generic_err(format!(
+            "Data length must be a multiple of {len}"
+        )));
+    }
+    Ok(chunks)
+}
+
+fn check_input_count(inputs: usize, ic: usize) -> CryptoResult<()> {
+    if ic == 0 || inputs != ic - 1 {
+        return Err(CryptoError::generic_err(format!(
+            "Number of public inputs ({inputs}) does not match the verifying key"
+        )));
+    }
+    Ok(())
+}
+
+fn bn254_fq_from_bytes(data: &[u8]) -> CryptoResult<Fq> {
+    Fq::from_slice(data).map_err(|_| CryptoError::invalid_point())
+}
+
+fn bn254_g1_from_bytes(data: &[u8]) -> CryptoResult<G1> {
+    let (x, y) = data.split_at(BN254_FIELD_ELEMENT_LEN);
+    let (x, y) = (bn254_fq_from_bytes(x)?, bn254_fq_from_bytes(y)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1::zero());
+    }
+    AffineG1::new(x, y)
+        .map(G1::from)
+        .map_err(|_| CryptoError::invalid_point())
+}
+
+/// Parses a G2 point. In addition to the curve check, this checks that the point
+/// is in the correct subgroup.
+fn bn254_g2_from_bytes(data: &[u8]) -> CryptoResult<G2> {
+    let mut elements = data.chunks_exact(BN254_FIELD_ELEMENT_LEN);
+    let mut next = || bn254_fq_from_bytes(elements.next().unwrap());
+    let (x_im, x_re, y_im, y_re) = (next()?, next()?, next()?, next()?);
+    let (x, y) = (Fq2::new(x_re, x_im), Fq2::new(y_re, y_im));
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2::zero());
+    }
+    AffineG2::new(x, y)
+        .map(G2::from)
+        .map_err(|_| CryptoError::invalid_point())
+}
+
+fn bn254_scalar_from_bytes(data: &[u8]) -> CryptoResult<Fr> {
+    // `Fr::from_slice` silently reduces values >= r, so we go through `Fr::new`
+    // to reject non-canonical encodings.
+    bn::arith::U256::from_slice(data)
+        .ok()
+        .and_then(Fr::new)
+        .ok_or_else(CryptoError::invalid_scalar)
+}
+
+fn bls12_381_scalar_from_bytes(data: &[u8]) -> CryptoResult<Scalar> {
+    let mut bytes: [u8; GROTH16_PUBLIC_INPUT_LEN] = data.try_into().unwrap();
+    bytes.reverse();
+    Option::from(Scalar::from_bytes(&bytes)).ok_or_else(CryptoError::invalid_scalar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bls12_381::{G2Affine, G2Projective};
+
+    /// Scalars of a synthetic proof: `a·b = alpha·beta + l·gamma + c·delta`
+    /// with `l = ic0 + Σ input_i·ic_i`.
+    struct Setup {
+        alpha: u64,
+        beta: u64,
+        gamma: u64,
+        delta: u64,
+        ic: Vec<u64>,
+        inputs: Vec<u64>,
+        a: u64,
+        b: u64,
+    }
+
+    fn setup() -> Setup {
+        Setup {
+            alpha: 3,
+            beta: 5,
+            gamma: 7,
+            delta: 11,
+            ic: vec![13, 17, 19],
+            inputs: vec![23, 29],
+            a: 31,
+            b: 37,
+        }
+    }
+
+    fn be_scalar(value: u64) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        out[24..].copy_from_slice(&value.to_be_bytes());
+        out
+    }
+
+    fn bn_fr(value: u64) -> Fr {
+        Fr::from_str(&value.to_string()).unwrap()
+    }
+
+    fn bn_g1(point: G1) -> Vec<u8> {
+        let point = AffineG1::from_jacobian(point).unwrap();
+        let mut out = vec![0u8; BN254_G1_POINT_LEN];
+        point.x().to_big_endian(&mut out[..32]).unwrap();
+        point.y().to_big_endian(&mut out[32..]).unwrap();
+        out
+    }
+
+    fn bn_g2(point: G2) -> Vec<u8> {
+        let point = AffineG2::from_jacobian(point).unwrap();
+        let mut out = vec![0u8; BN254_G2_POINT_LEN];
+        let (x, y) = (point.x(), point.y());
+        x.imaginary().to_big_endian(&mut out[..32]).unwrap();
+        x.real().to_big_endian(&mut out[32..64]).unwrap();
+        y.imaginary().to_big_endian(&mut out[64..96]).unwrap();
+        y.real().to_big_endian(&mut out[96..]).unwrap();
+        out
+    }
+
+    /// Returns verifying key, proof and public inputs
+    fn bn254_fixture(setup: &Setup) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let s = bn_fr;
+        let l = setup
+            .inputs
+            .iter()
+            .zip(&setup.ic[1..])
+            .fold(s(setup.ic[0]), |acc, (i, ic)| acc + s(*i) * s(*ic));
+        let c = (s(setup.a) * s(setup.b) - s(setup.alpha) * s(setup.beta) - l * s(setup.gamma))
+            * s(setup.delta).inverse().unwrap();
+
+        let mut vk = bn_g1(G1::one() * s(setup.alpha));
+        vk.extend(bn_g2(G2::one() * s(setup.beta)));
+        vk.extend(bn_g2(G2::one() * s(setup.gamma)));
+        vk.extend(bn_g2(G2::one() * s(setup.delta)));
+        for ic in &setup.ic {
+            vk.extend(bn_g1(G1::one() * s(*ic)));
+        }
+        let mut proof = bn_g1(G1::one() * s(setup.a));
+        proof.extend(bn_g2(G2::one() * s(setup.b)));
+        proof.extend(bn_g1(G1::one() * c));
+        let inputs = setup.inputs.iter().flat_map(|i| be_scalar(*i)).collect();
+        (vk, proof, inputs)
+    }
+
+    fn bls_g1(scalar: Scalar) -> Vec<u8> {
+        G1Affine::from(G1Projective::generator() * scalar)
+            .to_compressed()
+            .to_vec()
+    }
+
+    fn bls_g2(scalar: Scalar) -> Vec<u8> {
+        G2Affine::from(G2Projective::generator() * scalar)
+            .to_compressed()
+            .to_vec()
+    }
+
+    /// Returns verifying key, proof and public inputs
+    fn bls12_381_fixture(setup: &Setup) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let s = Scalar::from;
+        let l = setup
+            .inputs
+            .iter()
+            .zip(&setup.ic[1..])
+            .fold(s(setup.ic[0]), |acc, (i, ic)| acc + s(*i) * s(*ic));
+        let c = (s(setup.a) * s(setup.b) - s(setup.alpha) * s(setup.beta) - l * s(setup.gamma))
+            * s(setup.delta).invert().unwrap();
+
+        let mut vk = bls_g1(s(setup.alpha));
+        vk.extend(bls_g2(s(setup.beta)));
+        vk.extend(bls_g2(s(setup.gamma)));
+        vk.extend(bls_g2(s(setup.delta)));
+        for ic in &setup.ic {
+            vk.extend(bls_g1(s(*ic)));
+        }
+        let mut proof = bls_g1(s(setup.a));
+        proof.extend(bls_g2(s(setup.b)));
+        proof.extend(bls_g1(c));
+        let inputs = setup.inputs.iter().flat_map(|i| be_scalar(*i)).collect();
+        (vk, proof, inputs)
+    }
+
+    #[test]
+    fn groth16_verify_bn254_works() {
+        let (vk, proof, inputs) = bn254_fixture(&setup());
+        assert!(groth16_verify_bn254(&vk, &proof, &inputs).unwrap());
+
+        // wrong public input
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[31] ^= 1;
+        assert!(!groth16_verify_bn254(&vk, &proof, &wrong_inputs).unwrap());
+
+        // proof for different public inputs
+        let (_, other_proof, _) = bn254_fixture(&Setup {
+            inputs: vec![23, 30],
+            ..setup()
+        });
+        assert!(!groth16_verify_bn254(&vk, &other_proof, &inputs).unwrap());
+    }
+
+    #[test]
+    fn groth16_verify_bn254_works_without_public_inputs() {
+        let setup = Setup {
+            ic: vec![13],
+            inputs: vec![],
+            ..setup()
+        };
+        let (vk, proof, inputs) = bn254_fixture(&setup);
+        assert!(inputs.is_empty());
+        assert!(groth16_verify_bn254(&vk, &proof, &inputs).unwrap());
+    }
+
+    #[test]
+    fn groth16_verify_bn254_rejects_malformed_input() {
+        let (vk, proof, inputs) = bn254_fixture(&setup());
+
+        // input count mismatch
+        let result = groth16_verify_bn254(&vk, &proof, &inputs[..32]);
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+        let result = groth16_verify_bn254(&vk, &proof, &inputs[..40]);
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+
+        // truncated verifying key and proof
+        let result = groth16_verify_bn254(&vk[..vk.len() - 1], &proof, &inputs);
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+        let result = groth16_verify_bn254(&vk[..100], &proof, &inputs);
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+        let result = groth16_verify_bn254(&vk, &proof[..proof.len() - 1], &inputs);
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+
+        // point not on curve
+        let mut broken_proof = proof.clone();
+        broken_proof[63] ^= 1;
+        let result = groth16_verify_bn254(&vk, &broken_proof, &inputs);
+        assert!(matches!(result, Err(CryptoError::InvalidPoint { .. })));
+
+        // scalar not smaller than the group order
+        let mut broken_inputs = inputs;
+        broken_inputs[..32].copy_from_slice(&[0xff; 32]);
+        let result = groth16_verify_bn254(&vk, &proof, &broken_inputs);
+        assert!(matches!(result, Err(CryptoError::InvalidScalar { .. })));
+    }
+
+    #[test]
+    fn groth16_verify_bls12_381_works() {
+        let (vk, proof, inputs) = bls12_381_fixture(&setup());
+        assert!(groth16_verify_bls12_381(&vk, &proof, &inputs).unwrap());
+
+        // wrong public input
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[31] ^= 1;
+        assert!(!groth16_verify_bls12_381(&vk, &proof, &wrong_inputs).unwrap());
+
+        // proof for different public inputs
+        let (_, other_proof, _) = bls12_381_fixture(&Setup {
+            inputs: vec![23, 30],
+            ..setup()
+        });
+        assert!(!groth16_verify_bls12_381(&vk, &other_proof, &inputs).unwrap());
+    }
+
+    #[test]
+    fn groth16_verify_bls12_381_rejects_malformed_input() {
+        let (vk, proof, inputs) = bls12_381_fixture(&setup());
+
+        // input count mismatch
+        let result = groth16_verify_bls12_381(&vk, &proof, &inputs[..32]);
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+
+        // truncated verifying key and proof
+        let result = groth16_verify_bls12_381(&vk[..vk.len() - 1], &proof, &inputs);
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+        let result = groth16_verify_bls12_381(&vk, &proof[..proof.len() - 1], &inputs);
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+
+        // invalid point encoding
+        let mut broken_proof = proof.clone();
+        broken_proof[0] ^= 0x80;
+        let result = groth16_verify_bls12_381(&vk, &broken_proof, &inputs);
+        assert!(matches!(result, Err(CryptoError::InvalidPoint { .. })));
+
+        // scalar not smaller than the group order
+        let mut broken_inputs = inputs;
+        broken_inputs[..32].copy_from_slice(&[0xff; 32]);
+        let result = groth16_verify_bls12_381(&vk, &proof, &broken_inputs);
+        assert!(matches!(result, Err(CryptoError::InvalidScalar { .. })));
+    }
+}
@@ -0,0 +1,66 @@
+use blake2::Blake2b512;
+use digest::Digest; // trait
+use sha2::Sha512;
+use sha3::Keccak256;
+
+/// Computes the Keccak-256 hash of the given data, as used by Ethereum.
+///
+/// Please note that this is the original Keccak submission and not the
+/// finalized SHA3-256 standard, which uses a different padding.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Computes the SHA-512 hash of the given data.
+pub fn sha512(data: &[u8]) -> [u8; 64] {
+    Sha512::digest(data).into()
+}
+
+/// Computes the BLAKE2b hash of the given data with an output size of 64 bytes.
+pub fn blake2b512(data: &[u8]) -> [u8; 64] {
+    Blake2b512::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hex_literal::hex;
+
+    #[test]
+    fn keccak256_works() {
+        assert_eq!(
+            keccak256(b""),
+            hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(
+            keccak256(b"hello world"),
+            hex!("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad")
+        );
+    }
+
+    #[test]
+    fn sha512_works() {
+        assert_eq!(
+            sha512(b""),
+            hex!("cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e")
+        );
+        assert_eq!(
+            sha512(b"abc"),
+            hex!("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f")
+        );
+    }
+
+    #[test]
+    fn blake2b512_works() {
+        // Test vectors from RFC 7693, Appendix A and the BLAKE2 reference implementation
+        assert_eq!(
+            blake2b512(b""),
+            hex!("786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce")
+        );
+        assert_eq!(
+            blake2b512(b"abc"),
+            hex!("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923")
+        );
+    }
+}
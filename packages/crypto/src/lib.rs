@@ -5,11 +5,25 @@
 #![cfg_attr(feature = "backtraces", feature(error_generic_member_access))]
 #![cfg_attr(feature = "backtraces", feature(provide_any))]
 
+mod bls12_381;
 mod ed25519;
 mod errors;
+mod groth16;
+mod hashes;
 mod identity_digest;
 mod secp256k1;
+mod secp256r1;
+mod sr25519;
 
+#[doc(hidden)]
+pub use crate::bls12_381::{
+    bls12_381_aggregate_g1, bls12_381_aggregate_g2, bls12_381_hash_to_g1, bls12_381_hash_to_g2,
+    bls12_381_pairing_equality, HashFunction,
+};
+#[doc(hidden)]
+pub use crate::bls12_381::{
+    BLS12_381_G1_GENERATOR, BLS12_381_G1_POINT_LEN, BLS12_381_G2_GENERATOR, BLS12_381_G2_POINT_LEN,
+};
 #[doc(hidden)]
 pub use crate::ed25519::EDDSA_PUBKEY_LEN;
 #[doc(hidden)]
@@ -17,6 +31,23 @@ pub use crate::ed25519::{ed25519_batch_verify, ed25519_verify};
 #[doc(hidden)]
 pub use crate::errors::{CryptoError, CryptoResult};
 #[doc(hidden)]
-pub use crate::secp256k1::{secp256k1_recover_pubkey, secp256k1_verify};
+pub use crate::groth16::{groth16_verify_bls12_381, groth16_verify_bn254};
+#[doc(hidden)]
+pub use crate::groth16::{BN254_G1_POINT_LEN, BN254_G2_POINT_LEN, GROTH16_PUBLIC_INPUT_LEN};
+#[doc(hidden)]
+pub use crate::hashes::{blake2b512, keccak256, sha512};
+#[doc(hidden)]
+pub use crate::secp256k1::{
+    secp256k1_batch_verify, secp256k1_point_add, secp256k1_point_mul, secp256k1_recover_pubkey,
+    secp256k1_verify,
+};
+#[doc(hidden)]
+pub use crate::secp256k1::{
+    ECDSA_COMPRESSED_PUBKEY_LEN, ECDSA_PUBKEY_MAX_LEN, ECDSA_SIGNATURE_LEN, MESSAGE_HASH_MAX_LEN,
+};
+#[doc(hidden)]
+pub use crate::secp256r1::{secp256r1_recover_pubkey, secp256r1_verify};
+#[doc(hidden)]
+pub use crate::sr25519::sr25519_verify;
 #[doc(hidden)]
-pub use crate::secp256k1::{ECDSA_PUBKEY_MAX_LEN, ECDSA_SIGNATURE_LEN, MESSAGE_HASH_MAX_LEN};
+pub use crate::sr25519::{SR25519_PUBKEY_LEN, SR25519_SIGNATURE_LEN};
@@ -4,6 +4,11 @@ use k256::{
     ecdsa::signature::{DigestVerifier, Signature as _}, // traits
     ecdsa::{Signature, VerifyingKey},                   // type aliases
     elliptic_curve::sec1::ToEncodedPoint,
+    elliptic_curve::PrimeField,
+    FieldBytes,
+    ProjectivePoint,
+    PublicKey,
+    Scalar,
 };
 
 use crate::errors::{CryptoError, CryptoResult};
@@ -19,7 +24,7 @@ pub const MESSAGE_HASH_MAX_LEN: usize = 32;
 pub const ECDSA_SIGNATURE_LEN: usize = 64;
 
 /// Length of a serialized compressed public key
-const ECDSA_COMPRESSED_PUBKEY_LEN: usize = 33;
+pub const ECDSA_COMPRESSED_PUBKEY_LEN: usize = 33;
 /// Length of a serialized uncompressed public key
 const ECDSA_UNCOMPRESSED_PUBKEY_LEN: usize = 65;
 /// Max length of a serialized public key
@@ -124,8 +129,137 @@ pub fn secp256k1_recover_pubkey(
     Ok(encoded)
 }
 
+/// Performs batch ECDSA secp256k1 signature verification.
+///
+/// The message hashes, signatures and public keys are in the same format as for
+/// [`secp256k1_verify`]. There is no batch verification equation for ECDSA, so the
+/// signatures are still verified one after another. However, all inputs are checked
+/// upfront and each public key is only parsed once, which makes this cheaper than
+/// calling [`secp256k1_verify`] multiple times when the same public key is used.
+///
+/// Three variants are supported in the input for convenience:
+///  - Equal number of message hashes, signatures, and public keys: Standard, generic functionality.
+///  - One message hash, and an equal number of signatures and public keys: Multiple digital signature
+///    (multisig) verification of a single message.
+///  - One public key, and an equal number of message hashes and signatures: Verification of multiple
+///    messages, all signed with the same private key.
+///
+/// Any other variants of input vectors result in an error.
+///
+/// Notes:
+///  - The "one-message, with zero signatures and zero public keys" case, is considered the empty
+///    case.
+///  - The "one-public key, with zero messages and zero signatures" case, is considered the empty
+///    case.
+///  - The empty case (no messages, no signatures and no public keys) returns true.
+pub fn secp256k1_batch_verify(
+    message_hashes: &[&[u8]],
+    signatures: &[&[u8]],
+    public_keys: &[&[u8]],
+) -> CryptoResult<bool> {
+    // Structural checks
+    let messages_len = message_hashes.len();
+    let signatures_len = signatures.len();
+    let public_keys_len = public_keys.len();
+
+    if !(messages_len == signatures_len && messages_len == public_keys_len
+        || messages_len == 1 && signatures_len == public_keys_len
+        || public_keys_len == 1 && messages_len == signatures_len)
+    {
+        return Err(CryptoError::batch_err(
+            "Mismatched / erroneous number of messages / signatures / public keys",
+        ));
+    }
+
+    // Validation
+    let message_hashes = message_hashes
+        .iter()
+        .map(|hash| read_hash(hash))
+        .collect::<Result<Vec<_>, _>>()?;
+    let signatures = signatures
+        .iter()
+        .map(|signature| {
+            let signature = read_signature(signature)?;
+            let mut signature = Signature::from_bytes(&signature)
+                .map_err(|e| CryptoError::generic_err(e.to_string()))?;
+            // See secp256k1_verify for why normalization is needed
+            if let Some(normalized) = signature.normalize_s() {
+                signature = normalized;
+            }
+            Ok(signature)
+        })
+        .collect::<CryptoResult<Vec<_>>>()?;
+    let public_keys = public_keys
+        .iter()
+        .map(|public_key| {
+            check_pubkey(public_key)?;
+            VerifyingKey::from_sec1_bytes(public_key)
+                .map_err(|e| CryptoError::generic_err(e.to_string()))
+        })
+        .collect::<CryptoResult<Vec<_>>>()?;
+
+    // Verification
+    for (i, signature) in signatures.iter().enumerate() {
+        let message_hash = message_hashes[if messages_len == 1 { 0 } else { i }];
+        let public_key = &public_keys[if public_keys_len == 1 { 0 } else { i }];
+        let message_digest = Identity256::new().chain(message_hash);
+        if public_key.verify_digest(message_digest, signature).is_err() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Adds two secp256k1 points, e.g. to combine public keys in adaptor signature or
+/// threshold schemes.
+///
+/// Both points are SEC 1 encoded public keys (33 or 65 bytes). The sum is returned
+/// as a compressed public key (33 bytes). An error is returned if the sum is the
+/// point at infinity, which cannot be encoded as a public key.
+pub fn secp256k1_point_add(p: &[u8], q: &[u8]) -> CryptoResult<[u8; ECDSA_COMPRESSED_PUBKEY_LEN]> {
+    let p = read_point(p)?;
+    let q = read_point(q)?;
+    encode_point(p + q)
+}
+
+/// Multiplies a secp256k1 point with a scalar, e.g. to derive a tweaked public key.
+///
+/// The point is an SEC 1 encoded public key (33 or 65 bytes) and the scalar is a 32 byte
+/// big-endian integer smaller than the group order. The product is returned as a compressed
+/// public key (33 bytes). An error is returned if the product is the point at infinity,
+/// i.e. if the scalar is zero.
+pub fn secp256k1_point_mul(
+    point: &[u8],
+    scalar: &[u8],
+) -> CryptoResult<[u8; ECDSA_COMPRESSED_PUBKEY_LEN]> {
+    let point = read_point(point)?;
+    let scalar = read_scalar(scalar)?;
+    encode_point(point * scalar)
+}
+
+fn read_point(data: &[u8]) -> CryptoResult<ProjectivePoint> {
+    check_pubkey(data)?;
+    let public_key = PublicKey::from_sec1_bytes(data).map_err(|_| CryptoError::invalid_point())?;
+    Ok(public_key.to_projective())
+}
+
+fn read_scalar(data: &[u8]) -> CryptoResult<Scalar> {
+    let bytes: [u8; 32] = data.try_into().map_err(|_| CryptoError::invalid_scalar())?;
+    Option::from(Scalar::from_repr(FieldBytes::from(bytes))).ok_or_else(CryptoError::invalid_scalar)
+}
+
+fn encode_point(point: ProjectivePoint) -> CryptoResult<[u8; ECDSA_COMPRESSED_PUBKEY_LEN]> {
+    let public_key = PublicKey::from_affine(point.to_affine())
+        .map_err(|_| CryptoError::generic_err("Result is the point at infinity"))?;
+    Ok(public_key
+        .to_encoded_point(true)
+        .as_bytes()
+        .try_into()
+        .unwrap())
+}
+
 /// Error raised when hash is not 32 bytes long
-struct InvalidSecp256k1HashFormat;
+pub(crate) struct InvalidSecp256k1HashFormat;
 
 impl From<InvalidSecp256k1HashFormat> for CryptoError {
     fn from(_original: InvalidSecp256k1HashFormat) -> Self {
@@ -133,12 +267,12 @@ impl From<InvalidSecp256k1HashFormat> for CryptoError {
     }
 }
 
-fn read_hash(data: &[u8]) -> Result<[u8; 32], InvalidSecp256k1HashFormat> {
+pub(crate) fn read_hash(data: &[u8]) -> Result<[u8; 32], InvalidSecp256k1HashFormat> {
     data.try_into().map_err(|_| InvalidSecp256k1HashFormat)
 }
 
 /// Error raised when signature is not 64 bytes long (32 bytes r, 32 bytes s)
-struct InvalidSecp256k1SignatureFormat;
+pub(crate) struct InvalidSecp256k1SignatureFormat;
 
 impl From<InvalidSecp256k1SignatureFormat> for CryptoError {
     fn from(_original: InvalidSecp256k1SignatureFormat) -> Self {
@@ -146,14 +280,14 @@ impl From<InvalidSecp256k1SignatureFormat> for CryptoError {
     }
 }
 
-fn read_signature(data: &[u8]) -> Result<[u8; 64], InvalidSecp256k1SignatureFormat> {
+pub(crate) fn read_signature(data: &[u8]) -> Result<[u8; 64], InvalidSecp256k1SignatureFormat> {
     data.try_into().map_err(|_| InvalidSecp256k1SignatureFormat)
 }
 
 /// Error raised when public key is not in one of the two supported formats:
 /// 1. Uncompressed: 65 bytes starting with 0x04
 /// 2. Compressed: 33 bytes starting with 0x02 or 0x03
-struct InvalidSecp256k1PubkeyFormat;
+pub(crate) struct InvalidSecp256k1PubkeyFormat;
 
 impl From<InvalidSecp256k1PubkeyFormat> for CryptoError {
     fn from(_original: InvalidSecp256k1PubkeyFormat) -> Self {
@@ -161,7 +295,7 @@ impl From<InvalidSecp256k1PubkeyFormat> for CryptoError {
     }
 }
 
-fn check_pubkey(data: &[u8]) -> Result<(), InvalidSecp256k1PubkeyFormat> {
+pub(crate) fn check_pubkey(data: &[u8]) -> Result<(), InvalidSecp256k1PubkeyFormat> {
     let ok = match data.first() {
         Some(0x02) | Some(0x03) => data.len() == ECDSA_COMPRESSED_PUBKEY_LEN,
         Some(0x04) => data.len() == ECDSA_UNCOMPRESSED_PUBKEY_LEN,
@@ -424,4 +558,237 @@ mod tests {
             err => panic!("Unexpected error: {}", err),
         }
     }
+
+    fn sign_random(message_hash_input: &str) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let message_digest = Sha256::new().chain(message_hash_input);
+        let message_hash = message_digest.clone().finalize().to_vec();
+        let secret_key = SigningKey::random(&mut OsRng);
+        let signature: Signature = secret_key.sign_digest(message_digest);
+        let public_key = VerifyingKey::from(&secret_key)
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec();
+        (message_hash, signature.as_bytes().to_vec(), public_key)
+    }
+
+    #[test]
+    fn secp256k1_batch_verify_works() {
+        let (hashes, (signatures, public_keys)): (Vec<_>, (Vec<_>, Vec<_>)) = ["a", "b", "c"]
+            .iter()
+            .map(|msg| {
+                let (hash, signature, public_key) = sign_random(msg);
+                (hash, (signature, public_key))
+            })
+            .unzip();
+        let hashes: Vec<&[u8]> = hashes.iter().map(|h| h.as_slice()).collect();
+        let signatures: Vec<&[u8]> = signatures.iter().map(|s| s.as_slice()).collect();
+        let public_keys: Vec<&[u8]> = public_keys.iter().map(|p| p.as_slice()).collect();
+
+        assert!(secp256k1_batch_verify(&hashes, &signatures, &public_keys).unwrap());
+
+        // One invalid signature makes the whole batch fail
+        let mut swapped = signatures.clone();
+        swapped.swap(0, 1);
+        assert!(!secp256k1_batch_verify(&hashes, &swapped, &public_keys).unwrap());
+
+        // Empty case
+        assert!(secp256k1_batch_verify(&[], &[], &[]).unwrap());
+    }
+
+    #[test]
+    fn secp256k1_batch_verify_works_for_one_message_and_one_pubkey() {
+        let message_digest = Sha256::new().chain(MSG);
+        let message_hash = message_digest.clone().finalize();
+
+        // One message, multiple signers
+        let keys: Vec<SigningKey> = (0..3).map(|_| SigningKey::random(&mut OsRng)).collect();
+        let signatures: Vec<Signature> = keys
+            .iter()
+            .map(|key| key.sign_digest(message_digest.clone()))
+            .collect();
+        let public_keys: Vec<_> = keys
+            .iter()
+            .map(|key| VerifyingKey::from(key).to_encoded_point(false))
+            .collect();
+        let signatures: Vec<&[u8]> = signatures.iter().map(|s| s.as_bytes()).collect();
+        let public_keys: Vec<&[u8]> = public_keys.iter().map(|p| p.as_bytes()).collect();
+        assert!(secp256k1_batch_verify(&[&message_hash], &signatures, &public_keys).unwrap());
+        assert!(secp256k1_batch_verify(&[&message_hash], &[], &[]).unwrap());
+
+        // One signer, multiple messages
+        let key = SigningKey::random(&mut OsRng);
+        let digests: Vec<_> = ["a", "b", "c"]
+            .iter()
+            .map(|msg| Sha256::new().chain(msg))
+            .collect();
+        let hashes: Vec<_> = digests.iter().map(|d| d.clone().finalize()).collect();
+        let signatures: Vec<Signature> = digests
+            .into_iter()
+            .map(|digest| key.sign_digest(digest))
+            .collect();
+        let public_key = VerifyingKey::from(&key).to_encoded_point(true);
+        let hashes: Vec<&[u8]> = hashes.iter().map(|h| h.as_slice()).collect();
+        let signatures: Vec<&[u8]> = signatures.iter().map(|s| s.as_bytes()).collect();
+        assert!(secp256k1_batch_verify(&hashes, &signatures, &[public_key.as_bytes()]).unwrap());
+        assert!(secp256k1_batch_verify(&[], &[], &[public_key.as_bytes()]).unwrap());
+    }
+
+    #[test]
+    fn secp256k1_batch_verify_errors_for_invalid_input() {
+        let (hash, signature, public_key) = sign_random(MSG);
+        let (hash2, signature2, public_key2) = sign_random(MSG);
+
+        // Mismatched number of items
+        match secp256k1_batch_verify(
+            &[&hash, &hash2],
+            &[&signature, &signature2],
+            &[&public_key, &public_key2, &public_key],
+        )
+        .unwrap_err()
+        {
+            CryptoError::BatchErr { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+        match secp256k1_batch_verify(&[&hash], &[&signature, &signature2], &[&public_key])
+            .unwrap_err()
+        {
+            CryptoError::BatchErr { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // Invalid formats are reported even if an earlier signature is invalid
+        match secp256k1_batch_verify(
+            &[&hash2, &hash[1..]],
+            &[&signature, &signature2],
+            &[&public_key, &public_key2],
+        )
+        .unwrap_err()
+        {
+            CryptoError::InvalidHashFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+        match secp256k1_batch_verify(&[&hash], &[&signature[1..]], &[&public_key]).unwrap_err() {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+        match secp256k1_batch_verify(&[&hash], &[&signature], &[&public_key[1..]]).unwrap_err() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    /// Public key of the secret key `k`
+    fn pubkey(k: u64, compress: bool) -> Vec<u8> {
+        let mut secret = [0u8; 32];
+        secret[24..].copy_from_slice(&k.to_be_bytes());
+        k256::SecretKey::from_be_bytes(&secret)
+            .unwrap()
+            .public_key()
+            .to_encoded_point(compress)
+            .as_bytes()
+            .to_vec()
+    }
+
+    fn be_scalar(k: u64) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        out[24..].copy_from_slice(&k.to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn secp256k1_point_add_works() {
+        let g = hex!("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        assert_eq!(pubkey(1, true), g);
+
+        let sum = secp256k1_point_add(&pubkey(1, true), &pubkey(2, true)).unwrap();
+        assert_eq!(sum.to_vec(), pubkey(3, true));
+        let sum = secp256k1_point_add(&pubkey(2, true), &pubkey(1, true)).unwrap();
+        assert_eq!(sum.to_vec(), pubkey(3, true));
+
+        // uncompressed inputs are accepted, the output is always compressed
+        let sum = secp256k1_point_add(&pubkey(5, false), &pubkey(7, true)).unwrap();
+        assert_eq!(sum.to_vec(), pubkey(12, true));
+
+        // doubling
+        let sum = secp256k1_point_add(&g, &g).unwrap();
+        assert_eq!(sum.to_vec(), pubkey(2, true));
+    }
+
+    #[test]
+    fn secp256k1_point_add_fails_for_infinity() {
+        // -P has the same x coordinate with the other y parity
+        let p = pubkey(42, true);
+        let mut minus_p = p.clone();
+        minus_p[0] ^= 0x01;
+        match secp256k1_point_add(&p, &minus_p).unwrap_err() {
+            CryptoError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Result is the point at infinity")
+            }
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn secp256k1_point_add_errors_for_invalid_input() {
+        let p = pubkey(1, true);
+
+        // wrong length / prefix
+        let result = secp256k1_point_add(&p[..32], &p);
+        assert!(matches!(
+            result,
+            Err(CryptoError::InvalidPubkeyFormat { .. })
+        ));
+        let result = secp256k1_point_add(&p, &[]);
+        assert!(matches!(
+            result,
+            Err(CryptoError::InvalidPubkeyFormat { .. })
+        ));
+
+        // not on the curve
+        let mut not_on_curve = pubkey(1, false);
+        not_on_curve[64] ^= 0x01;
+        let result = secp256k1_point_add(&p, &not_on_curve);
+        assert!(matches!(result, Err(CryptoError::InvalidPoint { .. })));
+    }
+
+    #[test]
+    fn secp256k1_point_mul_works() {
+        let product = secp256k1_point_mul(&pubkey(1, true), &be_scalar(3)).unwrap();
+        assert_eq!(product.to_vec(), pubkey(3, true));
+
+        let product = secp256k1_point_mul(&pubkey(7, false), &be_scalar(6)).unwrap();
+        assert_eq!(product.to_vec(), pubkey(42, true));
+
+        // n - 1 negates the point
+        let n_minus_one = hex!("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140");
+        let product = secp256k1_point_mul(&pubkey(1, true), &n_minus_one).unwrap();
+        let mut minus_g = pubkey(1, true);
+        minus_g[0] ^= 0x01;
+        assert_eq!(product.to_vec(), minus_g);
+    }
+
+    #[test]
+    fn secp256k1_point_mul_errors_for_invalid_input() {
+        let p = pubkey(1, true);
+
+        // zero scalar results in the point at infinity
+        let result = secp256k1_point_mul(&p, &be_scalar(0));
+        assert!(matches!(result, Err(CryptoError::GenericErr { .. })));
+
+        // scalar not smaller than the group order
+        let n = hex!("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141");
+        let result = secp256k1_point_mul(&p, &n);
+        assert!(matches!(result, Err(CryptoError::InvalidScalar { .. })));
+
+        // wrong scalar length
+        let result = secp256k1_point_mul(&p, &be_scalar(3)[1..]);
+        assert!(matches!(result, Err(CryptoError::InvalidScalar { .. })));
+
+        // invalid point
+        let result = secp256k1_point_mul(&p[1..], &be_scalar(3));
+        assert!(matches!(
+            result,
+            Err(CryptoError::InvalidPubkeyFormat { .. })
+        ));
+    }
 }
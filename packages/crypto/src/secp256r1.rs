@@ -0,0 +1,246 @@
+use digest::{Digest, Update}; // trait
+use p256::{
+    ecdsa::signature::{DigestVerifier, Signature as _}, // traits
+    ecdsa::{Signature, VerifyingKey},                   // type aliases
+    elliptic_curve::bigint::U256,
+    elliptic_curve::ops::Reduce,
+    elliptic_curve::subtle::Choice,
+    elliptic_curve::DecompressPoint,
+    AffinePoint,
+    FieldBytes,
+    ProjectivePoint,
+    Scalar,
+};
+
+use crate::errors::{CryptoError, CryptoResult};
+use crate::identity_digest::Identity256;
+use crate::secp256k1::{check_pubkey, read_hash, read_signature};
+
+/// ECDSA secp256r1 (P-256) implementation.
+///
+/// This function verifies message hashes (typically, hashed unsing SHA-256) against a signature,
+/// with the public key of the signer, using the secp256r1 elliptic curve digital signature
+/// parametrization / algorithm. This is the curve used by WebAuthn / passkeys.
+///
+/// The signature and public key are in the same format as for [`secp256k1_verify`](crate::secp256k1_verify):
+/// - signature:  Serialized "compact" signature (64 bytes).
+/// - public key: Serialized according to SEC 2 (33 or 65 bytes).
+///
+/// This implementation accepts both high-S and low-S signatures. If signature
+/// malleability is a concern for your protocol, the signature needs
+/// to be tested for low-S in addition to this verification.
+pub fn secp256r1_verify(
+    message_hash: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> CryptoResult<bool> {
+    let message_hash = read_hash(message_hash)?;
+    let signature = read_signature(signature)?;
+    check_pubkey(public_key)?;
+
+    // Already hashed, just build Digest container
+    let message_digest = Identity256::new().chain(message_hash);
+
+    let signature =
+        Signature::from_bytes(&signature).map_err(|e| CryptoError::generic_err(e.to_string()))?;
+
+    let public_key = VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|e| CryptoError::generic_err(e.to_string()))?;
+
+    match public_key.verify_digest(message_digest, &signature) {
+        Ok(()) => Ok(true),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Recovers a secp256r1 public key from a message hash and a signature.
+///
+/// `recovery_param` must be 0 or 1 and encodes the parity of the y coordinate of
+/// the signature's curve point R. The values 2 and 3 (for the case that the x coordinate of
+/// R is larger than the curve order) are unsupported, just like for secp256k1.
+///
+/// Returns the recovered pubkey in uncompressed form, which can be used
+/// in secp256r1_verify directly.
+///
+/// This implementation accepts both high-S and low-S signatures.
+pub fn secp256r1_recover_pubkey(
+    message_hash: &[u8],
+    signature: &[u8],
+    recovery_param: u8,
+) -> Result<Vec<u8>, CryptoError> {
+    let message_hash = read_hash(message_hash)?;
+    let signature = read_signature(signature)?;
+    if recovery_param > 1 {
+        return Err(CryptoError::invalid_recovery_param());
+    }
+
+    let signature =
+        Signature::from_bytes(&signature).map_err(|e| CryptoError::generic_err(e.to_string()))?;
+    let (r, s) = signature.split_scalars();
+
+    // The curve point R has the x coordinate r and the y parity given by the recovery param
+    let big_r: Option<AffinePoint> =
+        AffinePoint::decompress(&r.to_bytes(), Choice::from(recovery_param)).into();
+    let big_r = big_r.ok_or_else(|| CryptoError::generic_err("Invalid signature point"))?;
+
+    // Q = r⁻¹ (sR - zG)
+    let z = <Scalar as Reduce<U256>>::from_be_bytes_reduced(FieldBytes::from(message_hash));
+    // r is non-zero, so it can always be inverted
+    let r_inv = r.invert().unwrap();
+    let u1 = -(r_inv * z);
+    let u2 = r_inv * *s;
+    let pubkey = (ProjectivePoint::GENERATOR * u1 + ProjectivePoint::from(big_r) * u2).to_affine();
+    let pubkey =
+        VerifyingKey::from_affine(pubkey).map_err(|e| CryptoError::generic_err(e.to_string()))?;
+
+    let encoded: Vec<u8> = pubkey.to_encoded_point(false).as_bytes().into();
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hex_literal::hex;
+    use p256::{
+        ecdsa::signature::DigestSigner, // trait
+        ecdsa::SigningKey,              // type alias
+        elliptic_curve::rand_core::OsRng,
+    };
+    use sha2::Sha256;
+
+    // For generic signature verification
+    const MSG: &str = "Hello World!";
+
+    // Test vector from RFC 6979, A.2.5 (P-256, SHA-256, message "sample").
+    // The signature is high-S.
+    const RFC6979_PUBKEY: [u8; 65] = hex!("0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299");
+    const RFC6979_SIGNATURE: [u8; 64] = hex!("efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8");
+    const RFC6979_MSG: &str = "sample";
+
+    #[test]
+    fn test_secp256r1_verify() {
+        // Explicit / external hashing
+        let message_digest = Sha256::new().chain(MSG);
+        let message_hash = message_digest.clone().finalize();
+
+        // Signing
+        let secret_key = SigningKey::random(&mut OsRng);
+        let signature: Signature = secret_key.sign_digest(message_digest);
+
+        let public_key = VerifyingKey::from(&secret_key);
+
+        // Verification (uncompressed public key)
+        assert!(secp256r1_verify(
+            &message_hash,
+            signature.as_bytes(),
+            public_key.to_encoded_point(false).as_bytes()
+        )
+        .unwrap());
+
+        // Verification (compressed public key)
+        assert!(secp256r1_verify(
+            &message_hash,
+            signature.as_bytes(),
+            public_key.to_encoded_point(true).as_bytes()
+        )
+        .unwrap());
+
+        // Wrong message fails
+        let bad_message_hash = Sha256::new().chain(MSG).chain("\0").finalize();
+        assert!(!secp256r1_verify(
+            &bad_message_hash,
+            signature.as_bytes(),
+            public_key.to_encoded_point(false).as_bytes()
+        )
+        .unwrap());
+
+        // Other pubkey fails
+        let other_secret_key = SigningKey::random(&mut OsRng);
+        let other_public_key = VerifyingKey::from(&other_secret_key);
+        assert!(!secp256r1_verify(
+            &message_hash,
+            signature.as_bytes(),
+            other_public_key.to_encoded_point(false).as_bytes()
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn test_secp256r1_verify_rfc6979() {
+        let message_hash = Sha256::digest(RFC6979_MSG);
+        assert!(secp256r1_verify(&message_hash, &RFC6979_SIGNATURE, &RFC6979_PUBKEY).unwrap());
+
+        // secp256k1 does not verify the same signature
+        assert!(matches!(
+            crate::secp256k1_verify(&message_hash, &RFC6979_SIGNATURE, &RFC6979_PUBKEY),
+            Ok(false) | Err(_)
+        ));
+    }
+
+    #[test]
+    fn test_secp256r1_verify_fails_for_invalid_input() {
+        let message_hash = Sha256::digest(RFC6979_MSG);
+
+        match secp256r1_verify(&message_hash[1..], &RFC6979_SIGNATURE, &RFC6979_PUBKEY).unwrap_err()
+        {
+            CryptoError::InvalidHashFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+        match secp256r1_verify(&message_hash, &RFC6979_SIGNATURE[1..], &RFC6979_PUBKEY).unwrap_err()
+        {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+        match secp256r1_verify(&message_hash, &RFC6979_SIGNATURE, &RFC6979_PUBKEY[1..]).unwrap_err()
+        {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn secp256r1_recover_pubkey_works() {
+        // RFC 6979 test vector
+        let message_hash = Sha256::digest(RFC6979_MSG);
+        let recovered: Vec<Vec<u8>> = [0, 1]
+            .iter()
+            .map(|param| secp256r1_recover_pubkey(&message_hash, &RFC6979_SIGNATURE, *param))
+            .filter_map(Result::ok)
+            .collect();
+        assert!(recovered.contains(&RFC6979_PUBKEY.to_vec()));
+
+        // Random keys
+        for _ in 0..10 {
+            let message_digest = Sha256::new().chain(MSG);
+            let message_hash = message_digest.clone().finalize();
+            let secret_key = SigningKey::random(&mut OsRng);
+            let signature: Signature = secret_key.sign_digest(message_digest);
+            let public_key = VerifyingKey::from(&secret_key).to_encoded_point(false);
+
+            let matches = [0, 1]
+                .iter()
+                .filter(|param| {
+                    secp256r1_recover_pubkey(&message_hash, signature.as_bytes(), **param)
+                        .map(|pubkey| pubkey == public_key.as_bytes())
+                        .unwrap_or(false)
+                })
+                .count();
+            assert_eq!(matches, 1);
+        }
+    }
+
+    #[test]
+    fn secp256r1_recover_pubkey_fails_for_invalid_recovery_param() {
+        let message_hash = Sha256::digest(RFC6979_MSG);
+
+        for recovery_param in [2, 3, 4, 255] {
+            match secp256r1_recover_pubkey(&message_hash, &RFC6979_SIGNATURE, recovery_param)
+                .unwrap_err()
+            {
+                CryptoError::InvalidRecoveryParam { .. } => {}
+                err => panic!("Unexpected error: {}", err),
+            }
+        }
+    }
+}
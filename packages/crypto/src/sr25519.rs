@@ -0,0 +1,136 @@
+use schnorrkel::{PublicKey, Signature};
+
+use crate::errors::{CryptoError, CryptoResult};
+
+/// Length of a serialized public key
+pub const SR25519_PUBKEY_LEN: usize = 32;
+
+/// Length of a serialized signature
+pub const SR25519_SIGNATURE_LEN: usize = 64;
+
+/// The signing context used by Substrate based chains such as Polkadot and Kusama
+const SIGNING_CONTEXT: &[u8] = b"substrate";
+
+/// Schnorrkel sr25519 implementation.
+///
+/// This function verifies messages against a signature, with the public key of the signer,
+/// using Schnorr signatures over the Ristretto group of Curve25519.
+///
+/// The signature and public key are in [Substrate](https://docs.substrate.io/) format,
+/// signed with the `substrate` signing context:
+/// - signature: raw sr25519 signature (64 bytes), with the schnorrkel marker bit set.
+/// - public key: raw sr25519 public key (32 bytes), a compressed Ristretto point.
+pub fn sr25519_verify(message: &[u8], signature: &[u8], public_key: &[u8]) -> CryptoResult<bool> {
+    // Validation
+    let signature = read_signature(signature)?;
+    let pubkey = read_pubkey(public_key)?;
+
+    // Verification
+    match pubkey.verify_simple(SIGNING_CONTEXT, message, &signature) {
+        Ok(()) => Ok(true),
+        Err(_) => Ok(false),
+    }
+}
+
+fn read_signature(data: &[u8]) -> CryptoResult<Signature> {
+    if data.len() != SR25519_SIGNATURE_LEN {
+        return Err(CryptoError::invalid_signature_format());
+    }
+    Signature::from_bytes(data).map_err(|_| CryptoError::invalid_signature_format())
+}
+
+fn read_pubkey(data: &[u8]) -> CryptoResult<PublicKey> {
+    if data.len() != SR25519_PUBKEY_LEN {
+        return Err(CryptoError::invalid_pubkey_format());
+    }
+    PublicKey::from_bytes(data).map_err(|_| CryptoError::invalid_pubkey_format())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use schnorrkel::{ExpansionMode, MiniSecretKey};
+
+    // Generated with the mini secret key 0x4242…42 expanded in ed25519 mode
+    const MSG: &[u8] = b"CosmWasm sr25519 test message";
+    const PUBLIC_KEY: [u8; 32] =
+        hex!("a4d007a95b28ce117e4051904503612abdcbd22b60475121f92a8b5dedac375a");
+    const SIGNATURE: [u8; 64] = hex!("ee5ec89ebe97fc4c5f5c3d2ddc4923fd647f287aa06f7c8739b60be2a206e21b1e2bc01f22e13cf04ec92ff40aeb9bf4964c2cf11572159f1bdd346274476585");
+
+    #[test]
+    fn sr25519_verify_works() {
+        assert!(sr25519_verify(MSG, &SIGNATURE, &PUBLIC_KEY).unwrap());
+
+        // Wrong message fails
+        let bad_message = [MSG, b"\0"].concat();
+        assert!(!sr25519_verify(&bad_message, &SIGNATURE, &PUBLIC_KEY).unwrap());
+
+        // Other pubkey fails
+        let other_public_key = MiniSecretKey::from_bytes(&[0x43; 32])
+            .unwrap()
+            .expand_to_public(ExpansionMode::Ed25519)
+            .to_bytes();
+        assert!(!sr25519_verify(MSG, &SIGNATURE, &other_public_key).unwrap());
+    }
+
+    #[test]
+    fn sr25519_verify_works_for_fresh_signatures() {
+        let keypair = MiniSecretKey::from_bytes(&[0x01; 32])
+            .unwrap()
+            .expand_to_keypair(ExpansionMode::Ed25519);
+        let signature = keypair.sign_simple(SIGNING_CONTEXT, b"Hello World!");
+
+        assert!(sr25519_verify(
+            b"Hello World!",
+            &signature.to_bytes(),
+            &keypair.public.to_bytes()
+        )
+        .unwrap());
+
+        // Signatures of other contexts are not valid
+        let signature = keypair.sign_simple(b"polkadot", b"Hello World!");
+        assert!(!sr25519_verify(
+            b"Hello World!",
+            &signature.to_bytes(),
+            &keypair.public.to_bytes()
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn sr25519_verify_fails_for_malformed_input() {
+        // Wrong lengths
+        let result = sr25519_verify(MSG, &SIGNATURE[..63], &PUBLIC_KEY);
+        assert!(matches!(
+            result,
+            Err(CryptoError::InvalidSignatureFormat { .. })
+        ));
+        let result = sr25519_verify(MSG, &SIGNATURE, &PUBLIC_KEY[..31]);
+        assert!(matches!(
+            result,
+            Err(CryptoError::InvalidPubkeyFormat { .. })
+        ));
+        let result = sr25519_verify(MSG, &SIGNATURE, &[]);
+        assert!(matches!(
+            result,
+            Err(CryptoError::InvalidPubkeyFormat { .. })
+        ));
+
+        // Missing schnorrkel marker bit, e.g. an ed25519 signature
+        let mut signature = SIGNATURE;
+        signature[63] &= 0x7f;
+        let result = sr25519_verify(MSG, &signature, &PUBLIC_KEY);
+        assert!(matches!(
+            result,
+            Err(CryptoError::InvalidSignatureFormat { .. })
+        ));
+
+        // Not a valid Ristretto point
+        let result = sr25519_verify(MSG, &SIGNATURE, &[0xff; 32]);
+        assert!(matches!(
+            result,
+            Err(CryptoError::InvalidPubkeyFormat { .. })
+        ));
+    }
+}
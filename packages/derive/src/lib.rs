@@ -51,6 +51,42 @@ use std::str::FromStr;
 ///
 /// where `InstantiateMsg`, `ExecuteMsg`, and `QueryMsg` are contract defined
 /// types that implement `DeserializeOwned + JsonSchema`.
+///
+/// The `reply` entry point can take a `Reply<JsonPayload<P>>` with a contract defined payload
+/// type `P` instead of a `Reply`. Then the payload set in `SubMsg::with_payload` is deserialized
+/// from JSON into `P`, and the call fails with an error if this does not work:
+/// ```
+/// # use cosmwasm_std::{entry_point, DepsMut, Env, JsonPayload, Reply, Response, StdError};
+/// // The payload is set as `to_binary(&(recipient, amount))?`
+/// type Payload = (String, u64);
+///
+/// #[entry_point]
+/// pub fn reply(
+///     deps: DepsMut,
+///     env: Env,
+///     msg: Reply<JsonPayload<Payload>>,
+/// ) -> Result<Response, StdError> {
+///     let JsonPayload((recipient, amount)) = msg.payload;
+/// #   Ok(Default::default())
+/// }
+/// ```
+///
+/// The `migrate` entry point can take a `MigrateInfo` as fourth argument to get e.g. the
+/// migrate version of the code the contract is migrated from. This requires the `cosmwasm_1_5`
+/// feature of cosmwasm-std, which makes the contract require the `cosmwasm_1_5` capability:
+/// ```
+/// # use cosmwasm_std::{entry_point, DepsMut, Env, MigrateInfo, Response, StdError};
+/// # type MigrateMsg = ();
+/// #[entry_point]
+/// pub fn migrate(
+///     deps: DepsMut,
+///     env: Env,
+///     msg: MigrateMsg,
+///     info: MigrateInfo,
+/// ) -> Result<Response, StdError> {
+/// #   Ok(Default::default())
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     let cloned = item.clone();
@@ -58,6 +94,11 @@ pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     let name = function.sig.ident.to_string();
     // The first argument is `deps`, the rest is region pointers
     let args = function.sig.inputs.len() - 1;
+    let export = if name == "migrate" && function.sig.inputs.len() == 4 {
+        "do_migrate_with_info".to_string()
+    } else {
+        format!("do_{}", name)
+    };
 
     // E.g. "ptr0: u32, ptr1: u32, ptr2: u32, "
     let typed_ptrs = (0..args).fold(String::new(), |acc, i| format!("{}ptr{}: u32, ", acc, i));
@@ -70,11 +111,12 @@ pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
         mod __wasm_export_{name} {{ // new module to avoid conflict of function name
             #[no_mangle]
             extern "C" fn {name}({typed_ptrs}) -> u32 {{
-                cosmwasm_std::do_{name}(&super::{name}, {ptrs})
+                cosmwasm_std::{export}(&super::{name}, {ptrs})
             }}
         }}
     "##,
         name = name,
+        export = export,
         typed_ptrs = typed_ptrs,
         ptrs = ptrs
     );
@@ -82,3 +124,231 @@ pub fn entry_point(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     item.extend(entry);
     item
 }
+
+/// This derive macro implements `cosmwasm_std::ContractEvent` for a struct with named fields.
+///
+/// Every field becomes an attribute with the field name as key. Values are converted using
+/// `ToString` and parsed back using `FromStr`. Fields of type `Option<T>` are only emitted
+/// if they are set. The event type defaults to the struct name in snake case.
+///
+/// ```
+/// # use cosmwasm_std::{ContractEvent, Event};
+/// #[derive(ContractEvent)]
+/// #[event(name = "mint")]
+/// struct MintEvent {
+///     minter: String,
+///     amount: u128,
+/// }
+///
+/// let event = MintEvent { minter: "alice".to_string(), amount: 7 }.to_event();
+/// assert_eq!(event, Event::new("mint").add_attributes([("minter", "alice"), ("amount", "7")]));
+/// ```
+///
+/// Attribute keys starting with an underscore are reserved and rejected:
+///
+/// ```compile_fail
+/// # use cosmwasm_std::ContractEvent;
+/// #[derive(ContractEvent)]
+/// struct Transfer {
+///     _amount: u128,
+/// }
+/// ```
+#[proc_macro_derive(ContractEvent, attributes(event))]
+pub fn contract_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match contract_event_impl(&input) {
+        Ok(code) => TokenStream::from_str(&code).unwrap(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn contract_event_impl(input: &syn::DeriveInput) -> syn::Result<String> {
+    let name = input.ident.to_string();
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "ContractEvent cannot be derived for generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "ContractEvent can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut event_type = to_snake_case(&name);
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("event"))
+    {
+        event_type = parse_event_name(attr)?;
+    }
+
+    // E.g. "event = event.add_attribute("amount", self.amount.to_string());"
+    let mut to_event = String::new();
+    // E.g. "amount: cosmwasm_std::parse_event_attribute(event, "Transfer", "amount")?,"
+    let mut from_event = String::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap().to_string();
+        let key = ident.trim_start_matches("r#");
+        if key.starts_with('_') {
+            return Err(syn::Error::new_spanned(
+                &field.ident,
+                "attribute keys starting with an underscore are reserved",
+            ));
+        }
+        if is_option(&field.ty) {
+            to_event.push_str(&format!(
+                r#"if let Some(value) = &self.{ident} {{
+                    event = event.add_attribute("{key}", value.to_string());
+                }}"#,
+                ident = ident,
+                key = key
+            ));
+            from_event.push_str(&format!(
+                r#"{ident}: cosmwasm_std::parse_optional_event_attribute(event, "{name}", "{key}")?,"#,
+                ident = ident,
+                name = name,
+                key = key
+            ));
+        } else {
+            to_event.push_str(&format!(
+                r#"event = event.add_attribute("{key}", self.{ident}.to_string());"#,
+                ident = ident,
+                key = key
+            ));
+            from_event.push_str(&format!(
+                r#"{ident}: cosmwasm_std::parse_event_attribute(event, "{name}", "{key}")?,"#,
+                ident = ident,
+                name = name,
+                key = key
+            ));
+        }
+    }
+
+    Ok(format!(
+        r##"
+        impl cosmwasm_std::ContractEvent for {name} {{
+            const EVENT_TYPE: &'static str = {event_type:?};
+
+            #[allow(unused_mut)]
+            fn to_event(&self) -> cosmwasm_std::Event {{
+                let mut event = cosmwasm_std::Event::new(Self::EVENT_TYPE);
+                {to_event}
+                event
+            }}
+
+            fn from_event(event: &cosmwasm_std::Event) -> cosmwasm_std::StdResult<Self> {{
+                cosmwasm_std::check_event_type(event, "{name}", Self::EVENT_TYPE)?;
+                Ok(Self {{ {from_event} }})
+            }}
+        }}
+    "##,
+        name = name,
+        event_type = event_type,
+        to_event = to_event,
+        from_event = from_event
+    ))
+}
+
+/// Parses `#[event(name = "...")]`
+fn parse_event_name(attr: &syn::Attribute) -> syn::Result<String> {
+    if let syn::Meta::List(list) = attr.parse_meta()? {
+        if let [syn::NestedMeta::Meta(syn::Meta::NameValue(name_value))] =
+            list.nested.iter().collect::<Vec<_>>()[..]
+        {
+            if let (true, syn::Lit::Str(name)) = (name_value.path.is_ident("name"), &name_value.lit)
+            {
+                if name.value().trim().is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        name,
+                        "event name must not be empty",
+                    ));
+                }
+                return Ok(name.value());
+            }
+        }
+    }
+    Err(syn::Error::new_spanned(
+        attr,
+        r#"expected `#[event(name = "...")]`"#,
+    ))
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => matches!(
+            path.path.segments.last(),
+            Some(segment) if segment.ident == "Option"
+        ),
+        _ => false,
+    }
+}
+
+/// Converts e.g. `TokenTransfer` to `token_transfer`
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_snake_case_works() {
+        assert_eq!(to_snake_case("Transfer"), "transfer");
+        assert_eq!(to_snake_case("TokenTransfer"), "token_transfer");
+        assert_eq!(to_snake_case("already_snake"), "already_snake");
+    }
+
+    #[test]
+    fn contract_event_impl_rejects_reserved_keys() {
+        let input: syn::DeriveInput = parse_quote! {
+            struct Transfer {
+                _amount: u128,
+            }
+        };
+        let err = contract_event_impl(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "attribute keys starting with an underscore are reserved"
+        );
+    }
+
+    #[test]
+    fn contract_event_impl_uses_custom_name() {
+        let input: syn::DeriveInput = parse_quote! {
+            #[event(name = "mint")]
+            struct MintEvent {
+                amount: u128,
+            }
+        };
+        let code = contract_event_impl(&input).unwrap();
+        assert!(code.contains(r#"const EVENT_TYPE: &'static str = "mint";"#));
+
+        let input: syn::DeriveInput = parse_quote! {
+            #[event(title = "mint")]
+            struct MintEvent {}
+        };
+        contract_event_impl(&input).unwrap_err();
+    }
+}
@@ -10,6 +10,15 @@ use syn::{
 pub fn write_api_impl(input: Options) -> Block {
     let api_object = generate_api_impl(&input);
     let name = input.name;
+    let typescript = if input.typescript {
+        quote! {
+            let path = out_dir.join(concat!(#name, ".d.ts"));
+            write(&path, api.to_typescript().unwrap()).unwrap();
+            println!("Exported TypeScript types as {}", path.to_str().unwrap());
+        }
+    } else {
+        quote! {}
+    };
 
     parse_quote! {
         {
@@ -43,6 +52,8 @@ pub fn write_api_impl(input: Options) -> Block {
                 write(&path, json + "\n").unwrap();
                 println!("Exported {}", path.to_str().unwrap());
             }
+
+            #typescript
         }
     }
 }
@@ -57,6 +68,7 @@ pub fn generate_api_impl(input: &Options) -> ExprStruct {
         migrate,
         sudo,
         responses,
+        typescript: _,
     } = input;
 
     parse_quote! {
@@ -77,6 +89,7 @@ pub fn generate_api_impl(input: &Options) -> ExprStruct {
 enum Value {
     Type(syn::Path),
     Str(syn::LitStr),
+    Bool(syn::LitBool),
 }
 
 impl Value {
@@ -95,11 +108,21 @@ impl Value {
             panic!("expected a string literal");
         }
     }
+
+    fn unwrap_bool(self) -> bool {
+        if let Self::Bool(b) = self {
+            b.value
+        } else {
+            panic!("expected a boolean literal");
+        }
+    }
 }
 
 impl Parse for Value {
     fn parse(input: ParseStream) -> syn::parse::Result<Self> {
-        if let Ok(p) = input.parse::<syn::Path>() {
+        if input.peek(syn::LitBool) {
+            Ok(Self::Bool(input.parse::<syn::LitBool>()?))
+        } else if let Ok(p) = input.parse::<syn::Path>() {
             Ok(Self::Type(p))
         } else {
             Ok(Self::Str(input.parse::<syn::LitStr>()?))
@@ -130,6 +153,7 @@ pub struct Options {
     migrate: TokenStream,
     sudo: TokenStream,
     responses: TokenStream,
+    typescript: bool,
 }
 
 impl Parse for Options {
@@ -199,6 +223,11 @@ impl Parse for Options {
             None => quote! { None },
         };
 
+        let typescript = map
+            .remove(&parse_quote!(typescript))
+            .map(Value::unwrap_bool)
+            .unwrap_or(false);
+
         if let Some((invalid_option, _)) = map.into_iter().next() {
             panic!("unknown generate_api option: {}", invalid_option);
         }
@@ -212,6 +241,7 @@ impl Parse for Options {
             migrate,
             sudo,
             responses,
+            typescript,
         })
     }
 }
@@ -289,6 +319,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn typescript_option() {
+        let options: Options = parse_quote! {
+            instantiate: InstantiateMsg,
+        };
+        assert!(!options.typescript);
+
+        let options: Options = parse_quote! {
+            instantiate: InstantiateMsg,
+            typescript: true,
+        };
+        assert!(options.typescript);
+
+        let options: Options = parse_quote! {
+            instantiate: InstantiateMsg,
+            typescript: false,
+        };
+        assert!(!options.typescript);
+    }
+
     #[test]
     #[should_panic(expected = "unknown generate_api option: asd")]
     fn invalid_option() {
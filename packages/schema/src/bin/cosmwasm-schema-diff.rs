@@ -0,0 +1,81 @@
+//! Compares two versions of a contract's schema and reports breaking changes.
+//!
+//! Usage: `cosmwasm-schema-diff <old> <new>`
+//!
+//! Both files are either IDL files written by `write_api!` (`schema/<contract>.json`) or
+//! single schemas like `schema/raw/execute.json`. Files named `response_to_*.json` are compared
+//! as query responses. The exit code is 0 if there are no breaking changes, 1 if there are
+//! breaking changes and 2 if the files could not be compared.
+
+use std::fs;
+use std::path::Path;
+use std::process::exit;
+
+use cosmwasm_schema::schemars::schema::RootSchema;
+use cosmwasm_schema::{diff_api, diff_schemas, SchemaChange, SchemaUsage};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (old_path, new_path) = match args.as_slice() {
+        [old, new] => (old, new),
+        _ => {
+            eprintln!("Usage: cosmwasm-schema-diff <old> <new>");
+            exit(2);
+        }
+    };
+
+    let changes = compare(Path::new(old_path), Path::new(new_path)).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        exit(2);
+    });
+
+    if changes.is_empty() {
+        println!("No changes");
+        return;
+    }
+    for change in &changes {
+        println!("{}", change);
+    }
+    let breaking = changes.iter().filter(|change| change.breaking).count();
+    println!("{} changes, {} of them breaking", changes.len(), breaking);
+    if breaking > 0 {
+        exit(1);
+    }
+}
+
+fn compare(old_path: &Path, new_path: &Path) -> Result<Vec<SchemaChange>, String> {
+    let old = read(old_path)?;
+    let new = read(new_path)?;
+
+    if is_idl(&old) && is_idl(&new) {
+        return diff_api(&old, &new).map_err(|err| err.to_string());
+    }
+
+    let old_schema: RootSchema = parse(old_path, &old)?;
+    let new_schema: RootSchema = parse(new_path, &new)?;
+    let name = new_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let usage = if name.starts_with("response_to_") {
+        SchemaUsage::Response
+    } else {
+        SchemaUsage::Message
+    };
+    Ok(diff_schemas(&name, &old_schema, &new_schema, usage))
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path.display(), err))
+}
+
+fn parse(path: &Path, json: &str) -> Result<RootSchema, String> {
+    serde_json::from_str(json).map_err(|err| format!("Invalid schema {}: {}", path.display(), err))
+}
+
+/// IDL files are the only schema files with an `idl_version` field
+fn is_idl(json: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(json)
+        .map(|value| value.get("idl_version").is_some())
+        .unwrap_or(false)
+}
@@ -9,6 +9,20 @@ pub fn to_snake_case(name: &str) -> String {
     out
 }
 
+/// Converts snake_case (or any other `_` separated name) to PascalCase,
+/// e.g. "balance_response" to "BalanceResponse".
+pub fn to_pascal_case(name: &str) -> String {
+    let mut out = String::new();
+    for part in name.split('_') {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.extend(chars);
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,4 +41,14 @@ mod tests {
         assert_eq!(to_snake_case("FooBar"), "foo_bar");
         assert_eq!(to_snake_case("ABC"), "a_b_c");
     }
+
+    #[test]
+    fn to_pascal_case_works() {
+        assert_eq!(to_pascal_case(""), "");
+        assert_eq!(to_pascal_case("a"), "A");
+        assert_eq!(to_pascal_case("foo_bar"), "FooBar");
+        assert_eq!(to_pascal_case("FooBar"), "FooBar");
+        assert_eq!(to_pascal_case("Response_for_Empty"), "ResponseForEmpty");
+        assert_eq!(to_pascal_case("a__b_"), "AB");
+    }
 }
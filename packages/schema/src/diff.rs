@@ -0,0 +1,443 @@
+//! Detection of breaking changes between two versions of a contract's API
+//!
+//! This allows gating contract migrations in CI, such that clients written against
+//! the old schema keep working with the new contract.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DiffError {
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(#[from] serde_json::Error),
+}
+
+/// How a schema is used, which determines what is a breaking change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaUsage {
+    /// The schema of a message sent to the contract. Everything valid for the old schema
+    /// must be valid for the new one, e.g. fields must not be removed or become required.
+    Message,
+    /// The schema of a query response. Everything valid for the new schema must be
+    /// valid for the old one, e.g. fields must not be removed or become optional.
+    Response,
+}
+
+/// A difference between two versions of a schema
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaChange {
+    /// The location of the change, e.g. `execute.mint.amount`
+    pub path: String,
+    /// True if clients of the old schema can break because of the change
+    pub breaking: bool,
+    pub description: String,
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.breaking {
+            "breaking"
+        } else {
+            "compatible"
+        };
+        write!(f, "{} ({}): {}", self.path, kind, self.description)
+    }
+}
+
+/// The schemas of the IDL file written by `write_api!`
+#[derive(Deserialize)]
+struct IdlSchemas {
+    instantiate: RootSchema,
+    execute: Option<RootSchema>,
+    query: Option<RootSchema>,
+    migrate: Option<RootSchema>,
+    sudo: Option<RootSchema>,
+    responses: Option<BTreeMap<String, RootSchema>>,
+}
+
+/// Compares two IDL files as written by `write_api!` (e.g. `schema/<contract>.json`)
+/// and returns all changes of the messages and query responses.
+///
+/// Responses are only compared for queries that exist in both versions, since removing
+/// a query is reported as a change of the query message already.
+pub fn diff_api(old_json: &str, new_json: &str) -> Result<Vec<SchemaChange>, DiffError> {
+    let old: IdlSchemas = serde_json::from_str(old_json)?;
+    let new: IdlSchemas = serde_json::from_str(new_json)?;
+
+    let mut changes = diff_schemas(
+        "instantiate",
+        &old.instantiate,
+        &new.instantiate,
+        SchemaUsage::Message,
+    );
+    for (name, old_schema, new_schema) in [
+        ("execute", &old.execute, &new.execute),
+        ("query", &old.query, &new.query),
+        ("migrate", &old.migrate, &new.migrate),
+        ("sudo", &old.sudo, &new.sudo),
+    ] {
+        match (old_schema, new_schema) {
+            (Some(old_schema), Some(new_schema)) => changes.extend(diff_schemas(
+                name,
+                old_schema,
+                new_schema,
+                SchemaUsage::Message,
+            )),
+            (Some(_), None) => changes.push(change(name, true, "message was removed")),
+            (None, Some(_)) => changes.push(change(name, false, "message was added")),
+            (None, None) => {}
+        }
+    }
+
+    let no_responses = BTreeMap::new();
+    let old_responses = old.responses.as_ref().unwrap_or(&no_responses);
+    let new_responses = new.responses.as_ref().unwrap_or(&no_responses);
+    for (query, old_response) in old_responses {
+        if let Some(new_response) = new_responses.get(query) {
+            changes.extend(diff_schemas(
+                &format!("responses.{}", query),
+                old_response,
+                new_response,
+                SchemaUsage::Response,
+            ));
+        }
+    }
+    Ok(changes)
+}
+
+/// Compares two versions of a single schema, e.g. two `raw/execute.json` files.
+/// `path` is the prefix of the paths of all changes.
+pub fn diff_schemas(
+    path: &str,
+    old: &RootSchema,
+    new: &RootSchema,
+    usage: SchemaUsage,
+) -> Vec<SchemaChange> {
+    let mut differ = Differ {
+        old,
+        new,
+        usage,
+        visited: HashSet::new(),
+        changes: Vec::new(),
+    };
+    differ.diff(
+        path,
+        &Schema::Object(old.schema.clone()),
+        &Schema::Object(new.schema.clone()),
+    );
+    differ.changes
+}
+
+fn change(path: &str, breaking: bool, description: impl Into<String>) -> SchemaChange {
+    SchemaChange {
+        path: path.to_string(),
+        breaking,
+        description: description.into(),
+    }
+}
+
+/// The structure of a schema, which is what gets compared
+enum Shape<'a> {
+    /// An object with a fixed set of properties. The bool is true for required properties.
+    Struct(BTreeMap<&'a str, (&'a Schema, bool)>),
+    /// An enum. Unit variants have no schema.
+    Variants(BTreeMap<String, Option<&'a Schema>>),
+    Nullable(Schema),
+    Array(&'a Schema),
+    Map(&'a Schema),
+    /// Anything else, described by a type name like "string" or "integer (uint64)"
+    Primitive(String),
+    Any,
+}
+
+struct Differ<'a> {
+    old: &'a RootSchema,
+    new: &'a RootSchema,
+    usage: SchemaUsage,
+    /// The pairs of definitions compared already. This stops recursive types.
+    visited: HashSet<(String, String)>,
+    changes: Vec<SchemaChange>,
+}
+
+impl<'a> Differ<'a> {
+    fn diff(&mut self, path: &str, old: &Schema, new: &Schema) {
+        let (old, old_reference) = resolve(self.old, old);
+        let (new, new_reference) = resolve(self.new, new);
+        if let (Some(old_reference), Some(new_reference)) = (old_reference, new_reference) {
+            if !self
+                .visited
+                .insert((old_reference.to_string(), new_reference.to_string()))
+            {
+                return;
+            }
+        }
+
+        let input = self.usage == SchemaUsage::Message;
+        match (shape(old), shape(new)) {
+            (Shape::Nullable(old), Shape::Nullable(new)) => self.diff(path, &old, &new),
+            (Shape::Nullable(old), _) => {
+                if input {
+                    self.push(path, true, "null is no longer accepted");
+                }
+                self.diff(path, &old, new);
+            }
+            (_, Shape::Nullable(new)) => {
+                if !input {
+                    self.push(path, true, "value can be null now");
+                }
+                self.diff(path, old, &new);
+            }
+            (Shape::Struct(old), Shape::Struct(new)) => self.diff_struct(path, &old, &new),
+            (Shape::Variants(old), Shape::Variants(new)) => self.diff_variants(path, &old, &new),
+            (Shape::Array(old), Shape::Array(new)) => self.diff(&format!("{}[]", path), old, new),
+            (Shape::Map(old), Shape::Map(new)) => self.diff(&format!("{}{{}}", path), old, new),
+            (Shape::Primitive(old), Shape::Primitive(new)) => {
+                if old != new {
+                    self.push(path, true, format!("type changed from {} to {}", old, new));
+                }
+            }
+            (Shape::Any, Shape::Any) => {}
+            (old, new) => self.push(
+                path,
+                true,
+                format!("type changed from {} to {}", describe(&old), describe(&new)),
+            ),
+        }
+    }
+
+    fn diff_struct(
+        &mut self,
+        path: &str,
+        old: &BTreeMap<&str, (&Schema, bool)>,
+        new: &BTreeMap<&str, (&Schema, bool)>,
+    ) {
+        let input = self.usage == SchemaUsage::Message;
+        for (field, (old_schema, old_required)) in old {
+            let field_path = format!("{}.{}", path, field);
+            match new.get(field) {
+                Some((new_schema, new_required)) => {
+                    if input && !old_required && *new_required {
+                        self.push(&field_path, true, "optional field became required");
+                    }
+                    if !input && *old_required && !new_required {
+                        self.push(&field_path, true, "required field became optional");
+                    }
+                    self.diff(&field_path, old_schema, new_schema);
+                }
+                None => self.push(&field_path, true, "field was removed"),
+            }
+        }
+        for (field, (_, required)) in new {
+            if !old.contains_key(field) {
+                let field_path = format!("{}.{}", path, field);
+                if input && *required {
+                    self.push(&field_path, true, "required field was added");
+                } else {
+                    self.push(&field_path, false, "field was added");
+                }
+            }
+        }
+    }
+
+    fn diff_variants(
+        &mut self,
+        path: &str,
+        old: &BTreeMap<String, Option<&Schema>>,
+        new: &BTreeMap<String, Option<&Schema>>,
+    ) {
+        let input = self.usage == SchemaUsage::Message;
+        for (variant, old_schema) in old {
+            let variant_path = format!("{}.{}", path, variant);
+            match new.get(variant) {
+                Some(new_schema) => match (old_schema, new_schema) {
+                    (Some(old_schema), Some(new_schema)) => {
+                        self.diff(&variant_path, old_schema, new_schema)
+                    }
+                    (None, None) => {}
+                    _ => self.push(
+                        &variant_path,
+                        true,
+                        "variant changed between unit and non-unit",
+                    ),
+                },
+                None => self.push(&variant_path, input, "variant was removed"),
+            }
+        }
+        for variant in new.keys() {
+            if !old.contains_key(variant) {
+                self.push(
+                    &format!("{}.{}", path, variant),
+                    !input,
+                    "variant was added",
+                );
+            }
+        }
+    }
+
+    fn push(&mut self, path: &str, breaking: bool, description: impl Into<String>) {
+        self.changes.push(change(path, breaking, description));
+    }
+}
+
+/// Follows references to definitions and returns the referenced definition name if any
+fn resolve<'a>(root: &'a RootSchema, schema: &'a Schema) -> (&'a Schema, Option<&'a str>) {
+    let mut schema = schema;
+    let mut name = None;
+    // Limit the depth in case of references pointing to each other
+    for _ in 0..16 {
+        let object = match schema {
+            Schema::Object(object) => object,
+            Schema::Bool(_) => break,
+        };
+        if let Some(reference) = &object.reference {
+            let definition = reference.trim_start_matches("#/definitions/");
+            match root.definitions.get(definition) {
+                Some(resolved) => {
+                    schema = resolved;
+                    name = Some(definition);
+                    continue;
+                }
+                None => break,
+            }
+        }
+        // `allOf` with a single element is used for fields with a description
+        match object.subschemas.as_ref().and_then(|s| s.all_of.as_deref()) {
+            Some([inner]) => schema = inner,
+            _ => break,
+        }
+    }
+    (schema, name)
+}
+
+fn shape(schema: &Schema) -> Shape<'_> {
+    let object = match schema {
+        Schema::Bool(_) => return Shape::Any,
+        Schema::Object(object) => object,
+    };
+
+    if let Some(subschemas) = &object.subschemas {
+        // This is how an `Option<T>` is represented for non-primitive `T`
+        if let Some([inner, null]) = subschemas.any_of.as_deref() {
+            if is_null(null) {
+                return Shape::Nullable(inner.clone());
+            }
+        }
+        if let Some(variants) = &subschemas.one_of {
+            let mut map = BTreeMap::new();
+            for variant in variants {
+                if let Schema::Object(variant_object) = variant {
+                    if let Some(values) = string_enum_values(variant_object) {
+                        map.extend(values.into_iter().map(|value| (value, None)));
+                        continue;
+                    }
+                    if let Some((name, inner)) = single_property(variant_object) {
+                        map.insert(name.to_string(), Some(inner));
+                        continue;
+                    }
+                }
+                return Shape::Primitive("union".to_string());
+            }
+            return Shape::Variants(map);
+        }
+    }
+
+    if let Some(values) = string_enum_values(object) {
+        return Shape::Variants(values.into_iter().map(|value| (value, None)).collect());
+    }
+
+    match &object.instance_type {
+        Some(SingleOrVec::Vec(types)) => match types.as_slice() {
+            [instance_type, InstanceType::Null] | [InstanceType::Null, instance_type] => {
+                let mut inner = object.clone();
+                inner.instance_type = Some(SingleOrVec::Single(Box::new(*instance_type)));
+                Shape::Nullable(Schema::Object(inner))
+            }
+            _ => Shape::Primitive(format!("{:?}", types).to_lowercase()),
+        },
+        Some(SingleOrVec::Single(instance_type)) => match **instance_type {
+            InstanceType::Object => {
+                let properties = object.object.as_ref();
+                let value = properties.and_then(|o| o.additional_properties.as_deref());
+                match (properties, value) {
+                    (Some(o), Some(value))
+                        if o.properties.is_empty() && !matches!(value, Schema::Bool(false)) =>
+                    {
+                        Shape::Map(value)
+                    }
+                    _ => Shape::Struct(
+                        properties
+                            .map(|o| {
+                                o.properties
+                                    .iter()
+                                    .map(|(name, schema)| {
+                                        (name.as_str(), (schema, o.required.contains(name)))
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    ),
+                }
+            }
+            InstanceType::Array => {
+                match object.array.as_ref().and_then(|array| array.items.as_ref()) {
+                    Some(SingleOrVec::Single(item)) => Shape::Array(item),
+                    _ => Shape::Primitive("tuple".to_string()),
+                }
+            }
+            InstanceType::Integer => match &object.format {
+                Some(format) => Shape::Primitive(format!("integer ({})", format)),
+                None => Shape::Primitive("integer".to_string()),
+            },
+            other => Shape::Primitive(format!("{:?}", other).to_lowercase()),
+        },
+        None => Shape::Any,
+    }
+}
+
+fn describe(shape: &Shape) -> String {
+    match shape {
+        Shape::Struct(_) => "object".to_string(),
+        Shape::Variants(_) => "enum".to_string(),
+        Shape::Nullable(_) => "nullable".to_string(),
+        Shape::Array(_) => "array".to_string(),
+        Shape::Map(_) => "map".to_string(),
+        Shape::Primitive(name) => name.clone(),
+        Shape::Any => "any".to_string(),
+    }
+}
+
+fn string_enum_values(schema: &SchemaObject) -> Option<BTreeSet<String>> {
+    schema
+        .enum_values
+        .as_ref()?
+        .iter()
+        .map(|value| value.as_str().map(ToString::to_string))
+        .collect()
+}
+
+fn is_null(schema: &Schema) -> bool {
+    match schema {
+        Schema::Object(schema) => {
+            schema.instance_type == Some(SingleOrVec::Single(Box::new(InstanceType::Null)))
+        }
+        Schema::Bool(_) => false,
+    }
+}
+
+/// Returns the name and schema of the only property if the schema is an object with exactly
+/// one required property. This is how the non-unit variants of an enum are represented.
+fn single_property(schema: &SchemaObject) -> Option<(&str, &Schema)> {
+    let object = schema.object.as_ref()?;
+    if object.properties.len() != 1 || object.required.len() != 1 {
+        return None;
+    }
+    let (name, inner) = object.properties.iter().next()?;
+    if !object.required.contains(name) {
+        return None;
+    }
+    Some((name, inner))
+}
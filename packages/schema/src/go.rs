@@ -0,0 +1,460 @@
+//! Generation of Go types from a contract's API
+//!
+//! This allows chain-side integrations (e.g. query plugins or tests written in Go)
+//! to use the contract's messages without porting them by hand.
+
+use std::fmt::Write;
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use thiserror::Error;
+
+use crate::casing::to_pascal_case;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GoError {
+    #[error("Cannot represent {name} in Go: {reason}")]
+    Unsupported { name: String, reason: String },
+    #[error("Found conflicting definitions for the Go type {name}")]
+    ConflictingDefinitions { name: String },
+}
+
+impl GoError {
+    fn unsupported(name: &str, reason: &str) -> Self {
+        GoError::Unsupported {
+            name: name.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Renders a Go source file with one type for each of the given root schemas
+/// (named like the first tuple element) and for all the definitions they use.
+pub(crate) fn render_go(package: &str, roots: &[(String, &RootSchema)]) -> Result<String, GoError> {
+    let mut generator = Generator::default();
+    for (name, root) in roots {
+        generator.declare(name, &root.schema)?;
+    }
+    for (_, root) in roots {
+        for (name, definition) in &root.definitions {
+            match definition {
+                Schema::Object(schema) => generator.declare(&to_pascal_case(name), schema)?,
+                Schema::Bool(_) => {
+                    return Err(GoError::unsupported(name, "boolean schema definition"))
+                }
+            }
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("// Code generated by cosmwasm-schema. DO NOT EDIT.\n\n");
+    writeln!(out, "package {}", package).unwrap();
+    if generator.uses_json {
+        out.push_str("\nimport \"encoding/json\"\n");
+    }
+    for (_, code) in &generator.decls {
+        out.push('\n');
+        out.push_str(code);
+    }
+    Ok(out)
+}
+
+#[derive(Default)]
+struct Generator {
+    /// Type declarations (name and code) in the order of creation
+    decls: Vec<(String, String)>,
+    /// True if the `encoding/json` package needs to be imported
+    uses_json: bool,
+}
+
+impl Generator {
+    /// Adds a named type for the given schema. Declaring the same type twice is allowed
+    /// as long as both declarations are equal.
+    fn declare(&mut self, name: &str, schema: &SchemaObject) -> Result<(), GoError> {
+        // Reserve the slot before generating the code, such that the parent type
+        // is written before the types nested in it.
+        let index = self.decls.len();
+        self.decls.push((name.to_string(), String::new()));
+        let code = self.declaration(name, schema)?;
+
+        match self.decls[..index].iter().position(|(n, _)| n == name) {
+            Some(existing) => {
+                if self.decls[existing].1 != code {
+                    return Err(GoError::ConflictingDefinitions {
+                        name: name.to_string(),
+                    });
+                }
+                self.decls.remove(index);
+            }
+            None => self.decls[index].1 = code,
+        }
+        Ok(())
+    }
+
+    fn declaration(&mut self, name: &str, schema: &SchemaObject) -> Result<String, GoError> {
+        let mut code = comment(schema, "");
+
+        if let Some(values) = string_enum_values(schema) {
+            write_string_enum(&mut code, name, &values);
+        } else if let Some(variants) = schema.subschemas.as_ref().and_then(|s| s.one_of.as_ref()) {
+            // Enums with only unit variants that have a description
+            let unit_values: Option<Vec<&str>> = variants
+                .iter()
+                .map(|variant| match variant {
+                    Schema::Object(variant) => string_enum_values(variant),
+                    Schema::Bool(_) => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|values| values.concat());
+            if let Some(values) = unit_values {
+                write_string_enum(&mut code, name, &values);
+            } else {
+                self.write_tagged_enum(&mut code, name, variants)?;
+            }
+        } else if is_struct(schema) {
+            self.write_struct(&mut code, name, schema)?;
+        } else {
+            let ty = self.go_type(&Schema::Object(schema.clone()), name)?;
+            writeln!(code, "type {} {}", name, ty).unwrap();
+        }
+        Ok(code)
+    }
+
+    /// Writes an externally tagged enum as a struct with one optional field per variant
+    fn write_tagged_enum(
+        &mut self,
+        code: &mut String,
+        name: &str,
+        variants: &[Schema],
+    ) -> Result<(), GoError> {
+        let mut fields = Vec::new();
+        for variant in variants {
+            let (tag, variant, inner) = match variant {
+                Schema::Object(variant) => match single_property(variant) {
+                    Some((tag, inner)) => (tag, variant, inner),
+                    None if string_enum_values(variant).is_some() => {
+                        return Err(GoError::unsupported(
+                            name,
+                            "unit variants are serialized as strings and cannot be mixed with other variants",
+                        ))
+                    }
+                    None => return Err(GoError::unsupported(name, "unsupported enum variant")),
+                },
+                Schema::Bool(_) => return Err(GoError::unsupported(name, "unsupported enum variant")),
+            };
+            let field = to_pascal_case(tag);
+            let ty = self.go_type(inner, &format!("{}{}", name, field))?;
+            fields.push(Field {
+                comment: comment(variant, "\t"),
+                name: field,
+                ty: pointer(ty),
+                tag: format!("{},omitempty", tag),
+            });
+        }
+        write_struct_fields(code, name, &fields);
+        Ok(())
+    }
+
+    fn write_struct(
+        &mut self,
+        code: &mut String,
+        name: &str,
+        schema: &SchemaObject,
+    ) -> Result<(), GoError> {
+        let mut fields = Vec::new();
+        if let Some(object) = &schema.object {
+            for (property, property_schema) in &object.properties {
+                let field = to_pascal_case(property);
+                let ty = self.go_type(property_schema, &format!("{}{}", name, field))?;
+                let (ty, tag) = if object.required.contains(property) {
+                    (ty, property.clone())
+                } else {
+                    (pointer(ty), format!("{},omitempty", property))
+                };
+                let comment = match property_schema {
+                    Schema::Object(property_schema) => comment(property_schema, "\t"),
+                    Schema::Bool(_) => String::new(),
+                };
+                fields.push(Field {
+                    comment,
+                    name: field,
+                    ty,
+                    tag,
+                });
+            }
+        }
+        write_struct_fields(code, name, &fields);
+        Ok(())
+    }
+
+    /// Returns the Go type for the given schema. Inline structs and enums are declared
+    /// as named types using the name `hint`.
+    fn go_type(&mut self, schema: &Schema, hint: &str) -> Result<String, GoError> {
+        let schema = match schema {
+            Schema::Bool(true) => return Ok(self.raw_message()),
+            Schema::Bool(false) => {
+                return Err(GoError::unsupported(
+                    hint,
+                    "schema does not allow any value",
+                ))
+            }
+            Schema::Object(schema) => schema,
+        };
+
+        if let Some(reference) = &schema.reference {
+            return Ok(to_pascal_case(
+                reference.trim_start_matches("#/definitions/"),
+            ));
+        }
+
+        if let Some(subschemas) = &schema.subschemas {
+            if let Some([inner]) = subschemas.all_of.as_deref() {
+                return self.go_type(inner, hint);
+            }
+            // This is how an `Option<T>` is represented for non-primitive `T`
+            if let Some([inner, null]) = subschemas.any_of.as_deref() {
+                if is_null(null) {
+                    return Ok(pointer(self.go_type(inner, hint)?));
+                }
+            }
+            if subschemas.one_of.is_some() {
+                self.declare(hint, schema)?;
+                return Ok(hint.to_string());
+            }
+            return Err(GoError::unsupported(hint, "unsupported subschemas"));
+        }
+
+        if string_enum_values(schema).is_some() {
+            self.declare(hint, schema)?;
+            return Ok(hint.to_string());
+        }
+
+        match &schema.instance_type {
+            Some(SingleOrVec::Single(instance_type)) => {
+                self.instance_go_type(instance_type, schema, hint)
+            }
+            Some(SingleOrVec::Vec(types)) => match types.as_slice() {
+                [instance_type, InstanceType::Null] | [InstanceType::Null, instance_type] => Ok(
+                    pointer(self.instance_go_type(instance_type, schema, hint)?),
+                ),
+                _ => Err(GoError::unsupported(hint, "multiple types")),
+            },
+            None => Ok(self.raw_message()),
+        }
+    }
+
+    fn instance_go_type(
+        &mut self,
+        instance_type: &InstanceType,
+        schema: &SchemaObject,
+        hint: &str,
+    ) -> Result<String, GoError> {
+        let ty = match instance_type {
+            InstanceType::Null => return Err(GoError::unsupported(hint, "null type")),
+            InstanceType::Boolean => "bool",
+            InstanceType::String => "string",
+            InstanceType::Number => "float64",
+            InstanceType::Integer => match schema.format.as_deref() {
+                Some("uint8") => "uint8",
+                Some("uint16") => "uint16",
+                Some("uint32") => "uint32",
+                Some("uint64") => "uint64",
+                Some("uint") => "uint",
+                Some("int8") => "int8",
+                Some("int16") => "int16",
+                Some("int32") => "int32",
+                Some("int64") => "int64",
+                Some("int") => "int",
+                // There is no 128 bit integer in Go. json.Number keeps the exact value.
+                Some("uint128") | Some("int128") => {
+                    self.uses_json = true;
+                    "json.Number"
+                }
+                _ => "int64",
+            },
+            InstanceType::Array => {
+                let items = schema.array.as_ref().and_then(|array| array.items.as_ref());
+                return match items {
+                    Some(SingleOrVec::Single(item)) => Ok(format!(
+                        "[]{}",
+                        self.go_type(item, &format!("{}Item", hint))?
+                    )),
+                    _ => Err(GoError::unsupported(hint, "tuples are not supported")),
+                };
+            }
+            InstanceType::Object => {
+                if !is_struct(schema) {
+                    let value = schema
+                        .object
+                        .as_ref()
+                        .and_then(|object| object.additional_properties.as_deref());
+                    if let Some(value) = value {
+                        return Ok(format!(
+                            "map[string]{}",
+                            self.go_type(value, &format!("{}Value", hint))?
+                        ));
+                    }
+                }
+                self.declare(hint, schema)?;
+                return Ok(hint.to_string());
+            }
+        };
+        Ok(ty.to_string())
+    }
+
+    fn raw_message(&mut self) -> String {
+        self.uses_json = true;
+        "json.RawMessage".to_string()
+    }
+}
+
+struct Field {
+    comment: String,
+    name: String,
+    ty: String,
+    tag: String,
+}
+
+/// Writes a struct with columns aligned like gofmt does
+fn write_struct_fields(code: &mut String, name: &str, fields: &[Field]) {
+    if fields.is_empty() {
+        writeln!(code, "type {} struct{{}}", name).unwrap();
+        return;
+    }
+
+    writeln!(code, "type {} struct {{", name).unwrap();
+    // gofmt aligns the columns of consecutive fields. A comment line ends such a section.
+    let mut start = 0;
+    while start < fields.len() {
+        let end = fields[start + 1..]
+            .iter()
+            .position(|field| !field.comment.is_empty())
+            .map_or(fields.len(), |offset| start + 1 + offset);
+        let section = &fields[start..end];
+        let name_width = section
+            .iter()
+            .map(|f| f.name.len())
+            .max()
+            .unwrap_or_default();
+        let ty_width = section.iter().map(|f| f.ty.len()).max().unwrap_or_default();
+        for field in section {
+            code.push_str(&field.comment);
+            writeln!(
+                code,
+                "\t{:name_width$} {:ty_width$} `json:\"{}\"`",
+                field.name,
+                field.ty,
+                field.tag,
+                name_width = name_width,
+                ty_width = ty_width,
+            )
+            .unwrap();
+        }
+        start = end;
+    }
+    code.push_str("}\n");
+}
+
+/// Writes a string type with one constant per value
+fn write_string_enum(code: &mut String, name: &str, values: &[&str]) {
+    writeln!(code, "type {} string", name).unwrap();
+    code.push_str("\nconst (\n");
+    let constants: Vec<String> = values
+        .iter()
+        .map(|value| format!("{}{}", name, to_pascal_case(value)))
+        .collect();
+    let width = constants.iter().map(|c| c.len()).max().unwrap_or_default();
+    for (constant, value) in constants.iter().zip(values) {
+        writeln!(
+            code,
+            "\t{:width$} {} = \"{}\"",
+            constant,
+            name,
+            value,
+            width = width
+        )
+        .unwrap();
+    }
+    code.push_str(")\n");
+}
+
+fn comment(schema: &SchemaObject, indent: &str) -> String {
+    let description = schema
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.description.as_deref());
+    let mut out = String::new();
+    if let Some(description) = description {
+        for line in description.lines() {
+            if line.is_empty() {
+                writeln!(out, "{}//", indent).unwrap();
+            } else {
+                writeln!(out, "{}// {}", indent, line).unwrap();
+            }
+        }
+    }
+    out
+}
+
+fn pointer(ty: String) -> String {
+    // Slices, maps and raw messages are nullable already
+    if ty.starts_with('*')
+        || ty.starts_with("[]")
+        || ty.starts_with("map[")
+        || ty == "json.RawMessage"
+    {
+        ty
+    } else {
+        format!("*{}", ty)
+    }
+}
+
+/// Returns the values if the schema is a string enum, i.e. an enum with only unit variants
+fn string_enum_values(schema: &SchemaObject) -> Option<Vec<&str>> {
+    schema
+        .enum_values
+        .as_ref()?
+        .iter()
+        .map(|value| value.as_str())
+        .collect()
+}
+
+/// Returns true if the schema is an object with a fixed set of properties,
+/// in contrast to a map
+fn is_struct(schema: &SchemaObject) -> bool {
+    if schema.instance_type != Some(SingleOrVec::Single(Box::new(InstanceType::Object))) {
+        return false;
+    }
+    match &schema.object {
+        Some(object) => {
+            !object.properties.is_empty()
+                || matches!(
+                    object.additional_properties.as_deref(),
+                    None | Some(Schema::Bool(false))
+                )
+        }
+        None => true,
+    }
+}
+
+fn is_null(schema: &Schema) -> bool {
+    match schema {
+        Schema::Object(schema) => {
+            schema.instance_type == Some(SingleOrVec::Single(Box::new(InstanceType::Null)))
+        }
+        Schema::Bool(_) => false,
+    }
+}
+
+/// Returns the name and schema of the only property if the schema is an object with exactly
+/// one required property. This is how the non-unit variants of an enum are represented.
+fn single_property(schema: &SchemaObject) -> Option<(&str, &Schema)> {
+    let object = schema.object.as_ref()?;
+    if object.properties.len() != 1 || object.required.len() != 1 {
+        return None;
+    }
+    let (name, inner) = object.properties.iter().next()?;
+    if !object.required.contains(name) {
+        return None;
+    }
+    Some((name, inner))
+}
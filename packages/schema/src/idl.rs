@@ -2,9 +2,13 @@
 
 use std::collections::BTreeMap;
 
-use schemars::schema::RootSchema;
+use schemars::schema::{InstanceType, RootSchema, SingleOrVec};
 use thiserror::Error;
 
+use crate::casing::to_pascal_case;
+use crate::go::{render_go, GoError};
+use crate::typescript::{render_typescript, TypeScriptError};
+
 /// The version of the CosmWasm IDL.
 ///
 /// Follows Semantic Versioning 2.0.0: <https://semver.org/>
@@ -130,6 +134,60 @@ impl JsonApi {
     pub fn to_writer(&self, writer: impl std::io::Write) -> Result<(), EncodeError> {
         serde_json::to_writer_pretty(writer, self).map_err(Into::into)
     }
+
+    /// Generates Go types for all messages and query responses of the contract.
+    ///
+    /// The result is the content of a single Go source file in the given package.
+    /// Messages are named `InstantiateMsg`, `ExecuteMsg` and so on. Query responses
+    /// use the name of the Rust type if they are a struct or enum and
+    /// `<QueryVariant>Response` otherwise.
+    pub fn to_go(&self, package: &str) -> Result<String, GoError> {
+        render_go(package, &self.named_roots())
+    }
+
+    /// Generates TypeScript type definitions for all messages and query responses of
+    /// the contract.
+    ///
+    /// The result is the content of a single `.d.ts` file. The types are named like
+    /// the ones of [`JsonApi::to_go`].
+    pub fn to_typescript(&self) -> Result<String, TypeScriptError> {
+        render_typescript(&self.named_roots())
+    }
+
+    /// All root schemas with the name of the type generated for them
+    fn named_roots(&self) -> Vec<(String, &RootSchema)> {
+        let mut roots = vec![("InstantiateMsg".to_string(), &self.instantiate)];
+        for (name, schema) in [
+            ("ExecuteMsg", &self.execute),
+            ("QueryMsg", &self.query),
+            ("MigrateMsg", &self.migrate),
+            ("SudoMsg", &self.sudo),
+        ] {
+            if let Some(schema) = schema {
+                roots.push((name.to_string(), schema));
+            }
+        }
+        if let Some(responses) = &self.responses {
+            for (query, response) in responses {
+                roots.push((response_type_name(query, response), response));
+            }
+        }
+        roots
+    }
+}
+
+/// The name of the generated type for a query response
+fn response_type_name(query: &str, response: &RootSchema) -> String {
+    let schema = &response.schema;
+    let is_named_type = schema.instance_type
+        == Some(SingleOrVec::Single(Box::new(InstanceType::Object)))
+        || schema.enum_values.is_some()
+        || matches!(&schema.subschemas, Some(s) if s.one_of.is_some());
+    let title = schema.metadata.as_ref().and_then(|m| m.title.as_deref());
+    match title {
+        Some(title) if is_named_type => to_pascal_case(title),
+        _ => format!("{}Response", to_pascal_case(query)),
+    }
 }
 
 #[derive(Error, Debug)]
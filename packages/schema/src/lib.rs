@@ -1,14 +1,20 @@
 mod casing;
+mod diff;
 mod export;
+mod go;
 mod idl;
 mod query_response;
 mod remove;
 mod schema_for;
+mod typescript;
 
+pub use diff::{diff_api, diff_schemas, DiffError, SchemaChange, SchemaUsage};
 pub use export::{export_schema, export_schema_with_title};
+pub use go::GoError;
 pub use idl::{Api, IDL_VERSION};
 pub use query_response::{combine_subqueries, IntegrityError, QueryResponses};
 pub use remove::remove_schemas;
+pub use typescript::TypeScriptError;
 
 // Re-exports
 /// An attribute macro that annotates types with things they need to be properly (de)serialized
@@ -74,6 +80,8 @@ pub use cosmwasm_schema_derive::generate_api;
 /// - `execute` - execute msg type, empty by default
 /// - `migrate` - migrate msg type, empty by default
 /// - `sudo` - sudo msg type, empty by default
+/// - `typescript` - if `true`, TypeScript type definitions for all messages and query
+///   responses are written to `<name>.d.ts` in addition to the JSON schemas, `false` by default
 ///
 /// # Example
 /// ```
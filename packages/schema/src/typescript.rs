@@ -0,0 +1,286 @@
+//! Generation of TypeScript type definitions from a contract's API
+//!
+//! This allows frontends to use the contract's messages without maintaining
+//! hand-written types that drift from the contract.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use thiserror::Error;
+
+use crate::casing::to_pascal_case;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TypeScriptError {
+    #[error("Found conflicting definitions for the TypeScript type {name}")]
+    ConflictingDefinitions { name: String },
+}
+
+const INDENT: &str = "  ";
+
+/// Renders a TypeScript declaration file (`.d.ts`) with one exported type for each of the
+/// given root schemas (named like the first tuple element) and for all the definitions they use.
+pub(crate) fn render_typescript(
+    roots: &[(String, &RootSchema)],
+) -> Result<String, TypeScriptError> {
+    let mut decls: Vec<(String, String)> = Vec::new();
+    let mut declare = |name: &str, schema: &Schema| {
+        let code = declaration(name, schema);
+        match decls.iter().find(|(n, _)| n == name) {
+            Some((_, existing)) if *existing != code => {
+                Err(TypeScriptError::ConflictingDefinitions {
+                    name: name.to_string(),
+                })
+            }
+            Some(_) => Ok(()),
+            None => {
+                decls.push((name.to_string(), code));
+                Ok(())
+            }
+        }
+    };
+
+    for (name, root) in roots {
+        declare(name, &Schema::Object(root.schema.clone()))?;
+    }
+    for (_, root) in roots {
+        for (name, definition) in &root.definitions {
+            declare(&to_pascal_case(name), definition)?;
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("// Code generated by cosmwasm-schema. DO NOT EDIT.\n");
+    for (_, code) in &decls {
+        out.push('\n');
+        out.push_str(code);
+    }
+    Ok(out)
+}
+
+fn declaration(name: &str, schema: &Schema) -> String {
+    let mut code = match schema {
+        Schema::Object(schema) => comment(schema, ""),
+        Schema::Bool(_) => String::new(),
+    };
+    match schema {
+        Schema::Object(object) if is_struct(object) => {
+            writeln!(code, "export interface {} {}", name, ts_type(schema, "")).unwrap();
+        }
+        _ => {
+            writeln!(
+                code,
+                "export type {} ={};",
+                name,
+                spaced(&ts_type(schema, ""))
+            )
+            .unwrap();
+        }
+    }
+    code
+}
+
+/// Returns the TypeScript type for the given schema. Multi-line types are indented
+/// with `indent`, which is the indentation of the line they start in.
+fn ts_type(schema: &Schema, indent: &str) -> String {
+    let schema = match schema {
+        Schema::Bool(true) => return "unknown".to_string(),
+        Schema::Bool(false) => return "never".to_string(),
+        Schema::Object(schema) => schema,
+    };
+
+    if let Some(reference) = &schema.reference {
+        return to_pascal_case(reference.trim_start_matches("#/definitions/"));
+    }
+
+    if let Some(subschemas) = &schema.subschemas {
+        if let Some([inner]) = subschemas.all_of.as_deref() {
+            return ts_type(inner, indent);
+        }
+        if let Some(variants) = &subschemas.any_of {
+            return union(variants.iter().map(|variant| ts_type(variant, indent)));
+        }
+        if let Some(variants) = &subschemas.one_of {
+            // One variant per line, each with its documentation
+            let variant_indent = format!("{}{}", indent, INDENT);
+            let mut out = String::new();
+            for variant in variants {
+                out.push('\n');
+                if let Schema::Object(variant) = variant {
+                    out.push_str(&comment(variant, &variant_indent));
+                }
+                write!(
+                    out,
+                    "{}| {}",
+                    variant_indent,
+                    ts_type(variant, &variant_indent)
+                )
+                .unwrap();
+            }
+            return out;
+        }
+    }
+
+    if let Some(values) = &schema.enum_values {
+        return union(values.iter().map(|value| value.to_string()));
+    }
+
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(instance_type)) => instance_ts_type(instance_type, schema, indent),
+        Some(SingleOrVec::Vec(types)) => union(
+            types
+                .iter()
+                .map(|instance_type| instance_ts_type(instance_type, schema, indent)),
+        ),
+        None => "unknown".to_string(),
+    }
+}
+
+fn instance_ts_type(instance_type: &InstanceType, schema: &SchemaObject, indent: &str) -> String {
+    match instance_type {
+        InstanceType::Null => "null".to_string(),
+        InstanceType::Boolean => "boolean".to_string(),
+        InstanceType::String => "string".to_string(),
+        InstanceType::Number | InstanceType::Integer => "number".to_string(),
+        InstanceType::Array => {
+            let items = schema.array.as_ref().and_then(|array| array.items.as_ref());
+            match items {
+                Some(SingleOrVec::Single(item)) => {
+                    let item = ts_type(item, indent);
+                    if item.contains('|') {
+                        format!("({})[]", item)
+                    } else {
+                        format!("{}[]", item)
+                    }
+                }
+                Some(SingleOrVec::Vec(items)) => format!(
+                    "[{}]",
+                    items
+                        .iter()
+                        .map(|item| ts_type(item, indent))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                None => "unknown[]".to_string(),
+            }
+        }
+        InstanceType::Object => {
+            if !is_struct(schema) {
+                let value = schema
+                    .object
+                    .as_ref()
+                    .and_then(|object| object.additional_properties.as_deref());
+                if let Some(value) = value {
+                    return format!("Record<string, {}>", ts_type(value, indent));
+                }
+            }
+            object_type(schema, indent)
+        }
+    }
+}
+
+/// Writes an object type with one property per line
+fn object_type(schema: &SchemaObject, indent: &str) -> String {
+    let properties = match &schema.object {
+        Some(object) if !object.properties.is_empty() => object,
+        _ => return "{}".to_string(),
+    };
+
+    let property_indent = format!("{}{}", indent, INDENT);
+    let mut out = String::from("{\n");
+    for (property, property_schema) in &properties.properties {
+        if let Schema::Object(property_schema) = property_schema {
+            out.push_str(&comment(property_schema, &property_indent));
+        }
+        let optional = if properties.required.contains(property) {
+            ""
+        } else {
+            "?"
+        };
+        writeln!(
+            out,
+            "{}{}{}:{};",
+            property_indent,
+            property_name(property),
+            optional,
+            spaced(&ts_type(property_schema, &property_indent))
+        )
+        .unwrap();
+    }
+    write!(out, "{}}}", indent).unwrap();
+    out
+}
+
+/// Prefixes a type with a space unless it starts on a new line
+fn spaced(ty: &str) -> String {
+    if ty.starts_with('\n') {
+        ty.to_string()
+    } else {
+        format!(" {}", ty)
+    }
+}
+
+/// Joins the types into a union, keeping the first occurrence of duplicates
+fn union(types: impl Iterator<Item = String>) -> String {
+    let mut seen = HashSet::new();
+    let types: Vec<String> = types.filter(|ty| seen.insert(ty.clone())).collect();
+    types.join(" | ")
+}
+
+/// Quotes property names which are not valid identifiers
+fn property_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = matches!(chars.next(), Some(first) if first.is_ascii_alphabetic() || first == '_' || first == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+fn comment(schema: &SchemaObject, indent: &str) -> String {
+    let description = schema
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.description.as_deref());
+    let mut out = String::new();
+    if let Some(description) = description {
+        // Don't let the description end the comment
+        let description = description.replace("*/", "*\\/");
+        let lines: Vec<&str> = description.lines().collect();
+        if let [line] = lines.as_slice() {
+            writeln!(out, "{}/** {} */", indent, line).unwrap();
+        } else {
+            writeln!(out, "{}/**", indent).unwrap();
+            for line in lines {
+                if line.is_empty() {
+                    writeln!(out, "{} *", indent).unwrap();
+                } else {
+                    writeln!(out, "{} * {}", indent, line).unwrap();
+                }
+            }
+            writeln!(out, "{} */", indent).unwrap();
+        }
+    }
+    out
+}
+
+/// Returns true if the schema is an object with a fixed set of properties,
+/// in contrast to a map
+fn is_struct(schema: &SchemaObject) -> bool {
+    if schema.instance_type != Some(SingleOrVec::Single(Box::new(InstanceType::Object))) {
+        return false;
+    }
+    match &schema.object {
+        Some(object) => {
+            !object.properties.is_empty()
+                || matches!(
+                    object.additional_properties.as_deref(),
+                    None | Some(Schema::Bool(false))
+                )
+        }
+        None => true,
+    }
+}
@@ -0,0 +1,204 @@
+use cosmwasm_schema::{diff_api, diff_schemas, schema_for, DiffError, SchemaUsage};
+
+mod v1 {
+    use cosmwasm_schema::{cw_serde, generate_api, QueryResponses};
+    use cosmwasm_std::Uint128;
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub admin: String,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Mint { amount: Uint128, recipient: String },
+        Burn { amount: Uint128 },
+        Freeze {},
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(BalanceResponse)]
+        Balance { account: String },
+        #[returns(ConfigResponse)]
+        Config {},
+    }
+
+    #[cw_serde]
+    pub struct BalanceResponse {
+        pub balance: Uint128,
+        pub height: u64,
+    }
+
+    #[cw_serde]
+    pub struct ConfigResponse {
+        pub mode: Mode,
+    }
+
+    #[cw_serde]
+    pub enum Mode {
+        Open,
+        Closed,
+    }
+
+    pub fn api() -> String {
+        generate_api! {
+            name: "token",
+            instantiate: InstantiateMsg,
+            execute: ExecuteMsg,
+            query: QueryMsg,
+        }
+        .render()
+        .to_string()
+        .unwrap()
+    }
+}
+
+mod v2 {
+    use cosmwasm_schema::{cw_serde, generate_api, QueryResponses};
+    use cosmwasm_std::Uint128;
+
+    #[cw_serde]
+    pub struct InstantiateMsg {
+        pub admin: String,
+        pub label: Option<String>,
+    }
+
+    #[cw_serde]
+    pub enum ExecuteMsg {
+        Mint {
+            amount: Uint128,
+            recipient: String,
+            memo: String,
+        },
+        Burn {
+            amount: u64,
+        },
+        Thaw {},
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(BalanceResponse)]
+        Balance { account: String },
+        #[returns(ConfigResponse)]
+        Config {},
+    }
+
+    #[cw_serde]
+    pub struct BalanceResponse {
+        pub balance: Uint128,
+        pub height: Option<u64>,
+        pub denom: String,
+    }
+
+    #[cw_serde]
+    pub struct ConfigResponse {
+        pub mode: Mode,
+    }
+
+    #[cw_serde]
+    pub enum Mode {
+        Open,
+        Closed,
+        Paused,
+    }
+
+    pub fn api() -> String {
+        generate_api! {
+            name: "token",
+            instantiate: InstantiateMsg,
+            execute: ExecuteMsg,
+            query: QueryMsg,
+        }
+        .render()
+        .to_string()
+        .unwrap()
+    }
+}
+
+#[test]
+fn diff_api_without_changes() {
+    let api = v1::api();
+    assert_eq!(diff_api(&api, &api).unwrap(), []);
+}
+
+#[test]
+fn diff_api_works() {
+    let changes: Vec<_> = diff_api(&v1::api(), &v2::api())
+        .unwrap()
+        .into_iter()
+        .map(|change| (change.path, change.breaking))
+        .collect();
+    let expected = [
+        ("instantiate.label", false),
+        ("execute.burn.amount", true),
+        ("execute.freeze", true),
+        ("execute.mint.memo", true),
+        ("execute.thaw", false),
+        ("responses.balance.height", true),
+        ("responses.balance.height", true),
+        ("responses.balance.denom", false),
+        ("responses.config.mode.paused", true),
+    ];
+    assert_eq!(
+        changes,
+        expected.map(|(path, breaking)| (path.to_string(), breaking))
+    );
+}
+
+#[test]
+fn diff_api_reports_removed_messages() {
+    let mut old: serde_json::Value = serde_json::from_str(&v1::api()).unwrap();
+    let new = v1::api();
+    old["migrate"] = old["instantiate"].clone();
+
+    let changes = diff_api(&old.to_string(), &new).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, "migrate");
+    assert!(changes[0].breaking);
+    assert_eq!(
+        changes[0].to_string(),
+        "migrate (breaking): message was removed"
+    );
+
+    // adding a message is fine
+    let changes = diff_api(&new, &old.to_string()).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert!(!changes[0].breaking);
+}
+
+#[test]
+fn diff_schemas_depends_on_usage() {
+    let old = schema_for!(v1::BalanceResponse);
+    let new = schema_for!(v2::BalanceResponse);
+
+    // as a message, adding a required field breaks but making one optional is fine
+    let changes: Vec<_> = diff_schemas("balance", &old, &new, SchemaUsage::Message)
+        .into_iter()
+        .map(|change| (change.path, change.breaking))
+        .collect();
+    assert_eq!(changes, [("balance.denom".to_string(), true)]);
+
+    let changes: Vec<_> = diff_schemas("balance", &old, &new, SchemaUsage::Response)
+        .into_iter()
+        .map(|change| (change.path, change.breaking))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ("balance.height".to_string(), true),
+            ("balance.height".to_string(), true),
+            ("balance.denom".to_string(), false),
+        ]
+    );
+}
+
+#[test]
+fn diff_api_fails_for_invalid_input() {
+    let api = v1::api();
+    let err = diff_api("{}", &api).unwrap_err();
+    assert!(matches!(err, DiffError::InvalidSchema(_)));
+}
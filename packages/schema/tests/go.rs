@@ -0,0 +1,152 @@
+use cosmwasm_schema::{cw_serde, generate_api, QueryResponses};
+use cosmwasm_std::{Binary, Uint128};
+
+/// Creates a new token
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub admin: String,
+    /// The maximum supply
+    pub cap: Option<Uint128>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Mints new tokens
+    Mint {
+        amount: Uint128,
+        recipient: String,
+    },
+    Burn {
+        amount: Uint128,
+    },
+    SetConfig(Config),
+}
+
+#[cw_serde]
+pub struct Config {
+    pub mode: Mode,
+    pub memo: Option<Binary>,
+    pub tags: Vec<String>,
+}
+
+#[cw_serde]
+pub enum Mode {
+    Open,
+    Closed,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(BalanceResponse)]
+    Balance { account: String },
+    #[returns(Vec<String>)]
+    Accounts {},
+    #[returns(u128)]
+    TotalSupply {},
+}
+
+#[cw_serde]
+pub struct BalanceResponse {
+    pub balance: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub enum MixedExecuteMsg {
+    Reset,
+    Mint { amount: Uint128 },
+}
+
+#[test]
+fn go_types_are_generated() {
+    let go = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+    }
+    .render()
+    .to_go("token")
+    .unwrap();
+
+    assert!(go.starts_with(
+        "// Code generated by cosmwasm-schema. DO NOT EDIT.\n\npackage token\n\nimport \"encoding/json\"\n"
+    ));
+
+    let expected_decls = [
+        // Structs with comments and optional fields
+        r#"// Creates a new token
+type InstantiateMsg struct {
+	Admin string `json:"admin"`
+	// The maximum supply
+	Cap *Uint128 `json:"cap,omitempty"`
+}
+"#,
+        // Enums
+        r#"type ExecuteMsg struct {
+	// Mints new tokens
+	Mint      *ExecuteMsgMint `json:"mint,omitempty"`
+	Burn      *ExecuteMsgBurn `json:"burn,omitempty"`
+	SetConfig *Config         `json:"set_config,omitempty"`
+}
+"#,
+        r#"type ExecuteMsgMint struct {
+	Amount    Uint128 `json:"amount"`
+	Recipient string  `json:"recipient"`
+}
+"#,
+        r#"type QueryMsgAccounts struct{}
+"#,
+        r#"type Mode string
+
+const (
+	ModeOpen   Mode = "open"
+	ModeClosed Mode = "closed"
+)
+"#,
+        // Definitions
+        r#"type Config struct {
+	Memo *Binary  `json:"memo,omitempty"`
+	Mode Mode     `json:"mode"`
+	Tags []string `json:"tags"`
+}
+"#,
+        "type Uint128 string\n",
+        // Responses
+        r#"type BalanceResponse struct {
+	Balance Uint128 `json:"balance"`
+	Height  uint64  `json:"height"`
+}
+"#,
+        "type AccountsResponse []string\n",
+        "type TotalSupplyResponse json.Number\n",
+    ];
+    for expected in expected_decls {
+        assert!(
+            go.contains(expected),
+            "Missing declaration:\n{}\nin:\n{}",
+            expected,
+            go
+        );
+    }
+}
+
+#[test]
+fn go_types_fail_for_mixed_enum() {
+    let err = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        execute: MixedExecuteMsg,
+    }
+    .render()
+    .to_go("token")
+    .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Cannot represent ExecuteMsg in Go: unit variants are serialized as strings and cannot be mixed with other variants"
+    );
+}
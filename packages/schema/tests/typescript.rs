@@ -0,0 +1,192 @@
+use cosmwasm_schema::{cw_serde, generate_api, QueryResponses};
+use cosmwasm_std::{Binary, Uint128};
+
+/// Creates a new token
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub admin: String,
+    /// The maximum supply
+    pub cap: Option<Uint128>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Mints new tokens
+    Mint {
+        amount: Uint128,
+        recipient: String,
+    },
+    Burn {
+        amount: Uint128,
+    },
+    SetConfig(Config),
+}
+
+#[cw_serde]
+pub struct Config {
+    pub mode: Mode,
+    pub memo: Option<Binary>,
+    pub tags: Vec<String>,
+}
+
+#[cw_serde]
+pub enum Mode {
+    Open,
+    Closed,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(BalanceResponse)]
+    Balance { account: String },
+    #[returns(Vec<String>)]
+    Accounts {},
+    #[returns(u128)]
+    TotalSupply {},
+}
+
+#[cw_serde]
+pub struct BalanceResponse {
+    pub balance: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub enum MixedExecuteMsg {
+    Reset,
+    Mint { amount: Uint128 },
+}
+
+#[cw_serde]
+#[serde(untagged)]
+pub enum Amount {
+    Count(u64),
+    Name(String),
+    Delta(i64),
+}
+
+/// Contains `*/`, which must not end the comment
+#[cw_serde]
+pub struct Glob {
+    pub pattern: String,
+}
+
+#[test]
+fn typescript_types_are_generated() {
+    let ts = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+        migrate: MixedExecuteMsg,
+    }
+    .render()
+    .to_typescript()
+    .unwrap();
+
+    assert!(ts.starts_with("// Code generated by cosmwasm-schema. DO NOT EDIT.\n\n"));
+
+    let expected_decls = [
+        // Structs with comments and optional fields
+        r#"/** Creates a new token */
+export interface InstantiateMsg {
+  admin: string;
+  /** The maximum supply */
+  cap?: Uint128 | null;
+}
+"#,
+        // Enums
+        r#"export type ExecuteMsg =
+  /** Mints new tokens */
+  | {
+    mint: {
+      amount: Uint128;
+      recipient: string;
+    };
+  }
+  | {
+    burn: {
+      amount: Uint128;
+    };
+  }
+  | {
+    set_config: Config;
+  };
+"#,
+        r#"  | {
+    accounts: {};
+  }
+"#,
+        r#"export type MigrateMsg =
+  | "reset"
+  | {
+    mint: {
+      amount: Uint128;
+    };
+  };
+"#,
+        r#"export type Mode = "open" | "closed";
+"#,
+        // Definitions
+        r#"export interface Config {
+  memo?: Binary | null;
+  mode: Mode;
+  tags: string[];
+}
+"#,
+        "export type Uint128 = string;\n",
+        // Responses
+        r#"export interface BalanceResponse {
+  balance: Uint128;
+  height: number;
+}
+"#,
+        "export type AccountsResponse = string[];\n",
+        "export type TotalSupplyResponse = number;\n",
+    ];
+    for expected in expected_decls {
+        assert!(
+            ts.contains(expected),
+            "Missing declaration:\n{}\nin:\n{}",
+            expected,
+            ts
+        );
+    }
+    // Every type is declared once
+    assert_eq!(ts.matches("export type Uint128 ").count(), 1);
+}
+
+#[test]
+fn typescript_comments_are_escaped() {
+    let ts = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: Glob,
+    }
+    .render()
+    .to_typescript()
+    .unwrap();
+
+    assert!(ts.contains("/** Contains `*\\/`, which must not end the comment */\n"));
+}
+
+#[test]
+fn typescript_unions_are_deduplicated() {
+    let ts = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: Amount,
+    }
+    .render()
+    .to_typescript()
+    .unwrap();
+
+    // u64 and i64 are both numbers, which are not next to each other
+    assert!(
+        ts.contains("export type InstantiateMsg = number | string;\n"),
+        "{}",
+        ts
+    );
+}
@@ -783,6 +783,34 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn instantiate2_address_works() {
+        let checksum1 =
+            HexBinary::from_hex("13a1fc994cc6d1c81b746ee0c0ff6f90043875e0bf1d9be6b7d779fc978dc2a5")
+                .unwrap();
+        let creator1 = CanonicalAddr::from(hex!("9999999999aaaaaaaaaabbbbbbbbbbcccccccccc"));
+        let salt1 = hex!("61");
+
+        // Same as instantiate2_address_impl with an empty msg (the wasmd default)
+        let expected = CanonicalAddr::from(hex!(
+            "5e865d3e45ad3e961f77fd77d46543417ced44d924dc3e079b5415ff6775f847"
+        ));
+        assert_eq!(
+            instantiate2_address(&checksum1, &creator1, &salt1).unwrap(),
+            expected
+        );
+
+        // Errors are passed through
+        assert_eq!(
+            instantiate2_address(&checksum1, &creator1, b"").unwrap_err(),
+            Instantiate2AddressError::InvalidSaltLength
+        );
+        assert_eq!(
+            instantiate2_address(b"too short", &creator1, &salt1).unwrap_err(),
+            Instantiate2AddressError::InvalidChecksumLength
+        );
+    }
+
     #[test]
     fn instantiate2_address_impl_works_for_cosmjs_testvectors() {
         // Test data from https://github.com/cosmos/cosmjs/pull/1253
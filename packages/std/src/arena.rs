@@ -0,0 +1,304 @@
+//! An arena-style global allocator for contracts that do many small allocations per call.
+//!
+//! Allocations are served by bumping a pointer through the Wasm linear memory. Individual
+//! deallocations are ignored, except for the most recent allocation which can be reclaimed
+//! immediately. All memory is released at once by the `cosmwasm_arena_reset` export, which
+//! the VM calls before every entry point call. This is safe because no heap data outlives an
+//! entry point call. The only exception are lazily initialized statics, which must not be used
+//! together with this allocator.
+//!
+//! This module is only compiled when the `arena_allocator` feature is enabled. The bookkeeping
+//! in [`ArenaState`] is independent of Wasm such that it can be tested natively.
+
+use core::alloc::Layout;
+
+/// The size of a Wasm memory page in bytes
+const PAGE_SIZE: usize = 64 * 1024;
+
+/// Access to the linear memory the arena lives in
+trait LinearMemory {
+    /// The current size of the memory in pages
+    fn size(&self) -> usize;
+
+    /// Grows the memory by the given number of pages. Returns false if that is not possible.
+    fn grow(&mut self, pages: usize) -> bool;
+}
+
+struct ArenaState {
+    /// The beginning of the arena. Set on the first allocation.
+    start: usize,
+    /// The beginning of the free space in the arena
+    next: usize,
+    /// The end of the memory owned by the arena
+    end: usize,
+}
+
+impl ArenaState {
+    const fn new() -> Self {
+        ArenaState {
+            start: 0,
+            next: 0,
+            end: 0,
+        }
+    }
+
+    /// Returns the address of a new allocation or `None` if the memory cannot grow enough.
+    fn alloc(&mut self, layout: Layout, memory: &mut impl LinearMemory) -> Option<usize> {
+        if self.start == 0 {
+            // Everything above the current memory size is unused by the data segments
+            // and the stack, so this is where the arena starts.
+            let start = memory.size() * PAGE_SIZE;
+            self.start = start;
+            self.next = start;
+            self.end = start;
+        }
+
+        // Layout guarantees the alignment is a power of two
+        let aligned = self.next.checked_add(layout.align() - 1)? & !(layout.align() - 1);
+        let new_next = aligned.checked_add(layout.size())?;
+
+        if new_next > self.end {
+            let missing = new_next - self.end;
+            let pages = missing.checked_add(PAGE_SIZE - 1)? / PAGE_SIZE;
+            if !memory.grow(pages) {
+                return None;
+            }
+            self.end += pages * PAGE_SIZE;
+        }
+
+        self.next = new_next;
+        Some(aligned)
+    }
+
+    fn dealloc(&mut self, ptr: usize, layout: Layout) {
+        // Only the most recent allocation can be reclaimed right away.
+        // Everything else is released by `reset`.
+        if ptr + layout.size() == self.next {
+            self.next = ptr;
+        }
+    }
+
+    /// Releases all allocations at once. The memory is kept and re-used for
+    /// future allocations since Wasm memory cannot shrink.
+    fn reset(&mut self) {
+        self.next = self.start;
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod allocator {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::arch::wasm32;
+    use core::cell::UnsafeCell;
+    use core::ptr;
+
+    use super::{ArenaState, LinearMemory};
+
+    #[global_allocator]
+    static ALLOCATOR: ArenaAllocator = ArenaAllocator::new();
+
+    /// The memory of the running contract
+    struct WasmMemory;
+
+    impl LinearMemory for WasmMemory {
+        fn size(&self) -> usize {
+            wasm32::memory_size(0)
+        }
+
+        fn grow(&mut self, pages: usize) -> bool {
+            wasm32::memory_grow(0, pages) != usize::MAX
+        }
+    }
+
+    struct ArenaAllocator {
+        state: UnsafeCell<ArenaState>,
+    }
+
+    // Contracts are executed single threaded
+    unsafe impl Sync for ArenaAllocator {}
+
+    impl ArenaAllocator {
+        const fn new() -> Self {
+            ArenaAllocator {
+                state: UnsafeCell::new(ArenaState::new()),
+            }
+        }
+    }
+
+    unsafe impl GlobalAlloc for ArenaAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let state = &mut *self.state.get();
+            match state.alloc(layout, &mut WasmMemory) {
+                Some(address) => address as *mut u8,
+                None => ptr::null_mut(),
+            }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            let state = &mut *self.state.get();
+            state.dealloc(ptr as usize, layout);
+        }
+    }
+
+    /// Releases all memory allocated in the arena since the last reset.
+    ///
+    /// # Safety
+    ///
+    /// There must not be any live reference into memory allocated before the call.
+    pub unsafe fn reset() {
+        let state = &mut *ALLOCATOR.state.get();
+        state.reset();
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use allocator::reset;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A memory of `pages` pages which can grow up to `max_pages` pages
+    struct MockMemory {
+        pages: usize,
+        max_pages: usize,
+    }
+
+    impl MockMemory {
+        fn new(pages: usize, max_pages: usize) -> Self {
+            MockMemory { pages, max_pages }
+        }
+    }
+
+    impl LinearMemory for MockMemory {
+        fn size(&self) -> usize {
+            self.pages
+        }
+
+        fn grow(&mut self, pages: usize) -> bool {
+            if self.pages + pages > self.max_pages {
+                return false;
+            }
+            self.pages += pages;
+            true
+        }
+    }
+
+    fn layout(size: usize, align: usize) -> Layout {
+        Layout::from_size_align(size, align).unwrap()
+    }
+
+    #[test]
+    fn alloc_starts_after_existing_memory() {
+        let mut memory = MockMemory::new(2, 10);
+        let mut arena = ArenaState::new();
+
+        let address = arena.alloc(layout(8, 1), &mut memory).unwrap();
+        assert_eq!(address, 2 * PAGE_SIZE);
+        let address = arena.alloc(layout(8, 1), &mut memory).unwrap();
+        assert_eq!(address, 2 * PAGE_SIZE + 8);
+    }
+
+    #[test]
+    fn alloc_respects_alignment() {
+        let mut memory = MockMemory::new(1, 10);
+        let mut arena = ArenaState::new();
+
+        let address = arena.alloc(layout(1, 1), &mut memory).unwrap();
+        assert_eq!(address, PAGE_SIZE);
+        let address = arena.alloc(layout(4, 4), &mut memory).unwrap();
+        assert_eq!(address, PAGE_SIZE + 4);
+        let address = arena.alloc(layout(1, 1), &mut memory).unwrap();
+        assert_eq!(address, PAGE_SIZE + 8);
+        let address = arena.alloc(layout(16, 16), &mut memory).unwrap();
+        assert_eq!(address, PAGE_SIZE + 16);
+        let address = arena.alloc(layout(0, 128), &mut memory).unwrap();
+        assert_eq!(address, PAGE_SIZE + 128);
+    }
+
+    #[test]
+    fn alloc_grows_memory() {
+        let mut memory = MockMemory::new(1, 10);
+        let mut arena = ArenaState::new();
+
+        // The arena does not own any memory initially
+        arena.alloc(layout(1, 1), &mut memory).unwrap();
+        assert_eq!(memory.pages, 2);
+
+        // Fits into the current page
+        arena.alloc(layout(PAGE_SIZE - 1, 1), &mut memory).unwrap();
+        assert_eq!(memory.pages, 2);
+
+        // Needs multiple pages at once
+        let address = arena
+            .alloc(layout(2 * PAGE_SIZE + 1, 1), &mut memory)
+            .unwrap();
+        assert_eq!(address, 2 * PAGE_SIZE);
+        assert_eq!(memory.pages, 5);
+    }
+
+    #[test]
+    fn alloc_fails_when_memory_cannot_grow() {
+        let mut memory = MockMemory::new(1, 3);
+        let mut arena = ArenaState::new();
+
+        assert_eq!(arena.alloc(layout(3 * PAGE_SIZE, 1), &mut memory), None);
+        assert_eq!(memory.pages, 1);
+
+        // Smaller allocations still work
+        let address = arena.alloc(layout(2 * PAGE_SIZE, 1), &mut memory).unwrap();
+        assert_eq!(address, PAGE_SIZE);
+        assert_eq!(memory.pages, 3);
+    }
+
+    #[test]
+    fn alloc_fails_on_overflow() {
+        let mut memory = MockMemory::new(1, 10);
+        let end = usize::MAX - 16;
+        let mut arena = ArenaState {
+            start: end,
+            next: end,
+            end,
+        };
+
+        // Overflow when aligning
+        assert_eq!(arena.alloc(layout(1, 32), &mut memory), None);
+        // Overflow when adding the size
+        assert_eq!(arena.alloc(layout(17, 1), &mut memory), None);
+        assert_eq!(memory.pages, 1);
+        assert_eq!(arena.next, end);
+    }
+
+    #[test]
+    fn dealloc_reclaims_most_recent_allocation_only() {
+        let mut memory = MockMemory::new(1, 10);
+        let mut arena = ArenaState::new();
+
+        let first = arena.alloc(layout(8, 1), &mut memory).unwrap();
+        let second = arena.alloc(layout(8, 1), &mut memory).unwrap();
+
+        // Not the most recent allocation, so this is ignored
+        arena.dealloc(first, layout(8, 1));
+        assert_eq!(arena.alloc(layout(8, 1), &mut memory).unwrap(), second + 8);
+
+        arena.dealloc(second + 8, layout(8, 1));
+        assert_eq!(arena.alloc(layout(8, 1), &mut memory).unwrap(), second + 8);
+    }
+
+    #[test]
+    fn reset_releases_all_allocations() {
+        let mut memory = MockMemory::new(1, 10);
+        let mut arena = ArenaState::new();
+
+        let first = arena.alloc(layout(100, 1), &mut memory).unwrap();
+        arena.alloc(layout(2 * PAGE_SIZE, 8), &mut memory).unwrap();
+        assert_eq!(memory.pages, 4);
+
+        arena.reset();
+
+        // Memory is re-used from the start of the arena without growing
+        assert_eq!(arena.alloc(layout(100, 1), &mut memory).unwrap(), first);
+        arena.alloc(layout(2 * PAGE_SIZE, 8), &mut memory).unwrap();
+        assert_eq!(memory.pages, 4);
+    }
+}
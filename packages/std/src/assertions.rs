@@ -26,8 +26,41 @@
 /// # Ok(())
 /// # }
 /// ```
+///
+/// Instead of an error, a format string with at least one argument can be passed.
+/// This returns a [`StdError::GenericErr`](crate::StdError::GenericErr) with the
+/// formatted message, converted into the function's error type. This works for
+/// any error type implementing `From<StdError>`.
+///
+/// ```
+/// # use cosmwasm_std::{ensure, StdError};
+/// # #[derive(Debug)]
+/// # enum ContractError {
+/// #     Std(StdError),
+/// # }
+/// # impl From<StdError> for ContractError {
+/// #     fn from(err: StdError) -> Self {
+/// #         ContractError::Std(err)
+/// #     }
+/// # }
+/// fn check_amount(amount: u128, max: u128) -> Result<(), ContractError> {
+///     ensure!(amount <= max, "amount {} exceeds maximum of {}", amount, max);
+///     Ok(())
+/// }
+///
+/// assert!(check_amount(7, 10).is_ok());
+/// assert!(matches!(
+///     check_amount(11, 10).unwrap_err(),
+///     ContractError::Std(StdError::GenericErr { msg, .. }) if msg == "amount 11 exceeds maximum of 10"
+/// ));
+/// ```
 #[macro_export]
 macro_rules! ensure {
+    ($cond:expr, $fmt:literal, $($arg:tt)+) => {
+        if !($cond) {
+            return Err(std::convert::From::from($crate::StdError::generic_err(format!($fmt, $($arg)+))));
+        }
+    };
     ($cond:expr, $e:expr) => {
         if !($cond) {
             return Err(std::convert::From::from($e));
@@ -37,6 +70,7 @@ macro_rules! ensure {
 
 /// Quick check for a guard. Like `assert_eq!`, but rather than panic,
 /// it returns the third argument `x` wrapped in `Err(x)`.
+/// Like in [`ensure!`], a format string with arguments can be used instead of an error.
 ///
 /// ```
 /// # use cosmwasm_std::{MessageInfo, Addr};
@@ -65,6 +99,11 @@ macro_rules! ensure {
 /// ```
 #[macro_export]
 macro_rules! ensure_eq {
+    ($a:expr, $b:expr, $fmt:literal, $($arg:tt)+) => {
+        if !($a == $b) {
+            return Err(std::convert::From::from($crate::StdError::generic_err(format!($fmt, $($arg)+))));
+        }
+    };
     ($a:expr, $b:expr, $e:expr) => {
         // Not implemented via `ensure!` because the caller would have to import both macros.
         if !($a == $b) {
@@ -75,6 +114,7 @@ macro_rules! ensure_eq {
 
 /// Quick check for a guard. Like `assert_ne!`, but rather than panic,
 /// it returns the third argument `x` wrapped in Err(x).
+/// Like in [`ensure!`], a format string with arguments can be used instead of an error.
 ///
 /// ```
 /// # enum ContractError {
@@ -97,6 +137,11 @@ macro_rules! ensure_eq {
 /// ```
 #[macro_export]
 macro_rules! ensure_ne {
+    ($a:expr, $b:expr, $fmt:literal, $($arg:tt)+) => {
+        if !($a != $b) {
+            return Err(std::convert::From::from($crate::StdError::generic_err(format!($fmt, $($arg)+))));
+        }
+    };
     ($a:expr, $b:expr, $e:expr) => {
         // Not implemented via `ensure!` because the caller would have to import both macros.
         if !($a != $b) {
@@ -105,6 +150,60 @@ macro_rules! ensure_ne {
     };
 }
 
+/// Returns early with an error. This is the unconditional version of [`ensure!`].
+///
+/// The argument is converted into the function's error type using `From`, so it
+/// works with custom contract error enums as well as with `StdError`.
+///
+/// ```
+/// # #[derive(Debug)]
+/// # enum ContractError {
+/// #     UnknownDenom { denom: String },
+/// # }
+/// #
+/// # fn body(denom: &str) -> Result<(), ContractError> {
+/// use cosmwasm_std::bail;
+///
+/// if denom != "ucosm" {
+///     bail!(ContractError::UnknownDenom { denom: denom.to_string() });
+/// }
+///
+/// // is the same as
+///
+/// if denom != "ucosm" {
+///     return Err(ContractError::UnknownDenom { denom: denom.to_string() });
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A format string with at least one argument returns a
+/// [`StdError::GenericErr`](crate::StdError::GenericErr) converted into the function's error type:
+///
+/// ```
+/// # use cosmwasm_std::{bail, StdError, StdResult};
+/// fn parse_percent(value: u64) -> StdResult<u64> {
+///     if value > 100 {
+///         bail!("{} is not a valid percentage", value);
+///     }
+///     Ok(value)
+/// }
+///
+/// assert_eq!(
+///     parse_percent(101).unwrap_err(),
+///     StdError::generic_err("101 is not a valid percentage")
+/// );
+/// ```
+#[macro_export]
+macro_rules! bail {
+    ($fmt:literal, $($arg:tt)+) => {
+        return Err(std::convert::From::from($crate::StdError::generic_err(format!($fmt, $($arg)+))))
+    };
+    ($e:expr) => {
+        return Err(std::convert::From::from($e))
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use crate::StdError;
@@ -208,4 +307,71 @@ mod tests {
 
         check().unwrap();
     }
+
+    #[test]
+    fn ensure_macros_support_format_strings() {
+        #[derive(Debug)]
+        enum ContractError {
+            Std(StdError),
+        }
+
+        impl From<StdError> for ContractError {
+            fn from(original: StdError) -> Self {
+                ContractError::Std(original)
+            }
+        }
+
+        fn check(a: u32, b: u32) -> Result<(), ContractError> {
+            ensure!(a < 100, "{} is too large", a);
+            ensure_eq!(a % 2, 0, "{} is odd", a);
+            ensure_ne!(a, b, "expected different values, got {} twice", b);
+            Ok(())
+        }
+
+        check(2, 4).unwrap();
+        let ContractError::Std(err) = check(100, 4).unwrap_err();
+        assert_eq!(err, StdError::generic_err("100 is too large"));
+        let ContractError::Std(err) = check(3, 4).unwrap_err();
+        assert_eq!(err, StdError::generic_err("3 is odd"));
+        let ContractError::Std(err) = check(2, 2).unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("expected different values, got 2 twice")
+        );
+    }
+
+    #[test]
+    fn bail_works() {
+        #[derive(Debug, PartialEq)]
+        enum ContractError {
+            Std(StdError),
+            Unauthorized,
+        }
+
+        impl From<StdError> for ContractError {
+            fn from(original: StdError) -> Self {
+                ContractError::Std(original)
+            }
+        }
+
+        fn check(sender: &str) -> Result<u32, ContractError> {
+            match sender {
+                "admin" => Ok(1),
+                "nobody" => bail!(ContractError::Unauthorized),
+                "std" => bail!(StdError::not_found("sender")),
+                other => bail!("unknown sender {}", other),
+            }
+        }
+
+        assert_eq!(check("admin").unwrap(), 1);
+        assert_eq!(check("nobody").unwrap_err(), ContractError::Unauthorized);
+        assert_eq!(
+            check("std").unwrap_err(),
+            ContractError::Std(StdError::not_found("sender"))
+        );
+        assert_eq!(
+            check("alice").unwrap_err(),
+            ContractError::Std(StdError::generic_err("unknown sender alice"))
+        );
+    }
 }
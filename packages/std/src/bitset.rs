@@ -0,0 +1,242 @@
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{de, ser, Deserialize, Deserializer, Serialize};
+
+/// A compact set of `u32` indices, stored as one bit per index.
+///
+/// This is useful for tracking flags of a large number of indices in a single storage entry,
+/// e.g. claims in an airdrop. Index `i` is stored in bit `i % 8` of byte `i / 8`.
+/// Trailing zero bytes are never stored, such that equal sets always have the
+/// same representation.
+///
+/// It is serialized as a base64 string of the underlying bytes.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::BitSet;
+/// let mut claimed = BitSet::new();
+/// assert!(claimed.set(17));
+/// assert!(!claimed.set(17)); // already set
+/// assert!(claimed.get(17));
+/// assert!(!claimed.get(18));
+/// assert_eq!(claimed.count(), 1);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Hash, JsonSchema)]
+pub struct BitSet(#[schemars(with = "String")] Vec<u8>);
+
+impl BitSet {
+    /// Creates an empty set
+    pub fn new() -> Self {
+        BitSet(Vec::new())
+    }
+
+    /// Creates a set from its byte representation.
+    /// Trailing zero bytes are removed.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        let mut set = BitSet(bytes.into());
+        set.trim();
+        set
+    }
+
+    /// Returns the byte representation of the set
+    pub fn as_slice(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+
+    /// Adds the index to the set. Returns `true` if it was not set before.
+    pub fn set(&mut self, index: u32) -> bool {
+        let (byte, mask) = Self::position(index);
+        if byte >= self.0.len() {
+            self.0.resize(byte + 1, 0);
+        }
+        let was_set = self.0[byte] & mask != 0;
+        self.0[byte] |= mask;
+        !was_set
+    }
+
+    /// Removes the index from the set. Returns `true` if it was set before.
+    pub fn unset(&mut self, index: u32) -> bool {
+        let (byte, mask) = Self::position(index);
+        match self.0.get_mut(byte) {
+            Some(value) if *value & mask != 0 => {
+                *value &= !mask;
+                self.trim();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the index is in the set
+    pub fn get(&self, index: u32) -> bool {
+        let (byte, mask) = Self::position(index);
+        matches!(self.0.get(byte), Some(value) if value & mask != 0)
+    }
+
+    /// Returns the number of indices in the set
+    pub fn count(&self) -> u32 {
+        self.0.iter().map(|byte| byte.count_ones()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over all indices in the set in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().enumerate().flat_map(|(byte_index, byte)| {
+            (0..8u32)
+                .filter(move |bit| byte & (1 << bit) != 0)
+                .map(move |bit| byte_index as u32 * 8 + bit)
+        })
+    }
+
+    fn position(index: u32) -> (usize, u8) {
+        ((index / 8) as usize, 1 << (index % 8))
+    }
+
+    /// Removes trailing zero bytes to keep the representation canonical
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<u32> for BitSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        for index in iter {
+            set.set(index);
+        }
+        set
+    }
+}
+
+/// Serializes as a base64 string
+impl Serialize for BitSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&base64::encode(&self.0))
+    }
+}
+
+/// Deserializes as a base64 string
+impl<'de> Deserialize<'de> for BitSet {
+    fn deserialize<D>(deserializer: D) -> Result<BitSet, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(BitSetVisitor)
+    }
+}
+
+struct BitSetVisitor;
+
+impl<'de> de::Visitor<'de> for BitSetVisitor {
+    type Value = BitSet;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("valid base64 encoded string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match base64::decode(v) {
+            Ok(bytes) => Ok(BitSet::from_bytes(bytes)),
+            Err(_) => Err(E::custom(format!("invalid base64: {}", v))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::{from_slice, to_vec};
+
+    #[test]
+    fn set_and_get_work() {
+        let mut set = BitSet::new();
+        assert!(!set.get(0));
+        assert!(set.set(0));
+        assert!(set.set(9));
+        assert!(!set.set(9));
+        assert!(set.get(0));
+        assert!(set.get(9));
+        assert!(!set.get(8));
+        assert!(!set.get(1_000_000));
+        assert_eq!(set.as_slice(), [0b0000_0001, 0b0000_0010]);
+
+        assert!(set.set(u32::MAX));
+        assert!(set.get(u32::MAX));
+    }
+
+    #[test]
+    fn unset_works() {
+        let mut set: BitSet = [3, 20].into_iter().collect();
+        assert!(!set.unset(4));
+        assert!(!set.unset(1_000));
+        assert!(set.unset(20));
+        assert!(!set.get(20));
+        // trailing zero bytes are removed
+        assert_eq!(set.as_slice(), [0b0000_1000]);
+        assert!(set.unset(3));
+        assert!(set.is_empty());
+        assert_eq!(set, BitSet::new());
+    }
+
+    #[test]
+    fn count_works() {
+        assert_eq!(BitSet::new().count(), 0);
+        let set: BitSet = (0..1000).step_by(3).collect();
+        assert_eq!(set.count(), 334);
+    }
+
+    #[test]
+    fn iter_works() {
+        let set: BitSet = [900, 7, 0, 8, 7].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0, 7, 8, 900]);
+        assert_eq!(BitSet::new().iter().count(), 0);
+    }
+
+    #[test]
+    fn from_bytes_trims_trailing_zeros() {
+        let set = BitSet::from_bytes(vec![0b1000_0000, 0, 0]);
+        assert_eq!(set.as_slice(), [0b1000_0000]);
+        assert_eq!(set, [7].into_iter().collect());
+    }
+
+    #[test]
+    fn implements_debug() {
+        let set: BitSet = [1, 42].into_iter().collect();
+        assert_eq!(format!("{:?}", set), "{1, 42}");
+    }
+
+    #[test]
+    fn serialization_works() {
+        let set: BitSet = [0, 9].into_iter().collect();
+        let json = to_vec(&set).unwrap();
+        assert_eq!(json, br#""AQI=""#);
+        let deserialized: BitSet = from_slice(&json).unwrap();
+        assert_eq!(deserialized, set);
+
+        // non-canonical input is normalized
+        let deserialized: BitSet = from_slice(br#""AQIAAA==""#).unwrap();
+        assert_eq!(deserialized, set);
+
+        let err = from_slice::<BitSet>(br#""!!""#).unwrap_err();
+        assert!(err.to_string().contains("invalid base64"));
+    }
+}
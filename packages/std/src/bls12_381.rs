@@ -0,0 +1,69 @@
+/// Length of a compressed BLS12-381 G1 point
+pub const BLS12_381_G1_POINT_LEN: usize = 48;
+
+/// Length of a compressed BLS12-381 G2 point
+pub const BLS12_381_G2_POINT_LEN: usize = 96;
+
+/// The generator of the BLS12-381 G1 group in compressed form
+pub const BLS12_381_G1_GENERATOR: [u8; BLS12_381_G1_POINT_LEN] = [
+    151, 241, 211, 167, 49, 151, 215, 148, 38, 149, 99, 140, 79, 169, 172, 15, 195, 104, 140, 79,
+    151, 116, 185, 5, 161, 78, 58, 63, 23, 27, 172, 88, 108, 85, 232, 63, 249, 122, 26, 239, 251,
+    58, 240, 10, 219, 34, 198, 187,
+];
+
+/// The generator of the BLS12-381 G2 group in compressed form
+pub const BLS12_381_G2_GENERATOR: [u8; BLS12_381_G2_POINT_LEN] = [
+    147, 224, 43, 96, 82, 113, 159, 96, 125, 172, 211, 160, 136, 39, 79, 101, 89, 107, 208, 208,
+    153, 32, 182, 26, 181, 218, 97, 187, 220, 127, 80, 73, 51, 76, 241, 18, 19, 148, 93, 87, 229,
+    172, 125, 5, 93, 4, 43, 126, 2, 74, 162, 178, 240, 143, 10, 145, 38, 8, 5, 39, 45, 197, 16, 81,
+    198, 228, 122, 212, 250, 64, 59, 2, 180, 81, 11, 100, 122, 227, 209, 119, 11, 172, 3, 38, 168,
+    5, 187, 239, 212, 128, 86, 200, 193, 33, 189, 184,
+];
+
+/// The hash function used to hash a message to a BLS12-381 curve point,
+/// see [`Api::bls12_381_hash_to_g1`](crate::Api::bls12_381_hash_to_g1).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HashFunction {
+    Sha256 = 0,
+}
+
+impl HashFunction {
+    /// The numeric representation passed to the VM
+    pub fn to_u32(self) -> u32 {
+        self as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_match_crypto() {
+        assert_eq!(
+            BLS12_381_G1_GENERATOR,
+            cosmwasm_crypto::BLS12_381_G1_GENERATOR
+        );
+        assert_eq!(
+            BLS12_381_G2_GENERATOR,
+            cosmwasm_crypto::BLS12_381_G2_GENERATOR
+        );
+        assert_eq!(
+            BLS12_381_G1_POINT_LEN,
+            cosmwasm_crypto::BLS12_381_G1_POINT_LEN
+        );
+        assert_eq!(
+            BLS12_381_G2_POINT_LEN,
+            cosmwasm_crypto::BLS12_381_G2_POINT_LEN
+        );
+    }
+
+    #[test]
+    fn hash_function_to_u32_matches_crypto() {
+        assert_eq!(
+            HashFunction::Sha256.to_u32(),
+            cosmwasm_crypto::HashFunction::Sha256.to_u32()
+        );
+    }
+}
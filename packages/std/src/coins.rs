@@ -0,0 +1,347 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{de, ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::{CoinsError, StdResult};
+use crate::{Coin, Uint128};
+
+/// A collection of coins with unique denoms, similar to `sdk.Coins` in the Cosmos SDK.
+///
+/// Coins are sorted by denom and coins with a zero amount are removed.
+///
+/// It is serialized as a list of coins but can be deserialized from either a list of coins or
+/// the compact string form of the Cosmos SDK CLI, e.g. `"100uatom,20uosmo"`.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{coin, Coins};
+/// let coins = Coins::try_from("20uosmo,100uatom").unwrap();
+/// assert_eq!(coins.to_vec(), [coin(100, "uatom"), coin(20, "uosmo")]);
+/// assert_eq!(coins.to_string(), "100uatom,20uosmo");
+/// ```
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Coins(BTreeMap<String, Coin>);
+
+impl Coins {
+    /// Returns the coins sorted by denom
+    pub fn to_vec(&self) -> Vec<Coin> {
+        self.0.values().cloned().collect()
+    }
+
+    /// Returns the coins sorted by denom
+    pub fn into_vec(self) -> Vec<Coin> {
+        self.0.into_values().collect()
+    }
+
+    /// Returns the number of denoms
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the denoms in sorted order
+    pub fn denoms(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+
+    /// Returns the amount of the given denom or zero if the denom is not present
+    pub fn amount_of(&self, denom: &str) -> Uint128 {
+        self.0
+            .get(denom)
+            .map(|coin| coin.amount)
+            .unwrap_or_else(Uint128::zero)
+    }
+
+    /// Iterates over the coins sorted by denom
+    pub fn iter(&self) -> impl Iterator<Item = &Coin> {
+        self.0.values()
+    }
+
+    /// Adds the given coin to the collection
+    pub fn add(&mut self, coin: Coin) -> StdResult<()> {
+        if coin.amount.is_zero() {
+            return Ok(());
+        }
+        match self.0.get_mut(&coin.denom) {
+            Some(existing) => {
+                existing.amount = existing.amount.checked_add(coin.amount)?;
+            }
+            None => {
+                self.0.insert(coin.denom.clone(), coin);
+            }
+        }
+        Ok(())
+    }
+
+    /// Subtracts the given coin from the collection.
+    /// Fails if the collection does not contain enough of the denom.
+    pub fn sub(&mut self, coin: Coin) -> StdResult<()> {
+        let remaining = self.amount_of(&coin.denom).checked_sub(coin.amount)?;
+        if remaining.is_zero() {
+            self.0.remove(&coin.denom);
+        } else if let Some(existing) = self.0.get_mut(&coin.denom) {
+            existing.amount = remaining;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<Vec<Coin>> for Coins {
+    type Error = CoinsError;
+
+    /// Fails if a denom occurs more than once. Coins with a zero amount are removed.
+    fn try_from(vec: Vec<Coin>) -> Result<Self, Self::Error> {
+        let mut map = BTreeMap::new();
+        for coin in vec {
+            if coin.amount.is_zero() {
+                continue;
+            }
+            if map.contains_key(&coin.denom) {
+                return Err(CoinsError::DuplicateDenom);
+            }
+            map.insert(coin.denom.clone(), coin);
+        }
+        Ok(Coins(map))
+    }
+}
+
+impl TryFrom<&[Coin]> for Coins {
+    type Error = CoinsError;
+
+    fn try_from(slice: &[Coin]) -> Result<Self, Self::Error> {
+        slice.to_vec().try_into()
+    }
+}
+
+impl TryFrom<&str> for Coins {
+    type Error = CoinsError;
+
+    /// Parses the compact form of the Cosmos SDK CLI, e.g. `"100uatom,20uosmo"`.
+    /// An empty string results in an empty collection.
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(Coins::default());
+        }
+        let vec = s
+            .split(',')
+            .map(|part| Coin::from_str(part.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        vec.try_into()
+    }
+}
+
+impl FromStr for Coins {
+    type Err = CoinsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Coins::try_from(s)
+    }
+}
+
+impl From<Coin> for Coins {
+    fn from(coin: Coin) -> Self {
+        let mut coins = Coins::default();
+        // adding a single coin to an empty collection cannot overflow
+        coins.add(coin).unwrap();
+        coins
+    }
+}
+
+impl From<Coins> for Vec<Coin> {
+    fn from(coins: Coins) -> Self {
+        coins.into_vec()
+    }
+}
+
+impl fmt::Display for Coins {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self.0.values().map(|coin| coin.to_string()).collect();
+        f.write_str(&parts.join(","))
+    }
+}
+
+/// Serializes as a list of coins sorted by denom
+impl Serialize for Coins {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for coin in self.0.values() {
+            seq.serialize_element(coin)?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserializes from a list of coins or from the compact string form
+impl<'de> Deserialize<'de> for Coins {
+    fn deserialize<D>(deserializer: D) -> Result<Coins, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(CoinsVisitor)
+    }
+}
+
+struct CoinsVisitor;
+
+impl<'de> de::Visitor<'de> for CoinsVisitor {
+    type Value = Coins;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of coins or a string like \"100uatom,20uosmo\"")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Coins::try_from(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut vec = Vec::new();
+        while let Some(coin) = seq.next_element::<Coin>()? {
+            vec.push(coin);
+        }
+        Coins::try_from(vec).map_err(de::Error::custom)
+    }
+}
+
+impl JsonSchema for Coins {
+    fn schema_name() -> String {
+        "Coins".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        <Vec<Coin>>::json_schema(gen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coin, from_slice, to_vec, StdError};
+
+    #[test]
+    fn try_from_vec_works() {
+        let coins =
+            Coins::try_from(vec![coin(5, "uosmo"), coin(0, "ujuno"), coin(1, "uatom")]).unwrap();
+        assert_eq!(coins.to_vec(), [coin(1, "uatom"), coin(5, "uosmo")]);
+        assert_eq!(coins.len(), 2);
+        assert_eq!(coins.denoms(), ["uatom", "uosmo"]);
+
+        let err = Coins::try_from(vec![coin(5, "uosmo"), coin(1, "uosmo")]).unwrap_err();
+        assert_eq!(err, CoinsError::DuplicateDenom);
+
+        let coins = Coins::try_from(&[coin(5, "uosmo")][..]).unwrap();
+        assert_eq!(coins.into_vec(), [coin(5, "uosmo")]);
+    }
+
+    #[test]
+    fn try_from_str_works() {
+        let coins = Coins::try_from("100uatom,20uosmo").unwrap();
+        assert_eq!(coins.to_vec(), [coin(100, "uatom"), coin(20, "uosmo")]);
+
+        // whitespace, order and zero amounts
+        let coins = Coins::try_from(" 20uosmo , 100uatom,0ujuno").unwrap();
+        assert_eq!(coins.to_vec(), [coin(100, "uatom"), coin(20, "uosmo")]);
+
+        // IBC denoms
+        let coins: Coins = "7ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            coins.amount_of("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"),
+            Uint128::new(7)
+        );
+
+        assert!(Coins::try_from("").unwrap().is_empty());
+
+        assert_eq!(
+            Coins::try_from("1uatom,2uatom").unwrap_err(),
+            CoinsError::DuplicateDenom
+        );
+        assert!(matches!(
+            Coins::try_from("1uatom,,2uosmo").unwrap_err(),
+            CoinsError::FromStr(_)
+        ));
+        assert!(matches!(
+            Coins::try_from("uatom").unwrap_err(),
+            CoinsError::FromStr(_)
+        ));
+    }
+
+    #[test]
+    fn display_works() {
+        let coins = Coins::try_from("20uosmo,100uatom").unwrap();
+        assert_eq!(coins.to_string(), "100uatom,20uosmo");
+        assert_eq!(Coins::default().to_string(), "");
+
+        // roundtrip
+        assert_eq!(Coins::try_from(coins.to_string().as_str()).unwrap(), coins);
+    }
+
+    #[test]
+    fn add_and_sub_work() {
+        let mut coins = Coins::from(coin(10, "uatom"));
+        coins.add(coin(5, "uatom")).unwrap();
+        coins.add(coin(3, "uosmo")).unwrap();
+        coins.add(coin(0, "ujuno")).unwrap();
+        assert_eq!(coins.to_vec(), [coin(15, "uatom"), coin(3, "uosmo")]);
+
+        coins.sub(coin(3, "uosmo")).unwrap();
+        coins.sub(coin(1, "uatom")).unwrap();
+        assert_eq!(coins.to_vec(), [coin(14, "uatom")]);
+
+        let err = coins.sub(coin(1, "uosmo")).unwrap_err();
+        assert!(matches!(err, StdError::Overflow { .. }));
+        let err = coins.add(coin(u128::MAX, "uatom")).unwrap_err();
+        assert!(matches!(err, StdError::Overflow { .. }));
+    }
+
+    #[test]
+    fn serialization_works() {
+        let coins = Coins::try_from("20uosmo,100uatom").unwrap();
+        let json = to_vec(&coins).unwrap();
+        assert_eq!(
+            String::from_utf8(json.clone()).unwrap(),
+            r#"[{"denom":"uatom","amount":"100"},{"denom":"uosmo","amount":"20"}]"#
+        );
+        assert_eq!(from_slice::<Coins>(&json).unwrap(), coins);
+    }
+
+    #[test]
+    fn deserialization_accepts_string_form() {
+        let coins: Coins = from_slice(br#""100uatom,20uosmo""#).unwrap();
+        assert_eq!(coins.to_vec(), [coin(100, "uatom"), coin(20, "uosmo")]);
+
+        let coins: Coins = from_slice(br#""""#).unwrap();
+        assert!(coins.is_empty());
+
+        match from_slice::<Coins>(br#""1uatom,1uatom""#).unwrap_err() {
+            StdError::ParseErr { msg, .. } => assert!(msg.contains("Duplicate denom")),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        match from_slice::<Coins>(br#"[{"denom":"a","amount":"1"},{"denom":"a","amount":"2"}]"#)
+            .unwrap_err()
+        {
+            StdError::ParseErr { msg, .. } => assert!(msg.contains("Duplicate denom")),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Contract metadata that is embedded into the Wasm blob as a custom section.
+//!
+//! The section contains one `key=value` line per field, terminated by a newline.
+//! The keys are `name`, `version`, `cosmwasm_std_version` and `authors`, in this order.
+//! Multiple authors are separated by `:` like in the `CARGO_PKG_AUTHORS` variable.
+
+/// The name of the Wasm custom section written by [`contract_metadata!`](crate::contract_metadata)
+pub const CONTRACT_METADATA_SECTION: &str = "cosmwasm_metadata";
+
+/// The version of cosmwasm-std the contract is compiled with
+const COSMWASM_STD_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const KEYS: [&str; 4] = ["name", "version", "cosmwasm_std_version", "authors"];
+
+/// Embeds the contract name, version and authors as well as the cosmwasm-std version
+/// into the Wasm blob such that the provenance of stored code can be inspected.
+///
+/// Without arguments, the package name, version and authors are taken from the contract's
+/// Cargo.toml. They can also be set explicitly. The values must not contain newlines.
+///
+/// The section is only written when compiling to Wasm.
+///
+/// ```
+/// // Use the values from Cargo.toml
+/// cosmwasm_std::contract_metadata!();
+/// ```
+///
+/// ```
+/// cosmwasm_std::contract_metadata!(
+///     name = "crates.io:my-contract",
+///     version = "1.0.0",
+///     authors = "Alice <alice@example.com>:Bob <bob@example.com>",
+/// );
+/// ```
+#[macro_export]
+macro_rules! contract_metadata {
+    () => {
+        $crate::contract_metadata!(
+            name = env!("CARGO_PKG_NAME"),
+            version = env!("CARGO_PKG_VERSION"),
+            authors = env!("CARGO_PKG_AUTHORS"),
+        );
+    };
+    (name = $name:expr, version = $version:expr, authors = $authors:expr $(,)?) => {
+        // The static is defined on all targets, such that the encoding can be tested natively
+        #[cfg_attr(target_arch = "wasm32", link_section = "cosmwasm_metadata")]
+        #[cfg_attr(target_arch = "wasm32", used)]
+        #[allow(dead_code)]
+        static __COSMWASM_CONTRACT_METADATA: [u8; $crate::contract_metadata_len(
+            $name, $version, $authors,
+        )] = $crate::encode_contract_metadata($name, $version, $authors);
+    };
+}
+
+/// Returns the length of the encoded metadata. Used by [`contract_metadata!`](crate::contract_metadata).
+#[doc(hidden)]
+pub const fn contract_metadata_len(name: &str, version: &str, authors: &str) -> usize {
+    let values = [name, version, COSMWASM_STD_VERSION, authors];
+    let mut len = 0;
+    let mut i = 0;
+    while i < KEYS.len() {
+        // key, '=', value, '\n'
+        len += KEYS[i].len() + values[i].len() + 2;
+        i += 1;
+    }
+    len
+}
+
+/// Encodes the metadata. Used by [`contract_metadata!`](crate::contract_metadata).
+///
+/// `N` must be the result of [`contract_metadata_len`] for the same arguments.
+#[doc(hidden)]
+pub const fn encode_contract_metadata<const N: usize>(
+    name: &str,
+    version: &str,
+    authors: &str,
+) -> [u8; N] {
+    let values = [name, version, COSMWASM_STD_VERSION, authors];
+    let mut out = [0u8; N];
+    let mut pos = 0;
+    let mut i = 0;
+    while i < KEYS.len() {
+        let key = KEYS[i].as_bytes();
+        let mut j = 0;
+        while j < key.len() {
+            out[pos] = key[j];
+            pos += 1;
+            j += 1;
+        }
+        out[pos] = b'=';
+        pos += 1;
+
+        let value = values[i].as_bytes();
+        let mut j = 0;
+        while j < value.len() {
+            assert!(
+                value[j] != b'\n',
+                "Contract metadata must not contain newlines"
+            );
+            out[pos] = value[j];
+            pos += 1;
+            j += 1;
+        }
+        out[pos] = b'\n';
+        pos += 1;
+        i += 1;
+    }
+    assert!(pos == N, "Length of contract metadata does not match");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_contract_metadata_works() {
+        const LEN: usize = contract_metadata_len("hackatom", "0.1.0", "Alice:Bob");
+        const ENCODED: [u8; LEN] = encode_contract_metadata("hackatom", "0.1.0", "Alice:Bob");
+        let expected = format!(
+            "name=hackatom\nversion=0.1.0\ncosmwasm_std_version={}\nauthors=Alice:Bob\n",
+            COSMWASM_STD_VERSION
+        );
+        assert_eq!(LEN, expected.len());
+        assert_eq!(String::from_utf8(ENCODED.to_vec()).unwrap(), expected);
+
+        // empty values
+        const EMPTY_LEN: usize = contract_metadata_len("", "", "");
+        const EMPTY: [u8; EMPTY_LEN] = encode_contract_metadata("", "", "");
+        assert_eq!(
+            String::from_utf8(EMPTY.to_vec()).unwrap(),
+            format!(
+                "name=\nversion=\ncosmwasm_std_version={}\nauthors=\n",
+                COSMWASM_STD_VERSION
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract metadata must not contain newlines")]
+    fn encode_contract_metadata_panics_for_newlines() {
+        let _: [u8; 100] = encode_contract_metadata("a\nb", "", "");
+    }
+
+    #[test]
+    fn contract_metadata_macro_works() {
+        {
+            crate::contract_metadata!();
+            let expected = format!(
+                "name=cosmwasm-std\nversion={0}\ncosmwasm_std_version={0}\nauthors={1}\n",
+                COSMWASM_STD_VERSION,
+                env!("CARGO_PKG_AUTHORS"),
+            );
+            assert_eq!(__COSMWASM_CONTRACT_METADATA, expected.as_bytes());
+        }
+        {
+            crate::contract_metadata!(name = "foo", version = "1.2.3", authors = "Alice");
+            let expected = format!(
+                "name=foo\nversion=1.2.3\ncosmwasm_std_version={}\nauthors=Alice\n",
+                COSMWASM_STD_VERSION
+            );
+            assert_eq!(__COSMWASM_CONTRACT_METADATA, expected.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::Decimal256;
+
+/// A coin type with decimal amount, as used for distribution rewards.
+/// Modeled after the Cosmos SDK's [DecCoin] type.
+///
+/// [DecCoin]: https://github.com/cosmos/cosmos-sdk/blob/v0.47.4/proto/cosmos/base/v1beta1/coin.proto#L28-L38
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DecCoin {
+    pub denom: String,
+    /// An amount in the base denom of the distributed token.
+    ///
+    /// This uses `Decimal256` because some chains use atto (10^-18) base denominations,
+    /// for which `Decimal` could only hold up to 340.28 tokens.
+    pub amount: Decimal256,
+}
+
+impl DecCoin {
+    pub fn new(amount: impl Into<Decimal256>, denom: impl Into<String>) -> Self {
+        Self {
+            denom: denom.into(),
+            amount: amount.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dec_coin_serializes_to_correct_json() {
+        let coin = DecCoin::new(Decimal256::percent(150), "uatom");
+        let json = crate::to_vec(&coin).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"denom":"uatom","amount":"1.5"}"#
+        );
+    }
+}
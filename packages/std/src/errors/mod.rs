@@ -6,8 +6,8 @@ mod verification_error;
 pub use recover_pubkey_error::RecoverPubkeyError;
 pub use std_error::{
     CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError,
-    CoinFromStrError, ConversionOverflowError, DivideByZeroError, OverflowError, OverflowOperation,
-    RoundUpOverflowError, StdError, StdResult,
+    CoinFromStrError, CoinsError, ConversionOverflowError, DivideByZeroError, OverflowError,
+    OverflowOperation, RoundUpOverflowError, StdError, StdResult,
 };
 pub use system_error::SystemError;
 pub use verification_error::VerificationError;
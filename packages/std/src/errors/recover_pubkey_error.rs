@@ -69,7 +69,11 @@ impl From<CryptoError> for RecoverPubkeyError {
             }
             CryptoError::GenericErr { .. } => RecoverPubkeyError::unknown_err(original.code()),
             CryptoError::InvalidRecoveryParam { .. } => RecoverPubkeyError::InvalidRecoveryParam,
-            CryptoError::BatchErr { .. } => panic!("Conversion not supported"),
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => panic!("Conversion not supported"),
         }
     }
 }
@@ -10,6 +10,10 @@ use crate::errors::{RecoverPubkeyError, VerificationError};
 /// This can be serialized and passed over the Wasm/VM boundary, which allows us to use structured
 /// error types in e.g. integration tests. In that process backtraces are stripped off.
 ///
+/// Errors which wrap another error keep it as their source, such that the full error chain
+/// is available via [`std::error::Error::source`]. With the `backtraces` feature enabled, a
+/// backtrace is captured when the error is created.
+///
 /// The prefix "Std" means "the standard error within the standard library". This is not the only
 /// result/error type in cosmwasm-std.
 ///
@@ -38,6 +42,9 @@ pub enum StdError {
     #[error("Generic error: {msg}")]
     GenericErr {
         msg: String,
+        /// The underlying error, if available. It is not included in the message
+        /// and ignored when comparing errors.
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
@@ -78,6 +85,9 @@ pub enum StdError {
         /// the target type that was attempted
         target_type: String,
         msg: String,
+        /// The underlying error, if available. It is not included in the message
+        /// and ignored when comparing errors.
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
@@ -86,6 +96,9 @@ pub enum StdError {
         /// the source type that was attempted
         source_type: String,
         msg: String,
+        /// The underlying error, if available. It is not included in the message
+        /// and ignored when comparing errors.
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
@@ -130,6 +143,21 @@ impl StdError {
     pub fn generic_err(msg: impl Into<String>) -> Self {
         StdError::GenericErr {
             msg: msg.into(),
+            source: None,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    /// Creates a generic error that keeps the given error as its source, such that
+    /// the full error chain is available via [`std::error::Error::source`].
+    pub fn generic_err_with_source(
+        msg: impl Into<String>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        StdError::GenericErr {
+            msg: msg.into(),
+            source: Some(Box::new(source)),
             #[cfg(feature = "backtraces")]
             backtrace: Backtrace::capture(),
         }
@@ -181,6 +209,22 @@ impl StdError {
         StdError::ParseErr {
             target_type: target.into(),
             msg: msg.to_string(),
+            source: None,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    /// Like [`StdError::parse_err`], but keeps the given error as the source.
+    /// The message is the source's message.
+    pub fn parse_err_with_source(
+        target: impl Into<String>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        StdError::ParseErr {
+            target_type: target.into(),
+            msg: source.to_string(),
+            source: Some(Box::new(source)),
             #[cfg(feature = "backtraces")]
             backtrace: Backtrace::capture(),
         }
@@ -190,6 +234,22 @@ impl StdError {
         StdError::SerializeErr {
             source_type: source.into(),
             msg: msg.to_string(),
+            source: None,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    /// Like [`StdError::serialize_err`], but keeps the given error as the source.
+    /// The message is the source's message.
+    pub fn serialize_err_with_source(
+        source_type: impl Into<String>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        StdError::SerializeErr {
+            source_type: source_type.into(),
+            msg: source.to_string(),
+            source: Some(Box::new(source)),
             #[cfg(feature = "backtraces")]
             backtrace: Backtrace::capture(),
         }
@@ -249,11 +309,13 @@ impl PartialEq<StdError> for StdError {
             }
             StdError::GenericErr {
                 msg,
+                source: _,
                 #[cfg(feature = "backtraces")]
                     backtrace: _,
             } => {
                 if let StdError::GenericErr {
                     msg: rhs_msg,
+                    source: _,
                     #[cfg(feature = "backtraces")]
                         backtrace: _,
                 } = rhs
@@ -348,12 +410,14 @@ impl PartialEq<StdError> for StdError {
             StdError::ParseErr {
                 target_type,
                 msg,
+                source: _,
                 #[cfg(feature = "backtraces")]
                     backtrace: _,
             } => {
                 if let StdError::ParseErr {
                     target_type: rhs_target_type,
                     msg: rhs_msg,
+                    source: _,
                     #[cfg(feature = "backtraces")]
                         backtrace: _,
                 } = rhs
@@ -366,12 +430,14 @@ impl PartialEq<StdError> for StdError {
             StdError::SerializeErr {
                 source_type,
                 msg,
+                source: _,
                 #[cfg(feature = "backtraces")]
                     backtrace: _,
             } => {
                 if let StdError::SerializeErr {
                     source_type: rhs_source_type,
                     msg: rhs_msg,
+                    source: _,
                     #[cfg(feature = "backtraces")]
                         backtrace: _,
                 } = rhs
@@ -612,6 +678,20 @@ impl From<CoinFromStrError> for StdError {
     }
 }
 
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CoinsError {
+    #[error("Duplicate denom")]
+    DuplicateDenom,
+    #[error("{0}")]
+    FromStr(#[from] CoinFromStrError),
+}
+
+impl From<CoinsError> for StdError {
+    fn from(value: CoinsError) -> Self {
+        Self::generic_err(format!("Creating Coins: {}", value))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -762,6 +842,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn errors_with_source_work() {
+        use std::error::Error;
+
+        let utf8_err = String::from_utf8(vec![0xff]).unwrap_err();
+        let parse_err = StdError::parse_err_with_source("Book", utf8_err.clone());
+        assert_eq!(
+            parse_err,
+            StdError::parse_err("Book", "invalid utf-8 sequence of 1 bytes from index 0")
+        );
+        assert_eq!(
+            parse_err.source().unwrap().to_string(),
+            utf8_err.to_string()
+        );
+
+        // errors can be chained across multiple layers
+        let outer = StdError::generic_err_with_source("Loading config failed", parse_err);
+        assert_eq!(outer.to_string(), "Generic error: Loading config failed");
+        let inner = outer.source().unwrap();
+        assert_eq!(
+            inner.to_string(),
+            "Error parsing into type Book: invalid utf-8 sequence of 1 bytes from index 0"
+        );
+        assert!(inner.source().unwrap().is::<std::string::FromUtf8Error>());
+
+        let serialize_err = StdError::serialize_err_with_source("Book", utf8_err);
+        assert!(serialize_err.source().is_some());
+
+        // errors created without a source don't have one
+        assert!(StdError::generic_err("foo").source().is_none());
+        assert!(StdError::parse_err("Book", "bar").source().is_none());
+    }
+
     #[test]
     fn underflow_works_for_u128() {
         let error =
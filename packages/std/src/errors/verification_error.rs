@@ -20,6 +20,14 @@ pub enum VerificationError {
     InvalidPubkeyFormat,
     #[error("Invalid recovery parameter. Supported values: 0 and 1.")]
     InvalidRecoveryParam,
+    #[error("Invalid point")]
+    InvalidPoint,
+    #[error("Unequal number of points")]
+    UnequalPointAmount,
+    #[error("Unknown hash function")]
+    UnknownHashFunction,
+    #[error("Invalid scalar")]
+    InvalidScalar,
     #[error("Unknown error: {error_code}")]
     UnknownErr {
         error_code: u32,
@@ -55,6 +63,14 @@ impl PartialEq<VerificationError> for VerificationError {
             VerificationError::InvalidRecoveryParam => {
                 matches!(rhs, VerificationError::InvalidRecoveryParam)
             }
+            VerificationError::InvalidPoint => matches!(rhs, VerificationError::InvalidPoint),
+            VerificationError::UnequalPointAmount => {
+                matches!(rhs, VerificationError::UnequalPointAmount)
+            }
+            VerificationError::UnknownHashFunction => {
+                matches!(rhs, VerificationError::UnknownHashFunction)
+            }
+            VerificationError::InvalidScalar => matches!(rhs, VerificationError::InvalidScalar),
             VerificationError::UnknownErr { error_code, .. } => {
                 if let VerificationError::UnknownErr {
                     error_code: rhs_error_code,
@@ -80,6 +96,10 @@ impl From<CryptoError> for VerificationError {
             CryptoError::GenericErr { .. } => VerificationError::GenericErr,
             CryptoError::InvalidRecoveryParam { .. } => VerificationError::InvalidRecoveryParam,
             CryptoError::BatchErr { .. } => VerificationError::BatchErr,
+            CryptoError::InvalidPoint { .. } => VerificationError::InvalidPoint,
+            CryptoError::UnequalPointAmount { .. } => VerificationError::UnequalPointAmount,
+            CryptoError::UnknownHashFunction { .. } => VerificationError::UnknownHashFunction,
+            CryptoError::InvalidScalar { .. } => VerificationError::InvalidScalar,
         }
     }
 }
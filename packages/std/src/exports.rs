@@ -2,9 +2,10 @@
 //!
 //! interface_version_8, allocate and deallocate turn into Wasm exports
 //! as soon as cosmwasm_std is `use`d in the contract, even privately.
+//! With the `arena_allocator` feature, an additional cosmwasm_arena_reset export is created.
 //!
-//! `do_execute`, `do_instantiate`, `do_migrate`, `do_query`, `do_reply`
-//! and `do_sudo` should be wrapped with a extern "C" entry point including
+//! `do_execute`, `do_instantiate`, `do_migrate`, `do_migrate_with_info`, `do_query`,
+//! `do_reply` and `do_sudo` should be wrapped with a extern "C" entry point including
 //! the contract-specific function pointer. This is done via the `#[entry_point]`
 //! macro attribute from cosmwasm-derive.
 use std::marker::PhantomData;
@@ -19,14 +20,19 @@ use crate::ibc::{
     IbcChannelOpenResponse, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
     IbcReceiveResponse,
 };
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+use crate::ibc::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
 use crate::imports::{ExternalApi, ExternalQuerier, ExternalStorage};
 use crate::memory::{alloc, consume_region, release_buffer, Region};
 #[cfg(feature = "abort")]
 use crate::panic::install_panic_handler;
 use crate::query::CustomQuery;
-use crate::results::{ContractResult, QueryResponse, Reply, Response};
+use crate::results::{ContractResult, QueryResponse, Reply, ReplyPayload, Response};
 use crate::serde::{from_slice, to_vec};
 use crate::types::Env;
+#[cfg(feature = "cosmwasm_1_5")]
+use crate::types::MigrateInfo;
+use crate::validation::validate_messages;
 use crate::{CustomMsg, Deps, DepsMut, MessageInfo};
 
 #[cfg(feature = "iterator")]
@@ -41,6 +47,22 @@ extern "C" fn requires_staking() -> () {}
 #[no_mangle]
 extern "C" fn requires_stargate() -> () {}
 
+#[cfg(feature = "feegrant")]
+#[no_mangle]
+extern "C" fn requires_feegrant() -> () {}
+
+#[cfg(feature = "authz")]
+#[no_mangle]
+extern "C" fn requires_authz() -> () {}
+
+#[cfg(feature = "zk")]
+#[no_mangle]
+extern "C" fn requires_zk() -> () {}
+
+#[cfg(feature = "sr25519")]
+#[no_mangle]
+extern "C" fn requires_sr25519() -> () {}
+
 #[cfg(feature = "cosmwasm_1_1")]
 #[no_mangle]
 extern "C" fn requires_cosmwasm_1_1() -> () {}
@@ -49,6 +71,18 @@ extern "C" fn requires_cosmwasm_1_1() -> () {}
 #[no_mangle]
 extern "C" fn requires_cosmwasm_1_2() -> () {}
 
+#[cfg(feature = "cosmwasm_1_3")]
+#[no_mangle]
+extern "C" fn requires_cosmwasm_1_3() -> () {}
+
+#[cfg(feature = "cosmwasm_1_4")]
+#[no_mangle]
+extern "C" fn requires_cosmwasm_1_4() -> () {}
+
+#[cfg(feature = "cosmwasm_1_5")]
+#[no_mangle]
+extern "C" fn requires_cosmwasm_1_5() -> () {}
+
 /// interface_version_* exports mark which Wasm VM interface level this contract is compiled for.
 /// They can be checked by cosmwasm_vm.
 /// Update this whenever the Wasm VM interface breaks.
@@ -71,6 +105,14 @@ extern "C" fn deallocate(pointer: u32) {
     let _ = unsafe { consume_region(pointer as *mut Region) };
 }
 
+/// cosmwasm_arena_reset releases all memory of the arena allocator at once. It is called by the VM
+/// before every entry point call, i.e. when no memory allocated by the contract is in use.
+#[cfg(feature = "arena_allocator")]
+#[no_mangle]
+extern "C" fn cosmwasm_arena_reset() {
+    unsafe { crate::arena::reset() };
+}
+
 // TODO: replace with https://doc.rust-lang.org/std/ops/trait.Try.html once stabilized
 macro_rules! r#try_into_contract_result {
     ($expr:expr) => {
@@ -170,6 +212,38 @@ where
     release_buffer(v) as u32
 }
 
+/// do_migrate_with_info should be wrapped in an external "C" export, containing a contract-specific function as arg.
+/// Like do_migrate, but for `migrate` functions taking a [`MigrateInfo`] as additional argument.
+///
+/// - `Q`: custom query type (see QueryRequest)
+/// - `M`: message type for request
+/// - `C`: custom response message type (see CosmosMsg)
+/// - `E`: error type for responses
+#[cfg(feature = "cosmwasm_1_5")]
+pub fn do_migrate_with_info<Q, M, C, E>(
+    migrate_fn: &dyn Fn(DepsMut<Q>, Env, M, MigrateInfo) -> Result<Response<C>, E>,
+    env_ptr: u32,
+    msg_ptr: u32,
+    migrate_info_ptr: u32,
+) -> u32
+where
+    Q: CustomQuery,
+    M: DeserializeOwned,
+    C: CustomMsg,
+    E: ToString,
+{
+    #[cfg(feature = "abort")]
+    install_panic_handler();
+    let res = _do_migrate_with_info(
+        migrate_fn,
+        env_ptr as *mut Region,
+        msg_ptr as *mut Region,
+        migrate_info_ptr as *mut Region,
+    );
+    let v = to_vec(&res).unwrap();
+    release_buffer(v) as u32
+}
+
 /// do_sudo should be wrapped in an external "C" export, containing a contract-specific function as arg
 ///
 /// - `Q`: custom query type (see QueryRequest)
@@ -198,15 +272,17 @@ where
 /// message body is always `SubcallResult`
 ///
 /// - `Q`: custom query type (see QueryRequest)
+/// - `P`: payload type of the reply, see [`ReplyPayload`]
 /// - `C`: custom response message type (see CosmosMsg)
 /// - `E`: error type for responses
-pub fn do_reply<Q, C, E>(
-    reply_fn: &dyn Fn(DepsMut<Q>, Env, Reply) -> Result<Response<C>, E>,
+pub fn do_reply<Q, P, C, E>(
+    reply_fn: &dyn Fn(DepsMut<Q>, Env, Reply<P>) -> Result<Response<C>, E>,
     env_ptr: u32,
     msg_ptr: u32,
 ) -> u32
 where
     Q: CustomQuery,
+    P: ReplyPayload,
     C: CustomMsg,
     E: ToString,
 {
@@ -391,6 +467,63 @@ where
     release_buffer(v) as u32
 }
 
+/// do_ibc_source_callback is designed for use with #[entry_point] to make a "C" extern
+///
+/// contract_fn is called when a packet for which this contract requested a source callback
+/// (see [`IbcSourceCallbackMsg`]) was acknowledged or timed out.
+///
+/// - `Q`: custom query type (see QueryRequest)
+/// - `C`: custom response message type (see CosmosMsg)
+/// - `E`: error type for responses
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub fn do_ibc_source_callback<Q, C, E>(
+    contract_fn: &dyn Fn(DepsMut<Q>, Env, IbcSourceCallbackMsg) -> Result<IbcBasicResponse<C>, E>,
+    env_ptr: u32,
+    msg_ptr: u32,
+) -> u32
+where
+    Q: CustomQuery,
+    C: CustomMsg,
+    E: ToString,
+{
+    #[cfg(feature = "abort")]
+    install_panic_handler();
+    let res = _do_ibc_source_callback(contract_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
+    let v = to_vec(&res).unwrap();
+    release_buffer(v) as u32
+}
+
+/// do_ibc_destination_callback is designed for use with #[entry_point] to make a "C" extern
+///
+/// contract_fn is called when a packet for which the sender requested a destination callback
+/// to this contract (see [`IbcDestinationCallbackMsg`]) was received and acknowledged.
+///
+/// - `Q`: custom query type (see QueryRequest)
+/// - `C`: custom response message type (see CosmosMsg)
+/// - `E`: error type for responses
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub fn do_ibc_destination_callback<Q, C, E>(
+    contract_fn: &dyn Fn(
+        DepsMut<Q>,
+        Env,
+        IbcDestinationCallbackMsg,
+    ) -> Result<IbcBasicResponse<C>, E>,
+    env_ptr: u32,
+    msg_ptr: u32,
+) -> u32
+where
+    Q: CustomQuery,
+    C: CustomMsg,
+    E: ToString,
+{
+    #[cfg(feature = "abort")]
+    install_panic_handler();
+    let res =
+        _do_ibc_destination_callback(contract_fn, env_ptr as *mut Region, msg_ptr as *mut Region);
+    let v = to_vec(&res).unwrap();
+    release_buffer(v) as u32
+}
+
 fn _do_instantiate<Q, M, C, E>(
     instantiate_fn: &dyn Fn(DepsMut<Q>, Env, MessageInfo, M) -> Result<Response<C>, E>,
     env_ptr: *mut Region,
@@ -412,7 +545,9 @@ where
     let msg: M = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    instantiate_fn(deps.as_mut(), env, info, msg).into()
+    let response = try_into_contract_result!(instantiate_fn(deps.as_mut(), env, info, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 fn _do_execute<Q, M, C, E>(
@@ -436,7 +571,9 @@ where
     let msg: M = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    execute_fn(deps.as_mut(), env, info, msg).into()
+    let response = try_into_contract_result!(execute_fn(deps.as_mut(), env, info, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 fn _do_migrate<Q, M, C, E>(
@@ -457,7 +594,36 @@ where
     let msg: M = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    migrate_fn(deps.as_mut(), env, msg).into()
+    let response = try_into_contract_result!(migrate_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
+}
+
+#[cfg(feature = "cosmwasm_1_5")]
+fn _do_migrate_with_info<Q, M, C, E>(
+    migrate_fn: &dyn Fn(DepsMut<Q>, Env, M, MigrateInfo) -> Result<Response<C>, E>,
+    env_ptr: *mut Region,
+    msg_ptr: *mut Region,
+    migrate_info_ptr: *mut Region,
+) -> ContractResult<Response<C>>
+where
+    Q: CustomQuery,
+    M: DeserializeOwned,
+    C: CustomMsg,
+    E: ToString,
+{
+    let env: Vec<u8> = unsafe { consume_region(env_ptr) };
+    let msg: Vec<u8> = unsafe { consume_region(msg_ptr) };
+    let migrate_info: Vec<u8> = unsafe { consume_region(migrate_info_ptr) };
+
+    let env: Env = try_into_contract_result!(from_slice(&env));
+    let msg: M = try_into_contract_result!(from_slice(&msg));
+    let migrate_info: MigrateInfo = try_into_contract_result!(from_slice(&migrate_info));
+
+    let mut deps = make_dependencies();
+    let response = try_into_contract_result!(migrate_fn(deps.as_mut(), env, msg, migrate_info));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 fn _do_sudo<Q, M, C, E>(
@@ -478,16 +644,19 @@ where
     let msg: M = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    sudo_fn(deps.as_mut(), env, msg).into()
+    let response = try_into_contract_result!(sudo_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
-fn _do_reply<Q, C, E>(
-    reply_fn: &dyn Fn(DepsMut<Q>, Env, Reply) -> Result<Response<C>, E>,
+fn _do_reply<Q, P, C, E>(
+    reply_fn: &dyn Fn(DepsMut<Q>, Env, Reply<P>) -> Result<Response<C>, E>,
     env_ptr: *mut Region,
     msg_ptr: *mut Region,
 ) -> ContractResult<Response<C>>
 where
     Q: CustomQuery,
+    P: ReplyPayload,
     C: CustomMsg,
     E: ToString,
 {
@@ -496,9 +665,12 @@ where
 
     let env: Env = try_into_contract_result!(from_slice(&env));
     let msg: Reply = try_into_contract_result!(from_slice(&msg));
+    let msg: Reply<P> = try_into_contract_result!(P::from_reply(msg));
 
     let mut deps = make_dependencies();
-    reply_fn(deps.as_mut(), env, msg).into()
+    let response = try_into_contract_result!(reply_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 fn _do_query<Q, M, E>(
@@ -559,7 +731,9 @@ where
     let msg: IbcChannelConnectMsg = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    contract_fn(deps.as_mut(), env, msg).into()
+    let response = try_into_contract_result!(contract_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 #[cfg(feature = "stargate")]
@@ -580,7 +754,9 @@ where
     let msg: IbcChannelCloseMsg = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    contract_fn(deps.as_mut(), env, msg).into()
+    let response = try_into_contract_result!(contract_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 #[cfg(feature = "stargate")]
@@ -601,7 +777,9 @@ where
     let msg: IbcPacketReceiveMsg = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    contract_fn(deps.as_mut(), env, msg).into()
+    let response = try_into_contract_result!(contract_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 #[cfg(feature = "stargate")]
@@ -622,7 +800,9 @@ where
     let msg: IbcPacketAckMsg = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    contract_fn(deps.as_mut(), env, msg).into()
+    let response = try_into_contract_result!(contract_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 #[cfg(feature = "stargate")]
@@ -643,7 +823,59 @@ where
     let msg: IbcPacketTimeoutMsg = try_into_contract_result!(from_slice(&msg));
 
     let mut deps = make_dependencies();
-    contract_fn(deps.as_mut(), env, msg).into()
+    let response = try_into_contract_result!(contract_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
+}
+
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+fn _do_ibc_source_callback<Q, C, E>(
+    contract_fn: &dyn Fn(DepsMut<Q>, Env, IbcSourceCallbackMsg) -> Result<IbcBasicResponse<C>, E>,
+    env_ptr: *mut Region,
+    msg_ptr: *mut Region,
+) -> ContractResult<IbcBasicResponse<C>>
+where
+    Q: CustomQuery,
+    C: CustomMsg,
+    E: ToString,
+{
+    let env: Vec<u8> = unsafe { consume_region(env_ptr) };
+    let msg: Vec<u8> = unsafe { consume_region(msg_ptr) };
+
+    let env: Env = try_into_contract_result!(from_slice(&env));
+    let msg: IbcSourceCallbackMsg = try_into_contract_result!(from_slice(&msg));
+
+    let mut deps = make_dependencies();
+    let response = try_into_contract_result!(contract_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
+}
+
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+fn _do_ibc_destination_callback<Q, C, E>(
+    contract_fn: &dyn Fn(
+        DepsMut<Q>,
+        Env,
+        IbcDestinationCallbackMsg,
+    ) -> Result<IbcBasicResponse<C>, E>,
+    env_ptr: *mut Region,
+    msg_ptr: *mut Region,
+) -> ContractResult<IbcBasicResponse<C>>
+where
+    Q: CustomQuery,
+    C: CustomMsg,
+    E: ToString,
+{
+    let env: Vec<u8> = unsafe { consume_region(env_ptr) };
+    let msg: Vec<u8> = unsafe { consume_region(msg_ptr) };
+
+    let env: Env = try_into_contract_result!(from_slice(&env));
+    let msg: IbcDestinationCallbackMsg = try_into_contract_result!(from_slice(&msg));
+
+    let mut deps = make_dependencies();
+    let response = try_into_contract_result!(contract_fn(deps.as_mut(), env, msg));
+    try_into_contract_result!(validate_messages(&response.messages));
+    ContractResult::Ok(response)
 }
 
 /// Makes all bridges to external dependencies (i.e. Wasm imports) that are injected by the VM
@@ -6,7 +6,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::cmp::{Ord, Ordering, PartialOrd};
 
-#[cfg(feature = "ibc3")]
+#[cfg(any(feature = "ibc3", feature = "cosmwasm_1_4"))]
 use crate::addresses::Addr;
 use crate::binary::Binary;
 use crate::coin::Coin;
@@ -36,6 +36,13 @@ pub enum IbcMsg {
         amount: Coin,
         /// when packet times out, measured on remote chain
         timeout: IbcTimeout,
+        /// An optional memo included in the ICS-20 packet data. This is used e.g. by IBC hooks
+        /// and packet-forward middleware to trigger actions on the receiving chain.
+        ///
+        /// There is no difference between an empty memo and no memo.
+        #[cfg(feature = "cosmwasm_1_4")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memo: Option<String>,
     },
     /// Sends an IBC packet with given data over the existing channel.
     /// Data should be encoded in a format defined by the channel version,
@@ -49,6 +56,60 @@ pub enum IbcMsg {
     /// This will close an existing channel that is owned by this contract.
     /// Port is auto-assigned to the contract's IBC port
     CloseChannel { channel_id: String },
+    /// Incentivizes the next IBC packet sent after this message with a fee (ICS-29).
+    /// Note that this does not necessarily have to be a packet sent by this contract.
+    /// The fees are taken from the contract's balance immediately and locked until the
+    /// packet is handled.
+    ///
+    /// The channel must be fee-enabled, see [`IbcChannel::fee_version`].
+    #[cfg(feature = "cosmwasm_1_4")]
+    PayPacketFee {
+        /// The port id on the chain where the packet is sent from (this chain).
+        port_id: String,
+        /// The channel id on the chain where the packet is sent from (this chain).
+        channel_id: String,
+        fee: IbcFee,
+        /// Allowlist of relayer addresses that can receive the fee.
+        /// An empty list means that any relayer can receive the fee.
+        ///
+        /// This is currently not implemented and *must* be empty.
+        relayers: Vec<String>,
+    },
+    /// Incentivizes an already sent IBC packet with a fee (ICS-29), e.g. when the
+    /// original fee turns out to be too low for relayers.
+    /// The fees are taken from the contract's balance immediately and locked until the
+    /// packet is handled.
+    #[cfg(feature = "cosmwasm_1_4")]
+    PayPacketFeeAsync {
+        /// The port id on the chain where the packet is sent from (this chain).
+        port_id: String,
+        /// The channel id on the chain where the packet is sent from (this chain).
+        channel_id: String,
+        /// The sequence number of the packet that should be incentivized.
+        sequence: u64,
+        fee: IbcFee,
+        /// Allowlist of relayer addresses that can receive the fee.
+        /// An empty list means that any relayer can receive the fee.
+        ///
+        /// This is currently not implemented and *must* be empty.
+        relayers: Vec<String>,
+    },
+}
+
+/// The fees paid to relayers for handling a packet (ICS-29).
+///
+/// The receive fee and either the acknowledgement or the timeout fee are paid out,
+/// the remainder is refunded to the payer.
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IbcFee {
+    /// The fee paid to the relayer which relays the packet to the counterparty chain.
+    /// This is paid on acknowledgement only.
+    pub receive_fee: Vec<Coin>,
+    /// The fee paid to the relayer which relays the acknowledgement back to this chain.
+    pub ack_fee: Vec<Coin>,
+    /// The fee paid to the relayer which relays the timeout back to this chain.
+    pub timeout_fee: Vec<Coin>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -126,6 +187,13 @@ pub struct IbcChannel {
     /// The connection upon which this channel was created. If this is a multi-hop
     /// channel, we only expose the first hop.
     pub connection_id: String,
+    /// The fee version (e.g. "ics29-1") if this channel is fee-enabled (ICS-29).
+    /// In that case `version` is the version of the underlying application.
+    /// This is `None` for channels without relayer incentivization and for hosts
+    /// which do not report it.
+    #[cfg(feature = "cosmwasm_1_4")]
+    #[serde(default)]
+    pub fee_version: Option<String>,
 }
 
 impl IbcChannel {
@@ -143,8 +211,24 @@ impl IbcChannel {
             order,
             version: version.into(),
             connection_id: connection_id.into(),
+            #[cfg(feature = "cosmwasm_1_4")]
+            fee_version: None,
         }
     }
+
+    /// Sets the fee version of a fee-enabled (ICS-29) channel
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn with_fee_version(mut self, fee_version: impl Into<String>) -> Self {
+        self.fee_version = Some(fee_version.into());
+        self
+    }
+
+    /// Returns true if relayers on this channel can be incentivized using
+    /// `IbcMsg::PayPacketFee` and `IbcMsg::PayPacketFeeAsync`
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn is_fee_enabled(&self) -> bool {
+        self.fee_version.is_some()
+    }
 }
 
 /// IbcOrder defines if a channel is ORDERED or UNORDERED
@@ -474,6 +558,82 @@ impl IbcPacketTimeoutMsg {
     }
 }
 
+/// The message that is passed into `ibc_source_callback` (IBC callbacks, ADR-8).
+///
+/// A contract which sends a packet, e.g. an ICS-20 transfer using [`IbcMsg::Transfer`], can
+/// request this callback by adding `{"src_callback": {"address": "<contract address>"}}` to
+/// the memo. Once the packet is acknowledged or times out, the contract is called with the
+/// result, even though it does not own the channel.
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcSourceCallbackMsg {
+    Acknowledgement(IbcAckCallbackMsg),
+    Timeout(IbcTimeoutCallbackMsg),
+}
+
+/// The acknowledgement of a packet for which the contract requested a source callback
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[non_exhaustive]
+pub struct IbcAckCallbackMsg {
+    pub acknowledgement: IbcAcknowledgement,
+    pub original_packet: IbcPacket,
+    pub relayer: Addr,
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl IbcAckCallbackMsg {
+    pub fn new(
+        acknowledgement: IbcAcknowledgement,
+        original_packet: IbcPacket,
+        relayer: Addr,
+    ) -> Self {
+        Self {
+            acknowledgement,
+            original_packet,
+            relayer,
+        }
+    }
+}
+
+/// The timeout of a packet for which the contract requested a source callback
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[non_exhaustive]
+pub struct IbcTimeoutCallbackMsg {
+    pub packet: IbcPacket,
+    pub relayer: Addr,
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl IbcTimeoutCallbackMsg {
+    pub fn new(packet: IbcPacket, relayer: Addr) -> Self {
+        Self { packet, relayer }
+    }
+}
+
+/// The message that is passed into `ibc_destination_callback` (IBC callbacks, ADR-8).
+///
+/// The sender of a packet can request this callback on the receiving chain by adding
+/// `{"dest_callback": {"address": "<contract address>"}}` to the memo. The contract is
+/// called after the packet was received and acknowledged by the receiving module,
+/// e.g. after the tokens of an ICS-20 transfer arrived.
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[non_exhaustive]
+pub struct IbcDestinationCallbackMsg {
+    pub packet: IbcPacket,
+    pub ack: IbcAcknowledgement,
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl IbcDestinationCallbackMsg {
+    pub fn new(packet: IbcPacket, ack: IbcAcknowledgement) -> Self {
+        Self { packet, ack }
+    }
+}
+
 /// This is the return value for the majority of the ibc handlers.
 /// That are able to dispatch messages / events on their own,
 /// but have no meaningful return value to the calling code.
@@ -783,12 +943,138 @@ mod tests {
             to_address: "my-special-addr".into(),
             amount: Coin::new(12345678, "uatom"),
             timeout: IbcTimeout::with_timestamp(Timestamp::from_nanos(1234567890)),
+            #[cfg(feature = "cosmwasm_1_4")]
+            memo: None,
         };
         let encoded = to_string(&msg).unwrap();
         let expected = r#"{"transfer":{"channel_id":"channel-123","to_address":"my-special-addr","amount":{"denom":"uatom","amount":"12345678"},"timeout":{"block":null,"timestamp":"1234567890"}}}"#;
         assert_eq!(encoded.as_str(), expected);
     }
 
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn serialize_transfer_with_memo() {
+        let msg = IbcMsg::Transfer {
+            channel_id: "channel-123".to_string(),
+            to_address: "my-special-addr".into(),
+            amount: Coin::new(12345678, "uatom"),
+            timeout: IbcTimeout::with_timestamp(Timestamp::from_nanos(1234567890)),
+            memo: Some(r#"{"wasm":{"contract":"cosmos1contract","msg":{}}}"#.to_string()),
+        };
+        let encoded = to_string(&msg).unwrap();
+        let expected = r#"{"transfer":{"channel_id":"channel-123","to_address":"my-special-addr","amount":{"denom":"uatom","amount":"12345678"},"timeout":{"block":null,"timestamp":"1234567890"},"memo":"{\"wasm\":{\"contract\":\"cosmos1contract\",\"msg\":{}}}"}}"#;
+        assert_eq!(encoded.as_str(), expected);
+        assert_eq!(serde_json_wasm::from_str::<IbcMsg>(expected).unwrap(), msg);
+
+        // memo is optional when deserializing
+        let json = r#"{"transfer":{"channel_id":"channel-123","to_address":"my-special-addr","amount":{"denom":"uatom","amount":"12345678"},"timeout":{"block":null,"timestamp":"1234567890"}}}"#;
+        match serde_json_wasm::from_str::<IbcMsg>(json).unwrap() {
+            IbcMsg::Transfer { memo, .. } => assert_eq!(memo, None),
+            msg => panic!("Unexpected message: {:?}", msg),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn serialize_pay_packet_fee() {
+        let fee = IbcFee {
+            receive_fee: vec![Coin::new(100, "ucosm")],
+            ack_fee: vec![],
+            timeout_fee: vec![Coin::new(50, "ucosm")],
+        };
+        let msg = IbcMsg::PayPacketFee {
+            port_id: "transfer".to_string(),
+            channel_id: "channel-0".to_string(),
+            fee: fee.clone(),
+            relayers: vec![],
+        };
+        let expected = r#"{"pay_packet_fee":{"port_id":"transfer","channel_id":"channel-0","fee":{"receive_fee":[{"denom":"ucosm","amount":"100"}],"ack_fee":[],"timeout_fee":[{"denom":"ucosm","amount":"50"}]},"relayers":[]}}"#;
+        assert_eq!(to_string(&msg).unwrap(), expected);
+
+        let msg = IbcMsg::PayPacketFeeAsync {
+            port_id: "transfer".to_string(),
+            channel_id: "channel-0".to_string(),
+            sequence: 7,
+            fee,
+            relayers: vec![],
+        };
+        let expected = r#"{"pay_packet_fee_async":{"port_id":"transfer","channel_id":"channel-0","sequence":7,"fee":{"receive_fee":[{"denom":"ucosm","amount":"100"}],"ack_fee":[],"timeout_fee":[{"denom":"ucosm","amount":"50"}]},"relayers":[]}}"#;
+        assert_eq!(to_string(&msg).unwrap(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn serialize_callback_msgs() {
+        let packet = IbcPacket::new(
+            b"foo",
+            IbcEndpoint {
+                port_id: "transfer".to_string(),
+                channel_id: "channel-0".to_string(),
+            },
+            IbcEndpoint {
+                port_id: "transfer".to_string(),
+                channel_id: "channel-7".to_string(),
+            },
+            3,
+            IbcTimeout::with_timestamp(Timestamp::from_nanos(1234567890)),
+        );
+        let packet_json = r#"{"data":"Zm9v","src":{"port_id":"transfer","channel_id":"channel-0"},"dest":{"port_id":"transfer","channel_id":"channel-7"},"sequence":3,"timeout":{"block":null,"timestamp":"1234567890"}}"#;
+
+        let msg = IbcSourceCallbackMsg::Acknowledgement(IbcAckCallbackMsg::new(
+            IbcAcknowledgement::new(b"ack"),
+            packet.clone(),
+            Addr::unchecked("relayer"),
+        ));
+        let expected = format!(
+            r#"{{"acknowledgement":{{"acknowledgement":{{"data":"YWNr"}},"original_packet":{},"relayer":"relayer"}}}}"#,
+            packet_json
+        );
+        assert_eq!(to_string(&msg).unwrap(), expected);
+        assert_eq!(
+            serde_json_wasm::from_str::<IbcSourceCallbackMsg>(&expected).unwrap(),
+            msg
+        );
+
+        let msg = IbcSourceCallbackMsg::Timeout(IbcTimeoutCallbackMsg::new(
+            packet.clone(),
+            Addr::unchecked("relayer"),
+        ));
+        let expected = format!(
+            r#"{{"timeout":{{"packet":{},"relayer":"relayer"}}}}"#,
+            packet_json
+        );
+        assert_eq!(to_string(&msg).unwrap(), expected);
+
+        let msg = IbcDestinationCallbackMsg::new(packet, IbcAcknowledgement::new(b"ack"));
+        let expected = format!(r#"{{"packet":{},"ack":{{"data":"YWNr"}}}}"#, packet_json);
+        assert_eq!(to_string(&msg).unwrap(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn ibc_channel_fee_version_works() {
+        let endpoint = IbcEndpoint {
+            port_id: "port".to_string(),
+            channel_id: "channel-1".to_string(),
+        };
+        let channel = IbcChannel::new(
+            endpoint.clone(),
+            endpoint,
+            IbcOrder::Unordered,
+            "ics20-1",
+            "connection-0",
+        );
+        assert!(!channel.is_fee_enabled());
+        let channel = channel.with_fee_version("ics29-1");
+        assert!(channel.is_fee_enabled());
+        assert_eq!(channel.fee_version.as_deref(), Some("ics29-1"));
+
+        // hosts not knowing about fees do not send the field
+        let json = r#"{"endpoint":{"port_id":"port","channel_id":"channel-1"},"counterparty_endpoint":{"port_id":"port","channel_id":"channel-1"},"order":"ORDER_UNORDERED","version":"ics20-1","connection_id":"connection-0"}"#;
+        let channel: IbcChannel = serde_json_wasm::from_str(json).unwrap();
+        assert_eq!(channel.fee_version, None);
+    }
+
     #[test]
     fn ibc_timeout_serialize() {
         let timestamp = IbcTimeout::with_timestamp(Timestamp::from_nanos(684816844));
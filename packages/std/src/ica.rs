@@ -0,0 +1,299 @@
+//! Helpers for controlling Interchain Accounts (ICS-27) from a contract.
+//!
+//! The messages in here are encoded as [`AnyMsg`]s for the ICA controller module of ibc-go.
+//! Only the few message types needed here are encoded, such that we do not need a full
+//! protobuf implementation.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::binary::Binary;
+use crate::ibc::IbcOrder;
+use crate::protobuf::{encode_bytes, encode_message, encode_varint};
+use crate::results::{AnyMsg, CosmosMsg};
+
+const REGISTER_INTERCHAIN_ACCOUNT: &str =
+    "/ibc.applications.interchain_accounts.controller.v1.MsgRegisterInterchainAccount";
+const SEND_TX: &str = "/ibc.applications.interchain_accounts.controller.v1.MsgSendTx";
+
+/// The version of the ICS-27 protocol
+pub const ICA_VERSION: &str = "ics27-1";
+
+/// The version metadata that is negotiated when opening an interchain account channel.
+///
+/// See https://github.com/cosmos/ibc-go/blob/v7.3.0/proto/ibc/applications/interchain_accounts/v1/metadata.proto
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IcaMetadata {
+    /// The ICS-27 protocol version, i.e. [`ICA_VERSION`]
+    pub version: String,
+    pub controller_connection_id: String,
+    pub host_connection_id: String,
+    /// The interchain account address on the host chain. This is empty when registering
+    /// and filled in by the host during the channel handshake.
+    #[serde(default)]
+    pub address: String,
+    /// The encoding of the messages in the packet data, e.g. `proto3`
+    pub encoding: String,
+    /// The type of transactions supported, e.g. `sdk_multi_msg`
+    pub tx_type: String,
+}
+
+impl IcaMetadata {
+    /// Creates the metadata for registering a new interchain account using protobuf
+    /// encoded Cosmos SDK messages.
+    pub fn new(
+        controller_connection_id: impl Into<String>,
+        host_connection_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            version: ICA_VERSION.to_string(),
+            controller_connection_id: controller_connection_id.into(),
+            host_connection_id: host_connection_id.into(),
+            address: String::new(),
+            encoding: "proto3".to_string(),
+            tx_type: "sdk_multi_msg".to_string(),
+        }
+    }
+}
+
+/// The type of an [`InterchainAccountPacketData`]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum IcaPacketType {
+    /// Execute the transaction in `data` using the interchain account
+    #[serde(rename = "TYPE_EXECUTE_TX")]
+    ExecuteTx,
+}
+
+impl IcaPacketType {
+    /// The protobuf enum value
+    fn to_proto(self) -> u64 {
+        match self {
+            IcaPacketType::ExecuteTx => 1,
+        }
+    }
+}
+
+/// The packet data of an interchain account packet.
+///
+/// See https://github.com/cosmos/ibc-go/blob/v7.3.0/proto/ibc/applications/interchain_accounts/v1/packet.proto
+///
+/// The JSON encoding of this type is the format sent over the wire. `data` contains
+/// the protobuf encoded `CosmosTx` with the messages to execute.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InterchainAccountPacketData {
+    #[serde(rename = "type")]
+    pub packet_type: IcaPacketType,
+    pub data: Binary,
+    #[serde(default)]
+    pub memo: String,
+}
+
+impl InterchainAccountPacketData {
+    /// Creates the packet data for executing the given messages on the host chain.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{AnyMsg, Binary, InterchainAccountPacketData};
+    /// let send = AnyMsg::new(
+    ///     "/cosmos.bank.v1beta1.MsgSend",
+    ///     Binary::from_base64("CgVhbGljZRIDYm9iGgoKBXVjb3NtEgExCg==").unwrap(),
+    /// );
+    /// let packet_data = InterchainAccountPacketData::execute_tx(&[send], "");
+    /// assert_eq!(packet_data.data.len(), 59);
+    /// ```
+    pub fn execute_tx(msgs: &[AnyMsg], memo: impl Into<String>) -> Self {
+        // CosmosTx { repeated google.protobuf.Any messages = 1; }
+        let mut data = vec![];
+        for msg in msgs {
+            let mut any = vec![];
+            encode_bytes(1, msg.type_url.as_bytes(), &mut any);
+            encode_bytes(2, msg.value.as_slice(), &mut any);
+            encode_message(1, &any, &mut data);
+        }
+        Self {
+            packet_type: IcaPacketType::ExecuteTx,
+            data: data.into(),
+            memo: memo.into(),
+        }
+    }
+
+    /// Returns the protobuf encoding of the packet data
+    fn encode(&self) -> Vec<u8> {
+        let mut out = vec![];
+        encode_uint64(1, self.packet_type.to_proto(), &mut out);
+        encode_bytes(2, self.data.as_slice(), &mut out);
+        encode_bytes(3, self.memo.as_bytes(), &mut out);
+        out
+    }
+}
+
+/// Registers an interchain account on the host chain of the given connection.
+///
+/// This is translated to a [MsgRegisterInterchainAccount](https://github.com/cosmos/ibc-go/blob/v7.3.0/proto/ibc/applications/interchain_accounts/controller/v1/tx.proto#L25-L32).
+/// The account is owned by `owner`, which must be the address of the contract
+/// sending the message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MsgRegisterInterchainAccount {
+    pub owner: String,
+    pub connection_id: String,
+    /// The JSON encoded [`IcaMetadata`]. If empty, the host chain picks the default metadata.
+    pub version: String,
+    /// The ordering of the channel. `None` uses the default of the chain, which is
+    /// ordered for ibc-go versions before v8.1.
+    pub ordering: Option<IbcOrder>,
+}
+
+impl MsgRegisterInterchainAccount {
+    /// Returns the message encoded as an [`AnyMsg`]
+    pub fn to_any(&self) -> AnyMsg {
+        let mut value = vec![];
+        encode_bytes(1, self.owner.as_bytes(), &mut value);
+        encode_bytes(2, self.connection_id.as_bytes(), &mut value);
+        encode_bytes(3, self.version.as_bytes(), &mut value);
+        if let Some(ordering) = &self.ordering {
+            let ordering = match ordering {
+                IbcOrder::Unordered => 1,
+                IbcOrder::Ordered => 2,
+            };
+            encode_uint64(4, ordering, &mut value);
+        }
+        AnyMsg::new(REGISTER_INTERCHAIN_ACCOUNT, value)
+    }
+}
+
+impl<T> From<MsgRegisterInterchainAccount> for CosmosMsg<T> {
+    fn from(msg: MsgRegisterInterchainAccount) -> Self {
+        CosmosMsg::Any(msg.to_any())
+    }
+}
+
+/// Sends a transaction to be executed by the interchain account of `owner`.
+///
+/// This is translated to a [MsgSendTx](https://github.com/cosmos/ibc-go/blob/v7.3.0/proto/ibc/applications/interchain_accounts/controller/v1/tx.proto#L40-L50).
+/// `owner` must be the address of the contract sending the message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MsgSendTx {
+    pub owner: String,
+    pub connection_id: String,
+    pub packet_data: InterchainAccountPacketData,
+    /// The timeout of the packet in nanoseconds, relative to the block time of the controller chain
+    pub relative_timeout: u64,
+}
+
+impl MsgSendTx {
+    /// Returns the message encoded as an [`AnyMsg`]
+    pub fn to_any(&self) -> AnyMsg {
+        let mut value = vec![];
+        encode_bytes(1, self.owner.as_bytes(), &mut value);
+        encode_bytes(2, self.connection_id.as_bytes(), &mut value);
+        encode_message(3, &self.packet_data.encode(), &mut value);
+        encode_uint64(4, self.relative_timeout, &mut value);
+        AnyMsg::new(SEND_TX, value)
+    }
+}
+
+impl<T> From<MsgSendTx> for CosmosMsg<T> {
+    fn from(msg: MsgSendTx) -> Self {
+        CosmosMsg::Any(msg.to_any())
+    }
+}
+
+/// Encodes a varint field. Zero values are omitted.
+fn encode_uint64(number: u64, value: u64, out: &mut Vec<u8>) {
+    if value != 0 {
+        encode_varint(number << 3, out);
+        encode_varint(value, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::{from_slice, to_vec};
+
+    #[test]
+    fn ica_metadata_serializes_to_correct_json() {
+        let metadata = IcaMetadata::new("connection-0", "connection-3");
+        assert_eq!(
+            String::from_utf8(to_vec(&metadata).unwrap()).unwrap(),
+            r#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-3","address":"","encoding":"proto3","tx_type":"sdk_multi_msg"}"#
+        );
+
+        // the counterparty version after the handshake contains the address
+        let metadata: IcaMetadata = from_slice(br#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-3","address":"cosmos1ica","encoding":"proto3","tx_type":"sdk_multi_msg"}"#).unwrap();
+        assert_eq!(metadata.address, "cosmos1ica");
+    }
+
+    #[test]
+    fn interchain_account_packet_data_works() {
+        let msg = AnyMsg::new("/a.B", b"\x01\x02".to_vec());
+        let packet_data = InterchainAccountPacketData::execute_tx(&[msg.clone(), msg], "hi");
+        // two Any messages with type_url and value
+        assert_eq!(
+            packet_data.data.as_slice(),
+            b"\x0a\x0a\x0a\x04/a.B\x12\x02\x01\x02\x0a\x0a\x0a\x04/a.B\x12\x02\x01\x02"
+        );
+        assert_eq!(
+            String::from_utf8(to_vec(&packet_data).unwrap()).unwrap(),
+            r#"{"type":"TYPE_EXECUTE_TX","data":"CgoKBC9hLkISAgECCgoKBC9hLkISAgEC","memo":"hi"}"#
+        );
+
+        // memo is optional in JSON
+        let decoded: InterchainAccountPacketData =
+            from_slice(br#"{"type":"TYPE_EXECUTE_TX","data":""}"#).unwrap();
+        assert_eq!(decoded.memo, "");
+        assert_eq!(decoded.data, Binary::default());
+
+        // empty messages are still encoded
+        let packet_data = InterchainAccountPacketData::execute_tx(&[AnyMsg::new("", vec![])], "");
+        assert_eq!(packet_data.data.as_slice(), b"\x0a\x00");
+    }
+
+    #[test]
+    fn msg_register_interchain_account_works() {
+        let msg = MsgRegisterInterchainAccount {
+            owner: "owner".to_string(),
+            connection_id: "connection-0".to_string(),
+            version: String::new(),
+            ordering: None,
+        };
+        let any = msg.to_any();
+        assert_eq!(any.type_url, REGISTER_INTERCHAIN_ACCOUNT);
+        assert_eq!(any.value.as_slice(), b"\x0a\x05owner\x12\x0cconnection-0");
+
+        let msg = MsgRegisterInterchainAccount {
+            version: "v".to_string(),
+            ordering: Some(IbcOrder::Unordered),
+            ..msg
+        };
+        let cosmos_msg: CosmosMsg = msg.into();
+        match cosmos_msg {
+            CosmosMsg::Any(any) => assert_eq!(
+                any.value.as_slice(),
+                b"\x0a\x05owner\x12\x0cconnection-0\x1a\x01v\x20\x01"
+            ),
+            _ => panic!("must encode in Any variant"),
+        }
+    }
+
+    #[test]
+    fn msg_send_tx_works() {
+        let msg = MsgSendTx {
+            owner: "owner".to_string(),
+            connection_id: "connection-0".to_string(),
+            packet_data: InterchainAccountPacketData {
+                packet_type: IcaPacketType::ExecuteTx,
+                data: Binary::from(b"tx"),
+                memo: String::new(),
+            },
+            relative_timeout: 600_000_000_000,
+        };
+        let any = msg.to_any();
+        assert_eq!(any.type_url, SEND_TX);
+        assert_eq!(
+            any.value.as_slice(),
+            b"\x0a\x05owner\x12\x0cconnection-0\x1a\x06\x08\x01\x12\x02tx\x20\x80\xe0\xa5\x96\xbb\x11"
+        );
+    }
+}
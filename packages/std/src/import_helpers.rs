@@ -1,12 +1,10 @@
 /// Returns the four most significant bytes
-#[allow(dead_code)] // only used in Wasm builds
 #[inline]
 pub fn from_high_half(data: u64) -> u32 {
     (data >> 32).try_into().unwrap()
 }
 
 /// Returns the four least significant bytes
-#[allow(dead_code)] // only used in Wasm builds
 #[inline]
 pub fn from_low_half(data: u64) -> u32 {
     (data & 0xFFFFFFFF).try_into().unwrap()
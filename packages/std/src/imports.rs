@@ -5,21 +5,30 @@ use crate::errors::{RecoverPubkeyError, StdError, StdResult, SystemError, Verifi
 use crate::import_helpers::{from_high_half, from_low_half};
 use crate::memory::{alloc, build_region, consume_region, Region};
 use crate::results::SystemResult;
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::sections::decode_sections;
 #[cfg(feature = "iterator")]
 use crate::sections::decode_sections2;
 use crate::sections::encode_sections;
 use crate::serde::from_slice;
 use crate::traits::{Api, Querier, QuerierResult, Storage};
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::types::ContractSelfInfo;
 #[cfg(feature = "iterator")]
 use crate::{
     iterator::{Order, Record},
     memory::get_optional_region_address,
 };
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::{HashFunction, BLS12_381_G1_POINT_LEN, BLS12_381_G2_POINT_LEN};
 
 /// An upper bound for typical canonical address lengths (e.g. 20 in Cosmos SDK/Ethereum or 32 in Nano/Substrate)
 const CANONICAL_ADDRESS_BUFFER_LENGTH: usize = 64;
 /// An upper bound for typical human readable address formats (e.g. 42 for Ethereum hex addresses or 90 for bech32)
 const HUMAN_ADDRESS_BUFFER_LENGTH: usize = 90;
+/// The max length of a bech32 human readable part according to BIP-173
+#[cfg(feature = "cosmwasm_1_4")]
+const BECH32_PREFIX_MAX_LENGTH: usize = 83;
 
 // This interface will compile into required Wasm imports.
 // A complete documentation those functions is available in the VM that provides them:
@@ -29,6 +38,8 @@ extern "C" {
     fn abort(source_ptr: u32);
 
     fn db_read(key: u32) -> u32;
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn db_read_batch(keys_ptr: u32) -> u32;
     fn db_write(key: u32, value: u32);
     fn db_remove(key: u32);
 
@@ -37,10 +48,24 @@ extern "C" {
     fn db_scan(start_ptr: u32, end_ptr: u32, order: i32) -> u32;
     #[cfg(feature = "iterator")]
     fn db_next(iterator_id: u32) -> u32;
+    // Loads up to limit entries of the range at once
+    #[cfg(all(feature = "iterator", feature = "cosmwasm_1_4"))]
+    fn db_scan_bounded(start_ptr: u32, end_ptr: u32, order: i32, limit: u32) -> u32;
+    // Removes all entries in the range from start (inclusive) to end (exclusive)
+    #[cfg(all(feature = "iterator", feature = "cosmwasm_1_4"))]
+    fn db_remove_range(start_ptr: u32, end_ptr: u32);
 
     fn addr_validate(source_ptr: u32) -> u32;
     fn addr_canonicalize(source_ptr: u32, destination_ptr: u32) -> u32;
     fn addr_humanize(source_ptr: u32, destination_ptr: u32) -> u32;
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_encode(prefix_ptr: u32, data_ptr: u32, destination_ptr: u32) -> u32;
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_decode(
+        source_ptr: u32,
+        prefix_destination_ptr: u32,
+        data_destination_ptr: u32,
+    ) -> u32;
 
     /// Verifies message hashes against a signature with a public key, using the
     /// secp256k1 ECDSA parametrization.
@@ -54,6 +79,87 @@ extern "C" {
         recovery_param: u32,
     ) -> u64;
 
+    /// Verifies a batch of message hashes against a batch of signatures and public keys, using the
+    /// secp256k1 ECDSA parametrization.
+    /// Returns 0 on verification success (all batches verify correctly), 1 on verification failure, and values
+    /// greater than 1 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_batch_verify(
+        message_hashes_ptr: u32,
+        signatures_ptr: u32,
+        public_keys_ptr: u32,
+    ) -> u32;
+
+    /// Adds the secp256k1 points in `p_ptr` and `q_ptr` and writes the compressed
+    /// sum to `out_ptr`.
+    /// Returns 0 on success and values greater than 0 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_add(p_ptr: u32, q_ptr: u32, out_ptr: u32) -> u32;
+
+    /// Multiplies the secp256k1 point in `point_ptr` with the scalar in `scalar_ptr`
+    /// and writes the compressed product to `out_ptr`.
+    /// Returns 0 on success and values greater than 0 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_mul(point_ptr: u32, scalar_ptr: u32, out_ptr: u32) -> u32;
+
+    /// Verifies message hashes against a signature with a public key, using the
+    /// secp256r1 ECDSA parametrization.
+    /// Returns 0 on verification success, 1 on verification failure, and values
+    /// greater than 1 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_verify(message_hash_ptr: u32, signature_ptr: u32, public_key_ptr: u32) -> u32;
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_recover_pubkey(
+        message_hash_ptr: u32,
+        signature_ptr: u32,
+        recovery_param: u32,
+    ) -> u64;
+
+    /// Adds up the compressed BLS12-381 G1 points in `g1s_ptr` and writes the
+    /// compressed sum to `out_ptr`.
+    /// Returns 0 on success and values greater than 0 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_aggregate_g1(g1s_ptr: u32, out_ptr: u32) -> u32;
+
+    /// Adds up the compressed BLS12-381 G2 points in `g2s_ptr` and writes the
+    /// compressed sum to `out_ptr`.
+    /// Returns 0 on success and values greater than 0 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_aggregate_g2(g2s_ptr: u32, out_ptr: u32) -> u32;
+
+    /// Checks if `e(p_1, q_1) * … * e(p_n, q_n) == e(r, s)` for compressed BLS12-381 points.
+    /// Returns 0 if the equality holds, 1 if it does not hold, and values
+    /// greater than 1 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_pairing_equality(ps_ptr: u32, qs_ptr: u32, r_ptr: u32, s_ptr: u32) -> u32;
+
+    /// Hashes a message to a BLS12-381 G1 point and writes the compressed point to `out_ptr`.
+    /// Returns 0 on success and values greater than 0 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_hash_to_g1(hash_function: u32, msg_ptr: u32, dst_ptr: u32, out_ptr: u32) -> u32;
+
+    /// Hashes a message to a BLS12-381 G2 point and writes the compressed point to `out_ptr`.
+    /// Returns 0 on success and values greater than 0 in case of error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_hash_to_g2(hash_function: u32, msg_ptr: u32, dst_ptr: u32, out_ptr: u32) -> u32;
+
+    /// Verifies a Groth16 proof over BN254 against a verifying key and public inputs.
+    /// Returns 0 if the proof is valid, 1 if it is not valid, and values
+    /// greater than 1 in case of error.
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bn254(verifying_key_ptr: u32, proof_ptr: u32, public_inputs_ptr: u32) -> u32;
+
+    /// Verifies a Groth16 proof over BLS12-381 against a verifying key and public inputs.
+    /// Returns 0 if the proof is valid, 1 if it is not valid, and values
+    /// greater than 1 in case of error.
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bls12_381(
+        verifying_key_ptr: u32,
+        proof_ptr: u32,
+        public_inputs_ptr: u32,
+    ) -> u32;
+
     /// Verifies a message against a signature with a public key, using the
     /// ed25519 EdDSA scheme.
     /// Returns 0 on verification success, 1 on verification failure, and values
@@ -66,6 +172,28 @@ extern "C" {
     /// greater than 1 in case of error.
     fn ed25519_batch_verify(messages_ptr: u32, signatures_ptr: u32, public_keys_ptr: u32) -> u32;
 
+    /// Verifies a message against a signature with a public key, using the
+    /// Schnorrkel sr25519 scheme with the `substrate` signing context.
+    /// Returns 0 on verification success, 1 on verification failure, and values
+    /// greater than 1 in case of error.
+    #[cfg(feature = "sr25519")]
+    fn sr25519_verify(message_ptr: u32, signature_ptr: u32, public_key_ptr: u32) -> u32;
+
+    /// Writes the Keccak-256 hash of the data in `data_ptr` to `out_ptr`, which must
+    /// have a capacity of at least 32 bytes.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn keccak256(data_ptr: u32, out_ptr: u32);
+
+    /// Writes the SHA-512 hash of the data in `data_ptr` to `out_ptr`, which must
+    /// have a capacity of at least 64 bytes.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn sha512(data_ptr: u32, out_ptr: u32);
+
+    /// Writes the BLAKE2b-512 hash of the data in `data_ptr` to `out_ptr`, which must
+    /// have a capacity of at least 64 bytes.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn blake2b512(data_ptr: u32, out_ptr: u32);
+
     /// Writes a debug message (UFT-8 encoded) to the host for debugging purposes.
     /// The host is free to log or process this in any way it considers appropriate.
     /// In production environments it is expected that those messages are discarded.
@@ -74,6 +202,11 @@ extern "C" {
     /// Executes a query on the chain (import). Not to be confused with the
     /// query export, which queries the state of the contract.
     fn query_chain(request: u32) -> u32;
+
+    /// Returns information about the currently executing contract as a JSON serialized
+    /// `ContractSelfInfo`. This is provided by the host without going through the querier.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn contract_self_info() -> u32;
 }
 
 /// A stateless convenience wrapper around database imports provided by the VM.
@@ -102,6 +235,24 @@ impl Storage for ExternalStorage {
         Some(data)
     }
 
+    /// Reads all keys in one call using the `db_read_batch` import.
+    /// Without the `cosmwasm_1_4` feature, the keys are read one by one, which works on older hosts.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        let keys = build_region(&encode_sections(keys));
+        let keys_ptr = &*keys as *const Region as u32;
+
+        let result = unsafe { db_read_batch(keys_ptr) };
+        let data = unsafe { consume_region(result as *mut Region) };
+        decode_sections(data)
+            .into_iter()
+            .map(|section| match section.split_first() {
+                Some((1, value)) => Some(value.to_vec()),
+                _ => None,
+            })
+            .collect()
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]) {
         if value.is_empty() {
             panic!("TL;DR: Value must not be empty in Storage::set but in most cases you can use Storage::remove instead. Long story: Getting empty values from storage is not well supported at the moment. Some of our internal interfaces cannot differentiate between a non-existent key and an empty value. Right now, you cannot rely on the behaviour of empty values. To protect you from trouble later on, we stop here. Sorry for the inconvenience! We highly welcome you to contribute to CosmWasm, making this more solid one way or the other.");
@@ -139,9 +290,74 @@ impl Storage for ExternalStorage {
         let iter = ExternalIterator { iterator_id };
         Box::new(iter)
     }
+
+    #[cfg(all(feature = "iterator", feature = "cosmwasm_1_4"))]
+    fn range_bounded(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+        limit: usize,
+    ) -> Vec<Record> {
+        let mut start = start.map(|start| start.to_vec());
+        let mut end = end.map(|end| end.to_vec());
+        let mut records = Vec::new();
+        // The VM may return fewer records than requested per call, so we continue
+        // until the limit is reached or the range is exhausted (signalled by an empty page)
+        while records.len() < limit {
+            let page_limit = u32::try_from(limit - records.len()).unwrap_or(u32::MAX);
+            let page = scan_bounded_page(start.as_deref(), end.as_deref(), order, page_limit);
+            let last_key = match page.last() {
+                Some((last_key, _)) => last_key,
+                None => break,
+            };
+            // continue right after the last key in the given order
+            match order {
+                Order::Ascending => start = Some([last_key.as_slice(), &[0]].concat()),
+                Order::Descending => end = Some(last_key.clone()),
+            }
+            records.extend(page);
+        }
+        records
+    }
+
+    #[cfg(all(feature = "iterator", feature = "cosmwasm_1_4"))]
+    fn remove_range(&mut self, start: Option<&[u8]>, end: Option<&[u8]>) {
+        // keep the boxes in scope, so we free it at the end (don't cast to pointers same line as build_region)
+        let start_region = start.map(build_region);
+        let end_region = end.map(build_region);
+        let start_region_addr = get_optional_region_address(&start_region.as_ref());
+        let end_region_addr = get_optional_region_address(&end_region.as_ref());
+        unsafe { db_remove_range(start_region_addr, end_region_addr) };
+    }
+}
+
+/// Loads up to `limit` records with a single db_scan_bounded call.
+/// The VM may return fewer records even if the range contains more.
+#[cfg(all(feature = "iterator", feature = "cosmwasm_1_4"))]
+fn scan_bounded_page(
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+    limit: u32,
+) -> Vec<Record> {
+    // keep the boxes in scope, so we free it at the end (don't cast to pointers same line as build_region)
+    let start_region = start.map(build_region);
+    let end_region = end.map(build_region);
+    let start_region_addr = get_optional_region_address(&start_region.as_ref());
+    let end_region_addr = get_optional_region_address(&end_region.as_ref());
+    let result =
+        unsafe { db_scan_bounded(start_region_addr, end_region_addr, order as i32, limit) };
+    let data = unsafe { consume_region(result as *mut Region) };
+
+    let mut records = Vec::new();
+    let mut sections = decode_sections(data).into_iter();
+    while let (Some(key), Some(value)) = (sections.next(), sections.next()) {
+        records.push((key, value));
+    }
+    records
 }
 
-#[cfg(feature = "iterator")]
 /// ExternalIterator makes a call out to next.
 /// We use the pointer to differentiate between multiple open iterators.
 struct ExternalIterator {
@@ -244,6 +460,61 @@ impl Api for ExternalApi {
         Ok(Addr::unchecked(address))
     }
 
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_encode(&self, prefix: &str, data: &[u8]) -> StdResult<String> {
+        if prefix.len() > BECH32_PREFIX_MAX_LENGTH {
+            // In this case, the VM will refuse to read the input from the contract.
+            // Stop here to allow handling the error in the contract.
+            return Err(StdError::generic_err("prefix too long for bech32_encode"));
+        }
+        if data.len() > CANONICAL_ADDRESS_BUFFER_LENGTH {
+            return Err(StdError::generic_err("data too long for bech32_encode"));
+        }
+        let prefix_send = build_region(prefix.as_bytes());
+        let prefix_send_ptr = &*prefix_send as *const Region as u32;
+        let data_send = build_region(data);
+        let data_send_ptr = &*data_send as *const Region as u32;
+        let address = alloc(HUMAN_ADDRESS_BUFFER_LENGTH);
+
+        let result = unsafe { bech32_encode(prefix_send_ptr, data_send_ptr, address as u32) };
+        if result != 0 {
+            let error = unsafe { consume_string_region_written_by_vm(result as *mut Region) };
+            return Err(StdError::generic_err(format!(
+                "bech32_encode errored: {}",
+                error
+            )));
+        }
+
+        Ok(unsafe { consume_string_region_written_by_vm(address) })
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_decode(&self, address: &str) -> StdResult<(String, Vec<u8>)> {
+        if address.len() > 256 {
+            // See MAX_LENGTH_HUMAN_ADDRESS in the VM.
+            // In this case, the VM will refuse to read the input from the contract.
+            // Stop here to allow handling the error in the contract.
+            return Err(StdError::generic_err("input too long for bech32_decode"));
+        }
+        let send = build_region(address.as_bytes());
+        let send_ptr = &*send as *const Region as u32;
+        let prefix = alloc(HUMAN_ADDRESS_BUFFER_LENGTH);
+        let data = alloc(CANONICAL_ADDRESS_BUFFER_LENGTH);
+
+        let result = unsafe { bech32_decode(send_ptr, prefix as u32, data as u32) };
+        let prefix = unsafe { consume_string_region_written_by_vm(prefix) };
+        let data = unsafe { consume_region(data) };
+        if result != 0 {
+            let error = unsafe { consume_string_region_written_by_vm(result as *mut Region) };
+            return Err(StdError::generic_err(format!(
+                "bech32_decode errored: {}",
+                error
+            )));
+        }
+
+        Ok((prefix, data))
+    }
+
     fn secp256k1_verify(
         &self,
         message_hash: &[u8],
@@ -298,6 +569,292 @@ impl Api for ExternalApi {
         }
     }
 
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_batch_verify(
+        &self,
+        message_hashes: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        let hashes_encoded = encode_sections(message_hashes);
+        let hashes_send = build_region(&hashes_encoded);
+        let hashes_send_ptr = &*hashes_send as *const Region as u32;
+
+        let sigs_encoded = encode_sections(signatures);
+        let sigs_send = build_region(&sigs_encoded);
+        let sigs_send_ptr = &*sigs_send as *const Region as u32;
+
+        let pubkeys_encoded = encode_sections(public_keys);
+        let pubkeys_send = build_region(&pubkeys_encoded);
+        let pubkeys_send_ptr = &*pubkeys_send as *const Region as u32;
+
+        let result =
+            unsafe { secp256k1_batch_verify(hashes_send_ptr, sigs_send_ptr, pubkeys_send_ptr) };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            3 => Err(VerificationError::InvalidHashFormat),
+            4 => Err(VerificationError::InvalidSignatureFormat),
+            5 => Err(VerificationError::InvalidPubkeyFormat),
+            7 => Err(VerificationError::BatchErr),
+            10 => Err(VerificationError::GenericErr),
+            error_code => Err(VerificationError::unknown_err(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_add(&self, p: &[u8], q: &[u8]) -> Result<[u8; 33], VerificationError> {
+        let p_send = build_region(p);
+        let p_send_ptr = &*p_send as *const Region as u32;
+        let q_send = build_region(q);
+        let q_send_ptr = &*q_send as *const Region as u32;
+        let out = alloc(33);
+
+        let result = unsafe { secp256k1_point_add(p_send_ptr, q_send_ptr, out as u32) };
+        let point = unsafe { consume_region(out) };
+        match result {
+            0 => Ok(point.try_into().unwrap()),
+            error_code => Err(secp256k1_point_error(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_mul(
+        &self,
+        point: &[u8],
+        scalar: &[u8],
+    ) -> Result<[u8; 33], VerificationError> {
+        let point_send = build_region(point);
+        let point_send_ptr = &*point_send as *const Region as u32;
+        let scalar_send = build_region(scalar);
+        let scalar_send_ptr = &*scalar_send as *const Region as u32;
+        let out = alloc(33);
+
+        let result = unsafe { secp256k1_point_mul(point_send_ptr, scalar_send_ptr, out as u32) };
+        let product = unsafe { consume_region(out) };
+        match result {
+            0 => Ok(product.try_into().unwrap()),
+            error_code => Err(secp256k1_point_error(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_verify(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let hash_send = build_region(message_hash);
+        let hash_send_ptr = &*hash_send as *const Region as u32;
+        let sig_send = build_region(signature);
+        let sig_send_ptr = &*sig_send as *const Region as u32;
+        let pubkey_send = build_region(public_key);
+        let pubkey_send_ptr = &*pubkey_send as *const Region as u32;
+
+        let result = unsafe { secp256r1_verify(hash_send_ptr, sig_send_ptr, pubkey_send_ptr) };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            2 => panic!("MessageTooLong must not happen. This is a bug in the VM."),
+            3 => Err(VerificationError::InvalidHashFormat),
+            4 => Err(VerificationError::InvalidSignatureFormat),
+            5 => Err(VerificationError::InvalidPubkeyFormat),
+            10 => Err(VerificationError::GenericErr),
+            error_code => Err(VerificationError::unknown_err(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_recover_pubkey(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        recover_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        let hash_send = build_region(message_hash);
+        let hash_send_ptr = &*hash_send as *const Region as u32;
+        let sig_send = build_region(signature);
+        let sig_send_ptr = &*sig_send as *const Region as u32;
+
+        let result =
+            unsafe { secp256r1_recover_pubkey(hash_send_ptr, sig_send_ptr, recover_param.into()) };
+        let error_code = from_high_half(result);
+        let pubkey_ptr = from_low_half(result);
+        match error_code {
+            0 => {
+                let pubkey = unsafe { consume_region(pubkey_ptr as *mut Region) };
+                Ok(pubkey)
+            }
+            2 => panic!("MessageTooLong must not happen. This is a bug in the VM."),
+            3 => Err(RecoverPubkeyError::InvalidHashFormat),
+            4 => Err(RecoverPubkeyError::InvalidSignatureFormat),
+            6 => Err(RecoverPubkeyError::InvalidRecoveryParam),
+            error_code => Err(RecoverPubkeyError::unknown_err(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_aggregate_g1(
+        &self,
+        g1s: &[u8],
+    ) -> Result<[u8; BLS12_381_G1_POINT_LEN], VerificationError> {
+        let g1s_send = build_region(g1s);
+        let g1s_send_ptr = &*g1s_send as *const Region as u32;
+        let out = alloc(BLS12_381_G1_POINT_LEN);
+
+        let result = unsafe { bls12_381_aggregate_g1(g1s_send_ptr, out as u32) };
+        let point = unsafe { consume_region(out) };
+        match result {
+            0 => Ok(point.try_into().unwrap()),
+            error_code => Err(bls12_381_error(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_aggregate_g2(
+        &self,
+        g2s: &[u8],
+    ) -> Result<[u8; BLS12_381_G2_POINT_LEN], VerificationError> {
+        let g2s_send = build_region(g2s);
+        let g2s_send_ptr = &*g2s_send as *const Region as u32;
+        let out = alloc(BLS12_381_G2_POINT_LEN);
+
+        let result = unsafe { bls12_381_aggregate_g2(g2s_send_ptr, out as u32) };
+        let point = unsafe { consume_region(out) };
+        match result {
+            0 => Ok(point.try_into().unwrap()),
+            error_code => Err(bls12_381_error(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_pairing_equality(
+        &self,
+        ps: &[u8],
+        qs: &[u8],
+        r: &[u8],
+        s: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let ps_send = build_region(ps);
+        let ps_send_ptr = &*ps_send as *const Region as u32;
+        let qs_send = build_region(qs);
+        let qs_send_ptr = &*qs_send as *const Region as u32;
+        let r_send = build_region(r);
+        let r_send_ptr = &*r_send as *const Region as u32;
+        let s_send = build_region(s);
+        let s_send_ptr = &*s_send as *const Region as u32;
+
+        let result =
+            unsafe { bls12_381_pairing_equality(ps_send_ptr, qs_send_ptr, r_send_ptr, s_send_ptr) };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            error_code => Err(bls12_381_error(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_hash_to_g1(
+        &self,
+        hash_function: HashFunction,
+        msg: &[u8],
+        dst: &[u8],
+    ) -> Result<[u8; BLS12_381_G1_POINT_LEN], VerificationError> {
+        let msg_send = build_region(msg);
+        let msg_send_ptr = &*msg_send as *const Region as u32;
+        let dst_send = build_region(dst);
+        let dst_send_ptr = &*dst_send as *const Region as u32;
+        let out = alloc(BLS12_381_G1_POINT_LEN);
+
+        let result = unsafe {
+            bls12_381_hash_to_g1(
+                hash_function.to_u32(),
+                msg_send_ptr,
+                dst_send_ptr,
+                out as u32,
+            )
+        };
+        let point = unsafe { consume_region(out) };
+        match result {
+            0 => Ok(point.try_into().unwrap()),
+            error_code => Err(bls12_381_error(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_hash_to_g2(
+        &self,
+        hash_function: HashFunction,
+        msg: &[u8],
+        dst: &[u8],
+    ) -> Result<[u8; BLS12_381_G2_POINT_LEN], VerificationError> {
+        let msg_send = build_region(msg);
+        let msg_send_ptr = &*msg_send as *const Region as u32;
+        let dst_send = build_region(dst);
+        let dst_send_ptr = &*dst_send as *const Region as u32;
+        let out = alloc(BLS12_381_G2_POINT_LEN);
+
+        let result = unsafe {
+            bls12_381_hash_to_g2(
+                hash_function.to_u32(),
+                msg_send_ptr,
+                dst_send_ptr,
+                out as u32,
+            )
+        };
+        let point = unsafe { consume_region(out) };
+        match result {
+            0 => Ok(point.try_into().unwrap()),
+            error_code => Err(bls12_381_error(error_code)),
+        }
+    }
+
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bn254(
+        &self,
+        verifying_key: &[u8],
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let vk_send = build_region(verifying_key);
+        let vk_send_ptr = &*vk_send as *const Region as u32;
+        let proof_send = build_region(proof);
+        let proof_send_ptr = &*proof_send as *const Region as u32;
+        let inputs_send = build_region(public_inputs);
+        let inputs_send_ptr = &*inputs_send as *const Region as u32;
+
+        let result = unsafe { groth16_verify_bn254(vk_send_ptr, proof_send_ptr, inputs_send_ptr) };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            error_code => Err(groth16_error(error_code)),
+        }
+    }
+
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bls12_381(
+        &self,
+        verifying_key: &[u8],
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let vk_send = build_region(verifying_key);
+        let vk_send_ptr = &*vk_send as *const Region as u32;
+        let proof_send = build_region(proof);
+        let proof_send_ptr = &*proof_send as *const Region as u32;
+        let inputs_send = build_region(public_inputs);
+        let inputs_send_ptr = &*inputs_send as *const Region as u32;
+
+        let result =
+            unsafe { groth16_verify_bls12_381(vk_send_ptr, proof_send_ptr, inputs_send_ptr) };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            error_code => Err(groth16_error(error_code)),
+        }
+    }
+
     fn ed25519_verify(
         &self,
         message: &[u8],
@@ -356,12 +913,112 @@ impl Api for ExternalApi {
         }
     }
 
+    #[cfg(feature = "sr25519")]
+    fn sr25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let msg_send = build_region(message);
+        let msg_send_ptr = &*msg_send as *const Region as u32;
+        let sig_send = build_region(signature);
+        let sig_send_ptr = &*sig_send as *const Region as u32;
+        let pubkey_send = build_region(public_key);
+        let pubkey_send_ptr = &*pubkey_send as *const Region as u32;
+
+        let result = unsafe { sr25519_verify(msg_send_ptr, sig_send_ptr, pubkey_send_ptr) };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            4 => Err(VerificationError::InvalidSignatureFormat),
+            5 => Err(VerificationError::InvalidPubkeyFormat),
+            10 => Err(VerificationError::GenericErr),
+            error_code => Err(VerificationError::unknown_err(error_code)),
+        }
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn keccak256(&self, data: &[u8]) -> StdResult<[u8; 32]> {
+        let data_send = build_region(data);
+        let data_send_ptr = &*data_send as *const Region as u32;
+        let out = alloc(32);
+
+        unsafe { keccak256(data_send_ptr, out as u32) };
+        let hash = unsafe { consume_region(out) };
+        Ok(hash.try_into().unwrap())
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn sha512(&self, data: &[u8]) -> StdResult<[u8; 64]> {
+        let data_send = build_region(data);
+        let data_send_ptr = &*data_send as *const Region as u32;
+        let out = alloc(64);
+
+        unsafe { sha512(data_send_ptr, out as u32) };
+        let hash = unsafe { consume_region(out) };
+        Ok(hash.try_into().unwrap())
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn blake2b512(&self, data: &[u8]) -> StdResult<[u8; 64]> {
+        let data_send = build_region(data);
+        let data_send_ptr = &*data_send as *const Region as u32;
+        let out = alloc(64);
+
+        unsafe { blake2b512(data_send_ptr, out as u32) };
+        let hash = unsafe { consume_region(out) };
+        Ok(hash.try_into().unwrap())
+    }
+
     fn debug(&self, message: &str) {
         // keep the boxes in scope, so we free it at the end (don't cast to pointers same line as build_region)
         let region = build_region(message.as_bytes());
         let region_ptr = region.as_ref() as *const Region as u32;
         unsafe { debug(region_ptr) };
     }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn contract_self_info(&self) -> StdResult<ContractSelfInfo> {
+        let response_ptr = unsafe { contract_self_info() };
+        let response = unsafe { consume_region(response_ptr as *mut Region) };
+        from_slice(&response)
+    }
+}
+
+/// Maps the error codes of the BLS12-381 imports
+#[cfg(feature = "cosmwasm_1_4")]
+fn bls12_381_error(error_code: u32) -> VerificationError {
+    match error_code {
+        10 => VerificationError::GenericErr,
+        11 => VerificationError::InvalidPoint,
+        12 => VerificationError::UnequalPointAmount,
+        13 => VerificationError::UnknownHashFunction,
+        error_code => VerificationError::unknown_err(error_code),
+    }
+}
+
+/// Maps the error codes of the secp256k1 point arithmetic imports
+#[cfg(feature = "cosmwasm_1_4")]
+fn secp256k1_point_error(error_code: u32) -> VerificationError {
+    match error_code {
+        5 => VerificationError::InvalidPubkeyFormat,
+        10 => VerificationError::GenericErr,
+        11 => VerificationError::InvalidPoint,
+        14 => VerificationError::InvalidScalar,
+        error_code => VerificationError::unknown_err(error_code),
+    }
+}
+
+/// Maps the error codes of the Groth16 imports
+#[cfg(feature = "zk")]
+fn groth16_error(error_code: u32) -> VerificationError {
+    match error_code {
+        10 => VerificationError::GenericErr,
+        11 => VerificationError::InvalidPoint,
+        14 => VerificationError::InvalidScalar,
+        error_code => VerificationError::unknown_err(error_code),
+    }
 }
 
 /// Takes a pointer to a Region and reads the data into a String.
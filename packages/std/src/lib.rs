@@ -6,23 +6,34 @@
 mod addresses;
 mod assertions;
 mod binary;
+mod bitset;
+#[cfg(feature = "cosmwasm_1_4")]
+mod bls12_381;
 mod coin;
-mod conversion;
+mod coins;
+mod contract_metadata;
+mod dec_coin;
 mod deps;
 mod errors;
 mod forward_ref;
 mod hex_binary;
 mod ibc;
-mod import_helpers;
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_3"))]
+mod ica;
 #[cfg(feature = "iterator")]
 mod iterator;
 mod math;
+mod metadata;
 mod never;
-mod panic;
+mod ownership;
+#[cfg(feature = "cosmwasm_1_4")]
+mod pagination;
+mod protobuf;
 mod query;
 mod results;
-mod sections;
 mod serde;
+#[cfg(feature = "stargate")]
+mod stdack;
 mod storage;
 mod timestamp;
 mod traits;
@@ -35,12 +46,23 @@ pub mod storage_keys;
 
 pub use crate::addresses::{instantiate2_address, Addr, CanonicalAddr, Instantiate2AddressError};
 pub use crate::binary::Binary;
+pub use crate::bitset::BitSet;
+#[cfg(feature = "cosmwasm_1_4")]
+pub use crate::bls12_381::{
+    HashFunction, BLS12_381_G1_GENERATOR, BLS12_381_G1_POINT_LEN, BLS12_381_G2_GENERATOR,
+    BLS12_381_G2_POINT_LEN,
+};
 pub use crate::coin::{coin, coins, has_coins, Coin};
+pub use crate::coins::Coins;
+pub use crate::contract_metadata::CONTRACT_METADATA_SECTION;
+#[doc(hidden)]
+pub use crate::contract_metadata::{contract_metadata_len, encode_contract_metadata};
+pub use crate::dec_coin::DecCoin;
 pub use crate::deps::{Deps, DepsMut, OwnedDeps};
 pub use crate::errors::{
     CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError,
-    ConversionOverflowError, DivideByZeroError, OverflowError, OverflowOperation,
-    RecoverPubkeyError, StdError, StdResult, SystemError, VerificationError,
+    CoinFromStrError, CoinsError, ConversionOverflowError, DivideByZeroError, OverflowError,
+    OverflowOperation, RecoverPubkeyError, StdError, StdResult, SystemError, VerificationError,
 };
 pub use crate::hex_binary::HexBinary;
 #[cfg(feature = "stargate")]
@@ -50,15 +72,34 @@ pub use crate::ibc::{
     IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse,
     IbcTimeout, IbcTimeoutBlock,
 };
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub use crate::ibc::{
+    IbcAckCallbackMsg, IbcDestinationCallbackMsg, IbcFee, IbcSourceCallbackMsg,
+    IbcTimeoutCallbackMsg,
+};
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_3"))]
+pub use crate::ica::{
+    IcaMetadata, IcaPacketType, InterchainAccountPacketData, MsgRegisterInterchainAccount,
+    MsgSendTx, ICA_VERSION,
+};
 #[cfg(feature = "iterator")]
 pub use crate::iterator::{Order, Record};
 pub use crate::math::{
     Decimal, Decimal256, Decimal256RangeExceeded, DecimalRangeExceeded, Fraction, Isqrt, Uint128,
     Uint256, Uint512, Uint64,
 };
+pub use crate::metadata::{format_amount, DenomMetadata, DenomUnit};
 pub use crate::never::Never;
+pub use crate::ownership::{
+    assert_owner, get_ownership, initialize_owner, update_ownership, Ownership, OwnershipAction,
+    OwnershipError,
+};
+#[cfg(feature = "cosmwasm_1_4")]
+pub use crate::pagination::PageRequest;
 #[cfg(feature = "cosmwasm_1_2")]
 pub use crate::query::CodeInfoResponse;
+#[cfg(feature = "cosmwasm_1_3")]
+pub use crate::query::SendEnabledResponse;
 #[cfg(feature = "cosmwasm_1_1")]
 pub use crate::query::SupplyResponse;
 pub use crate::query::{
@@ -70,36 +111,79 @@ pub use crate::query::{
     AllDelegationsResponse, AllValidatorsResponse, BondedDenomResponse, Delegation,
     DelegationResponse, FullDelegation, StakingQuery, Validator, ValidatorResponse,
 };
+#[cfg(feature = "cosmwasm_1_4")]
+pub use crate::query::{
+    AllDenomMetadataResponse, DelegationRewardsResponse, DelegationTotalRewardsResponse,
+    DelegatorReward, DelegatorValidatorsResponse, DenomMetadataResponse, DistributionQuery,
+    GrpcQuery,
+};
 #[cfg(feature = "stargate")]
 pub use crate::query::{ChannelResponse, IbcQuery, ListChannelsResponse, PortIdResponse};
+#[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+pub use crate::query::{UnbondingDelegationEntry, UnbondingDelegationsResponse};
+#[cfg(feature = "cosmwasm_1_3")]
+pub use crate::results::AnyMsg;
 #[allow(deprecated)]
 pub use crate::results::SubMsgExecutionResponse;
 #[cfg(all(feature = "stargate", feature = "cosmwasm_1_2"))]
 pub use crate::results::WeightedVoteOption;
 pub use crate::results::{
-    attr, wasm_execute, wasm_instantiate, Attribute, BankMsg, ContractResult, CosmosMsg, CustomMsg,
-    Empty, Event, QueryResponse, Reply, ReplyOn, Response, SubMsg, SubMsgResponse, SubMsgResult,
-    SystemResult, WasmMsg,
+    attr, wasm_execute, wasm_instantiate, Attribute, BankMsg, ContractEvent, ContractResult,
+    CosmosMsg, CustomMsg, Empty, Event, JsonPayload, MsgExecuteContractResponse,
+    MsgInstantiateContractResponse, MsgResponse, MsgSendResponse, QueryResponse, Reply, ReplyOn,
+    ReplyPayload, Response, SubMsg, SubMsgResponse, SubMsgResult, SystemResult, WasmMsg,
 };
+#[doc(hidden)]
+pub use crate::results::{check_event_type, parse_event_attribute, parse_optional_event_attribute};
+#[cfg(feature = "authz")]
+pub use crate::results::{Authorization, AuthzMsg};
 #[cfg(feature = "staking")]
 pub use crate::results::{DistributionMsg, StakingMsg};
+#[cfg(feature = "feegrant")]
+pub use crate::results::{FeeAllowance, FeegrantMsg};
 #[cfg(feature = "stargate")]
 pub use crate::results::{GovMsg, VoteOption};
-pub use crate::serde::{from_binary, from_slice, to_binary, to_vec};
-pub use crate::storage::MemoryStorage;
+pub use crate::serde::{from_binary, from_json_str, from_slice, to_binary, to_json_string, to_vec};
+#[cfg(feature = "stargate")]
+pub use crate::stdack::StdAck;
+pub use crate::storage::{
+    MemoryStorage, MemoryStorageSnapshot, SnapshotJournal, StorageSnapshot, TransactionalStorage,
+};
 pub use crate::timestamp::Timestamp;
 pub use crate::traits::{Api, Querier, QuerierResult, QuerierWrapper, Storage};
+#[cfg(feature = "cosmwasm_1_4")]
+pub use crate::types::ContractSelfInfo;
+#[cfg(feature = "cosmwasm_1_5")]
+pub use crate::types::MigrateInfo;
 pub use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
 
 // Exposed in wasm build only
+//
+// Everything needed to talk to the VM (exports, imports, memory and panic handling) lives here,
+// such that native builds contain the types only. This allows host-side tools like indexers
+// or simulators to depend on cosmwasm-std without pulling in any VM-specific code.
 
+#[cfg(any(all(feature = "arena_allocator", target_arch = "wasm32"), test))]
+mod arena;
+#[cfg(any(target_arch = "wasm32", test))]
+mod conversion; // Used by sections only
 #[cfg(target_arch = "wasm32")]
 mod exports;
+#[cfg(any(target_arch = "wasm32", test))]
+mod import_helpers;
 #[cfg(target_arch = "wasm32")]
 mod imports;
 #[cfg(target_arch = "wasm32")]
 mod memory; // Used by exports and imports only. This assumes pointers are 32 bit long, which makes it untestable on dev machines.
+#[cfg(target_arch = "wasm32")]
+mod panic;
+#[cfg(any(target_arch = "wasm32", test))]
+mod sections;
+#[cfg(any(target_arch = "wasm32", test))]
+mod validation; // Used by exports only
 
+#[cfg(all(feature = "cosmwasm_1_5", target_arch = "wasm32"))]
+pub use crate::exports::do_migrate_with_info;
 #[cfg(target_arch = "wasm32")]
 pub use crate::exports::{do_execute, do_instantiate, do_migrate, do_query, do_reply, do_sudo};
 #[cfg(all(feature = "stargate", target_arch = "wasm32"))]
@@ -107,6 +191,8 @@ pub use crate::exports::{
     do_ibc_channel_close, do_ibc_channel_connect, do_ibc_channel_open, do_ibc_packet_ack,
     do_ibc_packet_receive, do_ibc_packet_timeout,
 };
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4", target_arch = "wasm32"))]
+pub use crate::exports::{do_ibc_destination_callback, do_ibc_source_callback};
 #[cfg(target_arch = "wasm32")]
 pub use crate::imports::{ExternalApi, ExternalQuerier, ExternalStorage};
 
@@ -117,4 +203,4 @@ pub mod testing;
 
 // Re-exports
 
-pub use cosmwasm_derive::entry_point;
+pub use cosmwasm_derive::{entry_point, ContractEvent};
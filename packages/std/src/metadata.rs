@@ -0,0 +1,235 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{StdError, StdResult};
+use crate::math::Uint128;
+
+/// Replicates the cosmos-sdk bank module Metadata type
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DenomMetadata {
+    pub description: String,
+    pub denom_units: Vec<DenomUnit>,
+    /// The base denom, i.e. the smallest unit (e.g. "uatom")
+    pub base: String,
+    /// The suggested denom that should be displayed in clients (e.g. "atom")
+    pub display: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub uri_hash: String,
+}
+
+/// Replicates the cosmos-sdk bank module DenomUnit type
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DenomUnit {
+    pub denom: String,
+    /// The power of 10 by which 1 of this unit is larger than 1 of the base unit.
+    /// E.g. 1 atom = 10^6 uatom, so the exponent of "atom" is 6.
+    pub exponent: u32,
+    pub aliases: Vec<String>,
+}
+
+/// The largest exponent supported by [`format_amount`]. This is the number of digits of
+/// [`Uint128::MAX`], so with larger exponents no amount reaches a tenth of the display unit.
+const MAX_EXPONENT: u32 = 39;
+
+/// Formats an amount of the base denom as a human readable string in the display unit,
+/// e.g. 1234500 uatom as "1.2345 ATOM".
+///
+/// The display unit is the entry of `metadata.denom_units` whose denom or one of whose aliases
+/// matches `metadata.display`. The amount is written using its symbol if set and the
+/// display denom otherwise. At most `max_decimals` fractional digits are shown. Further digits
+/// are truncated (rounded towards zero) and trailing zeros are removed. The result only depends
+/// on the inputs and uses no floating point arithmetic, so it is safe to use in events.
+///
+/// Returns an error if the display unit is not found or its exponent is larger than 39.
+///
+/// # Examples
+///
+/// ```
+/// # use cosmwasm_std::{format_amount, DenomMetadata, DenomUnit, Uint128};
+/// let metadata = DenomMetadata {
+///     denom_units: vec![
+///         DenomUnit { denom: "uatom".to_string(), exponent: 0, aliases: vec![] },
+///         DenomUnit { denom: "atom".to_string(), exponent: 6, aliases: vec![] },
+///     ],
+///     base: "uatom".to_string(),
+///     display: "atom".to_string(),
+///     symbol: "ATOM".to_string(),
+///     ..Default::default()
+/// };
+/// let formatted = format_amount(Uint128::new(1234567), &metadata, 2).unwrap();
+/// assert_eq!(formatted, "1.23 ATOM");
+/// ```
+pub fn format_amount(
+    amount: Uint128,
+    metadata: &DenomMetadata,
+    max_decimals: u32,
+) -> StdResult<String> {
+    let unit = metadata
+        .denom_units
+        .iter()
+        .find(|unit| unit.denom == metadata.display || unit.aliases.contains(&metadata.display))
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Display unit '{}' not found in denom units",
+                metadata.display
+            ))
+        })?;
+    if unit.exponent > MAX_EXPONENT {
+        return Err(StdError::generic_err(format!(
+            "Exponent {} of display unit '{}' exceeds the maximum of {}",
+            unit.exponent, metadata.display, MAX_EXPONENT
+        )));
+    }
+    let exponent = unit.exponent as usize;
+
+    // Left-pad with zeros such that there is at least one digit before the decimal point
+    let digits = amount.to_string();
+    let digits = format!("{:0>width$}", digits, width = exponent + 1);
+    let (whole, fractional) = digits.split_at(digits.len() - exponent);
+    let fractional = &fractional[..fractional.len().min(max_decimals as usize)];
+    let fractional = fractional.trim_end_matches('0');
+
+    let label = if metadata.symbol.is_empty() {
+        &metadata.display
+    } else {
+        &metadata.symbol
+    };
+
+    if fractional.is_empty() {
+        Ok(format!("{} {}", whole, label))
+    } else {
+        Ok(format!("{}.{} {}", whole, fractional, label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom_metadata() -> DenomMetadata {
+        DenomMetadata {
+            description: "The native staking token of the Cosmos Hub.".to_string(),
+            denom_units: vec![
+                DenomUnit {
+                    denom: "uatom".to_string(),
+                    exponent: 0,
+                    aliases: vec!["microatom".to_string()],
+                },
+                DenomUnit {
+                    denom: "matom".to_string(),
+                    exponent: 3,
+                    aliases: vec!["milliatom".to_string()],
+                },
+                DenomUnit {
+                    denom: "atom".to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+            ],
+            base: "uatom".to_string(),
+            display: "atom".to_string(),
+            name: "Cosmos Hub Atom".to_string(),
+            symbol: "ATOM".to_string(),
+            uri: "".to_string(),
+            uri_hash: "".to_string(),
+        }
+    }
+
+    #[test]
+    fn format_amount_works() {
+        let metadata = atom_metadata();
+
+        let cases = [
+            (0, 6, "0 ATOM"),
+            (1, 6, "0.000001 ATOM"),
+            (1, 5, "0 ATOM"),
+            (1_000_000, 6, "1 ATOM"),
+            (1_234_567, 6, "1.234567 ATOM"),
+            (1_234_567, 2, "1.23 ATOM"),
+            (1_239_999, 2, "1.23 ATOM"),
+            (1_200_000, 6, "1.2 ATOM"),
+            (1_234_567, 0, "1 ATOM"),
+            (987_654_321_000_000, 3, "987654321 ATOM"),
+        ];
+        for (amount, max_decimals, expected) in cases {
+            let formatted = format_amount(Uint128::new(amount), &metadata, max_decimals).unwrap();
+            assert_eq!(formatted, expected);
+        }
+
+        let formatted = format_amount(Uint128::MAX, &metadata, 6).unwrap();
+        assert_eq!(formatted, "340282366920938463463374607431768.211455 ATOM");
+    }
+
+    #[test]
+    fn format_amount_uses_display_denom_without_symbol() {
+        let mut metadata = atom_metadata();
+        metadata.symbol = "".to_string();
+        let formatted = format_amount(Uint128::new(1_500_000), &metadata, 6).unwrap();
+        assert_eq!(formatted, "1.5 atom");
+    }
+
+    #[test]
+    fn format_amount_finds_display_unit_by_alias() {
+        let mut metadata = atom_metadata();
+        metadata.display = "milliatom".to_string();
+        metadata.symbol = "".to_string();
+        let formatted = format_amount(Uint128::new(1_500), &metadata, 6).unwrap();
+        assert_eq!(formatted, "1.5 milliatom");
+    }
+
+    #[test]
+    fn format_amount_errors_for_missing_display_unit() {
+        let mut metadata = atom_metadata();
+        metadata.display = "katom".to_string();
+        let err = format_amount(Uint128::new(1), &metadata, 6).unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Display unit 'katom' not found in denom units")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn format_amount_errors_for_too_large_exponent() {
+        let mut metadata = atom_metadata();
+
+        metadata.denom_units[2].exponent = 38;
+        let formatted = format_amount(Uint128::MAX, &metadata, 2).unwrap();
+        assert_eq!(formatted, "3.4 ATOM");
+
+        // Uint128::MAX has 39 digits
+        assert_eq!(Uint128::MAX.to_string().len(), 39);
+        metadata.denom_units[2].exponent = 39;
+        let formatted = format_amount(Uint128::MAX, &metadata, 2).unwrap();
+        assert_eq!(formatted, "0.34 ATOM");
+        let formatted = format_amount(Uint128::new(1), &metadata, 39).unwrap();
+        assert_eq!(formatted, "0.000000000000000000000000000000000000001 ATOM");
+
+        metadata.denom_units[2].exponent = 40;
+        let err = format_amount(Uint128::MAX, &metadata, 2).unwrap_err();
+        match err {
+            StdError::GenericErr { msg, .. } => {
+                assert_eq!(
+                    msg,
+                    "Exponent 40 of display unit 'atom' exceeds the maximum of 39"
+                )
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        metadata.denom_units[2].exponent = u32::MAX;
+        format_amount(Uint128::new(1), &metadata, 2).unwrap_err();
+    }
+
+    #[test]
+    fn denom_metadata_serializes_to_correct_json() {
+        let json = crate::to_vec(&atom_metadata()).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"description":"The native staking token of the Cosmos Hub.","denom_units":[{"denom":"uatom","exponent":0,"aliases":["microatom"]},{"denom":"matom","exponent":3,"aliases":["milliatom"]},{"denom":"atom","exponent":6,"aliases":[]}],"base":"uatom","display":"atom","name":"Cosmos Hub Atom","symbol":"ATOM","uri":"","uri_hash":""}"#
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::addresses::Addr;
+use crate::errors::StdError;
+use crate::results::{attr, Attribute};
+use crate::serde::{from_slice, to_vec};
+use crate::timestamp::Timestamp;
+use crate::traits::{Api, Storage};
+use crate::types::BlockInfo;
+
+/// The storage key under which the [`Ownership`] is stored.
+/// Contracts using the ownership helpers must not use this key for anything else.
+const OWNERSHIP_KEY: &[u8] = b"ownership";
+
+/// The owner of a contract and a pending ownership transfer, if any.
+///
+/// This is stored by the ownership helpers and intended to be returned as is
+/// from an ownership query of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Ownership {
+    /// The current owner. `None` if the ownership was renounced.
+    pub owner: Option<Addr>,
+    /// The account that can accept the pending ownership transfer
+    pub pending_owner: Option<Addr>,
+    /// The time at which the pending ownership transfer expires.
+    /// `None` if there is no pending transfer or it does not expire.
+    pub pending_expiry: Option<Timestamp>,
+}
+
+impl Ownership {
+    /// Creates attributes describing the ownership, e.g. to be added to a response
+    /// after the ownership changed.
+    pub fn into_attributes(self) -> Vec<Attribute> {
+        vec![
+            attr("owner", none_or(self.owner)),
+            attr("pending_owner", none_or(self.pending_owner)),
+            attr("pending_expiry", none_or(self.pending_expiry)),
+        ]
+    }
+}
+
+fn none_or(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| "none".to_string(), |v| v.to_string())
+}
+
+/// An action updating the ownership, to be embedded in the execute message of the contract.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{
+/// #     entry_point, update_ownership, DepsMut, Env, MessageInfo, OwnershipAction,
+/// #     OwnershipError, Response,
+/// # };
+/// # use schemars::JsonSchema;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+/// #[serde(rename_all = "snake_case")]
+/// pub enum ExecuteMsg {
+///     UpdateOwnership(OwnershipAction),
+/// }
+///
+/// #[entry_point]
+/// pub fn execute(
+///     deps: DepsMut,
+///     env: Env,
+///     info: MessageInfo,
+///     msg: ExecuteMsg,
+/// ) -> Result<Response, OwnershipError> {
+///     match msg {
+///         ExecuteMsg::UpdateOwnership(action) => {
+///             let ownership =
+///                 update_ownership(deps.storage, deps.api, &env.block, &info.sender, action)?;
+///             Ok(Response::new().add_attributes(ownership.into_attributes()))
+///         }
+///     }
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnershipAction {
+    /// Proposes a new owner, which then has to accept the ownership.
+    /// This replaces an existing pending transfer. Can only be called by the owner.
+    TransferOwnership {
+        new_owner: String,
+        /// The time after which the transfer cannot be accepted anymore.
+        /// `None` means it does not expire.
+        expiry: Option<Timestamp>,
+    },
+    /// Accepts the pending ownership transfer. Can only be called by the pending owner.
+    AcceptOwnership {},
+    /// Removes the owner and the pending transfer for good. Can only be called by the owner.
+    RenounceOwnership {},
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum OwnershipError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("Contract ownership has been renounced")]
+    NoOwner,
+    #[error("Caller is not the contract's current owner")]
+    NotOwner,
+    #[error("Caller is not the contract's pending owner")]
+    NotPendingOwner,
+    #[error("There is no pending ownership transfer")]
+    TransferNotFound,
+    #[error("The pending ownership transfer expired")]
+    TransferExpired,
+    #[error("The expiry of the ownership transfer is in the past")]
+    InvalidExpiry,
+}
+
+/// Stores the initial owner of the contract, usually called during instantiation.
+/// `None` creates a contract without owner.
+pub fn initialize_owner(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    owner: Option<&str>,
+) -> Result<Ownership, OwnershipError> {
+    let ownership = Ownership {
+        owner: owner.map(|owner| api.addr_validate(owner)).transpose()?,
+        pending_owner: None,
+        pending_expiry: None,
+    };
+    save_ownership(storage, &ownership)?;
+    Ok(ownership)
+}
+
+/// Loads the ownership. Returns an error if [`initialize_owner`] was never called.
+pub fn get_ownership(storage: &dyn Storage) -> Result<Ownership, OwnershipError> {
+    match storage.get(OWNERSHIP_KEY) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(StdError::not_found("Ownership").into()),
+    }
+}
+
+/// Returns an error unless `sender` is the current owner
+pub fn assert_owner(storage: &dyn Storage, sender: &Addr) -> Result<(), OwnershipError> {
+    let ownership = get_ownership(storage)?;
+    check_owner(&ownership, sender)
+}
+
+/// Executes the given action on behalf of `sender` and returns the new ownership
+pub fn update_ownership(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    block: &BlockInfo,
+    sender: &Addr,
+    action: OwnershipAction,
+) -> Result<Ownership, OwnershipError> {
+    let ownership = get_ownership(storage)?;
+    let ownership = match action {
+        OwnershipAction::TransferOwnership { new_owner, expiry } => {
+            check_owner(&ownership, sender)?;
+            if matches!(expiry, Some(expiry) if block.time >= expiry) {
+                return Err(OwnershipError::InvalidExpiry);
+            }
+            Ownership {
+                pending_owner: Some(api.addr_validate(&new_owner)?),
+                pending_expiry: expiry,
+                ..ownership
+            }
+        }
+        OwnershipAction::AcceptOwnership {} => {
+            let pending_owner = ownership
+                .pending_owner
+                .ok_or(OwnershipError::TransferNotFound)?;
+            if pending_owner != *sender {
+                return Err(OwnershipError::NotPendingOwner);
+            }
+            if matches!(ownership.pending_expiry, Some(expiry) if block.time >= expiry) {
+                return Err(OwnershipError::TransferExpired);
+            }
+            Ownership {
+                owner: Some(pending_owner),
+                pending_owner: None,
+                pending_expiry: None,
+            }
+        }
+        OwnershipAction::RenounceOwnership {} => {
+            check_owner(&ownership, sender)?;
+            Ownership {
+                owner: None,
+                pending_owner: None,
+                pending_expiry: None,
+            }
+        }
+    };
+    save_ownership(storage, &ownership)?;
+    Ok(ownership)
+}
+
+fn check_owner(ownership: &Ownership, sender: &Addr) -> Result<(), OwnershipError> {
+    match &ownership.owner {
+        Some(owner) if owner == sender => Ok(()),
+        Some(_) => Err(OwnershipError::NotOwner),
+        None => Err(OwnershipError::NoOwner),
+    }
+}
+
+fn save_ownership(storage: &mut dyn Storage, ownership: &Ownership) -> Result<(), OwnershipError> {
+    storage.set(OWNERSHIP_KEY, &to_vec(ownership)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_dependencies, mock_env};
+
+    #[test]
+    fn initialize_owner_works() {
+        let mut deps = mock_dependencies();
+
+        let ownership = initialize_owner(&mut deps.storage, &deps.api, Some("alice")).unwrap();
+        assert_eq!(
+            ownership,
+            Ownership {
+                owner: Some(Addr::unchecked("alice")),
+                pending_owner: None,
+                pending_expiry: None,
+            }
+        );
+        assert_eq!(get_ownership(&deps.storage).unwrap(), ownership);
+
+        // without owner
+        let ownership = initialize_owner(&mut deps.storage, &deps.api, None).unwrap();
+        assert_eq!(ownership.owner, None);
+
+        // invalid address
+        let err = initialize_owner(&mut deps.storage, &deps.api, Some("")).unwrap_err();
+        assert!(matches!(err, OwnershipError::Std(_)));
+    }
+
+    #[test]
+    fn get_ownership_fails_when_not_initialized() {
+        let deps = mock_dependencies();
+        let err = get_ownership(&deps.storage).unwrap_err();
+        assert!(matches!(
+            err,
+            OwnershipError::Std(StdError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn assert_owner_works() {
+        let mut deps = mock_dependencies();
+        initialize_owner(&mut deps.storage, &deps.api, Some("alice")).unwrap();
+
+        assert_owner(&deps.storage, &Addr::unchecked("alice")).unwrap();
+        let err = assert_owner(&deps.storage, &Addr::unchecked("bob")).unwrap_err();
+        assert_eq!(err, OwnershipError::NotOwner);
+
+        initialize_owner(&mut deps.storage, &deps.api, None).unwrap();
+        let err = assert_owner(&deps.storage, &Addr::unchecked("alice")).unwrap_err();
+        assert_eq!(err, OwnershipError::NoOwner);
+    }
+
+    #[test]
+    fn transfer_and_accept_works() {
+        let mut deps = mock_dependencies();
+        let block = mock_env().block;
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        initialize_owner(&mut deps.storage, &deps.api, Some("alice")).unwrap();
+
+        // only the owner can transfer
+        let err = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &bob,
+            OwnershipAction::TransferOwnership {
+                new_owner: "bob".to_string(),
+                expiry: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, OwnershipError::NotOwner);
+
+        let expiry = block.time.plus_seconds(100);
+        let ownership = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &alice,
+            OwnershipAction::TransferOwnership {
+                new_owner: "bob".to_string(),
+                expiry: Some(expiry),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            ownership,
+            Ownership {
+                owner: Some(alice.clone()),
+                pending_owner: Some(bob.clone()),
+                pending_expiry: Some(expiry),
+            }
+        );
+
+        // only the pending owner can accept
+        let err = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &alice,
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert_eq!(err, OwnershipError::NotPendingOwner);
+
+        let ownership = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &bob,
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap();
+        assert_eq!(
+            ownership,
+            Ownership {
+                owner: Some(bob),
+                pending_owner: None,
+                pending_expiry: None,
+            }
+        );
+        assert_eq!(get_ownership(&deps.storage).unwrap(), ownership);
+
+        // nothing left to accept
+        let err = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &alice,
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert_eq!(err, OwnershipError::TransferNotFound);
+    }
+
+    #[test]
+    fn transfer_respects_expiry() {
+        let mut deps = mock_dependencies();
+        let mut block = mock_env().block;
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        initialize_owner(&mut deps.storage, &deps.api, Some("alice")).unwrap();
+
+        // expiry must be in the future
+        let err = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &alice,
+            OwnershipAction::TransferOwnership {
+                new_owner: "bob".to_string(),
+                expiry: Some(block.time),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, OwnershipError::InvalidExpiry);
+
+        update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &alice,
+            OwnershipAction::TransferOwnership {
+                new_owner: "bob".to_string(),
+                expiry: Some(block.time.plus_seconds(10)),
+            },
+        )
+        .unwrap();
+
+        block.time = block.time.plus_seconds(10);
+        let err = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &bob,
+            OwnershipAction::AcceptOwnership {},
+        )
+        .unwrap_err();
+        assert_eq!(err, OwnershipError::TransferExpired);
+    }
+
+    #[test]
+    fn renounce_works() {
+        let mut deps = mock_dependencies();
+        let block = mock_env().block;
+        let alice = Addr::unchecked("alice");
+        initialize_owner(&mut deps.storage, &deps.api, Some("alice")).unwrap();
+        update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &alice,
+            OwnershipAction::TransferOwnership {
+                new_owner: "bob".to_string(),
+                expiry: None,
+            },
+        )
+        .unwrap();
+
+        let ownership = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &alice,
+            OwnershipAction::RenounceOwnership {},
+        )
+        .unwrap();
+        assert_eq!(
+            ownership,
+            Ownership {
+                owner: None,
+                pending_owner: None,
+                pending_expiry: None,
+            }
+        );
+
+        // renouncing is final
+        let err = update_ownership(
+            &mut deps.storage,
+            &deps.api,
+            &block,
+            &alice,
+            OwnershipAction::RenounceOwnership {},
+        )
+        .unwrap_err();
+        assert_eq!(err, OwnershipError::NoOwner);
+    }
+
+    #[test]
+    fn into_attributes_works() {
+        let ownership = Ownership {
+            owner: Some(Addr::unchecked("alice")),
+            pending_owner: Some(Addr::unchecked("bob")),
+            pending_expiry: None,
+        };
+        assert_eq!(
+            ownership.into_attributes(),
+            vec![
+                attr("owner", "alice"),
+                attr("pending_owner", "bob"),
+                attr("pending_expiry", "none"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::Binary;
+
+/// Simplified version of the PageRequest type for pagination from the cosmos-sdk
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PageRequest {
+    /// The key to start the page at (inclusive), as returned in the `next_key` field of
+    /// the previous page. `None` starts at the beginning.
+    pub key: Option<Binary>,
+    /// The maximum number of items in the page. 0 means the default limit of the chain.
+    pub limit: u32,
+    /// Iterate in descending instead of ascending order
+    pub reverse: bool,
+}
@@ -3,7 +3,7 @@
 ///
 /// This overrides any previous panic handler. See <https://doc.rust-lang.org/std/panic/fn.set_hook.html>
 /// for details.
-#[cfg(all(feature = "abort", target_arch = "wasm32"))]
+#[cfg(feature = "abort")]
 pub fn install_panic_handler() {
     use super::imports::handle_panic;
     std::panic::set_hook(Box::new(|info| {
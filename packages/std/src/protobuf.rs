@@ -0,0 +1,131 @@
+//! The few protobuf primitives needed for encoding and decoding messages in binary form,
+//! such that we do not need a full protobuf implementation.
+
+/// Encodes a length-delimited field. Empty values are omitted, which is the
+/// protobuf semantics for default values of scalar fields.
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(feature = "stargate", feature = "cosmwasm_1_3")
+))]
+pub(crate) fn encode_bytes(number: u64, value: &[u8], out: &mut Vec<u8>) {
+    if !value.is_empty() {
+        encode_message(number, value, out);
+    }
+}
+
+/// Encodes an embedded message, which is written even if it is empty.
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(feature = "stargate", feature = "cosmwasm_1_3")
+))]
+pub(crate) fn encode_message(number: u64, value: &[u8], out: &mut Vec<u8>) {
+    encode_varint(number << 3 | 2, out);
+    encode_varint(value.len() as u64, out);
+    out.extend_from_slice(value);
+}
+
+#[cfg(any(
+    not(target_arch = "wasm32"),
+    all(feature = "stargate", feature = "cosmwasm_1_3")
+))]
+pub(crate) fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Decodes a protobuf message into its length-delimited fields (field number and value).
+/// Fields of other wire types are validated and skipped.
+pub(crate) fn decode_fields(mut data: &[u8]) -> Result<Vec<(u64, &[u8])>, String> {
+    let mut fields = vec![];
+    while !data.is_empty() {
+        let key = read_varint(&mut data)?;
+        let number = key >> 3;
+        match key & 0b111 {
+            0 => {
+                read_varint(&mut data)?;
+            }
+            1 => data = skip(data, 8)?,
+            2 => {
+                let length = usize::try_from(read_varint(&mut data)?)
+                    .map_err(|_| "length out of range".to_string())?;
+                if data.len() < length {
+                    return Err("unexpected end of data".to_string());
+                }
+                let (value, rest) = data.split_at(length);
+                fields.push((number, value));
+                data = rest;
+            }
+            5 => data = skip(data, 4)?,
+            wire_type => return Err(format!("unsupported wire type {}", wire_type)),
+        }
+    }
+    Ok(fields)
+}
+
+fn skip(data: &[u8], length: usize) -> Result<&[u8], String> {
+    data.get(length..)
+        .ok_or_else(|| "unexpected end of data".to_string())
+}
+
+fn read_varint(data: &mut &[u8]) -> Result<u64, String> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *data = &data[i + 1..];
+            return Ok(value);
+        }
+    }
+    Err("invalid varint".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_varint_works() {
+        for (value, expected) in [
+            (0u64, &[0x00][..]),
+            (1, &[0x01]),
+            (127, &[0x7f]),
+            (150, &[0x96, 0x01]),
+            (
+                u64::MAX,
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+            ),
+        ] {
+            let mut out = vec![];
+            encode_varint(value, &mut out);
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn read_varint_works() {
+        let mut data: &[u8] = b"\x96\x01\x05";
+        assert_eq!(read_varint(&mut data).unwrap(), 150);
+        assert_eq!(data, b"\x05");
+
+        let mut data: &[u8] = b"\x80\x80";
+        assert_eq!(read_varint(&mut data).unwrap_err(), "invalid varint");
+    }
+
+    #[test]
+    fn encode_and_decode_fields_round_trip() {
+        let mut data = vec![];
+        encode_bytes(1, b"contract", &mut data);
+        encode_bytes(2, b"", &mut data);
+        encode_message(3, b"", &mut data);
+        encode_varint(4 << 3, &mut data);
+        encode_varint(300, &mut data);
+        encode_bytes(5, b"abc", &mut data);
+        assert_eq!(
+            decode_fields(&data).unwrap(),
+            [(1, &b"contract"[..]), (3, b""), (5, b"abc")]
+        );
+    }
+}
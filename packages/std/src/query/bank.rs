@@ -2,6 +2,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::Coin;
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::{Binary, DenomMetadata, PageRequest};
 
 use super::query_response::QueryResponseType;
 
@@ -21,6 +23,20 @@ pub enum BankQuery {
     /// Note that this may be much more expensive than Balance and should be avoided if possible.
     /// Return value is AllBalanceResponse.
     AllBalances { address: String },
+    /// This calls into the native bank module for querying whether a denomination
+    /// can be transferred. Use this to fail early with a helpful error instead of
+    /// emitting a `BankMsg::Send` that fails downstream.
+    /// Return value is SendEnabledResponse.
+    #[cfg(feature = "cosmwasm_1_3")]
+    SendEnabled { denom: String },
+    /// This calls into the native bank module for querying metadata for a specific bank token.
+    /// Return value is DenomMetadataResponse
+    #[cfg(feature = "cosmwasm_1_4")]
+    DenomMetadata { denom: String },
+    /// This calls into the native bank module for querying metadata for all bank tokens that have a metadata entry.
+    /// Return value is AllDenomMetadataResponse
+    #[cfg(feature = "cosmwasm_1_4")]
+    AllDenomMetadata { pagination: Option<PageRequest> },
 }
 
 #[cfg(feature = "cosmwasm_1_1")]
@@ -54,3 +70,55 @@ pub struct AllBalanceResponse {
 }
 
 impl QueryResponseType for AllBalanceResponse {}
+
+#[cfg(feature = "cosmwasm_1_3")]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct SendEnabledResponse {
+    /// The denom this response is about
+    pub denom: String,
+    /// True iff the denom can be sent. This is true for unknown denoms,
+    /// matching the default send enabled setting of the bank module.
+    pub enabled: bool,
+}
+
+#[cfg(feature = "cosmwasm_1_3")]
+impl SendEnabledResponse {
+    pub fn new(denom: impl Into<String>, enabled: bool) -> Self {
+        Self {
+            denom: denom.into(),
+            enabled,
+        }
+    }
+}
+
+#[cfg(feature = "cosmwasm_1_3")]
+impl QueryResponseType for SendEnabledResponse {}
+
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct DenomMetadataResponse {
+    /// The metadata for the queried denom.
+    pub metadata: DenomMetadata,
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl QueryResponseType for DenomMetadataResponse {}
+
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct AllDenomMetadataResponse {
+    /// Always returns metadata for all token denoms on the base chain.
+    pub metadata: Vec<DenomMetadata>,
+    /// The key to pass in `PageRequest::key` to get the next page.
+    /// `None` if this is the last page.
+    pub next_key: Option<Binary>,
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl QueryResponseType for AllDenomMetadataResponse {}
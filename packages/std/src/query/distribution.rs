@@ -0,0 +1,139 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::DecCoin;
+
+use super::query_response::QueryResponseType;
+
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributionQuery {
+    /// Returns the rewards accrued by a delegation so far.
+    ///
+    /// The query response type is `DelegationRewardsResponse`.
+    DelegationRewards {
+        delegator_address: String,
+        validator_address: String,
+    },
+    /// Returns the rewards accrued by all delegations of the given delegator, per validator
+    /// and in total.
+    ///
+    /// The query response type is `DelegationTotalRewardsResponse`.
+    DelegationTotalRewards { delegator_address: String },
+    /// Returns the addresses of all validators the given delegator has delegated to.
+    ///
+    /// The query response type is `DelegatorValidatorsResponse`.
+    DelegatorValidators { delegator_address: String },
+}
+
+/// See <https://github.com/cosmos/cosmos-sdk/blob/c74e2887b0b73e81d48c2f33e6b1020090089ee0/proto/cosmos/distribution/v1beta1/query.proto#L169-L178>
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct DelegationRewardsResponse {
+    pub rewards: Vec<DecCoin>,
+}
+
+impl QueryResponseType for DelegationRewardsResponse {}
+
+impl DelegationRewardsResponse {
+    pub fn new(rewards: Vec<DecCoin>) -> Self {
+        Self { rewards }
+    }
+}
+
+/// A delegator reward, i.e. the rewards accrued by the delegation to a single validator.
+///
+/// See <https://github.com/cosmos/cosmos-sdk/blob/c74e2887b0b73e81d48c2f33e6b1020090089ee0/proto/cosmos/distribution/v1beta1/distribution.proto#L152-L160>
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct DelegatorReward {
+    pub validator_address: String,
+    pub reward: Vec<DecCoin>,
+}
+
+impl DelegatorReward {
+    pub fn new(validator_address: impl Into<String>, reward: Vec<DecCoin>) -> Self {
+        Self {
+            validator_address: validator_address.into(),
+            reward,
+        }
+    }
+}
+
+/// See <https://github.com/cosmos/cosmos-sdk/blob/c74e2887b0b73e81d48c2f33e6b1020090089ee0/proto/cosmos/distribution/v1beta1/query.proto#L189-L198>
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct DelegationTotalRewardsResponse {
+    pub rewards: Vec<DelegatorReward>,
+    pub total: Vec<DecCoin>,
+}
+
+impl QueryResponseType for DelegationTotalRewardsResponse {}
+
+impl DelegationTotalRewardsResponse {
+    pub fn new(rewards: Vec<DelegatorReward>, total: Vec<DecCoin>) -> Self {
+        Self { rewards, total }
+    }
+}
+
+/// See <https://github.com/cosmos/cosmos-sdk/blob/c74e2887b0b73e81d48c2f33e6b1020090089ee0/proto/cosmos/distribution/v1beta1/query.proto#L206-L213>
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub struct DelegatorValidatorsResponse {
+    pub validators: Vec<String>,
+}
+
+impl QueryResponseType for DelegatorValidatorsResponse {}
+
+impl DelegatorValidatorsResponse {
+    pub fn new(validators: Vec<String>) -> Self {
+        Self { validators }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Decimal256;
+
+    #[test]
+    fn distribution_query_serializes_to_correct_json() {
+        let query = DistributionQuery::DelegationRewards {
+            delegator_address: "alice".to_string(),
+            validator_address: "valoper1".to_string(),
+        };
+        let json = crate::to_vec(&query).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"delegation_rewards":{"delegator_address":"alice","validator_address":"valoper1"}}"#
+        );
+
+        let query = DistributionQuery::DelegatorValidators {
+            delegator_address: "alice".to_string(),
+        };
+        let json = crate::to_vec(&query).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"delegator_validators":{"delegator_address":"alice"}}"#
+        );
+    }
+
+    #[test]
+    fn delegation_total_rewards_response_serializes_to_correct_json() {
+        let rewards = vec![DecCoin::new(Decimal256::percent(150), "ustake")];
+        let response = DelegationTotalRewardsResponse::new(
+            vec![DelegatorReward::new("valoper1", rewards.clone())],
+            rewards,
+        );
+        let json = crate::to_vec(&response).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"rewards":[{"validator_address":"valoper1","reward":[{"denom":"ustake","amount":"1.5"}]}],"total":[{"denom":"ustake","amount":"1.5"}]}"#
+        );
+    }
+}
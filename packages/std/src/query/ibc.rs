@@ -20,6 +20,28 @@ pub enum IbcQuery {
     ///
     /// Returns a `ListChannelsResponse`.
     ListChannels { port_id: Option<String> },
+    /// Like [`IbcQuery::ListChannels`], but returns the channels page by page and
+    /// can filter them by the port of the counterparty.
+    ///
+    /// The channels are ordered by channel ID. To get the next page, use the ID of the
+    /// last channel returned as `start_after`.
+    ///
+    /// Returns a `ListChannelsResponse`.
+    #[cfg(feature = "cosmwasm_1_4")]
+    ListChannelsPaginated {
+        /// If omitted, the channels bound to the contract's port are listed.
+        port_id: Option<String>,
+        /// If set, only channels whose counterparty is bound to this port are returned.
+        #[serde(default)]
+        counterparty_port_id: Option<String>,
+        /// The channel ID after which to start the page (exclusive).
+        /// `None` starts at the first channel.
+        #[serde(default)]
+        start_after: Option<String>,
+        /// The maximum number of channels to return. `None` returns all channels.
+        #[serde(default)]
+        limit: Option<u32>,
+    },
     /// Lists all information for a (portID, channelID) pair.
     /// If port_id is omitted, it will default to the contract's own channel.
     /// (To save a PortId{} call)
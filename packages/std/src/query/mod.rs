@@ -1,19 +1,30 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[cfg(feature = "stargate")]
+#[cfg(any(feature = "stargate", feature = "cosmwasm_1_4"))]
 use crate::Binary;
 use crate::Empty;
 
 mod bank;
+#[cfg(feature = "cosmwasm_1_4")]
+mod distribution;
 mod ibc;
 mod query_response;
 mod staking;
 mod wasm;
 
+#[cfg(feature = "cosmwasm_1_3")]
+pub use bank::SendEnabledResponse;
 #[cfg(feature = "cosmwasm_1_1")]
 pub use bank::SupplyResponse;
 pub use bank::{AllBalanceResponse, BalanceResponse, BankQuery};
+#[cfg(feature = "cosmwasm_1_4")]
+pub use bank::{AllDenomMetadataResponse, DenomMetadataResponse};
+#[cfg(feature = "cosmwasm_1_4")]
+pub use distribution::{
+    DelegationRewardsResponse, DelegationTotalRewardsResponse, DelegatorReward,
+    DelegatorValidatorsResponse, DistributionQuery,
+};
 #[cfg(feature = "stargate")]
 pub use ibc::{ChannelResponse, IbcQuery, ListChannelsResponse, PortIdResponse};
 #[cfg(feature = "staking")]
@@ -21,6 +32,8 @@ pub use staking::{
     AllDelegationsResponse, AllValidatorsResponse, BondedDenomResponse, Delegation,
     DelegationResponse, FullDelegation, StakingQuery, Validator, ValidatorResponse,
 };
+#[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+pub use staking::{UnbondingDelegationEntry, UnbondingDelegationsResponse};
 #[cfg(feature = "cosmwasm_1_2")]
 pub use wasm::CodeInfoResponse;
 pub use wasm::{ContractInfoResponse, WasmQuery};
@@ -48,6 +61,27 @@ pub enum QueryRequest<C> {
     #[cfg(feature = "stargate")]
     Ibc(IbcQuery),
     Wasm(WasmQuery),
+    #[cfg(feature = "cosmwasm_1_4")]
+    Grpc(GrpcQuery),
+    #[cfg(feature = "cosmwasm_1_4")]
+    Distribution(DistributionQuery),
+}
+
+/// Queries the chain using a gRPC query.
+/// This allows to query information that is not exposed in our API.
+/// The chain needs to allowlist the supported queries.
+/// The drawback of this query is that you have to handle the protobuf encoding and decoding yourself.
+///
+/// The returned data is protobuf encoded. The protobuf type depends on the query.
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct GrpcQuery {
+    /// The fully qualified endpoint path used for routing.
+    /// It follows the format `/service_path/method_name`,
+    /// eg. "/cosmos.authz.v1beta1.Query/Grants"
+    pub path: String,
+    /// The expected protobuf message type (not [Any](https://protobuf.dev/programming-guides/proto3/#any)), binary encoded
+    pub data: Binary,
 }
 
 /// A trait that is required to avoid conflicts with other query types like BankQuery and WasmQuery
@@ -106,3 +140,17 @@ impl<C: CustomQuery> From<IbcQuery> for QueryRequest<C> {
         QueryRequest::Ibc(msg)
     }
 }
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl<C: CustomQuery> From<DistributionQuery> for QueryRequest<C> {
+    fn from(msg: DistributionQuery) -> Self {
+        QueryRequest::Distribution(msg)
+    }
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl<C: CustomQuery> From<GrpcQuery> for QueryRequest<C> {
+    fn from(msg: GrpcQuery) -> Self {
+        QueryRequest::Grpc(msg)
+    }
+}
@@ -3,8 +3,13 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::Timestamp;
 use crate::{Addr, Coin, Decimal};
 
+#[cfg(feature = "cosmwasm_1_4")]
+use super::query_response::QueryResponseType;
+
 #[non_exhaustive]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -31,6 +36,14 @@ pub enum StakingQuery {
         /// The validator's address (e.g. (e.g. cosmosvaloper1...))
         address: String,
     },
+    /// Returns the pending unbondings of the delegation of `delegator` to `validator`.
+    ///
+    /// The query response type is `UnbondingDelegationsResponse`.
+    #[cfg(feature = "cosmwasm_1_4")]
+    UnbondingDelegations {
+        delegator: String,
+        validator: String,
+    },
 }
 
 /// BondedDenomResponse is data format returned from StakingRequest::BondedDenom query
@@ -117,3 +130,39 @@ pub struct Validator {
     /// TODO: what units are these (in terms of time)?
     pub max_change_rate: Decimal,
 }
+
+/// The data format returned from StakingRequest::UnbondingDelegations query
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[non_exhaustive]
+pub struct UnbondingDelegationsResponse {
+    /// The pending unbondings, sorted by completion time. Empty if there are none.
+    pub entries: Vec<UnbondingDelegationEntry>,
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl QueryResponseType for UnbondingDelegationsResponse {}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl UnbondingDelegationsResponse {
+    pub fn new(entries: Vec<UnbondingDelegationEntry>) -> Self {
+        Self { entries }
+    }
+}
+
+/// A single pending unbonding of a delegation.
+///
+/// Instances are created in the querier.
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UnbondingDelegationEntry {
+    /// The height at which the unbonding was started
+    pub creation_height: u64,
+    /// The time at which the funds become available
+    pub completion_time: Timestamp,
+    /// The amount that was unbonded initially
+    pub initial_balance: Coin,
+    /// The amount that will be paid out, which is less than `initial_balance` if the
+    /// validator was slashed in the meantime
+    pub balance: Coin,
+}
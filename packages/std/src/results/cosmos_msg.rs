@@ -1,6 +1,7 @@
 use derivative::Derivative;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::fmt;
 
 use crate::binary::Binary;
@@ -9,6 +10,8 @@ use crate::errors::StdResult;
 #[cfg(feature = "stargate")]
 use crate::ibc::IbcMsg;
 use crate::serde::to_binary;
+#[cfg(any(feature = "feegrant", feature = "authz"))]
+use crate::timestamp::Timestamp;
 #[cfg(all(feature = "stargate", feature = "cosmwasm_1_2"))]
 use crate::Decimal;
 
@@ -45,6 +48,52 @@ pub enum CosmosMsg<T = Empty> {
     Wasm(WasmMsg),
     #[cfg(feature = "stargate")]
     Gov(GovMsg),
+    /// An arbitrary protobuf message of any Cosmos SDK module. This allows contracts to
+    /// use new module messages without waiting for a typed variant in cosmwasm-std.
+    ///
+    /// The caller is responsible for encoding `value` correctly for the given `type_url`.
+    #[cfg(feature = "cosmwasm_1_3")]
+    Any(AnyMsg),
+    #[cfg(feature = "feegrant")]
+    Feegrant(FeegrantMsg),
+    #[cfg(feature = "authz")]
+    Authz(AuthzMsg),
+}
+
+/// A message encoded the same way as a protobuf [Any](https://github.com/protocolbuffers/protobuf/blob/master/src/google/protobuf/any.proto).
+/// This is the same structure as messages in `TxBody` from [ADR-020](https://github.com/cosmos/cosmos-sdk/blob/master/docs/architecture/adr-020-protobuf-transaction-encoding.md).
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{Binary, CosmosMsg};
+/// # #[cfg(feature = "cosmwasm_1_3")]
+/// use cosmwasm_std::AnyMsg;
+///
+/// # #[cfg(feature = "cosmwasm_1_3")]
+/// let msg: CosmosMsg = AnyMsg::new(
+///     "/cosmos.bank.v1beta1.MsgSend",
+///     Binary::from_base64("CgVhbGljZRIDYm9iGgoKBXVjb3NtEgExCg==").unwrap(),
+/// )
+/// .into();
+/// ```
+#[cfg(feature = "cosmwasm_1_3")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AnyMsg {
+    /// The fully qualified protobuf type name, e.g. `/cosmos.bank.v1beta1.MsgSend`
+    pub type_url: String,
+    /// The protobuf encoded message
+    pub value: Binary,
+}
+
+#[cfg(feature = "cosmwasm_1_3")]
+impl AnyMsg {
+    pub fn new(type_url: impl Into<String>, value: impl Into<Binary>) -> Self {
+        Self {
+            type_url: type_url.into(),
+            value: value.into(),
+        }
+    }
 }
 
 /// The message types of the bank module.
@@ -89,6 +138,18 @@ pub enum StakingMsg {
         dst_validator: String,
         amount: Coin,
     },
+    /// This is translated to a [MsgCancelUnbondingDelegation](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/staking/v1beta1/tx.proto#L139-L153).
+    /// `delegator_address` is automatically filled with the current contract's address.
+    ///
+    /// The unbonding entry is identified by `creation_height`, which is the block height at which
+    /// the undelegation was started. `amount` is re-delegated to the validator and can be less than
+    /// the full balance of the entry.
+    #[cfg(feature = "cosmwasm_1_4")]
+    CancelUnbondingDelegation {
+        validator: String,
+        amount: Coin,
+        creation_height: u64,
+    },
 }
 
 /// The message types of the distribution module.
@@ -111,6 +172,13 @@ pub enum DistributionMsg {
         /// The `validator_address`
         validator: String,
     },
+    /// This is translated to a [MsgFundCommunityPool](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/distribution/v1beta1/tx.proto#L69-L76).
+    /// `depositor` is automatically filled with the current contract's address.
+    #[cfg(feature = "cosmwasm_1_4")]
+    FundCommunityPool {
+        /// The amount to spend
+        amount: Vec<Coin>,
+    },
 }
 
 fn binary_to_string(data: &Binary, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
@@ -291,6 +359,9 @@ pub enum GovMsg {
         proposal_id: u64,
         options: Vec<WeightedVoteOption>,
     },
+    /// This maps directly to [MsgDeposit](https://github.com/cosmos/cosmos-sdk/blob/v0.45.8/proto/cosmos/gov/v1beta1/tx.proto#L83-L93) in the Cosmos SDK with depositor set to the contract address.
+    #[cfg(feature = "cosmwasm_1_4")]
+    Deposit { proposal_id: u64, amount: Vec<Coin> },
 }
 
 #[cfg(feature = "stargate")]
@@ -310,6 +381,104 @@ pub struct WeightedVoteOption {
     pub weight: Decimal,
 }
 
+/// The message types of the feegrant module. The contract is the granter,
+/// i.e. the fees of the grantee's transactions are paid from the contract's balance.
+///
+/// See https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/feegrant/v1beta1/tx.proto
+#[cfg(feature = "feegrant")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeegrantMsg {
+    /// This is translated to a [MsgGrantAllowance](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/feegrant/v1beta1/tx.proto#L25-L35).
+    /// `granter` is automatically filled with the current contract's address.
+    GrantAllowance {
+        grantee: String,
+        allowance: FeeAllowance,
+    },
+    /// This is translated to a [MsgRevokeAllowance](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/feegrant/v1beta1/tx.proto#L40-L47).
+    /// `granter` is automatically filled with the current contract's address.
+    RevokeAllowance { grantee: String },
+}
+
+/// A fee allowance granted by [`FeegrantMsg::GrantAllowance`]
+#[cfg(feature = "feegrant")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeAllowance {
+    /// This is translated to a [BasicAllowance](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/feegrant/v1beta1/feegrant.proto#L13-L26).
+    Basic {
+        /// The maximum amount the grantee can spend in total. Empty means no limit.
+        spend_limit: Vec<Coin>,
+        /// The time at which the allowance expires. `None` means it never expires.
+        expiration: Option<Timestamp>,
+    },
+    /// This is translated to a [PeriodicAllowance](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/feegrant/v1beta1/feegrant.proto#L28-L50).
+    Periodic {
+        /// The maximum amount the grantee can spend in total. Empty means no limit.
+        spend_limit: Vec<Coin>,
+        /// The time at which the allowance expires. `None` means it never expires.
+        expiration: Option<Timestamp>,
+        /// The length of a period in seconds
+        period: u64,
+        /// The maximum amount the grantee can spend per period
+        period_spend_limit: Vec<Coin>,
+    },
+}
+
+/// The message types of the authz module.
+///
+/// See https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/authz/v1beta1/tx.proto
+#[cfg(feature = "authz")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthzMsg {
+    /// This is translated to a [MsgGrant](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/authz/v1beta1/tx.proto#L34-L42).
+    /// `granter` is automatically filled with the current contract's address.
+    Grant {
+        grantee: String,
+        authorization: Authorization,
+        /// The time at which the grant expires. `None` means it never expires.
+        expiration: Option<Timestamp>,
+    },
+    /// This is translated to a [MsgRevoke](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/authz/v1beta1/tx.proto#L62-L68).
+    /// `granter` is automatically filled with the current contract's address.
+    Revoke {
+        grantee: String,
+        /// The type URL of the message the grant is revoked for, e.g. `/cosmos.bank.v1beta1.MsgSend`
+        msg_type_url: String,
+    },
+    /// This is translated to a [MsgExec](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/authz/v1beta1/tx.proto#L50-L58).
+    /// `grantee` is automatically filled with the current contract's address.
+    ///
+    /// The messages are executed on behalf of their signers, which must have granted
+    /// the contract an authorization for them.
+    Exec { msgs: Vec<AnyMsg> },
+}
+
+/// An authorization granted by [`AuthzMsg::Grant`]
+#[cfg(feature = "authz")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Authorization {
+    /// This is translated to a [SendAuthorization](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/bank/v1beta1/authz.proto#L10-L27).
+    Send {
+        /// The maximum amount the grantee can send in total
+        spend_limit: Vec<Coin>,
+        /// The addresses the grantee can send to. Empty means any address.
+        allow_list: Vec<String>,
+    },
+    /// This is translated to a [GenericAuthorization](https://github.com/cosmos/cosmos-sdk/blob/v0.46.0/proto/cosmos/authz/v1beta1/authz.proto#L13-L18),
+    /// which allows the grantee to execute any message of the given type without further limits.
+    Generic {
+        /// The type URL of the message, e.g. `/cosmos.gov.v1beta1.MsgVote`
+        msg_type_url: String,
+    },
+}
+
 /// Shortcut helper as the construction of WasmMsg::Instantiate can be quite verbose in contract code.
 ///
 /// When using this, `admin` is always unset. If you need more flexibility, create the message directly.
@@ -343,6 +512,49 @@ pub fn wasm_execute(
     })
 }
 
+impl<T> CosmosMsg<T> {
+    /// Converts the custom message type using the given fallible function.
+    ///
+    /// Messages of all other variants are moved over unchanged. This is useful for converting
+    /// between chain-specific and `Empty` message types in adapter layers.
+    pub fn try_map_custom<U, E>(
+        self,
+        f: impl FnOnce(T) -> Result<U, E>,
+    ) -> Result<CosmosMsg<U>, E> {
+        Ok(match self {
+            CosmosMsg::Bank(msg) => CosmosMsg::Bank(msg),
+            CosmosMsg::Custom(msg) => CosmosMsg::Custom(f(msg)?),
+            #[cfg(feature = "staking")]
+            CosmosMsg::Staking(msg) => CosmosMsg::Staking(msg),
+            #[cfg(feature = "staking")]
+            CosmosMsg::Distribution(msg) => CosmosMsg::Distribution(msg),
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Stargate { type_url, value } => CosmosMsg::Stargate { type_url, value },
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Ibc(msg) => CosmosMsg::Ibc(msg),
+            CosmosMsg::Wasm(msg) => CosmosMsg::Wasm(msg),
+            #[cfg(feature = "stargate")]
+            CosmosMsg::Gov(msg) => CosmosMsg::Gov(msg),
+            #[cfg(feature = "cosmwasm_1_3")]
+            CosmosMsg::Any(msg) => CosmosMsg::Any(msg),
+            #[cfg(feature = "feegrant")]
+            CosmosMsg::Feegrant(msg) => CosmosMsg::Feegrant(msg),
+            #[cfg(feature = "authz")]
+            CosmosMsg::Authz(msg) => CosmosMsg::Authz(msg),
+        })
+    }
+
+    /// Converts the custom message type using the given function.
+    ///
+    /// Messages of all other variants are moved over unchanged.
+    pub fn map_custom<U>(self, f: impl FnOnce(T) -> U) -> CosmosMsg<U> {
+        match self.try_map_custom(|msg| Ok::<_, Infallible>(f(msg))) {
+            Ok(msg) => msg,
+            Err(never) => match never {},
+        }
+    }
+}
+
 impl<T> From<BankMsg> for CosmosMsg<T> {
     fn from(msg: BankMsg) -> Self {
         CosmosMsg::Bank(msg)
@@ -383,6 +595,27 @@ impl<T> From<GovMsg> for CosmosMsg<T> {
     }
 }
 
+#[cfg(feature = "feegrant")]
+impl<T> From<FeegrantMsg> for CosmosMsg<T> {
+    fn from(msg: FeegrantMsg) -> Self {
+        CosmosMsg::Feegrant(msg)
+    }
+}
+
+#[cfg(feature = "authz")]
+impl<T> From<AuthzMsg> for CosmosMsg<T> {
+    fn from(msg: AuthzMsg) -> Self {
+        CosmosMsg::Authz(msg)
+    }
+}
+
+#[cfg(feature = "cosmwasm_1_3")]
+impl<T> From<AnyMsg> for CosmosMsg<T> {
+    fn from(msg: AnyMsg) -> Self {
+        CosmosMsg::Any(msg)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -400,6 +633,58 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "cosmwasm_1_3")]
+    fn from_any_msg_works() {
+        let any = AnyMsg::new("/cosmos.foo.v1beta.MsgBar", b"\x12\x34".to_vec());
+        let msg: CosmosMsg = any.clone().into();
+        match msg {
+            CosmosMsg::Any(msg) => assert_eq!(any, msg),
+            _ => panic!("must encode in Any variant"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_3")]
+    fn any_msg_serializes_to_correct_json() {
+        let msg: CosmosMsg = AnyMsg::new("/cosmos.foo.v1beta.MsgBar", b"\x12\x34".to_vec()).into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"any":{"type_url":"/cosmos.foo.v1beta.MsgBar","value":"EjQ="}}"#,
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    fn cancel_unbonding_delegation_serializes_to_correct_json() {
+        let msg: CosmosMsg = StakingMsg::CancelUnbondingDelegation {
+            validator: "validator1".to_string(),
+            amount: coin(1234, "ustake"),
+            creation_height: 42,
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"staking":{"cancel_unbonding_delegation":{"validator":"validator1","amount":{"denom":"ustake","amount":"1234"},"creation_height":42}}}"#,
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    fn fund_community_pool_serializes_to_correct_json() {
+        let msg: CosmosMsg = DistributionMsg::FundCommunityPool {
+            amount: coins(321, "utoken"),
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"distribution":{"fund_community_pool":{"amount":[{"denom":"utoken","amount":"321"}]}}}"#,
+        );
+    }
+
     #[test]
     fn wasm_msg_serializes_to_correct_json() {
         // Instantiate with admin
@@ -499,6 +784,114 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "feegrant")]
+    fn feegrant_msg_serializes_to_correct_json() {
+        // GrantAllowance (basic)
+        let msg: CosmosMsg = FeegrantMsg::GrantAllowance {
+            grantee: "newbie".to_string(),
+            allowance: FeeAllowance::Basic {
+                spend_limit: coins(500, "ucosm"),
+                expiration: Some(Timestamp::from_seconds(1_700_000_000)),
+            },
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"feegrant":{"grant_allowance":{"grantee":"newbie","allowance":{"basic":{"spend_limit":[{"denom":"ucosm","amount":"500"}],"expiration":"1700000000000000000"}}}}}"#,
+        );
+
+        // GrantAllowance (periodic)
+        let msg: CosmosMsg = FeegrantMsg::GrantAllowance {
+            grantee: "newbie".to_string(),
+            allowance: FeeAllowance::Periodic {
+                spend_limit: vec![],
+                expiration: None,
+                period: 86400,
+                period_spend_limit: coins(20, "ucosm"),
+            },
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"feegrant":{"grant_allowance":{"grantee":"newbie","allowance":{"periodic":{"spend_limit":[],"expiration":null,"period":86400,"period_spend_limit":[{"denom":"ucosm","amount":"20"}]}}}}}"#,
+        );
+
+        // RevokeAllowance
+        let msg: CosmosMsg = FeegrantMsg::RevokeAllowance {
+            grantee: "newbie".to_string(),
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"feegrant":{"revoke_allowance":{"grantee":"newbie"}}}"#,
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "authz")]
+    fn authz_msg_serializes_to_correct_json() {
+        // Grant (send)
+        let msg: CosmosMsg = AuthzMsg::Grant {
+            grantee: "bot".to_string(),
+            authorization: Authorization::Send {
+                spend_limit: coins(100, "ucosm"),
+                allow_list: vec!["alice".to_string()],
+            },
+            expiration: Some(Timestamp::from_seconds(1_700_000_000)),
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"authz":{"grant":{"grantee":"bot","authorization":{"send":{"spend_limit":[{"denom":"ucosm","amount":"100"}],"allow_list":["alice"]}},"expiration":"1700000000000000000"}}}"#,
+        );
+
+        // Grant (generic)
+        let msg: CosmosMsg = AuthzMsg::Grant {
+            grantee: "bot".to_string(),
+            authorization: Authorization::Generic {
+                msg_type_url: "/cosmos.gov.v1beta1.MsgVote".to_string(),
+            },
+            expiration: None,
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"authz":{"grant":{"grantee":"bot","authorization":{"generic":{"msg_type_url":"/cosmos.gov.v1beta1.MsgVote"}},"expiration":null}}}"#,
+        );
+
+        // Revoke
+        let msg: CosmosMsg = AuthzMsg::Revoke {
+            grantee: "bot".to_string(),
+            msg_type_url: "/cosmos.gov.v1beta1.MsgVote".to_string(),
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"authz":{"revoke":{"grantee":"bot","msg_type_url":"/cosmos.gov.v1beta1.MsgVote"}}}"#,
+        );
+
+        // Exec
+        let msg: CosmosMsg = AuthzMsg::Exec {
+            msgs: vec![AnyMsg::new(
+                "/cosmos.bank.v1beta1.MsgSend",
+                b"\x12\x34".to_vec(),
+            )],
+        }
+        .into();
+        let json = to_binary(&msg).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&json),
+            r#"{"authz":{"exec":{"msgs":[{"type_url":"/cosmos.bank.v1beta1.MsgSend","value":"EjQ="}]}}}"#,
+        );
+    }
+
     #[test]
     #[cfg(feature = "stargate")]
     fn gov_msg_serializes_to_correct_json() {
@@ -540,5 +933,20 @@ mod tests {
                 r#"{"vote_weighted":{"proposal_id":25,"options":[{"option":"yes","weight":"0.25"},{"option":"no","weight":"0.25"},{"option":"abstain","weight":"0.5"}]}}"#,
             );
         }
+
+        // Deposit
+        #[cfg(feature = "cosmwasm_1_4")]
+        {
+            let msg = GovMsg::Deposit {
+                proposal_id: 12,
+                amount: coins(1000, "ustake"),
+            };
+
+            let json = to_binary(&msg).unwrap();
+            assert_eq!(
+                String::from_utf8_lossy(&json),
+                r#"{"deposit":{"proposal_id":12,"amount":[{"denom":"ustake","amount":"1000"}]}}"#,
+            );
+        }
     }
 }
@@ -1,6 +1,10 @@
+use std::fmt;
+use std::str::FromStr;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::errors::{StdError, StdResult};
 use crate::forward_ref_partial_eq;
 
 /// A full [*Cosmos SDK* event].
@@ -117,6 +121,111 @@ impl<K: AsRef<str>, V: AsRef<str>> PartialEq<&Attribute> for (K, V) {
     }
 }
 
+/// A typed event, i.e. a struct whose fields are emitted as the attributes of an [`Event`].
+///
+/// This is usually implemented using `#[derive(ContractEvent)]`, which uses the field names
+/// as attribute keys and `ToString`/`FromStr` to convert the values. Fields of type `Option<T>`
+/// are only emitted if they are set. The event type is the struct name in snake case and
+/// can be overridden with `#[event(name = "...")]`. Field names starting with an underscore
+/// are rejected at compile time, since such attribute keys are reserved.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{ContractEvent, Event, Uint128};
+/// #[derive(ContractEvent, Debug, PartialEq)]
+/// struct Transfer {
+///     recipient: String,
+///     amount: Uint128,
+///     memo: Option<String>,
+/// }
+///
+/// let transfer = Transfer {
+///     recipient: "bob".to_string(),
+///     amount: Uint128::new(42),
+///     memo: None,
+/// };
+/// let event = transfer.to_event();
+/// assert_eq!(
+///     event,
+///     Event::new("transfer").add_attributes([("recipient", "bob"), ("amount", "42")])
+/// );
+/// assert_eq!(Transfer::from_event(&event).unwrap(), transfer);
+/// ```
+pub trait ContractEvent: Sized {
+    /// The type of the emitted event
+    const EVENT_TYPE: &'static str;
+
+    /// Converts this into an event of type [`ContractEvent::EVENT_TYPE`]
+    fn to_event(&self) -> Event;
+
+    /// Parses an event created by [`ContractEvent::to_event`]. This is mostly useful for tests.
+    ///
+    /// Fails if the event type does not match or an attribute is missing or cannot be parsed.
+    /// Additional attributes are ignored.
+    fn from_event(event: &Event) -> StdResult<Self>;
+}
+
+/// Ensures the event has the given type. Used by `#[derive(ContractEvent)]`.
+#[doc(hidden)]
+pub fn check_event_type(event: &Event, target: &str, ty: &str) -> StdResult<()> {
+    if event.ty != ty {
+        return Err(StdError::parse_err(
+            target,
+            format!("expected event type `{}`, got `{}`", ty, event.ty),
+        ));
+    }
+    Ok(())
+}
+
+/// Parses a required attribute. Used by `#[derive(ContractEvent)]`.
+#[doc(hidden)]
+pub fn parse_event_attribute<T>(event: &Event, target: &str, key: &str) -> StdResult<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    parse_optional_event_attribute(event, target, key)?
+        .ok_or_else(|| StdError::parse_err(target, format!("missing attribute `{}`", key)))
+}
+
+/// Parses an optional attribute. Used by `#[derive(ContractEvent)]`.
+#[doc(hidden)]
+pub fn parse_optional_event_attribute<T>(
+    event: &Event,
+    target: &str,
+    key: &str,
+) -> StdResult<Option<T>>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| {
+            attr.value.parse().map_err(|err| {
+                StdError::parse_err(target, format!("invalid attribute `{}`: {}", key, err))
+            })
+        })
+        .transpose()
+}
+
+/// Ensures an attribute key is not empty and does not use the reserved `_` prefix
+pub(crate) fn validate_attribute_key(key: &str) -> StdResult<()> {
+    if key.trim().is_empty() {
+        return Err(StdError::generic_err("Attribute key must not be empty"));
+    }
+    if key.starts_with('_') {
+        return Err(StdError::generic_err(format!(
+            "Attribute key `{}` is invalid - keys starting with an underscore are reserved",
+            key
+        )));
+    }
+    Ok(())
+}
+
 /// Creates a new Attribute. `Attribute::new` is an alias for this.
 #[inline]
 pub fn attr(key: impl Into<String>, value: impl Into<String>) -> Attribute {
@@ -160,4 +269,44 @@ mod tests {
         assert_eq!(attr("foo", "42"), expected);
         assert_eq!(attr("foo", Uint128::new(42)), expected);
     }
+
+    #[test]
+    fn parse_event_attribute_works() {
+        let event = Event::new("transfer").add_attributes([("amount", "42"), ("memo", "hi")]);
+
+        check_event_type(&event, "Transfer", "transfer").unwrap();
+        let amount: Uint128 = parse_event_attribute(&event, "Transfer", "amount").unwrap();
+        assert_eq!(amount, Uint128::new(42));
+        let memo: Option<String> =
+            parse_optional_event_attribute(&event, "Transfer", "memo").unwrap();
+        assert_eq!(memo.as_deref(), Some("hi"));
+        let sender: Option<String> =
+            parse_optional_event_attribute(&event, "Transfer", "sender").unwrap();
+        assert_eq!(sender, None);
+    }
+
+    #[test]
+    fn parse_event_attribute_fails_for_invalid_events() {
+        let event = Event::new("transfer").add_attribute("amount", "many");
+
+        match check_event_type(&event, "Mint", "mint").unwrap_err() {
+            StdError::ParseErr {
+                target_type, msg, ..
+            } => {
+                assert_eq!(target_type, "Mint");
+                assert_eq!(msg, "expected event type `mint`, got `transfer`");
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        match parse_event_attribute::<String>(&event, "Transfer", "recipient").unwrap_err() {
+            StdError::ParseErr { msg, .. } => assert_eq!(msg, "missing attribute `recipient`"),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        match parse_event_attribute::<Uint128>(&event, "Transfer", "amount").unwrap_err() {
+            StdError::ParseErr { msg, .. } => {
+                assert!(msg.starts_with("invalid attribute `amount`: "))
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
 }
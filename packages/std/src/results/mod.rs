@@ -4,24 +4,38 @@ mod contract_result;
 mod cosmos_msg;
 mod empty;
 mod events;
+pub(crate) mod msg_responses;
 mod query;
 mod response;
 mod submessages;
 mod system_result;
 
 pub use contract_result::ContractResult;
+#[cfg(feature = "cosmwasm_1_3")]
+pub use cosmos_msg::AnyMsg;
 #[cfg(all(feature = "stargate", feature = "cosmwasm_1_2"))]
 pub use cosmos_msg::WeightedVoteOption;
 pub use cosmos_msg::{wasm_execute, wasm_instantiate, BankMsg, CosmosMsg, CustomMsg, WasmMsg};
+#[cfg(feature = "authz")]
+pub use cosmos_msg::{Authorization, AuthzMsg};
 #[cfg(feature = "staking")]
 pub use cosmos_msg::{DistributionMsg, StakingMsg};
+#[cfg(feature = "feegrant")]
+pub use cosmos_msg::{FeeAllowance, FeegrantMsg};
 #[cfg(feature = "stargate")]
 pub use cosmos_msg::{GovMsg, VoteOption};
 pub use empty::Empty;
-pub use events::{attr, Attribute, Event};
+pub use events::{attr, Attribute, ContractEvent, Event};
+#[doc(hidden)]
+pub use events::{check_event_type, parse_event_attribute, parse_optional_event_attribute};
+pub use msg_responses::{
+    MsgExecuteContractResponse, MsgInstantiateContractResponse, MsgSendResponse,
+};
 pub use query::QueryResponse;
 pub use response::Response;
 #[allow(deprecated)]
 pub use submessages::SubMsgExecutionResponse;
-pub use submessages::{Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult};
+pub use submessages::{
+    JsonPayload, MsgResponse, Reply, ReplyOn, ReplyPayload, SubMsg, SubMsgResponse, SubMsgResult,
+};
 pub use system_result::SystemResult;
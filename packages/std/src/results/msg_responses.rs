@@ -0,0 +1,223 @@
+//! Decoding of common message responses contained in `SubMsgResponse::msg_responses`.
+//!
+//! Only the few fields needed here are decoded, such that we do not need a full
+//! protobuf implementation.
+
+use crate::errors::{StdError, StdResult};
+use crate::protobuf::decode_fields;
+use crate::Binary;
+
+use super::MsgResponse;
+
+pub(crate) const INSTANTIATE_CONTRACT_RESPONSE: &str =
+    "/cosmwasm.wasm.v1.MsgInstantiateContractResponse";
+const INSTANTIATE_CONTRACT_2_RESPONSE: &str = "/cosmwasm.wasm.v1.MsgInstantiateContract2Response";
+pub(crate) const EXECUTE_CONTRACT_RESPONSE: &str = "/cosmwasm.wasm.v1.MsgExecuteContractResponse";
+pub(crate) const BANK_SEND_RESPONSE: &str = "/cosmos.bank.v1beta1.MsgSendResponse";
+
+/// The response of a `WasmMsg::Instantiate` or `WasmMsg::Instantiate2`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgInstantiateContractResponse {
+    /// The address of the new contract
+    pub contract_address: String,
+    /// The data returned by the instantiate entry point of the new contract
+    pub data: Option<Binary>,
+}
+
+/// The response of a `WasmMsg::Execute`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgExecuteContractResponse {
+    /// The data returned by the execute entry point of the contract
+    pub data: Option<Binary>,
+}
+
+/// The response of a `BankMsg::Send`. This does not contain any data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgSendResponse {}
+
+impl MsgResponse {
+    /// Decodes the response of a `WasmMsg::Instantiate` or `WasmMsg::Instantiate2`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{Binary, MsgResponse};
+    /// let response = MsgResponse {
+    ///     type_url: "/cosmwasm.wasm.v1.MsgInstantiateContractResponse".to_string(),
+    ///     value: Binary::from(b"\x0a\x06cosmos\x12\x02\x01\x02"),
+    /// };
+    /// let decoded = response.instantiate_contract_response().unwrap();
+    /// assert_eq!(decoded.contract_address, "cosmos");
+    /// assert_eq!(decoded.data, Some(Binary::from([1, 2])));
+    /// ```
+    pub fn instantiate_contract_response(&self) -> StdResult<MsgInstantiateContractResponse> {
+        if self.type_url != INSTANTIATE_CONTRACT_RESPONSE
+            && self.type_url != INSTANTIATE_CONTRACT_2_RESPONSE
+        {
+            return Err(self.unexpected_type("MsgInstantiateContractResponse"));
+        }
+        let fields = decode_fields(&self.value)
+            .map_err(|msg| StdError::parse_err("MsgInstantiateContractResponse", msg))?;
+        let address = last_field(&fields, 1).unwrap_or_default();
+        let contract_address = String::from_utf8(address.to_vec())
+            .map_err(|err| StdError::parse_err("MsgInstantiateContractResponse", err))?;
+        Ok(MsgInstantiateContractResponse {
+            contract_address,
+            data: last_field(&fields, 2).map(Binary::from),
+        })
+    }
+
+    /// Decodes the response of a `WasmMsg::Execute`
+    pub fn execute_contract_response(&self) -> StdResult<MsgExecuteContractResponse> {
+        if self.type_url != EXECUTE_CONTRACT_RESPONSE {
+            return Err(self.unexpected_type("MsgExecuteContractResponse"));
+        }
+        let fields = decode_fields(&self.value)
+            .map_err(|msg| StdError::parse_err("MsgExecuteContractResponse", msg))?;
+        Ok(MsgExecuteContractResponse {
+            data: last_field(&fields, 1).map(Binary::from),
+        })
+    }
+
+    /// Decodes the response of a `BankMsg::Send`
+    pub fn bank_send_response(&self) -> StdResult<MsgSendResponse> {
+        if self.type_url != BANK_SEND_RESPONSE {
+            return Err(self.unexpected_type("MsgSendResponse"));
+        }
+        decode_fields(&self.value).map_err(|msg| StdError::parse_err("MsgSendResponse", msg))?;
+        Ok(MsgSendResponse {})
+    }
+
+    fn unexpected_type(&self, target: &str) -> StdError {
+        StdError::parse_err(
+            target,
+            format!("unexpected response type URL: {}", self.type_url),
+        )
+    }
+}
+
+/// Returns the value of the last occurrence of a length-delimited field,
+/// which is the protobuf semantics for non-repeated fields.
+fn last_field<'a>(fields: &[(u64, &'a [u8])], number: u64) -> Option<&'a [u8]> {
+    fields
+        .iter()
+        .rev()
+        .find(|(field_number, _)| *field_number == number)
+        .map(|(_, value)| *value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(type_url: &str, value: &[u8]) -> MsgResponse {
+        MsgResponse {
+            type_url: type_url.to_string(),
+            value: Binary::from(value),
+        }
+    }
+
+    #[test]
+    fn instantiate_contract_response_works() {
+        let res = response(
+            INSTANTIATE_CONTRACT_RESPONSE,
+            b"\x0a\x08contract\x12\x03abc",
+        );
+        assert_eq!(
+            res.instantiate_contract_response().unwrap(),
+            MsgInstantiateContractResponse {
+                contract_address: "contract".to_string(),
+                data: Some(Binary::from(b"abc")),
+            }
+        );
+
+        // Instantiate2 has the same format
+        let res = response(INSTANTIATE_CONTRACT_2_RESPONSE, b"\x0a\x08contract");
+        assert_eq!(
+            res.instantiate_contract_response().unwrap(),
+            MsgInstantiateContractResponse {
+                contract_address: "contract".to_string(),
+                data: None,
+            }
+        );
+
+        // unknown fields are skipped
+        let res = response(
+            INSTANTIATE_CONTRACT_RESPONSE,
+            b"\x18\x96\x01\x0a\x01a\x25\x01\x02\x03\x04",
+        );
+        assert_eq!(
+            res.instantiate_contract_response().unwrap(),
+            MsgInstantiateContractResponse {
+                contract_address: "a".to_string(),
+                data: None,
+            }
+        );
+    }
+
+    #[test]
+    fn instantiate_contract_response_fails_for_invalid_data() {
+        let res = response(INSTANTIATE_CONTRACT_RESPONSE, b"\x0a\x08contr");
+        match res.instantiate_contract_response().unwrap_err() {
+            StdError::ParseErr {
+                target_type, msg, ..
+            } => {
+                assert_eq!(target_type, "MsgInstantiateContractResponse");
+                assert_eq!(msg, "unexpected end of data");
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        let res = response(INSTANTIATE_CONTRACT_RESPONSE, b"\x0a\x02\xff\xfe");
+        match res.instantiate_contract_response().unwrap_err() {
+            StdError::ParseErr { target_type, .. } => {
+                assert_eq!(target_type, "MsgInstantiateContractResponse")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn execute_contract_response_works() {
+        let res = response(EXECUTE_CONTRACT_RESPONSE, b"\x0a\x02{}");
+        assert_eq!(
+            res.execute_contract_response().unwrap(),
+            MsgExecuteContractResponse {
+                data: Some(Binary::from(b"{}")),
+            }
+        );
+
+        let res = response(EXECUTE_CONTRACT_RESPONSE, b"");
+        assert_eq!(
+            res.execute_contract_response().unwrap(),
+            MsgExecuteContractResponse { data: None }
+        );
+    }
+
+    #[test]
+    fn bank_send_response_works() {
+        let res = response(BANK_SEND_RESPONSE, b"");
+        assert_eq!(res.bank_send_response().unwrap(), MsgSendResponse {});
+    }
+
+    #[test]
+    fn decoding_fails_for_wrong_type_url() {
+        let res = response(BANK_SEND_RESPONSE, b"");
+        match res.execute_contract_response().unwrap_err() {
+            StdError::ParseErr {
+                target_type, msg, ..
+            } => {
+                assert_eq!(target_type, "MsgExecuteContractResponse");
+                assert_eq!(
+                    msg,
+                    "unexpected response type URL: /cosmos.bank.v1beta1.MsgSendResponse"
+                );
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        res.instantiate_contract_response().unwrap_err();
+
+        let res = response(EXECUTE_CONTRACT_RESPONSE, b"");
+        res.bank_send_response().unwrap_err();
+    }
+}
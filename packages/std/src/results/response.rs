@@ -1,8 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::errors::{StdError, StdResult};
 use crate::Binary;
 
+use super::events::validate_attribute_key;
 use super::{Attribute, CosmosMsg, Empty, Event, SubMsg};
 
 /// A response of a contract entry point, such as `instantiate`, `execute` or `migrate`.
@@ -109,6 +111,22 @@ impl<T> Response<T> {
         self
     }
 
+    /// Like [`add_attribute`][Self::add_attribute], but validates the key first.
+    ///
+    /// Returns an error if the key is empty, consists of whitespace only or starts with
+    /// the reserved `_` prefix. The chain rejects responses with such attributes, so this
+    /// allows handling invalid keys, e.g. ones built from user input, in the contract.
+    pub fn add_attribute_checked(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> StdResult<Self> {
+        let key = key.into();
+        validate_attribute_key(&key)?;
+        self.attributes.push(Attribute::new(key, value));
+        Ok(self)
+    }
+
     /// This creates a "fire and forget" message, by using `SubMsg::new()` to wrap it,
     /// and adds it to the list of messages to process.
     pub fn add_message(mut self, msg: impl Into<CosmosMsg<T>>) -> Self {
@@ -116,6 +134,31 @@ impl<T> Response<T> {
         self
     }
 
+    /// Adds a "fire and forget" message like [`add_message`][Self::add_message]
+    /// if `condition` is true and leaves the response unchanged otherwise.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, BankMsg, Response};
+    /// let fee = 0u128;
+    /// let res: Response = Response::new().add_message_if(
+    ///     fee > 0,
+    ///     BankMsg::Send {
+    ///         to_address: "collector".to_string(),
+    ///         amount: coins(fee, "ucosm"),
+    ///     },
+    /// );
+    /// assert!(res.messages.is_empty());
+    /// ```
+    pub fn add_message_if(self, condition: bool, msg: impl Into<CosmosMsg<T>>) -> Self {
+        if condition {
+            self.add_message(msg)
+        } else {
+            self
+        }
+    }
+
     /// This takes an explicit SubMsg (creates via eg. `reply_on_error`)
     /// and adds it to the list of messages to process.
     pub fn add_submessage(mut self, msg: SubMsg<T>) -> Self {
@@ -133,6 +176,22 @@ impl<T> Response<T> {
         self
     }
 
+    /// Like [`add_event`][Self::add_event], but validates the event first.
+    ///
+    /// Fails if the event type is empty or an attribute key is empty or starts
+    /// with the reserved `_` prefix, such that invalid events are caught when
+    /// building the response rather than when the chain processes it.
+    pub fn try_add_event(mut self, event: Event) -> StdResult<Self> {
+        if event.ty.trim().is_empty() {
+            return Err(StdError::generic_err("Event type must not be empty"));
+        }
+        for attr in &event.attributes {
+            validate_attribute_key(&attr.key)?;
+        }
+        self.events.push(event);
+        Ok(self)
+    }
+
     /// Bulk add attributes included in the main `wasm` event.
     ///
     /// Anything that can be turned into an iterator and yields something
@@ -228,6 +287,53 @@ impl<T> Response<T> {
         self.data = Some(data.into());
         self
     }
+
+    /// Converts the custom message type of all messages using the given fallible function.
+    /// The first error is returned. Attributes, events and data are kept.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{CosmosMsg, Empty, Response, StdError};
+    /// # #[derive(Clone, Debug, PartialEq)]
+    /// # enum MyMsg { Ping }
+    /// let res: Response<MyMsg> = Response::new().add_message(CosmosMsg::Custom(MyMsg::Ping));
+    /// // Empty has no way to represent custom messages
+    /// let converted: Result<Response<Empty>, StdError> =
+    ///     res.try_map_msgs(|_| Err(StdError::generic_err("custom messages not supported")));
+    /// assert!(converted.is_err());
+    /// ```
+    pub fn try_map_msgs<U, E>(
+        self,
+        mut f: impl FnMut(T) -> Result<U, E>,
+    ) -> Result<Response<U>, E> {
+        let messages = self
+            .messages
+            .into_iter()
+            .map(|msg| msg.try_map(&mut f))
+            .collect::<Result<_, E>>()?;
+        Ok(Response {
+            messages,
+            attributes: self.attributes,
+            events: self.events,
+            data: self.data,
+        })
+    }
+
+    /// Converts the custom message type of all messages using the given function.
+    /// Attributes, events and data are kept.
+    pub fn map_custom_msgs<U>(self, mut f: impl FnMut(T) -> U) -> Response<U> {
+        Response {
+            messages: self
+                .messages
+                .into_iter()
+                .map(|msg| msg.map(&mut f))
+                .collect(),
+            attributes: self.attributes,
+            events: self.events,
+            data: self.data,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -237,6 +343,44 @@ mod tests {
     use crate::results::submessages::{ReplyOn, UNUSED_MSG_ID};
     use crate::{coins, from_slice, to_vec, ContractResult};
 
+    #[test]
+    fn response_map_custom_msgs_works() {
+        let msg = SubMsg::reply_always(CosmosMsg::Custom(7u32), 42).with_gas_limit(100);
+        let bank = BankMsg::Burn {
+            amount: coins(1, "earth"),
+        };
+        let res = Response::new()
+            .add_submessage(msg)
+            .add_message(bank.clone())
+            .add_attribute("foo", "bar")
+            .set_data(b"hi");
+
+        let converted = res.clone().map_custom_msgs(|n| n.to_string());
+        assert_eq!(converted.messages.len(), 2);
+        assert_eq!(converted.messages[0].id, 42);
+        assert_eq!(converted.messages[0].gas_limit, Some(100));
+        assert_eq!(converted.messages[0].reply_on, ReplyOn::Always);
+        assert_eq!(
+            converted.messages[0].msg,
+            CosmosMsg::Custom("7".to_string())
+        );
+        assert_eq!(converted.messages[1].msg, CosmosMsg::Bank(bank.clone()));
+        assert_eq!(converted.attributes, res.attributes);
+        assert_eq!(converted.data, res.data);
+
+        let converted = res
+            .clone()
+            .try_map_msgs(|n| u8::try_from(n).map_err(|_| "too big"))
+            .unwrap();
+        assert_eq!(converted.messages[0].msg, CosmosMsg::Custom(7u8));
+        assert_eq!(converted.messages[1].msg, CosmosMsg::Bank(bank));
+
+        let err = res
+            .try_map_msgs(|_| Err::<Empty, _>("unsupported"))
+            .unwrap_err();
+        assert_eq!(err, "unsupported");
+    }
+
     #[test]
     fn response_add_attributes_works() {
         let res = Response::<Empty>::new().add_attributes(std::iter::empty::<Attribute>());
@@ -282,6 +426,7 @@ mod tests {
             messages: vec![
                 SubMsg {
                     id: 12,
+                    payload: Binary::from([9, 8, 7, 6, 5]),
                     msg: BankMsg::Send {
                         to_address: String::from("checker"),
                         amount: coins(888, "moon"),
@@ -292,6 +437,7 @@ mod tests {
                 },
                 SubMsg {
                     id: UNUSED_MSG_ID,
+                    payload: Binary::default(),
                     msg: BankMsg::Send {
                         to_address: String::from("you"),
                         amount: coins(1015, "earth"),
@@ -313,6 +459,63 @@ mod tests {
         assert_eq!(deserialized, original);
     }
 
+    #[test]
+    fn response_add_message_if_works() {
+        let msg = BankMsg::Burn {
+            amount: coins(1, "earth"),
+        };
+        let res: Response = Response::new()
+            .add_message_if(false, msg.clone())
+            .add_message_if(true, msg.clone());
+        assert_eq!(res.messages, [SubMsg::new(msg)]);
+    }
+
+    #[test]
+    fn response_add_attribute_checked_works() {
+        let res: Response = Response::new()
+            .add_attribute_checked("action", "mint")
+            .unwrap();
+        assert_eq!(res.attributes, [("action", "mint")]);
+
+        let err = Response::<Empty>::new()
+            .add_attribute_checked("", "mint")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Attribute key must not be empty")
+        );
+        let err = Response::<Empty>::new()
+            .add_attribute_checked("_contract_address", "contract")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err(
+                "Attribute key `_contract_address` is invalid - keys starting with an underscore are reserved"
+            )
+        );
+    }
+
+    #[test]
+    fn response_try_add_event_works() {
+        let event = Event::new("mint").add_attribute("amount", "7");
+        let res: Response = Response::new().try_add_event(event.clone()).unwrap();
+        assert_eq!(res.events, [event]);
+
+        let err = Response::<Empty>::new()
+            .try_add_event(Event::new(" "))
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Event type must not be empty"));
+
+        let mut event = Event::new("mint");
+        event.attributes.push(Attribute {
+            key: "_reserved".to_string(),
+            value: "7".to_string(),
+        });
+        Response::<Empty>::new().try_add_event(event).unwrap_err();
+        let event = Event::new("mint").add_attribute(" ", "7");
+        Response::<Empty>::new().try_add_event(event).unwrap_err();
+    }
+
     #[test]
     fn contract_result_is_ok_works() {
         let success = ContractResult::<()>::Ok(());
@@ -1,6 +1,9 @@
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::errors::{StdError, StdResult};
+use crate::serde::from_binary;
 use crate::Binary;
 
 use super::{CosmosMsg, Empty, Event};
@@ -19,6 +22,11 @@ pub enum ReplyOn {
     Success,
     /// Never make a callback - this is like the original CosmosMsg semantics
     Never,
+    /// Never make a callback, but require the SubMsg to succeed and use its `data`
+    /// as the `data` of the calling contract's response. This saves the `reply`
+    /// round-trip for contracts that only proxy a call and return its result.
+    #[cfg(feature = "cosmwasm_1_4")]
+    ForwardData,
 }
 
 /// A submessage that will guarantee a `reply` call on success or error, depending on
@@ -32,6 +40,18 @@ pub struct SubMsg<T = Empty> {
     /// An arbitrary ID chosen by the contract.
     /// This is typically used to match `Reply`s in the `reply` entry point to the submessage.
     pub id: u64,
+    /// Some arbitrary data that the contract can set in an application specific way.
+    /// This is just passed into the `reply` entry point and is not stored to state.
+    /// Any encoding can be used. If `id` is used to identify a particular action,
+    /// the encoding can also be different for each of those actions since you can match `id`
+    /// first and then start processing the `payload`.
+    ///
+    /// Unset/nil/null cannot be differentiated from empty data.
+    ///
+    /// Hosts that do not support this field ignore it, such that the `Reply` contains
+    /// an empty `payload`.
+    #[serde(default)]
+    pub payload: Binary,
     pub msg: CosmosMsg<T>,
     /// Gas limit measured in [Cosmos SDK gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
     pub gas_limit: Option<u64>,
@@ -46,6 +66,7 @@ impl<T> SubMsg<T> {
     pub fn new(msg: impl Into<CosmosMsg<T>>) -> Self {
         SubMsg {
             id: UNUSED_MSG_ID,
+            payload: Binary::default(),
             msg: msg.into(),
             reply_on: ReplyOn::Never,
             gas_limit: None,
@@ -67,6 +88,30 @@ impl<T> SubMsg<T> {
         Self::reply_on(msg.into(), id, ReplyOn::Always)
     }
 
+    /// create a `SubMsg` that never provides a `reply` but forwards the `data` of the
+    /// successful submessage execution as the `data` of this contract's response.
+    /// If the submessage fails, the whole transaction fails like with a regular message.
+    ///
+    /// If the contract's `Response` sets `data` itself, it takes precedence over the
+    /// forwarded data.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{to_binary, Empty, ReplyOn, SubMsg, WasmMsg};
+    /// let msg = WasmMsg::Execute {
+    ///     contract_addr: "target".to_string(),
+    ///     msg: to_binary(&Empty {}).unwrap(),
+    ///     funds: vec![],
+    /// };
+    /// let sub_msg: SubMsg = SubMsg::reply_never(msg);
+    /// assert_eq!(sub_msg.reply_on, ReplyOn::ForwardData);
+    /// ```
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn reply_never(msg: impl Into<CosmosMsg<T>>) -> Self {
+        Self::reply_on(msg.into(), UNUSED_MSG_ID, ReplyOn::ForwardData)
+    }
+
     /// Add a gas limit to the message.
     /// This gas limit measured in [Cosmos SDK gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
     ///
@@ -85,9 +130,51 @@ impl<T> SubMsg<T> {
         self
     }
 
+    /// Add a payload to the message. The payload is passed back to the `reply`
+    /// entry point in [`Reply::payload`], which avoids storing temporary state
+    /// to correlate a `reply` with the action that created the submessage.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{coins, BankMsg, Binary, SubMsg};
+    /// # let msg = BankMsg::Send { to_address: String::from("you"), amount: coins(1015, "earth") };
+    /// let sub_msg: SubMsg = SubMsg::reply_on_success(msg, 1234).with_payload(b"some context");
+    /// assert_eq!(sub_msg.payload, Binary::from(b"some context"));
+    /// ```
+    pub fn with_payload(mut self, payload: impl Into<Binary>) -> Self {
+        self.payload = payload.into();
+        self
+    }
+
+    /// Converts the custom message type of the contained message using the given
+    /// fallible function. All other fields are kept.
+    pub fn try_map<U, E>(self, f: impl FnOnce(T) -> Result<U, E>) -> Result<SubMsg<U>, E> {
+        Ok(SubMsg {
+            id: self.id,
+            payload: self.payload,
+            msg: self.msg.try_map_custom(f)?,
+            gas_limit: self.gas_limit,
+            reply_on: self.reply_on,
+        })
+    }
+
+    /// Converts the custom message type of the contained message using the given function.
+    /// All other fields are kept.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SubMsg<U> {
+        SubMsg {
+            id: self.id,
+            payload: self.payload,
+            msg: self.msg.map_custom(f),
+            gas_limit: self.gas_limit,
+            reply_on: self.reply_on,
+        }
+    }
+
     fn reply_on(msg: CosmosMsg<T>, id: u64, reply_on: ReplyOn) -> Self {
         SubMsg {
             id,
+            payload: Binary::default(),
             msg,
             reply_on,
             gas_limit: None,
@@ -98,13 +185,136 @@ impl<T> SubMsg<T> {
 /// The result object returned to `reply`. We always get the ID from the submessage
 /// back and then must handle success and error cases ourselves.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
-pub struct Reply {
+#[serde(
+    into = "ReplyJson",
+    from = "ReplyJson",
+    bound(serialize = "P: Clone + Into<Binary>", deserialize = "P: From<Binary>")
+)]
+#[schemars(rename = "Reply", bound = "P: JsonSchema + Default + Serialize")]
+pub struct Reply<P = Binary> {
     /// The ID that the contract set when emitting the `SubMsg`.
     /// Use this to identify which submessage triggered the `reply`.
     pub id: u64,
+    /// Some arbitrary data that the contract set when emitting the `SubMsg`.
+    /// This is just passed into the `reply` entry point and is not stored to state.
+    ///
+    /// Unset/nil/null cannot be differentiated from empty data.
+    ///
+    /// This is empty if the host does not support payloads.
+    #[schemars(default)]
+    pub payload: P,
     pub result: SubMsgResult,
 }
 
+/// The JSON representation of a [`Reply`], which only exists for byte payloads
+#[derive(Serialize, Deserialize)]
+struct ReplyJson {
+    id: u64,
+    #[serde(default)]
+    payload: Binary,
+    result: SubMsgResult,
+}
+
+impl<P: Into<Binary>> From<Reply<P>> for ReplyJson {
+    fn from(reply: Reply<P>) -> Self {
+        ReplyJson {
+            id: reply.id,
+            payload: reply.payload.into(),
+            result: reply.result,
+        }
+    }
+}
+
+impl<P: From<Binary>> From<ReplyJson> for Reply<P> {
+    fn from(reply: ReplyJson) -> Self {
+        Reply {
+            id: reply.id,
+            payload: reply.payload.into(),
+            result: reply.result,
+        }
+    }
+}
+
+/// The payload is passed as raw bytes by default. Only replies with byte payloads like [`Binary`]
+/// can be serialized, since this is the JSON format the host sends to the contract.
+/// Replies with other payload types are created via [`Reply::decode_payload`].
+/// A `reply` entry point can take a `Reply<JsonPayload<P>>` instead of a `Reply` to get the
+/// payload deserialized from JSON into `P`, see [`ReplyPayload`].
+impl Reply {
+    /// Deserializes the payload from JSON, e.g. a payload set via
+    /// `SubMsg::with_payload(to_binary(&payload)?)`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::{to_binary, Reply, SubMsgResult};
+    /// let reply = Reply {
+    ///     id: 1,
+    ///     payload: to_binary(&("alice", 5u64)).unwrap(),
+    ///     result: SubMsgResult::Err("failed".to_string()),
+    /// };
+    /// let decoded: Reply<(String, u64)> = reply.decode_payload().unwrap();
+    /// assert_eq!(decoded.payload, ("alice".to_string(), 5));
+    /// ```
+    pub fn decode_payload<P: DeserializeOwned>(self) -> StdResult<Reply<P>> {
+        let payload = from_binary(&self.payload).map_err(|err| {
+            StdError::generic_err(format!(
+                "Failed to decode payload of reply {}: {}",
+                self.id, err
+            ))
+        })?;
+        Ok(Reply {
+            id: self.id,
+            payload,
+            result: self.result,
+        })
+    }
+}
+
+/// A reply payload that is deserialized from JSON into `T`.
+///
+/// A `reply` entry point taking a `Reply<JsonPayload<T>>` gets the payload decoded via
+/// [`Reply::decode_payload`] and fails with an error if this does not work.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonPayload<T>(pub T);
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::Binary {}
+    impl<T> Sealed for super::JsonPayload<T> {}
+}
+
+/// The payload types a `reply` entry point can take.
+///
+/// This is implemented for [`Binary`], which gets the raw payload, and for [`JsonPayload`],
+/// which gets the payload deserialized from JSON.
+pub trait ReplyPayload: sealed::Sealed + Sized {
+    /// Converts the reply sent by the host into a reply with this payload type
+    fn from_reply(reply: Reply) -> StdResult<Reply<Self>>;
+}
+
+impl ReplyPayload for Binary {
+    fn from_reply(reply: Reply) -> StdResult<Reply<Self>> {
+        Ok(reply)
+    }
+}
+
+impl<T: DeserializeOwned> ReplyPayload for JsonPayload<T> {
+    fn from_reply(reply: Reply) -> StdResult<Reply<Self>> {
+        let Reply {
+            id,
+            payload,
+            result,
+        } = reply.decode_payload()?;
+        Ok(Reply {
+            id,
+            payload: JsonPayload(payload),
+            result,
+        })
+    }
+}
+
 /// This is the result type that is returned from a sub message execution.
 ///
 /// We use a custom type here instead of Rust's Result because we want to be able to
@@ -123,10 +333,11 @@ pub struct Reply {
 /// # use cosmwasm_std::{to_vec, Binary, Event, SubMsgResponse, SubMsgResult};
 /// let response = SubMsgResponse {
 ///     data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+///     msg_responses: vec![],
 ///     events: vec![Event::new("wasm").add_attribute("fo", "ba")],
 /// };
 /// let result: SubMsgResult = SubMsgResult::Ok(response);
-/// assert_eq!(to_vec(&result).unwrap(), br#"{"ok":{"events":[{"type":"wasm","attributes":[{"key":"fo","value":"ba"}]}],"data":"MTIzCg=="}}"#);
+/// assert_eq!(to_vec(&result).unwrap(), br#"{"ok":{"events":[{"type":"wasm","attributes":[{"key":"fo","value":"ba"}]}],"data":"MTIzCg==","msg_responses":[]}}"#);
 /// ```
 ///
 /// Failure:
@@ -196,7 +407,27 @@ impl From<SubMsgResult> for Result<SubMsgResponse, String> {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct SubMsgResponse {
     pub events: Vec<Event>,
+    /// The data returned by the message execution. This is deprecated in the Cosmos SDK
+    /// in favour of `msg_responses`, which contains the typed response of every message.
+    /// It is still filled on hosts that do not set `msg_responses`.
     pub data: Option<Binary>,
+    /// The responses of the messages executed, in the order of execution.
+    /// This mirrors `msg_responses` of Cosmos SDK 0.50 and is empty on hosts that
+    /// do not support it.
+    ///
+    /// See [`MsgResponse`] for helpers to decode common response types.
+    #[serde(default)]
+    pub msg_responses: Vec<MsgResponse>,
+}
+
+/// A protobuf encoded response of a single message, as contained in the
+/// `msg_responses` of a transaction result.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MsgResponse {
+    /// The type URL of the response, e.g. `/cosmwasm.wasm.v1.MsgExecuteContractResponse`
+    pub type_url: String,
+    /// The protobuf encoded response
+    pub value: Binary,
 }
 
 #[deprecated(note = "Renamed to SubMsgResponse")]
@@ -207,24 +438,159 @@ mod tests {
     use super::*;
     use crate::{from_slice, to_vec, StdError, StdResult};
 
+    #[test]
+    fn reply_on_serialization_works() {
+        assert_eq!(to_vec(&ReplyOn::Always).unwrap(), br#""always""#);
+        assert_eq!(to_vec(&ReplyOn::Never).unwrap(), br#""never""#);
+        #[cfg(feature = "cosmwasm_1_4")]
+        assert_eq!(to_vec(&ReplyOn::ForwardData).unwrap(), br#""forward_data""#);
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn sub_msg_reply_never_works() {
+        use crate::BankMsg;
+
+        let msg = BankMsg::Burn {
+            amount: crate::coins(1, "earth"),
+        };
+        let sub_msg: SubMsg = SubMsg::reply_never(msg.clone());
+        assert_eq!(
+            sub_msg,
+            SubMsg {
+                id: UNUSED_MSG_ID,
+                payload: Binary::default(),
+                msg: msg.into(),
+                gas_limit: None,
+                reply_on: ReplyOn::ForwardData,
+            }
+        );
+    }
+
+    #[test]
+    fn reply_serialization_works() {
+        let reply = Reply {
+            id: 15,
+            payload: Binary::from(b"context"),
+            result: SubMsgResult::Err("broken".to_string()),
+        };
+        assert_eq!(
+            &to_vec(&reply).unwrap(),
+            br#"{"id":15,"payload":"Y29udGV4dA==","result":{"error":"broken"}}"#
+        );
+
+        // payload defaults to empty
+        let reply: Reply = from_slice(br#"{"id":15,"result":{"error":"broken"}}"#).unwrap();
+        assert_eq!(reply.payload, Binary::default());
+    }
+
+    #[test]
+    fn reply_decode_payload_works() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Payload {
+            recipient: String,
+        }
+
+        let result = SubMsgResult::Err("broken".to_string());
+        let reply = Reply {
+            id: 15,
+            payload: Binary::from(br#"{"recipient":"alice"}"#),
+            result: result.clone(),
+        };
+        let decoded: Reply<Payload> = reply.decode_payload().unwrap();
+        assert_eq!(
+            decoded,
+            Reply {
+                id: 15,
+                payload: Payload {
+                    recipient: "alice".to_string()
+                },
+                result: result.clone(),
+            }
+        );
+
+        // mismatching payload
+        let reply = Reply {
+            id: 16,
+            payload: Binary::from(br#"{"sender":"alice"}"#),
+            result: result.clone(),
+        };
+        let err = reply.decode_payload::<Payload>().unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Generic error: Failed to decode payload of reply 16: "),
+            "{}",
+            err
+        );
+
+        // empty payload
+        let reply = Reply {
+            id: 17,
+            payload: Binary::default(),
+            result,
+        };
+        reply.decode_payload::<Payload>().unwrap_err();
+    }
+
+    #[test]
+    fn reply_payload_from_reply_works() {
+        let result = SubMsgResult::Err("broken".to_string());
+        let reply = Reply {
+            id: 15,
+            payload: Binary::from(br#""alice""#),
+            result: result.clone(),
+        };
+
+        // Binary payloads are passed through unchanged
+        assert_eq!(Binary::from_reply(reply.clone()).unwrap(), reply);
+
+        // JSON payloads are decoded
+        assert_eq!(
+            JsonPayload::<String>::from_reply(reply).unwrap(),
+            Reply {
+                id: 15,
+                payload: JsonPayload("alice".to_string()),
+                result,
+            }
+        );
+    }
+
+    #[test]
+    fn reply_schema_describes_binary_payload() {
+        let schema = serde_json::to_value(schemars::schema_for!(Reply)).unwrap();
+        assert_eq!(schema["title"], "Reply");
+        assert_eq!(
+            schema["description"],
+            "The result object returned to `reply`. We always get the ID from the submessage back and then must handle success and error cases ourselves."
+        );
+        assert_eq!(schema["required"], serde_json::json!(["id", "result"]));
+        assert_eq!(schema["properties"]["payload"]["default"], "");
+        assert_eq!(
+            schema["properties"]["payload"]["allOf"][0]["$ref"],
+            "#/definitions/Binary"
+        );
+    }
+
     #[test]
     fn sub_msg_result_serialization_works() {
         let result = SubMsgResult::Ok(SubMsgResponse {
             data: None,
+            msg_responses: vec![],
             events: vec![],
         });
         assert_eq!(
             &to_vec(&result).unwrap(),
-            br#"{"ok":{"events":[],"data":null}}"#
+            br#"{"ok":{"events":[],"data":null,"msg_responses":[]}}"#
         );
 
         let result = SubMsgResult::Ok(SubMsgResponse {
             data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+            msg_responses: vec![],
             events: vec![Event::new("wasm").add_attribute("fo", "ba")],
         });
         assert_eq!(
             &to_vec(&result).unwrap(),
-            br#"{"ok":{"events":[{"type":"wasm","attributes":[{"key":"fo","value":"ba"}]}],"data":"MTIzCg=="}}"#
+            br#"{"ok":{"events":[{"type":"wasm","attributes":[{"key":"fo","value":"ba"}]}],"data":"MTIzCg==","msg_responses":[]}}"#
         );
 
         let result: SubMsgResult = SubMsgResult::Err("broken".to_string());
@@ -239,6 +605,7 @@ mod tests {
             SubMsgResult::Ok(SubMsgResponse {
                 events: vec![],
                 data: None,
+                msg_responses: vec![],
             })
         );
 
@@ -248,10 +615,25 @@ mod tests {
             result,
             SubMsgResult::Ok(SubMsgResponse {
                 data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+                msg_responses: vec![],
                 events: vec![Event::new("wasm").add_attribute("fo", "ba")],
             })
         );
 
+        let result: SubMsgResult = from_slice(
+            br#"{"ok":{"events":[],"data":null,"msg_responses":[{"type_url":"/cosmos.bank.v1beta1.MsgSendResponse","value":""}]}}"#).unwrap();
+        assert_eq!(
+            result,
+            SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![MsgResponse {
+                    type_url: "/cosmos.bank.v1beta1.MsgSendResponse".to_string(),
+                    value: Binary::default(),
+                }],
+            })
+        );
+
         let result: SubMsgResult = from_slice(br#"{"error":"broken"}"#).unwrap();
         assert_eq!(result, SubMsgResult::Err("broken".to_string()));
 
@@ -272,6 +654,7 @@ mod tests {
     fn sub_msg_result_unwrap_works() {
         let response = SubMsgResponse {
             data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+            msg_responses: vec![],
             events: vec![Event::new("wasm").add_attribute("fo", "ba")],
         };
         let success = SubMsgResult::Ok(response.clone());
@@ -296,6 +679,7 @@ mod tests {
     fn sub_msg_result_unwrap_err_panics_for_ok() {
         let response = SubMsgResponse {
             data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+            msg_responses: vec![],
             events: vec![Event::new("wasm").add_attribute("fo", "ba")],
         };
         let success = SubMsgResult::Ok(response);
@@ -306,6 +690,7 @@ mod tests {
     fn sub_msg_result_is_ok_works() {
         let success = SubMsgResult::Ok(SubMsgResponse {
             data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+            msg_responses: vec![],
             events: vec![Event::new("wasm").add_attribute("fo", "ba")],
         });
         let failure = SubMsgResult::Err("broken".to_string());
@@ -317,6 +702,7 @@ mod tests {
     fn sub_msg_result_is_err_works() {
         let success = SubMsgResult::Ok(SubMsgResponse {
             data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+            msg_responses: vec![],
             events: vec![Event::new("wasm").add_attribute("fo", "ba")],
         });
         let failure = SubMsgResult::Err("broken".to_string());
@@ -328,6 +714,7 @@ mod tests {
     fn sub_msg_result_can_convert_from_core_result() {
         let original: Result<SubMsgResponse, StdError> = Ok(SubMsgResponse {
             data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+            msg_responses: vec![],
             events: vec![],
         });
         let converted: SubMsgResult = original.into();
@@ -335,6 +722,7 @@ mod tests {
             converted,
             SubMsgResult::Ok(SubMsgResponse {
                 data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+                msg_responses: vec![],
                 events: vec![],
             })
         );
@@ -351,6 +739,7 @@ mod tests {
     fn sub_msg_result_can_convert_to_core_result() {
         let original = SubMsgResult::Ok(SubMsgResponse {
             data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+            msg_responses: vec![],
             events: vec![],
         });
         let converted: Result<SubMsgResponse, String> = original.into();
@@ -358,6 +747,7 @@ mod tests {
             converted,
             Ok(SubMsgResponse {
                 data: Some(Binary::from_base64("MTIzCg==").unwrap()),
+                msg_responses: vec![],
                 events: vec![],
             })
         );
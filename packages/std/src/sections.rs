@@ -1,13 +1,25 @@
 use crate::conversion::force_to_u32;
 
 /// A sections decoder for the special case of two elements
-#[allow(dead_code)] // used in Wasm and tests only
 pub fn decode_sections2(data: Vec<u8>) -> (Vec<u8>, Vec<u8>) {
     let (rest, second) = split_tail(data);
     let (_, first) = split_tail(rest);
     (first, second)
 }
 
+/// Decodes any number of sections, see [`encode_sections`] for the format
+#[allow(dead_code)] // only used with the cosmwasm_1_4 feature
+pub fn decode_sections(mut data: Vec<u8>) -> Vec<Vec<u8>> {
+    let mut sections = Vec::new();
+    while !data.is_empty() {
+        let (rest, tail) = split_tail(data);
+        sections.push(tail);
+        data = rest;
+    }
+    sections.reverse();
+    sections
+}
+
 /// Encodes multiple sections of data into one vector.
 ///
 /// Each section is suffixed by a section length encoded as big endian uint32.
@@ -20,7 +32,6 @@ pub fn decode_sections2(data: Vec<u8>) -> (Vec<u8>, Vec<u8>) {
 /// ```ignore
 /// section1 || section1_len || section2 || section2_len || section3 || section3_len || …
 /// ```
-#[allow(dead_code)] // used in Wasm and tests only
 pub fn encode_sections(sections: &[&[u8]]) -> Vec<u8> {
     let mut out_len: usize = sections.iter().map(|section| section.len()).sum();
     out_len += 4 * sections.len();
@@ -106,6 +117,26 @@ mod tests {
         assert_ne!(second.as_ptr(), original_ptr);
     }
 
+    #[test]
+    fn decode_sections_works() {
+        assert_eq!(decode_sections(vec![]), Vec::<Vec<u8>>::new());
+
+        let data = b"\0\0\0\0".to_vec();
+        assert_eq!(decode_sections(data), vec![Vec::<u8>::new()]);
+
+        let data = b"\xAA\0\0\0\x01\xBB\xCC\0\0\0\x02\0\0\0\0\xDD\0\0\0\x01".to_vec();
+        assert_eq!(
+            decode_sections(data),
+            vec![vec![0xAA], vec![0xBB, 0xCC], vec![], vec![0xDD]]
+        );
+
+        let data = encode_sections(&[&[0xFF; 19], &[0x9D; 277], &[0x01]]);
+        assert_eq!(
+            decode_sections(data),
+            vec![vec![0xFF; 19], vec![0x9D; 277], vec![0x01]]
+        );
+    }
+
     #[test]
     fn encode_sections_works_for_empty_sections() {
         let enc = encode_sections(&[]);
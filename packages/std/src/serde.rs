@@ -9,7 +9,8 @@ use crate::binary::Binary;
 use crate::errors::{StdError, StdResult};
 
 pub fn from_slice<T: DeserializeOwned>(value: &[u8]) -> StdResult<T> {
-    serde_json_wasm::from_slice(value).map_err(|e| StdError::parse_err(type_name::<T>(), e))
+    serde_json_wasm::from_slice(value)
+        .map_err(|e| StdError::parse_err_with_source(type_name::<T>(), e))
 }
 
 pub fn from_binary<T: DeserializeOwned>(value: &Binary) -> StdResult<T> {
@@ -20,7 +21,8 @@ pub fn to_vec<T>(data: &T) -> StdResult<Vec<u8>>
 where
     T: Serialize + ?Sized,
 {
-    serde_json_wasm::to_vec(data).map_err(|e| StdError::serialize_err(type_name::<T>(), e))
+    serde_json_wasm::to_vec(data)
+        .map_err(|e| StdError::serialize_err_with_source(type_name::<T>(), e))
 }
 
 pub fn to_binary<T>(data: &T) -> StdResult<Binary>
@@ -30,6 +32,53 @@ where
     to_vec(data).map(Binary)
 }
 
+/// The maximum number of bytes of the input included in the errors of [`from_json_str`]
+const INPUT_SNIPPET_MAX_LENGTH: usize = 64;
+
+/// Deserializes a JSON string.
+///
+/// In contrast to [`from_slice`], the error message contains the beginning of the input,
+/// which helps to find out what was being parsed, e.g. when handling replies or IBC acknowledgements.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{from_json_str, StdError};
+/// let err = from_json_str::<u64>(r#""forty-two""#).unwrap_err();
+/// assert!(matches!(err, StdError::ParseErr { target_type, msg, .. }
+///     if target_type == "u64" && msg.ends_with(r#"(input: "forty-two")"#)));
+/// ```
+pub fn from_json_str<T: DeserializeOwned>(value: &str) -> StdResult<T> {
+    serde_json_wasm::from_str(value).map_err(|e| {
+        let mut err = StdError::parse_err_with_source(type_name::<T>(), e);
+        if let StdError::ParseErr { msg, .. } = &mut err {
+            msg.push_str(&format!(" (input: {})", input_snippet(value)));
+        }
+        err
+    })
+}
+
+/// Serializes the given data to a JSON string
+pub fn to_json_string<T>(data: &T) -> StdResult<String>
+where
+    T: Serialize + ?Sized,
+{
+    serde_json_wasm::to_string(data)
+        .map_err(|e| StdError::serialize_err_with_source(type_name::<T>(), e))
+}
+
+/// Returns the input truncated to [`INPUT_SNIPPET_MAX_LENGTH`] bytes
+fn input_snippet(value: &str) -> String {
+    if value.len() <= INPUT_SNIPPET_MAX_LENGTH {
+        return value.to_string();
+    }
+    let mut end = INPUT_SNIPPET_MAX_LENGTH;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &value[..end])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,4 +170,56 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn to_json_string_works() {
+        let msg = SomeMsg::Refund {};
+        assert_eq!(to_json_string(&msg).unwrap(), r#"{"refund":{}}"#);
+    }
+
+    #[test]
+    fn from_json_str_works() {
+        let deserialized: SomeMsg = from_json_str(r#"{"refund":{}}"#).unwrap();
+        assert_eq!(deserialized, SomeMsg::Refund {});
+
+        // roundtrip
+        let msg = SomeMsg::Cowsay {
+            text: "Hi 🐄".to_string(),
+        };
+        assert_eq!(
+            from_json_str::<SomeMsg>(&to_json_string(&msg).unwrap()).unwrap(),
+            msg
+        );
+    }
+
+    #[test]
+    fn from_json_str_error_contains_type_and_input() {
+        let err = from_json_str::<SomeMsg>(r#"{"refund_all":{}}"#).unwrap_err();
+        match err {
+            StdError::ParseErr {
+                target_type,
+                msg,
+                source,
+                ..
+            } => {
+                assert_eq!(target_type, "cosmwasm_std::serde::tests::SomeMsg");
+                assert!(msg.ends_with(r#"(input: {"refund_all":{}})"#), "{}", msg);
+                assert!(msg.starts_with(&source.unwrap().to_string()), "{}", msg);
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        // long inputs are truncated
+        let input = format!(r#"{{"cowsay":{{"text":"{}"}}}}"#, "🐄".repeat(40));
+        let err = from_json_str::<u64>(&input).unwrap_err();
+        match err {
+            StdError::ParseErr { msg, .. } => {
+                let snippet = &msg[msg.find("(input: ").unwrap() + 8..msg.len() - 1];
+                assert!(snippet.ends_with('…'));
+                assert!(snippet.len() <= INPUT_SNIPPET_MAX_LENGTH + '…'.len_utf8());
+                assert!(input.starts_with(snippet.trim_end_matches('…')));
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
 }
@@ -0,0 +1,190 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::binary::Binary;
+use crate::ibc::IbcAcknowledgement;
+use crate::serde::to_binary;
+
+/// This is a standard IBC acknowledgement type. IBC application are free
+/// to use any acknowledgement format they want. However, for compatibility
+/// purposes it is recommended to use this.
+///
+/// The original proto definition can be found at <https://github.com/cosmos/cosmos-sdk/blob/v0.42.0/proto/ibc/core/channel/v1/channel.proto#L141-L147>
+/// and <https://github.com/cosmos/ibc/tree/ed849c7bac/spec/core/ics-004-channel-and-packet-semantics#acknowledgement-envelope>.
+///
+/// In contrast to the original idea, [ICS-20](https://github.com/cosmos/ibc/tree/ed849c7bacf16204e9509f0f0df325391f3ce25c/spec/app/ics-020-fungible-token-transfer#technical-specification) and CosmWasm IBC protocols
+/// use JSON instead of a protobuf serialization.
+///
+/// ## Examples
+///
+/// For IBC packets in which the result has no further meaning, use a single byte
+/// success value:
+///
+/// ```
+/// use cosmwasm_std::{IbcReceiveResponse, StdAck};
+///
+/// let ack = StdAck::success(b"\x01");
+/// let res: IbcReceiveResponse = IbcReceiveResponse::new().set_ack(ack);
+/// ```
+///
+/// For errors, use a message describing the problem:
+///
+/// ```
+/// use cosmwasm_std::{IbcReceiveResponse, StdAck};
+///
+/// let ack = StdAck::error("insufficient funds");
+/// let res: IbcReceiveResponse = IbcReceiveResponse::new().set_ack(ack);
+/// assert_eq!(res.acknowledgement.as_slice(), br#"{"error":"insufficient funds"}"#);
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StdAck {
+    /// The success case, encoded as `{"result":"<base64 data>"}`
+    #[serde(rename = "result")]
+    Success(Binary),
+    /// The error case, encoded as `{"error":"<message>"}`
+    Error(String),
+}
+
+impl StdAck {
+    /// Creates a success ack with the given data
+    pub fn success(data: impl Into<Binary>) -> Self {
+        StdAck::Success(data.into())
+    }
+
+    /// Creates an error ack
+    pub fn error(err: impl Into<String>) -> Self {
+        StdAck::Error(err.into())
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, StdAck::Success(_))
+    }
+
+    pub fn is_error(&self) -> bool {
+        !self.is_success()
+    }
+
+    /// Serializes the ack to its canonical JSON encoding
+    pub fn to_binary(&self) -> Binary {
+        // We need a non-failing StdAck -> Binary conversion to allow using StdAck in
+        // `impl Into<Binary>` arguments.
+        // Pretty sure this cannot fail. If that changes we can create a non-failing implementation here.
+        to_binary(&self).unwrap()
+    }
+
+    /// Returns the success data.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the ack is an error
+    pub fn unwrap(self) -> Binary {
+        match self {
+            StdAck::Success(data) => data,
+            StdAck::Error(err) => panic!("{}", err),
+        }
+    }
+
+    /// Returns the error message.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the ack is a success
+    pub fn unwrap_err(self) -> String {
+        match self {
+            StdAck::Success(_) => panic!("not an error"),
+            StdAck::Error(err) => err,
+        }
+    }
+}
+
+impl From<StdAck> for Binary {
+    fn from(original: StdAck) -> Binary {
+        original.to_binary()
+    }
+}
+
+impl From<StdAck> for IbcAcknowledgement {
+    fn from(original: StdAck) -> IbcAcknowledgement {
+        IbcAcknowledgement::new(original)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::from_slice;
+
+    #[test]
+    fn stdack_success_works() {
+        let success = StdAck::success(b"foo");
+        match success {
+            StdAck::Success(data) => assert_eq!(data, b"foo"),
+            StdAck::Error(_err) => panic!("must not be an error"),
+        }
+    }
+
+    #[test]
+    fn stdack_error_works() {
+        let err = StdAck::error("bar");
+        match err {
+            StdAck::Success(_data) => panic!("must not be a success"),
+            StdAck::Error(err) => assert_eq!(err, "bar"),
+        }
+    }
+
+    #[test]
+    fn stdack_is_success_is_error_work() {
+        let success = StdAck::success(b"foo");
+        let err = StdAck::error("bar");
+        // is_success
+        assert!(success.is_success());
+        assert!(!err.is_success());
+        // is_error
+        assert!(!success.is_error());
+        assert!(err.is_error());
+    }
+
+    #[test]
+    fn stdack_to_binary_works() {
+        let ack1 = StdAck::success(b"\x01");
+        assert_eq!(ack1.to_binary(), br#"{"result":"AQ=="}"#);
+
+        let ack2 = StdAck::error("kaputt");
+        assert_eq!(ack2.to_binary(), br#"{"error":"kaputt"}"#);
+
+        // conversions
+        assert_eq!(Binary::from(ack1.clone()), ack1.to_binary());
+        assert_eq!(
+            IbcAcknowledgement::from(ack2.clone()).data,
+            ack2.to_binary()
+        );
+    }
+
+    #[test]
+    fn stdack_deserialization_works() {
+        let ack: StdAck = from_slice(br#"{"result":"AQ=="}"#).unwrap();
+        assert_eq!(ack, StdAck::success(b"\x01"));
+
+        let ack: StdAck = from_slice(br#"{"error":"kaputt"}"#).unwrap();
+        assert_eq!(ack, StdAck::error("kaputt"));
+    }
+
+    #[test]
+    fn stdack_unwrap_works() {
+        assert_eq!(StdAck::success(b"foo").unwrap(), b"foo");
+        assert_eq!(StdAck::error("bar").unwrap_err(), "bar");
+    }
+
+    #[test]
+    #[should_panic(expected = "bar")]
+    fn stdack_unwrap_panics_for_error() {
+        StdAck::error("bar").unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "not an error")]
+    fn stdack_unwrap_err_panics_for_success() {
+        StdAck::success(b"foo").unwrap_err();
+    }
+}
@@ -1,9 +1,12 @@
+#[cfg(feature = "iterator")]
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::fmt;
 #[cfg(feature = "iterator")]
 use std::iter;
 #[cfg(feature = "iterator")]
 use std::ops::{Bound, RangeBounds};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 
 #[cfg(feature = "iterator")]
 use crate::iterator::{Order, Record};
@@ -12,14 +15,154 @@ use crate::traits::Storage;
 #[derive(Default)]
 pub struct MemoryStorage {
     data: BTreeMap<Vec<u8>, Vec<u8>>,
+    journal: SnapshotJournal,
+}
+
+/// Clones the data only. The snapshots belong to the storage they were taken from,
+/// so they cannot be restored in the clone.
+impl Clone for MemoryStorage {
+    fn clone(&self) -> Self {
+        MemoryStorage {
+            data: self.data.clone(),
+            journal: SnapshotJournal::default(),
+        }
+    }
 }
 
+/// A handle to the state of a [`MemoryStorage`] at the time [`MemoryStorage::snapshot`] was called
+pub type MemoryStorageSnapshot = StorageSnapshot;
+
 impl MemoryStorage {
     pub fn new() -> Self {
         MemoryStorage::default()
     }
+
+    /// Takes a snapshot of the current state, which can be restored later using [`MemoryStorage::restore`].
+    ///
+    /// This does not copy the storage. Instead, the previous values of all keys written after the
+    /// snapshot are recorded, so restoring a snapshot is cheap when few keys were written.
+    pub fn snapshot(&mut self) -> MemoryStorageSnapshot {
+        self.journal.snapshot()
+    }
+
+    /// Restores the state at the time the snapshot was taken.
+    ///
+    /// The snapshot stays valid and can be restored again, e.g. after every call of a test
+    /// harness. All snapshots taken after this one are discarded.
+    ///
+    /// Panics if the snapshot was discarded or was taken from another storage.
+    pub fn restore(&mut self, snapshot: MemoryStorageSnapshot) {
+        self.journal.restore(snapshot, &mut self.data);
+    }
+
+    /// Returns the keys whose values changed since the snapshot was taken, along with their
+    /// current value. The value is `None` if the key was removed.
+    ///
+    /// Panics if the snapshot was discarded or was taken from another storage.
+    pub fn changes_since(
+        &self,
+        snapshot: MemoryStorageSnapshot,
+    ) -> BTreeMap<Vec<u8>, Option<Vec<u8>>> {
+        self.journal.changes_since(snapshot, &self.data)
+    }
+}
+
+/// The ID of the next snapshot. It is shared by all journals, such that a snapshot taken from
+/// one storage is never mistaken for a snapshot of another one.
+static NEXT_SNAPSHOT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A handle to the state of a storage at the time a snapshot was taken,
+/// see [`MemoryStorage::snapshot`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageSnapshot {
+    id: u64,
+}
+
+/// Records the previous values of the keys written to a key-value map, such that
+/// earlier states of the map can be restored.
+///
+/// This implements the snapshots of [`MemoryStorage`]. It is not part of the public API
+/// and only public such that the `MockStorage` of cosmwasm-vm can share it.
+/// The storage must call [`SnapshotJournal::record`] for every write.
+///
+/// A journal is not `Clone`, since its snapshots must not be restorable in two storages.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct SnapshotJournal {
+    /// The previous values of all keys written since the oldest snapshot was taken
+    entries: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+    /// The snapshot IDs along with the number of entries at the time they were taken
+    snapshots: Vec<(u64, usize)>,
+}
+
+impl SnapshotJournal {
+    /// Takes a snapshot of the current state of the map
+    pub fn snapshot(&mut self) -> StorageSnapshot {
+        let id = NEXT_SNAPSHOT_ID.fetch_add(1, AtomicOrdering::Relaxed);
+        self.snapshots.push((id, self.entries.len()));
+        StorageSnapshot { id }
+    }
+
+    /// Records the previous value of a written key if snapshots need to be restorable
+    pub fn record(&mut self, key: &[u8], previous: Option<Vec<u8>>) {
+        if !self.snapshots.is_empty() {
+            self.entries.push((key.to_vec(), previous));
+        }
+    }
+
+    /// Restores `data` to its state at the time the snapshot was taken and discards
+    /// all snapshots taken after this one.
+    ///
+    /// Panics if the snapshot was discarded or was taken from another journal.
+    pub fn restore(&mut self, snapshot: StorageSnapshot, data: &mut BTreeMap<Vec<u8>, Vec<u8>>) {
+        let (index, entries_len) = self.find(snapshot);
+        while self.entries.len() > entries_len {
+            let (key, previous) = self.entries.pop().unwrap();
+            match previous {
+                Some(value) => data.insert(key, value),
+                None => data.remove(&key),
+            };
+        }
+        self.snapshots.truncate(index + 1);
+    }
+
+    /// Returns the keys whose values in `data` changed since the snapshot was taken,
+    /// along with their current value. The value is `None` if the key was removed.
+    ///
+    /// Panics if the snapshot was discarded or was taken from another journal.
+    pub fn changes_since(
+        &self,
+        snapshot: StorageSnapshot,
+        data: &BTreeMap<Vec<u8>, Vec<u8>>,
+    ) -> BTreeMap<Vec<u8>, Option<Vec<u8>>> {
+        let (_, entries_len) = self.find(snapshot);
+        // The first entry of a key contains its value at the time of the snapshot
+        let mut previous_values = BTreeMap::new();
+        for (key, previous) in self.entries[entries_len..].iter().rev() {
+            previous_values.insert(key, previous);
+        }
+        previous_values
+            .into_iter()
+            .filter_map(|(key, previous)| {
+                let current = data.get(key);
+                (current != previous.as_ref()).then(|| (key.clone(), current.cloned()))
+            })
+            .collect()
+    }
+
+    fn find(&self, snapshot: StorageSnapshot) -> (usize, usize) {
+        self.snapshots
+            .iter()
+            .enumerate()
+            .find(|(_, (id, _))| *id == snapshot.id)
+            .map(|(index, (_, entries_len))| (index, *entries_len))
+            .expect("Snapshot does not exist. It was discarded when restoring an earlier snapshot or taken from another storage.")
+    }
 }
 
+/// The panic message of [`Storage::set`] implementations for empty values
+const EMPTY_VALUE_MESSAGE: &str = "TL;DR: Value must not be empty in Storage::set but in most cases you can use Storage::remove instead. Long story: Getting empty values from storage is not well supported at the moment. Some of our internal interfaces cannot differentiate between a non-existent key and an empty value. Right now, you cannot rely on the behaviour of empty values. To protect you from trouble later on, we stop here. Sorry for the inconvenience! We highly welcome you to contribute to CosmWasm, making this more solid one way or the other.";
+
 impl Storage for MemoryStorage {
     fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
         self.data.get(key).cloned()
@@ -27,14 +170,17 @@ impl Storage for MemoryStorage {
 
     fn set(&mut self, key: &[u8], value: &[u8]) {
         if value.is_empty() {
-            panic!("TL;DR: Value must not be empty in Storage::set but in most cases you can use Storage::remove instead. Long story: Getting empty values from storage is not well supported at the moment. Some of our internal interfaces cannot differentiate between a non-existent key and an empty value. Right now, you cannot rely on the behaviour of empty values. To protect you from trouble later on, we stop here. Sorry for the inconvenience! We highly welcome you to contribute to CosmWasm, making this more solid one way or the other.");
+            panic!("{}", EMPTY_VALUE_MESSAGE);
         }
 
-        self.data.insert(key.to_vec(), value.to_vec());
+        let previous = self.data.insert(key.to_vec(), value.to_vec());
+        self.journal.record(key, previous);
     }
 
     fn remove(&mut self, key: &[u8]) {
-        self.data.remove(key);
+        if let Some(previous) = self.data.remove(key) {
+            self.journal.record(key, Some(previous));
+        }
     }
 
     #[cfg(feature = "iterator")]
@@ -63,6 +209,29 @@ impl Storage for MemoryStorage {
             Order::Descending => Box::new(iter.rev().map(clone_item)),
         }
     }
+
+    #[cfg(feature = "iterator")]
+    fn remove_range(&mut self, start: Option<&[u8]>, end: Option<&[u8]>) {
+        let bounds = range_bounds(start, end);
+
+        // See range above. Start > end describes an empty range.
+        if let (Bound::Included(start), Bound::Excluded(end)) =
+            (bounds.start_bound(), bounds.end_bound())
+        {
+            if start > end {
+                return;
+            }
+        }
+
+        let keys: Vec<Vec<u8>> = self
+            .data
+            .range(bounds)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            self.remove(&key);
+        }
+    }
 }
 
 /// This debug implementation is made for inspecting storages in unit testing.
@@ -87,6 +256,154 @@ impl fmt::Debug for MemoryStorage {
     }
 }
 
+/// A [`Storage`] that buffers all writes to an underlying storage until [`commit`] is called.
+///
+/// Reads see the buffered writes on top of the underlying storage. Dropping the
+/// transactional storage or calling [`rollback`] discards all writes, which allows
+/// undoing partial writes when one of multiple fallible steps fails:
+///
+/// ```
+/// # use cosmwasm_std::{MemoryStorage, Storage, TransactionalStorage};
+/// let mut storage = MemoryStorage::new();
+///
+/// let mut tx = TransactionalStorage::new(&mut storage);
+/// tx.set(b"foo", b"bar");
+/// assert_eq!(tx.get(b"foo"), Some(b"bar".to_vec()));
+/// tx.rollback();
+/// assert_eq!(storage.get(b"foo"), None);
+///
+/// let mut tx = TransactionalStorage::new(&mut storage);
+/// tx.set(b"foo", b"bar");
+/// tx.commit();
+/// assert_eq!(storage.get(b"foo"), Some(b"bar".to_vec()));
+/// ```
+///
+/// Transactions can be nested by wrapping a transactional storage in another one.
+///
+/// [`commit`]: TransactionalStorage::commit
+/// [`rollback`]: TransactionalStorage::rollback
+pub struct TransactionalStorage<'a> {
+    storage: &'a mut dyn Storage,
+    /// The buffered writes. `None` marks a removed key.
+    pending: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> TransactionalStorage<'a> {
+    pub fn new(storage: &'a mut dyn Storage) -> Self {
+        TransactionalStorage {
+            storage,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Writes all buffered changes to the underlying storage
+    pub fn commit(self) {
+        for (key, value) in self.pending {
+            match value {
+                Some(value) => self.storage.set(&key, &value),
+                None => self.storage.remove(&key),
+            }
+        }
+    }
+
+    /// Discards all buffered changes. This is the same as dropping the transactional storage.
+    pub fn rollback(self) {}
+}
+
+impl Storage for TransactionalStorage<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.pending.get(key) {
+            Some(value) => value.clone(),
+            None => self.storage.get(key),
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let bounds = range_bounds(start, end);
+
+        // See MemoryStorage::range. Start > end describes an empty range.
+        if let (Bound::Included(start), Bound::Excluded(end)) =
+            (bounds.start_bound(), bounds.end_bound())
+        {
+            if start > end {
+                return Box::new(iter::empty());
+            }
+        }
+
+        let pending = self.pending.range(bounds);
+        let pending: Box<dyn Iterator<Item = PendingRecordRef> + 'a> = match order {
+            Order::Ascending => Box::new(pending),
+            Order::Descending => Box::new(pending.rev()),
+        };
+        Box::new(MergedRange {
+            base: self.storage.range(start, end, order).peekable(),
+            pending: pending.peekable(),
+            order,
+        })
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        // Fail here like MemoryStorage rather than when committing
+        if value.is_empty() {
+            panic!("{}", EMPTY_VALUE_MESSAGE);
+        }
+
+        self.pending.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.pending.insert(key.to_vec(), None);
+    }
+}
+
+#[cfg(feature = "iterator")]
+type PendingRecordRef<'a> = (&'a Vec<u8>, &'a Option<Vec<u8>>);
+
+/// Merges a range of the underlying storage with the buffered writes in the same range.
+/// Both iterators must yield their keys in `order`.
+#[cfg(feature = "iterator")]
+struct MergedRange<'a> {
+    base: iter::Peekable<Box<dyn Iterator<Item = Record> + 'a>>,
+    pending: iter::Peekable<Box<dyn Iterator<Item = PendingRecordRef<'a>> + 'a>>,
+    order: Order,
+}
+
+#[cfg(feature = "iterator")]
+impl Iterator for MergedRange<'_> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Record> {
+        loop {
+            let next = match (self.base.peek(), self.pending.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((base_key, _)), Some((pending_key, _))) => match self.order {
+                    Order::Ascending => base_key.cmp(pending_key),
+                    Order::Descending => (*pending_key).cmp(base_key),
+                },
+            };
+            if next == Ordering::Less {
+                return self.base.next();
+            }
+            if next == Ordering::Equal {
+                // the buffered write overrides the stored value
+                self.base.next();
+            }
+            if let Some((key, Some(value))) = self.pending.next() {
+                return Some((key.clone(), value.clone()));
+            }
+            // the key was removed, continue with the next one
+        }
+    }
+}
+
 #[cfg(feature = "iterator")]
 fn range_bounds(start: Option<&[u8]>, end: Option<&[u8]>) -> impl RangeBounds<Vec<u8>> {
     (
@@ -280,6 +597,79 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn remove_range_works() {
+        let mut store = MemoryStorage::new();
+        store.set(b"ant", b"hill");
+        store.set(b"foo", b"bar");
+        store.set(b"food", b"bank");
+        store.set(b"ze", b"bra");
+
+        // bounded
+        store.remove_range(Some(b"f"), Some(b"fop"));
+        let all: Vec<Record> = store.range(None, None, Order::Ascending).collect();
+        assert_eq!(
+            all,
+            vec![
+                (b"ant".to_vec(), b"hill".to_vec()),
+                (b"ze".to_vec(), b"bra".to_vec()),
+            ]
+        );
+
+        // empty range (start > end)
+        store.remove_range(Some(b"z"), Some(b"a"));
+        assert_eq!(store.range(None, None, Order::Ascending).count(), 2);
+
+        // end is exclusive
+        store.remove_range(None, Some(b"ze"));
+        let all: Vec<Record> = store.range(None, None, Order::Ascending).collect();
+        assert_eq!(all, vec![(b"ze".to_vec(), b"bra".to_vec())]);
+
+        // unbounded
+        store.remove_range(None, None);
+        assert_eq!(store.range(None, None, Order::Ascending).count(), 0);
+    }
+
+    #[test]
+    fn get_many_works() {
+        let mut store = MemoryStorage::new();
+        store.set(b"foo", b"bar");
+        store.set(b"ze", b"bra");
+
+        assert_eq!(store.get_many(&[]), Vec::<Option<Vec<u8>>>::new());
+        assert_eq!(
+            store.get_many(&[b"ze", b"food", b"foo"]),
+            vec![Some(b"bra".to_vec()), None, Some(b"bar".to_vec())]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn range_bounded_works() {
+        let mut store = MemoryStorage::new();
+        store.set(b"ant", b"hill");
+        store.set(b"foo", b"bar");
+        store.set(b"food", b"bank");
+        store.set(b"ze", b"bra");
+
+        let page = store.range_bounded(Some(b"f"), None, Order::Ascending, 2);
+        assert_eq!(
+            page,
+            vec![
+                (b"foo".to_vec(), b"bar".to_vec()),
+                (b"food".to_vec(), b"bank".to_vec()),
+            ]
+        );
+
+        let page = store.range_bounded(None, Some(b"ze"), Order::Descending, 10);
+        assert_eq!(page.len(), 3);
+        assert_eq!(page[0], (b"food".to_vec(), b"bank".to_vec()));
+
+        let page = store.range_bounded(None, None, Order::Ascending, 0);
+        assert_eq!(page, vec![]);
+    }
+
     #[test]
     fn memory_storage_implements_debug() {
         let store = MemoryStorage::new();
@@ -329,4 +719,264 @@ mod tests {
             }"
         );
     }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut store = MemoryStorage::new();
+        store.set(b"foo", b"bar");
+        store.set(b"food", b"bank");
+
+        let snapshot = store.snapshot();
+        assert_eq!(store.changes_since(snapshot), BTreeMap::new());
+
+        store.set(b"foo", b"baz");
+        store.set(b"foo", b"bam");
+        store.remove(b"food");
+        store.remove(b"nope");
+        store.set(b"ze", b"bra");
+        store.set(b"ant", b"hill");
+        store.remove(b"ant");
+        assert_eq!(
+            store.changes_since(snapshot),
+            BTreeMap::from([
+                (b"foo".to_vec(), Some(b"bam".to_vec())),
+                (b"food".to_vec(), None),
+                (b"ze".to_vec(), Some(b"bra".to_vec())),
+            ])
+        );
+
+        store.restore(snapshot);
+        assert_eq!(store.get(b"foo"), Some(b"bar".to_vec()));
+        assert_eq!(store.get(b"food"), Some(b"bank".to_vec()));
+        assert_eq!(store.get(b"ze"), None);
+        assert_eq!(store.get(b"ant"), None);
+        assert_eq!(store.changes_since(snapshot), BTreeMap::new());
+
+        // the snapshot can be restored again
+        store.set(b"foo", b"other");
+        store.restore(snapshot);
+        assert_eq!(store.get(b"foo"), Some(b"bar".to_vec()));
+    }
+
+    #[test]
+    fn snapshot_restore_discards_later_snapshots() {
+        let mut store = MemoryStorage::new();
+        let first = store.snapshot();
+        store.set(b"foo", b"bar");
+        let second = store.snapshot();
+        store.set(b"foo", b"baz");
+
+        assert_eq!(
+            store.changes_since(first),
+            BTreeMap::from([(b"foo".to_vec(), Some(b"baz".to_vec()))])
+        );
+        assert_eq!(
+            store.changes_since(second),
+            BTreeMap::from([(b"foo".to_vec(), Some(b"baz".to_vec()))])
+        );
+
+        store.restore(second);
+        assert_eq!(store.get(b"foo"), Some(b"bar".to_vec()));
+        store.restore(first);
+        assert_eq!(store.get(b"foo"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot does not exist")]
+    fn restore_panics_for_discarded_snapshot() {
+        let mut store = MemoryStorage::new();
+        let first = store.snapshot();
+        let second = store.snapshot();
+        store.restore(first);
+        store.restore(second);
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot does not exist")]
+    fn restore_panics_for_snapshot_of_other_storage() {
+        let mut store = MemoryStorage::new();
+        let mut other = MemoryStorage::new();
+        store.snapshot();
+        let snapshot = other.snapshot();
+        store.restore(snapshot);
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot does not exist")]
+    fn restore_panics_for_snapshot_of_cloned_storage() {
+        let mut store = MemoryStorage::new();
+        store.set(b"foo", b"bar");
+        let snapshot = store.snapshot();
+        store.set(b"foo", b"baz");
+
+        let mut clone = store.clone();
+        assert_eq!(clone.get(b"foo"), Some(b"baz".to_vec()));
+        clone.restore(snapshot);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn snapshot_restores_remove_range() {
+        let mut store = MemoryStorage::new();
+        store.set(b"ant", b"hill");
+        store.set(b"foo", b"bar");
+        let snapshot = store.snapshot();
+
+        store.remove_range(None, None);
+        assert_eq!(store.range(None, None, Order::Ascending).count(), 0);
+        store.restore(snapshot);
+        assert_eq!(
+            store
+                .range(None, None, Order::Ascending)
+                .collect::<Vec<_>>(),
+            vec![
+                (b"ant".to_vec(), b"hill".to_vec()),
+                (b"foo".to_vec(), b"bar".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn transactional_storage_commit_and_rollback() {
+        let mut store = MemoryStorage::new();
+        store.set(b"foo", b"bar");
+        store.set(b"food", b"bank");
+
+        let mut tx = TransactionalStorage::new(&mut store);
+        tx.set(b"foo", b"baz");
+        tx.remove(b"food");
+        tx.set(b"ze", b"bra");
+        assert_eq!(tx.get(b"foo"), Some(b"baz".to_vec()));
+        assert_eq!(tx.get(b"food"), None);
+        assert_eq!(tx.get(b"ze"), Some(b"bra".to_vec()));
+        tx.rollback();
+        assert_eq!(store.get(b"foo"), Some(b"bar".to_vec()));
+        assert_eq!(store.get(b"food"), Some(b"bank".to_vec()));
+        assert_eq!(store.get(b"ze"), None);
+
+        // dropping rolls back as well
+        {
+            let mut tx = TransactionalStorage::new(&mut store);
+            tx.set(b"foo", b"baz");
+        }
+        assert_eq!(store.get(b"foo"), Some(b"bar".to_vec()));
+
+        let mut tx = TransactionalStorage::new(&mut store);
+        tx.set(b"foo", b"baz");
+        tx.remove(b"food");
+        tx.set(b"ze", b"bra");
+        tx.commit();
+        assert_eq!(store.get(b"foo"), Some(b"baz".to_vec()));
+        assert_eq!(store.get(b"food"), None);
+        assert_eq!(store.get(b"ze"), Some(b"bra".to_vec()));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Getting empty values from storage is not well supported at the moment."
+    )]
+    fn transactional_storage_set_panics_for_empty() {
+        let mut store = MemoryStorage::new();
+        let mut tx = TransactionalStorage::new(&mut store);
+        tx.set(b"foo", b"");
+    }
+
+    #[test]
+    fn transactional_storage_can_be_nested() {
+        let mut store = MemoryStorage::new();
+
+        let mut outer = TransactionalStorage::new(&mut store);
+        outer.set(b"foo", b"bar");
+
+        let mut inner = TransactionalStorage::new(&mut outer);
+        inner.set(b"ze", b"bra");
+        assert_eq!(inner.get(b"foo"), Some(b"bar".to_vec()));
+        inner.rollback();
+
+        let mut inner = TransactionalStorage::new(&mut outer);
+        inner.remove(b"foo");
+        inner.set(b"ant", b"hill");
+        inner.commit();
+
+        assert_eq!(outer.get(b"foo"), None);
+        assert_eq!(outer.get(b"ze"), None);
+        outer.commit();
+        assert_eq!(store.get(b"ant"), Some(b"hill".to_vec()));
+        assert_eq!(store.get(b"foo"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn transactional_storage_range() {
+        let mut store = MemoryStorage::new();
+        store.set(b"ant", b"hill");
+        store.set(b"foo", b"bar");
+        store.set(b"moo", b"cow");
+        store.set(b"ze", b"bra");
+
+        let mut tx = TransactionalStorage::new(&mut store);
+        tx.set(b"bee", b"hive"); // new
+        tx.set(b"foo", b"baz"); // overridden
+        tx.remove(b"moo"); // removed
+        tx.remove(b"nope"); // removed but never existed
+        tx.set(b"zz", b"top"); // new after the last stored key
+
+        let all: Vec<Record> = tx.range(None, None, Order::Ascending).collect();
+        assert_eq!(
+            all,
+            vec![
+                (b"ant".to_vec(), b"hill".to_vec()),
+                (b"bee".to_vec(), b"hive".to_vec()),
+                (b"foo".to_vec(), b"baz".to_vec()),
+                (b"ze".to_vec(), b"bra".to_vec()),
+                (b"zz".to_vec(), b"top".to_vec()),
+            ]
+        );
+
+        let mut reversed: Vec<Record> = tx.range(None, None, Order::Descending).collect();
+        reversed.reverse();
+        assert_eq!(reversed, all);
+
+        // bounded
+        let elements: Vec<Record> = tx
+            .range(Some(b"b"), Some(b"ze"), Order::Ascending)
+            .collect();
+        assert_eq!(
+            elements,
+            vec![
+                (b"bee".to_vec(), b"hive".to_vec()),
+                (b"foo".to_vec(), b"baz".to_vec()),
+            ]
+        );
+        let elements: Vec<Record> = tx
+            .range(Some(b"f"), Some(b"zz"), Order::Descending)
+            .collect();
+        assert_eq!(
+            elements,
+            vec![
+                (b"ze".to_vec(), b"bra".to_vec()),
+                (b"foo".to_vec(), b"baz".to_vec()),
+            ]
+        );
+
+        // empty
+        assert_eq!(
+            tx.range(Some(b"z"), Some(b"a"), Order::Ascending).count(),
+            0
+        );
+
+        // default implementations on top of range work
+        tx.remove_range(Some(b"b"), Some(b"g"));
+        assert_eq!(
+            tx.range_bounded(None, None, Order::Ascending, 2),
+            vec![
+                (b"ant".to_vec(), b"hill".to_vec()),
+                (b"ze".to_vec(), b"bra".to_vec()),
+            ]
+        );
+        tx.commit();
+        assert_eq!(store.get(b"foo"), None);
+        assert_eq!(store.get(b"bee"), None);
+        assert_eq!(store.get(b"zz"), Some(b"top".to_vec()));
+    }
 }
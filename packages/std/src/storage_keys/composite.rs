@@ -0,0 +1,321 @@
+//! Order-preserving encodings of composite storage keys like `(Addr, u64)`.
+//!
+//! Integers and timestamps are encoded as fixed length big endian values, such that the
+//! lexicographical order of the encoded bytes matches the numerical order. Signed integers
+//! have their sign bit flipped to sort negative values before positive ones.
+//!
+//! Variable length components (addresses, strings and bytes) are prefixed with their length
+//! as a 2 byte big endian integer, like in [`to_length_prefixed`](super::to_length_prefixed).
+//! This makes the encoding of a tuple unambiguous, but means that those components are ordered
+//! by length first and by content second.
+//!
+//! The encoding of a tuple is the concatenation of the encodings of its components.
+//! Thus the encoding of `addr` is a prefix of the encoding of `(addr, n)` for every `n`, which
+//! allows iterating over all entries of one address in the order of the second component.
+
+use crate::addresses::Addr;
+use crate::errors::{StdError, StdResult};
+use crate::timestamp::Timestamp;
+
+/// A value that can be encoded as (a component of) a composite storage key
+pub trait KeyComponent: Sized {
+    /// Appends the encoding of this value to `out`
+    fn encode_component(&self, out: &mut Vec<u8>);
+
+    /// Decodes a value from the beginning of `data` and returns it together with the
+    /// remaining bytes
+    fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])>;
+}
+
+/// Encodes a key, e.g. a tuple like `(Addr, u64)`, to raw storage key bytes
+pub fn encode_composite_key<K: KeyComponent>(key: &K) -> Vec<u8> {
+    let mut out = Vec::new();
+    key.encode_component(&mut out);
+    out
+}
+
+/// Decodes raw storage key bytes created by [`encode_composite_key`]
+pub fn decode_composite_key<K: KeyComponent>(data: &[u8]) -> StdResult<K> {
+    let (key, rest) = K::decode_component(data)?;
+    if !rest.is_empty() {
+        return Err(StdError::parse_err(
+            std::any::type_name::<K>(),
+            format!("{} unexpected trailing bytes", rest.len()),
+        ));
+    }
+    Ok(key)
+}
+
+/// Splits off the first `N` bytes of `data`
+fn split_fixed<'a, const N: usize>(data: &'a [u8], target: &str) -> StdResult<([u8; N], &'a [u8])> {
+    if data.len() < N {
+        return Err(StdError::parse_err(
+            target,
+            format!("expected {} bytes but got {}", N, data.len()),
+        ));
+    }
+    let (value, rest) = data.split_at(N);
+    Ok((value.try_into().unwrap(), rest))
+}
+
+/// Splits off a length prefixed component of `data`
+fn split_length_prefixed<'a>(data: &'a [u8], target: &str) -> StdResult<(&'a [u8], &'a [u8])> {
+    let (length, rest) = split_fixed::<2>(data, target)?;
+    let length = u16::from_be_bytes(length) as usize;
+    if rest.len() < length {
+        return Err(StdError::parse_err(
+            target,
+            format!("expected {} bytes but got {}", length, rest.len()),
+        ));
+    }
+    Ok(rest.split_at(length))
+}
+
+/// Appends a length prefixed component to `out`
+fn extend_length_prefixed(out: &mut Vec<u8>, component: &[u8]) {
+    if component.len() > 0xFFFF {
+        panic!("only supports key components up to length 0xFFFF")
+    }
+    out.extend_from_slice(&(component.len() as u16).to_be_bytes());
+    out.extend_from_slice(component);
+}
+
+macro_rules! impl_unsigned_component {
+    ($($t:ty),+) => {
+        $(
+            impl KeyComponent for $t {
+                fn encode_component(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_be_bytes());
+                }
+
+                fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])> {
+                    let (bytes, rest) = split_fixed(data, stringify!($t))?;
+                    Ok((<$t>::from_be_bytes(bytes), rest))
+                }
+            }
+        )+
+    };
+}
+
+impl_unsigned_component!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_signed_component {
+    ($($t:ty),+) => {
+        $(
+            impl KeyComponent for $t {
+                fn encode_component(&self, out: &mut Vec<u8>) {
+                    // Flipping the sign bit sorts negative values before positive ones
+                    let mut bytes = self.to_be_bytes();
+                    bytes[0] ^= 0x80;
+                    out.extend_from_slice(&bytes);
+                }
+
+                fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])> {
+                    let (mut bytes, rest) = split_fixed(data, stringify!($t))?;
+                    bytes[0] ^= 0x80;
+                    Ok((<$t>::from_be_bytes(bytes), rest))
+                }
+            }
+        )+
+    };
+}
+
+impl_signed_component!(i8, i16, i32, i64, i128);
+
+impl KeyComponent for Timestamp {
+    fn encode_component(&self, out: &mut Vec<u8>) {
+        self.nanos().encode_component(out);
+    }
+
+    fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])> {
+        let (nanos, rest) = u64::decode_component(data)?;
+        Ok((Timestamp::from_nanos(nanos), rest))
+    }
+}
+
+impl KeyComponent for Vec<u8> {
+    fn encode_component(&self, out: &mut Vec<u8>) {
+        extend_length_prefixed(out, self);
+    }
+
+    fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])> {
+        let (value, rest) = split_length_prefixed(data, "Vec<u8>")?;
+        Ok((value.to_vec(), rest))
+    }
+}
+
+impl KeyComponent for String {
+    fn encode_component(&self, out: &mut Vec<u8>) {
+        extend_length_prefixed(out, self.as_bytes());
+    }
+
+    fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])> {
+        let (value, rest) = split_length_prefixed(data, "String")?;
+        let value = String::from_utf8(value.to_vec()).map_err(StdError::invalid_utf8)?;
+        Ok((value, rest))
+    }
+}
+
+impl KeyComponent for Addr {
+    fn encode_component(&self, out: &mut Vec<u8>) {
+        extend_length_prefixed(out, self.as_bytes());
+    }
+
+    fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])> {
+        let (value, rest) = String::decode_component(data)?;
+        Ok((Addr::unchecked(value), rest))
+    }
+}
+
+impl<A: KeyComponent, B: KeyComponent> KeyComponent for (A, B) {
+    fn encode_component(&self, out: &mut Vec<u8>) {
+        self.0.encode_component(out);
+        self.1.encode_component(out);
+    }
+
+    fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])> {
+        let (a, rest) = A::decode_component(data)?;
+        let (b, rest) = B::decode_component(rest)?;
+        Ok(((a, b), rest))
+    }
+}
+
+impl<A: KeyComponent, B: KeyComponent, C: KeyComponent> KeyComponent for (A, B, C) {
+    fn encode_component(&self, out: &mut Vec<u8>) {
+        self.0.encode_component(out);
+        self.1.encode_component(out);
+        self.2.encode_component(out);
+    }
+
+    fn decode_component(data: &[u8]) -> StdResult<(Self, &[u8])> {
+        let (a, rest) = A::decode_component(data)?;
+        let (b, rest) = B::decode_component(rest)?;
+        let (c, rest) = C::decode_component(rest)?;
+        Ok(((a, b, c), rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_composite_key_works() {
+        assert_eq!(encode_composite_key(&7u8), b"\x07");
+        assert_eq!(
+            encode_composite_key(&258u64),
+            b"\x00\x00\x00\x00\x00\x00\x01\x02"
+        );
+        assert_eq!(encode_composite_key(&-1i16), b"\x7f\xff");
+        assert_eq!(encode_composite_key(&1i16), b"\x80\x01");
+        assert_eq!(
+            encode_composite_key(&Timestamp::from_nanos(3)),
+            b"\x00\x00\x00\x00\x00\x00\x00\x03"
+        );
+        assert_eq!(
+            encode_composite_key(&Addr::unchecked("abc")),
+            b"\x00\x03abc"
+        );
+        assert_eq!(
+            encode_composite_key(&(Addr::unchecked("abc"), 5u32)),
+            b"\x00\x03abc\x00\x00\x00\x05"
+        );
+        assert_eq!(
+            encode_composite_key(&(5u32, "abc".to_string(), vec![0xffu8])),
+            b"\x00\x00\x00\x05\x00\x03abc\x00\x01\xff"
+        );
+    }
+
+    #[test]
+    fn decode_composite_key_works() {
+        fn roundtrip<K: KeyComponent + PartialEq + std::fmt::Debug>(key: K) {
+            let encoded = encode_composite_key(&key);
+            assert_eq!(decode_composite_key::<K>(&encoded).unwrap(), key);
+        }
+
+        roundtrip(u8::MAX);
+        roundtrip(u128::MAX);
+        roundtrip(i64::MIN);
+        roundtrip(-3i32);
+        roundtrip(Timestamp::from_seconds(1_700_000_000));
+        roundtrip(String::new());
+        roundtrip(vec![0u8, 1, 2]);
+        roundtrip((Addr::unchecked("creator"), 42u64));
+        roundtrip((42u64, Addr::unchecked("creator")));
+        roundtrip((
+            Addr::unchecked("creator"),
+            Timestamp::from_nanos(1),
+            "denom".to_string(),
+        ));
+    }
+
+    #[test]
+    fn decode_composite_key_fails_for_invalid_data() {
+        // too short
+        let err = decode_composite_key::<u64>(b"\x00\x01").unwrap_err();
+        assert!(err.to_string().contains("expected 8 bytes but got 2"));
+        let err = decode_composite_key::<(Addr, u64)>(b"\x00\x05abc").unwrap_err();
+        assert!(err.to_string().contains("expected 5 bytes but got 3"));
+
+        // trailing bytes
+        let err = decode_composite_key::<u8>(b"\x01\x02").unwrap_err();
+        assert!(err.to_string().contains("1 unexpected trailing bytes"));
+
+        // invalid utf8
+        let err = decode_composite_key::<String>(b"\x00\x01\xff").unwrap_err();
+        match err {
+            StdError::InvalidUtf8 { .. } => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn encoding_preserves_order() {
+        let values = [i64::MIN, -256, -1, 0, 1, 255, 256, i64::MAX];
+        for pair in values.windows(2) {
+            assert!(encode_composite_key(&pair[0]) < encode_composite_key(&pair[1]));
+        }
+
+        let times = [0, 999, 1_000, 1_571_797_419_879_305_533];
+        for pair in times.windows(2) {
+            assert!(
+                encode_composite_key(&Timestamp::from_nanos(pair[0]))
+                    < encode_composite_key(&Timestamp::from_nanos(pair[1]))
+            );
+        }
+
+        // entries of one address are contiguous and sorted by the second component,
+        // addresses are sorted by length first
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let mut keys = [
+            encode_composite_key(&(alice.clone(), 300u64)),
+            encode_composite_key(&(bob.clone(), 2u64)),
+            encode_composite_key(&(alice.clone(), 2u64)),
+            encode_composite_key(&(bob.clone(), 1u64)),
+        ];
+        keys.sort();
+        let decoded: Vec<(Addr, u64)> = keys
+            .iter()
+            .map(|key| decode_composite_key(key).unwrap())
+            .collect();
+        assert_eq!(
+            decoded,
+            vec![
+                (bob.clone(), 1),
+                (bob, 2),
+                (alice.clone(), 2),
+                (alice.clone(), 300)
+            ]
+        );
+        let prefix = encode_composite_key(&alice);
+        assert!(keys[2..].iter().all(|key| key.starts_with(&prefix)));
+        assert!(!keys[1].starts_with(&prefix));
+    }
+
+    #[test]
+    #[should_panic(expected = "only supports key components up to length 0xFFFF")]
+    fn encode_composite_key_panics_for_too_long_component() {
+        encode_composite_key(&vec![0u8; 0x10000]);
+    }
+}
@@ -1,5 +1,7 @@
+mod composite;
 mod length_prefixed;
 
 // Please note that the entire storage_keys module is public. So be careful
 // when adding elements here.
+pub use composite::{decode_composite_key, encode_composite_key, KeyComponent};
 pub use length_prefixed::{namespace_with_key, to_length_prefixed, to_length_prefixed_nested};
@@ -0,0 +1,1078 @@
+//! A lightweight in-process chain for integration tests of multiple native Rust contracts.
+//!
+//! Only the bank and wasm modules are simulated, which covers the typical flows of contracts
+//! sending funds, instantiating and executing each other and processing the replies.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::addresses::Addr;
+use crate::binary::Binary;
+use crate::coin::Coin;
+use crate::deps::{Deps, DepsMut};
+use crate::errors::{StdError, StdResult, SystemError};
+use crate::protobuf::encode_bytes;
+use crate::query::{
+    AllBalanceResponse, BalanceResponse, BankQuery, ContractInfoResponse, QueryRequest, WasmQuery,
+};
+use crate::results::msg_responses::{
+    BANK_SEND_RESPONSE, EXECUTE_CONTRACT_RESPONSE, INSTANTIATE_CONTRACT_RESPONSE,
+};
+use crate::results::{
+    Attribute, BankMsg, ContractResult, CosmosMsg, Empty, Event, MsgResponse, Reply, ReplyOn,
+    Response, SubMsgResponse, SubMsgResult, SystemResult, WasmMsg,
+};
+use crate::serde::{from_slice, to_binary};
+use crate::storage::MemoryStorage;
+use crate::traits::{Querier, QuerierResult, QuerierWrapper, Storage};
+use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
+
+use super::mock::{mock_env, MockApi};
+
+/// Seconds the block time advances per block in [`App::next_block`]
+const BLOCK_TIME_SECONDS: u64 = 5;
+
+/// The entry points of a native Rust contract that can be stored in an [`App`].
+///
+/// Messages are passed as JSON, like they would be to a Wasm contract. Most contracts
+/// do not implement this directly but use [`ContractWrapper`].
+pub trait Contract {
+    fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> StdResult<Response>;
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> StdResult<Response>;
+
+    fn query(&self, deps: Deps, env: Env, msg: &[u8]) -> StdResult<Binary>;
+
+    fn reply(&self, deps: DepsMut, env: Env, reply: Reply) -> StdResult<Response>;
+}
+
+type InstantiateFn = Box<dyn Fn(DepsMut, Env, MessageInfo, &[u8]) -> StdResult<Response>>;
+type ExecuteFn = Box<dyn Fn(DepsMut, Env, MessageInfo, &[u8]) -> StdResult<Response>>;
+type QueryFn = Box<dyn Fn(Deps, Env, &[u8]) -> StdResult<Binary>>;
+type ReplyFn = Box<dyn Fn(DepsMut, Env, Reply) -> StdResult<Response>>;
+
+/// Implements [`Contract`] using the entry point functions of a contract.
+///
+/// The errors of the entry points are converted to [`StdError::GenericErr`] with the
+/// error message.
+///
+/// ```ignore
+/// let code = ContractWrapper::new(contract::instantiate, contract::execute, contract::query)
+///     .with_reply(contract::reply);
+/// let code_id = app.store_code(code);
+/// ```
+pub struct ContractWrapper {
+    instantiate: InstantiateFn,
+    execute: ExecuteFn,
+    query: QueryFn,
+    reply: Option<ReplyFn>,
+}
+
+impl ContractWrapper {
+    pub fn new<I, X, Q, E1, E2, E3>(
+        instantiate: fn(DepsMut, Env, MessageInfo, I) -> Result<Response, E1>,
+        execute: fn(DepsMut, Env, MessageInfo, X) -> Result<Response, E2>,
+        query: fn(Deps, Env, Q) -> Result<Binary, E3>,
+    ) -> Self
+    where
+        I: DeserializeOwned + 'static,
+        X: DeserializeOwned + 'static,
+        Q: DeserializeOwned + 'static,
+        E1: Display + 'static,
+        E2: Display + 'static,
+        E3: Display + 'static,
+    {
+        ContractWrapper {
+            instantiate: Box::new(move |deps, env, info, msg| {
+                instantiate(deps, env, info, from_slice(msg)?).map_err(contract_error)
+            }),
+            execute: Box::new(move |deps, env, info, msg| {
+                execute(deps, env, info, from_slice(msg)?).map_err(contract_error)
+            }),
+            query: Box::new(move |deps, env, msg| {
+                query(deps, env, from_slice(msg)?).map_err(contract_error)
+            }),
+            reply: None,
+        }
+    }
+
+    /// Adds the `reply` entry point. Without it, replies fail.
+    pub fn with_reply<E>(mut self, reply: fn(DepsMut, Env, Reply) -> Result<Response, E>) -> Self
+    where
+        E: Display + 'static,
+    {
+        self.reply = Some(Box::new(move |deps, env, msg| {
+            reply(deps, env, msg).map_err(contract_error)
+        }));
+        self
+    }
+}
+
+impl Contract for ContractWrapper {
+    fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> StdResult<Response> {
+        (self.instantiate)(deps, env, info, msg)
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> StdResult<Response> {
+        (self.execute)(deps, env, info, msg)
+    }
+
+    fn query(&self, deps: Deps, env: Env, msg: &[u8]) -> StdResult<Binary> {
+        (self.query)(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut, env: Env, reply: Reply) -> StdResult<Response> {
+        match &self.reply {
+            Some(reply_fn) => reply_fn(deps, env, reply),
+            None => Err(StdError::generic_err("reply entry point not implemented")),
+        }
+    }
+}
+
+fn contract_error(err: impl Display) -> StdError {
+    StdError::generic_err(err.to_string())
+}
+
+/// The result of executing a message in an [`App`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppResponse {
+    /// All events emitted during the execution, including the ones of submessages
+    pub events: Vec<Event>,
+    /// The data returned by the executed contract
+    pub data: Option<Binary>,
+}
+
+/// The outcome of a single message, which is needed to create the reply of a submessage
+struct MsgOutcome {
+    response: AppResponse,
+    msg_response: MsgResponse,
+}
+
+#[derive(Clone)]
+struct ContractData {
+    code_id: u64,
+    creator: Addr,
+    admin: Option<Addr>,
+    storage: MemoryStorage,
+}
+
+/// Everything that is rolled back when a message fails
+#[derive(Clone, Default)]
+struct AppState {
+    balances: BTreeMap<String, Vec<Coin>>,
+    contracts: BTreeMap<Addr, ContractData>,
+    contract_count: u64,
+}
+
+/// An in-process chain to test the interaction of multiple contracts.
+///
+/// Contracts are stored with [`App::store_code`] and instantiated and executed with
+/// [`App::instantiate_contract`] and [`App::execute_contract`], which process all messages
+/// and replies of the contracts like a chain would. A failing message reverts all of its
+/// state changes. Contracts cannot query themselves, since their storage is in use
+/// while they are executed.
+///
+/// The bank module is simulated with balances set via [`App::set_balance`]. Only `BankMsg`
+/// and the `Instantiate` and `Execute` variants of `WasmMsg` are supported.
+///
+/// This is intended for use in test code only.
+pub struct App {
+    block: BlockInfo,
+    api: MockApi,
+    codes: Vec<Box<dyn Contract>>,
+    state: AppState,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    /// Creates an app with the block of [`mock_env`] and no balances or contracts
+    pub fn new() -> Self {
+        App {
+            block: mock_env().block,
+            api: MockApi::default(),
+            codes: Vec::new(),
+            state: AppState::default(),
+        }
+    }
+
+    /// Stores a contract and returns its code ID
+    pub fn store_code(&mut self, contract: impl Contract + 'static) -> u64 {
+        self.codes.push(Box::new(contract));
+        self.codes.len() as u64
+    }
+
+    /// Sets the balance of an account, replacing the existing one
+    pub fn set_balance(&mut self, address: impl Into<String>, balance: Vec<Coin>) {
+        self.state.balances.insert(address.into(), balance);
+    }
+
+    pub fn block_info(&self) -> BlockInfo {
+        self.block.clone()
+    }
+
+    /// Changes the current block, e.g. to jump to a specific time
+    pub fn update_block(&mut self, action: impl FnOnce(&mut BlockInfo)) {
+        action(&mut self.block);
+    }
+
+    /// Advances to the next block, which is 5 seconds after the current one
+    pub fn next_block(&mut self) {
+        self.block.height += 1;
+        self.block.time = self.block.time.plus_seconds(BLOCK_TIME_SECONDS);
+    }
+
+    /// Returns a querier wrapper for queries against the app's state
+    pub fn wrap(&self) -> QuerierWrapper<'_> {
+        QuerierWrapper::new(self)
+    }
+
+    /// Instantiates a contract from the given code and returns its address
+    pub fn instantiate_contract(
+        &mut self,
+        code_id: u64,
+        sender: impl Into<String>,
+        msg: &impl Serialize,
+        funds: &[Coin],
+        label: impl Into<String>,
+        admin: Option<String>,
+    ) -> StdResult<Addr> {
+        let msg = WasmMsg::Instantiate {
+            admin,
+            code_id,
+            msg: to_binary(msg)?,
+            funds: funds.to_vec(),
+            label: label.into(),
+        };
+        let response = self.execute(sender, msg.into())?;
+        // The instantiate event is always the first one
+        let address = response
+            .events
+            .first()
+            .and_then(|event| {
+                event
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key == "_contract_address")
+            })
+            .map(|attr| Addr::unchecked(&attr.value))
+            .expect("instantiate event not found");
+        Ok(address)
+    }
+
+    /// Executes a contract
+    pub fn execute_contract(
+        &mut self,
+        sender: impl Into<String>,
+        contract_addr: impl Into<String>,
+        msg: &impl Serialize,
+        funds: &[Coin],
+    ) -> StdResult<AppResponse> {
+        let msg = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: to_binary(msg)?,
+            funds: funds.to_vec(),
+        };
+        self.execute(sender, msg.into())
+    }
+
+    /// Executes a message sent by `sender` including all resulting submessages.
+    /// If any of them fails, all state changes are reverted.
+    pub fn execute(&mut self, sender: impl Into<String>, msg: CosmosMsg) -> StdResult<AppResponse> {
+        let backup = self.state.clone();
+        let result = self.execute_msg(&Addr::unchecked(sender), msg);
+        match result {
+            Ok(outcome) => Ok(outcome.response),
+            Err(err) => {
+                self.state = backup;
+                Err(err)
+            }
+        }
+    }
+
+    fn execute_msg(&mut self, sender: &Addr, msg: CosmosMsg) -> StdResult<MsgOutcome> {
+        match msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                self.transfer(sender.as_str(), &to_address, &amount)?;
+                let event = Event::new("transfer")
+                    .add_attribute("recipient", to_address)
+                    .add_attribute("sender", sender.as_str())
+                    .add_attribute("amount", coins_to_string(&amount));
+                Ok(MsgOutcome {
+                    response: AppResponse {
+                        events: vec![event],
+                        data: None,
+                    },
+                    msg_response: MsgResponse {
+                        type_url: BANK_SEND_RESPONSE.to_string(),
+                        value: Binary::default(),
+                    },
+                })
+            }
+            CosmosMsg::Bank(BankMsg::Burn { amount }) => {
+                for coin in &amount {
+                    self.sub_balance(sender.as_str(), coin)?;
+                }
+                let event = Event::new("burn")
+                    .add_attribute("burner", sender.as_str())
+                    .add_attribute("amount", coins_to_string(&amount));
+                Ok(MsgOutcome {
+                    response: AppResponse {
+                        events: vec![event],
+                        data: None,
+                    },
+                    msg_response: MsgResponse {
+                        type_url: "/cosmos.bank.v1beta1.MsgBurnResponse".to_string(),
+                        value: Binary::default(),
+                    },
+                })
+            }
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => {
+                let contract = Addr::unchecked(contract_addr);
+                if !self.state.contracts.contains_key(&contract) {
+                    return Err(no_such_contract(&contract));
+                }
+                self.transfer(sender.as_str(), contract.as_str(), &funds)?;
+                let info = MessageInfo {
+                    sender: sender.clone(),
+                    funds,
+                };
+                let response = self.with_contract(&contract, |code, deps, env| {
+                    code.execute(deps, env, info, &msg)
+                })?;
+                let event = Event::new("execute").add_attributes(vec![contract_attr(&contract)]);
+                let mut response = self.process_response(&contract, response)?;
+                response.events.insert(0, event);
+
+                let mut value = Vec::new();
+                encode_bytes(1, response.data.as_deref().unwrap_or_default(), &mut value);
+                Ok(MsgOutcome {
+                    response,
+                    msg_response: MsgResponse {
+                        type_url: EXECUTE_CONTRACT_RESPONSE.to_string(),
+                        value: value.into(),
+                    },
+                })
+            }
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                admin,
+                code_id,
+                msg,
+                funds,
+                label: _,
+            }) => {
+                if code_id == 0 || code_id > self.codes.len() as u64 {
+                    return Err(StdError::generic_err(format!(
+                        "Code {} does not exist",
+                        code_id
+                    )));
+                }
+                self.state.contract_count += 1;
+                let contract = Addr::unchecked(format!("contract{}", self.state.contract_count));
+                self.state.contracts.insert(
+                    contract.clone(),
+                    ContractData {
+                        code_id,
+                        creator: sender.clone(),
+                        admin: admin.map(Addr::unchecked),
+                        storage: MemoryStorage::new(),
+                    },
+                );
+                self.transfer(sender.as_str(), contract.as_str(), &funds)?;
+                let info = MessageInfo {
+                    sender: sender.clone(),
+                    funds,
+                };
+                let response = self.with_contract(&contract, |code, deps, env| {
+                    code.instantiate(deps, env, info, &msg)
+                })?;
+                let event = Event::new("instantiate").add_attributes(vec![
+                    contract_attr(&contract),
+                    Attribute::new("code_id", code_id.to_string()),
+                ]);
+                let mut response = self.process_response(&contract, response)?;
+                response.events.insert(0, event);
+
+                let mut value = Vec::new();
+                encode_bytes(1, contract.as_bytes(), &mut value);
+                encode_bytes(2, response.data.as_deref().unwrap_or_default(), &mut value);
+                Ok(MsgOutcome {
+                    response,
+                    msg_response: MsgResponse {
+                        type_url: INSTANTIATE_CONTRACT_RESPONSE.to_string(),
+                        value: value.into(),
+                    },
+                })
+            }
+            msg => Err(StdError::generic_err(format!(
+                "Unsupported message in App: {:?}",
+                msg
+            ))),
+        }
+    }
+
+    /// Turns the response of a contract into events and executes its submessages
+    fn process_response(&mut self, contract: &Addr, response: Response) -> StdResult<AppResponse> {
+        let Response {
+            messages,
+            attributes,
+            events,
+            data,
+            ..
+        } = response;
+
+        let mut app_response = AppResponse {
+            events: Vec::new(),
+            data,
+        };
+        if !attributes.is_empty() {
+            let mut wasm_attributes = vec![contract_attr(contract)];
+            wasm_attributes.extend(attributes);
+            app_response
+                .events
+                .push(Event::new("wasm").add_attributes(wasm_attributes));
+        }
+        for event in events {
+            let mut attributes = vec![contract_attr(contract)];
+            attributes.extend(event.attributes);
+            app_response
+                .events
+                .push(Event::new(format!("wasm-{}", event.ty)).add_attributes(attributes));
+        }
+
+        for sub_msg in messages {
+            let backup = self.state.clone();
+            let result = match self.execute_msg(contract, sub_msg.msg) {
+                Ok(outcome) => SubMsgResult::Ok(SubMsgResponse {
+                    events: outcome.response.events,
+                    data: outcome.response.data,
+                    msg_responses: vec![outcome.msg_response],
+                }),
+                Err(err) => {
+                    self.state = backup;
+                    SubMsgResult::Err(err.to_string())
+                }
+            };
+
+            let reply = matches!(
+                (&sub_msg.reply_on, &result),
+                (ReplyOn::Always, _)
+                    | (ReplyOn::Success, SubMsgResult::Ok(_))
+                    | (ReplyOn::Error, SubMsgResult::Err(_))
+            );
+            if let SubMsgResult::Ok(res) = &result {
+                app_response.events.extend(res.events.iter().cloned());
+            }
+            #[cfg(feature = "cosmwasm_1_4")]
+            if sub_msg.reply_on == ReplyOn::ForwardData {
+                if let SubMsgResult::Ok(res) = &result {
+                    app_response.data = res.data.clone();
+                }
+            }
+
+            if reply {
+                let reply = Reply {
+                    id: sub_msg.id,
+                    payload: sub_msg.payload,
+                    result,
+                };
+                let response =
+                    self.with_contract(contract, |code, deps, env| code.reply(deps, env, reply))?;
+                let response = self.process_response(contract, response)?;
+                app_response.events.extend(response.events);
+                if response.data.is_some() {
+                    app_response.data = response.data;
+                }
+            } else if let SubMsgResult::Err(err) = result {
+                return Err(StdError::generic_err(err));
+            }
+        }
+        Ok(app_response)
+    }
+
+    /// Calls an entry point of a contract with its storage.
+    ///
+    /// The contract's data is taken out of the state during the call, such that the querier
+    /// can borrow the rest of the state.
+    fn with_contract<T>(
+        &mut self,
+        contract: &Addr,
+        action: impl FnOnce(&dyn Contract, DepsMut, Env) -> StdResult<T>,
+    ) -> StdResult<T> {
+        let mut data = self
+            .state
+            .contracts
+            .remove(contract)
+            .ok_or_else(|| no_such_contract(contract))?;
+        let result = {
+            let deps = DepsMut {
+                storage: &mut data.storage,
+                api: &self.api,
+                querier: QuerierWrapper::new(self),
+            };
+            let code = self.codes[data.code_id as usize - 1].as_ref();
+            action(code, deps, self.env(contract))
+        };
+        self.state.contracts.insert(contract.clone(), data);
+        result
+    }
+
+    fn env(&self, contract: &Addr) -> Env {
+        Env {
+            block: self.block.clone(),
+            transaction: Some(TransactionInfo { index: 0 }),
+            contract: ContractInfo {
+                address: contract.clone(),
+            },
+        }
+    }
+
+    fn balance(&self, address: &str, denom: &str) -> Coin {
+        self.state
+            .balances
+            .get(address)
+            .and_then(|balance| balance.iter().find(|coin| coin.denom == denom))
+            .cloned()
+            .unwrap_or_else(|| Coin::new(0, denom))
+    }
+
+    fn transfer(&mut self, from: &str, to: &str, amount: &[Coin]) -> StdResult<()> {
+        for coin in amount {
+            self.sub_balance(from, coin)?;
+            self.add_balance(to, coin);
+        }
+        Ok(())
+    }
+
+    fn sub_balance(&mut self, address: &str, coin: &Coin) -> StdResult<()> {
+        let available = self.balance(address, &coin.denom);
+        let remaining = available.amount.checked_sub(coin.amount).map_err(|_| {
+            StdError::generic_err(format!(
+                "Insufficient funds: {} has {}, needs {}",
+                address, available, coin
+            ))
+        })?;
+        let balance = self.state.balances.entry(address.to_string()).or_default();
+        balance.retain(|c| c.denom != coin.denom);
+        if !remaining.is_zero() {
+            balance.push(Coin::new(remaining.u128(), &coin.denom));
+        }
+        Ok(())
+    }
+
+    fn add_balance(&mut self, address: &str, coin: &Coin) {
+        if coin.amount.is_zero() {
+            return;
+        }
+        let balance = self.state.balances.entry(address.to_string()).or_default();
+        match balance.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => existing.amount += coin.amount,
+            None => balance.push(coin.clone()),
+        }
+    }
+
+    fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        let contract_result: ContractResult<Binary> = match request {
+            QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
+                let res = BalanceResponse {
+                    amount: self.balance(address, denom),
+                };
+                to_binary(&res).into()
+            }
+            QueryRequest::Bank(BankQuery::AllBalances { address }) => {
+                let mut amount = self
+                    .state
+                    .balances
+                    .get(address)
+                    .cloned()
+                    .unwrap_or_default();
+                amount.sort_by(|a, b| a.denom.cmp(&b.denom));
+                to_binary(&AllBalanceResponse { amount }).into()
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                let data = match self.state.contracts.get(&Addr::unchecked(contract_addr)) {
+                    Some(data) => data,
+                    None => return no_such_contract_query(contract_addr),
+                };
+                let deps = Deps {
+                    storage: &data.storage,
+                    api: &self.api,
+                    querier: QuerierWrapper::new(self),
+                };
+                let code = &self.codes[data.code_id as usize - 1];
+                let env = self.env(&Addr::unchecked(contract_addr));
+                code.query(deps, env, msg).into()
+            }
+            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
+                let data = match self.state.contracts.get(&Addr::unchecked(contract_addr)) {
+                    Some(data) => data,
+                    None => return no_such_contract_query(contract_addr),
+                };
+                let value = data.storage.get(key).unwrap_or_default();
+                ContractResult::Ok(value.into())
+            }
+            QueryRequest::Wasm(WasmQuery::ContractInfo { contract_addr }) => {
+                let data = match self.state.contracts.get(&Addr::unchecked(contract_addr)) {
+                    Some(data) => data,
+                    None => return no_such_contract_query(contract_addr),
+                };
+                let res = ContractInfoResponse {
+                    code_id: data.code_id,
+                    creator: data.creator.to_string(),
+                    admin: data.admin.as_ref().map(|admin| admin.to_string()),
+                    pinned: false,
+                    ibc_port: None,
+                };
+                to_binary(&res).into()
+            }
+            _ => {
+                return SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: format!("{:?}", request),
+                })
+            }
+        };
+        SystemResult::Ok(contract_result)
+    }
+}
+
+impl Querier for App {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+fn contract_attr(contract: &Addr) -> Attribute {
+    // Keys starting with an underscore are reserved for the chain, so Attribute::new cannot be used
+    Attribute {
+        key: "_contract_address".to_string(),
+        value: contract.to_string(),
+    }
+}
+
+fn no_such_contract(contract: &Addr) -> StdError {
+    StdError::generic_err(format!("Contract {} does not exist", contract))
+}
+
+fn no_such_contract_query(contract_addr: &str) -> QuerierResult {
+    SystemResult::Err(SystemError::NoSuchContract {
+        addr: contract_addr.to_string(),
+    })
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serde::to_vec;
+    use crate::timestamp::Timestamp;
+    use crate::{coin, coins, SubMsg};
+    use serde::Deserialize;
+
+    const COUNT_KEY: &[u8] = b"count";
+    const REPLY_KEY: &[u8] = b"reply";
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum CounterExecuteMsg {
+        Increment {},
+        Fail {},
+        Payout { to: String, amount: Coin },
+    }
+
+    fn counter_instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: u32,
+    ) -> StdResult<Response> {
+        deps.storage.set(COUNT_KEY, &to_vec(&msg)?);
+        Ok(Response::new().set_data(b"init"))
+    }
+
+    fn counter_execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: CounterExecuteMsg,
+    ) -> Result<Response, String> {
+        match msg {
+            CounterExecuteMsg::Increment {} => {
+                let count: u32 = from_slice(&deps.storage.get(COUNT_KEY).unwrap()).unwrap();
+                deps.storage.set(COUNT_KEY, &to_vec(&(count + 1)).unwrap());
+                Ok(Response::new()
+                    .add_attribute("count", (count + 1).to_string())
+                    .set_data(to_binary(&(count + 1)).unwrap()))
+            }
+            CounterExecuteMsg::Fail {} => {
+                // this write must be reverted
+                deps.storage.set(COUNT_KEY, &to_vec(&1000u32).unwrap());
+                Err("counter failed".to_string())
+            }
+            CounterExecuteMsg::Payout { to, amount } => {
+                Ok(Response::new().add_message(BankMsg::Send {
+                    to_address: to,
+                    amount: vec![amount],
+                }))
+            }
+        }
+    }
+
+    fn counter_query(deps: Deps, env: Env, msg: String) -> StdResult<Binary> {
+        match msg.as_str() {
+            "count" => Ok(Binary::from(deps.storage.get(COUNT_KEY).unwrap())),
+            "height" => to_binary(&env.block.height),
+            _ => Err(StdError::generic_err("unknown query")),
+        }
+    }
+
+    fn counter() -> ContractWrapper {
+        ContractWrapper::new(counter_instantiate, counter_execute, counter_query)
+    }
+
+    /// Forwards a message to another contract as a submessage and stores the reply
+    #[derive(Serialize, Deserialize)]
+    struct ForwardMsg {
+        msg: CosmosMsg,
+        reply_on: ReplyOn,
+    }
+
+    fn forwarder_instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn forwarder_execute(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: ForwardMsg,
+    ) -> StdResult<Response> {
+        let sub_msg = SubMsg::reply_on_success(msg.msg, 7).with_payload(b"payload".to_vec());
+        let sub_msg = SubMsg {
+            reply_on: msg.reply_on,
+            ..sub_msg
+        };
+        Ok(Response::new().add_submessage(sub_msg))
+    }
+
+    fn forwarder_query(deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Ok(Binary::from(
+            deps.storage.get(REPLY_KEY).unwrap_or_default(),
+        ))
+    }
+
+    fn forwarder_reply(deps: DepsMut, _env: Env, reply: Reply) -> StdResult<Response> {
+        assert_eq!(reply.id, 7);
+        assert_eq!(reply.payload, Binary::from(b"payload"));
+        deps.storage.set(REPLY_KEY, &to_vec(&reply.result)?);
+        Ok(Response::new().set_data(b"replied"))
+    }
+
+    fn forwarder() -> ContractWrapper {
+        ContractWrapper::new(forwarder_instantiate, forwarder_execute, forwarder_query)
+            .with_reply(forwarder_reply)
+    }
+
+    fn forward(
+        app: &mut App,
+        forwarder: &Addr,
+        msg: CosmosMsg,
+        reply_on: ReplyOn,
+    ) -> StdResult<AppResponse> {
+        app.execute_contract("owner", forwarder, &ForwardMsg { msg, reply_on }, &[])
+    }
+
+    fn last_reply(app: &App, forwarder: &Addr) -> SubMsgResult {
+        app.wrap().query_wasm_smart(forwarder, &Empty {}).unwrap()
+    }
+
+    fn count(app: &App, counter: &Addr) -> u32 {
+        app.wrap().query_wasm_smart(counter, &"count").unwrap()
+    }
+
+    #[test]
+    fn instantiate_execute_and_query_work() {
+        let mut app = App::new();
+        let code_id = app.store_code(counter());
+        assert_eq!(code_id, 1);
+
+        let counter = app
+            .instantiate_contract(code_id, "owner", &5u32, &[], "counter", None)
+            .unwrap();
+        assert_eq!(counter, Addr::unchecked("contract1"));
+        assert_eq!(count(&app, &counter), 5);
+
+        let res = app
+            .execute_contract("owner", &counter, &CounterExecuteMsg::Increment {}, &[])
+            .unwrap();
+        assert_eq!(res.data, Some(to_binary(&6u32).unwrap()));
+        assert_eq!(res.events[0].ty, "execute");
+        assert_eq!(res.events[1].ty, "wasm");
+        assert_eq!(res.events[1].attributes[1], Attribute::new("count", "6"));
+        assert_eq!(count(&app, &counter), 6);
+
+        let info = app.wrap().query_wasm_contract_info(&counter).unwrap();
+        assert_eq!(info.code_id, 1);
+        assert_eq!(info.creator, "owner");
+
+        // errors revert all changes
+        let err = app
+            .execute_contract("owner", &counter, &CounterExecuteMsg::Fail {}, &[])
+            .unwrap_err();
+        assert!(err.to_string().contains("counter failed"));
+        assert_eq!(count(&app, &counter), 6);
+
+        // unknown contracts and codes
+        app.execute_contract("owner", "contract9", &CounterExecuteMsg::Increment {}, &[])
+            .unwrap_err();
+        app.instantiate_contract(5, "owner", &5u32, &[], "counter", None)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn bank_works() {
+        let mut app = App::new();
+        app.set_balance("owner", coins(100, "ucosm"));
+        let code_id = app.store_code(counter());
+
+        // funds are sent along with instantiation
+        let counter = app
+            .instantiate_contract(
+                code_id,
+                "owner",
+                &0u32,
+                &coins(60, "ucosm"),
+                "counter",
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            app.wrap().query_balance("owner", "ucosm").unwrap(),
+            coin(40, "ucosm")
+        );
+        assert_eq!(
+            app.wrap().query_all_balances(&counter).unwrap(),
+            coins(60, "ucosm")
+        );
+
+        // contract sends funds
+        let payout = CounterExecuteMsg::Payout {
+            to: "alice".to_string(),
+            amount: coin(25, "ucosm"),
+        };
+        let res = app
+            .execute_contract("owner", &counter, &payout, &[])
+            .unwrap();
+        assert_eq!(res.events.last().unwrap().ty, "transfer");
+        assert_eq!(
+            app.wrap().query_balance("alice", "ucosm").unwrap(),
+            coin(25, "ucosm")
+        );
+        assert_eq!(
+            app.wrap().query_balance(&counter, "ucosm").unwrap(),
+            coin(35, "ucosm")
+        );
+
+        // insufficient funds fail the whole execution
+        let payout = CounterExecuteMsg::Payout {
+            to: "alice".to_string(),
+            amount: coin(36, "ucosm"),
+        };
+        let err = app
+            .execute_contract("owner", &counter, &payout, &coins(1, "uatom"))
+            .unwrap_err();
+        assert!(err.to_string().contains("Insufficient funds"));
+        assert_eq!(
+            app.wrap().query_balance(&counter, "ucosm").unwrap(),
+            coin(35, "ucosm")
+        );
+
+        // direct bank messages
+        app.execute(
+            "owner",
+            BankMsg::Burn {
+                amount: coins(40, "ucosm"),
+            }
+            .into(),
+        )
+        .unwrap();
+        assert_eq!(app.wrap().query_all_balances("owner").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn submessage_replies_work() {
+        let mut app = App::new();
+        let counter_id = app.store_code(counter());
+        let forwarder_id = app.store_code(forwarder());
+        let counter = app
+            .instantiate_contract(counter_id, "owner", &0u32, &[], "counter", None)
+            .unwrap();
+        let forwarder = app
+            .instantiate_contract(forwarder_id, "owner", &Empty {}, &[], "forwarder", None)
+            .unwrap();
+
+        let increment: CosmosMsg = WasmMsg::Execute {
+            contract_addr: counter.to_string(),
+            msg: to_binary(&CounterExecuteMsg::Increment {}).unwrap(),
+            funds: vec![],
+        }
+        .into();
+        let fail: CosmosMsg = WasmMsg::Execute {
+            contract_addr: counter.to_string(),
+            msg: to_binary(&CounterExecuteMsg::Fail {}).unwrap(),
+            funds: vec![],
+        }
+        .into();
+
+        // success with reply
+        let res = forward(&mut app, &forwarder, increment.clone(), ReplyOn::Success).unwrap();
+        assert_eq!(res.data, Some(Binary::from(b"replied")));
+        assert_eq!(count(&app, &counter), 1);
+        match last_reply(&app, &forwarder) {
+            SubMsgResult::Ok(response) => {
+                let data = to_binary(&1u32).unwrap();
+                assert_eq!(response.data, Some(data.clone()));
+                let msg_response = response.msg_responses[0]
+                    .execute_contract_response()
+                    .unwrap();
+                assert_eq!(msg_response.data, Some(data));
+                assert_eq!(response.events[1].ty, "wasm");
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        // failure caught by reply reverts the submessage only
+        let res = forward(&mut app, &forwarder, fail.clone(), ReplyOn::Error).unwrap();
+        assert_eq!(res.data, Some(Binary::from(b"replied")));
+        assert_eq!(count(&app, &counter), 1);
+        match last_reply(&app, &forwarder) {
+            SubMsgResult::Err(err) => assert!(err.contains("counter failed")),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        // failure without reply fails the execution
+        let err = forward(&mut app, &forwarder, fail, ReplyOn::Success).unwrap_err();
+        assert!(err.to_string().contains("counter failed"));
+        assert_eq!(count(&app, &counter), 1);
+
+        // success without reply
+        let res = forward(&mut app, &forwarder, increment, ReplyOn::Never).unwrap();
+        assert_eq!(res.data, None);
+        assert_eq!(count(&app, &counter), 2);
+    }
+
+    #[test]
+    fn instantiate_submessage_returns_address() {
+        let mut app = App::new();
+        let counter_id = app.store_code(counter());
+        let forwarder_id = app.store_code(forwarder());
+        let forwarder = app
+            .instantiate_contract(forwarder_id, "owner", &Empty {}, &[], "forwarder", None)
+            .unwrap();
+
+        let instantiate: CosmosMsg = WasmMsg::Instantiate {
+            admin: Some("owner".to_string()),
+            code_id: counter_id,
+            msg: to_binary(&3u32).unwrap(),
+            funds: vec![],
+            label: "counter".to_string(),
+        }
+        .into();
+        forward(&mut app, &forwarder, instantiate, ReplyOn::Success).unwrap();
+        let response = match last_reply(&app, &forwarder) {
+            SubMsgResult::Ok(response) => response.msg_responses[0]
+                .instantiate_contract_response()
+                .unwrap(),
+            result => panic!("Unexpected result: {:?}", result),
+        };
+        assert_eq!(response.contract_address, "contract2");
+        assert_eq!(response.data, Some(Binary::from(b"init")));
+
+        let counter = Addr::unchecked(response.contract_address);
+        assert_eq!(count(&app, &counter), 3);
+        let info = app.wrap().query_wasm_contract_info(&counter).unwrap();
+        assert_eq!(info.creator, forwarder.as_str());
+        assert_eq!(info.admin, Some("owner".to_string()));
+    }
+
+    #[test]
+    fn block_advancement_works() {
+        let mut app = App::new();
+        let code_id = app.store_code(counter());
+        let counter = app
+            .instantiate_contract(code_id, "owner", &0u32, &[], "counter", None)
+            .unwrap();
+
+        let start = app.block_info();
+        app.next_block();
+        assert_eq!(app.block_info().height, start.height + 1);
+        assert_eq!(app.block_info().time, start.time.plus_seconds(5));
+        let height: u64 = app.wrap().query_wasm_smart(&counter, &"height").unwrap();
+        assert_eq!(height, start.height + 1);
+
+        app.update_block(|block| {
+            block.height = 1_000_000;
+            block.time = Timestamp::from_seconds(1_700_000_000);
+        });
+        let height: u64 = app.wrap().query_wasm_smart(&counter, &"height").unwrap();
+        assert_eq!(height, 1_000_000);
+        assert_eq!(
+            app.block_info().time,
+            Timestamp::from_seconds(1_700_000_000)
+        );
+    }
+}
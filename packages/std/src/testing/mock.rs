@@ -1,6 +1,7 @@
 use serde::de::DeserializeOwned;
 #[cfg(feature = "stargate")]
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
@@ -16,6 +17,8 @@ use crate::ibc::{
     IbcTimeoutBlock,
 };
 use crate::math::Uint128;
+#[cfg(feature = "cosmwasm_1_3")]
+use crate::query::SendEnabledResponse;
 #[cfg(feature = "cosmwasm_1_1")]
 use crate::query::SupplyResponse;
 use crate::query::{
@@ -26,19 +29,40 @@ use crate::query::{
     AllDelegationsResponse, AllValidatorsResponse, BondedDenomResponse, DelegationResponse,
     FullDelegation, StakingQuery, Validator, ValidatorResponse,
 };
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::query::{AllDenomMetadataResponse, DenomMetadataResponse, GrpcQuery};
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::query::{
+    DelegationRewardsResponse, DelegationTotalRewardsResponse, DelegatorReward,
+    DelegatorValidatorsResponse, DistributionQuery,
+};
+#[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+use crate::query::{UnbondingDelegationEntry, UnbondingDelegationsResponse};
 use crate::results::{ContractResult, Empty, SystemResult};
 use crate::serde::{from_slice, to_binary};
 use crate::storage::MemoryStorage;
 use crate::timestamp::Timestamp;
 use crate::traits::{Api, Querier, QuerierResult};
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::types::ContractSelfInfo;
 use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
 use crate::Attribute;
+use crate::DenomMetadata;
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::HexBinary;
 #[cfg(feature = "stargate")]
 use crate::{ChannelResponse, IbcQuery, ListChannelsResponse, PortIdResponse};
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::{DecCoin, Decimal256};
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::{HashFunction, BLS12_381_G1_POINT_LEN, BLS12_381_G2_POINT_LEN};
 
 use super::riffle_shuffle;
 
 pub const MOCK_CONTRACT_ADDR: &str = "cosmos2contract";
+/// The code ID returned by [`MockApi::contract_self_info`]
+#[cfg(feature = "cosmwasm_1_4")]
+pub const MOCK_CODE_ID: u64 = 1;
 
 /// Creates all external requirements that can be injected for unit tests.
 ///
@@ -100,12 +124,29 @@ pub struct MockApi {
     /// Length of canonical addresses created with this API. Contracts should not make any assumptions
     /// what this value is.
     canonical_length: usize,
+    /// When set, addresses are bech32 encoded with this prefix like on real chains
+    bech32_prefix: Option<&'static str>,
 }
 
 impl Default for MockApi {
     fn default() -> Self {
         MockApi {
             canonical_length: CANONICAL_LENGTH,
+            bech32_prefix: None,
+        }
+    }
+}
+
+impl MockApi {
+    /// Creates a MockApi that converts between bech32 addresses with the given prefix
+    /// (e.g. "osmo") and their raw data, like real chains do.
+    ///
+    /// Human addresses must be valid bech32 strings with this prefix. Canonical addresses
+    /// must be between 1 and 255 bytes long, which is the limit of the Cosmos SDK.
+    pub fn with_prefix(prefix: &'static str) -> Self {
+        MockApi {
+            bech32_prefix: Some(prefix),
+            ..MockApi::default()
         }
     }
 }
@@ -124,6 +165,10 @@ impl Api for MockApi {
     }
 
     fn addr_canonicalize(&self, input: &str) -> StdResult<CanonicalAddr> {
+        if let Some(prefix) = self.bech32_prefix {
+            return bech32_canonicalize(prefix, input);
+        }
+
         // Dummy input validation. This is more sophisticated for formats like bech32, where format and checksum are validated.
         let min_length = 3;
         let max_length = self.canonical_length;
@@ -156,6 +201,10 @@ impl Api for MockApi {
     }
 
     fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        if let Some(prefix) = self.bech32_prefix {
+            return bech32_humanize(prefix, canonical);
+        }
+
         if canonical.len() != self.canonical_length {
             return Err(StdError::generic_err(
                 "Invalid input: canonical address length not correct",
@@ -177,6 +226,37 @@ impl Api for MockApi {
         Ok(Addr::unchecked(human))
     }
 
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_encode(&self, prefix: &str, data: &[u8]) -> StdResult<String> {
+        use bech32::{ToBase32, Variant};
+
+        let address = bech32::encode(prefix, data.to_base32(), Variant::Bech32)
+            .map_err(|e| StdError::generic_err(format!("bech32_encode errored: {}", e)))?;
+        // Same limit as in the VM
+        if address.len() > 90 {
+            return Err(StdError::generic_err(
+                "bech32_encode errored: Bech32 string exceeds 90 characters",
+            ));
+        }
+        Ok(address)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_decode(&self, address: &str) -> StdResult<(String, Vec<u8>)> {
+        use bech32::{FromBase32, Variant};
+
+        let (prefix, data, variant) = bech32::decode(address)
+            .map_err(|e| StdError::generic_err(format!("bech32_decode errored: {}", e)))?;
+        if variant != Variant::Bech32 {
+            return Err(StdError::generic_err(
+                "bech32_decode errored: Expected bech32 but got bech32m",
+            ));
+        }
+        let data = Vec::<u8>::from_base32(&data)
+            .map_err(|e| StdError::generic_err(format!("bech32_decode errored: {}", e)))?;
+        Ok((prefix, data))
+    }
+
     fn secp256k1_verify(
         &self,
         message_hash: &[u8],
@@ -201,6 +281,145 @@ impl Api for MockApi {
         Ok(pubkey.to_vec())
     }
 
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_batch_verify(
+        &self,
+        message_hashes: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        Ok(cosmwasm_crypto::secp256k1_batch_verify(
+            message_hashes,
+            signatures,
+            public_keys,
+        )?)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_add(&self, p: &[u8], q: &[u8]) -> Result<[u8; 33], VerificationError> {
+        Ok(cosmwasm_crypto::secp256k1_point_add(p, q)?)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_mul(
+        &self,
+        point: &[u8],
+        scalar: &[u8],
+    ) -> Result<[u8; 33], VerificationError> {
+        Ok(cosmwasm_crypto::secp256k1_point_mul(point, scalar)?)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_verify(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Ok(cosmwasm_crypto::secp256r1_verify(
+            message_hash,
+            signature,
+            public_key,
+        )?)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_recover_pubkey(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        recovery_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        let pubkey =
+            cosmwasm_crypto::secp256r1_recover_pubkey(message_hash, signature, recovery_param)?;
+        Ok(pubkey)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_aggregate_g1(
+        &self,
+        g1s: &[u8],
+    ) -> Result<[u8; BLS12_381_G1_POINT_LEN], VerificationError> {
+        Ok(cosmwasm_crypto::bls12_381_aggregate_g1(g1s)?)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_aggregate_g2(
+        &self,
+        g2s: &[u8],
+    ) -> Result<[u8; BLS12_381_G2_POINT_LEN], VerificationError> {
+        Ok(cosmwasm_crypto::bls12_381_aggregate_g2(g2s)?)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_pairing_equality(
+        &self,
+        ps: &[u8],
+        qs: &[u8],
+        r: &[u8],
+        s: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Ok(cosmwasm_crypto::bls12_381_pairing_equality(ps, qs, r, s)?)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_hash_to_g1(
+        &self,
+        hash_function: HashFunction,
+        msg: &[u8],
+        dst: &[u8],
+    ) -> Result<[u8; BLS12_381_G1_POINT_LEN], VerificationError> {
+        let hash_function = cosmwasm_crypto::HashFunction::from_u32(hash_function.to_u32())?;
+        Ok(cosmwasm_crypto::bls12_381_hash_to_g1(
+            hash_function,
+            msg,
+            dst,
+        ))
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_hash_to_g2(
+        &self,
+        hash_function: HashFunction,
+        msg: &[u8],
+        dst: &[u8],
+    ) -> Result<[u8; BLS12_381_G2_POINT_LEN], VerificationError> {
+        let hash_function = cosmwasm_crypto::HashFunction::from_u32(hash_function.to_u32())?;
+        Ok(cosmwasm_crypto::bls12_381_hash_to_g2(
+            hash_function,
+            msg,
+            dst,
+        ))
+    }
+
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bn254(
+        &self,
+        verifying_key: &[u8],
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Ok(cosmwasm_crypto::groth16_verify_bn254(
+            verifying_key,
+            proof,
+            public_inputs,
+        )?)
+    }
+
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bls12_381(
+        &self,
+        verifying_key: &[u8],
+        proof: &[u8],
+        public_inputs: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Ok(cosmwasm_crypto::groth16_verify_bls12_381(
+            verifying_key,
+            proof,
+            public_inputs,
+        )?)
+    }
+
     fn ed25519_verify(
         &self,
         message: &[u8],
@@ -225,9 +444,89 @@ impl Api for MockApi {
         )?)
     }
 
+    #[cfg(feature = "sr25519")]
+    fn sr25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Ok(cosmwasm_crypto::sr25519_verify(
+            message, signature, public_key,
+        )?)
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn keccak256(&self, data: &[u8]) -> StdResult<[u8; 32]> {
+        Ok(cosmwasm_crypto::keccak256(data))
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn sha512(&self, data: &[u8]) -> StdResult<[u8; 64]> {
+        Ok(cosmwasm_crypto::sha512(data))
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn blake2b512(&self, data: &[u8]) -> StdResult<[u8; 64]> {
+        Ok(cosmwasm_crypto::blake2b512(data))
+    }
+
     fn debug(&self, message: &str) {
         println!("{}", message);
     }
+
+    /// Returns [`MOCK_CONTRACT_ADDR`], [`MOCK_CODE_ID`] and an all-zero checksum
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn contract_self_info(&self) -> StdResult<ContractSelfInfo> {
+        Ok(ContractSelfInfo {
+            address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            code_id: MOCK_CODE_ID,
+            checksum: HexBinary::from([0u8; 32]),
+        })
+    }
+}
+
+/// The maximum length of an address in the Cosmos SDK
+const MAX_BECH32_ADDRESS_DATA_LENGTH: usize = 255;
+
+fn bech32_canonicalize(prefix: &str, input: &str) -> StdResult<CanonicalAddr> {
+    use bech32::{FromBase32, Variant};
+
+    let (decoded_prefix, data, variant) = bech32::decode(input)
+        .map_err(|e| StdError::generic_err(format!("Invalid input: {}", e)))?;
+    if decoded_prefix != prefix {
+        return Err(StdError::generic_err(format!(
+            "Invalid input: wrong address prefix, expected {}",
+            prefix
+        )));
+    }
+    if variant != Variant::Bech32 {
+        return Err(StdError::generic_err(
+            "Invalid input: expected bech32 but got bech32m",
+        ));
+    }
+    let data = Vec::<u8>::from_base32(&data)
+        .map_err(|e| StdError::generic_err(format!("Invalid input: {}", e)))?;
+    validate_bech32_data_length(&data)?;
+    Ok(data.into())
+}
+
+fn bech32_humanize(prefix: &str, canonical: &CanonicalAddr) -> StdResult<Addr> {
+    use bech32::{ToBase32, Variant};
+
+    validate_bech32_data_length(canonical)?;
+    let human = bech32::encode(prefix, canonical.as_slice().to_base32(), Variant::Bech32)
+        .map_err(|e| StdError::generic_err(format!("Invalid input: {}", e)))?;
+    Ok(Addr::unchecked(human))
+}
+
+fn validate_bech32_data_length(data: &[u8]) -> StdResult<()> {
+    if data.is_empty() || data.len() > MAX_BECH32_ADDRESS_DATA_LENGTH {
+        return Err(StdError::generic_err(
+            "Invalid input: canonical address length not correct",
+        ));
+    }
+    Ok(())
 }
 
 /// Returns a default enviroment with height, time, chain_id, and contract address
@@ -249,6 +548,73 @@ pub fn mock_env() -> Env {
     }
 }
 
+/// Builds an [`Env`] starting from the values of [`mock_env`], for tests that need
+/// a specific block height, time or chain.
+///
+/// ```
+/// # use cosmwasm_std::testing::EnvBuilder;
+/// # use cosmwasm_std::Timestamp;
+/// let env = EnvBuilder::new()
+///     .with_height(123)
+///     .with_time(Timestamp::from_seconds(1_700_000_000))
+///     .build();
+/// assert_eq!(env.block.height, 123);
+/// ```
+///
+/// This is intended for use in test code only.
+#[derive(Clone, Debug)]
+pub struct EnvBuilder {
+    env: Env,
+}
+
+impl EnvBuilder {
+    pub fn new() -> Self {
+        EnvBuilder { env: mock_env() }
+    }
+
+    pub fn with_chain_id(mut self, chain_id: impl Into<String>) -> Self {
+        self.env.block.chain_id = chain_id.into();
+        self
+    }
+
+    pub fn with_height(mut self, height: u64) -> Self {
+        self.env.block.height = height;
+        self
+    }
+
+    pub fn with_time(mut self, time: Timestamp) -> Self {
+        self.env.block.time = time;
+        self
+    }
+
+    pub fn with_transaction_index(mut self, index: u32) -> Self {
+        self.env.transaction = Some(TransactionInfo { index });
+        self
+    }
+
+    /// Removes the transaction info, as is the case for executions outside of a transaction
+    /// (e.g. begin/end block)
+    pub fn without_transaction(mut self) -> Self {
+        self.env.transaction = None;
+        self
+    }
+
+    pub fn with_contract_address(mut self, address: impl Into<String>) -> Self {
+        self.env.contract.address = Addr::unchecked(address);
+        self
+    }
+
+    pub fn build(self) -> Env {
+        self.env
+    }
+}
+
+impl Default for EnvBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Just set sender and funds for the message.
 /// This is intended for use in test code only.
 pub fn mock_info(sender: &str, funds: &[Coin]) -> MessageInfo {
@@ -274,6 +640,8 @@ pub fn mock_ibc_channel(my_channel_id: &str, order: IbcOrder, version: &str) ->
         order,
         version: version.to_string(),
         connection_id: "connection-2".to_string(),
+        #[cfg(feature = "cosmwasm_1_4")]
+        fee_version: None,
     }
 }
 
@@ -436,9 +804,13 @@ pub struct MockQuerier<C: DeserializeOwned = Empty> {
     bank: BankQuerier,
     #[cfg(feature = "staking")]
     staking: StakingQuerier,
+    #[cfg(feature = "cosmwasm_1_4")]
+    distribution: DistributionQuerier,
     wasm: WasmQuerier,
     #[cfg(feature = "stargate")]
     ibc: IbcQuerier,
+    #[cfg(any(feature = "stargate", feature = "cosmwasm_1_4"))]
+    grpc: GrpcQuerier,
     /// A handler to handle custom queries. This is set to a dummy handler that
     /// always errors by default. Update it via `with_custom_handler`.
     ///
@@ -452,9 +824,13 @@ impl<C: DeserializeOwned> MockQuerier<C> {
             bank: BankQuerier::new(balances),
             #[cfg(feature = "staking")]
             staking: StakingQuerier::default(),
+            #[cfg(feature = "cosmwasm_1_4")]
+            distribution: DistributionQuerier::default(),
             wasm: WasmQuerier::default(),
             #[cfg(feature = "stargate")]
             ibc: IbcQuerier::default(),
+            #[cfg(any(feature = "stargate", feature = "cosmwasm_1_4"))]
+            grpc: GrpcQuerier::default(),
             // strange argument notation suggested as a workaround here: https://github.com/rust-lang/rust/issues/41078#issuecomment-294296365
             custom_handler: Box::from(|_: &_| -> MockQuerierCustomHandlerResult {
                 SystemResult::Err(SystemError::UnsupportedRequest {
@@ -473,6 +849,18 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         self.bank.update_balance(addr, balance)
     }
 
+    /// Sets whether the given denom can be sent. All denoms can be sent by default.
+    #[cfg(feature = "cosmwasm_1_3")]
+    pub fn update_send_enabled(&mut self, denom: impl Into<String>, enabled: bool) {
+        self.bank.update_send_enabled(denom, enabled)
+    }
+
+    /// Replaces the denom metadata returned by the bank queries
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn set_denom_metadata(&mut self, denom_metadata: &[DenomMetadata]) {
+        self.bank.set_denom_metadata(denom_metadata)
+    }
+
     #[cfg(feature = "staking")]
     pub fn update_staking(
         &mut self,
@@ -480,7 +868,77 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         validators: &[crate::query::Validator],
         delegations: &[crate::query::FullDelegation],
     ) {
+        // Keep unbonding delegations, which are set separately
+        #[cfg(feature = "cosmwasm_1_4")]
+        let unbonding_delegations = std::mem::take(&mut self.staking.unbonding_delegations);
         self.staking = StakingQuerier::new(denom, validators, delegations);
+        #[cfg(feature = "cosmwasm_1_4")]
+        {
+            self.staking.unbonding_delegations = unbonding_delegations;
+        }
+    }
+
+    /// Adds a validator or replaces the one with the same address
+    #[cfg(feature = "staking")]
+    pub fn update_validator(&mut self, validator: crate::query::Validator) {
+        self.staking.update_validator(validator);
+    }
+
+    /// Adds a delegation or replaces the one of the same delegator to the same validator.
+    ///
+    /// If rewards were set for this delegation via [`MockQuerier::update_delegation_rewards`],
+    /// they are used as its `accumulated_rewards`, like on a real chain.
+    #[cfg(feature = "staking")]
+    pub fn update_delegation(&mut self, delegation: crate::query::FullDelegation) {
+        #[cfg(feature = "cosmwasm_1_4")]
+        let mut delegation = delegation;
+        #[cfg(feature = "cosmwasm_1_4")]
+        if let Some(rewards) = self
+            .distribution
+            .rewards(delegation.delegator.as_str(), &delegation.validator)
+        {
+            delegation.accumulated_rewards = truncate_dec_coins(rewards);
+        }
+        self.staking.update_delegation(delegation);
+    }
+
+    /// Removes the delegation of `delegator` to `validator` and returns it
+    #[cfg(feature = "staking")]
+    pub fn remove_delegation(
+        &mut self,
+        delegator: &str,
+        validator: &str,
+    ) -> Option<crate::query::FullDelegation> {
+        self.staking.remove_delegation(delegator, validator)
+    }
+
+    /// Sets the pending unbondings of the delegation of `delegator` to `validator`
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    pub fn update_unbonding_delegations(
+        &mut self,
+        delegator: impl Into<String>,
+        validator: impl Into<String>,
+        entries: &[crate::query::UnbondingDelegationEntry],
+    ) {
+        self.staking
+            .update_unbonding_delegations(delegator, validator, entries);
+    }
+
+    /// Sets the rewards accrued by the delegation of `delegator` to `validator`
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn update_delegation_rewards(
+        &mut self,
+        delegator: impl Into<String>,
+        validator: impl Into<String>,
+        rewards: Vec<DecCoin>,
+    ) {
+        let delegator = delegator.into();
+        let validator = validator.into();
+        // Keep the rewards of the staking delegation in sync, which contain the whole coins
+        #[cfg(feature = "staking")]
+        self.staking
+            .set_accumulated_rewards(&delegator, &validator, truncate_dec_coins(&rewards));
+        self.distribution.set_rewards(delegator, validator, rewards);
     }
 
     #[cfg(feature = "stargate")]
@@ -488,6 +946,19 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         self.ibc = IbcQuerier::new(port_id, channels);
     }
 
+    /// Replaces the channels bound to `port_id` without affecting other ports.
+    /// See [`IbcQuerier::update_port`].
+    #[cfg(feature = "stargate")]
+    pub fn update_ibc_port(&mut self, port_id: &str, channels: &[IbcChannel]) {
+        self.ibc.update_port(port_id, channels);
+    }
+
+    /// Adds an IBC channel or replaces the one with the same port and channel ID
+    #[cfg(feature = "stargate")]
+    pub fn update_ibc_channel(&mut self, channel: IbcChannel) {
+        self.ibc.update_channel(channel);
+    }
+
     pub fn update_wasm<WH: 'static>(&mut self, handler: WH)
     where
         WH: Fn(&WasmQuery) -> QuerierResult,
@@ -502,6 +973,20 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         self.custom_handler = Box::from(handler);
         self
     }
+
+    /// Registers a handler for the Stargate and gRPC queries with the given path
+    /// (e.g. "/cosmos.bank.v1beta1.Query/Balance"). The handler receives the protobuf
+    /// encoded request and returns the protobuf encoded response.
+    ///
+    /// Queries to paths without a handler fail with an unsupported request error.
+    #[cfg(any(feature = "stargate", feature = "cosmwasm_1_4"))]
+    pub fn with_grpc_handler<GH>(mut self, path: impl Into<String>, handler: GH) -> Self
+    where
+        GH: Fn(&Binary) -> MockQuerierCustomHandlerResult + 'static,
+    {
+        self.grpc.update_handler(path, handler);
+        self
+    }
 }
 
 impl Default for MockQuerier {
@@ -534,11 +1019,45 @@ impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
             QueryRequest::Staking(staking_query) => self.staking.query(staking_query),
             QueryRequest::Wasm(msg) => self.wasm.query(msg),
             #[cfg(feature = "stargate")]
-            QueryRequest::Stargate { .. } => SystemResult::Err(SystemError::UnsupportedRequest {
-                kind: "Stargate".to_string(),
-            }),
+            QueryRequest::Stargate { path, data } => self.grpc.query(path, data, "Stargate"),
             #[cfg(feature = "stargate")]
             QueryRequest::Ibc(msg) => self.ibc.query(msg),
+            #[cfg(feature = "cosmwasm_1_4")]
+            QueryRequest::Distribution(distribution_query) => {
+                self.distribution.query(distribution_query)
+            }
+            #[cfg(feature = "cosmwasm_1_4")]
+            QueryRequest::Grpc(GrpcQuery { path, data }) => self.grpc.query(path, data, "GRPC"),
+        }
+    }
+}
+
+/// Answers Stargate and gRPC queries using handlers registered by path
+#[cfg(any(feature = "stargate", feature = "cosmwasm_1_4"))]
+#[derive(Default)]
+struct GrpcQuerier {
+    handlers: HashMap<String, GrpcHandler>,
+}
+
+#[cfg(any(feature = "stargate", feature = "cosmwasm_1_4"))]
+type GrpcHandler = Box<dyn Fn(&Binary) -> QuerierResult>;
+
+#[cfg(any(feature = "stargate", feature = "cosmwasm_1_4"))]
+impl GrpcQuerier {
+    fn update_handler<GH>(&mut self, path: impl Into<String>, handler: GH)
+    where
+        GH: Fn(&Binary) -> QuerierResult + 'static,
+    {
+        self.handlers.insert(path.into(), Box::from(handler));
+    }
+
+    /// Calls the handler for `path`. `kind` is the query type reported when no handler exists.
+    fn query(&self, path: &str, data: &Binary, kind: &str) -> QuerierResult {
+        match self.handlers.get(path) {
+            Some(handler) => handler(data),
+            None => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: kind.to_string(),
+            }),
         }
     }
 }
@@ -599,6 +1118,12 @@ pub struct BankQuerier {
     supplies: HashMap<String, Uint128>,
     /// HashMap<address, coins>
     balances: HashMap<String, Vec<Coin>>,
+    /// HashMap<denom, enabled>. Denoms not in here can be sent.
+    #[allow(dead_code)]
+    send_enabled: HashMap<String, bool>,
+    /// BTreeMap<denom, metadata>. Sorted by denom for pagination.
+    #[allow(dead_code)]
+    denom_metadata: BTreeMap<String, DenomMetadata>,
 }
 
 impl BankQuerier {
@@ -611,6 +1136,8 @@ impl BankQuerier {
         BankQuerier {
             supplies: Self::calculate_supplies(&balances),
             balances,
+            send_enabled: HashMap::new(),
+            denom_metadata: BTreeMap::new(),
         }
     }
 
@@ -625,6 +1152,21 @@ impl BankQuerier {
         result
     }
 
+    /// Sets whether the given denom can be sent. All denoms can be sent by default.
+    #[cfg(feature = "cosmwasm_1_3")]
+    pub fn update_send_enabled(&mut self, denom: impl Into<String>, enabled: bool) {
+        self.send_enabled.insert(denom.into(), enabled);
+    }
+
+    /// Replaces the stored denom metadata. Entries are keyed by their base denom.
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn set_denom_metadata(&mut self, denom_metadata: &[DenomMetadata]) {
+        self.denom_metadata = denom_metadata
+            .iter()
+            .map(|m| (m.base.clone(), m.clone()))
+            .collect();
+    }
+
     fn calculate_supplies(balances: &HashMap<String, Vec<Coin>>) -> HashMap<String, Uint128> {
         let mut supplies = HashMap::new();
 
@@ -678,6 +1220,45 @@ impl BankQuerier {
                 };
                 to_binary(&bank_res).into()
             }
+            #[cfg(feature = "cosmwasm_1_3")]
+            BankQuery::SendEnabled { denom } => {
+                let enabled = self.send_enabled.get(denom).copied().unwrap_or(true);
+                let bank_res = SendEnabledResponse::new(denom, enabled);
+                to_binary(&bank_res).into()
+            }
+            #[cfg(feature = "cosmwasm_1_4")]
+            BankQuery::DenomMetadata { denom } => match self.denom_metadata.get(denom) {
+                Some(metadata) => {
+                    let bank_res = DenomMetadataResponse {
+                        metadata: metadata.clone(),
+                    };
+                    to_binary(&bank_res).into()
+                }
+                None => ContractResult::Err(format!("Denom metadata not found for '{}'", denom)),
+            },
+            #[cfg(feature = "cosmwasm_1_4")]
+            BankQuery::AllDenomMetadata { pagination } => {
+                let (key, limit, reverse) = match pagination {
+                    // like in the SDK, a limit of 0 means the default, which is no limit here
+                    Some(p) if p.limit == 0 => (p.key.as_ref(), usize::MAX, p.reverse),
+                    Some(p) => (p.key.as_ref(), p.limit as usize, p.reverse),
+                    None => (None, usize::MAX, false),
+                };
+                let start = key.map(|k| String::from_utf8_lossy(k.as_slice()).into_owned());
+                let iter: Box<dyn Iterator<Item = (&String, &DenomMetadata)>> = match &start {
+                    Some(start) if reverse => {
+                        Box::new(self.denom_metadata.range(..=start.clone()).rev())
+                    }
+                    Some(start) => Box::new(self.denom_metadata.range(start.clone()..)),
+                    None if reverse => Box::new(self.denom_metadata.iter().rev()),
+                    None => Box::new(self.denom_metadata.iter()),
+                };
+                let mut iter = iter.peekable();
+                let metadata = iter.by_ref().take(limit).map(|(_, m)| m.clone()).collect();
+                let next_key = iter.peek().map(|(denom, _)| Binary::from(denom.as_bytes()));
+                let bank_res = AllDenomMetadataResponse { metadata, next_key };
+                to_binary(&bank_res).into()
+            }
         };
         // system result is always ok in the mock implementation
         SystemResult::Ok(contract_result)
@@ -703,6 +1284,43 @@ impl IbcQuerier {
         }
     }
 
+    /// Replaces the channels bound to `port_id`, keeping the channels of all other ports.
+    /// The port of the given channels' endpoints is set to `port_id`.
+    ///
+    /// This allows mocking a chain where multiple ports have open channels, while the
+    /// contract's own port stays the one given in [`IbcQuerier::new`].
+    pub fn update_port(&mut self, port_id: &str, channels: &[IbcChannel]) {
+        self.channels.retain(|c| c.endpoint.port_id != port_id);
+        self.channels
+            .extend(channels.iter().cloned().map(|mut channel| {
+                channel.endpoint.port_id = port_id.to_string();
+                channel
+            }));
+    }
+
+    /// Adds a channel or replaces the one with the same port and channel ID
+    pub fn update_channel(&mut self, channel: IbcChannel) {
+        match self
+            .channels
+            .iter_mut()
+            .find(|c| c.endpoint == channel.endpoint)
+        {
+            Some(existing) => *existing = channel,
+            None => self.channels.push(channel),
+        }
+    }
+
+    /// The channels bound to `port_id` or, if `None`, to the contract's port
+    fn channels_on_port<'a>(
+        &'a self,
+        port_id: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a IbcChannel> + 'a {
+        let port_id = port_id.unwrap_or(&self.port_id);
+        self.channels
+            .iter()
+            .filter(move |c| c.endpoint.port_id == port_id)
+    }
+
     pub fn query(&self, request: &IbcQuery) -> QuerierResult {
         let contract_result: ContractResult<Binary> = match request {
             IbcQuery::Channel {
@@ -724,15 +1342,33 @@ impl IbcQuerier {
                 to_binary(&res).into()
             }
             IbcQuery::ListChannels { port_id } => {
-                let channels = self
-                    .channels
-                    .iter()
-                    .filter(|c| match port_id {
-                        Some(p) => c.endpoint.port_id.eq(p),
-                        None => c.endpoint.port_id == self.port_id,
+                let channels = self.channels_on_port(port_id.as_deref()).cloned().collect();
+                let res = ListChannelsResponse { channels };
+                to_binary(&res).into()
+            }
+            #[cfg(feature = "cosmwasm_1_4")]
+            IbcQuery::ListChannelsPaginated {
+                port_id,
+                counterparty_port_id,
+                start_after,
+                limit,
+            } => {
+                let mut channels: Vec<IbcChannel> = self
+                    .channels_on_port(port_id.as_deref())
+                    .filter(|c| match counterparty_port_id {
+                        Some(p) => c.counterparty_endpoint.port_id.eq(p),
+                        None => true,
+                    })
+                    .filter(|c| match start_after {
+                        Some(start) => c.endpoint.channel_id.as_str() > start.as_str(),
+                        None => true,
                     })
                     .cloned()
                     .collect();
+                channels.sort_by(|a, b| a.endpoint.channel_id.cmp(&b.endpoint.channel_id));
+                if let Some(limit) = limit {
+                    channels.truncate(*limit as usize);
+                }
                 let res = ListChannelsResponse { channels };
                 to_binary(&res).into()
             }
@@ -754,6 +1390,9 @@ pub struct StakingQuerier {
     denom: String,
     validators: Vec<Validator>,
     delegations: Vec<FullDelegation>,
+    /// Pending unbondings by delegator and validator address
+    #[cfg(feature = "cosmwasm_1_4")]
+    unbonding_delegations: HashMap<(String, String), Vec<UnbondingDelegationEntry>>,
 }
 
 #[cfg(feature = "staking")]
@@ -763,6 +1402,69 @@ impl StakingQuerier {
             denom: denom.to_string(),
             validators: validators.to_vec(),
             delegations: delegations.to_vec(),
+            #[cfg(feature = "cosmwasm_1_4")]
+            unbonding_delegations: HashMap::new(),
+        }
+    }
+
+    /// Sets the pending unbondings of the delegation of `delegator` to `validator`
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn update_unbonding_delegations(
+        &mut self,
+        delegator: impl Into<String>,
+        validator: impl Into<String>,
+        entries: &[UnbondingDelegationEntry],
+    ) {
+        self.unbonding_delegations
+            .insert((delegator.into(), validator.into()), entries.to_vec());
+    }
+
+    /// Adds a validator or replaces the one with the same address
+    pub fn update_validator(&mut self, validator: Validator) {
+        match self
+            .validators
+            .iter_mut()
+            .find(|v| v.address == validator.address)
+        {
+            Some(existing) => *existing = validator,
+            None => self.validators.push(validator),
+        }
+    }
+
+    /// Adds a delegation or replaces the one of the same delegator to the same validator
+    pub fn update_delegation(&mut self, delegation: FullDelegation) {
+        match self
+            .delegations
+            .iter_mut()
+            .find(|d| d.delegator == delegation.delegator && d.validator == delegation.validator)
+        {
+            Some(existing) => *existing = delegation,
+            None => self.delegations.push(delegation),
+        }
+    }
+
+    /// Removes the delegation of `delegator` to `validator` and returns it
+    pub fn remove_delegation(
+        &mut self,
+        delegator: &str,
+        validator: &str,
+    ) -> Option<FullDelegation> {
+        let index = self
+            .delegations
+            .iter()
+            .position(|d| d.delegator.as_str() == delegator && d.validator == validator)?;
+        Some(self.delegations.remove(index))
+    }
+
+    /// Sets the `accumulated_rewards` of the delegation of `delegator` to `validator`, if it exists
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn set_accumulated_rewards(&mut self, delegator: &str, validator: &str, rewards: Vec<Coin>) {
+        if let Some(delegation) = self
+            .delegations
+            .iter_mut()
+            .find(|d| d.delegator.as_str() == delegator && d.validator == validator)
+        {
+            delegation.accumulated_rewards = rewards;
         }
     }
 
@@ -813,11 +1515,123 @@ impl StakingQuerier {
                 };
                 to_binary(&res).into()
             }
-        };
-        // system result is always ok in the mock implementation
-        SystemResult::Ok(contract_result)
-    }
-}
+            #[cfg(feature = "cosmwasm_1_4")]
+            StakingQuery::UnbondingDelegations {
+                delegator,
+                validator,
+            } => {
+                let mut entries = self
+                    .unbonding_delegations
+                    .get(&(delegator.clone(), validator.clone()))
+                    .cloned()
+                    .unwrap_or_default();
+                entries.sort_by_key(|entry| entry.completion_time);
+                let res = UnbondingDelegationsResponse::new(entries);
+                to_binary(&res).into()
+            }
+        };
+        // system result is always ok in the mock implementation
+        SystemResult::Ok(contract_result)
+    }
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Clone, Default)]
+pub struct DistributionQuerier {
+    /// Accrued rewards by delegator and validator address
+    rewards: BTreeMap<String, BTreeMap<String, Vec<DecCoin>>>,
+}
+
+#[cfg(feature = "cosmwasm_1_4")]
+impl DistributionQuerier {
+    pub fn new() -> Self {
+        DistributionQuerier::default()
+    }
+
+    /// Sets the rewards accrued by the delegation of `delegator` to `validator`.
+    /// This also makes the validator show up in `DelegatorValidators` queries.
+    pub fn set_rewards(
+        &mut self,
+        delegator: impl Into<String>,
+        validator: impl Into<String>,
+        rewards: Vec<DecCoin>,
+    ) {
+        self.rewards
+            .entry(delegator.into())
+            .or_default()
+            .insert(validator.into(), rewards);
+    }
+
+    /// Returns the rewards accrued by the delegation of `delegator` to `validator`
+    pub fn rewards(&self, delegator: &str, validator: &str) -> Option<&[DecCoin]> {
+        self.rewards
+            .get(delegator)
+            .and_then(|v| v.get(validator))
+            .map(|rewards| rewards.as_slice())
+    }
+
+    pub fn query(&self, request: &DistributionQuery) -> QuerierResult {
+        let contract_result: ContractResult<Binary> = match request {
+            DistributionQuery::DelegationRewards {
+                delegator_address,
+                validator_address,
+            } => {
+                let rewards = self
+                    .rewards
+                    .get(delegator_address)
+                    .and_then(|v| v.get(validator_address))
+                    .cloned()
+                    .unwrap_or_default();
+                let res = DelegationRewardsResponse::new(rewards);
+                to_binary(&res).into()
+            }
+            DistributionQuery::DelegationTotalRewards { delegator_address } => {
+                let mut rewards = vec![];
+                let mut total = BTreeMap::<String, Decimal256>::new();
+                for (validator, reward) in self.rewards.get(delegator_address).into_iter().flatten()
+                {
+                    for coin in reward {
+                        *total.entry(coin.denom.clone()).or_default() += coin.amount;
+                    }
+                    rewards.push(DelegatorReward::new(validator, reward.clone()));
+                }
+                let total = total
+                    .into_iter()
+                    .map(|(denom, amount)| DecCoin::new(amount, denom))
+                    .collect();
+                let res = DelegationTotalRewardsResponse::new(rewards, total);
+                to_binary(&res).into()
+            }
+            DistributionQuery::DelegatorValidators { delegator_address } => {
+                let validators = self
+                    .rewards
+                    .get(delegator_address)
+                    .map(|v| v.keys().cloned().collect())
+                    .unwrap_or_default();
+                let res = DelegatorValidatorsResponse::new(validators);
+                to_binary(&res).into()
+            }
+        };
+        // system result is always ok in the mock implementation
+        SystemResult::Ok(contract_result)
+    }
+}
+
+/// Converts rewards to the coins that can be withdrawn, dropping fractional amounts
+#[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+fn truncate_dec_coins(rewards: &[DecCoin]) -> Vec<Coin> {
+    rewards
+        .iter()
+        .map(|reward| Coin {
+            denom: reward.denom.clone(),
+            amount: reward
+                .amount
+                .to_uint_floor()
+                .try_into()
+                .expect("reward amount exceeds Uint128"),
+        })
+        .collect()
+}
 
 pub fn digit_sum(input: &[u8]) -> usize {
     input.iter().fold(0, |sum, val| sum + (*val as usize))
@@ -846,11 +1660,28 @@ mod tests {
     const SECP256K1_SIG_HEX: &str = "207082eb2c3dfa0b454e0906051270ba4074ac93760ba9e7110cd9471475111151eb0dbbc9920e72146fb564f99d039802bf6ef2561446eb126ef364d21ee9c4";
     const SECP256K1_PUBKEY_HEX: &str = "04051c1ee2190ecfb174bfe4f90763f2b4ff7517b70a2aec1876ebcfd644c4633fb03f3cfbd94b1f376e34592d9d41ccaf640bb751b00a1fadeb0c01157769eb73";
 
+    // RFC 6979, A.2.5 (P-256, SHA-256, message "sample")
+    #[cfg(feature = "cosmwasm_1_4")]
+    const SECP256R1_MSG_HASH_HEX: &str =
+        "af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf";
+    #[cfg(feature = "cosmwasm_1_4")]
+    const SECP256R1_SIG_HEX: &str = "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8";
+    #[cfg(feature = "cosmwasm_1_4")]
+    const SECP256R1_PUBKEY_HEX: &str = "0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299";
+
     const ED25519_MSG_HEX: &str = "72";
     const ED25519_SIG_HEX: &str = "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00";
     const ED25519_PUBKEY_HEX: &str =
         "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
 
+    #[cfg(feature = "sr25519")]
+    const SR25519_MSG: &[u8] = b"CosmWasm sr25519 test message";
+    #[cfg(feature = "sr25519")]
+    const SR25519_SIG_HEX: &str = "ee5ec89ebe97fc4c5f5c3d2ddc4923fd647f287aa06f7c8739b60be2a206e21b1e2bc01f22e13cf04ec92ff40aeb9bf4964c2cf11572159f1bdd346274476585";
+    #[cfg(feature = "sr25519")]
+    const SR25519_PUBKEY_HEX: &str =
+        "a4d007a95b28ce117e4051904503612abdcbd22b60475121f92a8b5dedac375a";
+
     #[test]
     fn mock_info_works() {
         let info = mock_info("my name", &coins(100, "atom"));
@@ -866,6 +1697,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn env_builder_works() {
+        // defaults to mock_env
+        assert_eq!(EnvBuilder::new().build(), mock_env());
+        assert_eq!(EnvBuilder::default().build(), mock_env());
+
+        let env = EnvBuilder::new()
+            .with_chain_id("juno-1")
+            .with_height(123)
+            .with_time(Timestamp::from_seconds(1_700_000_000))
+            .with_transaction_index(7)
+            .with_contract_address("contract7")
+            .build();
+        assert_eq!(
+            env,
+            Env {
+                block: BlockInfo {
+                    height: 123,
+                    time: Timestamp::from_seconds(1_700_000_000),
+                    chain_id: "juno-1".to_string(),
+                },
+                transaction: Some(TransactionInfo { index: 7 }),
+                contract: ContractInfo {
+                    address: Addr::unchecked("contract7"),
+                },
+            }
+        );
+
+        let env = EnvBuilder::new().without_transaction().build();
+        assert_eq!(env.transaction, None);
+        assert_eq!(env.block, mock_env().block);
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn contract_self_info_works() {
+        let api = MockApi::default();
+        let info = api.contract_self_info().unwrap();
+        assert_eq!(info.address, MOCK_CONTRACT_ADDR);
+        assert_eq!(info.code_id, MOCK_CODE_ID);
+        assert_eq!(info.checksum, [0u8; 32]);
+    }
+
     #[test]
     fn addr_validate_works() {
         let api = MockApi::default();
@@ -938,6 +1812,109 @@ mod tests {
             .contains("human address too long for this mock implementation (must be <= 90)"));
     }
 
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_encode_and_decode_work() {
+        let api = MockApi::default();
+
+        // Test vector from BIP-173
+        let data = hex!("00443214c74254b635cf84653a56d7c675be77df");
+        let address = api.bech32_encode("abcdef", &data).unwrap();
+        assert_eq!(address, "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw");
+        let (prefix, decoded) = api.bech32_decode(&address).unwrap();
+        assert_eq!(prefix, "abcdef");
+        assert_eq!(decoded, data);
+
+        // Round trip for another chain's address
+        let address = api.bech32_encode("osmo", &[0xAA; 32]).unwrap();
+        assert_eq!(
+            api.bech32_decode(&address).unwrap(),
+            ("osmo".to_string(), vec![0xAA; 32])
+        );
+
+        // Errors
+        api.bech32_encode("", &data).unwrap_err();
+        api.bech32_encode("cosmos", &[0x11; 64]).unwrap_err();
+        api.bech32_decode("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxx")
+            .unwrap_err();
+        api.bech32_decode("a1lqfn3a").unwrap_err();
+    }
+
+    #[test]
+    fn with_prefix_canonicalize_and_humanize_restores_original() {
+        let api = MockApi::with_prefix("osmo");
+
+        // 20 and 32 byte addresses
+        for data in [vec![0xAA; 20], vec![0x11; 32]] {
+            let human = api.addr_humanize(&data.clone().into()).unwrap();
+            assert!(human.as_str().starts_with("osmo1"));
+            let canonical = api.addr_canonicalize(human.as_str()).unwrap();
+            assert_eq!(canonical.as_slice(), data.as_slice());
+            assert_eq!(api.addr_validate(human.as_str()).unwrap(), human);
+        }
+
+        // Test vector from BIP-173 with custom prefix
+        let api = MockApi::with_prefix("abcdef");
+        let canonical = api
+            .addr_canonicalize("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw")
+            .unwrap();
+        assert_eq!(
+            canonical.as_slice(),
+            hex!("00443214c74254b635cf84653a56d7c675be77df")
+        );
+    }
+
+    #[test]
+    fn with_prefix_addr_validate_fails_for_invalid_addresses() {
+        let api = MockApi::with_prefix("osmo");
+        let valid = api.addr_humanize(&vec![0xAA; 20].into()).unwrap();
+
+        // wrong prefix
+        let juno = MockApi::with_prefix("juno")
+            .addr_humanize(&vec![0xAA; 20].into())
+            .unwrap();
+        let err = api.addr_validate(juno.as_str()).unwrap_err();
+        assert!(err.to_string().contains("wrong address prefix"));
+
+        // not normalized
+        let upper = valid.as_str().to_uppercase();
+        let err = api.addr_validate(&upper).unwrap_err();
+        assert!(err.to_string().contains("address not normalized"));
+
+        // invalid checksum
+        let mut broken = valid.to_string();
+        broken.pop();
+        broken.push(if valid.as_str().ends_with('q') {
+            'p'
+        } else {
+            'q'
+        });
+        api.addr_validate(&broken).unwrap_err();
+
+        // not bech32 at all
+        api.addr_validate("foobar123").unwrap_err();
+    }
+
+    #[test]
+    fn with_prefix_validates_canonical_length() {
+        let api = MockApi::with_prefix("osmo");
+
+        let err = api.addr_humanize(&CanonicalAddr::from(vec![])).unwrap_err();
+        assert!(err.to_string().contains("length not correct"));
+        let err = api
+            .addr_humanize(&CanonicalAddr::from(vec![0x11; 256]))
+            .unwrap_err();
+        assert!(err.to_string().contains("length not correct"));
+        api.addr_humanize(&CanonicalAddr::from(vec![0x11; 255]))
+            .unwrap();
+
+        // empty data
+        use bech32::{ToBase32, Variant};
+        let empty = bech32::encode("osmo", Vec::<u8>::new().to_base32(), Variant::Bech32).unwrap();
+        let err = api.addr_canonicalize(&empty).unwrap_err();
+        assert!(err.to_string().contains("length not correct"));
+    }
+
     #[test]
     #[should_panic(expected = "length not correct")]
     fn addr_humanize_input_length() {
@@ -989,6 +1966,219 @@ mod tests {
         assert_eq!(res.unwrap_err(), VerificationError::InvalidPubkeyFormat);
     }
 
+    // Basic "works" test. Exhaustive tests in cosmwasm-crypto
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_batch_verify_works() {
+        let api = MockApi::default();
+
+        let mut hash = hex::decode(SECP256K1_MSG_HASH_HEX).unwrap();
+        let signature = hex::decode(SECP256K1_SIG_HEX).unwrap();
+        let public_key = hex::decode(SECP256K1_PUBKEY_HEX).unwrap();
+
+        assert!(api
+            .secp256k1_batch_verify(&[&hash, &hash], &[&signature, &signature], &[&public_key])
+            .unwrap());
+
+        // alter hash
+        hash[0] ^= 0x01;
+        assert!(!api
+            .secp256k1_batch_verify(&[&hash], &[&signature], &[&public_key])
+            .unwrap());
+
+        let res = api.secp256k1_batch_verify(&[&hash], &[&signature, &signature], &[&public_key]);
+        assert_eq!(res.unwrap_err(), VerificationError::BatchErr);
+    }
+
+    // Basic "works" test. Exhaustive tests in cosmwasm-crypto
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_add_and_mul_work() {
+        let api = MockApi::default();
+
+        let g = hex!("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        let mut two = [0u8; 32];
+        two[31] = 2;
+
+        let double = api.secp256k1_point_add(&g, &g).unwrap();
+        assert_eq!(api.secp256k1_point_mul(&g, &two).unwrap(), double);
+
+        let res = api.secp256k1_point_add(&g, &g[1..]);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidPubkeyFormat);
+        let res = api.secp256k1_point_mul(&g, &[0xff; 32]);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidScalar);
+    }
+
+    // Basic "works" test. Exhaustive tests in cosmwasm-crypto
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_verify_works() {
+        let api = MockApi::default();
+
+        let mut hash = hex::decode(SECP256R1_MSG_HASH_HEX).unwrap();
+        let signature = hex::decode(SECP256R1_SIG_HEX).unwrap();
+        let public_key = hex::decode(SECP256R1_PUBKEY_HEX).unwrap();
+
+        assert!(api
+            .secp256r1_verify(&hash, &signature, &public_key)
+            .unwrap());
+
+        // alter hash
+        hash[0] ^= 0x01;
+        assert!(!api
+            .secp256r1_verify(&hash, &signature, &public_key)
+            .unwrap());
+
+        let res = api.secp256r1_verify(&hash, &signature, &[]);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidPubkeyFormat);
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_recover_pubkey_works() {
+        let api = MockApi::default();
+
+        let hash = hex::decode(SECP256R1_MSG_HASH_HEX).unwrap();
+        let signature = hex::decode(SECP256R1_SIG_HEX).unwrap();
+        let expected = hex::decode(SECP256R1_PUBKEY_HEX).unwrap();
+
+        let pubkey = api.secp256r1_recover_pubkey(&hash, &signature, 0).unwrap();
+        assert_eq!(pubkey, expected);
+
+        // Wrong recovery param leads to different pubkey
+        let pubkey = api.secp256r1_recover_pubkey(&hash, &signature, 1).unwrap();
+        assert_eq!(pubkey.len(), 65);
+        assert_ne!(pubkey, expected);
+
+        // Invalid recovery param leads to error
+        let result = api.secp256r1_recover_pubkey(&hash, &signature, 42);
+        match result.unwrap_err() {
+            RecoverPubkeyError::InvalidRecoveryParam => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    // Basic "works" test. Exhaustive tests in cosmwasm-crypto
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_works() {
+        use crate::{BLS12_381_G1_GENERATOR, BLS12_381_G2_GENERATOR};
+
+        let api = MockApi::default();
+
+        // BLS signature with signature in G1 and public key in G2, secret key 1
+        let dst = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+        let msg_point = api
+            .bls12_381_hash_to_g1(HashFunction::Sha256, b"message", dst)
+            .unwrap();
+        assert!(api
+            .bls12_381_pairing_equality(
+                &msg_point,
+                &BLS12_381_G2_GENERATOR,
+                &msg_point,
+                &BLS12_381_G2_GENERATOR
+            )
+            .unwrap());
+
+        // 2·G1 with G2 equals G1 with 2·G2
+        let g1_double = api
+            .bls12_381_aggregate_g1(&BLS12_381_G1_GENERATOR.repeat(2))
+            .unwrap();
+        let g2_double = api
+            .bls12_381_aggregate_g2(&BLS12_381_G2_GENERATOR.repeat(2))
+            .unwrap();
+        assert!(api
+            .bls12_381_pairing_equality(
+                &g1_double,
+                &BLS12_381_G2_GENERATOR,
+                &BLS12_381_G1_GENERATOR,
+                &g2_double
+            )
+            .unwrap());
+        assert!(!api
+            .bls12_381_pairing_equality(
+                &g1_double,
+                &g2_double,
+                &BLS12_381_G1_GENERATOR,
+                &BLS12_381_G2_GENERATOR
+            )
+            .unwrap());
+
+        let msg_point = api
+            .bls12_381_hash_to_g2(HashFunction::Sha256, b"message", dst)
+            .unwrap();
+        assert_eq!(msg_point.len(), 96);
+
+        // errors
+        let res = api.bls12_381_aggregate_g1(&BLS12_381_G1_GENERATOR[1..]);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidPoint);
+        let res = api.bls12_381_pairing_equality(
+            &g1_double.repeat(2),
+            &BLS12_381_G2_GENERATOR,
+            &BLS12_381_G1_GENERATOR,
+            &g2_double,
+        );
+        assert_eq!(res.unwrap_err(), VerificationError::UnequalPointAmount);
+    }
+
+    // Basic "works" test. Exhaustive tests in cosmwasm-crypto
+    #[test]
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bn254_works() {
+        const G1: [u8; 64] = hex!("00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002");
+        const G2: [u8; 128] = hex!("198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa");
+        const INFINITY: [u8; 64] = [0; 64];
+
+        let api = MockApi::default();
+
+        // e(-G1, G2) · e(G1, G2) · e(input · G1, G2) · e(0, G2) is 1 for input 0 only
+        let vk = [&G1[..], &G2, &G2, &G2, &INFINITY, &G1].concat();
+        let proof = [&G1[..], &G2, &INFINITY].concat();
+        let mut input = [0u8; 32];
+        assert!(api.groth16_verify_bn254(&vk, &proof, &input).unwrap());
+        input[31] = 1;
+        assert!(!api.groth16_verify_bn254(&vk, &proof, &input).unwrap());
+
+        // errors
+        let res = api.groth16_verify_bn254(&vk, &proof, &[]);
+        assert_eq!(res.unwrap_err(), VerificationError::GenericErr);
+        let res = api.groth16_verify_bn254(&vk, &proof, &[0xff; 32]);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidScalar);
+        let res = api.groth16_verify_bn254(&vk, &[&G2[..], &G2].concat(), &input);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidPoint);
+    }
+
+    // Basic "works" test. Exhaustive tests in cosmwasm-crypto
+    #[test]
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bls12_381_works() {
+        use cosmwasm_crypto::{BLS12_381_G1_GENERATOR, BLS12_381_G2_GENERATOR};
+
+        let g1 = |k: usize| {
+            cosmwasm_crypto::bls12_381_aggregate_g1(&BLS12_381_G1_GENERATOR.repeat(k)).unwrap()
+        };
+        let g2 = |k: usize| {
+            cosmwasm_crypto::bls12_381_aggregate_g2(&BLS12_381_G2_GENERATOR.repeat(k)).unwrap()
+        };
+        let api = MockApi::default();
+
+        // alpha = beta = gamma = delta = 1, ic = [1, 2] and input 3 give l = 7,
+        // so a = 3, b = 4 and c = 3·4 - 1 - 7 = 4 is a valid proof
+        let vk = [&g1(1)[..], &g2(1), &g2(1), &g2(1), &g1(1), &g1(2)].concat();
+        let proof = [&g1(3)[..], &g2(4), &g1(4)].concat();
+        let mut input = [0u8; 32];
+        input[31] = 3;
+        assert!(api.groth16_verify_bls12_381(&vk, &proof, &input).unwrap());
+        input[31] = 4;
+        assert!(!api.groth16_verify_bls12_381(&vk, &proof, &input).unwrap());
+
+        // errors
+        let res = api.groth16_verify_bls12_381(&vk, &proof, &[]);
+        assert_eq!(res.unwrap_err(), VerificationError::GenericErr);
+        let res = api.groth16_verify_bls12_381(&vk, &proof, &[0xff; 32]);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidScalar);
+    }
+
     #[test]
     fn secp256k1_recover_pubkey_works() {
         let api = MockApi::default();
@@ -1096,6 +2286,30 @@ mod tests {
         assert_eq!(res.unwrap_err(), VerificationError::InvalidPubkeyFormat);
     }
 
+    // Basic "works" test. Exhaustive tests in cosmwasm-crypto
+    #[test]
+    #[cfg(feature = "sr25519")]
+    fn sr25519_verify_works() {
+        let api = MockApi::default();
+
+        let signature = hex::decode(SR25519_SIG_HEX).unwrap();
+        let public_key = hex::decode(SR25519_PUBKEY_HEX).unwrap();
+        assert!(api
+            .sr25519_verify(SR25519_MSG, &signature, &public_key)
+            .unwrap());
+
+        // altered message
+        assert!(!api
+            .sr25519_verify(b"CosmWasm sr25519 test messagf", &signature, &public_key)
+            .unwrap());
+
+        // errors
+        let res = api.sr25519_verify(SR25519_MSG, &signature, &[]);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidPubkeyFormat);
+        let res = api.sr25519_verify(SR25519_MSG, &signature[..63], &public_key);
+        assert_eq!(res.unwrap_err(), VerificationError::InvalidSignatureFormat);
+    }
+
     // Basic "works" test.
     #[test]
     fn ed25519_batch_verify_works() {
@@ -1151,6 +2365,26 @@ mod tests {
         assert_eq!(res.unwrap_err(), VerificationError::InvalidPubkeyFormat);
     }
 
+    // Basic "works" test. Exhaustive tests in cosmwasm-crypto
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn hash_functions_work() {
+        let api = MockApi::default();
+
+        assert_eq!(
+            api.keccak256(b"hello world").unwrap(),
+            hex!("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad")
+        );
+        assert_eq!(
+            api.sha512(b"abc").unwrap(),
+            hex!("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f")
+        );
+        assert_eq!(
+            api.blake2b512(b"abc").unwrap(),
+            hex!("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923")
+        );
+    }
+
     #[cfg(feature = "cosmwasm_1_1")]
     #[test]
     fn bank_querier_supply() {
@@ -1191,6 +2425,153 @@ mod tests {
         assert_eq!(res.amount, coin(0, "ATOM"));
     }
 
+    #[cfg(feature = "cosmwasm_1_3")]
+    #[test]
+    fn bank_querier_send_enabled() {
+        let mut bank = BankQuerier::new(&[]);
+        bank.update_send_enabled("ELF", false);
+        bank.update_send_enabled("FLY", true);
+
+        let elf = bank
+            .query(&BankQuery::SendEnabled {
+                denom: "ELF".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: SendEnabledResponse = from_binary(&elf).unwrap();
+        assert_eq!(res, SendEnabledResponse::new("ELF", false));
+
+        let fly = bank
+            .query(&BankQuery::SendEnabled {
+                denom: "FLY".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: SendEnabledResponse = from_binary(&fly).unwrap();
+        assert_eq!(res, SendEnabledResponse::new("FLY", true));
+
+        // unknown denoms can be sent
+        let atom = bank
+            .query(&BankQuery::SendEnabled {
+                denom: "ATOM".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: SendEnabledResponse = from_binary(&atom).unwrap();
+        assert_eq!(res, SendEnabledResponse::new("ATOM", true));
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    #[test]
+    fn bank_querier_metadata_works() {
+        use crate::{DenomUnit, PageRequest};
+
+        let mut bank = BankQuerier::new(&[]);
+
+        // populate some metadata
+        let metadata: Vec<_> = (0..10)
+            .map(|i| DenomMetadata {
+                base: format!("ufoo{}", i),
+                display: format!("foo{}", i),
+                denom_units: vec![DenomUnit {
+                    denom: format!("ufoo{}", i),
+                    exponent: 0,
+                    aliases: vec![],
+                }],
+                ..Default::default()
+            })
+            .collect();
+        bank.set_denom_metadata(&metadata);
+
+        // query first page
+        let res = bank
+            .query(&BankQuery::AllDenomMetadata {
+                pagination: Some(PageRequest {
+                    key: None,
+                    limit: 3,
+                    reverse: false,
+                }),
+            })
+            .unwrap()
+            .unwrap();
+        let res: AllDenomMetadataResponse = from_binary(&res).unwrap();
+        assert_eq!(res.metadata, metadata[0..3]);
+        assert_eq!(res.next_key, Some(Binary::from(b"ufoo3")));
+
+        // query second page
+        let res = bank
+            .query(&BankQuery::AllDenomMetadata {
+                pagination: Some(PageRequest {
+                    key: res.next_key,
+                    limit: 8,
+                    reverse: false,
+                }),
+            })
+            .unwrap()
+            .unwrap();
+        let res: AllDenomMetadataResponse = from_binary(&res).unwrap();
+        assert_eq!(res.metadata, metadata[3..]);
+        assert_eq!(res.next_key, None);
+
+        // query in reverse
+        let res = bank
+            .query(&BankQuery::AllDenomMetadata {
+                pagination: Some(PageRequest {
+                    key: Some(Binary::from(b"ufoo5")),
+                    limit: 2,
+                    reverse: true,
+                }),
+            })
+            .unwrap()
+            .unwrap();
+        let res: AllDenomMetadataResponse = from_binary(&res).unwrap();
+        assert_eq!(res.metadata, vec![metadata[5].clone(), metadata[4].clone()]);
+        assert_eq!(res.next_key, Some(Binary::from(b"ufoo3")));
+
+        // a limit of 0 returns everything instead of an empty page
+        let res = bank
+            .query(&BankQuery::AllDenomMetadata {
+                pagination: Some(PageRequest {
+                    key: Some(Binary::from(b"ufoo8")),
+                    limit: 0,
+                    reverse: false,
+                }),
+            })
+            .unwrap()
+            .unwrap();
+        let res: AllDenomMetadataResponse = from_binary(&res).unwrap();
+        assert_eq!(res.metadata, metadata[8..]);
+        assert_eq!(res.next_key, None);
+
+        // query all without pagination
+        let res = bank
+            .query(&BankQuery::AllDenomMetadata { pagination: None })
+            .unwrap()
+            .unwrap();
+        let res: AllDenomMetadataResponse = from_binary(&res).unwrap();
+        assert_eq!(res.metadata, metadata);
+        assert_eq!(res.next_key, None);
+
+        // query single denom
+        let res = bank
+            .query(&BankQuery::DenomMetadata {
+                denom: "ufoo7".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DenomMetadataResponse = from_binary(&res).unwrap();
+        assert_eq!(res.metadata, metadata[7]);
+
+        // query unknown denom
+        let err = bank
+            .query(&BankQuery::DenomMetadata {
+                denom: "ubar".to_string(),
+            })
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err, "Denom metadata not found for 'ubar'");
+    }
+
     #[test]
     fn bank_querier_all_balances() {
         let addr = String::from("foobar");
@@ -1295,6 +2676,8 @@ mod tests {
             order: IbcOrder::Ordered,
             version: "ibc".to_string(),
             connection_id: "connection-2".to_string(),
+            #[cfg(feature = "cosmwasm_1_4")]
+            fee_version: None,
         };
         let chan2 = mock_ibc_channel("channel-1", IbcOrder::Ordered, "ibc");
 
@@ -1360,6 +2743,112 @@ mod tests {
         assert_eq!(res.channels, vec![]);
     }
 
+    #[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+    #[test]
+    fn ibc_querier_channels_paginated() {
+        let chan1 = mock_ibc_channel("channel-0", IbcOrder::Ordered, "ibc");
+        let chan2 = mock_ibc_channel("channel-1", IbcOrder::Ordered, "ibc");
+        let chan3 = mock_ibc_channel("channel-2", IbcOrder::Ordered, "ibc");
+
+        // channels are sorted by ID
+        let ibc = IbcQuerier::new("my_port", &[chan3.clone(), chan1.clone(), chan2.clone()]);
+
+        let query = |start_after: Option<&str>, limit: Option<u32>| {
+            let query = &IbcQuery::ListChannelsPaginated {
+                port_id: None,
+                counterparty_port_id: None,
+                start_after: start_after.map(ToString::to_string),
+                limit,
+            };
+            let raw = ibc.query(query).unwrap().unwrap();
+            from_binary::<ListChannelsResponse>(&raw).unwrap().channels
+        };
+
+        assert_eq!(
+            query(None, None),
+            vec![chan1.clone(), chan2.clone(), chan3.clone()]
+        );
+        assert_eq!(query(None, Some(2)), vec![chan1, chan2.clone()]);
+        assert_eq!(query(Some("channel-1"), Some(2)), vec![chan3]);
+        assert_eq!(query(Some("channel-2"), Some(2)), vec![]);
+        assert_eq!(query(Some("channel-0"), Some(0)), vec![]);
+        assert_eq!(query(Some("channel-0"), Some(1)), vec![chan2]);
+    }
+
+    #[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+    #[test]
+    fn ibc_querier_channels_by_counterparty_port() {
+        let chan1 = mock_ibc_channel("channel-0", IbcOrder::Ordered, "ibc");
+        let mut chan2 = mock_ibc_channel("channel-1", IbcOrder::Ordered, "ibc");
+        chan2.counterparty_endpoint.port_id = "transfer".to_string();
+
+        let ibc = IbcQuerier::new("my_port", &[chan1.clone(), chan2.clone()]);
+
+        let query = |counterparty_port_id: &str| {
+            let query = &IbcQuery::ListChannelsPaginated {
+                port_id: None,
+                counterparty_port_id: Some(counterparty_port_id.to_string()),
+                start_after: None,
+                limit: None,
+            };
+            let raw = ibc.query(query).unwrap().unwrap();
+            from_binary::<ListChannelsResponse>(&raw).unwrap().channels
+        };
+
+        assert_eq!(query("their_port"), vec![chan1]);
+        assert_eq!(query("transfer"), vec![chan2]);
+        assert_eq!(query("other"), vec![]);
+    }
+
+    #[cfg(feature = "stargate")]
+    #[test]
+    fn ibc_querier_multiple_ports() {
+        fn list(ibc: &IbcQuerier, port_id: Option<&str>) -> Vec<IbcChannel> {
+            let query = &IbcQuery::ListChannels {
+                port_id: port_id.map(|p| p.to_string()),
+            };
+            let raw = ibc.query(query).unwrap().unwrap();
+            from_binary::<ListChannelsResponse>(&raw).unwrap().channels
+        }
+
+        let chan1 = mock_ibc_channel("channel-0", IbcOrder::Ordered, "ibc");
+        let transfer = IbcChannel::new(
+            IbcEndpoint {
+                port_id: "transfer".to_string(),
+                channel_id: "channel-1".to_string(),
+            },
+            IbcEndpoint {
+                port_id: "transfer".to_string(),
+                channel_id: "channel-141".to_string(),
+            },
+            IbcOrder::Unordered,
+            "ics20-1",
+            "connection-0",
+        );
+
+        let mut ibc = IbcQuerier::new("my_port", &[chan1.clone()]);
+        // the port is set to the given one
+        ibc.update_port(
+            "transfer",
+            &[mock_ibc_channel("channel-1", IbcOrder::Ordered, "ibc")],
+        );
+        ibc.update_port("transfer", &[transfer.clone()]);
+
+        assert_eq!(list(&ibc, None), vec![chan1.clone()]);
+        assert_eq!(list(&ibc, Some("transfer")), vec![transfer.clone()]);
+
+        // channels are replaced by port and channel ID
+        let mut chan1_updated = chan1.clone();
+        chan1_updated.connection_id = "connection-9".to_string();
+        ibc.update_channel(chan1_updated.clone());
+        let mut chan2 = mock_ibc_channel("channel-2", IbcOrder::Ordered, "ibc");
+        chan2.counterparty_endpoint.channel_id = "channel-33".to_string();
+        ibc.update_channel(chan2.clone());
+
+        assert_eq!(list(&ibc, None), vec![chan1_updated, chan2]);
+        assert_eq!(list(&ibc, Some("transfer")), vec![transfer]);
+    }
+
     #[cfg(feature = "stargate")]
     #[test]
     fn ibc_querier_port() {
@@ -1374,6 +2863,105 @@ mod tests {
         assert_eq!(res.port_id, "myport");
     }
 
+    #[cfg(feature = "cosmwasm_1_4")]
+    #[test]
+    fn distribution_querier_delegation_rewards() {
+        let mut distribution = DistributionQuerier::new();
+        let rewards = vec![DecCoin::new(Decimal256::percent(150), "ustake")];
+        distribution.set_rewards("alice", "valoper1", rewards.clone());
+
+        // existing delegation
+        let raw = distribution
+            .query(&DistributionQuery::DelegationRewards {
+                delegator_address: "alice".to_string(),
+                validator_address: "valoper1".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DelegationRewardsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.rewards, rewards);
+
+        // unknown delegation
+        let raw = distribution
+            .query(&DistributionQuery::DelegationRewards {
+                delegator_address: "bob".to_string(),
+                validator_address: "valoper1".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DelegationRewardsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.rewards, vec![]);
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    #[test]
+    fn distribution_querier_delegation_total_rewards_and_validators() {
+        let mut distribution = DistributionQuerier::new();
+        distribution.set_rewards(
+            "alice",
+            "valoper2",
+            vec![
+                DecCoin::new(Decimal256::percent(50), "ustake"),
+                DecCoin::new(Decimal256::percent(10), "uatom"),
+            ],
+        );
+        distribution.set_rewards(
+            "alice",
+            "valoper1",
+            vec![DecCoin::new(Decimal256::percent(150), "ustake")],
+        );
+        distribution.set_rewards("bob", "valoper3", vec![]);
+
+        let raw = distribution
+            .query(&DistributionQuery::DelegationTotalRewards {
+                delegator_address: "alice".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DelegationTotalRewardsResponse = from_binary(&raw).unwrap();
+        assert_eq!(
+            res.rewards,
+            vec![
+                DelegatorReward::new(
+                    "valoper1",
+                    vec![DecCoin::new(Decimal256::percent(150), "ustake")]
+                ),
+                DelegatorReward::new(
+                    "valoper2",
+                    vec![
+                        DecCoin::new(Decimal256::percent(50), "ustake"),
+                        DecCoin::new(Decimal256::percent(10), "uatom"),
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(
+            res.total,
+            vec![
+                DecCoin::new(Decimal256::percent(10), "uatom"),
+                DecCoin::new(Decimal256::percent(200), "ustake"),
+            ]
+        );
+
+        let raw = distribution
+            .query(&DistributionQuery::DelegatorValidators {
+                delegator_address: "alice".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DelegatorValidatorsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.validators, vec!["valoper1", "valoper2"]);
+
+        let raw = distribution
+            .query(&DistributionQuery::DelegatorValidators {
+                delegator_address: "carl".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DelegatorValidatorsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.validators, Vec::<String>::new());
+    }
+
     #[cfg(feature = "staking")]
     #[test]
     fn staking_querier_all_validators() {
@@ -1484,6 +3072,57 @@ mod tests {
         dels.delegation
     }
 
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    #[test]
+    fn staking_querier_unbonding_delegations() {
+        use crate::{QuerierWrapper, Timestamp};
+
+        let entry1 = UnbondingDelegationEntry {
+            creation_height: 100,
+            completion_time: Timestamp::from_seconds(2000),
+            initial_balance: coin(100, "ustake"),
+            balance: coin(90, "ustake"),
+        };
+        let entry2 = UnbondingDelegationEntry {
+            creation_height: 50,
+            completion_time: Timestamp::from_seconds(1000),
+            initial_balance: coin(30, "ustake"),
+            balance: coin(30, "ustake"),
+        };
+
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_unbonding_delegations(
+            "investor",
+            "validator-one",
+            &[entry1.clone(), entry2.clone()],
+        );
+        // updating staking keeps the unbonding delegations
+        querier.update_staking("ustake", &[], &[]);
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        // sorted by completion time
+        let entries = wrapper
+            .query_unbonding_delegations("investor", "validator-one")
+            .unwrap();
+        assert_eq!(entries, vec![entry2, entry1]);
+
+        // no unbondings
+        let entries = wrapper
+            .query_unbonding_delegations("investor", "validator-two")
+            .unwrap();
+        assert_eq!(entries, vec![]);
+        let raw = querier
+            .staking
+            .query(&StakingQuery::UnbondingDelegations {
+                delegator: "hodler".to_string(),
+                validator: "validator-one".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: UnbondingDelegationsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.entries, vec![]);
+    }
+
     #[cfg(feature = "staking")]
     #[test]
     fn staking_querier_delegations() {
@@ -1569,6 +3208,131 @@ mod tests {
         assert_eq!(dels, Some(del2c));
     }
 
+    #[cfg(feature = "staking")]
+    #[test]
+    fn staking_querier_update_validator_and_delegations() {
+        let val1 = Validator {
+            address: String::from("validator-one"),
+            commission: Decimal::percent(1),
+            max_commission: Decimal::percent(3),
+            max_change_rate: Decimal::percent(1),
+        };
+        let del1 = FullDelegation {
+            delegator: Addr::unchecked("investor"),
+            validator: val1.address.clone(),
+            amount: coin(100, "ustake"),
+            can_redelegate: coin(100, "ustake"),
+            accumulated_rewards: coins(5, "ustake"),
+        };
+        let mut staking = StakingQuerier::new("ustake", &[], &[]);
+
+        // validators are added and replaced by address
+        staking.update_validator(val1.clone());
+        let val1_updated = Validator {
+            commission: Decimal::percent(2),
+            ..val1
+        };
+        staking.update_validator(val1_updated.clone());
+        let raw = staking
+            .query(&StakingQuery::AllValidators {})
+            .unwrap()
+            .unwrap();
+        let res: AllValidatorsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.validators, vec![val1_updated]);
+
+        // delegations are added and replaced by delegator and validator
+        staking.update_delegation(del1.clone());
+        let del1_updated = FullDelegation {
+            amount: coin(150, "ustake"),
+            ..del1
+        };
+        staking.update_delegation(del1_updated.clone());
+        assert_eq!(
+            get_all_delegators(&staking, "investor"),
+            vec![del1_updated.clone().into()]
+        );
+
+        assert_eq!(staking.remove_delegation("investor", "validator-two"), None);
+        assert_eq!(
+            staking.remove_delegation("investor", "validator-one"),
+            Some(del1_updated)
+        );
+        assert_eq!(get_delegator(&staking, "investor", "validator-one"), None);
+    }
+
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    #[test]
+    fn mock_querier_syncs_delegation_rewards() {
+        let delegation = FullDelegation {
+            delegator: Addr::unchecked("investor"),
+            validator: String::from("validator-one"),
+            amount: coin(100, "ustake"),
+            can_redelegate: coin(100, "ustake"),
+            accumulated_rewards: vec![],
+        };
+        let rewards = vec![
+            DecCoin::new(Decimal256::percent(250), "ustake"),
+            DecCoin::new(Decimal256::percent(50), "uatom"),
+        ];
+
+        // rewards set after the delegation
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_delegation(delegation.clone());
+        querier.update_delegation_rewards("investor", "validator-one", rewards.clone());
+        let res = get_delegator(&querier.staking, "investor", "validator-one").unwrap();
+        assert_eq!(
+            res.accumulated_rewards,
+            vec![coin(2, "ustake"), coin(0, "uatom")]
+        );
+
+        // rewards set before the delegation
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_delegation_rewards("investor", "validator-one", rewards.clone());
+        querier.update_delegation(delegation);
+        let res = get_delegator(&querier.staking, "investor", "validator-one").unwrap();
+        assert_eq!(
+            res.accumulated_rewards,
+            vec![coin(2, "ustake"), coin(0, "uatom")]
+        );
+
+        // the distribution module returns the exact rewards
+        let raw = querier
+            .distribution
+            .query(&DistributionQuery::DelegationRewards {
+                delegator_address: "investor".to_string(),
+                validator_address: "validator-one".to_string(),
+            })
+            .unwrap()
+            .unwrap();
+        let res: DelegationRewardsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.rewards, rewards);
+    }
+
+    #[cfg(feature = "stargate")]
+    #[test]
+    fn mock_querier_stargate_handler_works() {
+        let querier: MockQuerier = MockQuerier::new(&[])
+            .with_grpc_handler("/cosmos.staking.v1beta1.Query/Params", |_: &Binary| {
+                SystemResult::Ok(ContractResult::Ok(Binary::from(b"\x0a\x00")))
+            });
+
+        let request: QueryRequest<Empty> = QueryRequest::Stargate {
+            path: "/cosmos.staking.v1beta1.Query/Params".to_string(),
+            data: Binary::default(),
+        };
+        let res = querier.handle_query(&request).unwrap().unwrap();
+        assert_eq!(res.as_slice(), b"\x0a\x00");
+
+        let request: QueryRequest<Empty> = QueryRequest::Stargate {
+            path: "/cosmos.staking.v1beta1.Query/Pool".to_string(),
+            data: Binary::default(),
+        };
+        match querier.handle_query(&request).unwrap_err() {
+            SystemError::UnsupportedRequest { kind } => assert_eq!(kind, "Stargate"),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
     #[test]
     fn wasm_querier_works() {
         let mut querier = WasmQuerier::default();
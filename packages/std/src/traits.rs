@@ -4,12 +4,22 @@ use std::ops::Deref;
 
 use crate::addresses::{Addr, CanonicalAddr};
 use crate::binary::Binary;
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::bls12_381::{HashFunction, BLS12_381_G1_POINT_LEN, BLS12_381_G2_POINT_LEN};
 use crate::coin::Coin;
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::dec_coin::DecCoin;
 use crate::errors::{RecoverPubkeyError, StdError, StdResult, VerificationError};
 #[cfg(feature = "iterator")]
 use crate::iterator::{Order, Record};
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::metadata::DenomMetadata;
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::pagination::PageRequest;
 #[cfg(feature = "cosmwasm_1_2")]
 use crate::query::CodeInfoResponse;
+#[cfg(feature = "cosmwasm_1_3")]
+use crate::query::SendEnabledResponse;
 #[cfg(feature = "cosmwasm_1_1")]
 use crate::query::SupplyResponse;
 use crate::query::{
@@ -20,8 +30,17 @@ use crate::query::{
     AllDelegationsResponse, AllValidatorsResponse, BondedDenomResponse, Delegation,
     DelegationResponse, FullDelegation, StakingQuery, Validator, ValidatorResponse,
 };
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::query::{
+    AllDenomMetadataResponse, DelegationRewardsResponse, DelegationTotalRewardsResponse,
+    DelegatorValidatorsResponse, DenomMetadataResponse, DistributionQuery, GrpcQuery,
+};
+#[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+use crate::query::{UnbondingDelegationEntry, UnbondingDelegationsResponse};
 use crate::results::{ContractResult, Empty, SystemResult};
 use crate::serde::{from_binary, to_binary, to_vec};
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::types::ContractSelfInfo;
 use crate::ContractInfoResponse;
 
 /// Storage provides read and write access to a persistent storage.
@@ -34,6 +53,14 @@ pub trait Storage {
     /// is not great yet and might not be possible in all backends. But we're trying to get there.
     fn get(&self, key: &[u8]) -> Option<Vec<u8>>;
 
+    /// Reads multiple keys at once. The result contains one entry per key, in the same order.
+    ///
+    /// The default implementation calls [`Storage::get`] for every key.
+    /// Storage implementations can override this with a more efficient version.
+    fn get_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        keys.iter().map(|key| self.get(key)).collect()
+    }
+
     #[cfg(feature = "iterator")]
     /// Allows iteration over a set of key/value pairs, either forwards or backwards.
     ///
@@ -47,6 +74,24 @@ pub trait Storage {
         order: Order,
     ) -> Box<dyn Iterator<Item = Record> + 'a>;
 
+    #[cfg(feature = "iterator")]
+    /// Returns up to `limit` records from `start` (inclusive) to `end` (exclusive) in the given order.
+    ///
+    /// The bounds are interpreted in the same way as in [`Storage::range`]. This is useful for
+    /// pagination since the whole page can be loaded at once instead of record by record.
+    ///
+    /// The default implementation takes the records from [`Storage::range`].
+    /// Storage implementations can override this with a more efficient version.
+    fn range_bounded(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+        limit: usize,
+    ) -> Vec<Record> {
+        self.range(start, end, order).take(limit).collect()
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]);
 
     /// Removes a database entry at `key`.
@@ -54,6 +99,25 @@ pub trait Storage {
     /// The current interface does not allow to differentiate between a key that existed
     /// before and one that didn't exist. See https://github.com/CosmWasm/cosmwasm/issues/290
     fn remove(&mut self, key: &[u8]);
+
+    #[cfg(feature = "iterator")]
+    /// Removes all database entries from `start` (inclusive) to `end` (exclusive).
+    ///
+    /// The bounds are interpreted in the same way as in [`Storage::range`], i.e. `None`
+    /// means unbounded. This allows clearing a whole map or prefix without loading all
+    /// keys into the contract first.
+    ///
+    /// The default implementation collects the keys of the range and removes them one by one.
+    /// Storage implementations can override this with a more efficient version.
+    fn remove_range(&mut self, start: Option<&[u8]>, end: Option<&[u8]>) {
+        let keys: Vec<Vec<u8>> = self
+            .range(start, end, Order::Ascending)
+            .map(|(key, _)| key)
+            .collect();
+        for key in keys {
+            self.remove(&key);
+        }
+    }
 }
 
 /// Api are callbacks to system functions implemented outside of the wasm modules.
@@ -110,6 +174,39 @@ pub trait Api {
     /// [`addr_canonicalize`]: Api::addr_canonicalize
     fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr>;
 
+    /// Encodes `data` as a [bech32] string with the given human readable prefix.
+    ///
+    /// In contrast to [`Api::addr_humanize`], which is bound to the address format of the
+    /// host chain, this works for any prefix. This allows deriving addresses on other chains,
+    /// e.g. for ICS-20 receivers. The bech32m variant is not supported.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    ///
+    /// [bech32]: https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#bech32
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_encode(&self, _prefix: &str, _data: &[u8]) -> StdResult<String> {
+        Err(StdError::generic_err(
+            "Api::bech32_encode not supported by this implementation",
+        ))
+    }
+
+    /// Decodes a [bech32] string into its human readable prefix and data.
+    ///
+    /// This is the inverse of [`Api::bech32_encode`] and works for addresses of any chain.
+    /// The bech32m variant is not supported.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    ///
+    /// [bech32]: https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#bech32
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bech32_decode(&self, _address: &str) -> StdResult<(String, Vec<u8>)> {
+        Err(StdError::generic_err(
+            "Api::bech32_decode not supported by this implementation",
+        ))
+    }
+
     fn secp256k1_verify(
         &self,
         message_hash: &[u8],
@@ -124,6 +221,211 @@ pub trait Api {
         recovery_param: u8,
     ) -> Result<Vec<u8>, RecoverPubkeyError>;
 
+    /// Verifies multiple secp256k1 signatures in one call, which is cheaper in gas
+    /// than calling [`Api::secp256k1_verify`] for each of them.
+    ///
+    /// The same input variants as for [`Api::ed25519_batch_verify`] are supported: either
+    /// one message hash, signature and public key per item, a single message hash
+    /// signed by multiple keys or a single public key signing multiple message hashes.
+    /// Returns `Ok(false)` if any of the signatures is invalid.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_batch_verify(
+        &self,
+        _message_hashes: &[&[u8]],
+        _signatures: &[&[u8]],
+        _public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Adds the two secp256k1 points `p` and `q`. The inputs can be compressed or
+    /// uncompressed public keys; the sum is returned in compressed form (33 bytes).
+    /// A sum that is the point at infinity is an error.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_add(&self, _p: &[u8], _q: &[u8]) -> Result<[u8; 33], VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Multiplies the secp256k1 point `point` with `scalar`, a 32 byte big-endian integer
+    /// smaller than the group order. The product is returned in compressed form (33 bytes).
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256k1_point_mul(
+        &self,
+        _point: &[u8],
+        _scalar: &[u8],
+    ) -> Result<[u8; 33], VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Verifies a message hash against a signature using the secp256r1 (P-256) curve,
+    /// e.g. for signatures created by WebAuthn authenticators.
+    ///
+    /// Signature and public key use the same encodings as in [`Api::secp256k1_verify`].
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_verify(
+        &self,
+        _message_hash: &[u8],
+        _signature: &[u8],
+        _public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Recovers a secp256r1 (P-256) public key from a message hash and a signature.
+    /// The recovered public key is returned in uncompressed form.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn secp256r1_recover_pubkey(
+        &self,
+        _message_hash: &[u8],
+        _signature: &[u8],
+        _recovery_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        Err(RecoverPubkeyError::unknown_err(0))
+    }
+
+    /// Adds up a list of compressed BLS12-381 G1 points, concatenated into a single slice.
+    /// This is used to aggregate BLS signatures or public keys. An empty input results
+    /// in the identity element.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_aggregate_g1(
+        &self,
+        _g1s: &[u8],
+    ) -> Result<[u8; BLS12_381_G1_POINT_LEN], VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Adds up a list of compressed BLS12-381 G2 points, concatenated into a single slice.
+    /// This is used to aggregate BLS signatures or public keys. An empty input results
+    /// in the identity element.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_aggregate_g2(
+        &self,
+        _g2s: &[u8],
+    ) -> Result<[u8; BLS12_381_G2_POINT_LEN], VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Checks if `e(p_1, q_1) * … * e(p_n, q_n) == e(r, s)` for the BLS12-381 pairing `e`.
+    ///
+    /// `ps` are compressed G1 points and `qs` are compressed G2 points, each concatenated
+    /// into a single slice. `r` is a compressed G1 point and `s` a compressed G2 point.
+    ///
+    /// ## Examples
+    ///
+    /// Verifying a BLS signature in G1 with the public key in G2:
+    ///
+    /// ```
+    /// # use cosmwasm_std::{Api, HashFunction, BLS12_381_G2_GENERATOR};
+    /// fn verify(api: &dyn Api, msg: &[u8], signature: &[u8], pubkey: &[u8]) -> bool {
+    ///     let dst = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+    ///     let msg_point = api
+    ///         .bls12_381_hash_to_g1(HashFunction::Sha256, msg, dst)
+    ///         .unwrap();
+    ///     api.bls12_381_pairing_equality(signature, &BLS12_381_G2_GENERATOR, &msg_point, pubkey)
+    ///         .unwrap()
+    /// }
+    /// ```
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_pairing_equality(
+        &self,
+        _ps: &[u8],
+        _qs: &[u8],
+        _r: &[u8],
+        _s: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Hashes a message to a compressed BLS12-381 G1 point according to
+    /// [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380) using the domain separation tag `dst`.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_hash_to_g1(
+        &self,
+        _hash_function: HashFunction,
+        _msg: &[u8],
+        _dst: &[u8],
+    ) -> Result<[u8; BLS12_381_G1_POINT_LEN], VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Hashes a message to a compressed BLS12-381 G2 point according to
+    /// [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380) using the domain separation tag `dst`.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn bls12_381_hash_to_g2(
+        &self,
+        _hash_function: HashFunction,
+        _msg: &[u8],
+        _dst: &[u8],
+    ) -> Result<[u8; BLS12_381_G2_POINT_LEN], VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Verifies a Groth16 zk-SNARK proof over the BN254 (alt_bn128) curve.
+    ///
+    /// Points use the uncompressed encoding of the Ethereum precompiles (EIP-197).
+    /// `verifying_key` is `alpha_g1 ‖ beta_g2 ‖ gamma_g2 ‖ delta_g2 ‖ ic_0 ‖ … ‖ ic_n`,
+    /// `proof` is `a_g1 ‖ b_g2 ‖ c_g1` and `public_inputs` are `n` concatenated
+    /// 32 byte big-endian scalars.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bn254(
+        &self,
+        _verifying_key: &[u8],
+        _proof: &[u8],
+        _public_inputs: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Verifies a Groth16 zk-SNARK proof over the BLS12-381 curve.
+    ///
+    /// The layout is the same as for [`Api::groth16_verify_bn254`], but points are
+    /// compressed BLS12-381 points.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "zk")]
+    fn groth16_verify_bls12_381(
+        &self,
+        _verifying_key: &[u8],
+        _proof: &[u8],
+        _public_inputs: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
     fn ed25519_verify(
         &self,
         message: &[u8],
@@ -138,9 +440,76 @@ pub trait Api {
         public_keys: &[&[u8]],
     ) -> Result<bool, VerificationError>;
 
+    /// Verifies a message against a Schnorrkel sr25519 signature with the public key
+    /// of the signer, as used by Substrate based chains such as Polkadot.
+    ///
+    /// The signature (64 bytes) must have been created with the `substrate` signing context
+    /// and the public key is a compressed Ristretto point (32 bytes).
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "sr25519")]
+    fn sr25519_verify(
+        &self,
+        _message: &[u8],
+        _signature: &[u8],
+        _public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        Err(VerificationError::GenericErr)
+    }
+
+    /// Computes the Keccak-256 hash of `data` on the host, e.g. for Ethereum address derivation.
+    ///
+    /// This is cheaper in gas and code size than hashing inside of the contract.
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn keccak256(&self, _data: &[u8]) -> StdResult<[u8; 32]> {
+        Err(StdError::generic_err(
+            "Api::keccak256 not supported by this implementation",
+        ))
+    }
+
+    /// Computes the SHA-512 hash of `data` on the host.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn sha512(&self, _data: &[u8]) -> StdResult<[u8; 64]> {
+        Err(StdError::generic_err(
+            "Api::sha512 not supported by this implementation",
+        ))
+    }
+
+    /// Computes the BLAKE2b hash of `data` with an output size of 64 bytes on the host.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn blake2b512(&self, _data: &[u8]) -> StdResult<[u8; 64]> {
+        Err(StdError::generic_err(
+            "Api::blake2b512 not supported by this implementation",
+        ))
+    }
+
     /// Emits a debugging message that is handled depending on the environment (typically printed to console or ignored).
     /// Those messages are not persisted to chain.
     fn debug(&self, message: &str);
+
+    /// Returns the address, code ID and checksum of the currently executing contract.
+    ///
+    /// This is provided by the host directly and is much cheaper than a
+    /// [`WasmQuery::ContractInfo`] query on the contract's own address, e.g. for
+    /// authorization checks.
+    ///
+    /// The default implementation returns an error for implementors that do
+    /// not support this.
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn contract_self_info(&self) -> StdResult<ContractSelfInfo> {
+        Err(StdError::generic_err(
+            "Api::contract_self_info not supported by this implementation",
+        ))
+    }
 }
 
 /// A short-hand alias for the two-level query result (1. accessing the contract, 2. executing query in the contract)
@@ -191,6 +560,13 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
     /// one level. Only use this if you don't need to check the SystemError
     /// eg. If you don't differentiate between contract missing and contract returned error
     pub fn query<U: DeserializeOwned>(&self, request: &QueryRequest<C>) -> StdResult<U> {
+        self.query_raw(request).and_then(|raw| from_binary(&raw))
+    }
+
+    /// Internal helper to avoid code duplication.
+    /// Performs a query and returns the binary result without deserializing it,
+    /// wrapping any errors that may occur into `StdError`.
+    fn query_raw(&self, request: &QueryRequest<C>) -> StdResult<Binary> {
         let raw = to_vec(request).map_err(|serialize_err| {
             StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
         })?;
@@ -202,10 +578,22 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
             SystemResult::Ok(ContractResult::Err(contract_err)) => Err(StdError::generic_err(
                 format!("Querier contract error: {}", contract_err),
             )),
-            SystemResult::Ok(ContractResult::Ok(value)) => from_binary(&value),
+            SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
         }
     }
 
+    /// Queries an SDK gRPC endpoint, e.g. `/cosmos.bank.v1beta1.Query/DenomOwners`.
+    ///
+    /// `data` is the protobuf encoded request and the response is returned as protobuf
+    /// encoded bytes. The caller is responsible for encoding and decoding.
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn query_grpc(&self, path: impl Into<String>, data: Binary) -> StdResult<Binary> {
+        self.query_raw(&QueryRequest::Grpc(GrpcQuery {
+            path: path.into(),
+            data,
+        }))
+    }
+
     #[cfg(feature = "cosmwasm_1_1")]
     pub fn query_supply(&self, denom: impl Into<String>) -> StdResult<Coin> {
         let request = BankQuery::Supply {
@@ -216,6 +604,41 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         Ok(res.amount)
     }
 
+    /// Queries whether the given denom can be sent. Unknown denoms can be sent.
+    #[cfg(feature = "cosmwasm_1_3")]
+    pub fn query_send_enabled(&self, denom: impl Into<String>) -> StdResult<bool> {
+        let request = BankQuery::SendEnabled {
+            denom: denom.into(),
+        }
+        .into();
+        let res: SendEnabledResponse = self.query(&request)?;
+        Ok(res.enabled)
+    }
+
+    /// Queries the metadata of the given denom
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn query_denom_metadata(&self, denom: impl Into<String>) -> StdResult<DenomMetadata> {
+        let request = BankQuery::DenomMetadata {
+            denom: denom.into(),
+        }
+        .into();
+        let res: DenomMetadataResponse = self.query(&request)?;
+        Ok(res.metadata)
+    }
+
+    /// Queries one page of the metadata of all denoms that have metadata
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn query_all_denom_metadata(
+        &self,
+        pagination: PageRequest,
+    ) -> StdResult<AllDenomMetadataResponse> {
+        let request = BankQuery::AllDenomMetadata {
+            pagination: Some(pagination),
+        }
+        .into();
+        self.query(&request)
+    }
+
     pub fn query_balance(
         &self,
         address: impl Into<String>,
@@ -313,6 +736,49 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         self.query(&request)
     }
 
+    /// Returns the rewards accrued by the delegation of `delegator` to `validator`.
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn query_delegation_rewards(
+        &self,
+        delegator: impl Into<String>,
+        validator: impl Into<String>,
+    ) -> StdResult<Vec<DecCoin>> {
+        let request = DistributionQuery::DelegationRewards {
+            delegator_address: delegator.into(),
+            validator_address: validator.into(),
+        }
+        .into();
+        let res: DelegationRewardsResponse = self.query(&request)?;
+        Ok(res.rewards)
+    }
+
+    /// Returns the rewards accrued by all delegations of `delegator`.
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn query_delegation_total_rewards(
+        &self,
+        delegator: impl Into<String>,
+    ) -> StdResult<DelegationTotalRewardsResponse> {
+        let request = DistributionQuery::DelegationTotalRewards {
+            delegator_address: delegator.into(),
+        }
+        .into();
+        self.query(&request)
+    }
+
+    /// Returns the addresses of all validators `delegator` has delegated to.
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn query_delegator_validators(
+        &self,
+        delegator: impl Into<String>,
+    ) -> StdResult<Vec<String>> {
+        let request = DistributionQuery::DelegatorValidators {
+            delegator_address: delegator.into(),
+        }
+        .into();
+        let res: DelegatorValidatorsResponse = self.query(&request)?;
+        Ok(res.validators)
+    }
+
     #[cfg(feature = "staking")]
     pub fn query_all_validators(&self) -> StdResult<Vec<Validator>> {
         let request = StakingQuery::AllValidators {}.into();
@@ -364,6 +830,23 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         let res: DelegationResponse = self.query(&request)?;
         Ok(res.delegation)
     }
+
+    /// Returns the pending unbondings of the delegation of `delegator` to `validator`,
+    /// sorted by completion time.
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    pub fn query_unbonding_delegations(
+        &self,
+        delegator: impl Into<String>,
+        validator: impl Into<String>,
+    ) -> StdResult<Vec<UnbondingDelegationEntry>> {
+        let request = StakingQuery::UnbondingDelegations {
+            delegator: delegator.into(),
+            validator: validator.into(),
+        }
+        .into();
+        let res: UnbondingDelegationsResponse = self.query(&request)?;
+        Ok(res.entries)
+    }
 }
 
 #[cfg(test)]
@@ -431,6 +914,106 @@ mod tests {
         assert_eq!(all_balances, vec![coin(123, "ELF"), coin(777, "FLY")]);
     }
 
+    #[cfg(feature = "cosmwasm_1_3")]
+    #[test]
+    fn query_send_enabled_works() {
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.update_send_enabled("ELF", false);
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        assert!(!wrapper.query_send_enabled("ELF").unwrap());
+        assert!(wrapper.query_send_enabled("FLY").unwrap());
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    #[test]
+    fn denom_metadata_query_helpers_work() {
+        use crate::{DenomMetadata, PageRequest};
+
+        let metadata = DenomMetadata {
+            base: "uatom".to_string(),
+            display: "atom".to_string(),
+            ..Default::default()
+        };
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.set_denom_metadata(std::slice::from_ref(&metadata));
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        assert_eq!(wrapper.query_denom_metadata("uatom").unwrap(), metadata);
+        wrapper.query_denom_metadata("ufoo").unwrap_err();
+
+        let res = wrapper
+            .query_all_denom_metadata(PageRequest {
+                key: None,
+                limit: 10,
+                reverse: false,
+            })
+            .unwrap();
+        assert_eq!(res.metadata, vec![metadata]);
+        assert_eq!(res.next_key, None);
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    #[test]
+    fn distribution_query_helpers_work() {
+        use crate::Decimal256;
+
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        let rewards = vec![DecCoin::new(Decimal256::percent(150), "ustake")];
+        querier.update_delegation_rewards("alice", "valoper1", rewards.clone());
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        let res = wrapper
+            .query_delegation_rewards("alice", "valoper1")
+            .unwrap();
+        assert_eq!(res, rewards);
+
+        let res = wrapper.query_delegation_total_rewards("alice").unwrap();
+        assert_eq!(res.rewards.len(), 1);
+        assert_eq!(res.total, rewards);
+
+        let res = wrapper.query_delegator_validators("alice").unwrap();
+        assert_eq!(res, vec!["valoper1"]);
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    #[test]
+    fn query_grpc_works() {
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        let err = wrapper
+            .query_grpc(
+                "/cosmos.bank.v1beta1.Query/DenomOwners",
+                Binary::from(b"\x0a\x03foo"),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported query type: GRPC"));
+
+        // with a handler for the path
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[]).with_grpc_handler(
+            "/cosmos.bank.v1beta1.Query/DenomOwners",
+            |data: &Binary| {
+                assert_eq!(data.as_slice(), b"\x0a\x03foo");
+                SystemResult::Ok(ContractResult::Ok(Binary::from(b"\x12\x00")))
+            },
+        );
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let res = wrapper
+            .query_grpc(
+                "/cosmos.bank.v1beta1.Query/DenomOwners",
+                Binary::from(b"\x0a\x03foo"),
+            )
+            .unwrap();
+        assert_eq!(res.as_slice(), b"\x12\x00");
+
+        // other paths are still unsupported
+        let err = wrapper
+            .query_grpc("/cosmos.bank.v1beta1.Query/Balance", Binary::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported query type: GRPC"));
+    }
+
     #[test]
     fn contract_info() {
         const ACCT: &str = "foobar";
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::addresses::Addr;
 use crate::coin::Coin;
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::hex_binary::HexBinary;
 use crate::timestamp::Timestamp;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -109,3 +111,42 @@ pub struct MessageInfo {
 pub struct ContractInfo {
     pub address: Addr,
 }
+
+/// Additional information from `MsgMigrateContract`, which is passed into `migrate` entry points
+/// taking four arguments:
+///
+/// ```
+/// # use cosmwasm_std::{DepsMut, Env, MigrateInfo, Response, StdResult};
+/// # type MigrateMsg = ();
+/// // #[entry_point]
+/// pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg, info: MigrateInfo) -> StdResult<Response> {
+///     match info.old_migrate_version {
+///         Some(1) => { /* migrate state from version 1 */ }
+///         _ => { /* nothing to do */ }
+///     }
+///     Ok(Response::new())
+/// }
+/// ```
+#[cfg(feature = "cosmwasm_1_5")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MigrateInfo {
+    /// The address that sent the `MsgMigrateContract`, i.e. the admin of the contract
+    pub sender: Addr,
+    /// The migrate version of the code the contract is migrated from, as stored by the host.
+    /// This is `None` if the old code does not specify a version.
+    pub old_migrate_version: Option<u64>,
+}
+
+/// Information about the currently executing contract that the host provides
+/// directly, without going through the querier.
+///
+/// See [`Api::contract_self_info`](crate::Api::contract_self_info).
+#[cfg(feature = "cosmwasm_1_4")]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ContractSelfInfo {
+    pub address: Addr,
+    /// The code ID the contract is currently instantiated from
+    pub code_id: u64,
+    /// The checksum of the Wasm code the contract is currently instantiated from
+    pub checksum: HexBinary,
+}
@@ -0,0 +1,74 @@
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+use crate::errors::StdError;
+use crate::errors::StdResult;
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+use crate::ibc::IbcMsg;
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+use crate::results::CosmosMsg;
+use crate::results::SubMsg;
+
+/// The maximum length (in bytes) of the memo of an ICS-20 transfer accepted by ibc-go
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+const MAX_IBC_TRANSFER_MEMO_LENGTH: usize = 32768;
+
+/// Ensures the messages emitted by a contract can be dispatched by the chain.
+/// This turns errors that would otherwise only surface when the message is relayed
+/// into an error of the contract call.
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub fn validate_messages<T>(messages: &[SubMsg<T>]) -> StdResult<()> {
+    for sub_msg in messages {
+        if let CosmosMsg::Ibc(IbcMsg::Transfer {
+            memo: Some(memo), ..
+        }) = &sub_msg.msg
+        {
+            if memo.len() > MAX_IBC_TRANSFER_MEMO_LENGTH {
+                return Err(StdError::generic_err(format!(
+                    "IBC transfer memo of {} bytes exceeds the maximum of {} bytes",
+                    memo.len(),
+                    MAX_IBC_TRANSFER_MEMO_LENGTH
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(all(feature = "stargate", feature = "cosmwasm_1_4")))]
+#[allow(dead_code)] // only used by the exports of the Wasm build
+pub fn validate_messages<T>(_messages: &[SubMsg<T>]) -> StdResult<()> {
+    Ok(())
+}
+
+#[cfg(all(test, feature = "stargate", feature = "cosmwasm_1_4"))]
+mod tests {
+    use super::*;
+    use crate::{coin, Empty, IbcTimeout, Timestamp};
+
+    #[test]
+    fn validate_messages_checks_transfer_memo_length() {
+        let transfer = |memo: Option<String>| {
+            SubMsg::<Empty>::new(IbcMsg::Transfer {
+                channel_id: "channel-123".to_string(),
+                to_address: "receiver".to_string(),
+                amount: coin(123, "ucosm"),
+                timeout: IbcTimeout::with_timestamp(Timestamp::from_seconds(42)),
+                memo,
+            })
+        };
+
+        validate_messages(&[transfer(None)]).unwrap();
+        validate_messages(&[transfer(Some("a".repeat(MAX_IBC_TRANSFER_MEMO_LENGTH)))]).unwrap();
+
+        let messages = [
+            transfer(None),
+            transfer(Some("a".repeat(MAX_IBC_TRANSFER_MEMO_LENGTH + 1))),
+        ];
+        match validate_messages(&messages).unwrap_err() {
+            StdError::GenericErr { msg, .. } => assert_eq!(
+                msg,
+                "IBC transfer memo of 32769 bytes exceeds the maximum of 32768 bytes"
+            ),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+}
@@ -11,8 +11,9 @@ use cosmwasm_vm::testing::{
     mock_backend, mock_env, mock_info, mock_instance_options, MockApi, MockQuerier, MockStorage,
 };
 use cosmwasm_vm::{
-    call_execute, call_instantiate, capabilities_from_csv, Cache, CacheOptions, Checksum, Instance,
-    InstanceOptions, Size,
+    call_execute, call_instantiate, capabilities_from_csv, Cache, CacheOptions, Checksum,
+    GasLimits, GasSchedule, Instance, InstanceOptions, MemoryCachePolicy, Size, WasmLimits,
+    DEFAULT_MAX_QUERY_DEPTH,
 };
 
 // Instance
@@ -21,6 +22,11 @@ const DEFAULT_GAS_LIMIT: u64 = 1_000_000_000_000; // ~1ms
 const DEFAULT_INSTANCE_OPTIONS: InstanceOptions = InstanceOptions {
     gas_limit: DEFAULT_GAS_LIMIT,
     print_debug: false,
+    debug_handler: None,
+    readonly_ibc_channel_open: false,
+    gas_schedule: GasSchedule::v1(),
+    max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+    max_execution_time: None,
 };
 const HIGH_GAS_LIMIT: u64 = 20_000_000_000_000_000; // ~20s, allows many calls on one instance
 
@@ -125,7 +131,14 @@ fn bench_cache(c: &mut Criterion) {
         base_dir: TempDir::new().unwrap().into_path(),
         available_capabilities: capabilities_from_csv("iterator,staking"),
         memory_cache_size: MEMORY_CACHE_SIZE,
+        memory_cache_policy: MemoryCachePolicy::default(),
         instance_memory_limit: DEFAULT_MEMORY_LIMIT,
+        default_gas_limits: GasLimits::uniform(DEFAULT_GAS_LIMIT),
+        gas_schedule: GasSchedule::default(),
+        persist_pinned: false,
+        instance_pool_size: 0,
+        wasm_limits: WasmLimits::default(),
+        readonly_ibc_channel_open: false,
     };
 
     group.bench_function("save wasm", |b| {
@@ -165,7 +178,14 @@ fn bench_cache(c: &mut Criterion) {
             base_dir: TempDir::new().unwrap().into_path(),
             available_capabilities: capabilities_from_csv("iterator,staking"),
             memory_cache_size: Size(0),
+            memory_cache_policy: MemoryCachePolicy::default(),
             instance_memory_limit: DEFAULT_MEMORY_LIMIT,
+            default_gas_limits: GasLimits::uniform(DEFAULT_GAS_LIMIT),
+            gas_schedule: GasSchedule::default(),
+            persist_pinned: false,
+            instance_pool_size: 0,
+            wasm_limits: WasmLimits::default(),
+            readonly_ibc_channel_open: false,
         };
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
             unsafe { Cache::new(non_memcache).unwrap() };
@@ -222,6 +242,32 @@ fn bench_cache(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("instantiate from instance pool", |b| {
+        let checksum = Checksum::generate(CONTRACT);
+        let cache: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new(CacheOptions {
+                instance_pool_size: 1,
+                ..options.clone()
+            })
+            .unwrap()
+        };
+        // Put one instance into the pool
+        let instance = cache
+            .get_pooled_instance(&checksum, mock_backend(&[]), DEFAULT_INSTANCE_OPTIONS)
+            .unwrap();
+        cache.return_pooled_instance(&checksum, instance);
+
+        b.iter(|| {
+            let backend = mock_backend(&[]);
+            let instance = cache
+                .get_pooled_instance(&checksum, backend, DEFAULT_INSTANCE_OPTIONS)
+                .unwrap();
+            cache.return_pooled_instance(&checksum, instance);
+            assert_eq!(cache.metrics().elements_instance_pool, 1);
+            assert_eq!(cache.stats().hits_fs_cache, 1);
+        });
+    });
+
     group.finish();
 }
 
@@ -231,7 +277,14 @@ pub fn bench_instance_threads(c: &mut Criterion) {
             base_dir: TempDir::new().unwrap().into_path(),
             available_capabilities: capabilities_from_csv("iterator,staking"),
             memory_cache_size: MEMORY_CACHE_SIZE,
+            memory_cache_policy: MemoryCachePolicy::default(),
             instance_memory_limit: DEFAULT_MEMORY_LIMIT,
+            default_gas_limits: GasLimits::uniform(DEFAULT_GAS_LIMIT),
+            gas_schedule: GasSchedule::default(),
+            persist_pinned: false,
+            instance_pool_size: 0,
+            wasm_limits: WasmLimits::default(),
+            readonly_ibc_channel_open: false,
         };
 
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
@@ -5,8 +5,8 @@ use tempfile::TempDir;
 use cosmwasm_std::{coins, Empty};
 use cosmwasm_vm::testing::{mock_backend, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
 use cosmwasm_vm::{
-    call_execute, call_instantiate, capabilities_from_csv, Cache, CacheOptions, InstanceOptions,
-    Size,
+    call_execute, call_instantiate, capabilities_from_csv, Cache, CacheOptions, GasLimits,
+    GasSchedule, InstanceOptions, MemoryCachePolicy, Size, WasmLimits, DEFAULT_MAX_QUERY_DEPTH,
 };
 
 // Instance
@@ -15,6 +15,11 @@ const DEFAULT_GAS_LIMIT: u64 = 400_000 * 150_000;
 const DEFAULT_INSTANCE_OPTIONS: InstanceOptions = InstanceOptions {
     gas_limit: DEFAULT_GAS_LIMIT,
     print_debug: false,
+    debug_handler: None,
+    readonly_ibc_channel_open: false,
+    gas_schedule: GasSchedule::v1(),
+    max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+    max_execution_time: None,
 };
 // Cache
 const MEMORY_CACHE_SIZE: Size = Size::mebi(200);
@@ -30,7 +35,14 @@ pub fn main() {
         base_dir: TempDir::new().unwrap().into_path(),
         available_capabilities: capabilities_from_csv("iterator,staking"),
         memory_cache_size: MEMORY_CACHE_SIZE,
+        memory_cache_policy: MemoryCachePolicy::default(),
         instance_memory_limit: DEFAULT_MEMORY_LIMIT,
+        default_gas_limits: GasLimits::uniform(DEFAULT_GAS_LIMIT),
+        gas_schedule: GasSchedule::default(),
+        persist_pinned: false,
+        instance_pool_size: 0,
+        wasm_limits: WasmLimits::default(),
+        readonly_ibc_channel_open: false,
     };
 
     let cache: Cache<MockApi, MockStorage, MockQuerier> = unsafe { Cache::new(options).unwrap() };
@@ -0,0 +1,314 @@
+//! Async variants of the backend traits for embedders with asynchronous storage or queriers.
+//!
+//! The VM executes contracts synchronously, so async backends are bridged into
+//! [`Storage`] and [`Querier`] by [`BlockingStorage`] and [`BlockingQuerier`],
+//! which drive the returned futures to completion on the calling thread.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use cosmwasm_std::{Binary, ContractResult, SystemResult};
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, Record};
+
+use crate::backend::{BackendResult, Querier, Storage};
+
+/// The future returned by the methods of [`AsyncStorage`] and [`AsyncQuerier`]
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = BackendResult<T>> + 'a>>;
+
+/// Async access to the VM's backend storage. See [`Storage`] for the semantics of the methods.
+pub trait AsyncStorage {
+    fn get<'a>(&'a self, key: &'a [u8]) -> BackendFuture<'a, Option<Vec<u8>>>;
+
+    #[cfg(feature = "iterator")]
+    fn scan<'a>(
+        &'a mut self,
+        start: Option<&'a [u8]>,
+        end: Option<&'a [u8]>,
+        order: Order,
+    ) -> BackendFuture<'a, u32>;
+
+    #[cfg(feature = "iterator")]
+    fn next(&mut self, iterator_id: u32) -> BackendFuture<'_, Option<Record>>;
+
+    fn set<'a>(&'a mut self, key: &'a [u8], value: &'a [u8]) -> BackendFuture<'a, ()>;
+
+    fn remove<'a>(&'a mut self, key: &'a [u8]) -> BackendFuture<'a, ()>;
+}
+
+/// Async variant of [`Querier`]
+pub trait AsyncQuerier {
+    fn query_raw<'a>(
+        &'a self,
+        request: &'a [u8],
+        gas_limit: u64,
+    ) -> BackendFuture<'a, SystemResult<ContractResult<Binary>>>;
+}
+
+/// A [`Storage`] that forwards all calls to an [`AsyncStorage`] and blocks until they complete.
+///
+/// The futures are polled on the thread executing the contract, so they must not rely on
+/// a runtime context of that thread. Work that needs a runtime (e.g. network I/O on a tokio
+/// reactor) should be spawned onto it and awaited through a runtime-agnostic channel.
+pub struct BlockingStorage<T: AsyncStorage> {
+    inner: T,
+}
+
+impl<T: AsyncStorage> BlockingStorage<T> {
+    pub fn new(inner: T) -> Self {
+        BlockingStorage { inner }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: AsyncStorage> Storage for BlockingStorage<T> {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        block_on(self.inner.get(key))
+    }
+
+    #[cfg(feature = "iterator")]
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        block_on(self.inner.scan(start, end, order))
+    }
+
+    #[cfg(feature = "iterator")]
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        block_on(self.inner.next(iterator_id))
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+        block_on(self.inner.set(key, value))
+    }
+
+    fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+        block_on(self.inner.remove(key))
+    }
+}
+
+/// A [`Querier`] that forwards all calls to an [`AsyncQuerier`] and blocks until they complete.
+///
+/// The same restrictions as for [`BlockingStorage`] apply.
+pub struct BlockingQuerier<T: AsyncQuerier> {
+    inner: T,
+}
+
+impl<T: AsyncQuerier> BlockingQuerier<T> {
+    pub fn new(inner: T) -> Self {
+        BlockingQuerier { inner }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: AsyncQuerier> Querier for BlockingQuerier<T> {
+    fn query_raw(
+        &self,
+        request: &[u8],
+        gas_limit: u64,
+    ) -> BackendResult<SystemResult<ContractResult<Binary>>> {
+        block_on(self.inner.query_raw(request, gas_limit))
+    }
+}
+
+/// Wakes a thread parked in [`block_on`]
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs a future to completion on the current thread, parking it while the future is pending
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            // Spurious wakeups are fine since we poll again
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockQuerier, MockStorage};
+    use crate::GasInfo;
+    use cosmwasm_std::{
+        coin, from_binary, to_binary, AllBalanceResponse, BankQuery, Empty, QueryRequest,
+    };
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// A future that is completed by another thread after a short delay
+    struct Delayed<T> {
+        state: Arc<Mutex<(Option<T>, Option<Waker>)>>,
+    }
+
+    impl<T: Send + 'static> Delayed<T> {
+        fn new(value: T) -> Self {
+            let state = Arc::new(Mutex::new((None, None::<Waker>)));
+            let state_clone = Arc::clone(&state);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(5));
+                let mut state = state_clone.lock().unwrap();
+                state.0 = Some(value);
+                if let Some(waker) = state.1.take() {
+                    waker.wake();
+                }
+            });
+            Delayed { state }
+        }
+    }
+
+    impl<T> Future for Delayed<T> {
+        type Output = T;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+            let mut state = self.state.lock().unwrap();
+            match state.0.take() {
+                Some(value) => Poll::Ready(value),
+                None => {
+                    state.1 = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    /// Wraps a MockStorage, completing every call asynchronously
+    struct DelayedStorage(MockStorage);
+
+    impl AsyncStorage for DelayedStorage {
+        fn get<'a>(&'a self, key: &'a [u8]) -> BackendFuture<'a, Option<Vec<u8>>> {
+            let result = self.0.get(key);
+            Box::pin(Delayed::new(result))
+        }
+
+        #[cfg(feature = "iterator")]
+        fn scan<'a>(
+            &'a mut self,
+            start: Option<&'a [u8]>,
+            end: Option<&'a [u8]>,
+            order: Order,
+        ) -> BackendFuture<'a, u32> {
+            let result = self.0.scan(start, end, order);
+            Box::pin(Delayed::new(result))
+        }
+
+        #[cfg(feature = "iterator")]
+        fn next(&mut self, iterator_id: u32) -> BackendFuture<'_, Option<Record>> {
+            let result = self.0.next(iterator_id);
+            Box::pin(Delayed::new(result))
+        }
+
+        fn set<'a>(&'a mut self, key: &'a [u8], value: &'a [u8]) -> BackendFuture<'a, ()> {
+            let result = self.0.set(key, value);
+            Box::pin(Delayed::new(result))
+        }
+
+        fn remove<'a>(&'a mut self, key: &'a [u8]) -> BackendFuture<'a, ()> {
+            let result = self.0.remove(key);
+            Box::pin(Delayed::new(result))
+        }
+    }
+
+    struct DelayedQuerier(MockQuerier);
+
+    impl AsyncQuerier for DelayedQuerier {
+        fn query_raw<'a>(
+            &'a self,
+            request: &'a [u8],
+            gas_limit: u64,
+        ) -> BackendFuture<'a, SystemResult<ContractResult<Binary>>> {
+            let result = self.0.query_raw(request, gas_limit);
+            Box::pin(Delayed::new(result))
+        }
+    }
+
+    #[test]
+    fn block_on_works() {
+        assert_eq!(block_on(async { 42 }), 42);
+        assert_eq!(block_on(Delayed::new("foo")), "foo");
+    }
+
+    #[test]
+    fn blocking_storage_works() {
+        let mut storage = BlockingStorage::new(DelayedStorage(MockStorage::new()));
+        assert_eq!(storage.get(b"foo").0.unwrap(), None);
+
+        storage.set(b"foo", b"bar").0.unwrap();
+        assert_eq!(storage.get(b"foo").0.unwrap(), Some(b"bar".to_vec()));
+
+        storage.remove(b"foo").0.unwrap();
+        assert_eq!(storage.get(b"foo").0.unwrap(), None);
+
+        // gas info is passed through
+        let (result, gas_info) = storage.set(b"foo", b"bar");
+        result.unwrap();
+        assert_eq!(gas_info, GasInfo::with_externally_used(6));
+        storage.remove(b"foo").0.unwrap();
+
+        let inner = storage.into_inner();
+        assert_eq!(inner.0.get(b"foo").0.unwrap(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn blocking_storage_iteration_works() {
+        let mut storage = BlockingStorage::new(DelayedStorage(MockStorage::new()));
+        storage.set(b"foo", b"bar").0.unwrap();
+        storage.set(b"zoo", b"zebra").0.unwrap();
+
+        let id = storage.scan(None, None, Order::Descending).0.unwrap();
+        assert_eq!(
+            storage.next(id).0.unwrap(),
+            Some((b"zoo".to_vec(), b"zebra".to_vec()))
+        );
+        assert_eq!(
+            storage.next(id).0.unwrap(),
+            Some((b"foo".to_vec(), b"bar".to_vec()))
+        );
+        assert_eq!(storage.next(id).0.unwrap(), None);
+
+        // default implementation of remove_range works on top
+        assert_eq!(storage.remove_range(None, None, 10).0.unwrap(), 2);
+        assert_eq!(storage.get(b"foo").0.unwrap(), None);
+    }
+
+    #[test]
+    fn blocking_querier_works() {
+        let balance = vec![coin(123, "ELF")];
+        let querier =
+            BlockingQuerier::new(DelayedQuerier(MockQuerier::new(&[("foobar", &balance)])));
+        let request: QueryRequest<Empty> = BankQuery::AllBalances {
+            address: "foobar".to_string(),
+        }
+        .into();
+        let (result, _gas_info) = querier.query_raw(&to_binary(&request).unwrap(), 300_000);
+        let all = result.unwrap().unwrap().unwrap();
+        let res: AllBalanceResponse = from_binary(&all).unwrap();
+        assert_eq!(res.amount, balance);
+    }
+}
@@ -76,6 +76,11 @@ pub struct Backend<A: BackendApi, S: Storage, Q: Querier> {
 
 /// Access to the VM's backend storage, i.e. the chain
 pub trait Storage {
+    /// If true, the storage cannot be written to and instances do not wire the
+    /// `db_write`, `db_remove` and `db_remove_range` imports to it. Those imports
+    /// fail with a write access error instead.
+    const READONLY: bool = false;
+
     /// Returns Err on error.
     /// Returns Ok(None) when key does not exist.
     /// Returns Ok(Some(Vec<u8>)) when key exists.
@@ -112,6 +117,47 @@ pub trait Storage {
     #[cfg(feature = "iterator")]
     fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>>;
 
+    /// Returns up to `limit` records from `start` (inclusive) to `end` (exclusive) in the given order.
+    ///
+    /// The bounds are interpreted in the same way as in `scan`. This allows the VM to load a page
+    /// of records in one backend call instead of calling `next` for every record.
+    ///
+    /// `gas_limit` is the gas left in the VM. Implementations can stop collecting records once
+    /// they used more gas than that, since the VM aborts the call anyways then.
+    ///
+    /// The default implementation uses `scan` and `next`. Backends should override it
+    /// if they can push the limit down to the underlying database.
+    #[cfg(feature = "iterator")]
+    fn scan_bounded(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+        limit: usize,
+        gas_limit: u64,
+    ) -> BackendResult<Vec<Record>> {
+        let (result, mut gas_info) = self.scan(start, end, order);
+        let iterator_id = match result {
+            Ok(id) => id,
+            Err(err) => return (Err(err), gas_info),
+        };
+
+        let mut records = Vec::new();
+        while records.len() < limit {
+            if gas_info.cost.saturating_add(gas_info.externally_used) > gas_limit {
+                break;
+            }
+            let (result, info) = self.next(iterator_id);
+            gas_info += info;
+            match result {
+                Ok(Some(record)) => records.push(record),
+                Ok(None) => break,
+                Err(err) => return (Err(err), gas_info),
+            }
+        }
+        (Ok(records), gas_info)
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()>;
 
     /// Removes a database entry at `key`.
@@ -119,6 +165,72 @@ pub trait Storage {
     /// The current interface does not allow to differentiate between a key that existed
     /// before and one that didn't exist. See https://github.com/CosmWasm/cosmwasm/issues/290
     fn remove(&mut self, key: &[u8]) -> BackendResult<()>;
+
+    /// Removes up to `limit` database entries from `start` (inclusive) to `end` (exclusive),
+    /// starting with the smallest key. Returns the number of removed entries.
+    ///
+    /// The VM calls this repeatedly until less than `limit` entries were removed, such that
+    /// gas can be charged between the chunks.
+    ///
+    /// The default implementation uses `scan`, `next` and `remove`. Backends should override it
+    /// if they can delete a range more efficiently.
+    #[cfg(feature = "iterator")]
+    fn remove_range(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        limit: usize,
+    ) -> BackendResult<usize> {
+        let (result, mut gas_info) = self.scan(start, end, Order::Ascending);
+        let iterator_id = match result {
+            Ok(id) => id,
+            Err(err) => return (Err(err), gas_info),
+        };
+
+        let mut keys = Vec::new();
+        while keys.len() < limit {
+            let (result, info) = self.next(iterator_id);
+            gas_info += info;
+            match result {
+                Ok(Some((key, _))) => keys.push(key),
+                Ok(None) => break,
+                Err(err) => return (Err(err), gas_info),
+            }
+        }
+
+        for key in &keys {
+            let (result, info) = self.remove(key);
+            gas_info += info;
+            if let Err(err) = result {
+                return (Err(err), gas_info);
+            }
+        }
+        (Ok(keys.len()), gas_info)
+    }
+}
+
+/// Read access to a snapshot of the chain state.
+///
+/// In contrast to [`Storage`], all methods take `&self` and iteration does not keep state
+/// in the implementation, such that one snapshot can be shared between many instances
+/// running queries concurrently. Use it via [`QueryStorage`](crate::QueryStorage).
+pub trait ReadonlyStorage {
+    /// Returns Err on error.
+    /// Returns Ok(None) when key does not exist.
+    /// Returns Ok(Some(Vec<u8>)) when key exists.
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>>;
+
+    /// Returns the first record from `start` (inclusive) to `end` (exclusive) in the given order,
+    /// i.e. the record with the smallest key for ascending and the largest key for descending order.
+    ///
+    /// If `start` is lexicographically greater than or equal to `end`, an empty range is described, mo matter of the order.
+    #[cfg(feature = "iterator")]
+    fn first(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<Option<Record>>;
 }
 
 /// Callbacks to system functions defined outside of the wasm modules.
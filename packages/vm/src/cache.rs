@@ -1,26 +1,41 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
-use wasmer::Engine;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use wasmer::{Engine, Module};
 
-use crate::backend::{Backend, BackendApi, Querier, Storage};
+use crate::backend::{Backend, BackendApi, Querier, ReadonlyStorage, Storage};
 use crate::capabilities::required_capabilities_from_module;
 use crate::checksum::Checksum;
-use crate::compatibility::check_wasm;
+use crate::compatibility::{check_and_compile, WasmLimits};
+use crate::contract_metadata::{
+    contract_metadata_from_module, parse_contract_metadata, ContractMetadata,
+};
+use crate::environment::{GasSchedule, DEFAULT_MAX_QUERY_DEPTH};
 use crate::errors::{VmError, VmResult};
 use crate::filesystem::mkdir_p;
 use crate::instance::{Instance, InstanceOptions};
-use crate::modules::{CachedModule, FileSystemCache, InMemoryCache, PinnedMemoryCache};
+use crate::instance_pool::InstancePool;
+use crate::modules::{
+    CachedModule, FileSystemCache, InMemoryCache, MemoryCachePolicy, PinnedMemoryCache,
+};
+use crate::query_storage::QueryStorage;
 use crate::size::Size;
-use crate::static_analysis::{deserialize_wasm, has_ibc_entry_points};
-use crate::wasm_backend::{compile, make_store_with_engine};
+use crate::static_analysis::{
+    deserialize_wasm, entry_points, has_float_operations, has_ibc_entry_points, required_imports,
+};
+use crate::wasm_backend::{compile_with_operator_cost, make_store_with_engine};
 
 const STATE_DIR: &str = "state";
 // Things related to the state of the blockchain.
 const WASM_DIR: &str = "wasm";
+// The checksums of the pinned contracts, one lowercase hex checksum per line.
+const PINNED_FILE: &str = "pinned_checksums";
 
 const CACHE_DIR: &str = "cache";
 // Cacheable things.
@@ -41,6 +56,22 @@ pub struct Stats {
     pub misses: u32,
 }
 
+/// Statistics about the usage of a single contract in a cache instance,
+/// see [`Cache::stats_per_checksum`]. The same caveats as for [`Stats`] apply.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumStats {
+    pub hits_pinned_memory_cache: u32,
+    pub hits_memory_cache: u32,
+    pub hits_fs_cache: u32,
+    pub misses: u32,
+    /// The number of times the contract was compiled
+    pub compilations: u32,
+    /// The total time spent compiling the contract
+    pub compilation_time: Duration,
+    /// The size of the compiled module in bytes, as of the last time it was loaded or compiled
+    pub module_size: usize,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Metrics {
     pub stats: Stats,
@@ -48,6 +79,64 @@ pub struct Metrics {
     pub elements_memory_cache: usize,
     pub size_pinned_memory_cache: usize,
     pub size_memory_cache: usize,
+    /// The maximum cumulative size of the memory cache, i.e. its configured size
+    pub capacity_memory_cache: usize,
+    /// The number of idle instances in the instance pool
+    pub elements_instance_pool: usize,
+}
+
+/// The place a module was taken from when loading it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModuleSource {
+    PinnedMemoryCache,
+    MemoryCache,
+    FileSystemCache,
+    /// The module was not found in any cache (a miss) and compiled from the Wasm blob
+    Compilation,
+}
+
+/// Receives cache events as they happen, e.g. to export Prometheus metrics about
+/// which contracts are hot and whether the memory cache is sized correctly.
+///
+/// The methods are called while the cache is locked, so implementations should
+/// be fast, e.g. only increment counters.
+pub trait CacheMetricsSink: Send + Sync {
+    /// Called whenever a module was loaded, with the place it was taken from
+    fn module_loaded(&self, _checksum: &Checksum, _source: ModuleSource, _module_size: usize) {}
+
+    /// Called whenever a contract was compiled
+    fn module_compiled(&self, _checksum: &Checksum, _duration: Duration, _module_size: usize) {}
+}
+
+/// The result of [`Cache::gc`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GcReport {
+    /// The number of Wasm blobs removed from disk
+    pub removed_wasm: usize,
+    /// The number of compiled modules removed from the file system cache
+    pub removed_modules: usize,
+    /// The number of directories of other module versions removed from the file system cache
+    pub removed_module_versions: usize,
+}
+
+/// What [`Cache::migrate`] did with an outdated module
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationAction {
+    /// The module was compiled again from the stored Wasm blob
+    Recompiled,
+    /// The module was removed because its Wasm blob is missing or corrupted
+    Purged,
+}
+
+/// Reported by [`Cache::migrate`] after every outdated module
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MigrationProgress {
+    pub checksum: Checksum,
+    pub action: MigrationAction,
+    /// The number of modules migrated so far, including this one
+    pub done: usize,
+    /// The total number of outdated modules
+    pub total: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -59,9 +148,76 @@ pub struct CacheOptions {
     pub base_dir: PathBuf,
     pub available_capabilities: HashSet<String>,
     pub memory_cache_size: Size,
+    /// Limits of the memory cache in addition to `memory_cache_size`, such as a maximum
+    /// number of modules or a time to live, and the eviction strategy.
+    pub memory_cache_policy: MemoryCachePolicy,
     /// Memory limit for instances, in bytes. Use a value that is divisible by the Wasm page size 65536,
     /// e.g. full MiBs.
     pub instance_memory_limit: Size,
+    /// Default gas limits for instances created via [`Cache::instance_options`]
+    pub default_gas_limits: GasLimits,
+    /// The gas costs used for compiling contracts and for instances created via
+    /// [`Cache::instance_options`].
+    ///
+    /// Modules compiled with a non-default Wasm operator cost are stored in a separate
+    /// directory of the file system cache, such that changing the cost never loads
+    /// modules metered with a different cost.
+    pub gas_schedule: GasSchedule,
+    /// If enabled, the checksums of pinned contracts are stored in `base_dir` and
+    /// pinned again when the cache is created, such that pins survive node restarts.
+    /// Contracts that cannot be pinned again are skipped, see [`Cache::unrestored_pins`].
+    pub persist_pinned: bool,
+    /// The maximum number of idle instances kept for reuse by [`Cache::get_pooled_instance`].
+    /// Use 0 to disable pooling.
+    pub instance_pool_size: usize,
+    /// The limits applied during static validation in [`Cache::save_wasm`].
+    /// Use `WasmLimits::default()` for the default limits.
+    pub wasm_limits: WasmLimits,
+    /// Executes `ibc_channel_open` with read-only storage in instances created via
+    /// [`Cache::instance_options`]. See [`InstanceOptions::readonly_ibc_channel_open`].
+    pub readonly_ibc_channel_open: bool,
+}
+
+/// The kind of entry point an instance is created for, which selects
+/// the default gas limit from [`GasLimits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryPointKind {
+    /// `query`
+    Query,
+    /// `instantiate`, `execute`, `migrate`, `sudo` and `reply`
+    Execution,
+    /// The IBC entry points such as `ibc_channel_open` or `ibc_packet_receive`
+    Ibc,
+}
+
+/// Gas limits per kind of entry point, measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
+///
+/// This allows serving RPC queries and executing transactions from the same cache
+/// while enforcing different budgets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasLimits {
+    pub query: u64,
+    pub execution: u64,
+    pub ibc: u64,
+}
+
+impl GasLimits {
+    /// Uses the same gas limit for all kinds of entry points
+    pub const fn uniform(gas_limit: u64) -> Self {
+        GasLimits {
+            query: gas_limit,
+            execution: gas_limit,
+            ibc: gas_limit,
+        }
+    }
+
+    pub fn get(&self, kind: EntryPointKind) -> u64 {
+        match kind {
+            EntryPointKind::Query => self.query,
+            EntryPointKind::Execution => self.execution,
+            EntryPointKind::Ibc => self.ibc,
+        }
+    }
 }
 
 pub struct CacheInner {
@@ -74,25 +230,103 @@ pub struct CacheInner {
     memory_cache: InMemoryCache,
     fs_cache: FileSystemCache,
     stats: Stats,
+    checksum_stats: HashMap<Checksum, ChecksumStats>,
+    metrics_sink: Option<Arc<dyn CacheMetricsSink>>,
+    /// The file in which the pinned checksums are persisted, if enabled
+    pinned_path: Option<PathBuf>,
+}
+
+impl CacheInner {
+    /// Writes the currently pinned checksums to disk if persistence is enabled
+    fn persist_pinned(&self) -> VmResult<()> {
+        match &self.pinned_path {
+            Some(pinned_path) => {
+                save_pinned_to_disk(pinned_path, &self.pinned_memory_cache.checksums())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Updates the statistics after the module of `checksum` was taken from `source`
+    fn record_load(&mut self, checksum: &Checksum, source: ModuleSource, module_size: usize) {
+        let checksum_stats = self.checksum_stats.entry(*checksum).or_default();
+        let (total, per_checksum) = match source {
+            ModuleSource::PinnedMemoryCache => (
+                &mut self.stats.hits_pinned_memory_cache,
+                &mut checksum_stats.hits_pinned_memory_cache,
+            ),
+            ModuleSource::MemoryCache => (
+                &mut self.stats.hits_memory_cache,
+                &mut checksum_stats.hits_memory_cache,
+            ),
+            ModuleSource::FileSystemCache => (
+                &mut self.stats.hits_fs_cache,
+                &mut checksum_stats.hits_fs_cache,
+            ),
+            ModuleSource::Compilation => (&mut self.stats.misses, &mut checksum_stats.misses),
+        };
+        *total = total.saturating_add(1);
+        *per_checksum = per_checksum.saturating_add(1);
+        checksum_stats.module_size = module_size;
+
+        if let Some(sink) = &self.metrics_sink {
+            sink.module_loaded(checksum, source, module_size);
+        }
+    }
+
+    /// Updates the statistics after the contract `checksum` was compiled
+    fn record_compilation(&mut self, checksum: &Checksum, duration: Duration, module_size: usize) {
+        let checksum_stats = self.checksum_stats.entry(*checksum).or_default();
+        checksum_stats.compilations = checksum_stats.compilations.saturating_add(1);
+        checksum_stats.compilation_time = checksum_stats.compilation_time.saturating_add(duration);
+        checksum_stats.module_size = module_size;
+
+        if let Some(sink) = &self.metrics_sink {
+            sink.module_compiled(checksum, duration, module_size);
+        }
+    }
 }
 
 pub struct Cache<A: BackendApi, S: Storage, Q: Querier> {
     /// Available capabilities are immutable for the lifetime of the cache,
     /// i.e. any number of read-only references is allowed to access it concurrently.
     available_capabilities: HashSet<String>,
+    /// The static validation limits are immutable for the lifetime of the cache as well.
+    wasm_limits: WasmLimits,
+    /// Default gas limits are immutable for the lifetime of the cache as well.
+    default_gas_limits: GasLimits,
+    /// The gas schedule is immutable for the lifetime of the cache as well.
+    gas_schedule: GasSchedule,
+    /// Whether `ibc_channel_open` is executed with read-only storage by default
+    readonly_ibc_channel_open: bool,
     inner: Mutex<CacheInner>,
+    instance_pool: Mutex<InstancePool<A, S, Q>>,
     // Those two don't store data but only fix type information
     type_api: PhantomData<A>,
     type_storage: PhantomData<S>,
     type_querier: PhantomData<Q>,
     /// To prevent concurrent access to `WasmerInstance::new`
     instantiation_lock: Mutex<()>,
+    /// The persisted pins that could not be restored in `Cache::new`
+    unrestored_pins: Vec<(Checksum, VmError)>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct AnalysisReport {
     pub has_ibc_entry_points: bool,
     pub required_capabilities: HashSet<String>,
+    /// The entry points exported by the contract, such as `instantiate` or `ibc_packet_receive`
+    pub entry_points: BTreeSet<String>,
+    /// All imports of the contract in the format `<module>.<field>`, e.g. `env.db_read`
+    pub required_imports: BTreeSet<String>,
+    /// True if the code contains float operations. Such contracts cannot be compiled.
+    pub has_float_operations: bool,
+    /// The contract name from the contract metadata, see [`Cache::metadata`].
+    /// `None` if the contract has no valid metadata.
+    pub contract_name: Option<String>,
+    /// The contract version from the contract metadata, see [`Cache::metadata`].
+    /// `None` if the contract has no valid metadata.
+    pub contract_version: Option<String>,
 }
 
 impl<A, S, Q> Cache<A, S, Q>
@@ -108,12 +342,23 @@ where
     /// This function is marked unsafe due to `FileSystemCache::new`, which implicitly
     /// assumes the disk contents are correct, and there's no way to ensure the artifacts
     /// stored in the cache haven't been corrupted or tampered with.
+    ///
+    /// With [`CacheOptions::persist_pinned`], the persisted pins are restored. Contracts
+    /// that cannot be pinned, e.g. because their Wasm was removed from disk, are skipped
+    /// and removed from the persisted pins. They are returned by [`Cache::unrestored_pins`].
     pub unsafe fn new(options: CacheOptions) -> VmResult<Self> {
         let CacheOptions {
             base_dir,
             available_capabilities,
             memory_cache_size,
+            memory_cache_policy,
             instance_memory_limit,
+            default_gas_limits,
+            gas_schedule,
+            persist_pinned,
+            instance_pool_size,
+            wasm_limits,
+            readonly_ibc_channel_open,
         } = options;
 
         let state_path = base_dir.join(STATE_DIR);
@@ -126,29 +371,80 @@ where
         mkdir_p(&cache_path).map_err(|_e| VmError::cache_err("Error creating cache directory"))?;
         mkdir_p(&wasm_path).map_err(|_e| VmError::cache_err("Error creating wasm directory"))?;
 
-        let fs_cache = FileSystemCache::new(cache_path.join(MODULES_DIR))
+        let fs_cache = FileSystemCache::new(modules_path(&cache_path, &gas_schedule))
             .map_err(|e| VmError::cache_err(format!("Error file system cache: {}", e)))?;
-        Ok(Cache {
+        let pinned_path = if persist_pinned {
+            Some(state_path.join(PINNED_FILE))
+        } else {
+            None
+        };
+        let previously_pinned = match &pinned_path {
+            Some(pinned_path) => load_pinned_from_disk(pinned_path)?,
+            None => Vec::new(),
+        };
+        let mut cache = Cache {
             available_capabilities,
+            wasm_limits,
+            default_gas_limits,
+            gas_schedule,
+            readonly_ibc_channel_open,
             inner: Mutex::new(CacheInner {
                 wasm_path,
                 instance_memory_limit,
                 pinned_memory_cache: PinnedMemoryCache::new(),
-                memory_cache: InMemoryCache::new(memory_cache_size),
+                memory_cache: InMemoryCache::new(memory_cache_size, memory_cache_policy),
                 fs_cache,
                 stats: Stats::default(),
+                checksum_stats: HashMap::new(),
+                metrics_sink: None,
+                pinned_path,
             }),
+            instance_pool: Mutex::new(InstancePool::new(instance_pool_size)),
             type_storage: PhantomData::<S>,
             type_api: PhantomData::<A>,
             type_querier: PhantomData::<Q>,
             instantiation_lock: Mutex::new(()),
-        })
+            unrestored_pins: Vec::new(),
+        };
+
+        // Pinning compiles the module if it is not in the file system cache. The pins are
+        // only persisted once all of them are restored, such that an interrupted restore
+        // does not lose the remaining ones.
+        let mut unrestored_pins = Vec::new();
+        {
+            let mut inner = cache.inner.lock().unwrap();
+            for checksum in previously_pinned {
+                if inner.pinned_memory_cache.has(&checksum) {
+                    continue;
+                }
+                if let Err(e) = cache.pin_module(&mut inner, &checksum) {
+                    unrestored_pins.push((checksum, e));
+                }
+            }
+            if !unrestored_pins.is_empty() {
+                // Don't try to restore these again on the next start
+                inner.persist_pinned()?;
+            }
+        }
+        cache.unrestored_pins = unrestored_pins;
+        Ok(cache)
+    }
+
+    /// Returns the persisted pins that could not be restored when the cache was created,
+    /// together with the error of pinning them. These contracts are not pinned anymore.
+    pub fn unrestored_pins(&self) -> &[(Checksum, VmError)] {
+        &self.unrestored_pins
     }
 
     pub fn stats(&self) -> Stats {
         self.inner.lock().unwrap().stats
     }
 
+    /// Returns the statistics of each contract loaded or compiled by this cache instance
+    pub fn stats_per_checksum(&self) -> HashMap<Checksum, ChecksumStats> {
+        self.inner.lock().unwrap().checksum_stats.clone()
+    }
+
     pub fn metrics(&self) -> Metrics {
         let cache = self.inner.lock().unwrap();
         Metrics {
@@ -157,9 +453,22 @@ where
             elements_memory_cache: cache.memory_cache.len(),
             size_pinned_memory_cache: cache.pinned_memory_cache.size(),
             size_memory_cache: cache.memory_cache.size(),
+            capacity_memory_cache: cache.memory_cache.capacity(),
+            elements_instance_pool: self.instance_pool.lock().unwrap().len(),
         }
     }
 
+    /// Sets a sink that is notified about every module load and compilation.
+    /// This replaces the previously set sink.
+    pub fn set_metrics_sink(&self, sink: Arc<dyn CacheMetricsSink>) {
+        self.inner.lock().unwrap().metrics_sink = Some(sink);
+    }
+
+    /// Removes the sink set via [`Cache::set_metrics_sink`]
+    pub fn unset_metrics_sink(&self) {
+        self.inner.lock().unwrap().metrics_sink = None;
+    }
+
     /// Takes a Wasm bytecode and stores it to the cache.
     ///
     /// This performs static checks, compiles the bytescode to a module and
@@ -168,8 +477,14 @@ where
     /// This does the same as [`save_wasm_unchecked`] plus the static checks.
     /// When a Wasm blob is stored the first time, use this function.
     pub fn save_wasm(&self, wasm: &[u8]) -> VmResult<Checksum> {
-        check_wasm(wasm, &self.available_capabilities)?;
-        self.save_wasm_unchecked(wasm)
+        let start = Instant::now();
+        let (_, _engine, module) = check_and_compile(
+            wasm,
+            &self.available_capabilities,
+            &self.wasm_limits,
+            self.gas_schedule.wasm_operator_cost,
+        )?;
+        self.store_wasm(wasm, &module, start.elapsed())
     }
 
     /// Takes a Wasm bytecode and stores it to the cache.
@@ -181,33 +496,102 @@ where
     /// When a Wasm blob is stored which was previously checked (e.g. as part of state sync),
     /// use this function.
     pub fn save_wasm_unchecked(&self, wasm: &[u8]) -> VmResult<Checksum> {
-        let (_engine, module) = compile(wasm, &[])?;
+        let start = Instant::now();
+        let (_engine, module) =
+            compile_with_operator_cost(wasm, &[], self.gas_schedule.wasm_operator_cost)?;
+        self.store_wasm(wasm, &module, start.elapsed())
+    }
 
+    /// Stores the Wasm blob and its compiled module
+    fn store_wasm(
+        &self,
+        wasm: &[u8],
+        module: &Module,
+        compilation_time: Duration,
+    ) -> VmResult<Checksum> {
         let mut cache = self.inner.lock().unwrap();
         let checksum = save_wasm_to_disk(&cache.wasm_path, wasm)?;
-        cache.fs_cache.store(&checksum, &module)?;
+        let module_size = cache.fs_cache.store(&checksum, module)?;
+        cache.record_compilation(&checksum, compilation_time, module_size);
         Ok(checksum)
     }
 
     /// Removes the Wasm blob for the given checksum from disk and its
-    /// compiled module from the file system cache.
+    /// compiled module from the file system cache and the memory caches.
     ///
     /// The existence of the original code is required since the caller (wasmd)
     /// has to keep track of which entries we have here.
     pub fn remove_wasm(&self, checksum: &Checksum) -> VmResult<()> {
         let mut cache = self.inner.lock().unwrap();
 
-        // Remove compiled moduled from disk (if it exists).
-        // Here we could also delete from memory caches but this is not really
-        // necessary as they are pushed out from the LRU over time or disappear
-        // when the node process restarts.
+        // Remove compiled moduled from disk (if it exists) and memory
         cache.fs_cache.remove(checksum)?;
+        cache.memory_cache.remove(checksum);
+        cache.checksum_stats.remove(checksum);
+        // Persist the removal of the pin, such that a restart does not try to pin
+        // a contract without Wasm.
+        if cache.pinned_memory_cache.has(checksum) {
+            cache.pinned_memory_cache.remove(checksum)?;
+            cache.persist_pinned()?;
+        }
+        self.instance_pool.lock().unwrap().remove(checksum);
 
         let path = &cache.wasm_path;
         remove_wasm_from_disk(path, checksum)?;
         Ok(())
     }
 
+    /// Removes the Wasm blobs and compiled modules of all contracts that are not in `keep`
+    /// from disk and from the memory caches. Contracts not in `keep` are unpinned.
+    ///
+    /// This allows long running nodes to clean up the base directory, where `keep` is the
+    /// list of all code stored on chain. The directories of other module versions are
+    /// removed as well. Modules compiled for other gas schedules are not touched.
+    pub fn gc(&self, keep: &[Checksum]) -> VmResult<GcReport> {
+        let keep: HashSet<Checksum> = keep.iter().copied().collect();
+        let mut cache = self.inner.lock().unwrap();
+        let mut report = GcReport::default();
+
+        let wasm_path = cache.wasm_path.clone();
+        for checksum in wasm_checksums_on_disk(&wasm_path)? {
+            if !keep.contains(&checksum) {
+                remove_wasm_from_disk(&wasm_path, &checksum)?;
+                report.removed_wasm += 1;
+            }
+        }
+        for checksum in cache.fs_cache.checksums()? {
+            if !keep.contains(&checksum) {
+                cache.fs_cache.remove(&checksum)?;
+                report.removed_modules += 1;
+            }
+        }
+        report.removed_module_versions = cache.fs_cache.remove_obsolete_versions()?;
+
+        for checksum in cache.memory_cache.checksums() {
+            if !keep.contains(&checksum) {
+                cache.memory_cache.remove(&checksum);
+            }
+        }
+        let unpin: Vec<Checksum> = cache
+            .pinned_memory_cache
+            .checksums()
+            .into_iter()
+            .filter(|checksum| !keep.contains(checksum))
+            .collect();
+        for checksum in &unpin {
+            cache.pinned_memory_cache.remove(checksum)?;
+        }
+        if !unpin.is_empty() {
+            cache.persist_pinned()?;
+        }
+        cache
+            .checksum_stats
+            .retain(|checksum, _| keep.contains(checksum));
+        self.instance_pool.lock().unwrap().retain(&keep);
+
+        Ok(report)
+    }
+
     /// Retrieves a Wasm blob that was previously stored via save_wasm.
     /// When the cache is instantiated with the same base dir, this finds Wasm files on disc across multiple cache instances (i.e. node restarts).
     /// This function is public to allow a checksum to Wasm lookup in the blockchain.
@@ -235,12 +619,26 @@ where
         // Here we could use a streaming deserializer to slightly improve performance. However, this way it is DRYer.
         let wasm = self.load_wasm(checksum)?;
         let module = deserialize_wasm(&wasm)?;
+        // Invalid metadata does not affect the contract's execution, so it is not an error here
+        let metadata = contract_metadata_from_module(&module).ok().flatten();
         Ok(AnalysisReport {
             has_ibc_entry_points: has_ibc_entry_points(&module),
             required_capabilities: required_capabilities_from_module(&module),
+            entry_points: entry_points(&module),
+            required_imports: required_imports(&module),
+            has_float_operations: has_float_operations(&module),
+            contract_name: metadata.as_ref().map(|metadata| metadata.name.clone()),
+            contract_version: metadata.map(|metadata| metadata.version),
         })
     }
 
+    /// Returns the metadata embedded into the contract by `cosmwasm_std::contract_metadata!`,
+    /// or `None` if the contract was compiled without it.
+    pub fn metadata(&self, checksum: &Checksum) -> VmResult<Option<ContractMetadata>> {
+        let wasm = self.load_wasm(checksum)?;
+        parse_contract_metadata(&wasm)
+    }
+
     /// Pins a Module that was previously stored via save_wasm.
     ///
     /// The module is lookup first in the file system cache. If not found,
@@ -249,12 +647,18 @@ where
     ///
     /// If the given contract for the given checksum is not found, or the content
     /// does not match the checksum, an error is returned.
+    ///
+    /// If [`CacheOptions::persist_pinned`] is enabled, the pin is persisted on disk.
     pub fn pin(&self, checksum: &Checksum) -> VmResult<()> {
         let mut cache = self.inner.lock().unwrap();
         if cache.pinned_memory_cache.has(checksum) {
             return Ok(());
         }
+        self.pin_module(&mut cache, checksum)?;
+        cache.persist_pinned()
+    }
 
+    fn pin_module(&self, cache: &mut CacheInner, checksum: &Checksum) -> VmResult<()> {
         // We don't load from the memory cache because we had to create new store here and
         // serialize/deserialize the artifact to get a full clone. Could be done but adds some code
         // for a not-so-relevant use case.
@@ -262,7 +666,7 @@ where
         // Try to get module from file system cache
         let engine = Engine::headless();
         if let Some((module, module_size)) = cache.fs_cache.load(checksum, &engine)? {
-            cache.stats.hits_fs_cache = cache.stats.hits_fs_cache.saturating_add(1);
+            cache.record_load(checksum, ModuleSource::FileSystemCache, module_size);
             return cache
                 .pinned_memory_cache
                 .store(checksum, (engine, module), module_size);
@@ -270,9 +674,13 @@ where
 
         // Re-compile from original Wasm bytecode
         let code = self.load_wasm_with_path(&cache.wasm_path, checksum)?;
-        let (engine, module) = compile(&code, &[])?;
+        let start = Instant::now();
+        let (engine, module) =
+            compile_with_operator_cost(&code, &[], self.gas_schedule.wasm_operator_cost)?;
+        let compilation_time = start.elapsed();
         // Store into the fs cache too
         let module_size = cache.fs_cache.store(checksum, &module)?;
+        cache.record_compilation(checksum, compilation_time, module_size);
         cache
             .pinned_memory_cache
             .store(checksum, (engine, module), module_size)
@@ -283,11 +691,166 @@ where
     /// Not found IDs are silently ignored, and no integrity check (checksum validation) is done
     /// on the removed value.
     pub fn unpin(&self, checksum: &Checksum) -> VmResult<()> {
-        self.inner
-            .lock()
-            .unwrap()
-            .pinned_memory_cache
-            .remove(checksum)
+        let mut cache = self.inner.lock().unwrap();
+        cache.pinned_memory_cache.remove(checksum)?;
+        cache.persist_pinned()
+    }
+
+    /// Returns the checksums of all pinned contracts, sorted by their hex representation
+    pub fn pinned_checksums(&self) -> Vec<Checksum> {
+        self.inner.lock().unwrap().pinned_memory_cache.checksums()
+    }
+
+    /// Loads the modules of previously saved Wasm blobs into the memory cache, compiling them
+    /// if they are not in the file system cache.
+    ///
+    /// This allows warming up the cache on node startup such that the first execution of a
+    /// contract does not have to wait for its compilation. Modules that are pinned or already
+    /// in the memory cache are skipped. If the memory cache is too small for all modules,
+    /// the ones preloaded first are evicted again.
+    ///
+    /// This is the same as [`Cache::preload_with_threads`] with a single thread.
+    pub fn preload(&self, checksums: &[Checksum]) -> VmResult<()> {
+        self.preload_with_threads(checksums, 1)
+    }
+
+    /// Like [`Cache::preload`] but compiles up to `threads` modules in parallel.
+    ///
+    /// All checksums are processed even if some of them fail. In this case the error
+    /// of the first failing checksum in the list is returned.
+    pub fn preload_with_threads(&self, checksums: &[Checksum], threads: usize) -> VmResult<()> {
+        let threads = threads.clamp(1, checksums.len().max(1));
+        let mut results: Vec<VmResult<()>> = if threads == 1 {
+            checksums
+                .iter()
+                .map(|checksum| self.preload_module(checksum))
+                .collect()
+        } else {
+            let next = AtomicUsize::new(0);
+            let results = Mutex::new(Vec::with_capacity(checksums.len()));
+            thread::scope(|scope| {
+                for _ in 0..threads {
+                    scope.spawn(|| loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let checksum = match checksums.get(index) {
+                            Some(checksum) => checksum,
+                            None => break,
+                        };
+                        let result = self.preload_module(checksum);
+                        results.lock().unwrap().push((index, result));
+                    });
+                }
+            });
+            let mut results = results.into_inner().unwrap();
+            results.sort_by_key(|(index, _)| *index);
+            results.into_iter().map(|(_, result)| result).collect()
+        };
+        match results.iter().position(|result| result.is_err()) {
+            Some(index) => results.swap_remove(index),
+            None => Ok(()),
+        }
+    }
+
+    fn preload_module(&self, checksum: &Checksum) -> VmResult<()> {
+        let wasm_path = {
+            let mut cache = self.inner.lock().unwrap();
+            if cache.pinned_memory_cache.has(checksum) || cache.memory_cache.has(checksum) {
+                return Ok(());
+            }
+
+            let engine = Engine::headless();
+            if let Some((module, module_size)) = cache.fs_cache.load(checksum, &engine)? {
+                return cache
+                    .memory_cache
+                    .store(checksum, (engine, module), module_size);
+            }
+            cache.wasm_path.clone()
+        };
+
+        // Compile without holding the lock, such that multiple threads can compile in parallel
+        let wasm = self.load_wasm_with_path(&wasm_path, checksum)?;
+        let start = Instant::now();
+        let (engine, module) =
+            compile_with_operator_cost(&wasm, &[], self.gas_schedule.wasm_operator_cost)?;
+        let compilation_time = start.elapsed();
+
+        let mut cache = self.inner.lock().unwrap();
+        let module_size = cache.fs_cache.store(checksum, &module)?;
+        cache.record_compilation(checksum, compilation_time, module_size);
+        cache
+            .memory_cache
+            .store(checksum, (engine, module), module_size)
+    }
+
+    /// Returns the checksums of the modules in the file system cache that were written by a
+    /// different version of cosmwasm-vm or Wasmer, sorted by their hex representation.
+    ///
+    /// Those modules are found when the cache is created and cannot be loaded anymore.
+    /// They are compiled again when the contract is used or all at once via [`Cache::migrate`].
+    pub fn outdated_modules(&self) -> Vec<Checksum> {
+        self.inner.lock().unwrap().fs_cache.outdated_modules()
+    }
+
+    /// Migrates the file system cache to the current module format.
+    ///
+    /// This removes the directories of other module versions and compiles all
+    /// [outdated modules](Cache::outdated_modules) again. Outdated modules of which the
+    /// Wasm blob is missing or corrupted are removed instead. The callback is called after
+    /// every outdated module, e.g. to log the progress of a long migration on node startup.
+    pub fn migrate(&self, mut progress: impl FnMut(MigrationProgress)) -> VmResult<()> {
+        let (outdated, wasm_path) = {
+            let mut cache = self.inner.lock().unwrap();
+            cache.fs_cache.remove_obsolete_versions()?;
+            (cache.fs_cache.outdated_modules(), cache.wasm_path.clone())
+        };
+
+        let total = outdated.len();
+        for (index, checksum) in outdated.iter().enumerate() {
+            let action = match self.load_wasm_with_path(&wasm_path, checksum) {
+                Ok(wasm) => {
+                    // Compile without holding the lock, such that the cache can be used meanwhile
+                    let start = Instant::now();
+                    let (_engine, module) = compile_with_operator_cost(
+                        &wasm,
+                        &[],
+                        self.gas_schedule.wasm_operator_cost,
+                    )?;
+                    let compilation_time = start.elapsed();
+
+                    let mut cache = self.inner.lock().unwrap();
+                    let module_size = cache.fs_cache.store(checksum, &module)?;
+                    cache.record_compilation(checksum, compilation_time, module_size);
+                    MigrationAction::Recompiled
+                }
+                Err(_) => {
+                    self.inner.lock().unwrap().fs_cache.remove(checksum)?;
+                    MigrationAction::Purged
+                }
+            };
+            progress(MigrationProgress {
+                checksum: *checksum,
+                action,
+                done: index + 1,
+                total,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns instance options with the default gas limit for the given kind of entry point.
+    ///
+    /// The result can be adjusted before passing it to [`Cache::get_instance`],
+    /// e.g. to override the gas limit for a single call.
+    pub fn instance_options(&self, kind: EntryPointKind) -> InstanceOptions {
+        InstanceOptions {
+            gas_limit: self.default_gas_limits.get(kind),
+            print_debug: false,
+            debug_handler: None,
+            readonly_ibc_channel_open: self.readonly_ibc_channel_open,
+            gas_schedule: self.gas_schedule,
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            max_execution_time: None,
+        }
     }
 
     /// Returns an Instance tied to a previously saved Wasm.
@@ -299,20 +862,102 @@ where
         backend: Backend<A, S, Q>,
         options: InstanceOptions,
     ) -> VmResult<Instance<A, S, Q>> {
+        self.instantiate(checksum, backend, options)
+    }
+
+    /// Returns an instance for executing queries against a shared snapshot of the chain state.
+    ///
+    /// The storage of the instance is a [`QueryStorage`] that can only read from the snapshot.
+    /// The write imports of the contract are not wired to the storage but always fail, so many
+    /// query instances can use the same snapshot concurrently without cloning it.
+    pub fn get_query_instance<T: ReadonlyStorage + 'static>(
+        &self,
+        checksum: &Checksum,
+        api: A,
+        snapshot: Arc<T>,
+        querier: Q,
+        options: InstanceOptions,
+    ) -> VmResult<Instance<A, QueryStorage<T>, Q>> {
+        let backend = Backend {
+            api,
+            storage: QueryStorage::new(snapshot),
+            querier,
+        };
+        self.instantiate(checksum, backend, options)
+    }
+
+    fn instantiate<S2: Storage + 'static>(
+        &self,
+        checksum: &Checksum,
+        backend: Backend<A, S2, Q>,
+        options: InstanceOptions,
+    ) -> VmResult<Instance<A, S2, Q>> {
         let (cached, memory_limit, _from_pinned) = self.get_module(checksum)?;
         let store = make_store_with_engine(cached.engine, Some(memory_limit));
-        let instance = Instance::from_module(
+        let mut instance = Instance::from_module(
             store,
             &cached.module,
             backend,
             options.gas_limit,
+            options.gas_schedule.host_functions,
             options.print_debug,
             None,
             Some(&self.instantiation_lock),
         )?;
+        instance.set_readonly_ibc_channel_open(options.readonly_ibc_channel_open);
+        instance.set_max_query_depth(options.max_query_depth);
+        instance.set_max_execution_time(options.max_execution_time);
+        instance.set_shared_debug_handler(options.debug_handler.as_ref());
+        instance.set_checksum(*checksum);
+        Ok(instance)
+    }
+
+    /// Like [`Cache::get_instance`], but reuses an idle instance of the contract if available.
+    ///
+    /// Creating an instance dominates the execution time of small calls such as most queries.
+    /// Instances obtained here should be handed back via [`Cache::return_pooled_instance`]
+    /// instead of [`Instance::recycle`], such that they can be reused.
+    ///
+    /// Reused instances do not count as cache hits in [`Cache::stats`].
+    pub fn get_pooled_instance(
+        &self,
+        checksum: &Checksum,
+        backend: Backend<A, S, Q>,
+        options: InstanceOptions,
+    ) -> VmResult<Instance<A, S, Q>> {
+        let pooled = self.instance_pool.lock().unwrap().take(checksum);
+        if let Some(mut instance) = pooled {
+            instance.reuse(backend, options);
+            return Ok(instance);
+        }
+
+        let mut instance = self.get_instance(checksum, backend, options)?;
+        self.instance_pool
+            .lock()
+            .unwrap()
+            .register(checksum, &mut instance)?;
         Ok(instance)
     }
 
+    /// Hands an instance obtained via [`Cache::get_pooled_instance`] back to the pool
+    /// and returns its backend, like [`Instance::recycle`].
+    ///
+    /// The instance's memory is restored to the state right after instantiation before
+    /// it is reused. Since Wasm memory cannot shrink, a reused instance keeps the pages it
+    /// allocated, which makes its memory size and growth behaviour differ from a fresh
+    /// instance. Pooling is therefore intended for queries and not for consensus critical
+    /// calls.
+    ///
+    /// Instances are dropped instead if a call failed, since the state of the contract's
+    /// globals is unknown then, or if the pool is full.
+    pub fn return_pooled_instance(
+        &self,
+        checksum: &Checksum,
+        instance: Instance<A, S, Q>,
+    ) -> Option<Backend<A, S, Q>> {
+        self.instance_pool.lock().unwrap().put(checksum, instance)
+    }
+
     /// Returns a module tied to a previously saved Wasm.
     /// Depending on availability, this is either generated from a memory cache, file system cache or Wasm code.
     /// This is part of `get_instance` but pulled out to reduce the locking time.
@@ -320,21 +965,20 @@ where
         let mut cache = self.inner.lock().unwrap();
         // Try to get module from the pinned memory cache
         if let Some(element) = cache.pinned_memory_cache.load(checksum)? {
-            cache.stats.hits_pinned_memory_cache =
-                cache.stats.hits_pinned_memory_cache.saturating_add(1);
+            cache.record_load(checksum, ModuleSource::PinnedMemoryCache, element.size);
             return Ok((element, cache.instance_memory_limit, true));
         }
 
         // Get module from memory cache
         if let Some(element) = cache.memory_cache.load(checksum)? {
-            cache.stats.hits_memory_cache = cache.stats.hits_memory_cache.saturating_add(1);
+            cache.record_load(checksum, ModuleSource::MemoryCache, element.size);
             return Ok((element, cache.instance_memory_limit, false));
         }
 
         // Get module from file system cache
         let engine = Engine::headless();
         if let Some((module, module_size)) = cache.fs_cache.load(checksum, &engine)? {
-            cache.stats.hits_fs_cache = cache.stats.hits_fs_cache.saturating_add(1);
+            cache.record_load(checksum, ModuleSource::FileSystemCache, module_size);
 
             cache
                 .memory_cache
@@ -353,9 +997,13 @@ where
         // serialization format. If you do not replay all transactions, previous calls of `save_wasm`
         // stored the old module format.
         let wasm = self.load_wasm_with_path(&cache.wasm_path, checksum)?;
-        cache.stats.misses = cache.stats.misses.saturating_add(1);
-        let (engine, module) = compile(&wasm, &[])?;
+        let start = Instant::now();
+        let (engine, module) =
+            compile_with_operator_cost(&wasm, &[], self.gas_schedule.wasm_operator_cost)?;
+        let compilation_time = start.elapsed();
         let module_size = cache.fs_cache.store(checksum, &module)?;
+        cache.record_load(checksum, ModuleSource::Compilation, module_size);
+        cache.record_compilation(checksum, compilation_time, module_size);
 
         cache
             .memory_cache
@@ -385,6 +1033,19 @@ where
 {
 }
 
+/// Returns the directory of the file system cache for modules compiled with the given gas schedule.
+///
+/// Modules with the Wasm operator cost of [`GasSchedule::v1`] are stored in the modules
+/// directory as before, other costs get a sub-directory each.
+fn modules_path(cache_path: &Path, gas_schedule: &GasSchedule) -> PathBuf {
+    let modules_path = cache_path.join(MODULES_DIR);
+    if gas_schedule.wasm_operator_cost == GasSchedule::v1().wasm_operator_cost {
+        modules_path
+    } else {
+        modules_path.join(format!("operator-cost-{}", gas_schedule.wasm_operator_cost))
+    }
+}
+
 /// save stores the wasm code in the given directory and returns an ID for lookup.
 /// It will create the directory if it doesn't exist.
 /// Saving the same byte code multiple times is allowed.
@@ -423,6 +1084,72 @@ fn load_wasm_from_disk(dir: impl Into<PathBuf>, checksum: &Checksum) -> VmResult
     Ok(wasm)
 }
 
+/// Returns the checksums of all Wasm blobs in the given directory.
+/// Files that are not named after a checksum (with or without the wasm extension) are ignored.
+fn wasm_checksums_on_disk(dir: &Path) -> VmResult<HashSet<Checksum>> {
+    let entries =
+        fs::read_dir(dir).map_err(|_e| VmError::cache_err("Error reading wasm directory"))?;
+    let mut checksums = HashSet::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|_e| VmError::cache_err("Error reading wasm directory"))?
+            .path();
+        let has_wasm_extension = match path.extension() {
+            Some(extension) => extension == "wasm",
+            None => true,
+        };
+        if !has_wasm_extension {
+            continue;
+        }
+        let checksum = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| hex::decode(stem).ok())
+            .and_then(|data| Checksum::try_from(data.as_slice()).ok());
+        if let Some(checksum) = checksum {
+            checksums.insert(checksum);
+        }
+    }
+    Ok(checksums)
+}
+
+/// Writes the checksums to the given file, replacing its previous content.
+///
+/// The file is written to a temporary location first and then moved into place,
+/// such that a crash never leaves a partially written file behind.
+fn save_pinned_to_disk(path: &Path, checksums: &[Checksum]) -> VmResult<()> {
+    let content: String = checksums
+        .iter()
+        .map(|checksum| format!("{}\n", checksum))
+        .collect();
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content)
+        .map_err(|e| VmError::cache_err(format!("Error writing pinned checksums: {}", e)))?;
+    fs::rename(&tmp_path, path)
+        .map_err(|e| VmError::cache_err(format!("Error writing pinned checksums: {}", e)))?;
+    Ok(())
+}
+
+/// Reads the checksums written by [`save_pinned_to_disk`].
+/// A non-existent file is treated as an empty list.
+fn load_pinned_from_disk(path: &Path) -> VmResult<Vec<Checksum>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| VmError::cache_err(format!("Error reading pinned checksums: {}", e)))?;
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let data = hex::decode(line)
+                .map_err(|_e| VmError::cache_err(format!("Invalid pinned checksum: {}", line)))?;
+            Checksum::try_from(data.as_slice())
+        })
+        .collect()
+}
+
 /// Removes the Wasm blob for the given checksum from disk.
 ///
 /// In contrast to the file system cache, the existence of the original
@@ -455,9 +1182,11 @@ fn remove_wasm_from_disk(dir: impl Into<PathBuf>, checksum: &Checksum) -> VmResu
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::calls::{call_execute, call_instantiate};
+    use crate::calls::{call_execute, call_instantiate, call_query};
     use crate::capabilities::capabilities_from_csv;
+    use crate::contract_metadata::CONTRACT_METADATA_SECTION;
     use crate::errors::VmError;
+    use crate::instance::SharedDebugHandler;
     use crate::testing::{mock_backend, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
     use cosmwasm_std::{coins, Empty};
     use std::fs::{create_dir_all, OpenOptions};
@@ -469,11 +1198,17 @@ mod tests {
     const TESTING_OPTIONS: InstanceOptions = InstanceOptions {
         gas_limit: TESTING_GAS_LIMIT,
         print_debug: false,
+        debug_handler: None,
+        readonly_ibc_channel_open: false,
+        gas_schedule: GasSchedule::v1(),
+        max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+        max_execution_time: None,
     };
     const TESTING_MEMORY_CACHE_SIZE: Size = Size::mebi(200);
 
     static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
     static IBC_CONTRACT: &[u8] = include_bytes!("../testdata/ibc_reflect.wasm");
+    static CYBERPUNK: &[u8] = include_bytes!("../testdata/cyberpunk.wasm");
     // Invalid because it doesn't contain required memory and exports
     static INVALID_CONTRACT_WAT: &str = r#"(module
         (type $t0 (func (param i32) (result i32)))
@@ -492,7 +1227,14 @@ mod tests {
             base_dir: TempDir::new().unwrap().into_path(),
             available_capabilities: default_capabilities(),
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
+            memory_cache_policy: MemoryCachePolicy::default(),
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            default_gas_limits: GasLimits::uniform(TESTING_GAS_LIMIT),
+            gas_schedule: GasSchedule::default(),
+            persist_pinned: false,
+            instance_pool_size: 0,
+            wasm_limits: WasmLimits::default(),
+            readonly_ibc_channel_open: false,
         }
     }
 
@@ -503,7 +1245,14 @@ mod tests {
             base_dir: TempDir::new().unwrap().into_path(),
             available_capabilities: capabilities,
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
+            memory_cache_policy: MemoryCachePolicy::default(),
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            default_gas_limits: GasLimits::uniform(TESTING_GAS_LIMIT),
+            gas_schedule: GasSchedule::default(),
+            persist_pinned: false,
+            instance_pool_size: 0,
+            wasm_limits: WasmLimits::default(),
+            readonly_ibc_channel_open: false,
         }
     }
 
@@ -553,6 +1302,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn save_wasm_applies_wasm_limits() {
+        let options = CacheOptions {
+            wasm_limits: WasmLimits {
+                max_functions: 10,
+                ..WasmLimits::default()
+            },
+            ..make_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        match cache.save_wasm(CONTRACT).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert_eq!(msg, "Wasm contract contains more than 10 functions")
+            }
+            e => panic!("Unexpected error {:?}", e),
+        }
+
+        // Unchecked saving ignores the limits
+        cache.save_wasm_unchecked(CONTRACT).unwrap();
+    }
+
     #[test]
     fn save_wasm_fills_file_system_but_not_memory_cache() {
         // Who knows if and when the uploaded contract will be executed. Don't pollute
@@ -607,7 +1378,14 @@ mod tests {
                 base_dir: tmp_dir.path().to_path_buf(),
                 available_capabilities: default_capabilities(),
                 memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
+                memory_cache_policy: MemoryCachePolicy::default(),
                 instance_memory_limit: TESTING_MEMORY_LIMIT,
+                default_gas_limits: GasLimits::uniform(TESTING_GAS_LIMIT),
+                gas_schedule: GasSchedule::default(),
+                persist_pinned: false,
+                instance_pool_size: 0,
+                wasm_limits: WasmLimits::default(),
+                readonly_ibc_channel_open: false,
             };
             let cache1: Cache<MockApi, MockStorage, MockQuerier> =
                 unsafe { Cache::new(options1).unwrap() };
@@ -619,7 +1397,14 @@ mod tests {
                 base_dir: tmp_dir.path().to_path_buf(),
                 available_capabilities: default_capabilities(),
                 memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
+                memory_cache_policy: MemoryCachePolicy::default(),
                 instance_memory_limit: TESTING_MEMORY_LIMIT,
+                default_gas_limits: GasLimits::uniform(TESTING_GAS_LIMIT),
+                gas_schedule: GasSchedule::default(),
+                persist_pinned: false,
+                instance_pool_size: 0,
+                wasm_limits: WasmLimits::default(),
+                readonly_ibc_channel_open: false,
             };
             let cache2: Cache<MockApi, MockStorage, MockQuerier> =
                 unsafe { Cache::new(options2).unwrap() };
@@ -652,7 +1437,14 @@ mod tests {
             base_dir: tmp_dir.path().to_path_buf(),
             available_capabilities: default_capabilities(),
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
+            memory_cache_policy: MemoryCachePolicy::default(),
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            default_gas_limits: GasLimits::uniform(TESTING_GAS_LIMIT),
+            gas_schedule: GasSchedule::default(),
+            persist_pinned: false,
+            instance_pool_size: 0,
+            wasm_limits: WasmLimits::default(),
+            readonly_ibc_channel_open: false,
         };
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
             unsafe { Cache::new(options).unwrap() };
@@ -707,6 +1499,125 @@ mod tests {
         }
     }
 
+    #[test]
+    fn instance_options_uses_default_gas_limits() {
+        let options = CacheOptions {
+            default_gas_limits: GasLimits {
+                query: 3_000_000,
+                execution: TESTING_GAS_LIMIT,
+                ibc: 2 * TESTING_GAS_LIMIT,
+            },
+            ..make_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        assert_eq!(
+            cache.instance_options(EntryPointKind::Query).gas_limit,
+            3_000_000
+        );
+        assert_eq!(
+            cache.instance_options(EntryPointKind::Execution).gas_limit,
+            TESTING_GAS_LIMIT
+        );
+        assert_eq!(
+            cache.instance_options(EntryPointKind::Ibc).gas_limit,
+            2 * TESTING_GAS_LIMIT
+        );
+
+        // the query budget is enforced while the default can still be overridden per call
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let mut instance = cache
+            .get_instance(
+                &checksum,
+                mock_backend(&[]),
+                cache.instance_options(EntryPointKind::Query),
+            )
+            .unwrap();
+        assert_eq!(instance.get_gas_left(), 3_000_000);
+        let options = InstanceOptions {
+            gas_limit: 42_000_000,
+            ..cache.instance_options(EntryPointKind::Query)
+        };
+        instance = cache
+            .get_instance(&checksum, mock_backend(&[]), options)
+            .unwrap();
+        assert_eq!(instance.get_gas_left(), 42_000_000);
+    }
+
+    #[test]
+    fn instance_options_uses_readonly_ibc_channel_open() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        assert!(
+            !cache
+                .instance_options(EntryPointKind::Ibc)
+                .readonly_ibc_channel_open
+        );
+
+        let options = CacheOptions {
+            readonly_ibc_channel_open: true,
+            ..make_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        assert!(
+            cache
+                .instance_options(EntryPointKind::Ibc)
+                .readonly_ibc_channel_open
+        );
+    }
+
+    #[test]
+    fn gas_schedule_is_applied_to_instances() {
+        let options = make_testing_options();
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        let mut gas_schedule = GasSchedule::v1();
+        gas_schedule.wasm_operator_cost *= 2;
+        gas_schedule.host_functions.sha512_cost = 42;
+        let expensive_cache: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new(CacheOptions {
+                gas_schedule,
+                ..options
+            })
+            .unwrap()
+        };
+        assert_eq!(
+            expensive_cache
+                .instance_options(EntryPointKind::Execution)
+                .gas_schedule,
+            gas_schedule
+        );
+
+        let used_gas = |cache: &Cache<MockApi, MockStorage, MockQuerier>| {
+            let mut instance = cache
+                .get_instance(
+                    &checksum,
+                    mock_backend(&[]),
+                    cache.instance_options(EntryPointKind::Execution),
+                )
+                .unwrap();
+            let info = mock_info("creator", &coins(1000, "earth"));
+            let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+            call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+                .unwrap()
+                .unwrap();
+            instance.create_gas_report().used_internally
+        };
+        let default_used = used_gas(&cache);
+        assert_eq!(cache.stats().hits_fs_cache, 1);
+
+        // Modules metered with the default operator cost are not loaded from the file system
+        let expensive_used = used_gas(&expensive_cache);
+        assert_eq!(expensive_cache.stats().hits_fs_cache, 0);
+        assert_eq!(expensive_cache.stats().misses, 1);
+        // almost all internally used gas is spent on executing Wasm operators
+        assert!(expensive_used > 2 * default_used - 1_000_000);
+        assert!(expensive_used < 2 * default_used);
+    }
+
     #[test]
     fn get_instance_finds_cached_module() {
         let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
@@ -1044,6 +1955,11 @@ mod tests {
         let options = InstanceOptions {
             gas_limit: 10,
             print_debug: false,
+            debug_handler: None,
+            readonly_ibc_channel_open: false,
+            gas_schedule: GasSchedule::default(),
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            max_execution_time: None,
         };
         let mut instance1 = cache.get_instance(&checksum, backend1, options).unwrap();
         assert_eq!(cache.stats().hits_fs_cache, 1);
@@ -1064,6 +1980,11 @@ mod tests {
         let options = InstanceOptions {
             gas_limit: TESTING_GAS_LIMIT,
             print_debug: false,
+            debug_handler: None,
+            readonly_ibc_channel_open: false,
+            gas_schedule: GasSchedule::default(),
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            max_execution_time: None,
         };
         let mut instance2 = cache.get_instance(&checksum, backend2, options).unwrap();
         assert_eq!(cache.stats().hits_pinned_memory_cache, 0);
@@ -1146,26 +2067,108 @@ mod tests {
 
         let checksum1 = cache.save_wasm(CONTRACT).unwrap();
         let report1 = cache.analyze(&checksum1).unwrap();
+        assert!(!report1.has_ibc_entry_points);
+        assert_eq!(report1.required_capabilities, HashSet::new());
         assert_eq!(
-            report1,
-            AnalysisReport {
-                has_ibc_entry_points: false,
-                required_capabilities: HashSet::new(),
-            }
+            report1.entry_points,
+            BTreeSet::from(
+                ["execute", "instantiate", "migrate", "query", "sudo"].map(String::from)
+            )
         );
+        assert!(report1.required_imports.contains("env.db_read"));
+        assert!(!report1.has_float_operations);
+        assert_eq!(report1.contract_name, None);
+        assert_eq!(report1.contract_version, None);
 
         let checksum2 = cache.save_wasm(IBC_CONTRACT).unwrap();
         let report2 = cache.analyze(&checksum2).unwrap();
+        assert!(report2.has_ibc_entry_points);
         assert_eq!(
-            report2,
-            AnalysisReport {
-                has_ibc_entry_points: true,
-                required_capabilities: HashSet::from_iter([
-                    "iterator".to_string(),
-                    "stargate".to_string()
-                ]),
-            }
+            report2.required_capabilities,
+            HashSet::from_iter(["iterator".to_string(), "stargate".to_string()])
         );
+        assert_eq!(
+            report2.entry_points,
+            BTreeSet::from(
+                [
+                    "ibc_channel_close",
+                    "ibc_channel_connect",
+                    "ibc_channel_open",
+                    "ibc_packet_ack",
+                    "ibc_packet_receive",
+                    "ibc_packet_timeout",
+                    "instantiate",
+                    "migrate",
+                    "query",
+                    "reply",
+                ]
+                .map(String::from)
+            )
+        );
+        assert!(report2.required_imports.contains("env.query_chain"));
+        assert!(!report2.has_float_operations);
+        assert_eq!(report2.contract_name, None);
+        assert_eq!(report2.contract_version, None);
+    }
+
+    #[test]
+    fn analyze_reads_contract_name_and_version() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+
+        // Append a metadata section to an existing contract
+        let payload = "name=crates.io:hackatom\nversion=0.0.0\ncosmwasm_std_version=1.2.5\n";
+        let mut section = vec![CONTRACT_METADATA_SECTION.len() as u8];
+        section.extend_from_slice(CONTRACT_METADATA_SECTION.as_bytes());
+        section.extend_from_slice(payload.as_bytes());
+        let mut wasm = CONTRACT.to_vec();
+        wasm.push(0); // custom section id
+        wasm.push(section.len() as u8);
+        wasm.extend(section);
+
+        let checksum = cache.save_wasm(&wasm).unwrap();
+        let report = cache.analyze(&checksum).unwrap();
+        assert_eq!(report.contract_name.as_deref(), Some("crates.io:hackatom"));
+        assert_eq!(report.contract_version.as_deref(), Some("0.0.0"));
+    }
+
+    #[test]
+    fn metadata_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        assert_eq!(cache.metadata(&checksum).unwrap(), None);
+
+        // Append a metadata section to an existing contract
+        let payload = "name=hackatom\nversion=0.0.0\ncosmwasm_std_version=1.2.5\nauthors=Alice\n";
+        let mut section = vec![CONTRACT_METADATA_SECTION.len() as u8];
+        section.extend_from_slice(CONTRACT_METADATA_SECTION.as_bytes());
+        section.extend_from_slice(payload.as_bytes());
+        let mut wasm = CONTRACT.to_vec();
+        wasm.push(0); // custom section id
+        wasm.push(section.len() as u8);
+        wasm.extend(section);
+
+        let checksum = cache.save_wasm(&wasm).unwrap();
+        assert_eq!(
+            cache.metadata(&checksum).unwrap(),
+            Some(ContractMetadata {
+                name: "hackatom".to_string(),
+                version: "0.0.0".to_string(),
+                cosmwasm_std_version: "1.2.5".to_string(),
+                authors: vec!["Alice".to_string()],
+            })
+        );
+
+        // Unknown checksum
+        let checksum = Checksum::generate(b"unknown");
+        match cache.metadata(&checksum).unwrap_err() {
+            VmError::CacheErr { msg, .. } => {
+                assert_eq!(msg, "Error opening Wasm file for reading")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
     }
 
     #[test]
@@ -1228,6 +2231,775 @@ mod tests {
         cache.unpin(&non_id).unwrap();
     }
 
+    #[test]
+    fn preload_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_stargate_testing_options()).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(IBC_CONTRACT).unwrap();
+        assert_eq!(cache.metrics().elements_memory_cache, 0);
+
+        // Module 2 is not in the file system cache and needs to be compiled
+        cache
+            .inner
+            .lock()
+            .unwrap()
+            .fs_cache
+            .remove(&checksum2)
+            .unwrap();
+        cache.preload(&[checksum1, checksum2]).unwrap();
+        assert_eq!(cache.metrics().elements_memory_cache, 2);
+        // One compilation in `save_wasm` and one in `preload`
+        assert_eq!(cache.stats_per_checksum()[&checksum1].compilations, 1);
+        assert_eq!(cache.stats_per_checksum()[&checksum2].compilations, 2);
+        // Preloading does not count as a hit
+        assert_eq!(cache.stats().hits_fs_cache, 0);
+        assert_eq!(cache.stats().misses, 0);
+
+        let _instance = cache
+            .get_instance(&checksum2, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_memory_cache, 1);
+
+        // Preloading again does nothing
+        cache.preload(&[checksum1, checksum2]).unwrap();
+        assert_eq!(cache.metrics().elements_memory_cache, 2);
+        assert_eq!(cache.stats_per_checksum()[&checksum2].compilations, 2);
+    }
+
+    #[test]
+    fn preload_with_threads_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_stargate_testing_options()).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(IBC_CONTRACT).unwrap();
+        let unknown = Checksum::from([0x42; 32]);
+        for checksum in [checksum1, checksum2] {
+            cache
+                .inner
+                .lock()
+                .unwrap()
+                .fs_cache
+                .remove(&checksum)
+                .unwrap();
+        }
+
+        // Errors do not prevent the other modules from being preloaded
+        match cache
+            .preload_with_threads(&[checksum1, unknown, checksum2], 4)
+            .unwrap_err()
+        {
+            VmError::CacheErr { msg, .. } => assert_eq!(msg, "Error opening Wasm file for reading"),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        assert_eq!(cache.metrics().elements_memory_cache, 2);
+        assert_eq!(cache.stats_per_checksum()[&checksum1].compilations, 2);
+        assert_eq!(cache.stats_per_checksum()[&checksum2].compilations, 2);
+
+        // Zero threads and an empty list are fine
+        cache.preload_with_threads(&[checksum1], 0).unwrap();
+        cache.preload_with_threads(&[], 4).unwrap();
+    }
+
+    #[test]
+    fn get_pooled_instance_works() {
+        let options = CacheOptions {
+            instance_pool_size: 2,
+            ..make_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        let mut instance = cache
+            .get_pooled_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let fresh_memory = instance.memory_snapshot().unwrap();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        assert!(instance.memory_snapshot().unwrap() != fresh_memory);
+        let backend = cache.return_pooled_instance(&checksum, instance).unwrap();
+        assert_eq!(cache.metrics().elements_instance_pool, 1);
+        assert_eq!(cache.stats().hits_fs_cache, 1);
+
+        // The reused instance is wiped and works with the storage of the previous call
+        let mut instance = cache
+            .get_pooled_instance(&checksum, backend, TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.metrics().elements_instance_pool, 0);
+        assert_eq!(cache.stats().hits_memory_cache, 0);
+        let memory = instance.memory_snapshot().unwrap();
+        assert!(memory[..fresh_memory.len()] == fresh_memory[..]);
+        assert!(memory[fresh_memory.len()..].iter().all(|byte| *byte == 0));
+        assert_eq!(instance.get_gas_left(), TESTING_GAS_LIMIT);
+        let res = call_query(&mut instance, &mock_env(), br#"{"verifier":{}}"#).unwrap();
+        assert_eq!(
+            res.unwrap(),
+            cosmwasm_std::Binary::from(br#"{"verifier":"verifies"}"#)
+        );
+        assert!(instance.get_gas_left() < TESTING_GAS_LIMIT);
+        cache.return_pooled_instance(&checksum, instance).unwrap();
+        assert_eq!(cache.metrics().elements_instance_pool, 1);
+
+        // Removing the Wasm empties the pool
+        cache.remove_wasm(&checksum).unwrap();
+        assert_eq!(cache.metrics().elements_instance_pool, 0);
+    }
+
+    #[test]
+    fn get_pooled_instance_resets_globals() {
+        let options = CacheOptions {
+            instance_pool_size: 1,
+            ..make_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1)
+                (export "memory" (memory 0))
+                (global $internal (mut i32) (i32.const 0))
+                (global $exported (mut i64) (i64.const 7))
+                (export "exported" (global $exported))
+
+                (func (export "increment") (result i32)
+                    global.get $internal
+                    i32.const 1
+                    i32.add
+                    global.set $internal
+                    global.get $exported
+                    i64.const 1
+                    i64.add
+                    global.set $exported
+                    global.get $internal)
+                (func (export "exported_value") (result i64) global.get $exported)
+            )"#,
+        )
+        .unwrap();
+        let checksum = cache.save_wasm_unchecked(&wasm).unwrap();
+
+        let mut backend = mock_backend(&[]);
+        for _ in 0..2 {
+            let mut instance = cache
+                .get_pooled_instance(&checksum, backend, TESTING_OPTIONS)
+                .unwrap();
+            let internal = instance.call_function1("increment", &[]).unwrap();
+            assert_eq!(internal.unwrap_i32(), 1);
+            let exported = instance.call_function1("exported_value", &[]).unwrap();
+            assert_eq!(exported.unwrap_i64(), 8);
+            backend = cache.return_pooled_instance(&checksum, instance).unwrap();
+        }
+        // The second call used the pooled instance
+        assert_eq!(cache.stats().hits_fs_cache, 1);
+        assert_eq!(cache.stats().hits_memory_cache, 0);
+        assert_eq!(cache.metrics().elements_instance_pool, 1);
+    }
+
+    #[test]
+    fn debug_handler_receives_checksum() {
+        let options = CacheOptions {
+            instance_pool_size: 1,
+            ..make_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        let checksum = cache.save_wasm(CYBERPUNK).unwrap();
+
+        let checksums = Arc::new(Mutex::new(Vec::<Option<Checksum>>::new()));
+        let checksums_clone = Arc::clone(&checksums);
+        let options = InstanceOptions {
+            gas_limit: 70_000_000_000_000,
+            debug_handler: Some(SharedDebugHandler::new(move |_msg, info| {
+                checksums_clone.lock().unwrap().push(info.checksum.copied());
+            })),
+            ..TESTING_OPTIONS
+        };
+
+        let mut backend = mock_backend(&[]);
+        // The second instance is reused from the pool
+        for _ in 0..2 {
+            let mut instance = cache
+                .get_pooled_instance(&checksum, backend, options.clone())
+                .unwrap();
+            let info = mock_info("caller", &[]);
+            call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, br#"{"debug":{}}"#)
+                .unwrap()
+                .unwrap();
+            backend = cache.return_pooled_instance(&checksum, instance).unwrap();
+        }
+        assert_eq!(cache.metrics().elements_instance_pool, 1);
+
+        let checksums = checksums.lock().unwrap();
+        assert!(checksums.len() >= 2);
+        assert!(checksums.iter().all(|c| *c == Some(checksum)));
+    }
+
+    #[test]
+    fn get_pooled_instance_limits_pool_size() {
+        let options = CacheOptions {
+            instance_pool_size: 1,
+            ..make_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        let instance1 = cache
+            .get_pooled_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let instance2 = cache
+            .get_pooled_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        cache.return_pooled_instance(&checksum, instance1).unwrap();
+        cache.return_pooled_instance(&checksum, instance2).unwrap();
+        assert_eq!(cache.metrics().elements_instance_pool, 1);
+
+        // Pooling can be disabled
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let instance = cache
+            .get_pooled_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        cache.return_pooled_instance(&checksum, instance).unwrap();
+        assert_eq!(cache.metrics().elements_instance_pool, 0);
+    }
+
+    #[test]
+    fn get_pooled_instance_drops_failed_instances() {
+        let options = CacheOptions {
+            instance_pool_size: 2,
+            ..make_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        let low_gas = InstanceOptions {
+            gas_limit: 10,
+            ..TESTING_OPTIONS
+        };
+        let mut instance = cache
+            .get_pooled_instance(&checksum, mock_backend(&[]), low_gas)
+            .unwrap();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        match call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap_err()
+        {
+            VmError::GasDepletion { .. } => {}
+            e => panic!("unexpected error, {:?}", e),
+        }
+        // The backend is still returned
+        assert!(cache.return_pooled_instance(&checksum, instance).is_some());
+        assert_eq!(cache.metrics().elements_instance_pool, 0);
+    }
+
+    #[test]
+    fn get_query_instance_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        // Create state
+        let mut instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let backend = instance.recycle().unwrap();
+        let snapshot = Arc::new(backend.storage);
+
+        // Multiple instances read from the same snapshot
+        let mut instance1 = cache
+            .get_query_instance(
+                &checksum,
+                backend.api,
+                snapshot.clone(),
+                MockQuerier::new(&[]),
+                TESTING_OPTIONS,
+            )
+            .unwrap();
+        let mut instance2 = cache
+            .get_query_instance(
+                &checksum,
+                backend.api,
+                snapshot.clone(),
+                MockQuerier::new(&[]),
+                TESTING_OPTIONS,
+            )
+            .unwrap();
+        for instance in [&mut instance1, &mut instance2] {
+            let res = call_query(instance, &mock_env(), br#"{"verifier":{}}"#).unwrap();
+            assert_eq!(
+                res.unwrap(),
+                cosmwasm_std::Binary::from(br#"{"verifier":"verifies"}"#)
+            );
+        }
+
+        // Writing fails even outside of queries
+        match call_instantiate::<_, _, _, Empty>(&mut instance1, &mock_env(), &info, msg)
+            .unwrap_err()
+        {
+            VmError::WriteAccessDenied { .. } => {}
+            e => panic!("unexpected error, {:?}", e),
+        }
+        assert_eq!(Arc::strong_count(&snapshot), 3);
+    }
+
+    #[test]
+    fn pinned_checksums_works() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_stargate_testing_options()).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(IBC_CONTRACT).unwrap();
+        assert_eq!(cache.pinned_checksums(), Vec::<Checksum>::new());
+
+        cache.pin(&checksum1).unwrap();
+        cache.pin(&checksum2).unwrap();
+        let mut expected = vec![checksum1, checksum2];
+        expected.sort_by_key(|checksum| checksum.to_hex());
+        assert_eq!(cache.pinned_checksums(), expected);
+
+        cache.unpin(&checksum1).unwrap();
+        assert_eq!(cache.pinned_checksums(), vec![checksum2]);
+    }
+
+    #[test]
+    fn remove_wasm_removes_module_from_memory_cache() {
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let _instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.metrics().elements_memory_cache, 1);
+
+        cache.remove_wasm(&checksum).unwrap();
+        assert_eq!(cache.metrics().elements_memory_cache, 0);
+        assert_eq!(cache.metrics().size_memory_cache, 0);
+    }
+
+    #[test]
+    fn gc_works() {
+        let options = CacheOptions {
+            persist_pinned: true,
+            ..make_stargate_testing_options()
+        };
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(IBC_CONTRACT).unwrap();
+        let checksum3 = cache.save_wasm(CYBERPUNK).unwrap();
+        let _instance = cache
+            .get_instance(&checksum1, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        let _instance = cache
+            .get_instance(&checksum2, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        cache.pin(&checksum3).unwrap();
+        assert_eq!(cache.metrics().elements_memory_cache, 2);
+
+        // Unrelated files are kept
+        let wasm_path = options.base_dir.join(STATE_DIR).join(WASM_DIR);
+        fs::write(wasm_path.join("README.md"), b"Do not delete").unwrap();
+
+        let report = cache.gc(&[checksum1]).unwrap();
+        assert_eq!(
+            report,
+            GcReport {
+                removed_wasm: 2,
+                removed_modules: 2,
+                removed_module_versions: 0,
+            }
+        );
+        cache.load_wasm(&checksum1).unwrap();
+        cache.load_wasm(&checksum2).unwrap_err();
+        cache.load_wasm(&checksum3).unwrap_err();
+        assert!(wasm_path.join("README.md").exists());
+        assert_eq!(cache.metrics().elements_memory_cache, 1);
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 0);
+        assert_eq!(cache.pinned_checksums(), Vec::<Checksum>::new());
+        assert_eq!(
+            cache.stats_per_checksum().keys().collect::<Vec<_>>(),
+            vec![&checksum1]
+        );
+
+        // The kept contract still works and is loaded from the memory cache
+        let _instance = cache
+            .get_instance(&checksum1, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_memory_cache, 1);
+
+        // Nothing left to collect
+        assert_eq!(cache.gc(&[checksum1]).unwrap(), GcReport::default());
+
+        // Unpinning is persisted
+        drop(cache);
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        assert_eq!(cache.pinned_checksums(), Vec::<Checksum>::new());
+    }
+
+    #[test]
+    fn migrate_works() {
+        let options = make_stargate_testing_options();
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(IBC_CONTRACT).unwrap();
+        assert_eq!(cache.outdated_modules(), Vec::<Checksum>::new());
+        drop(cache);
+
+        // Strip the headers as if the modules were written by a previous version
+        let modules_path = options.base_dir.join(CACHE_DIR).join(MODULES_DIR);
+        let pattern = format!("{}/v*/*/*", modules_path.to_string_lossy());
+        for path in glob::glob(&pattern).unwrap() {
+            let path = path.unwrap();
+            let content = fs::read(&path).unwrap();
+            fs::write(&path, &content[16..]).unwrap();
+        }
+        let obsolete_path = modules_path.join("v4-wasmer3");
+        fs::create_dir_all(&obsolete_path).unwrap();
+        // The Wasm blob of contract 2 is gone
+        remove_wasm_from_disk(options.base_dir.join(STATE_DIR).join(WASM_DIR), &checksum2).unwrap();
+
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        let mut expected = vec![checksum1, checksum2];
+        expected.sort_by_key(|checksum| checksum.to_hex());
+        assert_eq!(cache.outdated_modules(), expected);
+
+        let mut reported = Vec::new();
+        cache.migrate(|progress| reported.push(progress)).unwrap();
+        assert_eq!(reported.len(), 2);
+        for (index, progress) in reported.iter().enumerate() {
+            assert_eq!(progress.checksum, expected[index]);
+            assert_eq!(progress.done, index + 1);
+            assert_eq!(progress.total, 2);
+            let expected_action = if progress.checksum == checksum1 {
+                MigrationAction::Recompiled
+            } else {
+                MigrationAction::Purged
+            };
+            assert_eq!(progress.action, expected_action);
+        }
+        assert_eq!(cache.outdated_modules(), Vec::<Checksum>::new());
+        assert!(!obsolete_path.exists());
+
+        // The recompiled module is loaded from the file system cache
+        let _instance = cache
+            .get_instance(&checksum1, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_fs_cache, 1);
+        assert_eq!(cache.stats().misses, 0);
+
+        // Nothing left to do
+        cache.migrate(|_| panic!("Nothing to migrate")).unwrap();
+    }
+
+    #[test]
+    fn persist_pinned_works() {
+        let options = CacheOptions {
+            persist_pinned: true,
+            ..make_testing_options()
+        };
+
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        cache.pin(&checksum).unwrap();
+        drop(cache);
+
+        // Pinned again on restart
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        assert_eq!(cache.pinned_checksums(), vec![checksum]);
+        assert_eq!(cache.metrics().elements_pinned_memory_cache, 1);
+        let _instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_pinned_memory_cache, 1);
+
+        // Compiles the module if it is not in the file system cache
+        cache
+            .inner
+            .lock()
+            .unwrap()
+            .fs_cache
+            .remove(&checksum)
+            .unwrap();
+        drop(cache);
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        assert_eq!(cache.pinned_checksums(), vec![checksum]);
+        assert_eq!(cache.stats_per_checksum()[&checksum].compilations, 1);
+
+        // Unpinning is persisted too
+        cache.unpin(&checksum).unwrap();
+        drop(cache);
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        assert_eq!(cache.pinned_checksums(), Vec::<Checksum>::new());
+
+        // Removing the Wasm removes the pin
+        cache.pin(&checksum).unwrap();
+        cache.remove_wasm(&checksum).unwrap();
+        drop(cache);
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        assert_eq!(cache.pinned_checksums(), Vec::<Checksum>::new());
+
+        // Pins are not restored without the option
+        cache.save_wasm(CONTRACT).unwrap();
+        cache.pin(&checksum).unwrap();
+        drop(cache);
+        let cache: Cache<MockApi, MockStorage, MockQuerier> = unsafe {
+            Cache::new(CacheOptions {
+                persist_pinned: false,
+                instance_pool_size: 0,
+                ..options
+            })
+            .unwrap()
+        };
+        assert_eq!(cache.pinned_checksums(), Vec::<Checksum>::new());
+    }
+
+    #[test]
+    fn persist_pinned_skips_contracts_that_cannot_be_pinned() {
+        let options = CacheOptions {
+            persist_pinned: true,
+            ..make_testing_options()
+        };
+
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(CYBERPUNK).unwrap();
+        cache.pin(&checksum1).unwrap();
+        cache.pin(&checksum2).unwrap();
+        assert_eq!(cache.unrestored_pins().len(), 0);
+
+        // The Wasm and module of the first contract are deleted behind the cache's back
+        let wasm_path = cache.inner.lock().unwrap().wasm_path.clone();
+        fs::remove_file(wasm_path.join(checksum1.to_hex()).with_extension("wasm")).unwrap();
+        cache
+            .inner
+            .lock()
+            .unwrap()
+            .fs_cache
+            .remove(&checksum1)
+            .unwrap();
+        drop(cache);
+
+        // The other contract is still pinned
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        assert_eq!(cache.pinned_checksums(), vec![checksum2]);
+        let unrestored = cache.unrestored_pins();
+        assert_eq!(unrestored.len(), 1);
+        assert_eq!(unrestored[0].0, checksum1);
+        match &unrestored[0].1 {
+            VmError::CacheErr { msg, .. } => {
+                assert_eq!(msg, "Error opening Wasm file for reading")
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        drop(cache);
+
+        // The failed pin was removed from disk
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        assert_eq!(cache.pinned_checksums(), vec![checksum2]);
+        assert_eq!(cache.unrestored_pins().len(), 0);
+    }
+
+    #[test]
+    fn persist_pinned_does_not_write_while_restoring() {
+        let options = CacheOptions {
+            persist_pinned: true,
+            ..make_testing_options()
+        };
+
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options.clone()).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(CYBERPUNK).unwrap();
+        cache.pin(&checksum1).unwrap();
+        cache.pin(&checksum2).unwrap();
+        drop(cache);
+
+        // Any attempt to write the pinned checksums fails from now on
+        let pinned_path = options.base_dir.join(STATE_DIR).join(PINNED_FILE);
+        fs::create_dir(pinned_path.with_extension("tmp")).unwrap();
+
+        let cache: Cache<MockApi, MockStorage, MockQuerier> =
+            unsafe { Cache::new(options).unwrap() };
+        let mut expected = vec![checksum1, checksum2];
+        expected.sort_by_key(|checksum| checksum.to_hex());
+        assert_eq!(cache.pinned_checksums(), expected);
+        assert_eq!(cache.unrestored_pins().len(), 0);
+    }
+
+    #[test]
+    fn load_pinned_from_disk_works() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join(PINNED_FILE);
+        assert_eq!(
+            load_pinned_from_disk(&path).unwrap(),
+            Vec::<Checksum>::new()
+        );
+
+        let checksums = vec![Checksum::from([0x11; 32]), Checksum::from([0xab; 32])];
+        save_pinned_to_disk(&path, &checksums).unwrap();
+        assert_eq!(load_pinned_from_disk(&path).unwrap(), checksums);
+
+        fs::write(&path, "nothex\n").unwrap();
+        match load_pinned_from_disk(&path).unwrap_err() {
+            VmError::CacheErr { msg, .. } => assert_eq!(msg, "Invalid pinned checksum: nothex"),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn stats_per_checksum_works() {
+        let cache = unsafe { Cache::new(make_stargate_testing_options()).unwrap() };
+        assert!(cache.stats_per_checksum().is_empty());
+
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let stats = cache.stats_per_checksum()[&checksum];
+        assert_eq!(stats.compilations, 1);
+        assert!(stats.compilation_time > Duration::ZERO);
+        assert!(stats.module_size > 0);
+        assert_eq!(stats.hits_fs_cache, 0);
+
+        // first hits the file system cache, then the memory cache
+        for _ in 0..3 {
+            let _instance = cache
+                .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+                .unwrap();
+        }
+        let other_checksum = cache.save_wasm(IBC_CONTRACT).unwrap();
+        cache.pin(&other_checksum).unwrap();
+        let _instance = cache
+            .get_instance(&other_checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+
+        let stats = cache.stats_per_checksum();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[&checksum].hits_pinned_memory_cache, 0);
+        assert_eq!(stats[&checksum].hits_memory_cache, 2);
+        assert_eq!(stats[&checksum].hits_fs_cache, 1);
+        assert_eq!(stats[&checksum].misses, 0);
+        assert_eq!(stats[&checksum].compilations, 1);
+        assert_eq!(stats[&other_checksum].hits_pinned_memory_cache, 1);
+        assert_eq!(stats[&other_checksum].hits_memory_cache, 0);
+        assert_eq!(stats[&other_checksum].hits_fs_cache, 1);
+
+        // removing the Wasm removes the statistics
+        cache.remove_wasm(&checksum).unwrap();
+        assert_eq!(cache.stats_per_checksum().len(), 1);
+    }
+
+    #[test]
+    fn metrics_works() {
+        let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        let _instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.stats.hits_fs_cache, 1);
+        assert_eq!(metrics.elements_pinned_memory_cache, 0);
+        assert_eq!(metrics.elements_memory_cache, 1);
+        assert_eq!(
+            metrics.size_memory_cache,
+            cache.stats_per_checksum()[&checksum].module_size
+        );
+        assert_eq!(metrics.capacity_memory_cache, TESTING_MEMORY_CACHE_SIZE.0);
+    }
+
+    #[test]
+    fn memory_cache_policy_is_applied() {
+        let options = CacheOptions {
+            memory_cache_policy: MemoryCachePolicy {
+                max_entries: Some(1),
+                ..MemoryCachePolicy::default()
+            },
+            ..make_stargate_testing_options()
+        };
+        let cache = unsafe { Cache::new(options).unwrap() };
+        let checksum1 = cache.save_wasm(CONTRACT).unwrap();
+        let checksum2 = cache.save_wasm(IBC_CONTRACT).unwrap();
+
+        cache
+            .get_instance(&checksum1, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        cache
+            .get_instance(&checksum2, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.metrics().elements_memory_cache, 1);
+
+        // The first module was evicted and must be loaded from disk again
+        cache
+            .get_instance(&checksum1, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_memory_cache, 0);
+        assert_eq!(cache.stats().hits_fs_cache, 3);
+    }
+
+    #[test]
+    fn metrics_sink_is_notified() {
+        #[derive(Default)]
+        struct RecordingSink {
+            loads: Mutex<Vec<(Checksum, ModuleSource)>>,
+            compilations: Mutex<Vec<Checksum>>,
+        }
+
+        impl CacheMetricsSink for RecordingSink {
+            fn module_loaded(&self, checksum: &Checksum, source: ModuleSource, _size: usize) {
+                self.loads.lock().unwrap().push((*checksum, source));
+            }
+
+            fn module_compiled(&self, checksum: &Checksum, _duration: Duration, _size: usize) {
+                self.compilations.lock().unwrap().push(*checksum);
+            }
+        }
+
+        let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
+        let sink = Arc::new(RecordingSink::default());
+        cache.set_metrics_sink(sink.clone());
+
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+        for _ in 0..2 {
+            let _instance = cache
+                .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+                .unwrap();
+        }
+        assert_eq!(*sink.compilations.lock().unwrap(), vec![checksum]);
+        assert_eq!(
+            *sink.loads.lock().unwrap(),
+            vec![
+                (checksum, ModuleSource::FileSystemCache),
+                (checksum, ModuleSource::MemoryCache)
+            ]
+        );
+
+        // no more events after unsetting
+        cache.unset_metrics_sink();
+        let _instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(sink.loads.lock().unwrap().len(), 2);
+    }
+
     #[test]
     fn loading_without_extension_works() {
         let tmp_dir = TempDir::new().unwrap();
@@ -1235,7 +3007,14 @@ mod tests {
             base_dir: tmp_dir.path().to_path_buf(),
             available_capabilities: default_capabilities(),
             memory_cache_size: TESTING_MEMORY_CACHE_SIZE,
+            memory_cache_policy: MemoryCachePolicy::default(),
             instance_memory_limit: TESTING_MEMORY_LIMIT,
+            default_gas_limits: GasLimits::uniform(TESTING_GAS_LIMIT),
+            gas_schedule: GasSchedule::default(),
+            persist_pinned: false,
+            instance_pool_size: 0,
+            wasm_limits: WasmLimits::default(),
+            readonly_ibc_channel_open: false,
         };
         let cache: Cache<MockApi, MockStorage, MockQuerier> =
             unsafe { Cache::new(options).unwrap() };
@@ -1,13 +1,20 @@
 use serde::de::DeserializeOwned;
 use wasmer::Value;
 
-use cosmwasm_std::{ContractResult, CustomMsg, Env, MessageInfo, QueryResponse, Reply, Response};
+#[cfg(feature = "cosmwasm_1_5")]
+use cosmwasm_std::MigrateInfo;
+use cosmwasm_std::{
+    ContractResult, CustomMsg, Env, MessageInfo, QueryResponse, Reply, ReplyOn, Response, SubMsg,
+    SubMsgResult,
+};
 #[cfg(feature = "stargate")]
 use cosmwasm_std::{
     Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
     IbcChannelOpenMsg, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
     IbcReceiveResponse,
 };
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+use cosmwasm_std::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
 
 use crate::backend::{BackendApi, Querier, Storage};
 use crate::conversion::ref_to_u32;
@@ -54,6 +61,12 @@ mod read_limits {
     /// Max length (in bytes) of the result data from a ibc_packet_timeout call.
     #[cfg(feature = "stargate")]
     pub const RESULT_IBC_PACKET_TIMEOUT: usize = 64 * MI;
+    /// Max length (in bytes) of the result data from a ibc_source_callback call.
+    #[cfg(feature = "stargate")]
+    pub const RESULT_IBC_SOURCE_CALLBACK: usize = 64 * MI;
+    /// Max length (in bytes) of the result data from a ibc_destination_callback call.
+    #[cfg(feature = "stargate")]
+    pub const RESULT_IBC_DESTINATION_CALLBACK: usize = 64 * MI;
 }
 
 /// The limits for the JSON deserialization.
@@ -93,6 +106,12 @@ mod deserialization_limits {
     /// Max length (in bytes) of the result data from a ibc_packet_timeout call.
     #[cfg(feature = "stargate")]
     pub const RESULT_IBC_PACKET_TIMEOUT: usize = 256 * KI;
+    /// Max length (in bytes) of the result data from a ibc_source_callback call.
+    #[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+    pub const RESULT_IBC_SOURCE_CALLBACK: usize = 256 * KI;
+    /// Max length (in bytes) of the result data from a ibc_destination_callback call.
+    #[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+    pub const RESULT_IBC_DESTINATION_CALLBACK: usize = 256 * KI;
 }
 
 pub fn call_instantiate<A, S, Q, U>(
@@ -153,6 +172,31 @@ where
     Ok(result)
 }
 
+/// Calls the `migrate` entry point with a [`MigrateInfo`].
+///
+/// The info is only passed to contracts whose `migrate` export takes it as
+/// third argument, such that older contracts can be migrated the same way.
+#[cfg(feature = "cosmwasm_1_5")]
+pub fn call_migrate_with_info<A, S, Q, U>(
+    instance: &mut Instance<A, S, Q>,
+    env: &Env,
+    msg: &[u8],
+    migrate_info: &MigrateInfo,
+) -> VmResult<ContractResult<Response<U>>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+    U: DeserializeOwned + CustomMsg,
+{
+    let env = to_vec(env)?;
+    let migrate_info = to_vec(migrate_info)?;
+    let data = call_migrate_with_info_raw(instance, &env, msg, &migrate_info)?;
+    let result: ContractResult<Response<U>> =
+        from_slice(&data, deserialization_limits::RESULT_MIGRATE)?;
+    Ok(result)
+}
+
 pub fn call_sudo<A, S, Q, U>(
     instance: &mut Instance<A, S, Q>,
     env: &Env,
@@ -190,6 +234,25 @@ where
     Ok(result)
 }
 
+/// Creates the [`Reply`] the host sends to the contract after executing `sub_msg` with the
+/// given result. The `id` and `payload` of the submessage are passed back unchanged, such
+/// that the contract can correlate the reply with the original action.
+///
+/// Returns `None` if `sub_msg.reply_on` does not ask for a reply in this case.
+pub fn reply_for_sub_msg<U>(sub_msg: &SubMsg<U>, result: SubMsgResult) -> Option<Reply> {
+    let send_reply = matches!(
+        (&sub_msg.reply_on, &result),
+        (ReplyOn::Always, _)
+            | (ReplyOn::Success, SubMsgResult::Ok(_))
+            | (ReplyOn::Error, SubMsgResult::Err(_))
+    );
+    send_reply.then(|| Reply {
+        id: sub_msg.id,
+        payload: sub_msg.payload.clone(),
+        result,
+    })
+}
+
 pub fn call_query<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     env: &Env,
@@ -328,6 +391,47 @@ where
     Ok(result)
 }
 
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub fn call_ibc_source_callback<A, S, Q, U>(
+    instance: &mut Instance<A, S, Q>,
+    env: &Env,
+    msg: &IbcSourceCallbackMsg,
+) -> VmResult<ContractResult<IbcBasicResponse<U>>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+    U: DeserializeOwned + CustomMsg,
+{
+    let env = to_vec(env)?;
+    let msg = to_vec(msg)?;
+    let data = call_ibc_source_callback_raw(instance, &env, &msg)?;
+    let result = from_slice(&data, deserialization_limits::RESULT_IBC_SOURCE_CALLBACK)?;
+    Ok(result)
+}
+
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub fn call_ibc_destination_callback<A, S, Q, U>(
+    instance: &mut Instance<A, S, Q>,
+    env: &Env,
+    msg: &IbcDestinationCallbackMsg,
+) -> VmResult<ContractResult<IbcBasicResponse<U>>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+    U: DeserializeOwned + CustomMsg,
+{
+    let env = to_vec(env)?;
+    let msg = to_vec(msg)?;
+    let data = call_ibc_destination_callback_raw(instance, &env, &msg)?;
+    let result = from_slice(
+        &data,
+        deserialization_limits::RESULT_IBC_DESTINATION_CALLBACK,
+    )?;
+    Ok(result)
+}
+
 /// Calls Wasm export "instantiate" and returns raw data from the contract.
 /// The result is length limited to prevent abuse but otherwise unchecked.
 pub fn call_instantiate_raw<A, S, Q>(
@@ -393,6 +497,33 @@ where
     )
 }
 
+/// Calls Wasm export "migrate" with a serialized [`MigrateInfo`] and returns raw data
+/// from the contract. If the export only takes env and msg, the info is not passed.
+/// The result is length limited to prevent abuse but otherwise unchecked.
+#[cfg(feature = "cosmwasm_1_5")]
+pub fn call_migrate_with_info_raw<A, S, Q>(
+    instance: &mut Instance<A, S, Q>,
+    env: &[u8],
+    msg: &[u8],
+    migrate_info: &[u8],
+) -> VmResult<Vec<u8>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    if instance.function_arity("migrate") != Some(3) {
+        return call_migrate_raw(instance, env, msg);
+    }
+    instance.set_storage_readonly(false);
+    call_raw(
+        instance,
+        "migrate",
+        &[env, msg, migrate_info],
+        read_limits::RESULT_MIGRATE,
+    )
+}
+
 /// Calls Wasm export "sudo" and returns raw data from the contract.
 /// The result is length limited to prevent abuse but otherwise unchecked.
 pub fn call_sudo_raw<A, S, Q>(
@@ -452,7 +583,8 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
-    instance.set_storage_readonly(false);
+    let readonly = instance.readonly_ibc_channel_open();
+    instance.set_storage_readonly(readonly);
     call_raw(
         instance,
         "ibc_channel_open",
@@ -561,6 +693,46 @@ where
     )
 }
 
+#[cfg(feature = "stargate")]
+pub fn call_ibc_source_callback_raw<A, S, Q>(
+    instance: &mut Instance<A, S, Q>,
+    env: &[u8],
+    msg: &[u8],
+) -> VmResult<Vec<u8>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    instance.set_storage_readonly(false);
+    call_raw(
+        instance,
+        "ibc_source_callback",
+        &[env, msg],
+        read_limits::RESULT_IBC_SOURCE_CALLBACK,
+    )
+}
+
+#[cfg(feature = "stargate")]
+pub fn call_ibc_destination_callback_raw<A, S, Q>(
+    instance: &mut Instance<A, S, Q>,
+    env: &[u8],
+    msg: &[u8],
+) -> VmResult<Vec<u8>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    instance.set_storage_readonly(false);
+    call_raw(
+        instance,
+        "ibc_destination_callback",
+        &[env, msg],
+        read_limits::RESULT_IBC_DESTINATION_CALLBACK,
+    )
+}
+
 /// Calls a function with the given arguments.
 /// The exported function must return exactly one result (an offset to the result Region).
 pub(crate) fn call_raw<A, S, Q>(
@@ -574,13 +746,19 @@ where
     S: Storage + 'static,
     Q: Querier + 'static,
 {
+    // Contracts using an arena allocator get a fresh arena for every call
+    instance.reset_memory()?;
+
     let mut arg_region_ptrs = Vec::<Value>::with_capacity(args.len());
     for arg in args {
         let region_ptr = instance.allocate(arg.len())?;
         instance.write_memory(region_ptr, arg)?;
         arg_region_ptrs.push(region_ptr.into());
     }
-    let result = instance.call_function1(name, &arg_region_ptrs)?;
+    instance.set_entry_point(Some(name));
+    let result = instance.call_function1(name, &arg_region_ptrs);
+    instance.set_entry_point(None);
+    let result = result?;
     let res_region_ptr = ref_to_u32(&result)?;
     let data = instance.read_memory(res_region_ptr, result_max_length)?;
     // free return value in wasm (arguments were freed in wasm code)
@@ -592,7 +770,7 @@ where
 mod tests {
     use super::*;
     use crate::testing::{mock_env, mock_info, mock_instance};
-    use cosmwasm_std::{coins, Empty};
+    use cosmwasm_std::{coins, BankMsg, Empty, SubMsg};
 
     static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
     static CYBERPUNK: &[u8] = include_bytes!("../testdata/cyberpunk.wasm");
@@ -717,6 +895,107 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "cosmwasm_1_5")]
+    #[test]
+    fn call_migrate_with_info_works() {
+        use cosmwasm_std::Addr;
+
+        let mut instance = mock_instance(CONTRACT, &[]);
+
+        // init
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // hackatom's migrate does not take the info, so it is not passed
+        let msg = br#"{"verifier": "someone else"}"#;
+        let migrate_info = MigrateInfo {
+            sender: Addr::unchecked("admin"),
+            old_migrate_version: Some(2),
+        };
+        call_migrate_with_info::<_, _, _, Empty>(&mut instance, &mock_env(), msg, &migrate_info)
+            .unwrap()
+            .unwrap();
+
+        let msg = br#"{"verifier":{}}"#;
+        let contract_result = call_query(&mut instance, &mock_env(), msg).unwrap();
+        assert_eq!(
+            contract_result.unwrap().as_slice(),
+            b"{\"verifier\":\"someone else\"}"
+        );
+    }
+
+    #[cfg(feature = "cosmwasm_1_5")]
+    #[test]
+    fn call_migrate_with_info_raw_passes_info() {
+        use crate::testing::{mock_backend, mock_instance_options};
+
+        // A contract with a bump allocator whose migrate returns the info it gets
+        let wasm = wat::parse_str(
+            r#"(module
+            (memory 1)
+            (export "memory" (memory 0))
+            (global $heap (mut i32) (i32.const 1024))
+
+            (func (export "interface_version_8"))
+            (func (export "allocate") (param $size i32) (result i32)
+                (local $region i32)
+                (local.set $region (global.get $heap))
+                (i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+                (i32.store offset=4 (local.get $region) (local.get $size))
+                (i32.store offset=8 (local.get $region) (i32.const 0))
+                (global.set $heap
+                    (i32.add (local.get $region) (i32.add (local.get $size) (i32.const 12))))
+                (local.get $region))
+            (func (export "deallocate") (param i32))
+            (func (export "migrate") (param i32 i32 i32) (result i32) (local.get 2))
+        )"#,
+        )
+        .unwrap();
+        let (instance_options, memory_limit) = mock_instance_options();
+        let mut instance =
+            Instance::from_code(&wasm, mock_backend(&[]), instance_options, memory_limit).unwrap();
+        assert_eq!(instance.function_arity("migrate"), Some(3));
+        assert_eq!(instance.function_arity("execute"), None);
+
+        let data = call_migrate_with_info_raw(&mut instance, b"{}", b"{}", b"info").unwrap();
+        assert_eq!(data, b"info");
+    }
+
+    #[test]
+    fn call_sudo_works() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+
+        // init
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // sudo
+        let msg = br#"{"steal_funds":{"recipient":"community-pool","amount":[{"amount":"700","denom":"gold"}]}}"#;
+        let res = call_sudo::<_, _, _, Empty>(&mut instance, &mock_env(), msg)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            res.messages,
+            [SubMsg::new(BankMsg::Send {
+                to_address: "community-pool".to_string(),
+                amount: coins(700, "gold"),
+            })]
+        );
+
+        // invalid message
+        let msg = br#"{"unknown":{}}"#;
+        let err = call_sudo::<_, _, _, Empty>(&mut instance, &mock_env(), msg)
+            .unwrap()
+            .unwrap_err();
+        assert!(err.contains("unknown variant"), "{}", err);
+    }
+
     #[test]
     fn call_query_works() {
         let mut instance = mock_instance(CONTRACT, &[]);
@@ -735,19 +1014,63 @@ mod tests {
         assert_eq!(query_response.as_slice(), b"{\"verifier\":\"verifies\"}");
     }
 
+    #[test]
+    fn reply_for_sub_msg_works() {
+        use cosmwasm_std::{ReplyOn, SubMsgResponse, SubMsgResult};
+
+        let ok = SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+            msg_responses: vec![],
+        });
+        let err = SubMsgResult::Err("insufficient funds".to_string());
+        let sub_msg = SubMsg::<Empty>::reply_always(
+            BankMsg::Burn {
+                amount: coins(10, "earth"),
+            },
+            7,
+        )
+        .with_payload(b"my context".to_vec());
+
+        // id and payload are passed back unchanged
+        let reply = reply_for_sub_msg(&sub_msg, ok.clone()).unwrap();
+        assert_eq!(reply.id, 7);
+        assert_eq!(reply.payload.as_slice(), b"my context");
+        assert_eq!(reply.result, ok);
+        let reply = reply_for_sub_msg(&sub_msg, err.clone()).unwrap();
+        assert_eq!(reply.result, err);
+
+        let mut on_success = sub_msg.clone();
+        on_success.reply_on = ReplyOn::Success;
+        assert!(reply_for_sub_msg(&on_success, ok.clone()).is_some());
+        assert_eq!(reply_for_sub_msg(&on_success, err.clone()), None);
+
+        let mut on_error = sub_msg.clone();
+        on_error.reply_on = ReplyOn::Error;
+        assert_eq!(reply_for_sub_msg(&on_error, ok.clone()), None);
+        assert!(reply_for_sub_msg(&on_error, err.clone()).is_some());
+
+        let mut never = sub_msg;
+        never.reply_on = ReplyOn::Never;
+        assert_eq!(reply_for_sub_msg(&never, ok), None);
+        assert_eq!(reply_for_sub_msg(&never, err), None);
+    }
+
     #[cfg(feature = "stargate")]
     mod ibc {
         use super::*;
         use crate::calls::{call_instantiate, call_reply};
+        use crate::instance::InstanceOptions;
         use crate::testing::{
-            mock_env, mock_info, mock_instance, MockApi, MockQuerier, MockStorage,
+            mock_backend, mock_env, mock_info, mock_instance, mock_instance_options,
+            mock_instance_with_options, MockApi, MockInstanceOptions, MockQuerier, MockStorage,
         };
         use cosmwasm_std::testing::{
             mock_ibc_channel_close_init, mock_ibc_channel_connect_ack, mock_ibc_channel_open_init,
             mock_ibc_packet_ack, mock_ibc_packet_recv, mock_ibc_packet_timeout, mock_wasmd_attr,
         };
         use cosmwasm_std::{
-            Empty, Event, IbcAcknowledgement, IbcOrder, Reply, ReplyOn, SubMsgResponse,
+            Binary, Empty, Event, IbcAcknowledgement, IbcOrder, Reply, ReplyOn, SubMsgResponse,
             SubMsgResult,
         };
         static CONTRACT: &[u8] = include_bytes!("../testdata/ibc_reflect.wasm");
@@ -793,9 +1116,11 @@ mod tests {
             // which creates a reflect account. here we get the callback
             let response = Reply {
                 id,
+                payload: Binary::default(),
                 result: SubMsgResult::Ok(SubMsgResponse {
                     events: vec![event],
                     data: None,
+                    msg_responses: vec![],
                 }),
             };
             call_reply::<_, _, _, Empty>(instance, &mock_env(), &response).unwrap();
@@ -808,6 +1133,94 @@ mod tests {
             setup(&mut instance, CHANNEL_ID, ACCOUNT);
         }
         #[test]
+        fn call_ibc_channel_open_respects_readonly_option() {
+            // A contract with a bump allocator whose ibc_channel_open writes k=v to storage
+            let wasm = wat::parse_str(
+                r#"(module
+                (import "env" "db_write" (func $db_write (param i32 i32)))
+                (memory 1)
+                (export "memory" (memory 0))
+                (global $heap (mut i32) (i32.const 1024))
+
+                ;; Regions of the key, the value and the result `{"ok":null}`
+                (data (i32.const 100) "\c8\00\00\00\01\00\00\00\01\00\00\00")
+                (data (i32.const 112) "\c9\00\00\00\01\00\00\00\01\00\00\00")
+                (data (i32.const 124) "\2c\01\00\00\0b\00\00\00\0b\00\00\00")
+                (data (i32.const 200) "kv")
+                (data (i32.const 300) "{\"ok\":null}")
+
+                (func (export "interface_version_8"))
+                (func (export "allocate") (param $size i32) (result i32)
+                    (local $region i32)
+                    (local.set $region (global.get $heap))
+                    (i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+                    (i32.store offset=4 (local.get $region) (local.get $size))
+                    (i32.store offset=8 (local.get $region) (i32.const 0))
+                    (global.set $heap
+                        (i32.add (local.get $region) (i32.add (local.get $size) (i32.const 12))))
+                    (local.get $region))
+                (func (export "deallocate") (param i32))
+                (func (export "ibc_channel_open") (param i32 i32) (result i32)
+                    (call $db_write (i32.const 100) (i32.const 112))
+                    (i32.const 124))
+            )"#,
+            )
+            .unwrap();
+            let handshake_open =
+                mock_ibc_channel_open_init(CHANNEL_ID, IbcOrder::Ordered, IBC_VERSION);
+
+            // writable by default
+            let (instance_options, memory_limit) = mock_instance_options();
+            let mut instance =
+                Instance::from_code(&wasm, mock_backend(&[]), instance_options, memory_limit)
+                    .unwrap();
+            call_ibc_channel_open(&mut instance, &mock_env(), &handshake_open)
+                .unwrap()
+                .unwrap();
+            let value = instance
+                .with_storage(|storage| Ok(storage.get(b"k").0.unwrap()))
+                .unwrap();
+            assert_eq!(value, Some(b"v".to_vec()));
+
+            let (instance_options, memory_limit) = mock_instance_options();
+            let options = InstanceOptions {
+                readonly_ibc_channel_open: true,
+                ..instance_options
+            };
+            let mut instance =
+                Instance::from_code(&wasm, mock_backend(&[]), options, memory_limit).unwrap();
+            let err =
+                call_ibc_channel_open(&mut instance, &mock_env(), &handshake_open).unwrap_err();
+            // The WriteAccessDenied error of db_write aborts the contract execution
+            match err {
+                VmError::WriteAccessDenied { .. } => {}
+                err => panic!("Unexpected error: {:?}", err),
+            }
+            let value = instance
+                .with_storage(|storage| Ok(storage.get(b"k").0.unwrap()))
+                .unwrap();
+            assert_eq!(value, None);
+
+            // other entry points are not affected
+            let mut instance = mock_instance_with_options(
+                CONTRACT,
+                MockInstanceOptions {
+                    readonly_ibc_channel_open: true,
+                    ..Default::default()
+                },
+            );
+            call_ibc_channel_open(&mut instance, &mock_env(), &handshake_open)
+                .unwrap()
+                .unwrap();
+            assert!(instance.is_storage_readonly());
+            let info = mock_info("creator", &[]);
+            let msg = br#"{"reflect_code_id":77}"#;
+            call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+                .unwrap()
+                .unwrap();
+            assert!(!instance.is_storage_readonly());
+        }
+        #[test]
         fn call_ibc_channel_close_works() {
             let mut instance = mock_instance(CONTRACT, &[]);
             setup(&mut instance, CHANNEL_ID, ACCOUNT);
@@ -846,5 +1259,149 @@ mod tests {
                 .unwrap()
                 .unwrap();
         }
+        #[test]
+        #[cfg(feature = "cosmwasm_1_4")]
+        fn call_ibc_callbacks_fail_for_missing_export() {
+            use cosmwasm_std::{Addr, IbcTimeoutCallbackMsg};
+
+            // ibc_reflect does not opt in to IBC callbacks
+            let mut instance = mock_instance(CONTRACT, &[]);
+            setup(&mut instance, CHANNEL_ID, ACCOUNT);
+            let packet = mock_ibc_packet_timeout(CHANNEL_ID, br#"{}"#)
+                .unwrap()
+                .packet;
+
+            let msg = IbcSourceCallbackMsg::Timeout(IbcTimeoutCallbackMsg::new(
+                packet.clone(),
+                Addr::unchecked("relayer"),
+            ));
+            let err = call_ibc_source_callback::<_, _, _, Empty>(&mut instance, &mock_env(), &msg)
+                .unwrap_err();
+            match err {
+                VmError::ResolveErr { msg, .. } => assert_eq!(
+                    msg,
+                    "Could not get export: Missing export ibc_source_callback"
+                ),
+                err => panic!("Unexpected error: {:?}", err),
+            }
+
+            let msg = IbcDestinationCallbackMsg::new(packet, IbcAcknowledgement::new(b"ack"));
+            let err =
+                call_ibc_destination_callback::<_, _, _, Empty>(&mut instance, &mock_env(), &msg)
+                    .unwrap_err();
+            match err {
+                VmError::ResolveErr { msg, .. } => assert_eq!(
+                    msg,
+                    "Could not get export: Missing export ibc_destination_callback"
+                ),
+                err => panic!("Unexpected error: {:?}", err),
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "cosmwasm_1_4")]
+        fn call_ibc_callbacks_works() {
+            use cosmwasm_std::{attr, Addr, IbcAckCallbackMsg, IbcTimeoutCallbackMsg};
+
+            // Each callback responds with an attribute naming the called entry point
+            let result = |entry_point: &str| {
+                format!(
+                    r#"{{"ok":{{"messages":[],"attributes":[{{"key":"entry_point","value":"{}"}}],"events":[]}}}}"#,
+                    entry_point
+                )
+            };
+            let region = |offset: u32, data: &str| -> String {
+                let length = data.len() as u32;
+                [offset, length, length]
+                    .iter()
+                    .flat_map(|value| value.to_le_bytes())
+                    .map(|byte| format!("\\{:02x}", byte))
+                    .collect()
+            };
+            let source_result = result("ibc_source_callback");
+            let destination_result = result("ibc_destination_callback");
+            // A contract with a bump allocator exporting both callbacks
+            let wasm = wat::parse_str(format!(
+                r#"(module
+                (memory 1)
+                (export "memory" (memory 0))
+                (global $heap (mut i32) (i32.const 1024))
+
+                ;; Regions of the source and destination callback results
+                (data (i32.const 100) "{}")
+                (data (i32.const 112) "{}")
+                (data (i32.const 200) "{}")
+                (data (i32.const 400) "{}")
+
+                (func (export "interface_version_8"))
+                (func (export "allocate") (param $size i32) (result i32)
+                    (local $region i32)
+                    (local.set $region (global.get $heap))
+                    (i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+                    (i32.store offset=4 (local.get $region) (local.get $size))
+                    (i32.store offset=8 (local.get $region) (i32.const 0))
+                    (global.set $heap
+                        (i32.add (local.get $region) (i32.add (local.get $size) (i32.const 12))))
+                    (local.get $region))
+                (func (export "deallocate") (param i32))
+                (func (export "ibc_source_callback") (param i32 i32) (result i32)
+                    (i32.const 100))
+                (func (export "ibc_destination_callback") (param i32 i32) (result i32)
+                    (i32.const 112))
+            )"#,
+                region(200, &source_result),
+                region(400, &destination_result),
+                source_result.replace('"', "\\\""),
+                destination_result.replace('"', "\\\""),
+            ))
+            .unwrap();
+            let (instance_options, memory_limit) = mock_instance_options();
+            let mut instance =
+                Instance::from_code(&wasm, mock_backend(&[]), instance_options, memory_limit)
+                    .unwrap();
+            let packet = mock_ibc_packet_timeout(CHANNEL_ID, br#"{}"#)
+                .unwrap()
+                .packet;
+
+            let msg = IbcSourceCallbackMsg::Acknowledgement(IbcAckCallbackMsg::new(
+                IbcAcknowledgement::new(b"ack"),
+                packet.clone(),
+                Addr::unchecked("relayer"),
+            ));
+            let response =
+                call_ibc_source_callback::<_, _, _, Empty>(&mut instance, &mock_env(), &msg)
+                    .unwrap()
+                    .unwrap();
+            assert_eq!(response.messages, []);
+            assert_eq!(
+                response.attributes,
+                [attr("entry_point", "ibc_source_callback")]
+            );
+
+            let msg = IbcSourceCallbackMsg::Timeout(IbcTimeoutCallbackMsg::new(
+                packet.clone(),
+                Addr::unchecked("relayer"),
+            ));
+            let response =
+                call_ibc_source_callback::<_, _, _, Empty>(&mut instance, &mock_env(), &msg)
+                    .unwrap()
+                    .unwrap();
+            assert_eq!(
+                response.attributes,
+                [attr("entry_point", "ibc_source_callback")]
+            );
+
+            let msg = IbcDestinationCallbackMsg::new(packet, IbcAcknowledgement::new(b"ack"));
+            let response =
+                call_ibc_destination_callback::<_, _, _, Empty>(&mut instance, &mock_env(), &msg)
+                    .unwrap()
+                    .unwrap();
+            assert_eq!(response.messages, []);
+            assert_eq!(
+                response.attributes,
+                [attr("entry_point", "ibc_destination_callback")]
+            );
+            assert!(response.events.is_empty());
+        }
     }
 }
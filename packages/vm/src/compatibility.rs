@@ -1,32 +1,66 @@
-use parity_wasm::elements::{External, ImportEntry, Module, TableType};
+use parity_wasm::elements::{
+    External, FunctionType, ImportEntry, Internal, Module, TableType, Type,
+};
 use std::collections::BTreeSet;
 use std::collections::HashSet;
+use wasmer::{Engine, Module as WasmerModule};
 
 use crate::capabilities::required_capabilities_from_module;
+use crate::checksum::Checksum;
+use crate::environment::GasSchedule;
 use crate::errors::{VmError, VmResult};
+use crate::instance::RESET_EXPORT;
 use crate::limited::LimitedDisplay;
-use crate::static_analysis::{deserialize_wasm, ExportInfo};
+use crate::static_analysis::{
+    deserialize_wasm, has_ibc_entry_points, non_deterministic_constructs, ExportInfo,
+};
+use crate::wasm_backend::{compile_with_operator_cost, INTERRUPT_FLAG_EXPORT};
 
 /// Lists all imports we provide upon instantiating the instance in Instance::from_module()
 /// This should be updated when new imports are added
 const SUPPORTED_IMPORTS: &[&str] = &[
     "env.abort",
     "env.db_read",
+    "env.db_read_batch",
     "env.db_write",
     "env.db_remove",
     "env.addr_validate",
     "env.addr_canonicalize",
     "env.addr_humanize",
+    "env.bech32_encode",
+    "env.bech32_decode",
     "env.secp256k1_verify",
     "env.secp256k1_recover_pubkey",
+    "env.secp256k1_batch_verify",
+    "env.secp256k1_point_add",
+    "env.secp256k1_point_mul",
+    "env.secp256r1_verify",
+    "env.secp256r1_recover_pubkey",
+    "env.bls12_381_aggregate_g1",
+    "env.bls12_381_aggregate_g2",
+    "env.bls12_381_pairing_equality",
+    "env.bls12_381_hash_to_g1",
+    "env.bls12_381_hash_to_g2",
+    "env.groth16_verify_bn254",
+    "env.groth16_verify_bls12_381",
     "env.ed25519_verify",
     "env.ed25519_batch_verify",
+    "env.sr25519_verify",
+    "env.keccak256",
+    "env.sha512",
+    "env.blake2b512",
     "env.debug",
     "env.query_chain",
+    #[cfg(feature = "cosmwasm_1_4")]
+    "env.contract_self_info",
     #[cfg(feature = "iterator")]
     "env.db_scan",
     #[cfg(feature = "iterator")]
     "env.db_next",
+    #[cfg(feature = "iterator")]
+    "env.db_scan_bounded",
+    #[cfg(feature = "iterator")]
+    "env.db_remove_range",
 ];
 
 /// Lists all entry points we expect to be present when calling a contract.
@@ -70,19 +104,134 @@ const TABLE_SIZE_LIMIT: u32 = 2500; // entries
 /// when a user accidentally includes wasm-bindgen, they get a bunch of unsupported imports.
 const MAX_IMPORTS: usize = 100;
 
+/// The maximum number of functions defined in the contract (i.e. not imported)
+const MAX_FUNCTIONS: usize = 20_000;
+
+/// The maximum number of parameters of a function type
+const MAX_FUNCTION_PARAMS: usize = 100;
+
+/// The limits applied to a Wasm blob during static validation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// The maximum initial memory size in pages
+    pub initial_memory_limit: u32,
+    /// The upper limit for the `max` value of the table
+    pub table_size_limit: u32,
+    /// The maximum number of imports
+    pub max_imports: usize,
+    /// The maximum number of functions defined in the contract
+    pub max_functions: usize,
+    /// The maximum number of parameters of a function type
+    pub max_function_params: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        WasmLimits {
+            initial_memory_limit: MEMORY_LIMIT,
+            table_size_limit: TABLE_SIZE_LIMIT,
+            max_imports: MAX_IMPORTS,
+            max_functions: MAX_FUNCTIONS,
+            max_function_params: MAX_FUNCTION_PARAMS,
+        }
+    }
+}
+
+/// Information about a Wasm blob that passed all store-time checks
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ValidationReport {
+    /// The checksum the blob would be stored under
+    pub checksum: Checksum,
+    pub has_ibc_entry_points: bool,
+    pub required_capabilities: HashSet<String>,
+    /// The names of all exported functions, including entry points and the interface version marker
+    pub exports: HashSet<String>,
+}
+
+/// Performs every check that is done when storing a Wasm blob through
+/// [`Cache::save_wasm`](crate::Cache::save_wasm), without touching the cache or disk.
+///
+/// This includes the static validation and the compilation of the module. It allows
+/// hosts to answer whether a blob can be stored without duplicating the checking logic.
+/// The module is compiled for the default [`GasSchedule`]. The Wasm operator cost does not
+/// affect whether a blob can be stored.
+pub fn validate_wasm(
+    wasm_code: &[u8],
+    wasm_limits: &WasmLimits,
+    available_capabilities: &HashSet<String>,
+) -> VmResult<ValidationReport> {
+    let (module, _engine, _compiled) = check_and_compile(
+        wasm_code,
+        available_capabilities,
+        wasm_limits,
+        GasSchedule::default().wasm_operator_cost,
+    )?;
+    Ok(ValidationReport {
+        checksum: Checksum::generate(wasm_code),
+        has_ibc_entry_points: has_ibc_entry_points(&module),
+        required_capabilities: required_capabilities_from_module(&module),
+        exports: module.exported_function_names(None),
+    })
+}
+
+/// Runs the static checks and compiles the module the same way as it is stored by the cache.
+/// This is the single path used by both [`validate_wasm`] and
+/// [`Cache::save_wasm`](crate::Cache::save_wasm), such that they cannot disagree.
+pub(crate) fn check_and_compile(
+    wasm_code: &[u8],
+    available_capabilities: &HashSet<String>,
+    wasm_limits: &WasmLimits,
+    wasm_operator_cost: u64,
+) -> VmResult<(Module, Engine, WasmerModule)> {
+    let module = check_wasm_with_limits(wasm_code, available_capabilities, wasm_limits)?;
+    let (engine, compiled) = compile_with_operator_cost(wasm_code, &[], wasm_operator_cost)?;
+    Ok((module, engine, compiled))
+}
+
 /// Checks if the data is valid wasm and compatibility with the CosmWasm API (imports and exports)
 pub fn check_wasm(wasm_code: &[u8], available_capabilities: &HashSet<String>) -> VmResult<()> {
+    check_wasm_with_limits(wasm_code, available_capabilities, &WasmLimits::default())?;
+    Ok(())
+}
+
+/// Like [`check_wasm`] but with custom limits instead of the defaults
+pub(crate) fn check_wasm_with_limits(
+    wasm_code: &[u8],
+    available_capabilities: &HashSet<String>,
+    wasm_limits: &WasmLimits,
+) -> VmResult<Module> {
     let module = deserialize_wasm(wasm_code)?;
-    check_wasm_tables(&module)?;
-    check_wasm_memories(&module)?;
+    check_wasm_determinism(&module)?;
+    check_wasm_tables(&module, wasm_limits)?;
+    check_wasm_memories(&module, wasm_limits)?;
+    check_wasm_functions(&module, wasm_limits)?;
     check_interface_version(&module)?;
     check_wasm_exports(&module)?;
-    check_wasm_imports(&module, SUPPORTED_IMPORTS)?;
+    check_wasm_imports(&module, SUPPORTED_IMPORTS, wasm_limits)?;
     check_wasm_capabilities(&module, available_capabilities)?;
-    Ok(())
+    Ok(module)
+}
+
+/// Checks that the contract does not use any of the non-deterministic constructs
+/// we can detect statically. Each category found is reported in the error message.
+fn check_wasm_determinism(module: &Module) -> VmResult<()> {
+    let constructs = non_deterministic_constructs(module);
+    if constructs.is_empty() {
+        return Ok(());
+    }
+    let list = constructs
+        .iter()
+        .map(|construct| construct.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(VmError::static_validation_err(format!(
+        "Wasm contract uses non-deterministic constructs: {}. Those are not supported in CosmWasm.",
+        list
+    )))
 }
 
-fn check_wasm_tables(module: &Module) -> VmResult<()> {
+fn check_wasm_tables(module: &Module, wasm_limits: &WasmLimits) -> VmResult<()> {
     let sections: &[TableType] = module
         .table_section()
         .map_or(&[], |section| section.entries());
@@ -96,7 +245,7 @@ fn check_wasm_tables(module: &Module) -> VmResult<()> {
                         "Wasm contract's first table section has a initial limit > max limit",
                     ));
                 }
-                if maximum > TABLE_SIZE_LIMIT {
+                if maximum > wasm_limits.table_size_limit {
                     return Err(VmError::static_validation_err(
                         "Wasm contract's first table section has a too large max limit",
                     ));
@@ -114,7 +263,7 @@ fn check_wasm_tables(module: &Module) -> VmResult<()> {
     }
 }
 
-fn check_wasm_memories(module: &Module) -> VmResult<()> {
+fn check_wasm_memories(module: &Module, wasm_limits: &WasmLimits) -> VmResult<()> {
     let section = match module.memory_section() {
         Some(section) => section,
         None => {
@@ -135,10 +284,10 @@ fn check_wasm_memories(module: &Module) -> VmResult<()> {
     // println!("Memory: {:?}", memory);
     let limits = memory.limits();
 
-    if limits.initial() > MEMORY_LIMIT {
+    if limits.initial() > wasm_limits.initial_memory_limit {
         return Err(VmError::static_validation_err(format!(
             "Wasm contract memory's minimum must not exceed {} pages.",
-            MEMORY_LIMIT
+            wasm_limits.initial_memory_limit
         )));
     }
 
@@ -150,6 +299,29 @@ fn check_wasm_memories(module: &Module) -> VmResult<()> {
     Ok(())
 }
 
+fn check_wasm_functions(module: &Module, wasm_limits: &WasmLimits) -> VmResult<()> {
+    let functions = module
+        .function_section()
+        .map_or(0, |section| section.entries().len());
+    if functions > wasm_limits.max_functions {
+        return Err(VmError::static_validation_err(format!(
+            "Wasm contract contains more than {} functions",
+            wasm_limits.max_functions
+        )));
+    }
+
+    let types: &[Type] = module.type_section().map_or(&[], |section| section.types());
+    for Type::Function(function_type) in types {
+        if function_type.params().len() > wasm_limits.max_function_params {
+            return Err(VmError::static_validation_err(format!(
+                "Wasm contract contains function type with more than {} parameters",
+                wasm_limits.max_function_params
+            )));
+        }
+    }
+    Ok(())
+}
+
 fn check_interface_version(module: &Module) -> VmResult<()> {
     let mut interface_version_exports = module
         .exported_function_names(Some(INTERFACE_VERSION_PREFIX))
@@ -190,22 +362,74 @@ fn check_wasm_exports(module: &Module) -> VmResult<()> {
             )));
         }
     }
+    if let Some(function_type) = exported_function_type(module, RESET_EXPORT) {
+        if !function_type.params().is_empty() || !function_type.results().is_empty() {
+            return Err(VmError::static_validation_err(format!(
+                "Wasm contract export \"{RESET_EXPORT}\" must have the signature () -> ()"
+            )));
+        }
+    }
+    // The VM adds this export to every module, see `InterruptCheck`
+    let exports_interrupt_flag = module.export_section().is_some_and(|section| {
+        section
+            .entries()
+            .iter()
+            .any(|entry| entry.field() == INTERRUPT_FLAG_EXPORT)
+    });
+    if exports_interrupt_flag {
+        return Err(VmError::static_validation_err(format!(
+            "Wasm contract must not export \"{INTERRUPT_FLAG_EXPORT}\", which is reserved by the VM"
+        )));
+    }
     Ok(())
 }
 
+/// Returns the type of the exported function with the given name, if any
+fn exported_function_type<'a>(module: &'a Module, name: &str) -> Option<&'a FunctionType> {
+    let function_index = module
+        .export_section()?
+        .entries()
+        .iter()
+        .find_map(|entry| match entry.internal() {
+            Internal::Function(index) if entry.field() == name => Some(*index as usize),
+            _ => None,
+        })?;
+    // The function index space starts with the imported functions
+    let imported_functions: Vec<u32> = module
+        .import_section()
+        .map_or(&[][..], |section| section.entries())
+        .iter()
+        .filter_map(|entry| match entry.external() {
+            External::Function(type_index) => Some(*type_index),
+            _ => None,
+        })
+        .collect();
+    let type_index = match function_index.checked_sub(imported_functions.len()) {
+        None => imported_functions[function_index],
+        Some(index) => module.function_section()?.entries().get(index)?.type_ref(),
+    };
+    match module.type_section()?.types().get(type_index as usize)? {
+        Type::Function(function_type) => Some(function_type),
+    }
+}
+
 /// Checks if the import requirements of the contract are satisfied.
 /// When this is not the case, we either have an incompatibility between contract and VM
 /// or a error in the contract.
-fn check_wasm_imports(module: &Module, supported_imports: &[&str]) -> VmResult<()> {
+fn check_wasm_imports(
+    module: &Module,
+    supported_imports: &[&str],
+    wasm_limits: &WasmLimits,
+) -> VmResult<()> {
     let required_imports: &[ImportEntry] = module
         .import_section()
         .map_or(&[], |import_section| import_section.entries());
 
-    if required_imports.len() > MAX_IMPORTS {
+    if required_imports.len() > wasm_limits.max_imports {
         return Err(VmError::static_validation_err(format!(
             "Import count exceeds limit. Imports: {}. Limit: {}.",
             required_imports.len(),
-            MAX_IMPORTS
+            wasm_limits.max_imports
         )));
     }
 
@@ -256,6 +480,7 @@ fn check_wasm_capabilities(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::capabilities::capabilities_from_csv;
     use crate::errors::VmError;
 
     static CONTRACT_0_7: &[u8] = include_bytes!("../testdata/hackatom_0.7.wasm");
@@ -263,6 +488,7 @@ mod tests {
     static CONTRACT_0_14: &[u8] = include_bytes!("../testdata/hackatom_0.14.wasm");
     static CONTRACT_0_15: &[u8] = include_bytes!("../testdata/hackatom_0.15.wasm");
     static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+    static IBC_CONTRACT: &[u8] = include_bytes!("../testdata/ibc_reflect.wasm");
 
     fn default_capabilities() -> HashSet<String> {
         ["staking".to_string()].into_iter().collect()
@@ -274,6 +500,72 @@ mod tests {
         check_wasm(CONTRACT, &default_capabilities()).unwrap();
     }
 
+    #[test]
+    fn validate_wasm_works() {
+        let report =
+            validate_wasm(CONTRACT, &WasmLimits::default(), &default_capabilities()).unwrap();
+        assert_eq!(report.checksum, Checksum::generate(CONTRACT));
+        assert!(!report.has_ibc_entry_points);
+        assert_eq!(report.required_capabilities, HashSet::new());
+        for export in [
+            "allocate",
+            "deallocate",
+            "instantiate",
+            "interface_version_8",
+        ] {
+            assert!(report.exports.contains(export), "missing export {}", export);
+        }
+    }
+
+    #[test]
+    fn validate_wasm_applies_limits() {
+        let wasm_limits = WasmLimits {
+            initial_memory_limit: 1,
+            ..WasmLimits::default()
+        };
+        match validate_wasm(CONTRACT, &wasm_limits, &default_capabilities()).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert_eq!(
+                    msg,
+                    "Wasm contract memory's minimum must not exceed 1 pages."
+                )
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        let wasm_limits = WasmLimits {
+            max_imports: 1,
+            ..WasmLimits::default()
+        };
+        match validate_wasm(CONTRACT, &wasm_limits, &default_capabilities()).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert!(msg.starts_with("Import count exceeds limit."))
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn validate_wasm_reports_ibc_contracts() {
+        let capabilities = capabilities_from_csv("iterator,staking,stargate");
+        let report = validate_wasm(IBC_CONTRACT, &WasmLimits::default(), &capabilities).unwrap();
+        assert!(report.has_ibc_entry_points);
+        assert!(report.required_capabilities.contains("stargate"));
+
+        match validate_wasm(
+            IBC_CONTRACT,
+            &WasmLimits::default(),
+            &default_capabilities(),
+        )
+        .unwrap_err()
+        {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert!(msg.starts_with("Wasm contract requires unavailable capabilities"))
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
     #[test]
     fn check_wasm_old_contract() {
         match check_wasm(CONTRACT_0_15, &default_capabilities()) {
@@ -313,33 +605,71 @@ mod tests {
         };
     }
 
+    #[test]
+    fn check_wasm_determinism_works() {
+        let wasm = wat::parse_str("(module (memory 3))").unwrap();
+        check_wasm_determinism(&deserialize_wasm(&wasm).unwrap()).unwrap();
+
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "seed" (global i32))
+                (memory 3 5 shared)
+                (func (param i32 i32 i32) local.get 0 local.get 1 local.get 2 memory.fill)
+            )"#,
+        )
+        .unwrap();
+        match check_wasm_determinism(&deserialize_wasm(&wasm).unwrap()).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => assert_eq!(
+                msg,
+                "Wasm contract uses non-deterministic constructs: bulk memory operations or passive segments, shared memory, imported globals. Those are not supported in CosmWasm."
+            ),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn check_wasm_reports_determinism_before_memories() {
+        // A shared memory always has a maximum, which would otherwise be reported
+        // by the memory check in a less helpful way.
+        let wasm = wat::parse_str(r#"(module (memory 3 5 shared))"#).unwrap();
+        match check_wasm(&wasm, &default_capabilities()).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert!(msg.contains("non-deterministic constructs: shared memory"))
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
     #[test]
     fn check_wasm_tables_works() {
         // No tables is fine
         let wasm = wat::parse_str("(module)").unwrap();
-        check_wasm_tables(&deserialize_wasm(&wasm).unwrap()).unwrap();
+        check_wasm_tables(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default()).unwrap();
 
         // One table (bound)
         let wasm = wat::parse_str("(module (table $name 123 123 funcref))").unwrap();
-        check_wasm_tables(&deserialize_wasm(&wasm).unwrap()).unwrap();
+        check_wasm_tables(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default()).unwrap();
 
         // One table (bound, initial > max)
         let wasm = wat::parse_str("(module (table $name 124 123 funcref))").unwrap();
-        let err = check_wasm_tables(&deserialize_wasm(&wasm).unwrap()).unwrap_err();
+        let err = check_wasm_tables(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default())
+            .unwrap_err();
         assert!(err
             .to_string()
             .contains("Wasm contract's first table section has a initial limit > max limit"));
 
         // One table (bound, max too large)
         let wasm = wat::parse_str("(module (table $name 100 9999 funcref))").unwrap();
-        let err = check_wasm_tables(&deserialize_wasm(&wasm).unwrap()).unwrap_err();
+        let err = check_wasm_tables(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default())
+            .unwrap_err();
         assert!(err
             .to_string()
             .contains("Wasm contract's first table section has a too large max limit"));
 
         // One table (unbound)
         let wasm = wat::parse_str("(module (table $name 100 funcref))").unwrap();
-        let err = check_wasm_tables(&deserialize_wasm(&wasm).unwrap()).unwrap_err();
+        let err = check_wasm_tables(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default())
+            .unwrap_err();
         assert!(err
             .to_string()
             .contains("Wasm contract must not have unbound table section"));
@@ -348,13 +678,13 @@ mod tests {
     #[test]
     fn check_wasm_memories_ok() {
         let wasm = wat::parse_str("(module (memory 1))").unwrap();
-        check_wasm_memories(&deserialize_wasm(&wasm).unwrap()).unwrap()
+        check_wasm_memories(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default()).unwrap()
     }
 
     #[test]
     fn check_wasm_memories_no_memory() {
         let wasm = wat::parse_str("(module)").unwrap();
-        match check_wasm_memories(&deserialize_wasm(&wasm).unwrap()) {
+        match check_wasm_memories(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default()) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract doesn't have a memory section"));
             }
@@ -378,7 +708,7 @@ mod tests {
         ))
         .unwrap();
 
-        match check_wasm_memories(&deserialize_wasm(&wasm).unwrap()) {
+        match check_wasm_memories(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default()) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract must contain exactly one memory"));
             }
@@ -399,7 +729,7 @@ mod tests {
         ))
         .unwrap();
 
-        match check_wasm_memories(&deserialize_wasm(&wasm).unwrap()) {
+        match check_wasm_memories(&deserialize_wasm(&wasm).unwrap(), &WasmLimits::default()) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract must contain exactly one memory"));
             }
@@ -411,10 +741,13 @@ mod tests {
     #[test]
     fn check_wasm_memories_initial_size() {
         let wasm_ok = wat::parse_str("(module (memory 512))").unwrap();
-        check_wasm_memories(&deserialize_wasm(&wasm_ok).unwrap()).unwrap();
+        check_wasm_memories(&deserialize_wasm(&wasm_ok).unwrap(), &WasmLimits::default()).unwrap();
 
         let wasm_too_big = wat::parse_str("(module (memory 513))").unwrap();
-        match check_wasm_memories(&deserialize_wasm(&wasm_too_big).unwrap()) {
+        match check_wasm_memories(
+            &deserialize_wasm(&wasm_too_big).unwrap(),
+            &WasmLimits::default(),
+        ) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract memory's minimum must not exceed 512 pages"));
             }
@@ -426,7 +759,10 @@ mod tests {
     #[test]
     fn check_wasm_memories_maximum_size() {
         let wasm_max = wat::parse_str("(module (memory 1 5))").unwrap();
-        match check_wasm_memories(&deserialize_wasm(&wasm_max).unwrap()) {
+        match check_wasm_memories(
+            &deserialize_wasm(&wasm_max).unwrap(),
+            &WasmLimits::default(),
+        ) {
             Err(VmError::StaticValidationErr { msg, .. }) => {
                 assert!(msg.starts_with("Wasm contract memory's maximum must be unset"));
             }
@@ -435,6 +771,62 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_wasm_functions_works() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (type (func (param i32 i32 i32)))
+                (func (type 0) nop)
+                (func (type 0) nop)
+                (func (type 0) nop)
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        check_wasm_functions(&module, &WasmLimits::default()).unwrap();
+
+        // exactly at the limits
+        let wasm_limits = WasmLimits {
+            max_functions: 3,
+            max_function_params: 3,
+            ..WasmLimits::default()
+        };
+        check_wasm_functions(&module, &wasm_limits).unwrap();
+
+        // too many functions
+        let wasm_limits = WasmLimits {
+            max_functions: 2,
+            ..WasmLimits::default()
+        };
+        match check_wasm_functions(&module, &wasm_limits) {
+            Err(VmError::StaticValidationErr { msg, .. }) => {
+                assert_eq!(msg, "Wasm contract contains more than 2 functions");
+            }
+            Err(e) => panic!("Unexpected error {:?}", e),
+            Ok(_) => panic!("Didn't reject wasm with too many functions"),
+        }
+
+        // too many params
+        let wasm_limits = WasmLimits {
+            max_function_params: 2,
+            ..WasmLimits::default()
+        };
+        match check_wasm_functions(&module, &wasm_limits) {
+            Err(VmError::StaticValidationErr { msg, .. }) => {
+                assert_eq!(
+                    msg,
+                    "Wasm contract contains function type with more than 2 parameters"
+                );
+            }
+            Err(e) => panic!("Unexpected error {:?}", e),
+            Ok(_) => panic!("Didn't reject wasm with too many function parameters"),
+        }
+
+        // latest contract
+        let module = deserialize_wasm(CONTRACT).unwrap();
+        check_wasm_functions(&module, &WasmLimits::default()).unwrap();
+    }
+
     #[test]
     fn check_interface_version_works() {
         // valid
@@ -620,6 +1012,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_wasm_exports_rejects_interrupt_flag_export() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (type (func))
+                (func (type 0) nop)
+                (global (mut i32) (i32.const 0))
+                (export "allocate" (func 0))
+                (export "deallocate" (func 0))
+                (export "instantiate" (func 0))
+                (export "cosmwasm_interrupted" (global 0))
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        match check_wasm_exports(&module) {
+            Err(VmError::StaticValidationErr { msg, .. }) => assert_eq!(
+                msg,
+                "Wasm contract must not export \"cosmwasm_interrupted\", which is reserved by the VM"
+            ),
+            Err(e) => panic!("Unexpected error {:?}", e),
+            Ok(_) => panic!("Didn't reject wasm exporting the interrupt flag"),
+        }
+    }
+
+    #[test]
+    fn check_wasm_exports_checks_arena_reset_signature() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (type (func))
+                (func (type 0) nop)
+                (export "allocate" (func 0))
+                (export "deallocate" (func 0))
+                (export "instantiate" (func 0))
+                (export "cosmwasm_arena_reset" (func 0))
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        check_wasm_exports(&module).unwrap();
+
+        // reset export with params
+        let wasm = wat::parse_str(
+            r#"(module
+                (type (func))
+                (func (type 0) nop)
+                (func (param i32) nop)
+                (export "allocate" (func 0))
+                (export "deallocate" (func 0))
+                (export "instantiate" (func 0))
+                (export "cosmwasm_arena_reset" (func 1))
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        match check_wasm_exports(&module).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => assert_eq!(
+                msg,
+                "Wasm contract export \"cosmwasm_arena_reset\" must have the signature () -> ()"
+            ),
+            e => panic!("Unexpected error {:?}", e),
+        }
+
+        // reset export with result, re-exported from an import
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "abort" (func (result i32)))
+                (type (func))
+                (func (type 0) nop)
+                (export "allocate" (func 1))
+                (export "deallocate" (func 1))
+                (export "instantiate" (func 1))
+                (export "cosmwasm_arena_reset" (func 0))
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        match check_wasm_exports(&module).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => assert_eq!(
+                msg,
+                "Wasm contract export \"cosmwasm_arena_reset\" must have the signature () -> ()"
+            ),
+            e => panic!("Unexpected error {:?}", e),
+        }
+    }
+
     #[test]
     fn check_wasm_exports_of_old_contract() {
         let module = deserialize_wasm(CONTRACT_0_7).unwrap();
@@ -651,7 +1129,12 @@ mod tests {
         )"#,
         )
         .unwrap();
-        check_wasm_imports(&deserialize_wasm(&wasm).unwrap(), SUPPORTED_IMPORTS).unwrap();
+        check_wasm_imports(
+            &deserialize_wasm(&wasm).unwrap(),
+            SUPPORTED_IMPORTS,
+            &WasmLimits::default(),
+        )
+        .unwrap();
     }
 
     #[test]
@@ -762,8 +1245,12 @@ mod tests {
         )"#,
         )
         .unwrap();
-        let err =
-            check_wasm_imports(&deserialize_wasm(&wasm).unwrap(), SUPPORTED_IMPORTS).unwrap_err();
+        let err = check_wasm_imports(
+            &deserialize_wasm(&wasm).unwrap(),
+            SUPPORTED_IMPORTS,
+            &WasmLimits::default(),
+        )
+        .unwrap_err();
         match err {
             VmError::StaticValidationErr { msg, .. } => {
                 assert_eq!(msg, "Import count exceeds limit. Imports: 101. Limit: 100.");
@@ -800,7 +1287,11 @@ mod tests {
             "env.debug",
             "env.query_chain",
         ];
-        let result = check_wasm_imports(&deserialize_wasm(&wasm).unwrap(), supported_imports);
+        let result = check_wasm_imports(
+            &deserialize_wasm(&wasm).unwrap(),
+            supported_imports,
+            &WasmLimits::default(),
+        );
         match result.unwrap_err() {
             VmError::StaticValidationErr { msg, .. } => {
                 println!("{}", msg);
@@ -816,7 +1307,7 @@ mod tests {
     #[test]
     fn check_wasm_imports_of_old_contract() {
         let module = deserialize_wasm(CONTRACT_0_7).unwrap();
-        let result = check_wasm_imports(&module, SUPPORTED_IMPORTS);
+        let result = check_wasm_imports(&module, SUPPORTED_IMPORTS, &WasmLimits::default());
         match result.unwrap_err() {
             VmError::StaticValidationErr { msg, .. } => {
                 assert!(
@@ -830,7 +1321,11 @@ mod tests {
     #[test]
     fn check_wasm_imports_wrong_type() {
         let wasm = wat::parse_str(r#"(module (import "env" "db_read" (memory 1 1)))"#).unwrap();
-        let result = check_wasm_imports(&deserialize_wasm(&wasm).unwrap(), SUPPORTED_IMPORTS);
+        let result = check_wasm_imports(
+            &deserialize_wasm(&wasm).unwrap(),
+            SUPPORTED_IMPORTS,
+            &WasmLimits::default(),
+        );
         match result.unwrap_err() {
             VmError::StaticValidationErr { msg, .. } => {
                 assert!(
@@ -0,0 +1,181 @@
+use parity_wasm::elements::Module;
+
+use crate::errors::{VmError, VmResult};
+use crate::static_analysis::deserialize_wasm;
+
+/// The name of the custom section written by `cosmwasm_std::contract_metadata!`
+pub const CONTRACT_METADATA_SECTION: &str = "cosmwasm_metadata";
+
+/// Provenance information embedded into a contract by `cosmwasm_std::contract_metadata!`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractMetadata {
+    pub name: String,
+    pub version: String,
+    /// The version of cosmwasm-std the contract was compiled with
+    pub cosmwasm_std_version: String,
+    pub authors: Vec<String>,
+}
+
+/// Reads the contract metadata from the `cosmwasm_metadata` custom section of the Wasm blob.
+///
+/// Returns `None` if the contract does not contain the section and an error if the
+/// section cannot be parsed. Unknown keys are ignored for forward compatibility.
+pub fn parse_contract_metadata(wasm_code: &[u8]) -> VmResult<Option<ContractMetadata>> {
+    let module = deserialize_wasm(wasm_code)?;
+    contract_metadata_from_module(&module)
+}
+
+/// Like [`parse_contract_metadata`] but for an already deserialized module
+pub(crate) fn contract_metadata_from_module(module: &Module) -> VmResult<Option<ContractMetadata>> {
+    let section = match module
+        .custom_sections()
+        .find(|section| section.name() == CONTRACT_METADATA_SECTION)
+    {
+        Some(section) => section,
+        None => return Ok(None),
+    };
+    parse_section(section.payload()).map(Some)
+}
+
+fn parse_section(payload: &[u8]) -> VmResult<ContractMetadata> {
+    let content =
+        std::str::from_utf8(payload).map_err(|_| invalid_metadata("Section is not valid UTF-8"))?;
+
+    let mut name = None;
+    let mut version = None;
+    let mut cosmwasm_std_version = None;
+    let mut authors = None;
+    for line in content.lines() {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid_metadata(format!("Line without '=': \"{}\"", line)))?;
+        match key {
+            "name" => name = Some(value.to_string()),
+            "version" => version = Some(value.to_string()),
+            "cosmwasm_std_version" => cosmwasm_std_version = Some(value.to_string()),
+            "authors" => {
+                authors = Some(
+                    value
+                        .split(':')
+                        .filter(|author| !author.is_empty())
+                        .map(|author| author.to_string())
+                        .collect(),
+                )
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ContractMetadata {
+        name: name.ok_or_else(|| invalid_metadata("Missing key \"name\""))?,
+        version: version.ok_or_else(|| invalid_metadata("Missing key \"version\""))?,
+        cosmwasm_std_version: cosmwasm_std_version
+            .ok_or_else(|| invalid_metadata("Missing key \"cosmwasm_std_version\""))?,
+        authors: authors.unwrap_or_default(),
+    })
+}
+
+fn invalid_metadata(msg: impl Into<String>) -> VmError {
+    VmError::static_validation_err(format!("Invalid contract metadata: {}", msg.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+
+    /// Appends a custom section to the given Wasm blob
+    fn with_custom_section(wasm: &[u8], name: &str, payload: &[u8]) -> Vec<u8> {
+        let mut section = vec![name.len() as u8];
+        section.extend_from_slice(name.as_bytes());
+        section.extend_from_slice(payload);
+
+        let mut out = wasm.to_vec();
+        out.push(0); // custom section id
+        leb128_u32(&mut out, section.len() as u32);
+        out.extend(section);
+        out
+    }
+
+    fn leb128_u32(out: &mut Vec<u8>, mut value: u32) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                break;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    #[test]
+    fn parse_contract_metadata_works() {
+        let wasm = with_custom_section(
+            CONTRACT,
+            CONTRACT_METADATA_SECTION,
+            b"name=hackatom\nversion=0.0.0\ncosmwasm_std_version=1.2.5\nauthors=Alice <alice@example.com>:Bob\n",
+        );
+        let metadata = parse_contract_metadata(&wasm).unwrap().unwrap();
+        assert_eq!(
+            metadata,
+            ContractMetadata {
+                name: "hackatom".to_string(),
+                version: "0.0.0".to_string(),
+                cosmwasm_std_version: "1.2.5".to_string(),
+                authors: vec!["Alice <alice@example.com>".to_string(), "Bob".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_contract_metadata_returns_none_without_section() {
+        assert_eq!(parse_contract_metadata(CONTRACT).unwrap(), None);
+
+        let wasm = with_custom_section(CONTRACT, "other", b"name=hackatom\n");
+        assert_eq!(parse_contract_metadata(&wasm).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_contract_metadata_ignores_unknown_keys() {
+        let wasm = with_custom_section(
+            CONTRACT,
+            CONTRACT_METADATA_SECTION,
+            b"name=hackatom\nversion=0.0.0\nlicense=Apache-2.0\ncosmwasm_std_version=1.2.5\nauthors=\n",
+        );
+        let metadata = parse_contract_metadata(&wasm).unwrap().unwrap();
+        assert_eq!(metadata.name, "hackatom");
+        assert_eq!(metadata.authors, Vec::<String>::new());
+    }
+
+    #[test]
+    fn parse_contract_metadata_fails_for_invalid_section() {
+        let wasm = with_custom_section(CONTRACT, CONTRACT_METADATA_SECTION, b"name=hackatom\n");
+        match parse_contract_metadata(&wasm).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert_eq!(msg, "Invalid contract metadata: Missing key \"version\"")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        let wasm = with_custom_section(CONTRACT, CONTRACT_METADATA_SECTION, b"hackatom\n");
+        match parse_contract_metadata(&wasm).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert_eq!(
+                    msg,
+                    "Invalid contract metadata: Line without '=': \"hackatom\""
+                )
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        let wasm = with_custom_section(CONTRACT, CONTRACT_METADATA_SECTION, b"name=\xff\n");
+        match parse_contract_metadata(&wasm).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert_eq!(msg, "Invalid contract metadata: Section is not valid UTF-8")
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+}
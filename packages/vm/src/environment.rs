@@ -1,17 +1,25 @@
 //! Internal details to be used by instance.rs only
 use std::borrow::{Borrow, BorrowMut};
-use std::cell::RefCell;
-use std::marker::PhantomData;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
 use std::ptr::NonNull;
 use std::rc::Rc;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
-use derivative::Derivative;
+#[cfg(feature = "cosmwasm_1_4")]
+use cosmwasm_std::ContractSelfInfo;
 use wasmer::{AsStoreMut, Instance as WasmerInstance, Memory, MemoryView, Value};
 use wasmer_middlewares::metering::{get_remaining_points, set_remaining_points, MeteringPoints};
 
 use crate::backend::{BackendApi, GasInfo, Querier, Storage};
+use crate::checksum::Checksum;
 use crate::errors::{VmError, VmResult};
+#[cfg(feature = "host_call_hooks")]
+use crate::hooks::HostCallHookFn;
+#[cfg(feature = "host_call_tracing")]
+use crate::host_call_trace::{HostCallRecord, HostCallTrace};
+use crate::watchdog::Watchdog;
 
 /// Keep this as low as necessary to avoid deepy nested errors like this:
 ///
@@ -20,6 +28,17 @@ use crate::errors::{VmError, VmResult};
 /// ```
 const MAX_CALL_DEPTH: usize = 2;
 
+/// The default for [`InstanceOptions::max_query_depth`](crate::InstanceOptions::max_query_depth).
+pub const DEFAULT_MAX_QUERY_DEPTH: usize = 10;
+
+thread_local! {
+    /// The number of `query_chain` imports currently being executed on this thread.
+    ///
+    /// The querier executes queries into other contracts synchronously on the calling thread,
+    /// such that this is the nesting depth of queries, no matter which instances are involved.
+    static QUERY_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
 /// Never can never be instantiated.
 /// Replace this with the [never primitive type](https://doc.rust-lang.org/std/primitive.never.html) when stable.
 #[derive(Debug)]
@@ -27,23 +46,87 @@ pub enum Never {}
 
 /** gas config data */
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct GasConfig {
     /// Gas costs of VM (not Backend) provided functionality
     /// secp256k1 signature verification cost
     pub secp256k1_verify_cost: u64,
     /// secp256k1 public key recovery cost
     pub secp256k1_recover_pubkey_cost: u64,
+    /// secp256k1 batch signature verification cost (per signature)
+    pub secp256k1_batch_verify_cost: u64,
+    /// secp256k1 batch signature verification cost (per signature, single public key)
+    pub secp256k1_batch_verify_one_pubkey_cost: u64,
+    /// secp256k1 point addition cost
+    pub secp256k1_point_add_cost: u64,
+    /// secp256k1 scalar multiplication cost
+    pub secp256k1_point_mul_cost: u64,
+    /// secp256r1 signature verification cost
+    pub secp256r1_verify_cost: u64,
+    /// secp256r1 public key recovery cost
+    pub secp256r1_recover_pubkey_cost: u64,
+    /// bls12-381 G1 aggregation base cost
+    pub bls12_381_aggregate_g1_cost: u64,
+    /// bls12-381 G1 aggregation cost per point
+    pub bls12_381_aggregate_g1_per_point_cost: u64,
+    /// bls12-381 G2 aggregation base cost
+    pub bls12_381_aggregate_g2_cost: u64,
+    /// bls12-381 G2 aggregation cost per point
+    pub bls12_381_aggregate_g2_per_point_cost: u64,
+    /// bls12-381 hash to G1 cost
+    pub bls12_381_hash_to_g1_cost: u64,
+    /// bls12-381 hash to G2 cost
+    pub bls12_381_hash_to_g2_cost: u64,
+    /// bls12-381 pairing equality check base cost
+    pub bls12_381_pairing_equality_cost: u64,
+    /// bls12-381 pairing equality check cost per pair on the left-hand side.
+    /// For lists of unequal length, the longer one is charged.
+    pub bls12_381_pairing_equality_per_pair_cost: u64,
+    /// Groth16 proof verification base cost over BN254
+    pub groth16_verify_bn254_cost: u64,
+    /// Groth16 proof verification cost over BN254 per public input
+    pub groth16_verify_bn254_per_input_cost: u64,
+    /// Groth16 proof verification base cost over BLS12-381
+    pub groth16_verify_bls12_381_cost: u64,
+    /// Groth16 proof verification cost over BLS12-381 per public input
+    pub groth16_verify_bls12_381_per_input_cost: u64,
     /// ed25519 signature verification cost
     pub ed25519_verify_cost: u64,
     /// ed25519 batch signature verification cost
     pub ed25519_batch_verify_cost: u64,
     /// ed25519 batch signature verification cost (single public key)
     pub ed25519_batch_verify_one_pubkey_cost: u64,
+    /// sr25519 signature verification cost
+    pub sr25519_verify_cost: u64,
+    /// keccak256 hashing base cost
+    pub keccak256_cost: u64,
+    /// keccak256 hashing cost per input byte
+    pub keccak256_per_byte_cost: u64,
+    /// sha512 hashing base cost
+    pub sha512_cost: u64,
+    /// sha512 hashing cost per input byte
+    pub sha512_per_byte_cost: u64,
+    /// blake2b512 hashing base cost
+    pub blake2b512_cost: u64,
+    /// blake2b512 hashing cost per input byte
+    pub blake2b512_per_byte_cost: u64,
+    /// bech32 encoding cost
+    pub bech32_encode_cost: u64,
+    /// bech32 decoding cost
+    pub bech32_decode_cost: u64,
+    /// contract_self_info cost (fixed, the data is held by the VM)
+    pub contract_self_info_cost: u64,
 }
 
 impl Default for GasConfig {
     fn default() -> Self {
+        Self::v1()
+    }
+}
+
+impl GasConfig {
+    /// The host function costs of [`GasSchedule::v1`]
+    pub const fn v1() -> Self {
         // Target is 10^12 per millisecond (see GAS.md), i.e. 10^9 gas per µ second.
         const GAS_PER_US: u64 = 1_000_000_000;
         Self {
@@ -51,16 +134,98 @@ impl Default for GasConfig {
             secp256k1_verify_cost: 154 * GAS_PER_US,
             // ~162 us in crypto benchmarks
             secp256k1_recover_pubkey_cost: 162 * GAS_PER_US,
+            // There is no ECDSA batch equation, so batches mainly save the per call overhead.
+            // Parsing a single public key only once saves ~10% in crypto benchmarks.
+            secp256k1_batch_verify_cost: 150 * GAS_PER_US,
+            secp256k1_batch_verify_one_pubkey_cost: 140 * GAS_PER_US,
+            // Scaled to the secp256k1_verify benchmark. Addition is dominated by
+            // decompressing the two input points.
+            secp256k1_point_add_cost: 25 * GAS_PER_US,
+            secp256k1_point_mul_cost: 110 * GAS_PER_US,
+            // ~2.6x secp256k1_verify in crypto benchmarks
+            secp256r1_verify_cost: 407 * GAS_PER_US,
+            // ~2.6x secp256k1_recover_pubkey in crypto benchmarks
+            secp256r1_recover_pubkey_cost: 422 * GAS_PER_US,
+            // BLS12-381 costs are scaled to the secp256k1_verify benchmark above.
+            // Aggregation is dominated by the subgroup checks of the inputs.
+            bls12_381_aggregate_g1_cost: 136 * GAS_PER_US,
+            bls12_381_aggregate_g1_per_point_cost: 195 * GAS_PER_US,
+            bls12_381_aggregate_g2_cost: 207 * GAS_PER_US,
+            bls12_381_aggregate_g2_per_point_cost: 455 * GAS_PER_US,
+            bls12_381_hash_to_g1_cost: 225 * GAS_PER_US,
+            bls12_381_hash_to_g2_cost: 1070 * GAS_PER_US,
+            bls12_381_pairing_equality_cost: 3300 * GAS_PER_US,
+            bls12_381_pairing_equality_per_pair_cost: 1300 * GAS_PER_US,
+            // ~12 ms for the four pairings, the per-input cost is dominated by the scalar multiplication
+            groth16_verify_bn254_cost: 12_000 * GAS_PER_US,
+            groth16_verify_bn254_per_input_cost: 30 * GAS_PER_US,
+            // ~10 ms for the four pairings, the per-input cost is dominated by the subgroup check
+            groth16_verify_bls12_381_cost: 10_000 * GAS_PER_US,
+            groth16_verify_bls12_381_per_input_cost: 1_100 * GAS_PER_US,
             // ~63 us in crypto benchmarks
             ed25519_verify_cost: 63 * GAS_PER_US,
             // Gas cost factors, relative to ed25519_verify cost
             // From https://docs.rs/ed25519-zebra/2.2.0/ed25519_zebra/batch/index.html
             ed25519_batch_verify_cost: 63 * GAS_PER_US / 2,
             ed25519_batch_verify_one_pubkey_cost: 63 * GAS_PER_US / 4,
+            // ~1.6 times the cost of ed25519 verification
+            sr25519_verify_cost: 100 * GAS_PER_US,
+            // ~1 us per call and 2-6 ns per byte in crypto benchmarks
+            keccak256_cost: GAS_PER_US,
+            keccak256_per_byte_cost: 6 * GAS_PER_US / 1000,
+            sha512_cost: GAS_PER_US,
+            sha512_per_byte_cost: 4 * GAS_PER_US / 1000,
+            blake2b512_cost: GAS_PER_US,
+            blake2b512_per_byte_cost: 2 * GAS_PER_US / 1000,
+            // Checksum computation over less than 100 characters
+            bech32_encode_cost: GAS_PER_US,
+            bech32_decode_cost: GAS_PER_US,
+            // Only serializes a small struct held in memory
+            contract_self_info_cost: GAS_PER_US,
+        }
+    }
+}
+
+/// A versioned set of all gas costs charged by the VM, i.e. the costs of the host
+/// functions (imports) and of executing Wasm operators.
+///
+/// Chains can start from one of the versioned defaults and adjust individual costs
+/// to calibrate gas to their hardware. Versions are never changed once released
+/// in order to keep gas usage stable for chains pinning a version; new defaults
+/// are added as a new version instead.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GasSchedule {
+    /// Costs of the host functions provided to contracts
+    pub host_functions: GasConfig,
+    /// Cost of executing a single Wasm operator.
+    ///
+    /// This is applied when a contract is compiled, i.e. changing it only affects
+    /// modules compiled with the new value.
+    pub wasm_operator_cost: u64,
+}
+
+impl GasSchedule {
+    /// The gas schedule of cosmwasm-vm 1.x
+    pub const fn v1() -> Self {
+        GasSchedule {
+            host_functions: GasConfig::v1(),
+            // A flat fee for each operation
+            // The target is 1 Teragas per millisecond (see GAS.md).
+            //
+            // In https://github.com/CosmWasm/cosmwasm/pull/1042 a profiler is developed to
+            // identify runtime differences between different Wasm operation, but this is not yet
+            // precise enough to derive insights from it.
+            wasm_operator_cost: 150_000,
         }
     }
 }
 
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self::v1()
+    }
+}
+
 /** context data **/
 
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
@@ -72,6 +237,9 @@ pub struct GasState {
     pub gas_limit: u64,
     /// Tracking the gas used in the Cosmos SDK, in CosmWasm gas units.
     pub externally_used_gas: u64,
+    /// Gas charged per host import. This is only recorded when enabled via
+    /// [`Instance::enable_gas_breakdown`](crate::Instance::enable_gas_breakdown).
+    pub import_gas: Option<BTreeMap<&'static str, ImportGasUsage>>,
 }
 
 impl GasState {
@@ -79,24 +247,43 @@ impl GasState {
         Self {
             gas_limit,
             externally_used_gas: 0,
+            import_gas: None,
+        }
+    }
+
+    fn record_import_gas(&mut self, import: &'static str, info: &GasInfo) {
+        if let Some(import_gas) = &mut self.import_gas {
+            let usage = import_gas.entry(import).or_default();
+            usage.cost += info.cost;
+            usage.externally_used += info.externally_used;
         }
     }
 }
 
+/// The gas charged by a single host import, e.g. `db_read` or `secp256k1_verify`,
+/// summed up over all calls of the import.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ImportGasUsage {
+    /// Gas charged by the VM according to the [`GasConfig`]
+    pub cost: u64,
+    /// Gas spend and metered externally, e.g. for storage access in the Cosmos SDK
+    pub externally_used: u64,
+}
+
 /// Additional environmental information in a debug call.
 ///
-/// The currently unused lifetime parameter 'a allows accessing referenced data in the debug implementation
-/// without cloning it.
-#[derive(Derivative)]
-#[derivative(Debug)]
+/// This allows debug handlers to attribute the message to a contract and call,
+/// e.g. when routing it into the logging system of the host.
+#[derive(Debug)]
 #[non_exhaustive]
 pub struct DebugInfo<'a> {
     pub gas_remaining: u64,
-    // This field is just to allow us to add the unused lifetime parameter. It can be removed
-    // at any time.
-    #[doc(hidden)]
-    #[derivative(Debug = "ignore")]
-    pub(crate) __lifetime: PhantomData<&'a ()>,
+    /// The entry point currently being executed, e.g. `execute` or `ibc_packet_receive`
+    pub entry_point: Option<&'a str>,
+    /// The address of the contract, if set via `Instance::set_contract_self_info`
+    pub contract_address: Option<&'a str>,
+    /// The checksum of the contract's Wasm code, if known
+    pub checksum: Option<&'a Checksum>,
 }
 
 // Unfortunately we cannot create an alias for the trait (https://github.com/rust-lang/rust/issues/41517).
@@ -113,6 +300,12 @@ pub struct Environment<A, S, Q> {
     pub memory: Option<Memory>,
     pub api: A,
     pub gas_config: GasConfig,
+    /// The maximum nesting depth of `query_chain` calls, see [`Environment::enter_query`]
+    pub max_query_depth: usize,
+    /// The maximum wall-clock time of a call into the contract, see [`Watchdog`]
+    pub max_execution_time: Option<Duration>,
+    /// The checksum of the contract's Wasm code, which is passed to the debug handler
+    pub checksum: Option<Checksum>,
     data: Arc<RwLock<ContextData<S, Q>>>,
 }
 
@@ -125,7 +318,10 @@ impl<A: BackendApi, S: Storage, Q: Querier> Clone for Environment<A, S, Q> {
         Environment {
             memory: None,
             api: self.api,
-            gas_config: self.gas_config.clone(),
+            gas_config: self.gas_config,
+            max_query_depth: self.max_query_depth,
+            max_execution_time: self.max_execution_time,
+            checksum: self.checksum,
             data: self.data.clone(),
         }
     }
@@ -137,6 +333,9 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
             memory: None,
             api,
             gas_config: GasConfig::default(),
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            max_execution_time: None,
+            checksum: None,
             data: Arc::new(RwLock::new(ContextData::new(gas_limit))),
         }
     }
@@ -147,6 +346,29 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
         })
     }
 
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn set_contract_self_info(&self, contract_self_info: Option<ContractSelfInfo>) {
+        self.with_context_data_mut(|context_data| {
+            context_data.contract_self_info = contract_self_info;
+        })
+    }
+
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn contract_self_info(&self) -> Option<ContractSelfInfo> {
+        self.with_context_data(|context_data| context_data.contract_self_info.clone())
+    }
+
+    /// Sets the name of the entry point being executed, which is passed to the debug handler
+    pub fn set_entry_point(&self, entry_point: Option<&str>) {
+        self.with_context_data_mut(|context_data| {
+            context_data.entry_point = entry_point.map(String::from);
+        })
+    }
+
+    pub fn entry_point(&self) -> Option<String> {
+        self.with_context_data(|context_data| context_data.entry_point.clone())
+    }
+
     pub fn debug_handler(&self) -> Option<Rc<RefCell<DebugHandlerFn>>> {
         self.with_context_data(|context_data| {
             // This clone here requires us to wrap the function in Rc instead of Box
@@ -154,6 +376,52 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
         })
     }
 
+    #[cfg(feature = "host_call_hooks")]
+    pub fn set_host_call_hook(&self, host_call_hook: Option<Rc<RefCell<HostCallHookFn>>>) {
+        self.with_context_data_mut(|context_data| {
+            context_data.host_call_hook = host_call_hook;
+        })
+    }
+
+    #[cfg(feature = "host_call_hooks")]
+    pub fn host_call_hook(&self) -> Option<Rc<RefCell<HostCallHookFn>>> {
+        self.with_context_data(|context_data| context_data.host_call_hook.clone())
+    }
+
+    /// Starts recording host calls into a new trace of the given capacity,
+    /// or stops recording if `capacity` is `None`
+    #[cfg(feature = "host_call_tracing")]
+    pub fn set_host_call_trace_capacity(&self, capacity: Option<usize>) {
+        self.with_context_data_mut(|context_data| {
+            context_data.host_call_trace = capacity.map(HostCallTrace::new);
+        })
+    }
+
+    #[cfg(feature = "host_call_tracing")]
+    pub fn is_host_call_tracing_enabled(&self) -> bool {
+        self.with_context_data(|context_data| context_data.host_call_trace.is_some())
+    }
+
+    #[cfg(feature = "host_call_tracing")]
+    pub fn record_host_call(&self, record: HostCallRecord) {
+        self.with_context_data_mut(|context_data| {
+            if let Some(trace) = context_data.host_call_trace.as_mut() {
+                trace.push(record);
+            }
+        })
+    }
+
+    #[cfg(feature = "host_call_tracing")]
+    pub fn host_call_trace(&self) -> Vec<HostCallRecord> {
+        self.with_context_data(|context_data| {
+            context_data
+                .host_call_trace
+                .as_ref()
+                .map(|trace| trace.records())
+                .unwrap_or_default()
+        })
+    }
+
     fn with_context_data_mut<C, R>(&self, callback: C) -> R
     where
         C: FnOnce(&mut ContextData<S, Q>) -> R,
@@ -214,11 +482,25 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
             let func = instance.exports.get_function(name)?;
             Ok(func.clone())
         })?;
-        self.increment_call_depth()?;
-        let res = func.call(store, args).map_err(|runtime_err| -> VmError {
+        let watchdog = match (self.increment_call_depth()?, self.max_execution_time) {
+            (1, Some(max_execution_time)) => Some(self.with_wasmer_instance(|instance| {
+                Watchdog::start(store, instance, max_execution_time)
+            })?),
+            _ => None,
+        };
+        let res = func.call(store, args);
+        if watchdog.map(Watchdog::finish).unwrap_or(false) {
+            self.decrement_call_depth();
+            return Err(VmError::deadline_exceeded());
+        }
+        let res = res.map_err(|runtime_err| -> VmError {
             self.with_wasmer_instance::<_, Never>(|instance| {
                 let err: VmError = match get_remaining_points(store, instance) {
-                    MeteringPoints::Remaining(_) => VmError::from(runtime_err),
+                    MeteringPoints::Remaining(_) => match runtime_err.downcast_ref::<VmError>() {
+                        // An import denied a storage write, which the caller may want to handle
+                        Some(VmError::WriteAccessDenied { .. }) => VmError::write_access_denied(),
+                        _ => VmError::from(runtime_err),
+                    },
                     MeteringPoints::Exhausted => VmError::gas_depletion(),
                 };
                 Err(err)
@@ -310,6 +592,20 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
         Ok(new)
     }
 
+    /// Marks the start of a `query_chain` call on this thread. The returned guard marks
+    /// the end of the call when dropped.
+    ///
+    /// Errors if this exceeds the maximum query depth of this environment. This is checked before
+    /// calling into the querier to prevent deep self-queries from exhausting the native stack.
+    pub fn enter_query(&self) -> VmResult<QueryDepthGuard> {
+        let new = QUERY_DEPTH.with(|depth| depth.get() + 1);
+        if new > self.max_query_depth {
+            return Err(VmError::max_query_depth_exceeded(self.max_query_depth));
+        }
+        QUERY_DEPTH.with(|depth| depth.set(new));
+        Ok(QueryDepthGuard { _private: () })
+    }
+
     /// Decrements the call depth by 1 and returns the new value
     pub fn decrement_call_depth(&self) -> usize {
         self.with_context_data_mut(|context_data| {
@@ -385,6 +681,29 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
             (context_data.storage.take(), context_data.querier.take())
         })
     }
+
+    /// Replaces the context data with a fresh one for the given gas limit, keeping only the
+    /// link to the wasmer instance. This drops the storage, querier and all handlers.
+    pub fn reset(&self, gas_limit: u64) {
+        self.with_context_data_mut(|context_data| {
+            let wasmer_instance = context_data.wasmer_instance;
+            *context_data = ContextData::new(gas_limit);
+            context_data.wasmer_instance = wasmer_instance;
+        });
+    }
+}
+
+/// Decrements the query depth of the current thread when dropped, see [`Environment::enter_query`]
+#[derive(Debug)]
+#[must_use]
+pub struct QueryDepthGuard {
+    _private: (),
+}
+
+impl Drop for QueryDepthGuard {
+    fn drop(&mut self) {
+        QUERY_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
 }
 
 pub struct ContextData<S, Q> {
@@ -394,6 +713,15 @@ pub struct ContextData<S, Q> {
     call_depth: usize,
     querier: Option<Q>,
     debug_handler: Option<Rc<RefCell<DebugHandlerFn>>>,
+    /// Information about the contract itself, set by the host
+    #[cfg(feature = "cosmwasm_1_4")]
+    contract_self_info: Option<ContractSelfInfo>,
+    /// The entry point currently being executed
+    entry_point: Option<String>,
+    #[cfg(feature = "host_call_hooks")]
+    host_call_hook: Option<Rc<RefCell<HostCallHookFn>>>,
+    #[cfg(feature = "host_call_tracing")]
+    host_call_trace: Option<HostCallTrace>,
     /// A non-owning link to the wasmer instance
     wasmer_instance: Option<NonNull<WasmerInstance>>,
 }
@@ -407,6 +735,13 @@ impl<S: Storage, Q: Querier> ContextData<S, Q> {
             call_depth: 0,
             querier: None,
             debug_handler: None,
+            #[cfg(feature = "cosmwasm_1_4")]
+            contract_self_info: None,
+            entry_point: None,
+            #[cfg(feature = "host_call_hooks")]
+            host_call_hook: None,
+            #[cfg(feature = "host_call_tracing")]
+            host_call_trace: None,
             wasmer_instance: None,
         }
     }
@@ -438,6 +773,18 @@ pub fn process_gas_info<A: BackendApi, S: Storage, Q: Querier>(
     }
 }
 
+/// Like [`process_gas_info`], but also attributes the gas to the given import
+/// if a gas breakdown is recorded.
+pub fn process_import_gas_info<A: BackendApi, S: Storage, Q: Querier>(
+    env: &Environment<A, S, Q>,
+    store: &mut impl AsStoreMut,
+    import: &'static str,
+    info: GasInfo,
+) -> VmResult<()> {
+    env.with_gas_state_mut(|gas_state| gas_state.record_import_gas(import, &info));
+    process_gas_info(env, store, info)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -490,10 +837,28 @@ mod tests {
                 "addr_validate" => Function::new_typed(&mut store, |_a: u32| -> u32 { 0 }),
                 "addr_canonicalize" => Function::new_typed(&mut store, |_a: u32, _b: u32| -> u32 { 0 }),
                 "addr_humanize" => Function::new_typed(&mut store, |_a: u32, _b: u32| -> u32 { 0 }),
+                "bech32_encode" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "bech32_decode" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
                 "secp256k1_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
                 "secp256k1_recover_pubkey" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u64 { 0 }),
+                "secp256k1_batch_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "secp256k1_point_add" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "secp256k1_point_mul" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "secp256r1_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "secp256r1_recover_pubkey" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u64 { 0 }),
+                "bls12_381_aggregate_g1" => Function::new_typed(&mut store, |_a: u32, _b: u32| -> u32 { 0 }),
+                "bls12_381_aggregate_g2" => Function::new_typed(&mut store, |_a: u32, _b: u32| -> u32 { 0 }),
+                "bls12_381_pairing_equality" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32, _d: u32| -> u32 { 0 }),
+                "bls12_381_hash_to_g1" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32, _d: u32| -> u32 { 0 }),
+                "bls12_381_hash_to_g2" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32, _d: u32| -> u32 { 0 }),
+                "groth16_verify_bn254" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "groth16_verify_bls12_381" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
                 "ed25519_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
                 "ed25519_batch_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "sr25519_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "keccak256" => Function::new_typed(&mut store, |_a: u32, _b: u32| {}),
+                "sha512" => Function::new_typed(&mut store, |_a: u32, _b: u32| {}),
+                "blake2b512" => Function::new_typed(&mut store, |_a: u32, _b: u32| {}),
                 "debug" => Function::new_typed(&mut store, |_a: u32| {}),
                 "abort" => Function::new_typed(&mut store, |_a: u32| {}),
             },
@@ -544,6 +909,36 @@ mod tests {
         assert!(endq.is_none());
     }
 
+    #[test]
+    fn enter_query_works() {
+        let (mut env, _store, _instance) = make_instance(TESTING_GAS_LIMIT);
+        env.max_query_depth = 2;
+
+        let first = env.enter_query().unwrap();
+        let second = env.enter_query().unwrap();
+        match env.enter_query().unwrap_err() {
+            VmError::MaxQueryDepthExceeded { max, .. } => assert_eq!(max, 2),
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        // dropping a guard allows entering again
+        drop(second);
+        let _second = env.enter_query().unwrap();
+        drop(first);
+        let _first = env.enter_query().unwrap();
+    }
+
+    #[test]
+    fn enter_query_fails_for_zero_max_query_depth() {
+        let (mut env, _store, _instance) = make_instance(TESTING_GAS_LIMIT);
+        env.max_query_depth = 0;
+
+        match env.enter_query().unwrap_err() {
+            VmError::MaxQueryDepthExceeded { max, .. } => assert_eq!(max, 0),
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
     #[test]
     fn process_gas_info_works_for_cost() {
         let (env, mut store, _instance) = make_instance(100);
@@ -150,6 +150,17 @@ pub enum VmError {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
+    #[error("Execution deadline exceeded.")]
+    DeadlineExceeded {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
+    #[error("Maximum query depth of {max} exceeded.")]
+    MaxQueryDepthExceeded {
+        max: usize,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
 }
 
 impl VmError {
@@ -326,6 +337,21 @@ impl VmError {
             backtrace: Backtrace::capture(),
         }
     }
+
+    pub(crate) fn deadline_exceeded() -> Self {
+        VmError::DeadlineExceeded {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    pub(crate) fn max_query_depth_exceeded(max: usize) -> Self {
+        VmError::MaxQueryDepthExceeded {
+            max,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
 }
 
 impl From<BackendError> for VmError {
@@ -593,4 +619,22 @@ mod tests {
             e => panic!("Unexpected error: {:?}", e),
         }
     }
+
+    #[test]
+    fn deadline_exceeded_works() {
+        let error = VmError::deadline_exceeded();
+        match error {
+            VmError::DeadlineExceeded { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn max_query_depth_exceeded_works() {
+        let error = VmError::max_query_depth_exceeded(10);
+        match error {
+            VmError::MaxQueryDepthExceeded { max, .. } => assert_eq!(max, 10),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
 }
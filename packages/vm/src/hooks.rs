@@ -0,0 +1,118 @@
+//! Hooks invoked at host import boundaries.
+//!
+//! This is only compiled with the `host_call_hooks` feature and intended for testing,
+//! in particular for differential fuzzing between VM versions or alternative runtimes.
+//! It is not meant to be used in production as every import call gets an additional
+//! indirection and, when a hook is set, a full copy of the contract memory.
+
+use derivative::Derivative;
+use wasmer::{Exports, Extern, Function, FunctionEnv, FunctionEnvMut, RuntimeError, Store, Value};
+
+use crate::backend::{BackendApi, Querier, Storage};
+use crate::environment::Environment;
+
+/// The point in time at which a host call hook is invoked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostCallPhase {
+    /// Right before the import implementation is executed
+    Enter,
+    /// Right after the import implementation was executed, no matter if it succeeded
+    Exit,
+}
+
+/// Information about the instance state passed to a host call hook.
+#[derive(Derivative)]
+#[derivative(Debug)]
+#[non_exhaustive]
+pub struct HostCallInfo<'a> {
+    /// The name of the import in the `env` namespace, e.g. "db_read"
+    pub import: &'a str,
+    pub phase: HostCallPhase,
+    /// The arguments the contract passed to the import
+    pub args: &'a [Value],
+    pub gas_remaining: u64,
+    /// The amount of gas that was spend and metered externally so far
+    pub gas_externally_used: u64,
+    /// A copy of the contract's linear memory
+    #[derivative(Debug = "ignore")]
+    pub memory: &'a [u8],
+}
+
+// Unfortunately we cannot create an alias for the trait (https://github.com/rust-lang/rust/issues/41517).
+// So we need to copy it in a few places.
+//
+//                            /- BEGIN TRAIT        END TRAIT \
+//                            |                              |
+//                            v                              v
+pub type HostCallHookFn = dyn for<'a> FnMut(HostCallInfo<'a>);
+
+/// Wraps all functions in `exports` such that the host call hook set in the environment
+/// is invoked before and after the original function.
+pub fn wrap_imports<A, S, Q>(
+    store: &mut Store,
+    fe: &FunctionEnv<Environment<A, S, Q>>,
+    exports: Exports,
+) -> Exports
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    let mut out = Exports::new();
+    for (name, ext) in exports.into_iter() {
+        let wrapped = match ext {
+            Extern::Function(original) => {
+                let ty = original.ty(store);
+                let import = name.clone();
+                let function = Function::new_with_env(
+                    store,
+                    fe,
+                    ty,
+                    move |mut env: FunctionEnvMut<Environment<A, S, Q>>,
+                          args: &[Value]|
+                          -> Result<Vec<Value>, RuntimeError> {
+                        call_hook(&mut env, &import, HostCallPhase::Enter, args);
+                        let result = original
+                            .call(&mut env, args)
+                            .map(|values| values.into_vec());
+                        call_hook(&mut env, &import, HostCallPhase::Exit, args);
+                        result
+                    },
+                );
+                Extern::Function(function)
+            }
+            other => other,
+        };
+        out.insert(name, wrapped);
+    }
+    out
+}
+
+fn call_hook<A, S, Q>(
+    env: &mut FunctionEnvMut<Environment<A, S, Q>>,
+    import: &str,
+    phase: HostCallPhase,
+    args: &[Value],
+) where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    let (data, mut store) = env.data_and_store_mut();
+    if let Some(hook) = data.host_call_hook() {
+        let gas_remaining = data.get_gas_left(&mut store);
+        let gas_externally_used = data.with_gas_state(|gas_state| gas_state.externally_used_gas);
+        let memory = data
+            .memory(&mut store)
+            .copy_to_vec()
+            .expect("Memory is not readable. This is a bug in the lifecycle.");
+        hook.borrow_mut()(HostCallInfo {
+            import,
+            phase,
+            args,
+            gas_remaining,
+            gas_externally_used,
+            memory: &memory,
+        });
+    }
+}
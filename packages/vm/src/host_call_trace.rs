@@ -0,0 +1,192 @@
+//! A flight recorder for host import calls.
+//!
+//! This is only compiled with the `host_call_tracing` feature. Once enabled via
+//! [`Instance::enable_host_call_tracing`](crate::Instance::enable_host_call_tracing),
+//! every host import call is recorded into a ring buffer, such that the calls leading
+//! to e.g. an error or running out of gas can be inspected by debuggers and profilers.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use wasmer::{Exports, Extern, Function, FunctionEnv, FunctionEnvMut, RuntimeError, Store, Value};
+
+use crate::backend::{BackendApi, Querier, Storage};
+use crate::environment::Environment;
+use crate::memory::region_length;
+
+/// Arguments of imports which are not pointers to a Region, as (import, argument index)
+const NON_REGION_ARGS: &[(&str, usize)] = &[
+    ("db_scan", 2),
+    ("db_next", 0),
+    ("db_scan_bounded", 2),
+    ("db_scan_bounded", 3),
+    ("secp256k1_recover_pubkey", 2),
+    ("secp256r1_recover_pubkey", 2),
+    ("bls12_381_hash_to_g1", 0),
+    ("bls12_381_hash_to_g2", 0),
+];
+
+/// A single call of a host import
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HostCallRecord {
+    /// The name of the import in the `env` namespace, e.g. "db_read"
+    pub import: String,
+    /// For every argument the length of the data in the Region it points to at the time
+    /// of the call. This is `None` for arguments that are not a pointer to a Region,
+    /// including null pointers.
+    pub arg_sizes: Vec<Option<u32>>,
+    pub gas_before: u64,
+    pub gas_after: u64,
+    pub duration: Duration,
+    /// False if the import returned an error, which aborts the contract execution
+    pub success: bool,
+}
+
+/// A ring buffer holding the most recent host calls
+#[derive(Debug)]
+pub struct HostCallTrace {
+    capacity: usize,
+    records: VecDeque<HostCallRecord>,
+}
+
+impl HostCallTrace {
+    pub fn new(capacity: usize) -> Self {
+        HostCallTrace {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Adds a record, dropping the oldest one if the buffer is full
+    pub fn push(&mut self, record: HostCallRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Returns the recorded calls, oldest first
+    pub fn records(&self) -> Vec<HostCallRecord> {
+        self.records.iter().cloned().collect()
+    }
+}
+
+/// Wraps all functions in `exports` such that calls are recorded into the
+/// host call trace of the environment if tracing is enabled.
+pub fn wrap_imports<A, S, Q>(
+    store: &mut Store,
+    fe: &FunctionEnv<Environment<A, S, Q>>,
+    exports: Exports,
+) -> Exports
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    let mut out = Exports::new();
+    for (name, ext) in exports.into_iter() {
+        let wrapped = match ext {
+            Extern::Function(original) => {
+                let ty = original.ty(store);
+                let import = name.clone();
+                let function = Function::new_with_env(
+                    store,
+                    fe,
+                    ty,
+                    move |mut env: FunctionEnvMut<Environment<A, S, Q>>,
+                          args: &[Value]|
+                          -> Result<Vec<Value>, RuntimeError> {
+                        let (data, mut store) = env.data_and_store_mut();
+                        if !data.is_host_call_tracing_enabled() {
+                            return original
+                                .call(&mut env, args)
+                                .map(|values| values.into_vec());
+                        }
+                        let arg_sizes = arg_sizes(&data.memory(&mut store), &import, args);
+                        let gas_before = data.get_gas_left(&mut store);
+
+                        let start = Instant::now();
+                        let result = original
+                            .call(&mut env, args)
+                            .map(|values| values.into_vec());
+                        let duration = start.elapsed();
+
+                        let (data, mut store) = env.data_and_store_mut();
+                        let gas_after = data.get_gas_left(&mut store);
+                        data.record_host_call(HostCallRecord {
+                            import: import.clone(),
+                            arg_sizes,
+                            gas_before,
+                            gas_after,
+                            duration,
+                            success: result.is_ok(),
+                        });
+                        result
+                    },
+                );
+                Extern::Function(function)
+            }
+            other => other,
+        };
+        out.insert(name, wrapped);
+    }
+    out
+}
+
+fn arg_sizes(memory: &wasmer::MemoryView, import: &str, args: &[Value]) -> Vec<Option<u32>> {
+    args.iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            if NON_REGION_ARGS.contains(&(import, index)) {
+                return None;
+            }
+            match arg {
+                Value::I32(ptr) => region_length(memory, *ptr as u32),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_record(import: &str) -> HostCallRecord {
+        HostCallRecord {
+            import: import.to_string(),
+            arg_sizes: vec![],
+            gas_before: 10,
+            gas_after: 5,
+            duration: Duration::from_micros(1),
+            success: true,
+        }
+    }
+
+    #[test]
+    fn host_call_trace_push_works() {
+        let mut trace = HostCallTrace::new(2);
+        assert_eq!(trace.records(), vec![]);
+
+        trace.push(make_record("db_read"));
+        assert_eq!(trace.records(), vec![make_record("db_read")]);
+
+        trace.push(make_record("db_write"));
+        trace.push(make_record("debug"));
+        assert_eq!(
+            trace.records(),
+            vec![make_record("db_write"), make_record("debug")]
+        );
+    }
+
+    #[test]
+    fn host_call_trace_push_works_for_zero_capacity() {
+        let mut trace = HostCallTrace::new(0);
+        trace.push(make_record("db_read"));
+        assert_eq!(trace.records(), vec![]);
+    }
+}
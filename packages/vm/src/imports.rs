@@ -1,11 +1,24 @@
 //! Import implementations
 
 use std::cmp::max;
-use std::marker::PhantomData;
 
+use bech32::{FromBase32, ToBase32, Variant};
+
+use cosmwasm_crypto::{
+    blake2b512, ed25519_batch_verify, ed25519_verify, keccak256, secp256k1_batch_verify,
+    secp256k1_recover_pubkey, secp256k1_verify, secp256r1_recover_pubkey, secp256r1_verify, sha512,
+    CryptoError,
+};
+use cosmwasm_crypto::{
+    bls12_381_aggregate_g1, bls12_381_aggregate_g2, bls12_381_hash_to_g1, bls12_381_hash_to_g2,
+    bls12_381_pairing_equality, HashFunction, BLS12_381_G1_POINT_LEN, BLS12_381_G2_POINT_LEN,
+};
 use cosmwasm_crypto::{
-    ed25519_batch_verify, ed25519_verify, secp256k1_recover_pubkey, secp256k1_verify, CryptoError,
+    groth16_verify_bls12_381, groth16_verify_bn254, CryptoResult, BN254_G1_POINT_LEN,
+    BN254_G2_POINT_LEN,
 };
+use cosmwasm_crypto::{secp256k1_point_add, secp256k1_point_mul, ECDSA_COMPRESSED_PUBKEY_LEN};
+use cosmwasm_crypto::{sr25519_verify, SR25519_PUBKEY_LEN, SR25519_SIGNATURE_LEN};
 use cosmwasm_crypto::{
     ECDSA_PUBKEY_MAX_LEN, ECDSA_SIGNATURE_LEN, EDDSA_PUBKEY_LEN, MESSAGE_HASH_MAX_LEN,
 };
@@ -16,7 +29,7 @@ use wasmer::{AsStoreMut, FunctionEnvMut};
 
 use crate::backend::{BackendApi, BackendError, Querier, Storage};
 use crate::conversion::{ref_to_u32, to_u32};
-use crate::environment::{process_gas_info, DebugInfo, Environment};
+use crate::environment::{process_import_gas_info, DebugInfo, Environment, GasConfig};
 use crate::errors::{CommunicationError, VmError, VmResult};
 #[cfg(feature = "iterator")]
 use crate::memory::maybe_read_region;
@@ -33,6 +46,16 @@ const KI: usize = 1024;
 const MI: usize = 1024 * 1024;
 /// Max key length for db_write/db_read/db_remove/db_scan (when VM reads the key argument from Wasm memory)
 const MAX_LENGTH_DB_KEY: usize = 64 * KI;
+/// Max length of the encoded keys for db_read_batch (when VM reads the keys argument from Wasm memory)
+const MAX_LENGTH_DB_READ_BATCH: usize = MI;
+/// Number of entries removed per backend call in db_remove_range. Gas is charged after every chunk,
+/// such that a large removal stops soon after the gas limit is reached.
+#[cfg(feature = "iterator")]
+const REMOVE_RANGE_CHUNK_SIZE: usize = 100;
+/// Max number of records returned by db_scan_bounded. Larger limits requested by the contract
+/// are capped, such that a single call cannot make the host collect an unbounded number of records.
+#[cfg(feature = "iterator")]
+const MAX_SCAN_BOUNDED_LIMIT: u32 = 1000;
 /// Max value length for db_write (when VM reads the value argument from Wasm memory)
 const MAX_LENGTH_DB_VALUE: usize = 128 * KI;
 /// Typically 20 (Cosmos SDK, Ethereum), 32 (Nano, Substrate) or 54 (MockApi)
@@ -41,6 +64,12 @@ const MAX_LENGTH_CANONICAL_ADDRESS: usize = 64;
 /// The maximum allowed size for [bech32](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#bech32)
 /// is 90 characters and we're adding some safety margin around that for other formats.
 const MAX_LENGTH_HUMAN_ADDRESS: usize = 256;
+/// The max length of a bech32 human readable part according to
+/// [BIP-173](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#bech32)
+const MAX_LENGTH_BECH32_PREFIX: usize = 83;
+/// The max length of a bech32 string according to
+/// [BIP-173](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki#bech32)
+const MAX_LENGTH_BECH32: usize = 90;
 const MAX_LENGTH_QUERY_CHAIN_REQUEST: usize = 64 * KI;
 /// Length of a serialized Ed25519  signature
 const MAX_LENGTH_ED25519_SIGNATURE: usize = 64;
@@ -52,6 +81,28 @@ const MAX_LENGTH_ED25519_MESSAGE: usize = 128 * 1024;
 /// This is an arbitrary value, for performance / memory contraints. If you need to batch-verify a
 /// larger number of signatures, let us know.
 const MAX_COUNT_ED25519_BATCH: usize = 256;
+/// Max length of a sr25519 message in bytes. Same as for Ed25519.
+const MAX_LENGTH_SR25519_MESSAGE: usize = 128 * 1024;
+/// Max number of batch secp256k1 message hashes / signatures / public_keys.
+/// This is an arbitrary value, for performance / memory contraints. If you need to batch-verify a
+/// larger number of signatures, let us know.
+const MAX_COUNT_SECP256K1_BATCH: usize = 256;
+/// Max length of the concatenated points passed to the BLS12-381 aggregation and pairing imports.
+/// This is an arbitrary value, for performance / memory contraints. Gas is charged per point.
+const MAX_LENGTH_BLS12_381_POINTS: usize = 2 * MI;
+/// Max length of a message hashed to a BLS12-381 curve point
+const MAX_LENGTH_BLS12_381_HASH_TO_CURVE_MSG: usize = 128 * KI;
+/// Max length of a domain separation tag for hashing to a BLS12-381 curve point
+const MAX_LENGTH_BLS12_381_HASH_TO_CURVE_DST: usize = 2 * KI;
+/// Max length of a Groth16 verifying key. Gas is charged per public input of the key.
+const MAX_LENGTH_GROTH16_VERIFYING_KEY: usize = 64 * KI;
+/// Max length of a Groth16 proof, which is 256 bytes for BN254 and 192 bytes for BLS12-381
+const MAX_LENGTH_GROTH16_PROOF: usize = 256;
+/// Max length of the concatenated public inputs of a Groth16 proof
+const MAX_LENGTH_GROTH16_PUBLIC_INPUTS: usize = 32 * KI;
+/// Max length of the data hashed by the keccak256, sha512 and blake2b512 imports.
+/// Gas is charged per byte.
+const MAX_LENGTH_HASH_DATA: usize = 2 * MI;
 
 /// Max length for a debug message
 const MAX_LENGTH_DEBUG: usize = 2 * MI;
@@ -76,7 +127,7 @@ pub fn do_db_read<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 's
     let key = read_region(&data.memory(&mut store), key_ptr, MAX_LENGTH_DB_KEY)?;
 
     let (result, gas_info) = data.with_storage_from_context::<_, _>(|store| Ok(store.get(&key)))?;
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "db_read", gas_info)?;
     let value = result?;
 
     let out_data = match value {
@@ -86,6 +137,40 @@ pub fn do_db_read<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 's
     write_to_contract(data, &mut store, &out_data)
 }
 
+/// Reads multiple storage entries from the VM's storage into Wasm memory.
+///
+/// The keys are passed as sections. The result contains one section per key, which is
+/// a single 0 byte if the key does not exist and a 1 byte followed by the value otherwise.
+pub fn do_db_read_batch<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    keys_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let keys = read_region(&data.memory(&mut store), keys_ptr, MAX_LENGTH_DB_READ_BATCH)?;
+    let keys = decode_sections(&keys);
+
+    let mut sections = Vec::with_capacity(keys.len());
+    for key in keys {
+        if key.len() > MAX_LENGTH_DB_KEY {
+            return Err(
+                CommunicationError::region_length_too_big(key.len(), MAX_LENGTH_DB_KEY).into(),
+            );
+        }
+        let (result, gas_info) =
+            data.with_storage_from_context::<_, _>(|store| Ok(store.get(key)))?;
+        process_import_gas_info(data, &mut store, "db_read_batch", gas_info)?;
+        let section = match result? {
+            Some(value) => [&[1u8][..], &value].concat(),
+            None => vec![0u8],
+        };
+        sections.push(section);
+    }
+
+    let out_data = encode_sections(&sections)?;
+    write_to_contract(data, &mut store, &out_data)
+}
+
 /// Writes a storage entry from Wasm memory into the VM's storage
 pub fn do_db_write<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
     mut env: FunctionEnvMut<Environment<A, S, Q>>,
@@ -103,7 +188,7 @@ pub fn do_db_write<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + '
 
     let (result, gas_info) =
         data.with_storage_from_context::<_, _>(|store| Ok(store.set(&key, &value)))?;
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "db_write", gas_info)?;
     result?;
 
     Ok(())
@@ -123,12 +208,22 @@ pub fn do_db_remove<A: BackendApi + 'static, S: Storage + 'static, Q: Querier +
 
     let (result, gas_info) =
         data.with_storage_from_context::<_, _>(|store| Ok(store.remove(&key)))?;
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "db_remove", gas_info)?;
     result?;
 
     Ok(())
 }
 
+/// Replaces `db_write` for storages that cannot be written to, see [`Storage::READONLY`]
+pub fn do_db_write_denied(_key_ptr: u32, _value_ptr: u32) -> VmResult<()> {
+    Err(VmError::write_access_denied())
+}
+
+/// Replaces `db_remove` for storages that cannot be written to, see [`Storage::READONLY`]
+pub fn do_db_remove_denied(_key_ptr: u32) -> VmResult<()> {
+    Err(VmError::write_access_denied())
+}
+
 pub fn do_addr_validate<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
     mut env: FunctionEnvMut<Environment<A, S, Q>>,
     source_ptr: u32,
@@ -150,7 +245,7 @@ pub fn do_addr_validate<A: BackendApi + 'static, S: Storage + 'static, Q: Querie
     };
 
     let (result, gas_info) = data.api.canonical_address(&source_string);
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "addr_validate", gas_info)?;
     let canonical = match result {
         Ok(data) => data,
         Err(BackendError::UserErr { msg, .. }) => {
@@ -160,7 +255,7 @@ pub fn do_addr_validate<A: BackendApi + 'static, S: Storage + 'static, Q: Querie
     };
 
     let (result, gas_info) = data.api.human_address(&canonical);
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "addr_validate", gas_info)?;
     let normalized = match result {
         Ok(addr) => addr,
         Err(BackendError::UserErr { msg, .. }) => {
@@ -198,7 +293,7 @@ pub fn do_addr_canonicalize<A: BackendApi + 'static, S: Storage + 'static, Q: Qu
     };
 
     let (result, gas_info) = data.api.canonical_address(&source_string);
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "addr_canonicalize", gas_info)?;
     match result {
         Ok(canonical) => {
             write_region(
@@ -229,7 +324,7 @@ pub fn do_addr_humanize<A: BackendApi + 'static, S: Storage + 'static, Q: Querie
     )?;
 
     let (result, gas_info) = data.api.human_address(&canonical);
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "addr_humanize", gas_info)?;
     match result {
         Ok(human) => {
             write_region(&data.memory(&mut store), destination_ptr, human.as_bytes())?;
@@ -242,6 +337,103 @@ pub fn do_addr_humanize<A: BackendApi + 'static, S: Storage + 'static, Q: Querie
     }
 }
 
+pub fn do_bech32_encode<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    prefix_ptr: u32,
+    data_ptr: u32,
+    destination_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let prefix = read_region(
+        &data.memory(&mut store),
+        prefix_ptr,
+        MAX_LENGTH_BECH32_PREFIX,
+    )?;
+    let payload = read_region(
+        &data.memory(&mut store),
+        data_ptr,
+        MAX_LENGTH_CANONICAL_ADDRESS,
+    )?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.bech32_encode_cost);
+    process_import_gas_info(data, &mut store, "bech32_encode", gas_info)?;
+    match bech32_encode(&prefix, &payload) {
+        Ok(address) => {
+            write_region(
+                &data.memory(&mut store),
+                destination_ptr,
+                address.as_bytes(),
+            )?;
+            Ok(0)
+        }
+        Err(msg) => Ok(write_to_contract(data, &mut store, msg.as_bytes())?),
+    }
+}
+
+pub fn do_bech32_decode<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    source_ptr: u32,
+    prefix_destination_ptr: u32,
+    data_destination_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let source = read_region(
+        &data.memory(&mut store),
+        source_ptr,
+        MAX_LENGTH_HUMAN_ADDRESS,
+    )?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.bech32_decode_cost);
+    process_import_gas_info(data, &mut store, "bech32_decode", gas_info)?;
+    match bech32_decode(&source) {
+        Ok((prefix, payload)) => {
+            write_region(
+                &data.memory(&mut store),
+                prefix_destination_ptr,
+                prefix.as_bytes(),
+            )?;
+            write_region(&data.memory(&mut store), data_destination_ptr, &payload)?;
+            Ok(0)
+        }
+        Err(msg) => Ok(write_to_contract(data, &mut store, msg.as_bytes())?),
+    }
+}
+
+/// Encodes the data as a bech32 (not bech32m) string with the given prefix.
+/// Errors are returned as messages for the contract.
+fn bech32_encode(prefix: &[u8], data: &[u8]) -> Result<String, String> {
+    let prefix = std::str::from_utf8(prefix).map_err(|_| "Prefix is not valid UTF-8")?;
+    let address =
+        bech32::encode(prefix, data.to_base32(), Variant::Bech32).map_err(|e| e.to_string())?;
+    if address.len() > MAX_LENGTH_BECH32 {
+        return Err(format!(
+            "Bech32 string exceeds {} characters",
+            MAX_LENGTH_BECH32
+        ));
+    }
+    Ok(address)
+}
+
+/// Decodes a bech32 (not bech32m) string into its prefix and data.
+/// Errors are returned as messages for the contract.
+fn bech32_decode(address: &[u8]) -> Result<(String, Vec<u8>), String> {
+    let address = std::str::from_utf8(address).map_err(|_| "Input is not valid UTF-8")?;
+    if address.len() > MAX_LENGTH_BECH32 {
+        return Err(format!(
+            "Bech32 string exceeds {} characters",
+            MAX_LENGTH_BECH32
+        ));
+    }
+    let (prefix, data, variant) = bech32::decode(address).map_err(|e| e.to_string())?;
+    if variant != Variant::Bech32 {
+        return Err("Expected bech32 but got bech32m".to_string());
+    }
+    let data = Vec::<u8>::from_base32(&data).map_err(|e| e.to_string())?;
+    Ok((prefix, data))
+}
+
 /// Return code (error code) for a valid signature
 const SECP256K1_VERIFY_CODE_VALID: u32 = 0;
 
@@ -261,7 +453,7 @@ pub fn do_secp256k1_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Que
     let pubkey = read_region(&data.memory(&mut store), pubkey_ptr, ECDSA_PUBKEY_MAX_LEN)?;
 
     let gas_info = GasInfo::with_cost(data.gas_config.secp256k1_verify_cost);
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "secp256k1_verify", gas_info)?;
     let result = secp256k1_verify(&hash, &signature, &pubkey);
     let code = match result {
         Ok(valid) => {
@@ -276,7 +468,78 @@ pub fn do_secp256k1_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Que
             | CryptoError::InvalidPubkeyFormat { .. }
             | CryptoError::InvalidSignatureFormat { .. }
             | CryptoError::GenericErr { .. } => err.code(),
-            CryptoError::BatchErr { .. } | CryptoError::InvalidRecoveryParam { .. } => {
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+pub fn do_secp256k1_batch_verify<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    hashes_ptr: u32,
+    signatures_ptr: u32,
+    public_keys_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let hashes = read_region(
+        &data.memory(&mut store),
+        hashes_ptr,
+        (MESSAGE_HASH_MAX_LEN + 4) * MAX_COUNT_SECP256K1_BATCH,
+    )?;
+    let signatures = read_region(
+        &data.memory(&mut store),
+        signatures_ptr,
+        (ECDSA_SIGNATURE_LEN + 4) * MAX_COUNT_SECP256K1_BATCH,
+    )?;
+    let public_keys = read_region(
+        &data.memory(&mut store),
+        public_keys_ptr,
+        (ECDSA_PUBKEY_MAX_LEN + 4) * MAX_COUNT_SECP256K1_BATCH,
+    )?;
+
+    let hashes = decode_sections(&hashes);
+    let signatures = decode_sections(&signatures);
+    let public_keys = decode_sections(&public_keys);
+
+    let gas_cost = if public_keys.len() == 1 {
+        data.gas_config.secp256k1_batch_verify_one_pubkey_cost
+    } else {
+        data.gas_config.secp256k1_batch_verify_cost
+    } * signatures.len() as u64;
+    let gas_info = GasInfo::with_cost(max(gas_cost, data.gas_config.secp256k1_verify_cost));
+    process_import_gas_info(data, &mut store, "secp256k1_batch_verify", gas_info)?;
+    let result = secp256k1_batch_verify(&hashes, &signatures, &public_keys);
+    let code = match result {
+        Ok(valid) => {
+            if valid {
+                SECP256K1_VERIFY_CODE_VALID
+            } else {
+                SECP256K1_VERIFY_CODE_INVALID
+            }
+        }
+        Err(err) => match err {
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::GenericErr { .. } => err.code(),
+            CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
                 panic!("Error must not happen for this call")
             }
         },
@@ -304,7 +567,7 @@ pub fn do_secp256k1_recover_pubkey<
     };
 
     let gas_info = GasInfo::with_cost(data.gas_config.secp256k1_recover_pubkey_cost);
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "secp256k1_recover_pubkey", gas_info)?;
     let result = secp256k1_recover_pubkey(&hash, &signature, recover_param);
     match result {
         Ok(pubkey) => {
@@ -316,7 +579,180 @@ pub fn do_secp256k1_recover_pubkey<
             | CryptoError::InvalidSignatureFormat { .. }
             | CryptoError::InvalidRecoveryParam { .. }
             | CryptoError::GenericErr { .. } => Ok(to_high_half(err.code())),
-            CryptoError::BatchErr { .. } | CryptoError::InvalidPubkeyFormat { .. } => {
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    }
+}
+
+/// Return code (error code) for a successful secp256k1 point operation
+const SECP256K1_POINT_CODE_SUCCESS: u32 = 0;
+
+/// Length of a secp256k1 scalar in bytes
+const SECP256K1_SCALAR_LEN: usize = 32;
+
+pub fn do_secp256k1_point_add<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    p_ptr: u32,
+    q_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let p = read_region(&data.memory(&mut store), p_ptr, ECDSA_PUBKEY_MAX_LEN)?;
+    let q = read_region(&data.memory(&mut store), q_ptr, ECDSA_PUBKEY_MAX_LEN)?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.secp256k1_point_add_cost);
+    process_import_gas_info(data, &mut store, "secp256k1_point_add", gas_info)?;
+    let result = secp256k1_point_add(&p, &q);
+    write_secp256k1_point_result(data, &mut store, out_ptr, result)
+}
+
+pub fn do_secp256k1_point_mul<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    point_ptr: u32,
+    scalar_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let point = read_region(&data.memory(&mut store), point_ptr, ECDSA_PUBKEY_MAX_LEN)?;
+    let scalar = read_region(&data.memory(&mut store), scalar_ptr, SECP256K1_SCALAR_LEN)?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.secp256k1_point_mul_cost);
+    process_import_gas_info(data, &mut store, "secp256k1_point_mul", gas_info)?;
+    let result = secp256k1_point_mul(&point, &scalar);
+    write_secp256k1_point_result(data, &mut store, out_ptr, result)
+}
+
+/// Writes the compressed result point of a secp256k1 point operation to `out_ptr`
+/// and returns the resulting code
+fn write_secp256k1_point_result<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    data: &Environment<A, S, Q>,
+    store: &mut impl AsStoreMut,
+    out_ptr: u32,
+    result: CryptoResult<[u8; ECDSA_COMPRESSED_PUBKEY_LEN]>,
+) -> VmResult<u32> {
+    let code = match result {
+        Ok(point) => {
+            write_region(&data.memory(store), out_ptr, &point)?;
+            SECP256K1_POINT_CODE_SUCCESS
+        }
+        Err(err) => match err {
+            CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::InvalidScalar { .. }
+            | CryptoError::GenericErr { .. } => err.code(),
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+pub fn do_secp256r1_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    hash_ptr: u32,
+    signature_ptr: u32,
+    pubkey_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let hash = read_region(&data.memory(&mut store), hash_ptr, MESSAGE_HASH_MAX_LEN)?;
+    let signature = read_region(&data.memory(&mut store), signature_ptr, ECDSA_SIGNATURE_LEN)?;
+    let pubkey = read_region(&data.memory(&mut store), pubkey_ptr, ECDSA_PUBKEY_MAX_LEN)?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.secp256r1_verify_cost);
+    process_import_gas_info(data, &mut store, "secp256r1_verify", gas_info)?;
+    let result = secp256r1_verify(&hash, &signature, &pubkey);
+    let code = match result {
+        Ok(valid) => {
+            if valid {
+                SECP256K1_VERIFY_CODE_VALID
+            } else {
+                SECP256K1_VERIFY_CODE_INVALID
+            }
+        }
+        Err(err) => match err {
+            CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::GenericErr { .. } => err.code(),
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+pub fn do_secp256r1_recover_pubkey<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    hash_ptr: u32,
+    signature_ptr: u32,
+    recover_param: u32,
+) -> VmResult<u64> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let hash = read_region(&data.memory(&mut store), hash_ptr, MESSAGE_HASH_MAX_LEN)?;
+    let signature = read_region(&data.memory(&mut store), signature_ptr, ECDSA_SIGNATURE_LEN)?;
+    let recover_param: u8 = match recover_param.try_into() {
+        Ok(rp) => rp,
+        Err(_) => return Ok((CryptoError::invalid_recovery_param().code() as u64) << 32),
+    };
+
+    let gas_info = GasInfo::with_cost(data.gas_config.secp256r1_recover_pubkey_cost);
+    process_import_gas_info(data, &mut store, "secp256r1_recover_pubkey", gas_info)?;
+    let result = secp256r1_recover_pubkey(&hash, &signature, recover_param);
+    match result {
+        Ok(pubkey) => {
+            let pubkey_ptr = write_to_contract(data, &mut store, pubkey.as_ref())?;
+            Ok(to_low_half(pubkey_ptr))
+        }
+        Err(err) => match err {
+            CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::GenericErr { .. } => Ok(to_high_half(err.code())),
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
                 panic!("Error must not happen for this call")
             }
         },
@@ -350,7 +786,7 @@ pub fn do_ed25519_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Queri
     let pubkey = read_region(&data.memory(&mut store), pubkey_ptr, EDDSA_PUBKEY_LEN)?;
 
     let gas_info = GasInfo::with_cost(data.gas_config.ed25519_verify_cost);
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "ed25519_verify", gas_info)?;
     let result = ed25519_verify(&message, &signature, &pubkey);
     let code = match result {
         Ok(valid) => {
@@ -366,7 +802,11 @@ pub fn do_ed25519_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Queri
             | CryptoError::GenericErr { .. } => err.code(),
             CryptoError::BatchErr { .. }
             | CryptoError::InvalidHashFormat { .. }
-            | CryptoError::InvalidRecoveryParam { .. } => {
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
                 panic!("Error must not happen for this call")
             }
         },
@@ -412,7 +852,7 @@ pub fn do_ed25519_batch_verify<
         data.gas_config.ed25519_batch_verify_cost
     } * signatures.len() as u64;
     let gas_info = GasInfo::with_cost(max(gas_cost, data.gas_config.ed25519_verify_cost));
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "ed25519_batch_verify", gas_info)?;
     let result = ed25519_batch_verify(&messages, &signatures, &public_keys);
     let code = match result {
         Ok(valid) => {
@@ -427,7 +867,12 @@ pub fn do_ed25519_batch_verify<
             | CryptoError::InvalidPubkeyFormat { .. }
             | CryptoError::InvalidSignatureFormat { .. }
             | CryptoError::GenericErr { .. } => err.code(),
-            CryptoError::InvalidHashFormat { .. } | CryptoError::InvalidRecoveryParam { .. } => {
+            CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
                 panic!("Error must not happen for this call")
             }
         },
@@ -435,23 +880,539 @@ pub fn do_ed25519_batch_verify<
     Ok(code)
 }
 
-/// Prints a debug message to console.
-/// This does not charge gas, so debug printing should be disabled when used in a blockchain module.
-pub fn do_debug<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+/// Return code (error code) for a valid sr25519 signature
+const SR25519_VERIFY_CODE_VALID: u32 = 0;
+
+/// Return code (error code) for an invalid sr25519 signature
+const SR25519_VERIFY_CODE_INVALID: u32 = 1;
+
+pub fn do_sr25519_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
     mut env: FunctionEnvMut<Environment<A, S, Q>>,
     message_ptr: u32,
-) -> VmResult<()> {
+    signature_ptr: u32,
+    pubkey_ptr: u32,
+) -> VmResult<u32> {
     let (data, mut store) = env.data_and_store_mut();
 
-    if let Some(debug_handler) = data.debug_handler() {
-        let message_data = read_region(&data.memory(&mut store), message_ptr, MAX_LENGTH_DEBUG)?;
-        let msg = String::from_utf8_lossy(&message_data);
-        let gas_remaining = data.get_gas_left(&mut store);
-        debug_handler.borrow_mut()(
+    let message = read_region(
+        &data.memory(&mut store),
+        message_ptr,
+        MAX_LENGTH_SR25519_MESSAGE,
+    )?;
+    let signature = read_region(
+        &data.memory(&mut store),
+        signature_ptr,
+        SR25519_SIGNATURE_LEN,
+    )?;
+    let pubkey = read_region(&data.memory(&mut store), pubkey_ptr, SR25519_PUBKEY_LEN)?;
+
+    let gas_info = GasInfo::with_cost(data.gas_config.sr25519_verify_cost);
+    process_import_gas_info(data, &mut store, "sr25519_verify", gas_info)?;
+    let result = sr25519_verify(&message, &signature, &pubkey);
+    let code = match result {
+        Ok(valid) => {
+            if valid {
+                SR25519_VERIFY_CODE_VALID
+            } else {
+                SR25519_VERIFY_CODE_INVALID
+            }
+        }
+        Err(err) => match err {
+            CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::GenericErr { .. } => err.code(),
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+/// Return code (error code) for a successful BLS12-381 operation
+const BLS12_381_CODE_SUCCESS: u32 = 0;
+
+/// Return code (error code) for a pairing equality check that holds
+const BLS12_381_PAIRING_EQUALITY_CODE_EQUAL: u32 = 0;
+
+/// Return code (error code) for a pairing equality check that does not hold
+const BLS12_381_PAIRING_EQUALITY_CODE_NOT_EQUAL: u32 = 1;
+
+pub fn do_bls12_381_aggregate_g1<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    g1s_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let g1s = read_region(
+        &data.memory(&mut store),
+        g1s_ptr,
+        MAX_LENGTH_BLS12_381_POINTS,
+    )?;
+
+    let point_count = (g1s.len() / BLS12_381_G1_POINT_LEN) as u64;
+    let gas_info = GasInfo::with_cost(
+        data.gas_config.bls12_381_aggregate_g1_cost.saturating_add(
+            data.gas_config
+                .bls12_381_aggregate_g1_per_point_cost
+                .saturating_mul(point_count),
+        ),
+    );
+    process_import_gas_info(data, &mut store, "bls12_381_aggregate_g1", gas_info)?;
+    let code = match bls12_381_aggregate_g1(&g1s) {
+        Ok(point) => {
+            write_region(&data.memory(&mut store), out_ptr, &point)?;
+            BLS12_381_CODE_SUCCESS
+        }
+        Err(err) => match err {
+            CryptoError::InvalidPoint { .. } => err.code(),
+            CryptoError::BatchErr { .. }
+            | CryptoError::GenericErr { .. }
+            | CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+pub fn do_bls12_381_aggregate_g2<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    g2s_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let g2s = read_region(
+        &data.memory(&mut store),
+        g2s_ptr,
+        MAX_LENGTH_BLS12_381_POINTS,
+    )?;
+
+    let point_count = (g2s.len() / BLS12_381_G2_POINT_LEN) as u64;
+    let gas_info = GasInfo::with_cost(
+        data.gas_config.bls12_381_aggregate_g2_cost.saturating_add(
+            data.gas_config
+                .bls12_381_aggregate_g2_per_point_cost
+                .saturating_mul(point_count),
+        ),
+    );
+    process_import_gas_info(data, &mut store, "bls12_381_aggregate_g2", gas_info)?;
+    let code = match bls12_381_aggregate_g2(&g2s) {
+        Ok(point) => {
+            write_region(&data.memory(&mut store), out_ptr, &point)?;
+            BLS12_381_CODE_SUCCESS
+        }
+        Err(err) => match err {
+            CryptoError::InvalidPoint { .. } => err.code(),
+            CryptoError::BatchErr { .. }
+            | CryptoError::GenericErr { .. }
+            | CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+pub fn do_bls12_381_pairing_equality<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    ps_ptr: u32,
+    qs_ptr: u32,
+    r_ptr: u32,
+    s_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let ps = read_region(
+        &data.memory(&mut store),
+        ps_ptr,
+        MAX_LENGTH_BLS12_381_POINTS,
+    )?;
+    let qs = read_region(
+        &data.memory(&mut store),
+        qs_ptr,
+        MAX_LENGTH_BLS12_381_POINTS,
+    )?;
+    let r = read_region(&data.memory(&mut store), r_ptr, BLS12_381_G1_POINT_LEN)?;
+    let s = read_region(&data.memory(&mut store), s_ptr, BLS12_381_G2_POINT_LEN)?;
+
+    // Charge for the larger of both lists, such that unbalanced inputs cannot be used to
+    // parse many points cheaply
+    let pair_count = (ps.len() / BLS12_381_G1_POINT_LEN).max(qs.len() / BLS12_381_G2_POINT_LEN);
+    let pair_count = pair_count as u64;
+    let gas_info = GasInfo::with_cost(
+        data.gas_config
+            .bls12_381_pairing_equality_cost
+            .saturating_add(
+                data.gas_config
+                    .bls12_381_pairing_equality_per_pair_cost
+                    .saturating_mul(pair_count),
+            ),
+    );
+    process_import_gas_info(data, &mut store, "bls12_381_pairing_equality", gas_info)?;
+    let code = match bls12_381_pairing_equality(&ps, &qs, &r, &s) {
+        Ok(equal) => {
+            if equal {
+                BLS12_381_PAIRING_EQUALITY_CODE_EQUAL
+            } else {
+                BLS12_381_PAIRING_EQUALITY_CODE_NOT_EQUAL
+            }
+        }
+        Err(err) => match err {
+            CryptoError::InvalidPoint { .. } | CryptoError::UnequalPointAmount { .. } => err.code(),
+            CryptoError::BatchErr { .. }
+            | CryptoError::GenericErr { .. }
+            | CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::UnknownHashFunction { .. }
+            | CryptoError::InvalidScalar { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+pub fn do_bls12_381_hash_to_g1<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    hash_function: u32,
+    msg_ptr: u32,
+    dst_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let msg = read_region(
+        &data.memory(&mut store),
+        msg_ptr,
+        MAX_LENGTH_BLS12_381_HASH_TO_CURVE_MSG,
+    )?;
+    let dst = read_region(
+        &data.memory(&mut store),
+        dst_ptr,
+        MAX_LENGTH_BLS12_381_HASH_TO_CURVE_DST,
+    )?;
+    let hash_function = match HashFunction::from_u32(hash_function) {
+        Ok(hash_function) => hash_function,
+        Err(err) => return Ok(err.code()),
+    };
+
+    let gas_info = GasInfo::with_cost(data.gas_config.bls12_381_hash_to_g1_cost);
+    process_import_gas_info(data, &mut store, "bls12_381_hash_to_g1", gas_info)?;
+    let point = bls12_381_hash_to_g1(hash_function, &msg, &dst);
+    write_region(&data.memory(&mut store), out_ptr, &point)?;
+    Ok(BLS12_381_CODE_SUCCESS)
+}
+
+pub fn do_bls12_381_hash_to_g2<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    hash_function: u32,
+    msg_ptr: u32,
+    dst_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let msg = read_region(
+        &data.memory(&mut store),
+        msg_ptr,
+        MAX_LENGTH_BLS12_381_HASH_TO_CURVE_MSG,
+    )?;
+    let dst = read_region(
+        &data.memory(&mut store),
+        dst_ptr,
+        MAX_LENGTH_BLS12_381_HASH_TO_CURVE_DST,
+    )?;
+    let hash_function = match HashFunction::from_u32(hash_function) {
+        Ok(hash_function) => hash_function,
+        Err(err) => return Ok(err.code()),
+    };
+
+    let gas_info = GasInfo::with_cost(data.gas_config.bls12_381_hash_to_g2_cost);
+    process_import_gas_info(data, &mut store, "bls12_381_hash_to_g2", gas_info)?;
+    let point = bls12_381_hash_to_g2(hash_function, &msg, &dst);
+    write_region(&data.memory(&mut store), out_ptr, &point)?;
+    Ok(BLS12_381_CODE_SUCCESS)
+}
+
+/// Return code (error code) for a valid Groth16 proof
+const GROTH16_VERIFY_CODE_VALID: u32 = 0;
+
+/// Return code (error code) for an invalid Groth16 proof
+const GROTH16_VERIFY_CODE_INVALID: u32 = 1;
+
+pub fn do_groth16_verify_bn254<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    env: FunctionEnvMut<Environment<A, S, Q>>,
+    verifying_key_ptr: u32,
+    proof_ptr: u32,
+    public_inputs_ptr: u32,
+) -> VmResult<u32> {
+    do_groth16_verify(
+        env,
+        "groth16_verify_bn254",
+        verifying_key_ptr,
+        proof_ptr,
+        public_inputs_ptr,
+        (
+            BN254_G1_POINT_LEN + 3 * BN254_G2_POINT_LEN,
+            BN254_G1_POINT_LEN,
+        ),
+        |gas_config| {
+            (
+                gas_config.groth16_verify_bn254_cost,
+                gas_config.groth16_verify_bn254_per_input_cost,
+            )
+        },
+        groth16_verify_bn254,
+    )
+}
+
+pub fn do_groth16_verify_bls12_381<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    env: FunctionEnvMut<Environment<A, S, Q>>,
+    verifying_key_ptr: u32,
+    proof_ptr: u32,
+    public_inputs_ptr: u32,
+) -> VmResult<u32> {
+    do_groth16_verify(
+        env,
+        "groth16_verify_bls12_381",
+        verifying_key_ptr,
+        proof_ptr,
+        public_inputs_ptr,
+        (
+            BLS12_381_G1_POINT_LEN + 3 * BLS12_381_G2_POINT_LEN,
+            BLS12_381_G1_POINT_LEN,
+        ),
+        |gas_config| {
+            (
+                gas_config.groth16_verify_bls12_381_cost,
+                gas_config.groth16_verify_bls12_381_per_input_cost,
+            )
+        },
+        groth16_verify_bls12_381,
+    )
+}
+
+/// Shared implementation of the Groth16 imports, charging gas to `import`.
+/// `key_layout` is the length of the fixed part of the verifying key and the length
+/// of a G1 point, which are used to charge gas per public input of the verifying key
+/// before any point is parsed.
+#[allow(clippy::too_many_arguments)]
+fn do_groth16_verify<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    import: &'static str,
+    verifying_key_ptr: u32,
+    proof_ptr: u32,
+    public_inputs_ptr: u32,
+    key_layout: (usize, usize),
+    costs: impl FnOnce(&GasConfig) -> (u64, u64),
+    verify: impl FnOnce(&[u8], &[u8], &[u8]) -> CryptoResult<bool>,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let verifying_key = read_region(
+        &data.memory(&mut store),
+        verifying_key_ptr,
+        MAX_LENGTH_GROTH16_VERIFYING_KEY,
+    )?;
+    let proof = read_region(
+        &data.memory(&mut store),
+        proof_ptr,
+        MAX_LENGTH_GROTH16_PROOF,
+    )?;
+    let public_inputs = read_region(
+        &data.memory(&mut store),
+        public_inputs_ptr,
+        MAX_LENGTH_GROTH16_PUBLIC_INPUTS,
+    )?;
+
+    let (fixed_len, g1_point_len) = key_layout;
+    let input_count = (verifying_key.len().saturating_sub(fixed_len) / g1_point_len) as u64;
+    let (base_cost, per_input_cost) = costs(&data.gas_config);
+    let gas_info =
+        GasInfo::with_cost(base_cost.saturating_add(per_input_cost.saturating_mul(input_count)));
+    process_import_gas_info(data, &mut store, import, gas_info)?;
+    let code = match verify(&verifying_key, &proof, &public_inputs) {
+        Ok(valid) => {
+            if valid {
+                GROTH16_VERIFY_CODE_VALID
+            } else {
+                GROTH16_VERIFY_CODE_INVALID
+            }
+        }
+        Err(err) => match err {
+            CryptoError::InvalidPoint { .. }
+            | CryptoError::InvalidScalar { .. }
+            | CryptoError::GenericErr { .. } => err.code(),
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidHashFormat { .. }
+            | CryptoError::InvalidPubkeyFormat { .. }
+            | CryptoError::InvalidSignatureFormat { .. }
+            | CryptoError::InvalidRecoveryParam { .. }
+            | CryptoError::UnequalPointAmount { .. }
+            | CryptoError::UnknownHashFunction { .. } => {
+                panic!("Error must not happen for this call")
+            }
+        },
+    };
+    Ok(code)
+}
+
+pub fn do_keccak256<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    env: FunctionEnvMut<Environment<A, S, Q>>,
+    data_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<()> {
+    do_hash(
+        env,
+        "keccak256",
+        data_ptr,
+        out_ptr,
+        |gas_config| {
+            (
+                gas_config.keccak256_cost,
+                gas_config.keccak256_per_byte_cost,
+            )
+        },
+        |data| keccak256(data).to_vec(),
+    )
+}
+
+pub fn do_sha512<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    env: FunctionEnvMut<Environment<A, S, Q>>,
+    data_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<()> {
+    do_hash(
+        env,
+        "sha512",
+        data_ptr,
+        out_ptr,
+        |gas_config| (gas_config.sha512_cost, gas_config.sha512_per_byte_cost),
+        |data| sha512(data).to_vec(),
+    )
+}
+
+pub fn do_blake2b512<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    env: FunctionEnvMut<Environment<A, S, Q>>,
+    data_ptr: u32,
+    out_ptr: u32,
+) -> VmResult<()> {
+    do_hash(
+        env,
+        "blake2b512",
+        data_ptr,
+        out_ptr,
+        |gas_config| {
+            (
+                gas_config.blake2b512_cost,
+                gas_config.blake2b512_per_byte_cost,
+            )
+        },
+        |data| blake2b512(data).to_vec(),
+    )
+}
+
+/// Shared implementation of the hash imports, charging gas to `import`. `costs` returns
+/// the base cost and the cost per input byte of the hash function.
+fn do_hash<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    import: &'static str,
+    data_ptr: u32,
+    out_ptr: u32,
+    costs: impl FnOnce(&GasConfig) -> (u64, u64),
+    hash: impl FnOnce(&[u8]) -> Vec<u8>,
+) -> VmResult<()> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let input = read_region(&data.memory(&mut store), data_ptr, MAX_LENGTH_HASH_DATA)?;
+
+    let (base_cost, per_byte_cost) = costs(&data.gas_config);
+    let gas_info = GasInfo::with_cost(
+        base_cost.saturating_add(per_byte_cost.saturating_mul(input.len() as u64)),
+    );
+    process_import_gas_info(data, &mut store, import, gas_info)?;
+    let digest = hash(&input);
+    write_region(&data.memory(&mut store), out_ptr, &digest)?;
+    Ok(())
+}
+
+/// Prints a debug message to console.
+/// This does not charge gas, so debug printing should be disabled when used in a blockchain module.
+pub fn do_debug<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    message_ptr: u32,
+) -> VmResult<()> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    if let Some(debug_handler) = data.debug_handler() {
+        let message_data = read_region(&data.memory(&mut store), message_ptr, MAX_LENGTH_DEBUG)?;
+        let msg = String::from_utf8_lossy(&message_data);
+        let gas_remaining = data.get_gas_left(&mut store);
+        let entry_point = data.entry_point();
+        #[cfg(feature = "cosmwasm_1_4")]
+        let contract_address = data
+            .contract_self_info()
+            .map(|info| info.address.into_string());
+        #[cfg(not(feature = "cosmwasm_1_4"))]
+        let contract_address: Option<String> = None;
+        debug_handler.borrow_mut()(
             &msg,
             DebugInfo {
                 gas_remaining,
-                __lifetime: PhantomData::default(),
+                entry_point: entry_point.as_deref(),
+                contract_address: contract_address.as_deref(),
+                checksum: data.checksum.as_ref(),
             },
         );
     }
@@ -482,15 +1443,41 @@ pub fn do_query_chain<A: BackendApi + 'static, S: Storage + 'static, Q: Querier
         MAX_LENGTH_QUERY_CHAIN_REQUEST,
     )?;
 
-    let gas_remaining = data.get_gas_left(&mut store);
-    let (result, gas_info) = data.with_querier_from_context::<_, _>(|querier| {
-        Ok(querier.query_raw(&request, gas_remaining))
-    })?;
-    process_gas_info(data, &mut store, gas_info)?;
+    let (result, gas_info) = {
+        // Counts as a nested query until the querier returns
+        let _query_depth_guard = data.enter_query()?;
+        let gas_remaining = data.get_gas_left(&mut store);
+        data.with_querier_from_context::<_, _>(|querier| {
+            Ok(querier.query_raw(&request, gas_remaining))
+        })?
+    };
+    process_import_gas_info(data, &mut store, "query_chain", gas_info)?;
     let serialized = to_vec(&result?)?;
     write_to_contract(data, &mut store, &serialized)
 }
 
+/// Returns the JSON serialized `ContractSelfInfo` set by the host.
+/// This is priced with a small fixed cost and does not go through the querier.
+#[cfg(feature = "cosmwasm_1_4")]
+pub fn do_contract_self_info<
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let gas_info = GasInfo::with_cost(data.gas_config.contract_self_info_cost);
+    process_import_gas_info(data, &mut store, "contract_self_info", gas_info)?;
+
+    let info = data
+        .contract_self_info()
+        .ok_or_else(|| VmError::generic_err("Contract self info not set by the host"))?;
+    let serialized = to_vec(&info)?;
+    write_to_contract(data, &mut store, &serialized)
+}
+
 #[cfg(feature = "iterator")]
 pub fn do_db_scan<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
     mut env: FunctionEnvMut<Environment<A, S, Q>>,
@@ -509,7 +1496,7 @@ pub fn do_db_scan<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 's
     let (result, gas_info) = data.with_storage_from_context::<_, _>(|store| {
         Ok(store.scan(start.as_deref(), end.as_deref(), order))
     })?;
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "db_scan", gas_info)?;
     let iterator_id = result?;
     Ok(iterator_id)
 }
@@ -524,7 +1511,7 @@ pub fn do_db_next<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 's
     let (result, gas_info) =
         data.with_storage_from_context::<_, _>(|store| Ok(store.next(iterator_id)))?;
 
-    process_gas_info(data, &mut store, gas_info)?;
+    process_import_gas_info(data, &mut store, "db_next", gas_info)?;
 
     // Empty key will later be treated as _no more element_.
     let (key, value) = result?.unwrap_or_else(|| (Vec::<u8>::new(), Vec::<u8>::new()));
@@ -533,26 +1520,95 @@ pub fn do_db_next<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 's
     write_to_contract(data, &mut store, &out_data)
 }
 
-/// Creates a Region in the contract, writes the given data to it and returns the memory location
-fn write_to_contract<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
-    data: &Environment<A, S, Q>,
-    store: &mut impl AsStoreMut,
-    input: &[u8],
-) -> VmResult<u32> {
-    let out_size = to_u32(input.len())?;
-    let result = data.call_function1(store, "allocate", &[out_size.into()])?;
-    let target_ptr = ref_to_u32(&result)?;
-    if target_ptr == 0 {
-        return Err(CommunicationError::zero_address().into());
-    }
-    write_region(&data.memory(store), target_ptr, input)?;
-    Ok(target_ptr)
-}
-
-/// Returns the data shifted by 32 bits towards the most significant bit.
+/// Loads up to `limit` records of the range in one backend call.
 ///
-/// This is independent of endianness. But to get the idea, it would be
-/// `data || 0x00000000` in big endian representation.
+/// The records are returned as sections key1, value1, key2, value2, … such that
+/// an empty result means there are no records in the range.
+#[cfg(feature = "iterator")]
+pub fn do_db_scan_bounded<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    start_ptr: u32,
+    end_ptr: u32,
+    order: i32,
+    limit: u32,
+) -> VmResult<u32> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    let start = maybe_read_region(&data.memory(&mut store), start_ptr, MAX_LENGTH_DB_KEY)?;
+    let end = maybe_read_region(&data.memory(&mut store), end_ptr, MAX_LENGTH_DB_KEY)?;
+    let order: Order = order
+        .try_into()
+        .map_err(|_| CommunicationError::invalid_order(order))?;
+
+    let limit = limit.min(MAX_SCAN_BOUNDED_LIMIT) as usize;
+    let gas_left = data.get_gas_left(&mut store);
+    let (result, gas_info) = data.with_storage_from_context::<_, _>(|store| {
+        Ok(store.scan_bounded(start.as_deref(), end.as_deref(), order, limit, gas_left))
+    })?;
+    process_import_gas_info(data, &mut store, "db_scan_bounded", gas_info)?;
+
+    let sections: Vec<Vec<u8>> = result?
+        .into_iter()
+        .flat_map(|(key, value)| [key, value])
+        .collect();
+    let out_data = encode_sections(&sections)?;
+    write_to_contract(data, &mut store, &out_data)
+}
+
+/// Replaces `db_remove_range` for storages that cannot be written to, see [`Storage::READONLY`]
+#[cfg(feature = "iterator")]
+pub fn do_db_remove_range_denied(_start_ptr: u32, _end_ptr: u32) -> VmResult<()> {
+    Err(VmError::write_access_denied())
+}
+
+#[cfg(feature = "iterator")]
+pub fn do_db_remove_range<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    mut env: FunctionEnvMut<Environment<A, S, Q>>,
+    start_ptr: u32,
+    end_ptr: u32,
+) -> VmResult<()> {
+    let (data, mut store) = env.data_and_store_mut();
+
+    if data.is_storage_readonly() {
+        return Err(VmError::write_access_denied());
+    }
+
+    let start = maybe_read_region(&data.memory(&mut store), start_ptr, MAX_LENGTH_DB_KEY)?;
+    let end = maybe_read_region(&data.memory(&mut store), end_ptr, MAX_LENGTH_DB_KEY)?;
+
+    loop {
+        let (result, gas_info) = data.with_storage_from_context::<_, _>(|store| {
+            Ok(store.remove_range(start.as_deref(), end.as_deref(), REMOVE_RANGE_CHUNK_SIZE))
+        })?;
+        process_import_gas_info(data, &mut store, "db_remove_range", gas_info)?;
+        if result? < REMOVE_RANGE_CHUNK_SIZE {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates a Region in the contract, writes the given data to it and returns the memory location
+fn write_to_contract<A: BackendApi + 'static, S: Storage + 'static, Q: Querier + 'static>(
+    data: &Environment<A, S, Q>,
+    store: &mut impl AsStoreMut,
+    input: &[u8],
+) -> VmResult<u32> {
+    let out_size = to_u32(input.len())?;
+    let result = data.call_function1(store, "allocate", &[out_size.into()])?;
+    let target_ptr = ref_to_u32(&result)?;
+    if target_ptr == 0 {
+        return Err(CommunicationError::zero_address().into());
+    }
+    write_region(&data.memory(store), target_ptr, input)?;
+    Ok(target_ptr)
+}
+
+/// Returns the data shifted by 32 bits towards the most significant bit.
+///
+/// This is independent of endianness. But to get the idea, it would be
+/// `data || 0x00000000` in big endian representation.
 #[inline]
 fn to_high_half(data: u32) -> u64 {
     // See https://stackoverflow.com/a/58956419/2013738 to understand
@@ -572,10 +1628,13 @@ fn to_low_half(data: u32) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cosmwasm_crypto::{BLS12_381_G1_GENERATOR, BLS12_381_G2_GENERATOR};
     use cosmwasm_std::{
         coins, from_binary, AllBalanceResponse, BankQuery, Binary, Empty, QueryRequest,
         SystemError, SystemResult, WasmQuery,
     };
+    #[cfg(feature = "cosmwasm_1_4")]
+    use cosmwasm_std::{Addr, ContractSelfInfo, HexBinary};
     use hex_literal::hex;
     use std::ptr::NonNull;
     use wasmer::{imports, Function, FunctionEnv, Instance as WasmerInstance, Store};
@@ -605,6 +1664,12 @@ mod tests {
     const ECDSA_SIG_HEX: &str = "207082eb2c3dfa0b454e0906051270ba4074ac93760ba9e7110cd9471475111151eb0dbbc9920e72146fb564f99d039802bf6ef2561446eb126ef364d21ee9c4";
     const ECDSA_PUBKEY_HEX: &str = "04051c1ee2190ecfb174bfe4f90763f2b4ff7517b70a2aec1876ebcfd644c4633fb03f3cfbd94b1f376e34592d9d41ccaf640bb751b00a1fadeb0c01157769eb73";
 
+    // RFC 6979, A.2.5 (P-256, SHA-256, message "sample")
+    const SECP256R1_HASH_HEX: &str =
+        "af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf";
+    const SECP256R1_SIG_HEX: &str = "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8";
+    const SECP256R1_PUBKEY_HEX: &str = "0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299";
+
     const EDDSA_MSG_HEX: &str = "";
     const EDDSA_SIG_HEX: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
     const EDDSA_PUBKEY_HEX: &str =
@@ -617,7 +1682,17 @@ mod tests {
         Store,
         Box<WasmerInstance>,
     ) {
-        let gas_limit = TESTING_GAS_LIMIT;
+        make_instance_with_gas_limit(api, TESTING_GAS_LIMIT)
+    }
+
+    fn make_instance_with_gas_limit(
+        api: MockApi,
+        gas_limit: u64,
+    ) -> (
+        FunctionEnv<Environment<MockApi, MockStorage, MockQuerier>>,
+        Store,
+        Box<WasmerInstance>,
+    ) {
         let env = Environment::new(api, gas_limit);
 
         let (engine, module) = compile(CONTRACT, &[]).unwrap();
@@ -629,18 +1704,40 @@ mod tests {
         let import_obj = imports! {
             "env" => {
                 "db_read" => Function::new_typed(&mut store, |_a: u32| -> u32 { 0 }),
+                "db_read_batch" => Function::new_typed(&mut store, |_a: u32| -> u32 { 0 }),
                 "db_write" => Function::new_typed(&mut store, |_a: u32, _b: u32| {}),
                 "db_remove" => Function::new_typed(&mut store, |_a: u32| {}),
                 "db_scan" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: i32| -> u32 { 0 }),
                 "db_next" => Function::new_typed(&mut store, |_a: u32| -> u32 { 0 }),
+                "db_scan_bounded" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: i32, _d: u32| -> u32 { 0 }),
+                "db_remove_range" => Function::new_typed(&mut store, |_a: u32, _b: u32| {}),
                 "query_chain" => Function::new_typed(&mut store, |_a: u32| -> u32 { 0 }),
+                "contract_self_info" => Function::new_typed(&mut store, || -> u32 { 0 }),
                 "addr_validate" => Function::new_typed(&mut store, |_a: u32| -> u32 { 0 }),
                 "addr_canonicalize" => Function::new_typed(&mut store, |_a: u32, _b: u32| -> u32 { 0 }),
                 "addr_humanize" => Function::new_typed(&mut store, |_a: u32, _b: u32| -> u32 { 0 }),
+                "bech32_encode" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "bech32_decode" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
                 "secp256k1_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
                 "secp256k1_recover_pubkey" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u64 { 0 }),
+                "secp256k1_batch_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "secp256k1_point_add" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "secp256k1_point_mul" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "secp256r1_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "secp256r1_recover_pubkey" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u64 { 0 }),
+                "bls12_381_aggregate_g1" => Function::new_typed(&mut store, |_a: u32, _b: u32| -> u32 { 0 }),
+                "bls12_381_aggregate_g2" => Function::new_typed(&mut store, |_a: u32, _b: u32| -> u32 { 0 }),
+                "bls12_381_pairing_equality" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32, _d: u32| -> u32 { 0 }),
+                "bls12_381_hash_to_g1" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32, _d: u32| -> u32 { 0 }),
+                "bls12_381_hash_to_g2" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32, _d: u32| -> u32 { 0 }),
+                "groth16_verify_bn254" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "groth16_verify_bls12_381" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
                 "ed25519_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
                 "ed25519_batch_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "sr25519_verify" => Function::new_typed(&mut store, |_a: u32, _b: u32, _c: u32| -> u32 { 0 }),
+                "keccak256" => Function::new_typed(&mut store, |_a: u32, _b: u32| {}),
+                "sha512" => Function::new_typed(&mut store, |_a: u32, _b: u32| {}),
+                "blake2b512" => Function::new_typed(&mut store, |_a: u32, _b: u32| {}),
                 "debug" => Function::new_typed(&mut store, |_a: u32| {}),
                 "abort" => Function::new_typed(&mut store, |_a: u32| {}),
             },
@@ -768,6 +1865,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn do_db_read_batch_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+
+        let keys = encode_sections(&[KEY2.to_vec(), b"unknown".to_vec(), KEY1.to_vec()]).unwrap();
+        let keys_ptr = write_data(&mut fe_mut, &keys);
+        let result_ptr = do_db_read_batch(fe_mut.as_mut(), keys_ptr).unwrap();
+        let result = force_read(&mut fe_mut, result_ptr);
+        assert_eq!(
+            decode_sections(&result),
+            vec![
+                [&[1u8][..], VALUE2].concat(),
+                vec![0u8],
+                [&[1u8][..], VALUE1].concat(),
+            ]
+        );
+
+        // no keys
+        let keys_ptr = write_data(&mut fe_mut, b"");
+        let result_ptr = do_db_read_batch(fe_mut.as_mut(), keys_ptr).unwrap();
+        assert_eq!(force_read(&mut fe_mut, result_ptr), b"");
+    }
+
+    #[test]
+    fn do_db_read_batch_fails_for_large_key() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+
+        let keys = encode_sections(&[KEY1.to_vec(), vec![7u8; 300 * 1024]]).unwrap();
+        let keys_ptr = write_data(&mut fe_mut, &keys);
+        let result = do_db_read_batch(fe_mut, keys_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::RegionLengthTooBig { length, .. },
+                ..
+            } => assert_eq!(length, 300 * 1024),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn do_db_write_works() {
         let api = MockApi::default();
@@ -1034,618 +2176,1352 @@ mod tests {
     }
 
     #[test]
-    fn do_addr_validate_reports_invalid_input_back_to_contract() {
+    fn do_addr_validate_reports_invalid_input_back_to_contract() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr1 = write_data(&mut fe_mut, b"fo\x80o"); // invalid UTF-8 (fo�o)
+        let source_ptr2 = write_data(&mut fe_mut, b""); // empty
+        let source_ptr3 = write_data(&mut fe_mut, b"addressexceedingaddressspacesuperlongreallylongiamensuringthatitislongerthaneverything"); // too long
+        let source_ptr4 = write_data(&mut fe_mut, b"fooBar"); // Not normalized. The definition of normalized is chain-dependent but the MockApi requires lower case.
+
+        let res = do_addr_validate(fe_mut.as_mut(), source_ptr1).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "Input is not valid UTF-8");
+
+        let res = do_addr_validate(fe_mut.as_mut(), source_ptr2).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "Input is empty");
+
+        let res = do_addr_validate(fe_mut.as_mut(), source_ptr3).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(
+            err,
+            "Invalid input: human address too long for this mock implementation (must be <= 64)."
+        );
+
+        let res = do_addr_validate(fe_mut.as_mut(), source_ptr4).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "Address is not normalized");
+    }
+
+    #[test]
+    fn do_addr_validate_fails_for_broken_backend() {
+        let api = MockApi::new_failing("Temporarily unavailable");
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(&mut fe_mut, b"foo");
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_addr_validate(fe_mut, source_ptr);
+        match result.unwrap_err() {
+            VmError::BackendErr {
+                source: BackendError::Unknown { msg, .. },
+                ..
+            } => assert_eq!(msg, "Temporarily unavailable"),
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn do_addr_validate_fails_for_large_inputs() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(&mut fe_mut, &[61; 333]);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_addr_validate(fe_mut, source_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source:
+                    CommunicationError::RegionLengthTooBig {
+                        length, max_length, ..
+                    },
+                ..
+            } => {
+                assert_eq!(length, 333);
+                assert_eq!(max_length, 256);
+            }
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn do_addr_canonicalize_works() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        let api = MockApi::default();
+
+        let source_ptr = write_data(&mut fe_mut, b"foo");
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, api.canonical_length() as u32);
+
+        leave_default_data(&mut fe_mut);
+
+        let api = MockApi::default();
+        let res = do_addr_canonicalize(fe_mut.as_mut(), source_ptr, dest_ptr).unwrap();
+        assert_eq!(res, 0);
+        let data = force_read(&mut fe_mut, dest_ptr);
+        assert_eq!(data.len(), api.canonical_length());
+    }
+
+    #[test]
+    fn do_addr_canonicalize_reports_invalid_input_back_to_contract() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr1 = write_data(&mut fe_mut, b"fo\x80o"); // invalid UTF-8 (fo�o)
+        let source_ptr2 = write_data(&mut fe_mut, b""); // empty
+        let source_ptr3 = write_data(&mut fe_mut, b"addressexceedingaddressspacesuperlongreallylongiamensuringthatitislongerthaneverything"); // too long
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
+
+        leave_default_data(&mut fe_mut);
+
+        let res = do_addr_canonicalize(fe_mut.as_mut(), source_ptr1, dest_ptr).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "Input is not valid UTF-8");
+
+        let res = do_addr_canonicalize(fe_mut.as_mut(), source_ptr2, dest_ptr).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "Input is empty");
+
+        let res = do_addr_canonicalize(fe_mut.as_mut(), source_ptr3, dest_ptr).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(
+            err,
+            "Invalid input: human address too long for this mock implementation (must be <= 64)."
+        );
+    }
+
+    #[test]
+    fn do_addr_canonicalize_fails_for_broken_backend() {
+        let api = MockApi::new_failing("Temporarily unavailable");
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(&mut fe_mut, b"foo");
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 7);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_addr_canonicalize(fe_mut.as_mut(), source_ptr, dest_ptr);
+        match result.unwrap_err() {
+            VmError::BackendErr {
+                source: BackendError::Unknown { msg, .. },
+                ..
+            } => assert_eq!(msg, "Temporarily unavailable"),
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn do_addr_canonicalize_fails_for_large_inputs() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(&mut fe_mut, &[61; 333]);
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 8);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_addr_canonicalize(fe_mut.as_mut(), source_ptr, dest_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source:
+                    CommunicationError::RegionLengthTooBig {
+                        length, max_length, ..
+                    },
+                ..
+            } => {
+                assert_eq!(length, 333);
+                assert_eq!(max_length, 256);
+            }
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn do_addr_canonicalize_fails_for_small_destination_region() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(&mut fe_mut, b"foo");
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 7);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_addr_canonicalize(fe_mut, source_ptr, dest_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::RegionTooSmall { size, required, .. },
+                ..
+            } => {
+                assert_eq!(size, 7);
+                assert_eq!(required, api.canonical_length());
+            }
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn do_addr_humanize_works() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        let api = MockApi::default();
+
+        let source_data = vec![0x22; api.canonical_length()];
+        let source_ptr = write_data(&mut fe_mut, &source_data);
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
+
+        leave_default_data(&mut fe_mut);
+
+        let error_ptr = do_addr_humanize(fe_mut.as_mut(), source_ptr, dest_ptr).unwrap();
+        assert_eq!(error_ptr, 0);
+        assert_eq!(force_read(&mut fe_mut, dest_ptr), source_data);
+    }
+
+    #[test]
+    fn do_addr_humanize_reports_invalid_input_back_to_contract() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(&mut fe_mut, b"foo"); // too short
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
+
+        leave_default_data(&mut fe_mut);
+
+        let res = do_addr_humanize(fe_mut.as_mut(), source_ptr, dest_ptr).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "Invalid input: canonical address length not correct");
+    }
+
+    #[test]
+    fn do_addr_humanize_fails_for_broken_backend() {
+        let api = MockApi::new_failing("Temporarily unavailable");
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(&mut fe_mut, b"foo\0\0\0\0\0");
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_addr_humanize(fe_mut, source_ptr, dest_ptr);
+        match result.unwrap_err() {
+            VmError::BackendErr {
+                source: BackendError::Unknown { msg, .. },
+                ..
+            } => assert_eq!(msg, "Temporarily unavailable"),
+            err => panic!("Incorrect error returned: {:?}", err),
+        };
+    }
+
+    #[test]
+    fn do_addr_humanize_fails_for_input_too_long() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(&mut fe_mut, &[61; 65]);
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_addr_humanize(fe_mut, source_ptr, dest_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source:
+                    CommunicationError::RegionLengthTooBig {
+                        length, max_length, ..
+                    },
+                ..
+            } => {
+                assert_eq!(length, 65);
+                assert_eq!(max_length, 64);
+            }
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn do_addr_humanize_fails_for_destination_region_too_small() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        let api = MockApi::default();
+
+        let source_data = vec![0x22; api.canonical_length()];
+        let source_ptr = write_data(&mut fe_mut, &source_data);
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 2);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_addr_humanize(fe_mut, source_ptr, dest_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::RegionTooSmall { size, required, .. },
+                ..
+            } => {
+                assert_eq!(size, 2);
+                assert_eq!(required, api.canonical_length());
+            }
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn do_bech32_encode_works() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        // Test vector from BIP-173
+        let prefix_ptr = write_data(&mut fe_mut, b"abcdef");
+        let data_ptr = write_data(
+            &mut fe_mut,
+            &hex!("00443214c74254b635cf84653a56d7c675be77df"),
+        );
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 90);
+
+        let error_ptr = do_bech32_encode(fe_mut.as_mut(), prefix_ptr, data_ptr, dest_ptr).unwrap();
+        assert_eq!(error_ptr, 0);
+        assert_eq!(
+            force_read(&mut fe_mut, dest_ptr),
+            b"abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw"
+        );
+    }
+
+    #[test]
+    fn do_bech32_encode_reports_invalid_input_back_to_contract() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 90);
+
+        // empty prefix
+        let prefix_ptr = write_data(&mut fe_mut, b"");
+        let data_ptr = write_data(&mut fe_mut, &[0x11; 20]);
+        let res = do_bech32_encode(fe_mut.as_mut(), prefix_ptr, data_ptr, dest_ptr).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "invalid length");
+
+        // too long result
+        let prefix_ptr = write_data(&mut fe_mut, b"cosmos");
+        let data_ptr = write_data(&mut fe_mut, &[0x11; 64]);
+        let res = do_bech32_encode(fe_mut.as_mut(), prefix_ptr, data_ptr, dest_ptr).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "Bech32 string exceeds 90 characters");
+    }
+
+    #[test]
+    fn do_bech32_decode_works() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let source_ptr = write_data(
+            &mut fe_mut,
+            b"abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+        );
+        let prefix_dest_ptr = create_empty(&mut instance, &mut fe_mut, 90);
+        let data_dest_ptr = create_empty(&mut instance, &mut fe_mut, 64);
+
+        let error_ptr =
+            do_bech32_decode(fe_mut.as_mut(), source_ptr, prefix_dest_ptr, data_dest_ptr).unwrap();
+        assert_eq!(error_ptr, 0);
+        assert_eq!(force_read(&mut fe_mut, prefix_dest_ptr), b"abcdef");
+        assert_eq!(
+            force_read(&mut fe_mut, data_dest_ptr),
+            hex!("00443214c74254b635cf84653a56d7c675be77df")
+        );
+    }
+
+    #[test]
+    fn do_bech32_decode_reports_invalid_input_back_to_contract() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let prefix_dest_ptr = create_empty(&mut instance, &mut fe_mut, 90);
+        let data_dest_ptr = create_empty(&mut instance, &mut fe_mut, 64);
+
+        // invalid checksum
+        let source_ptr = write_data(
+            &mut fe_mut,
+            b"abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxx",
+        );
+        let res =
+            do_bech32_decode(fe_mut.as_mut(), source_ptr, prefix_dest_ptr, data_dest_ptr).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "invalid checksum");
+
+        // bech32m (test vector from BIP-350)
+        let source_ptr = write_data(&mut fe_mut, b"a1lqfn3a");
+        let res =
+            do_bech32_decode(fe_mut.as_mut(), source_ptr, prefix_dest_ptr, data_dest_ptr).unwrap();
+        assert_ne!(res, 0);
+        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
+        assert_eq!(err, "Expected bech32 but got bech32m");
+    }
+
+    #[test]
+    fn do_secp256k1_verify_works() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn do_secp256k1_verify_wrong_hash_verify_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let mut hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        // alter hash
+        hash[0] ^= 0x01;
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn do_secp256k1_verify_larger_hash_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let mut hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        // extend / break hash
+        hash.push(0x00);
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        let result = do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::RegionLengthTooBig { length, .. },
+                ..
+            } => assert_eq!(length, MESSAGE_HASH_MAX_LEN + 1),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn do_secp256k1_verify_shorter_hash_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let mut hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        // reduce / break hash
+        hash.pop();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            3 // mapped InvalidHashFormat
+        );
+    }
+
+    #[test]
+    fn do_secp256k1_verify_wrong_sig_verify_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let mut sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        // alter sig
+        sig[0] ^= 0x01;
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn do_secp256k1_verify_larger_sig_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let mut sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        // extend / break sig
+        sig.push(0x00);
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        let result = do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::RegionLengthTooBig { length, .. },
+                ..
+            } => assert_eq!(length, ECDSA_SIGNATURE_LEN + 1),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn do_secp256k1_verify_shorter_sig_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let mut sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        // reduce / break sig
+        sig.pop();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            4 // mapped InvalidSignatureFormat
+        )
+    }
+
+    #[test]
+    fn do_secp256k1_verify_wrong_pubkey_format_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let mut pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        // alter pubkey format
+        pubkey[0] ^= 0x01;
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            5 // mapped InvalidPubkeyFormat
+        )
+    }
+
+    #[test]
+    fn do_secp256k1_verify_wrong_pubkey_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let mut pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        // alter pubkey
+        pubkey[1] ^= 0x01;
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            10 // mapped GenericErr
+        )
+    }
+
+    #[test]
+    fn do_secp256k1_verify_larger_pubkey_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let mut pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        // extend / break pubkey
+        pubkey.push(0x00);
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        let result = do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::RegionLengthTooBig { length, .. },
+                ..
+            } => assert_eq!(length, ECDSA_PUBKEY_MAX_LEN + 1),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn do_secp256k1_verify_shorter_pubkey_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let mut pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        // reduce / break pubkey
+        pubkey.pop();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            5 // mapped InvalidPubkeyFormat
+        )
+    }
+
+    #[test]
+    fn do_secp256k1_verify_empty_pubkey_fails() {
         let api = MockApi::default();
-        let (fe, mut store, _instance) = make_instance(api);
+        let (fe, mut store, mut _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr1 = write_data(&mut fe_mut, b"fo\x80o"); // invalid UTF-8 (fo�o)
-        let source_ptr2 = write_data(&mut fe_mut, b""); // empty
-        let source_ptr3 = write_data(&mut fe_mut, b"addressexceedingaddressspacesuperlongreallylongiamensuringthatitislongerthaneverything"); // too long
-        let source_ptr4 = write_data(&mut fe_mut, b"fooBar"); // Not normalized. The definition of normalized is chain-dependent but the MockApi requires lower case.
-
-        let res = do_addr_validate(fe_mut.as_mut(), source_ptr1).unwrap();
-        assert_ne!(res, 0);
-        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
-        assert_eq!(err, "Input is not valid UTF-8");
-
-        let res = do_addr_validate(fe_mut.as_mut(), source_ptr2).unwrap();
-        assert_ne!(res, 0);
-        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
-        assert_eq!(err, "Input is empty");
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = vec![];
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
 
-        let res = do_addr_validate(fe_mut.as_mut(), source_ptr3).unwrap();
-        assert_ne!(res, 0);
-        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
         assert_eq!(
-            err,
-            "Invalid input: human address too long for this mock implementation (must be <= 64)."
-        );
-
-        let res = do_addr_validate(fe_mut.as_mut(), source_ptr4).unwrap();
-        assert_ne!(res, 0);
-        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
-        assert_eq!(err, "Address is not normalized");
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            5 // mapped InvalidPubkeyFormat
+        )
     }
 
     #[test]
-    fn do_addr_validate_fails_for_broken_backend() {
-        let api = MockApi::new_failing("Temporarily unavailable");
-        let (fe, mut store, _instance) = make_instance(api);
+    fn do_secp256k1_verify_wrong_data_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr = write_data(&mut fe_mut, b"foo");
-
-        leave_default_data(&mut fe_mut);
+        let hash = vec![0x22; MESSAGE_HASH_MAX_LEN];
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig = vec![0x22; ECDSA_SIGNATURE_LEN];
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = vec![0x04; ECDSA_PUBKEY_MAX_LEN];
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
 
-        let result = do_addr_validate(fe_mut, source_ptr);
-        match result.unwrap_err() {
-            VmError::BackendErr {
-                source: BackendError::Unknown { msg, .. },
-                ..
-            } => assert_eq!(msg, "Temporarily unavailable"),
-            err => panic!("Incorrect error returned: {:?}", err),
-        }
+        assert_eq!(
+            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            10 // mapped GenericErr
+        )
     }
 
     #[test]
-    fn do_addr_validate_fails_for_large_inputs() {
+    fn do_secp256k1_batch_verify_works() {
         let api = MockApi::default();
-        let (fe, mut store, _instance) = make_instance(api);
+        let (fe, mut store, mut _instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr = write_data(&mut fe_mut, &[61; 333]);
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
 
-        leave_default_data(&mut fe_mut);
+        let hashes_ptr = write_data(
+            &mut fe_mut,
+            &encode_sections(&[hash.clone(), hash.clone()]).unwrap(),
+        );
+        let sigs_ptr = write_data(
+            &mut fe_mut,
+            &encode_sections(&[sig.clone(), sig.clone()]).unwrap(),
+        );
+        let pubkeys_ptr = write_data(&mut fe_mut, &encode_sections(&[pubkey]).unwrap());
+        assert_eq!(
+            do_secp256k1_batch_verify(fe_mut.as_mut(), hashes_ptr, sigs_ptr, pubkeys_ptr).unwrap(),
+            0
+        );
 
-        let result = do_addr_validate(fe_mut, source_ptr);
-        match result.unwrap_err() {
-            VmError::CommunicationErr {
-                source:
-                    CommunicationError::RegionLengthTooBig {
-                        length, max_length, ..
-                    },
-                ..
-            } => {
-                assert_eq!(length, 333);
-                assert_eq!(max_length, 256);
-            }
-            err => panic!("Incorrect error returned: {:?}", err),
-        }
+        // alter hash
+        let mut wrong_hash = hash.clone();
+        wrong_hash[0] ^= 0x01;
+        let hashes_ptr = write_data(
+            &mut fe_mut,
+            &encode_sections(&[hash.clone(), wrong_hash]).unwrap(),
+        );
+        assert_eq!(
+            do_secp256k1_batch_verify(fe_mut.as_mut(), hashes_ptr, sigs_ptr, pubkeys_ptr).unwrap(),
+            1
+        );
     }
 
     #[test]
-    fn do_addr_canonicalize_works() {
+    fn do_secp256k1_batch_verify_errors() {
         let api = MockApi::default();
-        let (fe, mut store, mut instance) = make_instance(api);
+        let (fe, mut store, mut _instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
-        let api = MockApi::default();
 
-        let source_ptr = write_data(&mut fe_mut, b"foo");
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, api.canonical_length() as u32);
+        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
 
-        leave_default_data(&mut fe_mut);
+        // mismatched number of items
+        let hashes_ptr = write_data(
+            &mut fe_mut,
+            &encode_sections(&[hash.clone(), hash.clone()]).unwrap(),
+        );
+        let sigs_ptr = write_data(
+            &mut fe_mut,
+            &encode_sections(&[sig.clone(), sig.clone(), sig.clone()]).unwrap(),
+        );
+        let pubkeys_ptr = write_data(
+            &mut fe_mut,
+            &encode_sections(std::slice::from_ref(&pubkey)).unwrap(),
+        );
+        assert_eq!(
+            do_secp256k1_batch_verify(fe_mut.as_mut(), hashes_ptr, sigs_ptr, pubkeys_ptr).unwrap(),
+            7 // mapped BatchErr
+        );
 
-        let api = MockApi::default();
-        let res = do_addr_canonicalize(fe_mut.as_mut(), source_ptr, dest_ptr).unwrap();
-        assert_eq!(res, 0);
-        let data = force_read(&mut fe_mut, dest_ptr);
-        assert_eq!(data.len(), api.canonical_length());
+        // invalid public key
+        let sigs_ptr = write_data(
+            &mut fe_mut,
+            &encode_sections(&[sig.clone(), sig.clone()]).unwrap(),
+        );
+        let pubkeys_ptr = write_data(
+            &mut fe_mut,
+            &encode_sections(&[pubkey[1..].to_vec()]).unwrap(),
+        );
+        assert_eq!(
+            do_secp256k1_batch_verify(fe_mut.as_mut(), hashes_ptr, sigs_ptr, pubkeys_ptr).unwrap(),
+            5 // mapped InvalidPubkeyFormat
+        );
     }
 
     #[test]
-    fn do_addr_canonicalize_reports_invalid_input_back_to_contract() {
+    fn do_hash_functions_work() {
         let api = MockApi::default();
         let (fe, mut store, mut instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr1 = write_data(&mut fe_mut, b"fo\x80o"); // invalid UTF-8 (fo�o)
-        let source_ptr2 = write_data(&mut fe_mut, b""); // empty
-        let source_ptr3 = write_data(&mut fe_mut, b"addressexceedingaddressspacesuperlongreallylongiamensuringthatitislongerthaneverything"); // too long
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
+        let data_ptr = write_data(&mut fe_mut, b"abc");
 
-        leave_default_data(&mut fe_mut);
-
-        let res = do_addr_canonicalize(fe_mut.as_mut(), source_ptr1, dest_ptr).unwrap();
-        assert_ne!(res, 0);
-        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
-        assert_eq!(err, "Input is not valid UTF-8");
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 32);
+        do_keccak256(fe_mut.as_mut(), data_ptr, out_ptr).unwrap();
+        assert_eq!(force_read(&mut fe_mut, out_ptr), keccak256(b"abc"));
 
-        let res = do_addr_canonicalize(fe_mut.as_mut(), source_ptr2, dest_ptr).unwrap();
-        assert_ne!(res, 0);
-        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
-        assert_eq!(err, "Input is empty");
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 64);
+        do_sha512(fe_mut.as_mut(), data_ptr, out_ptr).unwrap();
+        assert_eq!(force_read(&mut fe_mut, out_ptr), sha512(b"abc"));
 
-        let res = do_addr_canonicalize(fe_mut.as_mut(), source_ptr3, dest_ptr).unwrap();
-        assert_ne!(res, 0);
-        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
-        assert_eq!(
-            err,
-            "Invalid input: human address too long for this mock implementation (must be <= 64)."
-        );
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 64);
+        do_blake2b512(fe_mut.as_mut(), data_ptr, out_ptr).unwrap();
+        assert_eq!(force_read(&mut fe_mut, out_ptr), blake2b512(b"abc"));
     }
 
     #[test]
-    fn do_addr_canonicalize_fails_for_broken_backend() {
-        let api = MockApi::new_failing("Temporarily unavailable");
+    fn do_keccak256_charges_gas_per_byte() {
+        let api = MockApi::default();
         let (fe, mut store, mut instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr = write_data(&mut fe_mut, b"foo");
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 7);
+        let data_ptr = write_data(&mut fe_mut, &[0xab; 1000]);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 32);
 
-        leave_default_data(&mut fe_mut);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_before = env.get_gas_left(&mut store);
 
-        let result = do_addr_canonicalize(fe_mut.as_mut(), source_ptr, dest_ptr);
-        match result.unwrap_err() {
-            VmError::BackendErr {
-                source: BackendError::Unknown { msg, .. },
-                ..
-            } => assert_eq!(msg, "Temporarily unavailable"),
-            err => panic!("Incorrect error returned: {:?}", err),
-        }
+        do_keccak256(fe_mut.as_mut(), data_ptr, out_ptr).unwrap();
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_used = gas_before - env.get_gas_left(&mut store);
+        assert_eq!(
+            gas_used,
+            env.gas_config.keccak256_cost + 1000 * env.gas_config.keccak256_per_byte_cost
+        );
     }
 
     #[test]
-    fn do_addr_canonicalize_fails_for_large_inputs() {
+    fn do_keccak256_saturates_gas_cost() {
         let api = MockApi::default();
-        let (fe, mut store, mut instance) = make_instance(api);
+        let (fe, mut store, mut instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr = write_data(&mut fe_mut, &[61; 333]);
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 8);
+        let data_ptr = write_data(&mut fe_mut, &[0xab; 1000]);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 32);
 
-        leave_default_data(&mut fe_mut);
+        // 1000 bytes at this cost would wrap around to only 384 gas
+        let (env, _store) = fe_mut.data_and_store_mut();
+        env.gas_config.keccak256_per_byte_cost = u64::MAX / 1000 + 1;
 
-        let result = do_addr_canonicalize(fe_mut.as_mut(), source_ptr, dest_ptr);
+        let result = do_keccak256(fe_mut.as_mut(), data_ptr, out_ptr);
         match result.unwrap_err() {
-            VmError::CommunicationErr {
-                source:
-                    CommunicationError::RegionLengthTooBig {
-                        length, max_length, ..
-                    },
-                ..
-            } => {
-                assert_eq!(length, 333);
-                assert_eq!(max_length, 256);
-            }
+            VmError::GasDepletion { .. } => {}
             err => panic!("Incorrect error returned: {:?}", err),
         }
     }
 
     #[test]
-    fn do_addr_canonicalize_fails_for_small_destination_region() {
+    fn do_keccak256_fails_for_too_small_output_region() {
         let api = MockApi::default();
         let (fe, mut store, mut instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr = write_data(&mut fe_mut, b"foo");
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 7);
-
-        leave_default_data(&mut fe_mut);
+        let data_ptr = write_data(&mut fe_mut, b"abc");
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 31);
 
-        let result = do_addr_canonicalize(fe_mut, source_ptr, dest_ptr);
+        let result = do_keccak256(fe_mut.as_mut(), data_ptr, out_ptr);
         match result.unwrap_err() {
             VmError::CommunicationErr {
                 source: CommunicationError::RegionTooSmall { size, required, .. },
                 ..
             } => {
-                assert_eq!(size, 7);
-                assert_eq!(required, api.canonical_length());
+                assert_eq!(size, 31);
+                assert_eq!(required, 32);
             }
             err => panic!("Incorrect error returned: {:?}", err),
         }
     }
 
     #[test]
-    fn do_addr_humanize_works() {
-        let api = MockApi::default();
-        let (fe, mut store, mut instance) = make_instance(api);
-        let mut fe_mut = fe.into_mut(&mut store);
-        let api = MockApi::default();
-
-        let source_data = vec![0x22; api.canonical_length()];
-        let source_ptr = write_data(&mut fe_mut, &source_data);
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
-
-        leave_default_data(&mut fe_mut);
-
-        let error_ptr = do_addr_humanize(fe_mut.as_mut(), source_ptr, dest_ptr).unwrap();
-        assert_eq!(error_ptr, 0);
-        assert_eq!(force_read(&mut fe_mut, dest_ptr), source_data);
-    }
-
-    #[test]
-    fn do_addr_humanize_reports_invalid_input_back_to_contract() {
+    fn do_secp256k1_recover_pubkey_works() {
         let api = MockApi::default();
-        let (fe, mut store, mut instance) = make_instance(api);
-        let mut fe_mut = fe.into_mut(&mut store);
-
-        let source_ptr = write_data(&mut fe_mut, b"foo"); // too short
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
-
-        leave_default_data(&mut fe_mut);
-
-        let res = do_addr_humanize(fe_mut.as_mut(), source_ptr, dest_ptr).unwrap();
-        assert_ne!(res, 0);
-        let err = String::from_utf8(force_read(&mut fe_mut, res)).unwrap();
-        assert_eq!(err, "Invalid input: canonical address length not correct");
-    }
-
-    #[test]
-    fn do_addr_humanize_fails_for_broken_backend() {
-        let api = MockApi::new_failing("Temporarily unavailable");
-        let (fe, mut store, mut instance) = make_instance(api);
+        let (fe, mut store, mut _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr = write_data(&mut fe_mut, b"foo\0\0\0\0\0");
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
-
-        leave_default_data(&mut fe_mut);
+        // https://gist.github.com/webmaster128/130b628d83621a33579751846699ed15
+        let hash = hex!("5ae8317d34d1e595e3fa7247db80c0af4320cce1116de187f8f7e2e099c0d8d0");
+        let sig = hex!("45c0b7f8c09a9e1f1cea0c25785594427b6bf8f9f878a8af0b1abbb48e16d0920d8becd0c220f67c51217eecfd7184ef0732481c843857e6bc7fc095c4f6b788");
+        let recovery_param = 1;
+        let expected = hex!("044a071e8a6e10aada2b8cf39fa3b5fb3400b04e99ea8ae64ceea1a977dbeaf5d5f8c8fbd10b71ab14cd561f7df8eb6da50f8a8d81ba564342244d26d1d4211595");
 
-        let result = do_addr_humanize(fe_mut, source_ptr, dest_ptr);
-        match result.unwrap_err() {
-            VmError::BackendErr {
-                source: BackendError::Unknown { msg, .. },
-                ..
-            } => assert_eq!(msg, "Temporarily unavailable"),
-            err => panic!("Incorrect error returned: {:?}", err),
-        };
+        let hash_ptr = write_data(&mut fe_mut, &hash);
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let result =
+            do_secp256k1_recover_pubkey(fe_mut.as_mut(), hash_ptr, sig_ptr, recovery_param)
+                .unwrap();
+        let error = result >> 32;
+        let pubkey_ptr: u32 = (result & 0xFFFFFFFF).try_into().unwrap();
+        assert_eq!(error, 0);
+        assert_eq!(force_read(&mut fe_mut, pubkey_ptr), expected);
     }
 
+    // The secp256k1 generator point G and its multiples 2·G and 3·G
+    const SECP256K1_G: [u8; 33] =
+        hex!("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+    const SECP256K1_2G: [u8; 33] =
+        hex!("02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5");
+    const SECP256K1_3G: [u8; 33] =
+        hex!("02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+
     #[test]
-    fn do_addr_humanize_fails_for_input_too_long() {
+    fn do_secp256k1_point_add_works() {
         let api = MockApi::default();
         let (fe, mut store, mut instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let source_ptr = write_data(&mut fe_mut, &[61; 65]);
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 70);
-
-        leave_default_data(&mut fe_mut);
-
-        let result = do_addr_humanize(fe_mut, source_ptr, dest_ptr);
-        match result.unwrap_err() {
-            VmError::CommunicationErr {
-                source:
-                    CommunicationError::RegionLengthTooBig {
-                        length, max_length, ..
-                    },
-                ..
-            } => {
-                assert_eq!(length, 65);
-                assert_eq!(max_length, 64);
-            }
-            err => panic!("Incorrect error returned: {:?}", err),
-        }
+        let p_ptr = write_data(&mut fe_mut, &SECP256K1_G);
+        let q_ptr = write_data(&mut fe_mut, &SECP256K1_2G);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 33);
+        let result = do_secp256k1_point_add(fe_mut.as_mut(), p_ptr, q_ptr, out_ptr).unwrap();
+        assert_eq!(result, 0);
+        assert_eq!(force_read(&mut fe_mut, out_ptr), SECP256K1_3G);
+
+        // P + (-P) is the point at infinity
+        let mut minus_g = SECP256K1_G;
+        minus_g[0] = 0x03;
+        let q_ptr = write_data(&mut fe_mut, &minus_g);
+        let result = do_secp256k1_point_add(fe_mut.as_mut(), p_ptr, q_ptr, out_ptr).unwrap();
+        assert_eq!(result, 10); // mapped GenericErr
+
+        // invalid pubkey format
+        let q_ptr = write_data(&mut fe_mut, &SECP256K1_2G[1..]);
+        let result = do_secp256k1_point_add(fe_mut.as_mut(), p_ptr, q_ptr, out_ptr).unwrap();
+        assert_eq!(result, 5); // mapped InvalidPubkeyFormat
     }
 
     #[test]
-    fn do_addr_humanize_fails_for_destination_region_too_small() {
+    fn do_secp256k1_point_mul_works() {
         let api = MockApi::default();
         let (fe, mut store, mut instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
-        let api = MockApi::default();
 
-        let source_data = vec![0x22; api.canonical_length()];
-        let source_ptr = write_data(&mut fe_mut, &source_data);
-        let dest_ptr = create_empty(&mut instance, &mut fe_mut, 2);
+        let mut scalar = [0u8; 32];
+        scalar[31] = 3;
+        let point_ptr = write_data(&mut fe_mut, &SECP256K1_G);
+        let scalar_ptr = write_data(&mut fe_mut, &scalar);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 33);
+        let result =
+            do_secp256k1_point_mul(fe_mut.as_mut(), point_ptr, scalar_ptr, out_ptr).unwrap();
+        assert_eq!(result, 0);
+        assert_eq!(force_read(&mut fe_mut, out_ptr), SECP256K1_3G);
 
-        leave_default_data(&mut fe_mut);
+        // scalar not smaller than the group order
+        let n = hex!("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141");
+        let scalar_ptr = write_data(&mut fe_mut, &n);
+        let result =
+            do_secp256k1_point_mul(fe_mut.as_mut(), point_ptr, scalar_ptr, out_ptr).unwrap();
+        assert_eq!(result, 14); // mapped InvalidScalar
+    }
 
-        let result = do_addr_humanize(fe_mut, source_ptr, dest_ptr);
+    #[test]
+    fn do_secp256k1_point_mul_fails_for_large_scalar() {
+        let api = MockApi::default();
+        let (fe, mut store, mut instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let point_ptr = write_data(&mut fe_mut, &SECP256K1_G);
+        let scalar_ptr = write_data(&mut fe_mut, &[0x01; 33]);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, 33);
+        let result = do_secp256k1_point_mul(fe_mut.as_mut(), point_ptr, scalar_ptr, out_ptr);
         match result.unwrap_err() {
             VmError::CommunicationErr {
-                source: CommunicationError::RegionTooSmall { size, required, .. },
+                source:
+                    CommunicationError::RegionLengthTooBig {
+                        length, max_length, ..
+                    },
                 ..
             } => {
-                assert_eq!(size, 2);
-                assert_eq!(required, api.canonical_length());
+                assert_eq!(length, 33);
+                assert_eq!(max_length, 32);
             }
             err => panic!("Incorrect error returned: {:?}", err),
         }
     }
 
     #[test]
-    fn do_secp256k1_verify_works() {
+    fn do_secp256r1_verify_works() {
         let api = MockApi::default();
         let (fe, mut store, mut _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let hash = hex::decode(SECP256R1_HASH_HEX).unwrap();
         let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig = hex::decode(SECP256R1_SIG_HEX).unwrap();
         let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey = hex::decode(SECP256R1_PUBKEY_HEX).unwrap();
         let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
 
         assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            do_secp256r1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
             0
         );
     }
 
     #[test]
-    fn do_secp256k1_verify_wrong_hash_verify_fails() {
+    fn do_secp256r1_verify_wrong_hash_verify_fails() {
         let api = MockApi::default();
         let (fe, mut store, mut _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let mut hash = hex::decode(ECDSA_HASH_HEX).unwrap();
+        let mut hash = hex::decode(SECP256R1_HASH_HEX).unwrap();
         // alter hash
         hash[0] ^= 0x01;
         let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig = hex::decode(SECP256R1_SIG_HEX).unwrap();
         let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
+        let pubkey = hex::decode(SECP256R1_PUBKEY_HEX).unwrap();
         let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
 
         assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            do_secp256r1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
             1
         );
     }
 
     #[test]
-    fn do_secp256k1_verify_larger_hash_fails() {
+    fn do_secp256r1_verify_empty_pubkey_fails() {
         let api = MockApi::default();
         let (fe, mut store, mut _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let mut hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        // extend / break hash
-        hash.push(0x00);
+        let hash = hex::decode(SECP256R1_HASH_HEX).unwrap();
         let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
+        let sig = hex::decode(SECP256R1_SIG_HEX).unwrap();
         let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+        let pubkey_ptr = write_data(&mut fe_mut, b"");
 
-        let result = do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr);
-        match result.unwrap_err() {
-            VmError::CommunicationErr {
-                source: CommunicationError::RegionLengthTooBig { length, .. },
-                ..
-            } => assert_eq!(length, MESSAGE_HASH_MAX_LEN + 1),
-            e => panic!("Unexpected error: {:?}", e),
-        }
+        assert_eq!(
+            do_secp256r1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            5 // mapped InvalidPubkeyFormat
+        );
     }
 
     #[test]
-    fn do_secp256k1_verify_shorter_hash_fails() {
+    fn do_secp256r1_recover_pubkey_works() {
         let api = MockApi::default();
         let (fe, mut store, mut _instance) = make_instance(api);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let mut hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        // reduce / break hash
-        hash.pop();
+        let hash = hex::decode(SECP256R1_HASH_HEX).unwrap();
+        let sig = hex::decode(SECP256R1_SIG_HEX).unwrap();
+        let recovery_param = 0;
+        let expected = hex::decode(SECP256R1_PUBKEY_HEX).unwrap();
+
         let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
         let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
-
-        assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
-            3 // mapped InvalidHashFormat
-        );
+        let result =
+            do_secp256r1_recover_pubkey(fe_mut.as_mut(), hash_ptr, sig_ptr, recovery_param)
+                .unwrap();
+        let error = result >> 32;
+        let pubkey_ptr: u32 = (result & 0xFFFFFFFF).try_into().unwrap();
+        assert_eq!(error, 0);
+        assert_eq!(force_read(&mut fe_mut, pubkey_ptr), expected);
     }
 
     #[test]
-    fn do_secp256k1_verify_wrong_sig_verify_fails() {
+    fn do_bls12_381_aggregate_g1_works() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let (fe, mut store, mut instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let mut sig = hex::decode(ECDSA_SIG_HEX).unwrap();
-        // alter sig
-        sig[0] ^= 0x01;
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+        let g1s = BLS12_381_G1_GENERATOR.repeat(3);
+        let g1s_ptr = write_data(&mut fe_mut, &g1s);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, BLS12_381_G1_POINT_LEN as u32);
 
+        let result = do_bls12_381_aggregate_g1(fe_mut.as_mut(), g1s_ptr, out_ptr).unwrap();
+        assert_eq!(result, 0);
+        let sum = force_read(&mut fe_mut, out_ptr);
         assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
-            1
+            sum,
+            bls12_381_aggregate_g1(&BLS12_381_G1_GENERATOR.repeat(3)).unwrap()
         );
+
+        // invalid point
+        let g1s_ptr = write_data(&mut fe_mut, &BLS12_381_G1_GENERATOR[1..]);
+        let result = do_bls12_381_aggregate_g1(fe_mut.as_mut(), g1s_ptr, out_ptr).unwrap();
+        assert_eq!(result, 11); // mapped InvalidPoint
     }
 
     #[test]
-    fn do_secp256k1_verify_larger_sig_fails() {
-        let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
-        let mut fe_mut = fe.into_mut(&mut store);
+    fn do_bls12_381_aggregate_charges_base_cost() {
+        let gas_config = GasConfig::default();
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let mut sig = hex::decode(ECDSA_SIG_HEX).unwrap();
-        // extend / break sig
-        sig.push(0x00);
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+        // aggregating no points is not free
+        let gas_limit = gas_config.bls12_381_aggregate_g1_cost - 1;
+        let (fe, mut store, mut instance) =
+            make_instance_with_gas_limit(MockApi::default(), gas_limit);
+        let mut fe_mut = fe.into_mut(&mut store);
+        let g1s_ptr = write_data(&mut fe_mut, &[]);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, BLS12_381_G1_POINT_LEN as u32);
+        let result = do_bls12_381_aggregate_g1(fe_mut.as_mut(), g1s_ptr, out_ptr);
+        match result.unwrap_err() {
+            VmError::GasDepletion { .. } => {}
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
 
-        let result = do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr);
+        let gas_limit = gas_config.bls12_381_aggregate_g2_cost - 1;
+        let (fe, mut store, mut instance) =
+            make_instance_with_gas_limit(MockApi::default(), gas_limit);
+        let mut fe_mut = fe.into_mut(&mut store);
+        let g2s_ptr = write_data(&mut fe_mut, &[]);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, BLS12_381_G2_POINT_LEN as u32);
+        let result = do_bls12_381_aggregate_g2(fe_mut.as_mut(), g2s_ptr, out_ptr);
         match result.unwrap_err() {
-            VmError::CommunicationErr {
-                source: CommunicationError::RegionLengthTooBig { length, .. },
-                ..
-            } => assert_eq!(length, ECDSA_SIGNATURE_LEN + 1),
-            e => panic!("Unexpected error: {:?}", e),
+            VmError::GasDepletion { .. } => {}
+            err => panic!("Incorrect error returned: {:?}", err),
         }
     }
 
     #[test]
-    fn do_secp256k1_verify_shorter_sig_fails() {
+    fn do_bls12_381_aggregate_g2_works() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let (fe, mut store, mut instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let mut sig = hex::decode(ECDSA_SIG_HEX).unwrap();
-        // reduce / break sig
-        sig.pop();
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+        let g2s = BLS12_381_G2_GENERATOR.repeat(2);
+        let g2s_ptr = write_data(&mut fe_mut, &g2s);
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, BLS12_381_G2_POINT_LEN as u32);
 
-        assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
-            4 // mapped InvalidSignatureFormat
-        )
+        let result = do_bls12_381_aggregate_g2(fe_mut.as_mut(), g2s_ptr, out_ptr).unwrap();
+        assert_eq!(result, 0);
+        let sum = force_read(&mut fe_mut, out_ptr);
+        assert_eq!(sum, bls12_381_aggregate_g2(&g2s).unwrap());
     }
 
     #[test]
-    fn do_secp256k1_verify_wrong_pubkey_format_fails() {
+    fn do_bls12_381_pairing_equality_works() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let (fe, mut store, mut _instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let mut pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        // alter pubkey format
-        pubkey[0] ^= 0x01;
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+        let g1_double = bls12_381_aggregate_g1(&BLS12_381_G1_GENERATOR.repeat(2)).unwrap();
+        let g2_double = bls12_381_aggregate_g2(&BLS12_381_G2_GENERATOR.repeat(2)).unwrap();
 
-        assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
-            5 // mapped InvalidPubkeyFormat
-        )
+        // e(2·G1, G2) == e(G1, 2·G2)
+        let ps_ptr = write_data(&mut fe_mut, &g1_double);
+        let qs_ptr = write_data(&mut fe_mut, &BLS12_381_G2_GENERATOR);
+        let r_ptr = write_data(&mut fe_mut, &BLS12_381_G1_GENERATOR);
+        let s_ptr = write_data(&mut fe_mut, &g2_double);
+        let result =
+            do_bls12_381_pairing_equality(fe_mut.as_mut(), ps_ptr, qs_ptr, r_ptr, s_ptr).unwrap();
+        assert_eq!(result, 0);
+
+        // e(2·G1, G2) != e(G1, G2)
+        let s_ptr = write_data(&mut fe_mut, &BLS12_381_G2_GENERATOR);
+        let result =
+            do_bls12_381_pairing_equality(fe_mut.as_mut(), ps_ptr, qs_ptr, r_ptr, s_ptr).unwrap();
+        assert_eq!(result, 1);
+
+        // unequal number of points
+        let qs_ptr = write_data(&mut fe_mut, &BLS12_381_G2_GENERATOR.repeat(2));
+        let result =
+            do_bls12_381_pairing_equality(fe_mut.as_mut(), ps_ptr, qs_ptr, r_ptr, s_ptr).unwrap();
+        assert_eq!(result, 12); // mapped UnequalPointAmount
     }
 
     #[test]
-    fn do_secp256k1_verify_wrong_pubkey_fails() {
+    fn do_bls12_381_pairing_equality_charges_gas_per_pair() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let gas_config = GasConfig::default();
+        let gas_limit = gas_config.bls12_381_pairing_equality_cost
+            + gas_config.bls12_381_pairing_equality_per_pair_cost;
+        let (fe, mut store, mut _instance) = make_instance_with_gas_limit(api, gas_limit);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let mut pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        // alter pubkey
-        pubkey[1] ^= 0x01;
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
-
-        assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
-            10 // mapped GenericErr
-        )
+        let ps_ptr = write_data(&mut fe_mut, &BLS12_381_G1_GENERATOR.repeat(2));
+        let qs_ptr = write_data(&mut fe_mut, &BLS12_381_G2_GENERATOR.repeat(2));
+        let r_ptr = write_data(&mut fe_mut, &BLS12_381_G1_GENERATOR);
+        let s_ptr = write_data(&mut fe_mut, &BLS12_381_G2_GENERATOR);
+        let result = do_bls12_381_pairing_equality(fe_mut.as_mut(), ps_ptr, qs_ptr, r_ptr, s_ptr);
+        match result.unwrap_err() {
+            VmError::GasDepletion { .. } => {}
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
     }
 
     #[test]
-    fn do_secp256k1_verify_larger_pubkey_fails() {
+    fn do_bls12_381_pairing_equality_charges_gas_for_larger_list() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let gas_config = GasConfig::default();
+        let gas_limit = gas_config.bls12_381_pairing_equality_cost
+            + gas_config.bls12_381_pairing_equality_per_pair_cost;
+        let (fe, mut store, mut _instance) = make_instance_with_gas_limit(api, gas_limit);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let mut pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        // extend / break pubkey
-        pubkey.push(0x00);
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
-
-        let result = do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr);
+        // no G1 points, but many G2 points
+        let ps_ptr = write_data(&mut fe_mut, &[]);
+        let qs_ptr = write_data(&mut fe_mut, &BLS12_381_G2_GENERATOR.repeat(100));
+        let r_ptr = write_data(&mut fe_mut, &BLS12_381_G1_GENERATOR);
+        let s_ptr = write_data(&mut fe_mut, &BLS12_381_G2_GENERATOR);
+        let result = do_bls12_381_pairing_equality(fe_mut.as_mut(), ps_ptr, qs_ptr, r_ptr, s_ptr);
         match result.unwrap_err() {
-            VmError::CommunicationErr {
-                source: CommunicationError::RegionLengthTooBig { length, .. },
-                ..
-            } => assert_eq!(length, ECDSA_PUBKEY_MAX_LEN + 1),
-            e => panic!("Unexpected error: {:?}", e),
+            VmError::GasDepletion { .. } => {}
+            err => panic!("Incorrect error returned: {:?}", err),
         }
     }
 
     #[test]
-    fn do_secp256k1_verify_shorter_pubkey_fails() {
+    fn do_bls12_381_hash_to_g1_works() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let (fe, mut store, mut instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let mut pubkey = hex::decode(ECDSA_PUBKEY_HEX).unwrap();
-        // reduce / break pubkey
-        pubkey.pop();
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+        let msg_ptr = write_data(&mut fe_mut, b"message");
+        let dst_ptr = write_data(&mut fe_mut, b"dst");
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, BLS12_381_G1_POINT_LEN as u32);
 
+        let result =
+            do_bls12_381_hash_to_g1(fe_mut.as_mut(), 0, msg_ptr, dst_ptr, out_ptr).unwrap();
+        assert_eq!(result, 0);
+        let point = force_read(&mut fe_mut, out_ptr);
         assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
-            5 // mapped InvalidPubkeyFormat
-        )
+            point,
+            bls12_381_hash_to_g1(HashFunction::Sha256, b"message", b"dst")
+        );
+
+        // unknown hash function
+        let result =
+            do_bls12_381_hash_to_g1(fe_mut.as_mut(), 42, msg_ptr, dst_ptr, out_ptr).unwrap();
+        assert_eq!(result, 13); // mapped UnknownHashFunction
     }
 
     #[test]
-    fn do_secp256k1_verify_empty_pubkey_fails() {
+    fn do_bls12_381_hash_to_g2_works() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let (fe, mut store, mut instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = hex::decode(ECDSA_HASH_HEX).unwrap();
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = hex::decode(ECDSA_SIG_HEX).unwrap();
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = vec![];
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+        let msg_ptr = write_data(&mut fe_mut, b"message");
+        let dst_ptr = write_data(&mut fe_mut, b"dst");
+        let out_ptr = create_empty(&mut instance, &mut fe_mut, BLS12_381_G2_POINT_LEN as u32);
 
+        let result =
+            do_bls12_381_hash_to_g2(fe_mut.as_mut(), 0, msg_ptr, dst_ptr, out_ptr).unwrap();
+        assert_eq!(result, 0);
+        let point = force_read(&mut fe_mut, out_ptr);
         assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
-            5 // mapped InvalidPubkeyFormat
-        )
+            point,
+            bls12_381_hash_to_g2(HashFunction::Sha256, b"message", b"dst")
+        );
     }
 
+    /// Generators of the BN254 curve in the EIP-197 encoding
+    const BN254_G1_GENERATOR: [u8; 64] = hex!("00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002");
+    const BN254_G2_GENERATOR: [u8; 128] = hex!("198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa");
+    const BN254_INFINITY: [u8; 64] = [0; 64];
+
     #[test]
-    fn do_secp256k1_verify_wrong_data_fails() {
+    fn do_groth16_verify_bn254_works() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let (fe, mut store, mut _instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        let hash = vec![0x22; MESSAGE_HASH_MAX_LEN];
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig = vec![0x22; ECDSA_SIGNATURE_LEN];
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let pubkey = vec![0x04; ECDSA_PUBKEY_MAX_LEN];
-        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+        // e(-G1, G2) · e(G1, G2) · e(input · G1, G2) · e(0, G2) is 1 for input 0 only
+        let vk = [
+            &BN254_G1_GENERATOR[..],
+            &BN254_G2_GENERATOR,
+            &BN254_G2_GENERATOR,
+            &BN254_G2_GENERATOR,
+            &BN254_INFINITY,
+            &BN254_G1_GENERATOR,
+        ]
+        .concat();
+        let proof = [
+            &BN254_G1_GENERATOR[..],
+            &BN254_G2_GENERATOR,
+            &BN254_INFINITY,
+        ]
+        .concat();
+        let vk_ptr = write_data(&mut fe_mut, &vk);
+        let proof_ptr = write_data(&mut fe_mut, &proof);
+
+        let inputs_ptr = write_data(&mut fe_mut, &[0u8; 32]);
+        let result = do_groth16_verify_bn254(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        assert_eq!(result.unwrap(), 0);
 
-        assert_eq!(
-            do_secp256k1_verify(fe_mut, hash_ptr, sig_ptr, pubkey_ptr).unwrap(),
-            10 // mapped GenericErr
-        )
+        let mut input = [0u8; 32];
+        input[31] = 1;
+        let inputs_ptr = write_data(&mut fe_mut, &input);
+        let result = do_groth16_verify_bn254(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        assert_eq!(result.unwrap(), 1);
+
+        // errors
+        let inputs_ptr = write_data(&mut fe_mut, &[]);
+        let result = do_groth16_verify_bn254(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        assert_eq!(result.unwrap(), 10); // mapped GenericErr
+
+        let inputs_ptr = write_data(&mut fe_mut, &[0xff; 32]);
+        let result = do_groth16_verify_bn254(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        assert_eq!(result.unwrap(), 14); // mapped InvalidScalar
+
+        let inputs_ptr = write_data(&mut fe_mut, &input);
+        let proof_ptr = write_data(&mut fe_mut, &BN254_G2_GENERATOR.repeat(2));
+        let result = do_groth16_verify_bn254(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        assert_eq!(result.unwrap(), 11); // mapped InvalidPoint
     }
 
     #[test]
-    fn do_secp256k1_recover_pubkey_works() {
+    fn do_groth16_verify_bn254_charges_gas_per_input() {
         let api = MockApi::default();
-        let (fe, mut store, mut _instance) = make_instance(api);
+        let gas_config = GasConfig::default();
+        let gas_limit =
+            gas_config.groth16_verify_bn254_cost + gas_config.groth16_verify_bn254_per_input_cost;
+        let (fe, mut store, mut _instance) = make_instance_with_gas_limit(api, gas_limit);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        // two public inputs
+        let vk = [
+            &BN254_G1_GENERATOR[..],
+            &BN254_G2_GENERATOR.repeat(3),
+            &BN254_G1_GENERATOR.repeat(3),
+        ]
+        .concat();
+        let proof = [
+            &BN254_G1_GENERATOR[..],
+            &BN254_G2_GENERATOR,
+            &BN254_G1_GENERATOR,
+        ]
+        .concat();
+        let vk_ptr = write_data(&mut fe_mut, &vk);
+        let proof_ptr = write_data(&mut fe_mut, &proof);
+        let inputs_ptr = write_data(&mut fe_mut, &[0u8; 64]);
+        let result = do_groth16_verify_bn254(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        match result.unwrap_err() {
+            VmError::GasDepletion { .. } => {}
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn do_groth16_verify_bls12_381_works() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance_with_gas_limit(api, u64::MAX);
         let mut fe_mut = fe.into_mut(&mut store);
 
-        // https://gist.github.com/webmaster128/130b628d83621a33579751846699ed15
-        let hash = hex!("5ae8317d34d1e595e3fa7247db80c0af4320cce1116de187f8f7e2e099c0d8d0");
-        let sig = hex!("45c0b7f8c09a9e1f1cea0c25785594427b6bf8f9f878a8af0b1abbb48e16d0920d8becd0c220f67c51217eecfd7184ef0732481c843857e6bc7fc095c4f6b788");
-        let recovery_param = 1;
-        let expected = hex!("044a071e8a6e10aada2b8cf39fa3b5fb3400b04e99ea8ae64ceea1a977dbeaf5d5f8c8fbd10b71ab14cd561f7df8eb6da50f8a8d81ba564342244d26d1d4211595");
+        let g1 = |k: usize| bls12_381_aggregate_g1(&BLS12_381_G1_GENERATOR.repeat(k)).unwrap();
+        let g2 = |k: usize| bls12_381_aggregate_g2(&BLS12_381_G2_GENERATOR.repeat(k)).unwrap();
+
+        // alpha = beta = gamma = delta = 1, ic = [1, 2] and input 3 give l = 7,
+        // so a = 3, b = 4 and c = 3·4 - 1 - 7 = 4 is a valid proof
+        let vk = [&g1(1)[..], &g2(1), &g2(1), &g2(1), &g1(1), &g1(2)].concat();
+        let proof = [&g1(3)[..], &g2(4), &g1(4)].concat();
+        let vk_ptr = write_data(&mut fe_mut, &vk);
+        let proof_ptr = write_data(&mut fe_mut, &proof);
+
+        let mut input = [0u8; 32];
+        input[31] = 3;
+        let inputs_ptr = write_data(&mut fe_mut, &input);
+        let result = do_groth16_verify_bls12_381(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        assert_eq!(result.unwrap(), 0);
+
+        input[31] = 4;
+        let inputs_ptr = write_data(&mut fe_mut, &input);
+        let result = do_groth16_verify_bls12_381(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        assert_eq!(result.unwrap(), 1);
 
-        let hash_ptr = write_data(&mut fe_mut, &hash);
-        let sig_ptr = write_data(&mut fe_mut, &sig);
-        let result =
-            do_secp256k1_recover_pubkey(fe_mut.as_mut(), hash_ptr, sig_ptr, recovery_param)
-                .unwrap();
-        let error = result >> 32;
-        let pubkey_ptr: u32 = (result & 0xFFFFFFFF).try_into().unwrap();
-        assert_eq!(error, 0);
-        assert_eq!(force_read(&mut fe_mut, pubkey_ptr), expected);
+        // errors
+        let inputs_ptr = write_data(&mut fe_mut, &[0xff; 32]);
+        let result = do_groth16_verify_bls12_381(fe_mut.as_mut(), vk_ptr, proof_ptr, inputs_ptr);
+        assert_eq!(result.unwrap(), 14); // mapped InvalidScalar
     }
 
     #[test]
@@ -1885,6 +3761,90 @@ mod tests {
         )
     }
 
+    const SR25519_MSG: &[u8] = b"CosmWasm sr25519 test message";
+    const SR25519_SIG_HEX: &str = "ee5ec89ebe97fc4c5f5c3d2ddc4923fd647f287aa06f7c8739b60be2a206e21b1e2bc01f22e13cf04ec92ff40aeb9bf4964c2cf11572159f1bdd346274476585";
+    const SR25519_PUBKEY_HEX: &str =
+        "a4d007a95b28ce117e4051904503612abdcbd22b60475121f92a8b5dedac375a";
+
+    #[test]
+    fn do_sr25519_verify_works() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let msg_ptr = write_data(&mut fe_mut, SR25519_MSG);
+        let sig = hex::decode(SR25519_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(SR25519_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_sr25519_verify(fe_mut, msg_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn do_sr25519_verify_wrong_msg_verify_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let mut msg = SR25519_MSG.to_vec();
+        msg[0] ^= 0x01;
+        let msg_ptr = write_data(&mut fe_mut, &msg);
+        let sig = hex::decode(SR25519_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(SR25519_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        assert_eq!(
+            do_sr25519_verify(fe_mut, msg_ptr, sig_ptr, pubkey_ptr).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn do_sr25519_verify_shorter_pubkey_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let msg_ptr = write_data(&mut fe_mut, SR25519_MSG);
+        let sig = hex::decode(SR25519_SIG_HEX).unwrap();
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let mut pubkey = hex::decode(SR25519_PUBKEY_HEX).unwrap();
+        pubkey.pop();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        let result = do_sr25519_verify(fe_mut, msg_ptr, sig_ptr, pubkey_ptr);
+        assert_eq!(result.unwrap(), 5); // mapped InvalidPubkeyFormat
+    }
+
+    #[test]
+    fn do_sr25519_verify_larger_sig_fails() {
+        let api = MockApi::default();
+        let (fe, mut store, mut _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let msg_ptr = write_data(&mut fe_mut, SR25519_MSG);
+        let mut sig = hex::decode(SR25519_SIG_HEX).unwrap();
+        // extend / break sig
+        sig.push(0x00);
+        let sig_ptr = write_data(&mut fe_mut, &sig);
+        let pubkey = hex::decode(SR25519_PUBKEY_HEX).unwrap();
+        let pubkey_ptr = write_data(&mut fe_mut, &pubkey);
+
+        let result = do_sr25519_verify(fe_mut, msg_ptr, sig_ptr, pubkey_ptr);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::RegionLengthTooBig { length, .. },
+                ..
+            } => assert_eq!(length, SR25519_SIGNATURE_LEN + 1),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn do_query_chain_works() {
         let api = MockApi::default();
@@ -1910,6 +3870,34 @@ mod tests {
         assert_eq!(parsed_again.amount, coins(INIT_AMOUNT, INIT_DENOM));
     }
 
+    #[test]
+    fn do_query_chain_fails_when_max_query_depth_exceeded() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
+            address: INIT_ADDR.to_string(),
+        });
+        let request_data = cosmwasm_std::to_vec(&request).unwrap();
+        let request_ptr = write_data(&mut fe_mut, &request_data);
+
+        leave_default_data(&mut fe_mut);
+        fe_mut.data_mut().max_query_depth = 1;
+
+        // simulate being called from within a query
+        let outer_query = fe_mut.data().enter_query().unwrap();
+        let result = do_query_chain(fe_mut.as_mut(), request_ptr);
+        match result.unwrap_err() {
+            VmError::MaxQueryDepthExceeded { max, .. } => assert_eq!(max, 1),
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+
+        // works again once the outer query is done
+        drop(outer_query);
+        do_query_chain(fe_mut.as_mut(), request_ptr).unwrap();
+    }
+
     #[test]
     fn do_query_chain_fails_for_broken_request() {
         let api = MockApi::default();
@@ -1964,6 +3952,52 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn do_contract_self_info_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+        let info = ContractSelfInfo {
+            address: Addr::unchecked("cosmwasm1self"),
+            code_id: 42,
+            checksum: HexBinary::from([0xAA; 32]),
+        };
+        fe_mut.data().set_contract_self_info(Some(info.clone()));
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_before = env.get_gas_left(&mut store);
+
+        let response_ptr = do_contract_self_info(fe_mut.as_mut()).unwrap();
+        let response = force_read(&mut fe_mut, response_ptr);
+        let parsed: ContractSelfInfo = cosmwasm_std::from_slice(&response).unwrap();
+        assert_eq!(parsed, info);
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let gas_used = gas_before - env.get_gas_left(&mut store);
+        assert!(gas_used >= env.gas_config.contract_self_info_cost);
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn do_contract_self_info_fails_when_not_set() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+
+        let result = do_contract_self_info(fe_mut.as_mut());
+        match result.unwrap_err() {
+            VmError::GenericErr { msg, .. } => {
+                assert_eq!(msg, "Contract self info not set by the host")
+            }
+            err => panic!("Incorrect error returned: {:?}", err),
+        }
+    }
+
     #[test]
     #[cfg(feature = "iterator")]
     fn do_db_scan_unbound_works() {
@@ -2172,4 +4206,219 @@ mod tests {
             e => panic!("Unexpected error: {:?}", e),
         }
     }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_scan_bounded_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+
+        // unbounded
+        let region_ptr =
+            do_db_scan_bounded(fe_mut.as_mut(), 0, 0, Order::Ascending.into(), 10).unwrap();
+        assert_eq!(
+            force_read(&mut fe_mut, region_ptr),
+            [
+                KEY1,
+                b"\0\0\0\x03",
+                VALUE1,
+                b"\0\0\0\x06",
+                KEY2,
+                b"\0\0\0\x04",
+                VALUE2,
+                b"\0\0\0\x05"
+            ]
+            .concat()
+        );
+
+        // limited
+        let region_ptr =
+            do_db_scan_bounded(fe_mut.as_mut(), 0, 0, Order::Descending.into(), 1).unwrap();
+        assert_eq!(
+            force_read(&mut fe_mut, region_ptr),
+            [KEY2, b"\0\0\0\x04", VALUE2, b"\0\0\0\x05"].concat()
+        );
+
+        // bounded
+        let start = write_data(&mut fe_mut, b"anna");
+        let end = write_data(&mut fe_mut, b"bert");
+        let region_ptr =
+            do_db_scan_bounded(fe_mut.as_mut(), start, end, Order::Ascending.into(), 10).unwrap();
+        assert_eq!(
+            force_read(&mut fe_mut, region_ptr),
+            [KEY1, b"\0\0\0\x03", VALUE1, b"\0\0\0\x06"].concat()
+        );
+
+        // empty
+        let region_ptr =
+            do_db_scan_bounded(fe_mut.as_mut(), 0, 0, Order::Ascending.into(), 0).unwrap();
+        assert_eq!(force_read(&mut fe_mut, region_ptr), b"");
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_scan_bounded_caps_limit() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+        // More records than the cap, since the default data is there as well
+        fe_mut
+            .data()
+            .with_storage_from_context::<_, _>(|store| {
+                for i in 0..MAX_SCAN_BOUNDED_LIMIT {
+                    store.set(&i.to_be_bytes(), b"x").0.unwrap();
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        let region_ptr =
+            do_db_scan_bounded(fe_mut.as_mut(), 0, 0, Order::Ascending.into(), u32::MAX).unwrap();
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        let data = read_region(&env.memory(&mut store), region_ptr, 100 * KI).unwrap();
+        // The first records have a 4 byte key and a 1 byte value, each followed by its length
+        assert_eq!(
+            data.len(),
+            MAX_SCAN_BOUNDED_LIMIT as usize * (4 + 4 + 1 + 4)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_scan_bounded_errors_for_invalid_order_value() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+        leave_default_data(&mut fe_mut);
+
+        let result = do_db_scan_bounded(fe_mut, 0, 0, 42, 10);
+        match result.unwrap_err() {
+            VmError::CommunicationErr {
+                source: CommunicationError::InvalidOrder { .. },
+                ..
+            } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_remove_range_bound_works() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        let start = write_data(&mut fe_mut, b"anna");
+        let end = write_data(&mut fe_mut, b"bert");
+
+        leave_default_data(&mut fe_mut);
+
+        do_db_remove_range(fe_mut.as_mut(), start, end).unwrap();
+
+        let value = fe_mut
+            .data()
+            .with_storage_from_context::<_, _>(|store| Ok(store.get(KEY1)))
+            .unwrap()
+            .0
+            .unwrap();
+        assert_eq!(value, None);
+        let value = fe_mut
+            .data()
+            .with_storage_from_context::<_, _>(|store| Ok(store.get(KEY2)))
+            .unwrap()
+            .0
+            .unwrap();
+        assert_eq!(value, Some(VALUE2.to_vec()));
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_remove_range_removes_multiple_chunks() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+
+        let count = 2 * REMOVE_RANGE_CHUNK_SIZE + 50;
+        for i in 0..count {
+            let key = format!("key{:04}", i);
+            fe_mut
+                .data()
+                .with_storage_from_context::<_, _>(|store| Ok(store.set(key.as_bytes(), b"v")))
+                .unwrap()
+                .0
+                .unwrap();
+        }
+
+        // unbounded
+        do_db_remove_range(fe_mut.as_mut(), 0, 0).unwrap();
+
+        let id = do_db_scan(fe_mut.as_mut(), 0, 0, Order::Ascending.into()).unwrap();
+        let item = fe_mut
+            .data()
+            .with_storage_from_context::<_, _>(|store| Ok(store.next(id)))
+            .unwrap();
+        assert!(item.0.unwrap().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_remove_range_stops_when_out_of_gas() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+
+        let count = 2 * REMOVE_RANGE_CHUNK_SIZE;
+        for i in 0..count {
+            let key = format!("key{:04}", i);
+            fe_mut
+                .data()
+                .with_storage_from_context::<_, _>(|store| Ok(store.set(key.as_bytes(), b"v")))
+                .unwrap()
+                .0
+                .unwrap();
+        }
+
+        let (env, mut store) = fe_mut.data_and_store_mut();
+        env.set_gas_left(&mut store, 1000);
+
+        let result = do_db_remove_range(fe_mut.as_mut(), 0, 0);
+        match result.unwrap_err() {
+            VmError::GasDepletion { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+
+        // removal stopped at the chunk exceeding the gas limit
+        let value = fe_mut
+            .data()
+            .with_storage_from_context::<_, _>(|store| Ok(store.get(KEY2)))
+            .unwrap()
+            .0
+            .unwrap();
+        assert_eq!(value, Some(VALUE2.to_vec()));
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_remove_range_is_prohibited_in_readonly_contexts() {
+        let api = MockApi::default();
+        let (fe, mut store, _instance) = make_instance(api);
+        let mut fe_mut = fe.into_mut(&mut store);
+
+        leave_default_data(&mut fe_mut);
+        fe_mut.data().set_storage_readonly(true);
+
+        let result = do_db_remove_range(fe_mut, 0, 0);
+        match result.unwrap_err() {
+            VmError::WriteAccessDenied { .. } => {}
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
 }
@@ -1,31 +1,56 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::ptr::NonNull;
 use std::rc::Rc;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+#[cfg(feature = "cosmwasm_1_4")]
+use cosmwasm_std::ContractSelfInfo;
 use wasmer::{
-    Exports, Function, FunctionEnv, Imports, Instance as WasmerInstance, Module, Store, Value,
+    Exports, Extern, Function, FunctionEnv, Global, Imports, Instance as WasmerInstance, Module,
+    Mutability, Store, Value,
 };
 
 use crate::backend::{Backend, BackendApi, Querier, Storage};
 use crate::capabilities::required_capabilities_from_module;
+use crate::checksum::Checksum;
 use crate::conversion::{ref_to_u32, to_u32};
-use crate::environment::Environment;
+use crate::environment::{DebugHandlerFn, Environment, GasConfig, GasSchedule, ImportGasUsage};
 use crate::errors::{CommunicationError, VmError, VmResult};
+#[cfg(feature = "host_call_hooks")]
+use crate::hooks::HostCallInfo;
+#[cfg(feature = "host_call_tracing")]
+use crate::host_call_trace::HostCallRecord;
+#[cfg(feature = "cosmwasm_1_4")]
+use crate::imports::do_contract_self_info;
 use crate::imports::{
-    do_abort, do_addr_canonicalize, do_addr_humanize, do_addr_validate, do_db_read, do_db_remove,
-    do_db_write, do_debug, do_ed25519_batch_verify, do_ed25519_verify, do_query_chain,
-    do_secp256k1_recover_pubkey, do_secp256k1_verify,
+    do_abort, do_addr_canonicalize, do_addr_humanize, do_addr_validate, do_bech32_decode,
+    do_bech32_encode, do_blake2b512, do_bls12_381_aggregate_g1, do_bls12_381_aggregate_g2,
+    do_bls12_381_hash_to_g1, do_bls12_381_hash_to_g2, do_bls12_381_pairing_equality, do_db_read,
+    do_db_read_batch, do_db_remove, do_db_remove_denied, do_db_write, do_db_write_denied, do_debug,
+    do_ed25519_batch_verify, do_ed25519_verify, do_groth16_verify_bls12_381,
+    do_groth16_verify_bn254, do_keccak256, do_query_chain, do_secp256k1_batch_verify,
+    do_secp256k1_point_add, do_secp256k1_point_mul, do_secp256k1_recover_pubkey,
+    do_secp256k1_verify, do_secp256r1_recover_pubkey, do_secp256r1_verify, do_sha512,
+    do_sr25519_verify,
 };
 #[cfg(feature = "iterator")]
-use crate::imports::{do_db_next, do_db_scan};
+use crate::imports::{
+    do_db_next, do_db_remove_range, do_db_remove_range_denied, do_db_scan, do_db_scan_bounded,
+};
 use crate::memory::{read_region, write_region};
 use crate::size::Size;
-use crate::wasm_backend::{compile, make_store_with_engine};
+use crate::wasm_backend::{
+    compile_with_operator_cost, make_store_with_engine, INTERRUPT_FLAG_EXPORT,
+};
 
 pub use crate::environment::DebugInfo; // Re-exported as public via to be usable for set_debug_handler
 
+/// The optional export of contracts using an arena allocator, which releases all its memory at once
+pub(crate) const RESET_EXPORT: &str = "cosmwasm_arena_reset";
+
 #[derive(Copy, Clone, Debug)]
 pub struct GasReport {
     /// The original limit the instance was created with
@@ -39,11 +64,83 @@ pub struct GasReport {
     pub used_internally: u64,
 }
 
-#[derive(Copy, Clone, Debug)]
+/// The gas used by an instance, split up by where it was spend.
+/// See [`Instance::enable_gas_breakdown`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasBreakdown {
+    /// Gas used for executing Wasm, i.e. all internally used gas that was not charged by an import
+    pub wasm: u64,
+    /// Gas charged per import, keyed by the name of the import, e.g. "db_read".
+    /// Imports that did not charge any gas are not included.
+    pub imports: BTreeMap<&'static str, ImportGasUsage>,
+}
+
+/// A debug handler that can be passed to many instances via [`InstanceOptions::debug_handler`].
+///
+/// In contrast to [`Instance::set_debug_handler`], the handler is shared and thus must
+/// be `Fn + Send + Sync`.
+#[derive(Clone)]
+pub struct SharedDebugHandler(Arc<SharedDebugHandlerFn>);
+
+type SharedDebugHandlerFn = dyn for<'a, 'b> Fn(/* msg */ &'a str, DebugInfo<'b>) + Send + Sync;
+
+impl SharedDebugHandler {
+    pub fn new<H>(debug_handler: H) -> Self
+    where
+        H: for<'a, 'b> Fn(/* msg */ &'a str, DebugInfo<'b>) + Send + Sync + 'static,
+    {
+        SharedDebugHandler(Arc::new(debug_handler))
+    }
+
+    fn to_handler_fn(&self) -> Rc<RefCell<DebugHandlerFn>> {
+        let handler = Arc::clone(&self.0);
+        Rc::new(RefCell::new(move |msg: &str, info: DebugInfo<'_>| {
+            handler(msg, info)
+        }))
+    }
+}
+
+impl fmt::Debug for SharedDebugHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedDebugHandler")
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct InstanceOptions {
     /// Gas limit measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
     pub gas_limit: u64,
+    /// Prints debug messages of the contract to stderr. Ignored if `debug_handler` is set.
     pub print_debug: bool,
+    /// Receives the debug messages of the contract together with the contract's checksum,
+    /// the remaining gas and the call context, e.g. to route them into structured logs.
+    /// Takes precedence over `print_debug`.
+    pub debug_handler: Option<SharedDebugHandler>,
+    /// When set, `ibc_channel_open` is executed with read-only storage, i.e. any attempt of the
+    /// contract to write to storage fails with [`VmError::WriteAccessDenied`]. The handshake
+    /// should only validate the channel and version, so this catches buggy contracts early.
+    pub readonly_ibc_channel_open: bool,
+    /// The gas costs charged by the instance.
+    ///
+    /// The Wasm operator cost is applied when compiling the contract. Instances created
+    /// from a [`Cache`](crate::Cache) use the modules compiled with the schedule of the cache.
+    pub gas_schedule: GasSchedule,
+    /// The maximum nesting depth of queries via `query_chain`, e.g. a contract querying itself
+    /// recursively. Exceeding it fails with [`VmError::MaxQueryDepthExceeded`] independently
+    /// of the remaining gas, such that deep query chains cannot exhaust the native stack.
+    /// Use [`DEFAULT_MAX_QUERY_DEPTH`](crate::DEFAULT_MAX_QUERY_DEPTH) if unsure.
+    pub max_query_depth: usize,
+    /// The maximum wall-clock time of a single call into the contract. When exceeded, the call
+    /// is interrupted and fails with [`VmError::DeadlineExceeded`]. This is a backstop against
+    /// gas mispricing.
+    ///
+    /// Whether a call is interrupted depends on the speed of the node, so this must only be
+    /// used for queries and simulations and never for calls that are part of consensus.
+    ///
+    /// Every call with a maximum execution time starts a thread which sets an interrupt flag
+    /// once the time is up. Code inserted at the start of functions and loops checks this flag,
+    /// such that the contract traps no matter if it calls imports or not.
+    pub max_execution_time: Option<Duration>,
 }
 
 pub struct Instance<A: BackendApi, S: Storage, Q: Querier> {
@@ -55,6 +152,19 @@ pub struct Instance<A: BackendApi, S: Storage, Q: Querier> {
     _inner: Box<WasmerInstance>,
     fe: FunctionEnv<Environment<A, S, Q>>,
     store: Store,
+    /// True iff the contract exports `cosmwasm_arena_reset` with signature `() -> ()`
+    /// to release the memory of its arena allocator.
+    /// See the `arena_allocator` feature of cosmwasm-std.
+    has_reset_export: bool,
+    /// See [`InstanceOptions::readonly_ibc_channel_open`]
+    readonly_ibc_channel_open: bool,
+    /// True iff a call into the contract failed. In this case the state of the contract's
+    /// globals is unknown and the instance must not be reused.
+    failed_call: bool,
+    /// All mutable globals of the contract in export order, excluding the ones of the gas
+    /// metering. Internal globals are exported when compiling the module, such that this
+    /// includes e.g. the stack pointer.
+    mutable_globals: Vec<Global>,
 }
 
 impl<A, S, Q> Instance<A, S, Q>
@@ -71,17 +181,25 @@ where
         options: InstanceOptions,
         memory_limit: Option<Size>,
     ) -> VmResult<Self> {
-        let (engine, module) = compile(code, &[])?;
+        let (engine, module) =
+            compile_with_operator_cost(code, &[], options.gas_schedule.wasm_operator_cost)?;
         let store = make_store_with_engine(engine, memory_limit);
-        Instance::from_module(
+        let mut instance = Instance::from_module(
             store,
             &module,
             backend,
             options.gas_limit,
+            options.gas_schedule.host_functions,
             options.print_debug,
             None,
             None,
-        )
+        )?;
+        instance.set_readonly_ibc_channel_open(options.readonly_ibc_channel_open);
+        instance.set_max_query_depth(options.max_query_depth);
+        instance.set_max_execution_time(options.max_execution_time);
+        instance.set_shared_debug_handler(options.debug_handler.as_ref());
+        instance.set_checksum(Checksum::generate(code));
+        Ok(instance)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -90,18 +208,16 @@ where
         module: &Module,
         backend: Backend<A, S, Q>,
         gas_limit: u64,
+        gas_config: GasConfig,
         print_debug: bool,
         extra_imports: Option<HashMap<&str, Exports>>,
         instantiation_lock: Option<&Mutex<()>>,
     ) -> VmResult<Self> {
         let fe = FunctionEnv::new(&mut store, {
-            let e = Environment::new(backend.api, gas_limit);
+            let mut e = Environment::new(backend.api, gas_limit);
+            e.gas_config = gas_config;
             if print_debug {
-                e.set_debug_handler(Some(Rc::new(RefCell::new(
-                    |msg: &str, _gas_remaining: DebugInfo<'_>| {
-                        eprintln!("{msg}");
-                    },
-                ))))
+                e.set_debug_handler(Some(print_debug_handler()))
             }
             e
         });
@@ -118,11 +234,25 @@ where
             Function::new_typed_with_env(&mut store, &fe, do_db_read),
         );
 
+        // Reads the database entries at the given keys, which are encoded as sections.
+        // Returns a pointer to a region containing one section per key, which is a single 0 byte
+        // if the key does not exist and a 1 byte followed by the value otherwise.
+        // Ownership of the keys pointer is not transferred to the host.
+        // Ownership of the result pointer is transferred to the contract.
+        env_imports.insert(
+            "db_read_batch",
+            Function::new_typed_with_env(&mut store, &fe, do_db_read_batch),
+        );
+
         // Writes the given value into the database entry at the given key.
         // Ownership of both input and output pointer is not transferred to the host.
         env_imports.insert(
             "db_write",
-            Function::new_typed_with_env(&mut store, &fe, do_db_write),
+            if S::READONLY {
+                Function::new_typed(&mut store, do_db_write_denied)
+            } else {
+                Function::new_typed_with_env(&mut store, &fe, do_db_write)
+            },
         );
 
         // Removes the value at the given key. Different than writing &[] as future
@@ -131,7 +261,11 @@ where
         // Ownership of both key pointer is not transferred to the host.
         env_imports.insert(
             "db_remove",
-            Function::new_typed_with_env(&mut store, &fe, do_db_remove),
+            if S::READONLY {
+                Function::new_typed(&mut store, do_db_remove_denied)
+            } else {
+                Function::new_typed_with_env(&mut store, &fe, do_db_remove)
+            },
         );
 
         // Reads human address from source_ptr and checks if it is valid.
@@ -160,6 +294,24 @@ where
             Function::new_typed_with_env(&mut store, &fe, do_addr_humanize),
         );
 
+        // Encodes the data in data_ptr as a bech32 string with the prefix in prefix_ptr and writes it to destination_ptr.
+        // A prepared and sufficiently large memory Region is expected at destination_ptr that points to pre-allocated memory.
+        // Returns 0 on success. Returns a non-zero memory location to a Region containing an UTF-8 encoded error string for invalid inputs.
+        // Ownership of both input and output pointers is not transferred to the host.
+        env_imports.insert(
+            "bech32_encode",
+            Function::new_typed_with_env(&mut store, &fe, do_bech32_encode),
+        );
+
+        // Decodes the bech32 string in source_ptr and writes its prefix to prefix_destination_ptr and its data to data_destination_ptr.
+        // Prepared and sufficiently large memory Regions are expected at both destinations that point to pre-allocated memory.
+        // Returns 0 on success. Returns a non-zero memory location to a Region containing an UTF-8 encoded error string for invalid inputs.
+        // Ownership of both input and output pointers is not transferred to the host.
+        env_imports.insert(
+            "bech32_decode",
+            Function::new_typed_with_env(&mut store, &fe, do_bech32_decode),
+        );
+
         // Verifies message hashes against a signature with a public key, using the secp256k1 ECDSA parametrization.
         // Returns 0 on verification success, 1 on verification failure, and values greater than 1 in case of error.
         // Ownership of input pointers is not transferred to the host.
@@ -173,6 +325,99 @@ where
             Function::new_typed_with_env(&mut store, &fe, do_secp256k1_recover_pubkey),
         );
 
+        // Verifies a batch of message hashes against a batch of signatures and public keys, using the secp256k1 ECDSA parametrization.
+        // Returns 0 on verification success (all batches verify correctly), 1 on verification failure, and values greater than 1 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "secp256k1_batch_verify",
+            Function::new_typed_with_env(&mut store, &fe, do_secp256k1_batch_verify),
+        );
+
+        // Adds the secp256k1 points p and q and writes the compressed sum to out.
+        // Returns 0 on success and values greater than 0 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "secp256k1_point_add",
+            Function::new_typed_with_env(&mut store, &fe, do_secp256k1_point_add),
+        );
+
+        // Multiplies a secp256k1 point with a 32 byte big-endian scalar and writes the compressed product to out.
+        // Returns 0 on success and values greater than 0 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "secp256k1_point_mul",
+            Function::new_typed_with_env(&mut store, &fe, do_secp256k1_point_mul),
+        );
+
+        // Verifies message hashes against a signature with a public key, using the secp256r1 ECDSA parametrization.
+        // Returns 0 on verification success, 1 on verification failure, and values greater than 1 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "secp256r1_verify",
+            Function::new_typed_with_env(&mut store, &fe, do_secp256r1_verify),
+        );
+
+        env_imports.insert(
+            "secp256r1_recover_pubkey",
+            Function::new_typed_with_env(&mut store, &fe, do_secp256r1_recover_pubkey),
+        );
+
+        // Adds up the compressed BLS12-381 G1 points in g1s_ptr and writes the compressed sum to out_ptr.
+        // Returns 0 on success and values greater than 0 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "bls12_381_aggregate_g1",
+            Function::new_typed_with_env(&mut store, &fe, do_bls12_381_aggregate_g1),
+        );
+
+        // Adds up the compressed BLS12-381 G2 points in g2s_ptr and writes the compressed sum to out_ptr.
+        // Returns 0 on success and values greater than 0 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "bls12_381_aggregate_g2",
+            Function::new_typed_with_env(&mut store, &fe, do_bls12_381_aggregate_g2),
+        );
+
+        // Checks if e(p_1, q_1) * … * e(p_n, q_n) == e(r, s) for compressed BLS12-381 points.
+        // Returns 0 if the equality holds, 1 if it does not hold, and values greater than 1 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "bls12_381_pairing_equality",
+            Function::new_typed_with_env(&mut store, &fe, do_bls12_381_pairing_equality),
+        );
+
+        // Hashes a message to a BLS12-381 G1 point and writes the compressed point to out_ptr.
+        // Returns 0 on success and values greater than 0 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "bls12_381_hash_to_g1",
+            Function::new_typed_with_env(&mut store, &fe, do_bls12_381_hash_to_g1),
+        );
+
+        // Hashes a message to a BLS12-381 G2 point and writes the compressed point to out_ptr.
+        // Returns 0 on success and values greater than 0 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "bls12_381_hash_to_g2",
+            Function::new_typed_with_env(&mut store, &fe, do_bls12_381_hash_to_g2),
+        );
+
+        // Verifies a Groth16 proof over BN254 against a verifying key and public inputs.
+        // Returns 0 if the proof is valid, 1 if it is not valid, and values greater than 1 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "groth16_verify_bn254",
+            Function::new_typed_with_env(&mut store, &fe, do_groth16_verify_bn254),
+        );
+
+        // Verifies a Groth16 proof over BLS12-381 against a verifying key and public inputs.
+        // Returns 0 if the proof is valid, 1 if it is not valid, and values greater than 1 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "groth16_verify_bls12_381",
+            Function::new_typed_with_env(&mut store, &fe, do_groth16_verify_bls12_381),
+        );
+
         // Verifies a message against a signature with a public key, using the ed25519 EdDSA scheme.
         // Returns 0 on verification success, 1 on verification failure, and values greater than 1 in case of error.
         // Ownership of input pointers is not transferred to the host.
@@ -191,6 +436,36 @@ where
             Function::new_typed_with_env(&mut store, &fe, do_ed25519_batch_verify),
         );
 
+        // Verifies a message against a signature with a public key, using the sr25519 (Schnorrkel) scheme
+        // with the `substrate` signing context.
+        // Returns 0 on verification success, 1 on verification failure, and values greater than 1 in case of error.
+        // Ownership of input pointers is not transferred to the host.
+        env_imports.insert(
+            "sr25519_verify",
+            Function::new_typed_with_env(&mut store, &fe, do_sr25519_verify),
+        );
+
+        // Writes the Keccak-256 hash of the data to the output region, which must have a capacity of at least 32 bytes.
+        // Ownership of both input and output pointer is not transferred to the host.
+        env_imports.insert(
+            "keccak256",
+            Function::new_typed_with_env(&mut store, &fe, do_keccak256),
+        );
+
+        // Writes the SHA-512 hash of the data to the output region, which must have a capacity of at least 64 bytes.
+        // Ownership of both input and output pointer is not transferred to the host.
+        env_imports.insert(
+            "sha512",
+            Function::new_typed_with_env(&mut store, &fe, do_sha512),
+        );
+
+        // Writes the BLAKE2b-512 hash of the data to the output region, which must have a capacity of at least 64 bytes.
+        // Ownership of both input and output pointer is not transferred to the host.
+        env_imports.insert(
+            "blake2b512",
+            Function::new_typed_with_env(&mut store, &fe, do_blake2b512),
+        );
+
         // Allows the contract to emit debug logs that the host can either process or ignore.
         // This is never written to chain.
         // Takes a pointer argument of a memory region that must contain an UTF-8 encoded string.
@@ -213,6 +488,15 @@ where
             Function::new_typed_with_env(&mut store, &fe, do_query_chain),
         );
 
+        // Returns information about the contract itself (address, code ID, checksum) as set by the host
+        // via `Instance::set_contract_self_info`. This is much cheaper than a query through `query_chain`.
+        // Ownership of the output pointer is transferred to the contract.
+        #[cfg(feature = "cosmwasm_1_4")]
+        env_imports.insert(
+            "contract_self_info",
+            Function::new_typed_with_env(&mut store, &fe, do_contract_self_info),
+        );
+
         // Creates an iterator that will go from start to end.
         // If start_ptr == 0, the start is unbounded.
         // If end_ptr == 0, the end is unbounded.
@@ -236,6 +520,36 @@ where
             Function::new_typed_with_env(&mut store, &fe, do_db_next),
         );
 
+        // Loads up to `limit` elements from start to end in one call.
+        // Start, end and order are interpreted like in db_scan.
+        // Creates a region containing the sections key1, value1, key2, value2, … and returns its address.
+        // Ownership of the result region is transferred to the contract.
+        // An empty region means no elements in the range.
+        #[cfg(feature = "iterator")]
+        env_imports.insert(
+            "db_scan_bounded",
+            Function::new_typed_with_env(&mut store, &fe, do_db_scan_bounded),
+        );
+
+        // Removes all entries from start to end.
+        // If start_ptr == 0, the start is unbounded.
+        // If end_ptr == 0, the end is unbounded.
+        // Ownership of both start and end pointer is not transferred to the host.
+        #[cfg(feature = "iterator")]
+        env_imports.insert(
+            "db_remove_range",
+            if S::READONLY {
+                Function::new_typed(&mut store, do_db_remove_range_denied)
+            } else {
+                Function::new_typed_with_env(&mut store, &fe, do_db_remove_range)
+            },
+        );
+
+        #[cfg(feature = "host_call_hooks")]
+        let env_imports = crate::hooks::wrap_imports(&mut store, &fe, env_imports);
+        #[cfg(feature = "host_call_tracing")]
+        let env_imports = crate::host_call_trace::wrap_imports(&mut store, &fe, env_imports);
+
         import_obj.register_namespace("env", env_imports);
 
         if let Some(extra_imports) = extra_imports {
@@ -262,7 +576,30 @@ where
             })?
             .clone();
 
+        let mutable_globals = wasmer_instance
+            .exports
+            .iter()
+            .filter(|(name, _)| {
+                !name.starts_with("wasmer_metering_") && *name != INTERRUPT_FLAG_EXPORT
+            })
+            .filter_map(|(_, export)| match export {
+                Extern::Global(global) if global.ty(&store).mutability == Mutability::Var => {
+                    Some(global.clone())
+                }
+                _ => None,
+            })
+            .collect();
+
         let instance_ptr = NonNull::from(wasmer_instance.as_ref());
+        // Static validation rejects other signatures, but modules can also be instantiated
+        // without it, e.g. in tests. Calling a function with the wrong signature would fail.
+        let has_reset_export = matches!(
+            wasmer_instance.exports.get_function(RESET_EXPORT),
+            Ok(function) if {
+                let ty = function.ty(&store);
+                ty.params().is_empty() && ty.results().is_empty()
+            }
+        );
 
         {
             let mut fe_mut = fe.clone().into_mut(&mut store);
@@ -278,6 +615,10 @@ where
             _inner: wasmer_instance,
             fe,
             store,
+            has_reset_export,
+            readonly_ibc_channel_open: false,
+            failed_call: false,
+            mutable_globals,
         })
     }
 
@@ -305,6 +646,110 @@ where
         }
     }
 
+    /// Prepares an instance that was returned to the pool via [`Instance::wipe`]
+    /// for the next call. This sets everything [`Instance::from_module`] sets.
+    pub(crate) fn reuse(&mut self, backend: Backend<A, S, Q>, options: InstanceOptions) {
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+
+        env.api = backend.api;
+        env.gas_config = options.gas_schedule.host_functions;
+        env.reset(options.gas_limit);
+        if let Some(debug_handler) = &options.debug_handler {
+            env.set_debug_handler(Some(debug_handler.to_handler_fn()));
+        } else if options.print_debug {
+            env.set_debug_handler(Some(print_debug_handler()));
+        }
+        env.set_gas_left(&mut store, options.gas_limit);
+        env.max_query_depth = options.max_query_depth;
+        env.max_execution_time = options.max_execution_time;
+        env.move_in(backend.storage, backend.querier);
+        self.readonly_ibc_channel_open = options.readonly_ibc_channel_open;
+    }
+
+    /// Returns a copy of the contract's memory.
+    ///
+    /// When taken right after instantiation, this can be used to [`Instance::wipe`]
+    /// instances of the same module.
+    pub(crate) fn memory_snapshot(&mut self) -> VmResult<Vec<u8>> {
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+
+        env.memory(&mut store)
+            .copy_to_vec()
+            .map_err(|e| VmError::generic_err(format!("Error copying memory: {e}")))
+    }
+
+    /// Returns the values of all mutable globals of the contract.
+    ///
+    /// When taken right after instantiation, this can be used to [`Instance::wipe`]
+    /// instances of the same module.
+    pub(crate) fn globals_snapshot(&mut self) -> Vec<Value> {
+        self.mutable_globals
+            .iter()
+            .map(|global| global.get(&mut self.store))
+            .collect()
+    }
+
+    /// Decomposes the instance like [`Instance::recycle`], but keeps it for reuse by
+    /// restoring the memory and the mutable globals to the given snapshots and dropping
+    /// all handlers.
+    ///
+    /// Since Wasm memory cannot shrink, pages added after the snapshot are kept but zeroed.
+    ///
+    /// Returns the backend and the wiped instance. The instance is `None` if it cannot be
+    /// reused because a call failed.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn wipe(
+        mut self,
+        memory_snapshot: &[u8],
+        globals_snapshot: &[Value],
+    ) -> (Option<Backend<A, S, Q>>, Option<Instance<A, S, Q>>) {
+        let failed_call = self.failed_call;
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, mut store) = fe_mut.data_and_store_mut();
+
+        let backend = match env.move_out() {
+            (Some(storage), Some(querier)) => Some(Backend {
+                api: env.api,
+                storage,
+                querier,
+            }),
+            _ => None,
+        };
+        env.reset(0);
+
+        let memory = env.memory(&mut store);
+        let snapshot_size = memory_snapshot.len() as u64;
+        let reusable = !failed_call
+            && memory.data_size() >= snapshot_size
+            && memory.write(0, memory_snapshot).is_ok()
+            && memory
+                .write(
+                    snapshot_size,
+                    &vec![0u8; (memory.data_size() - snapshot_size) as usize],
+                )
+                .is_ok();
+        let reusable = reusable
+            && self.mutable_globals.len() == globals_snapshot.len()
+            && self
+                .mutable_globals
+                .iter()
+                .zip(globals_snapshot)
+                .all(|(global, value)| global.set(&mut self.store, value.clone()).is_ok());
+        if reusable {
+            (backend, Some(self))
+        } else {
+            (backend, None)
+        }
+    }
+
+    /// Sets a handler for debug messages of the contract, replacing the handler
+    /// installed by `InstanceOptions::print_debug`.
+    ///
+    /// Besides the message, the handler receives a [`DebugInfo`] with the remaining gas
+    /// and the call context (entry point and contract address), such that hosts can
+    /// route debug output into their logging system with attribution.
     pub fn set_debug_handler<H>(&mut self, debug_handler: H)
     where
         H: for<'a, 'b> FnMut(/* msg */ &'a str, DebugInfo<'b>) + 'static,
@@ -318,6 +763,75 @@ where
         self.fe.as_ref(&self.store).set_debug_handler(None);
     }
 
+    /// Installs the debug handler of [`InstanceOptions::debug_handler`] if set
+    pub(crate) fn set_shared_debug_handler(&mut self, debug_handler: Option<&SharedDebugHandler>) {
+        if let Some(debug_handler) = debug_handler {
+            self.fe
+                .as_ref(&self.store)
+                .set_debug_handler(Some(debug_handler.to_handler_fn()));
+        }
+    }
+
+    /// Sets the checksum of the contract's Wasm code, which is passed to the debug handler
+    pub(crate) fn set_checksum(&mut self, checksum: Checksum) {
+        self.fe.as_mut(&mut self.store).checksum = Some(checksum);
+    }
+
+    /// Sets the information returned by the `contract_self_info` import.
+    /// Calling the import before this was set results in an error.
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn set_contract_self_info(&mut self, info: ContractSelfInfo) {
+        self.fe
+            .as_ref(&self.store)
+            .set_contract_self_info(Some(info));
+    }
+
+    /// Sets the name of the entry point being executed, which is passed to the debug handler
+    pub(crate) fn set_entry_point(&mut self, entry_point: Option<&str>) {
+        self.fe.as_ref(&self.store).set_entry_point(entry_point);
+    }
+
+    /// Sets a hook that is called right before and right after every host import call.
+    ///
+    /// This is intended for testing only, e.g. for differential fuzzing.
+    #[cfg(feature = "host_call_hooks")]
+    pub fn set_host_call_hook<H>(&mut self, host_call_hook: H)
+    where
+        H: for<'a> FnMut(HostCallInfo<'a>) + 'static,
+    {
+        self.fe
+            .as_ref(&self.store)
+            .set_host_call_hook(Some(Rc::new(RefCell::new(host_call_hook))));
+    }
+
+    #[cfg(feature = "host_call_hooks")]
+    pub fn unset_host_call_hook(&mut self) {
+        self.fe.as_ref(&self.store).set_host_call_hook(None);
+    }
+
+    /// Starts recording host import calls into a ring buffer holding the
+    /// most recent `capacity` calls. Previously recorded calls are dropped.
+    #[cfg(feature = "host_call_tracing")]
+    pub fn enable_host_call_tracing(&mut self, capacity: usize) {
+        self.fe
+            .as_ref(&self.store)
+            .set_host_call_trace_capacity(Some(capacity));
+    }
+
+    /// Stops recording host import calls and drops the recorded calls
+    #[cfg(feature = "host_call_tracing")]
+    pub fn disable_host_call_tracing(&mut self) {
+        self.fe
+            .as_ref(&self.store)
+            .set_host_call_trace_capacity(None);
+    }
+
+    /// Returns the recorded host import calls, oldest first
+    #[cfg(feature = "host_call_tracing")]
+    pub fn host_call_trace(&self) -> Vec<HostCallRecord> {
+        self.fe.as_ref(&self.store).host_call_trace()
+    }
+
     /// Returns the features required by this contract.
     ///
     /// This is not needed for production because we can do static analysis
@@ -327,6 +841,18 @@ where
         required_capabilities_from_module(self._inner.module())
     }
 
+    /// Returns the number of parameters of the exported function with the given name
+    /// or `None` if the contract does not export such a function.
+    #[cfg(feature = "cosmwasm_1_5")]
+    pub(crate) fn function_arity(&self, name: &str) -> Option<usize> {
+        self._inner
+            .module()
+            .exports()
+            .functions()
+            .find(|export| export.name() == name)
+            .map(|export| export.ty().params().len())
+    }
+
     /// Returns the size of the default memory in pages.
     /// This provides a rough idea of the peak memory consumption. Note that
     /// Wasm memory always grows in 64 KiB steps (pages) and can never shrink
@@ -369,6 +895,35 @@ where
         }
     }
 
+    /// Starts recording the gas charged per import, which can then be retrieved via
+    /// [`Instance::gas_breakdown`]. Gas charged before this call is attributed to Wasm execution.
+    ///
+    /// This is intended for analyzing the gas consumption of contracts, e.g. in integration tests.
+    pub fn enable_gas_breakdown(&mut self) {
+        let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+        let (env, _) = fe_mut.data_and_store_mut();
+
+        env.with_gas_state_mut(|gas_state| {
+            gas_state.import_gas.get_or_insert_with(BTreeMap::new);
+        });
+    }
+
+    /// Returns the gas used so far, split up into Wasm execution and the individual imports.
+    /// Returns `None` unless [`Instance::enable_gas_breakdown`] was called before.
+    pub fn gas_breakdown(&mut self) -> Option<GasBreakdown> {
+        let import_gas = {
+            let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
+            let (env, _) = fe_mut.data_and_store_mut();
+            env.with_gas_state(|gas_state| gas_state.import_gas.clone())?
+        };
+        let import_cost: u64 = import_gas.values().map(|usage| usage.cost).sum();
+        let report = self.create_gas_report();
+        Some(GasBreakdown {
+            wasm: report.used_internally.saturating_sub(import_cost),
+            imports: import_gas,
+        })
+    }
+
     pub fn is_storage_readonly(&mut self) -> bool {
         let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
         let (env, _) = fe_mut.data_and_store_mut();
@@ -386,6 +941,29 @@ where
         env.set_storage_readonly(new_value);
     }
 
+    /// Sets whether `ibc_channel_open` is executed with read-only storage.
+    /// See [`InstanceOptions::readonly_ibc_channel_open`].
+    pub fn set_readonly_ibc_channel_open(&mut self, readonly: bool) {
+        self.readonly_ibc_channel_open = readonly;
+    }
+
+    /// Sets the maximum nesting depth of queries.
+    /// See [`InstanceOptions::max_query_depth`].
+    pub fn set_max_query_depth(&mut self, max_query_depth: usize) {
+        self.fe.as_mut(&mut self.store).max_query_depth = max_query_depth;
+    }
+
+    /// Sets the maximum wall-clock time of a call into the contract.
+    /// See [`InstanceOptions::max_execution_time`].
+    pub fn set_max_execution_time(&mut self, max_execution_time: Option<Duration>) {
+        self.fe.as_mut(&mut self.store).max_execution_time = max_execution_time;
+    }
+
+    #[cfg(feature = "stargate")]
+    pub(crate) fn readonly_ibc_channel_open(&self) -> bool {
+        self.readonly_ibc_channel_open
+    }
+
     pub fn with_storage<F: FnOnce(&mut S) -> VmResult<T>, T>(&mut self, func: F) -> VmResult<T> {
         self.fe
             .as_ref(&self.store)
@@ -409,6 +987,16 @@ where
         Ok(ptr)
     }
 
+    /// Releases all memory of the contract's arena allocator, if the contract uses one.
+    /// This must only be called between entry point calls, when no memory allocated
+    /// by the contract is in use. For contracts without a `cosmwasm_arena_reset` export, this is a no-op.
+    pub(crate) fn reset_memory(&mut self) -> VmResult<()> {
+        if self.has_reset_export {
+            self.call_function0(RESET_EXPORT, &[])?;
+        }
+        Ok(())
+    }
+
     // deallocate frees memory in the instance and that was either previously
     // allocated by us, or a pointer from a return value after we copy it into rust.
     // we need to clean up the wasm-side buffers to avoid memory leaks
@@ -440,7 +1028,9 @@ where
         let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
         let (env, mut store) = fe_mut.data_and_store_mut();
 
-        env.call_function0(&mut store, name, args)
+        let result = env.call_function0(&mut store, name, args);
+        self.failed_call |= result.is_err();
+        result
     }
 
     /// Calls a function exported by the instance.
@@ -449,10 +1039,18 @@ where
         let mut fe_mut = self.fe.clone().into_mut(&mut self.store);
         let (env, mut store) = fe_mut.data_and_store_mut();
 
-        env.call_function1(&mut store, name, args)
+        let result = env.call_function1(&mut store, name, args);
+        self.failed_call |= result.is_err();
+        result
     }
 }
 
+fn print_debug_handler() -> Rc<RefCell<DebugHandlerFn>> {
+    Rc::new(RefCell::new(|msg: &str, _gas_remaining: DebugInfo<'_>| {
+        eprintln!("{msg}");
+    }))
+}
+
 /// This exists only to be exported through `internals` for use by crates that are
 /// part of Cosmwasm.
 pub fn instance_from_module<A, S, Q>(
@@ -473,6 +1071,7 @@ where
         module,
         backend,
         gas_limit,
+        GasConfig::default(),
         print_debug,
         extra_imports,
         None,
@@ -494,6 +1093,7 @@ mod tests {
         mock_instance_with_balances, mock_instance_with_failing_api, mock_instance_with_gas_limit,
         mock_instance_with_options, MockInstanceOptions,
     };
+    use crate::wasm_backend::compile;
     use cosmwasm_std::{
         coin, coins, from_binary, AllBalanceResponse, BalanceResponse, BankQuery, Empty,
         QueryRequest,
@@ -514,6 +1114,46 @@ mod tests {
             Instance::from_code(CONTRACT, backend, instance_options, memory_limit).unwrap();
     }
 
+    #[test]
+    fn from_code_uses_gas_schedule() {
+        let (instance_options, memory_limit) = mock_instance_options();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+
+        let mut instance = Instance::from_code(
+            CONTRACT,
+            mock_backend(&[]),
+            instance_options.clone(),
+            memory_limit,
+        )
+        .unwrap();
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let default_used = instance.create_gas_report().used_internally;
+
+        let mut gas_schedule = GasSchedule::v1();
+        gas_schedule.wasm_operator_cost *= 2;
+        gas_schedule.host_functions.sha512_cost = 42;
+        let options = InstanceOptions {
+            gas_schedule,
+            ..instance_options
+        };
+        let mut instance =
+            Instance::from_code(CONTRACT, mock_backend(&[]), options, memory_limit).unwrap();
+        assert_eq!(
+            instance.fe.as_ref(&instance.store).gas_config,
+            gas_schedule.host_functions
+        );
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let used = instance.create_gas_report().used_internally;
+        // almost all internally used gas is spent on executing Wasm operators
+        assert!(used > 2 * default_used - 1_000_000);
+        assert!(used < 2 * default_used);
+    }
+
     #[test]
     fn set_debug_handler_and_unset_debug_handler_work() {
         const LIMIT: u64 = 70_000_000_000_000;
@@ -551,6 +1191,163 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn debug_handler_receives_call_context() {
+        use cosmwasm_std::{Addr, HexBinary};
+
+        let mut instance = mock_instance_with_gas_limit(CYBERPUNK, 70_000_000_000_000);
+        instance.set_contract_self_info(ContractSelfInfo {
+            address: Addr::unchecked("cosmwasm1self"),
+            code_id: 42,
+            checksum: HexBinary::from([0xAA; 32]),
+        });
+
+        let contexts = Rc::new(RefCell::new(Vec::<(String, String, u64)>::new()));
+        let contexts_clone = Rc::clone(&contexts);
+        instance.set_debug_handler(move |_msg, info| {
+            contexts_clone.borrow_mut().push((
+                info.entry_point.unwrap().to_string(),
+                info.contract_address.unwrap().to_string(),
+                info.gas_remaining,
+            ));
+        });
+
+        let info = mock_info("caller", &[]);
+        call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, br#"{"debug":{}}"#)
+            .unwrap()
+            .unwrap();
+
+        let contexts = contexts.borrow();
+        assert!(!contexts.is_empty());
+        for (entry_point, contract_address, gas_remaining) in contexts.iter() {
+            assert_eq!(entry_point, "execute");
+            assert_eq!(contract_address, "cosmwasm1self");
+            assert!(*gas_remaining > 0);
+        }
+        // the entry point is reset after the call
+        assert_eq!(instance.fe.as_ref(&instance.store).entry_point(), None);
+    }
+
+    #[test]
+    fn debug_handler_from_options_receives_checksum() {
+        let messages = Arc::new(Mutex::new(Vec::<(String, Option<Checksum>)>::new()));
+        let messages_clone = Arc::clone(&messages);
+        let (instance_options, memory_limit) = mock_instance_options();
+        let options = InstanceOptions {
+            gas_limit: 70_000_000_000_000,
+            debug_handler: Some(SharedDebugHandler::new(move |msg, info| {
+                messages_clone
+                    .lock()
+                    .unwrap()
+                    .push((msg.to_string(), info.checksum.copied()));
+            })),
+            ..instance_options
+        };
+        let mut instance =
+            Instance::from_code(CYBERPUNK, mock_backend(&[]), options, memory_limit).unwrap();
+
+        let info = mock_info("creator", &[]);
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, br#"{}"#)
+            .unwrap()
+            .unwrap();
+        let info = mock_info("caller", &[]);
+        call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, br#"{"debug":{}}"#)
+            .unwrap()
+            .unwrap();
+
+        let messages = messages.lock().unwrap();
+        assert!(!messages.is_empty());
+        for (msg, checksum) in messages.iter() {
+            assert!(!msg.is_empty());
+            assert_eq!(*checksum, Some(Checksum::generate(CYBERPUNK)));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "host_call_hooks")]
+    fn set_host_call_hook_and_unset_host_call_hook_work() {
+        use crate::HostCallPhase;
+
+        let mut instance = mock_instance(CONTRACT, &[]);
+
+        let calls = Rc::new(RefCell::new(Vec::<(String, HostCallPhase, u64)>::new()));
+        let calls_clone = calls.clone();
+        instance.set_host_call_hook(move |info| {
+            assert!(!info.memory.is_empty());
+            calls_clone.borrow_mut().push((
+                info.import.to_string(),
+                info.phase,
+                info.gas_remaining,
+            ));
+        });
+
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        let count = {
+            let calls = calls.borrow();
+            let writes: Vec<_> = calls.iter().filter(|c| c.0 == "db_write").collect();
+            assert_eq!(writes.len(), 2);
+            assert_eq!(writes[0].1, HostCallPhase::Enter);
+            assert_eq!(writes[1].1, HostCallPhase::Exit);
+            assert!(writes[1].2 < writes[0].2);
+            calls.len()
+        };
+
+        instance.unset_host_call_hook();
+        let info = mock_info("creator", &coins(1000, "earth"));
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        assert_eq!(calls.borrow().len(), count);
+    }
+
+    #[test]
+    #[cfg(feature = "host_call_tracing")]
+    fn host_call_tracing_works() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+
+        // disabled by default
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        assert_eq!(instance.host_call_trace(), vec![]);
+
+        instance.enable_host_call_tracing(100);
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let trace = instance.host_call_trace();
+        let writes: Vec<_> = trace.iter().filter(|r| r.import == "db_write").collect();
+        assert_eq!(writes.len(), 1);
+        let write = writes[0];
+        assert_eq!(write.arg_sizes.len(), 2);
+        assert!(write.arg_sizes.iter().all(|size| size.unwrap() > 0));
+        assert!(write.gas_after < write.gas_before);
+        assert!(write.success);
+        for (previous, next) in trace.iter().zip(trace.iter().skip(1)) {
+            assert!(next.gas_before <= previous.gas_after);
+        }
+
+        // ring buffer keeps the most recent calls
+        instance.enable_host_call_tracing(1);
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let short_trace = instance.host_call_trace();
+        assert_eq!(short_trace.len(), 1);
+        assert_eq!(short_trace[0].import, trace.last().unwrap().import);
+
+        instance.disable_host_call_tracing();
+        assert_eq!(instance.host_call_trace(), vec![]);
+    }
+
     #[test]
     fn required_capabilities_works() {
         let backend = mock_backend(&[]);
@@ -634,6 +1431,7 @@ mod tests {
             &module,
             backend,
             instance_options.gas_limit,
+            instance_options.gas_schedule.host_functions,
             false,
             Some(extra_imports),
             None,
@@ -703,6 +1501,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn reset_memory_works() {
+        // Contract without reset export
+        let mut instance = mock_instance(CONTRACT, &[]);
+        assert!(!instance.has_reset_export);
+        instance.reset_memory().unwrap();
+
+        // Contract with reset export
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 3)
+                (export "memory" (memory 0))
+                (global $resets (mut i32) (i32.const 0))
+
+                (func (export "cosmwasm_arena_reset")
+                    global.get $resets
+                    i32.const 1
+                    i32.add
+                    global.set $resets)
+                (func (export "resets") (result i32) global.get $resets)
+            )"#,
+        )
+        .unwrap();
+        let backend = mock_backend(&[]);
+        let (instance_options, memory_limit) = mock_instance_options();
+        let mut instance =
+            Instance::from_code(&wasm, backend, instance_options, memory_limit).unwrap();
+        assert!(instance.has_reset_export);
+
+        let resets = instance.call_function1("resets", &[]).unwrap();
+        assert_eq!(resets.unwrap_i32(), 0);
+        instance.reset_memory().unwrap();
+        instance.reset_memory().unwrap();
+        let resets = instance.call_function1("resets", &[]).unwrap();
+        assert_eq!(resets.unwrap_i32(), 2);
+
+        // Contract with reset export of the wrong signature
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 3)
+                (export "memory" (memory 0))
+                (func (export "cosmwasm_arena_reset") (param i32) nop)
+            )"#,
+        )
+        .unwrap();
+        let backend = mock_backend(&[]);
+        let (instance_options, memory_limit) = mock_instance_options();
+        let mut instance =
+            Instance::from_code(&wasm, backend, instance_options, memory_limit).unwrap();
+        assert!(!instance.has_reset_export);
+        instance.reset_memory().unwrap();
+    }
+
     #[test]
     fn write_and_read_memory_works() {
         let mut instance = mock_instance(CONTRACT, &[]);
@@ -838,6 +1689,70 @@ mod tests {
         assert_eq!(instance.memory_pages(), 19);
     }
 
+    #[test]
+    fn memory_grow_is_limited() {
+        const TESTING_MEMORY_LIMIT: Size = Size::mebi(16);
+        let limit_pages = TESTING_MEMORY_LIMIT.0 / (64 * KIB);
+
+        let (instance_options, _) = mock_instance_options();
+        let mut instance = Instance::from_code(
+            CYBERPUNK,
+            mock_backend(&[]),
+            instance_options,
+            Some(TESTING_MEMORY_LIMIT),
+        )
+        .unwrap();
+        let info = mock_info("creator", &[]);
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, br#"{}"#)
+            .unwrap()
+            .unwrap();
+        let initial_pages = instance.memory_pages();
+
+        let allocate_pages = |instance: &mut Instance<_, _, _>, pages: usize| {
+            let msg = format!(r#"{{"allocate_large_memory":{{"pages":{pages}}}}}"#);
+            call_execute::<_, _, _, Empty>(instance, &mock_env(), &info, msg.as_bytes()).unwrap()
+        };
+
+        // growing beyond the limit fails in the contract and leaves the memory untouched
+        let err = allocate_pages(&mut instance, limit_pages).unwrap_err();
+        assert_eq!(err, "Generic error: memory.grow failed");
+        assert_eq!(instance.memory_pages(), initial_pages);
+
+        // growing close to the limit works (leaving a few pages for the contract's allocator)
+        let res = allocate_pages(&mut instance, limit_pages - initial_pages - 8).unwrap();
+        assert_eq!(res.data.unwrap(), (initial_pages as u32).to_be_bytes());
+        let pages = instance.memory_pages();
+        assert!((limit_pages - 8..=limit_pages).contains(&pages));
+
+        // the limit is enforced for the remaining pages
+        let err = allocate_pages(&mut instance, limit_pages - pages + 1).unwrap_err();
+        assert_eq!(err, "Generic error: memory.grow failed");
+        assert!(instance.memory_pages() <= limit_pages);
+    }
+
+    #[test]
+    fn allocate_is_limited() {
+        const TESTING_MEMORY_LIMIT: Size = Size::mebi(16);
+
+        let (instance_options, _) = mock_instance_options();
+        let mut instance = Instance::from_code(
+            CONTRACT,
+            mock_backend(&[]),
+            instance_options,
+            Some(TESTING_MEMORY_LIMIT),
+        )
+        .unwrap();
+
+        // a huge allocation cannot be served by the contract's allocator
+        let err = instance.allocate(TESTING_MEMORY_LIMIT.0).unwrap_err();
+        assert!(matches!(err, VmError::RuntimeErr { .. }), "{err:?}");
+        assert!(instance.memory_pages() <= TESTING_MEMORY_LIMIT.0 / (64 * KIB));
+
+        // allocations that fit in the limit still work
+        instance.allocate(8 * MIB).unwrap();
+        assert!(instance.memory_pages() <= TESTING_MEMORY_LIMIT.0 / (64 * KIB));
+    }
+
     #[test]
     fn get_gas_left_works() {
         let mut instance = mock_instance_with_gas_limit(CONTRACT, 123321);
@@ -873,6 +1788,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gas_breakdown_works() {
+        const LIMIT: u64 = 700_000_000_000;
+        let mut instance = mock_instance_with_gas_limit(CONTRACT, LIMIT);
+
+        // disabled by default
+        assert_eq!(instance.gas_breakdown(), None);
+
+        instance.enable_gas_breakdown();
+        let breakdown = instance.gas_breakdown().unwrap();
+        assert_eq!(breakdown.wasm, 0);
+        assert!(breakdown.imports.is_empty());
+
+        // init contract
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        let report = instance.create_gas_report();
+        let breakdown = instance.gas_breakdown().unwrap();
+        assert!(breakdown.imports.contains_key("db_write"));
+        assert!(breakdown.imports.contains_key("addr_validate"));
+        let import_cost: u64 = breakdown.imports.values().map(|usage| usage.cost).sum();
+        let externally_used: u64 = breakdown
+            .imports
+            .values()
+            .map(|usage| usage.externally_used)
+            .sum();
+        assert!(import_cost > 0);
+        assert!(breakdown.wasm > import_cost);
+        assert_eq!(breakdown.wasm + import_cost, report.used_internally);
+        assert_eq!(externally_used, report.used_externally);
+    }
+
     #[test]
     fn set_storage_readonly_works() {
         let mut instance = mock_instance(CONTRACT, &[]);
@@ -1091,6 +2042,66 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn contract_enforces_max_execution_time() {
+        let mut instance = mock_instance_with_options(
+            CONTRACT,
+            MockInstanceOptions {
+                gas_limit: 20_000_000_000_000_000,
+                max_execution_time: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+        );
+
+        // init contract
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // writes to storage until the deadline passes, long before gas runs out
+        instance.set_max_execution_time(Some(Duration::from_millis(10)));
+        let info = mock_info("looper", &[]);
+        let msg = br#"{"storage_loop":{}}"#;
+        let err =
+            call_execute::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap_err();
+        assert!(matches!(err, VmError::DeadlineExceeded { .. }));
+    }
+
+    #[test]
+    fn max_execution_time_interrupts_code_without_imports() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1)
+                (export "memory" (memory 0))
+                (func (export "spin") (loop $forever br $forever))
+                (func (export "answer") (result i32) i32.const 42)
+            )"#,
+        )
+        .unwrap();
+        let backend = mock_backend(&[]);
+        let (instance_options, memory_limit) = mock_instance_options();
+        let options = InstanceOptions {
+            gas_limit: u64::MAX,
+            max_execution_time: Some(Duration::from_millis(50)),
+            ..instance_options
+        };
+        let mut instance = Instance::from_code(&wasm, backend, options, memory_limit).unwrap();
+
+        // calls that finish in time are not affected
+        let answer = instance.call_function1("answer", &[]).unwrap();
+        assert_eq!(answer.unwrap_i32(), 42);
+        assert!(instance.get_gas_left() > 0);
+
+        let err = instance.call_function0("spin", &[]).unwrap_err();
+        assert!(matches!(err, VmError::DeadlineExceeded { .. }));
+
+        // the interrupt flag is cleared, so the instance can be called again
+        let answer = instance.call_function1("answer", &[]).unwrap();
+        assert_eq!(answer.unwrap_i32(), 42);
+    }
+
     #[test]
     fn query_works_with_gas_metering() {
         let mut instance = mock_instance(CONTRACT, &[]);
@@ -0,0 +1,138 @@
+use std::collections::{HashMap, HashSet};
+
+use wasmer::Value;
+
+use crate::backend::{Backend, BackendApi, Querier, Storage};
+use crate::checksum::Checksum;
+use crate::errors::VmResult;
+use crate::instance::Instance;
+
+struct PoolEntry<A: BackendApi, S: Storage, Q: Querier> {
+    /// The memory of a fresh instance of this contract, used to wipe returned instances
+    memory_snapshot: Vec<u8>,
+    /// The mutable globals of a fresh instance of this contract, used to wipe returned instances
+    globals_snapshot: Vec<Value>,
+    idle: Vec<Instance<A, S, Q>>,
+    /// The number of instances taken from or created for the pool that were not returned yet
+    in_use: usize,
+}
+
+/// Idle instances of contracts, keyed by checksum.
+///
+/// Instances are wiped when they are returned such that no data of the previous
+/// call remains in them. A snapshot of the memory and the mutable globals is kept
+/// per contract as long as instances of it are idle or in use.
+pub(crate) struct InstancePool<A: BackendApi, S: Storage, Q: Querier> {
+    /// The maximum number of idle instances over all contracts
+    max_idle: usize,
+    idle: usize,
+    entries: HashMap<Checksum, PoolEntry<A, S, Q>>,
+}
+
+impl<A, S, Q> InstancePool<A, S, Q>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    pub fn new(max_idle: usize) -> Self {
+        InstancePool {
+            max_idle,
+            idle: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Takes an idle instance of the given contract out of the pool.
+    /// The instance needs to be prepared via [`Instance::reuse`] before it is used.
+    pub fn take(&mut self, checksum: &Checksum) -> Option<Instance<A, S, Q>> {
+        let entry = self.entries.get_mut(checksum)?;
+        let instance = entry.idle.pop()?;
+        entry.in_use += 1;
+        self.idle -= 1;
+        Some(instance)
+    }
+
+    /// Registers a freshly created instance that is handed out by the pool.
+    /// The snapshots of the contract are taken from it if needed.
+    pub fn register(
+        &mut self,
+        checksum: &Checksum,
+        instance: &mut Instance<A, S, Q>,
+    ) -> VmResult<()> {
+        if self.max_idle == 0 {
+            return Ok(());
+        }
+        if let Some(entry) = self.entries.get_mut(checksum) {
+            entry.in_use += 1;
+            return Ok(());
+        }
+        let memory_snapshot = instance.memory_snapshot()?;
+        let globals_snapshot = instance.globals_snapshot();
+        self.entries.insert(
+            *checksum,
+            PoolEntry {
+                memory_snapshot,
+                globals_snapshot,
+                idle: Vec::new(),
+                in_use: 1,
+            },
+        );
+        Ok(())
+    }
+
+    /// Wipes an instance handed out by the pool and keeps it for reuse if possible.
+    /// Returns the backend of the instance.
+    pub fn put(
+        &mut self,
+        checksum: &Checksum,
+        instance: Instance<A, S, Q>,
+    ) -> Option<Backend<A, S, Q>> {
+        let entry = match self.entries.get_mut(checksum) {
+            Some(entry) if entry.in_use > 0 => entry,
+            // Not handed out by the pool or the contract was removed in the meantime
+            _ => return instance.recycle(),
+        };
+        entry.in_use -= 1;
+
+        let (backend, wiped) = instance.wipe(&entry.memory_snapshot, &entry.globals_snapshot);
+        match wiped {
+            Some(wiped) if self.idle < self.max_idle => {
+                entry.idle.push(wiped);
+                self.idle += 1;
+            }
+            _ => {
+                if entry.idle.is_empty() && entry.in_use == 0 {
+                    self.entries.remove(checksum);
+                }
+            }
+        }
+        backend
+    }
+
+    /// Drops all idle instances and the snapshots of the given contract
+    pub fn remove(&mut self, checksum: &Checksum) {
+        if let Some(entry) = self.entries.remove(checksum) {
+            self.idle -= entry.idle.len();
+        }
+    }
+
+    /// Drops all idle instances and snapshots of contracts that are not in `keep`
+    pub fn retain(&mut self, keep: &HashSet<Checksum>) {
+        let mut dropped = 0;
+        self.entries.retain(|checksum, entry| {
+            if keep.contains(checksum) {
+                true
+            } else {
+                dropped += entry.idle.len();
+                false
+            }
+        });
+        self.idle -= dropped;
+    }
+
+    /// Returns the number of idle instances in the pool
+    pub fn len(&self) -> usize {
+        self.idle
+    }
+}
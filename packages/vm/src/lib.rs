@@ -1,53 +1,90 @@
 #![cfg_attr(feature = "backtraces", feature(error_generic_member_access))]
 #![cfg_attr(feature = "backtraces", feature(provide_any))]
 
+#[cfg(feature = "async_backend")]
+mod async_backend;
 mod backend;
 mod cache;
 mod calls;
 mod capabilities;
 mod checksum;
 mod compatibility;
+mod contract_metadata;
 mod conversion;
 mod environment;
 mod errors;
 mod filesystem;
+#[cfg(feature = "host_call_hooks")]
+mod hooks;
+#[cfg(feature = "host_call_tracing")]
+mod host_call_trace;
 mod imports;
 mod instance;
+mod instance_pool;
 mod limited;
 mod memory;
 mod modules;
+mod query_storage;
 mod sections;
 mod serde;
 mod size;
 mod static_analysis;
 pub mod testing;
 mod wasm_backend;
+mod wasm_stats;
+mod watchdog;
 
+#[cfg(feature = "async_backend")]
+pub use crate::async_backend::{
+    AsyncQuerier, AsyncStorage, BackendFuture, BlockingQuerier, BlockingStorage,
+};
 pub use crate::backend::{
-    Backend, BackendApi, BackendError, BackendResult, GasInfo, Querier, Storage,
+    Backend, BackendApi, BackendError, BackendResult, GasInfo, Querier, ReadonlyStorage, Storage,
+};
+pub use crate::cache::{
+    AnalysisReport, Cache, CacheMetricsSink, CacheOptions, ChecksumStats, EntryPointKind,
+    GasLimits, GcReport, Metrics, MigrationAction, MigrationProgress, ModuleSource, Stats,
 };
-pub use crate::cache::{AnalysisReport, Cache, CacheOptions, Metrics, Stats};
 pub use crate::calls::{
     call_execute, call_execute_raw, call_instantiate, call_instantiate_raw, call_migrate,
     call_migrate_raw, call_query, call_query_raw, call_reply, call_reply_raw, call_sudo,
-    call_sudo_raw,
+    call_sudo_raw, reply_for_sub_msg,
 };
 #[cfg(feature = "stargate")]
 pub use crate::calls::{
     call_ibc_channel_close, call_ibc_channel_close_raw, call_ibc_channel_connect,
     call_ibc_channel_connect_raw, call_ibc_channel_open, call_ibc_channel_open_raw,
-    call_ibc_packet_ack, call_ibc_packet_ack_raw, call_ibc_packet_receive,
-    call_ibc_packet_receive_raw, call_ibc_packet_timeout, call_ibc_packet_timeout_raw,
+    call_ibc_destination_callback_raw, call_ibc_packet_ack, call_ibc_packet_ack_raw,
+    call_ibc_packet_receive, call_ibc_packet_receive_raw, call_ibc_packet_timeout,
+    call_ibc_packet_timeout_raw, call_ibc_source_callback_raw,
 };
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub use crate::calls::{call_ibc_destination_callback, call_ibc_source_callback};
+#[cfg(feature = "cosmwasm_1_5")]
+pub use crate::calls::{call_migrate_with_info, call_migrate_with_info_raw};
 pub use crate::capabilities::capabilities_from_csv;
 pub use crate::checksum::Checksum;
+pub use crate::compatibility::{validate_wasm, ValidationReport, WasmLimits};
+pub use crate::contract_metadata::{
+    parse_contract_metadata, ContractMetadata, CONTRACT_METADATA_SECTION,
+};
+pub use crate::environment::{GasConfig, GasSchedule, ImportGasUsage, DEFAULT_MAX_QUERY_DEPTH};
 pub use crate::errors::{
     CommunicationError, CommunicationResult, RegionValidationError, RegionValidationResult,
     VmError, VmResult,
 };
-pub use crate::instance::{DebugInfo, GasReport, Instance, InstanceOptions};
+#[cfg(feature = "host_call_hooks")]
+pub use crate::hooks::{HostCallInfo, HostCallPhase};
+#[cfg(feature = "host_call_tracing")]
+pub use crate::host_call_trace::HostCallRecord;
+pub use crate::instance::{
+    DebugInfo, GasBreakdown, GasReport, Instance, InstanceOptions, SharedDebugHandler,
+};
+pub use crate::modules::{EvictionStrategy, MemoryCachePolicy};
+pub use crate::query_storage::QueryStorage;
 pub use crate::serde::{from_slice, to_vec};
 pub use crate::size::Size;
+pub use crate::wasm_stats::{wasm_stats, SectionSize, WasmStats};
 
 #[doc(hidden)]
 pub mod internals {
@@ -58,5 +95,8 @@ pub mod internals {
 
     pub use crate::compatibility::check_wasm;
     pub use crate::instance::instance_from_module;
-    pub use crate::wasm_backend::{compile, make_engine, make_runtime_store};
+    pub use crate::wasm_backend::{
+        compile, compile_with_operator_cost, make_engine, make_engine_with_operator_cost,
+        make_runtime_store,
+    };
 }
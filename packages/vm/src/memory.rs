@@ -89,6 +89,16 @@ pub fn write_region(memory: &wasmer::MemoryView, ptr: u32, data: &[u8]) -> VmRes
     Ok(())
 }
 
+/// Returns the length of the data in the Region at offset in Wasm memory,
+/// or `None` if the offset is 0 or does not point to a valid Region.
+#[cfg(feature = "host_call_tracing")]
+pub fn region_length(memory: &wasmer::MemoryView, offset: u32) -> Option<u32> {
+    if offset == 0 {
+        return None;
+    }
+    get_region(memory, offset).ok().map(|region| region.length)
+}
+
 /// Reads in a Region at offset in Wasm memory and returns a copy of it
 fn get_region(memory: &wasmer::MemoryView, offset: u32) -> CommunicationResult<Region> {
     let wptr = WasmPtr::<Region>::new(offset);
@@ -1,6 +1,7 @@
+use std::collections::HashSet;
 use std::fs;
 use std::hash::Hash;
-use std::io;
+use std::io::{self, Read};
 use std::path::Path;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -43,11 +44,32 @@ use crate::modules::current_wasmer_module_version;
 ///   fixed by bumping to "v4".
 /// - **v5**:<br>
 ///   Version for cosmwasm_vm 1.3+ which adds a sub-folder with the target identier for the modules.
-const MODULE_SERIALIZATION_VERSION: &str = "v5";
+/// - **v6**:<br>
+///   Version for cosmwasm_vm 1.3+ which exports all mutable globals of the modules, such that pooled
+///   instances can be reset.
+/// - **v7**:<br>
+///   Version for cosmwasm_vm 1.3+ which adds an interrupt flag to the modules that is checked
+///   at the start of functions and loops.
+const MODULE_SERIALIZATION_VERSION: &str = "v7";
+
+/// Magic bytes at the start of every module file written by this cache
+const MODULE_HEADER_MAGIC: &[u8; 8] = b"CWMODULE";
+
+/// The version of the header that is written in front of every serialized module.
+/// Bump this whenever the layout of module files changes.
+const MODULE_HEADER_VERSION: u32 = 1;
+
+const MODULE_HEADER_LEN: usize = 16;
 
 /// Representation of a directory that contains compiled Wasm artifacts.
 pub struct FileSystemCache {
+    /// The base directory containing the directories of all module versions
+    base_path: PathBuf,
     modules_path: PathBuf,
+    /// The header written in front of every module by this version of the cache
+    header: [u8; MODULE_HEADER_LEN],
+    /// Modules in `modules_path` that were written with a different header and cannot be loaded
+    outdated: HashSet<Checksum>,
 }
 
 /// An error type that hides system specific error information
@@ -62,6 +84,8 @@ pub enum NewFileSystemCacheError {
     ExistsButNoDirectory,
     #[error("Could not create cache path")]
     CouldntCreatePath,
+    #[error("Could not read the modules in the cache path")]
+    CouldntReadModules,
 }
 
 impl FileSystemCache {
@@ -89,17 +113,25 @@ impl FileSystemCache {
             mkdir_p(&base_path).map_err(|_e| NewFileSystemCacheError::CouldntCreatePath)?;
         }
 
+        let wasmer_module_version = current_wasmer_module_version();
+        let modules_path = modules_path(&base_path, wasmer_module_version, &Target::default());
+        let header = module_header(wasmer_module_version);
+        let outdated = find_outdated_modules(&modules_path, &header)
+            .map_err(|_e| NewFileSystemCacheError::CouldntReadModules)?;
+
         Ok(Self {
-            modules_path: modules_path(
-                &base_path,
-                current_wasmer_module_version(),
-                &Target::default(),
-            ),
+            base_path,
+            modules_path,
+            header,
+            outdated,
         })
     }
 
     /// Loads a serialized module from the file system and returns a module (i.e. artifact + store),
     /// along with the size of the serialized module.
+    ///
+    /// Outdated modules, i.e. modules written by a different version of the cache, are treated
+    /// as if they did not exist. They are replaced when the module is stored again.
     pub fn load(
         &self,
         checksum: &Checksum,
@@ -108,19 +140,30 @@ impl FileSystemCache {
         let filename = checksum.to_hex();
         let file_path = self.modules_path.join(filename);
 
-        let result = unsafe { Module::deserialize_from_file(engine, &file_path) };
-        match result {
-            Ok(module) => {
-                let module_size = module_size(&file_path)?;
-                Ok(Some((module, module_size)))
+        let bytes = match fs::read(&file_path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                return match err.kind() {
+                    io::ErrorKind::NotFound => Ok(None),
+                    _ => Err(VmError::cache_err(format!(
+                        "Error opening module file: {}",
+                        err
+                    ))),
+                }
             }
-            Err(DeserializeError::Io(err)) => match err.kind() {
-                io::ErrorKind::NotFound => Ok(None),
-                _ => Err(VmError::cache_err(format!(
-                    "Error opening module file: {}",
-                    err
-                ))),
-            },
+        };
+        if !bytes.starts_with(&self.header) {
+            return Ok(None);
+        }
+
+        let serialized = &bytes[MODULE_HEADER_LEN..];
+        let result = unsafe { Module::deserialize(engine, serialized) };
+        match result {
+            Ok(module) => Ok(Some((module, serialized.len()))),
+            Err(DeserializeError::Io(err)) => Err(VmError::cache_err(format!(
+                "Error opening module file: {}",
+                err
+            ))),
             Err(err) => Err(VmError::cache_err(format!(
                 "Error deserializing module: {}",
                 err
@@ -133,13 +176,73 @@ impl FileSystemCache {
         mkdir_p(&self.modules_path)
             .map_err(|_e| VmError::cache_err("Error creating modules directory"))?;
 
+        let serialized = module
+            .serialize()
+            .map_err(|e| VmError::cache_err(format!("Error serializing module: {}", e)))?;
+        let mut bytes = Vec::with_capacity(MODULE_HEADER_LEN + serialized.len());
+        bytes.extend_from_slice(&self.header);
+        bytes.extend_from_slice(&serialized);
+
         let filename = checksum.to_hex();
         let path = self.modules_path.join(filename);
-        module
-            .serialize_to_file(&path)
+        fs::write(path, bytes)
             .map_err(|e| VmError::cache_err(format!("Error writing module to disk: {}", e)))?;
-        let module_size = module_size(&path)?;
-        Ok(module_size)
+        self.outdated.remove(checksum);
+        Ok(serialized.len())
+    }
+
+    /// Returns the checksums of all modules stored in the current modules directory,
+    /// including outdated ones, sorted by their hex representation.
+    pub fn checksums(&self) -> VmResult<Vec<Checksum>> {
+        let mut checksums: Vec<Checksum> = module_files(&self.modules_path)
+            .map_err(|_e| VmError::cache_err("Error reading modules directory"))?
+            .into_iter()
+            .map(|(checksum, _path)| checksum)
+            .collect();
+        checksums.sort_by_key(|checksum| checksum.to_hex());
+        Ok(checksums)
+    }
+
+    /// Returns the checksums of all modules that were written by a different version
+    /// of the cache and cannot be loaded anymore, sorted by their hex representation.
+    pub fn outdated_modules(&self) -> Vec<Checksum> {
+        let mut checksums: Vec<Checksum> = self.outdated.iter().copied().collect();
+        checksums.sort_by_key(|checksum| checksum.to_hex());
+        checksums
+    }
+
+    /// Removes the directories of all other module versions in the base directory,
+    /// which are never read by this version of the cache.
+    ///
+    /// Returns the number of directories removed.
+    pub fn remove_obsolete_versions(&mut self) -> VmResult<usize> {
+        let current_version_dir = self
+            .modules_path
+            .parent()
+            .expect("Modules path must have a parent");
+        let entries = match fs::read_dir(&self.base_path) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(_e) => return Err(VmError::cache_err("Error reading cache directory")),
+        };
+
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry
+                .map_err(|_e| VmError::cache_err("Error reading cache directory"))?
+                .path();
+            let is_version_dir = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(is_version_dir_name)
+                .unwrap_or(false);
+            if is_version_dir && path.is_dir() && path != current_version_dir {
+                fs::remove_dir_all(&path)
+                    .map_err(|_e| VmError::cache_err("Error removing obsolete modules"))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
     }
 
     /// Removes a serialized module from the file system.
@@ -152,6 +255,7 @@ impl FileSystemCache {
         if file_path.exists() {
             fs::remove_file(file_path)
                 .map_err(|_e| VmError::cache_err("Error deleting module from disk"))?;
+            self.outdated.remove(checksum);
             Ok(true)
         } else {
             Ok(false)
@@ -159,15 +263,72 @@ impl FileSystemCache {
     }
 }
 
-/// Returns the size of the module stored on disk
-fn module_size(module_path: &Path) -> VmResult<usize> {
-    let module_size: usize = module_path
-        .metadata()
-        .map_err(|_e| VmError::cache_err("Error getting file metadata"))? // ensure error message is not system specific
-        .len()
-        .try_into()
-        .expect("Could not convert file size to usize");
-    Ok(module_size)
+/// The header in front of every module file, which identifies the version of the cache
+/// and of Wasmer's module format it was written with.
+fn module_header(wasmer_module_version: u32) -> [u8; MODULE_HEADER_LEN] {
+    let mut header = [0u8; MODULE_HEADER_LEN];
+    header[..8].copy_from_slice(MODULE_HEADER_MAGIC);
+    header[8..12].copy_from_slice(&MODULE_HEADER_VERSION.to_le_bytes());
+    header[12..].copy_from_slice(&wasmer_module_version.to_le_bytes());
+    header
+}
+
+/// Lists all files in the given directory that are named after a checksum
+fn module_files(modules_path: &Path) -> io::Result<Vec<(Checksum, PathBuf)>> {
+    let entries = match fs::read_dir(modules_path) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut out = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let checksum = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| hex::decode(name).ok())
+            .and_then(|data| Checksum::try_from(data.as_slice()).ok());
+        if let Some(checksum) = checksum {
+            out.push((checksum, entry.path()));
+        }
+    }
+    Ok(out)
+}
+
+/// Finds all modules in the given directory that do not start with the expected header.
+/// Files that are not named after a checksum are ignored.
+fn find_outdated_modules(
+    modules_path: &Path,
+    header: &[u8; MODULE_HEADER_LEN],
+) -> io::Result<HashSet<Checksum>> {
+    let mut outdated = HashSet::new();
+    for (checksum, path) in module_files(modules_path)? {
+        let mut file_header = Vec::with_capacity(MODULE_HEADER_LEN);
+        fs::File::open(path)?
+            .take(MODULE_HEADER_LEN as u64)
+            .read_to_end(&mut file_header)?;
+        if file_header != header {
+            outdated.insert(checksum);
+        }
+    }
+    Ok(outdated)
+}
+
+/// Returns true if the given directory name has the format of the version directories
+/// created by [`modules_path`], e.g. "v7-wasmer4"
+fn is_version_dir_name(name: &str) -> bool {
+    match name
+        .strip_prefix('v')
+        .and_then(|rest| rest.split_once("-wasmer"))
+    {
+        Some((serialization_version, wasmer_version)) => {
+            !serialization_version.is_empty()
+                && !wasmer_version.is_empty()
+                && serialization_version.chars().all(|c| c.is_ascii_digit())
+                && wasmer_version.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
 }
 
 /// Creates an identifier for the Wasmer `Target` that is used for
@@ -264,7 +425,7 @@ mod tests {
         cache.store(&checksum, &module).unwrap();
 
         let mut globber = glob::glob(&format!(
-            "{}/v5-wasmer4/**/{}",
+            "{}/v7-wasmer4/**/{}",
             tmp_dir.path().to_string_lossy(),
             checksum
         ))
@@ -289,6 +450,7 @@ mod tests {
         // It's there
         let store = make_runtime_store(TESTING_MEMORY_LIMIT);
         assert!(cache.load(&checksum, &store).unwrap().is_some());
+        assert_eq!(cache.checksums().unwrap(), vec![checksum]);
 
         // Remove module
         let existed = cache.remove(&checksum).unwrap();
@@ -297,12 +459,99 @@ mod tests {
         // it's gone now
         let store = make_runtime_store(TESTING_MEMORY_LIMIT);
         assert!(cache.load(&checksum, &store).unwrap().is_none());
+        assert_eq!(cache.checksums().unwrap(), Vec::<Checksum>::new());
 
         // Remove again
         let existed = cache.remove(&checksum).unwrap();
         assert!(!existed);
     }
 
+    #[test]
+    fn file_system_cache_store_writes_header() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache = unsafe { FileSystemCache::new(tmp_dir.path()).unwrap() };
+
+        let wasm = wat::parse_str(SOME_WAT).unwrap();
+        let checksum = Checksum::generate(&wasm);
+        let (_engine, module) = compile(&wasm, &[]).unwrap();
+        let module_size = cache.store(&checksum, &module).unwrap();
+
+        let content = fs::read(cache.modules_path.join(checksum.to_hex())).unwrap();
+        assert_eq!(content.len(), MODULE_HEADER_LEN + module_size);
+        assert_eq!(&content[..8], b"CWMODULE");
+        assert_eq!(&content[8..12], &MODULE_HEADER_VERSION.to_le_bytes());
+        assert_eq!(
+            &content[12..16],
+            &current_wasmer_module_version().to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn file_system_cache_detects_outdated_modules() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache = unsafe { FileSystemCache::new(tmp_dir.path()).unwrap() };
+        assert_eq!(cache.outdated_modules(), Vec::<Checksum>::new());
+
+        let wasm = wat::parse_str(SOME_WAT).unwrap();
+        let checksum = Checksum::generate(&wasm);
+        let (_engine, module) = compile(&wasm, &[]).unwrap();
+        cache.store(&checksum, &module).unwrap();
+
+        // Overwrite with a module without header as written by previous versions
+        let path = cache.modules_path.join(checksum.to_hex());
+        module.serialize_to_file(&path).unwrap();
+        // Files not named after a checksum are ignored
+        fs::write(cache.modules_path.join("foo.tmp"), b"bar").unwrap();
+
+        let mut cache = unsafe { FileSystemCache::new(tmp_dir.path()).unwrap() };
+        assert_eq!(cache.outdated_modules(), vec![checksum]);
+
+        // Outdated modules are not loaded
+        let store = make_runtime_store(TESTING_MEMORY_LIMIT);
+        assert!(cache.load(&checksum, &store).unwrap().is_none());
+
+        // Storing again replaces the outdated module
+        cache.store(&checksum, &module).unwrap();
+        assert_eq!(cache.outdated_modules(), Vec::<Checksum>::new());
+        assert!(cache.load(&checksum, &store).unwrap().is_some());
+    }
+
+    #[test]
+    fn file_system_cache_remove_obsolete_versions_works() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache = unsafe { FileSystemCache::new(tmp_dir.path()).unwrap() };
+
+        let wasm = wat::parse_str(SOME_WAT).unwrap();
+        let checksum = Checksum::generate(&wasm);
+        let (_engine, module) = compile(&wasm, &[]).unwrap();
+        cache.store(&checksum, &module).unwrap();
+
+        fs::create_dir_all(tmp_dir.path().join("v4-wasmer3").join("target")).unwrap();
+        fs::create_dir_all(tmp_dir.path().join("v5-wasmer1")).unwrap();
+        fs::create_dir_all(tmp_dir.path().join("operator-cost-300")).unwrap();
+
+        assert_eq!(cache.remove_obsolete_versions().unwrap(), 2);
+        assert!(!tmp_dir.path().join("v4-wasmer3").exists());
+        assert!(!tmp_dir.path().join("v5-wasmer1").exists());
+        // unrelated directories and the current version are kept
+        assert!(tmp_dir.path().join("operator-cost-300").exists());
+        let store = make_runtime_store(TESTING_MEMORY_LIMIT);
+        assert!(cache.load(&checksum, &store).unwrap().is_some());
+
+        assert_eq!(cache.remove_obsolete_versions().unwrap(), 0);
+    }
+
+    #[test]
+    fn is_version_dir_name_works() {
+        assert!(is_version_dir_name("v5-wasmer4"));
+        assert!(is_version_dir_name("v12-wasmer123"));
+        assert!(!is_version_dir_name("v5-wasmer"));
+        assert!(!is_version_dir_name("v-wasmer4"));
+        assert!(!is_version_dir_name("5-wasmer4"));
+        assert!(!is_version_dir_name("v5-wasmerx"));
+        assert!(!is_version_dir_name("operator-cost-300"));
+    }
+
     #[test]
     fn target_id_works() {
         let triple = wasmer::Triple {
@@ -342,9 +591,9 @@ mod tests {
         assert_eq!(
             p.as_os_str(),
             if cfg!(windows) {
-                "modules\\v5-wasmer17\\x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
+                "modules\\v7-wasmer17\\x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
             } else {
-                "modules/v5-wasmer17/x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
+                "modules/v7-wasmer17/x86_64-nintendo-fuchsia-gnu-coff-01E9F9FE"
             }
         );
     }
@@ -1,6 +1,8 @@
 use clru::{CLruCache, CLruCacheConfig, WeightScale};
+use std::cell::Cell;
 use std::collections::hash_map::RandomState;
 use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
 use wasmer::{Engine, Module};
 
 use super::cached_module::CachedModule;
@@ -15,29 +17,65 @@ use crate::{Checksum, Size, VmError, VmResult};
 // Which is a very small percentage (~0.03%) of our typical cache memory budget (2 GB).
 const MINIMUM_MODULE_SIZE: Size = Size::kibi(250);
 
+/// The strategy used to pick the module that is removed from the
+/// memory cache when it runs out of space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EvictionStrategy {
+    /// Evicts the module that was not used for the longest time
+    #[default]
+    LeastRecentlyUsed,
+    /// Evicts the module with the fewest cache hits since it was stored.
+    /// Among modules with the same number of hits, the least recently used one is evicted.
+    LeastFrequentlyUsed,
+}
+
+/// Limits of the memory cache in addition to its total size.
+///
+/// The default has no additional limits and uses LRU eviction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryCachePolicy {
+    /// The maximum number of modules held in the cache, independent of their size
+    pub max_entries: Option<usize>,
+    /// The time after which a stored module expires and must be loaded from
+    /// the file system cache again
+    pub ttl: Option<Duration>,
+    pub eviction: EvictionStrategy,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    module: CachedModule,
+    stored_at: Instant,
+    hits: Cell<u64>,
+}
+
 #[derive(Debug)]
 struct SizeScale;
 
-impl WeightScale<Checksum, CachedModule> for SizeScale {
+impl WeightScale<Checksum, CacheEntry> for SizeScale {
     #[inline]
-    fn weight(&self, _key: &Checksum, value: &CachedModule) -> usize {
-        value.size
+    fn weight(&self, _key: &Checksum, value: &CacheEntry) -> usize {
+        value.module.size
     }
 }
 
 /// An in-memory module cache
 pub struct InMemoryCache {
-    modules: Option<CLruCache<Checksum, CachedModule, RandomState, SizeScale>>,
+    modules: Option<CLruCache<Checksum, CacheEntry, RandomState, SizeScale>>,
+    policy: MemoryCachePolicy,
 }
 
 impl InMemoryCache {
-    /// Creates a new cache with the given size (in bytes)
+    /// Creates a new cache with the given size (in bytes), additional limits
     /// and pre-allocated entries.
-    pub fn new(size: Size) -> Self {
-        let preallocated_entries = size.0 / MINIMUM_MODULE_SIZE.0;
+    pub fn new(size: Size, policy: MemoryCachePolicy) -> Self {
+        let mut preallocated_entries = size.0 / MINIMUM_MODULE_SIZE.0;
+        if let Some(max_entries) = policy.max_entries {
+            preallocated_entries = preallocated_entries.min(max_entries);
+        }
 
         InMemoryCache {
-            modules: if size.0 > 0 {
+            modules: if size.0 > 0 && policy.max_entries != Some(0) {
                 Some(CLruCache::with_config(
                     CLruCacheConfig::new(NonZeroUsize::new(size.0).unwrap())
                         .with_memory(preallocated_entries)
@@ -46,6 +84,7 @@ impl InMemoryCache {
             } else {
                 None
             },
+            policy,
         }
     }
 
@@ -55,14 +94,29 @@ impl InMemoryCache {
         entry: (Engine, Module),
         size: usize,
     ) -> VmResult<()> {
+        let policy = self.policy;
         if let Some(modules) = &mut self.modules {
+            // Same condition as in `put_with_weight`, checked here to avoid evicting
+            // other modules for an element that does not fit anyways
+            if size >= modules.capacity() {
+                return Err(VmError::cache_err(format!(
+                    "Module of size {} does not fit into the memory cache",
+                    size
+                )));
+            }
+            modules.pop(checksum);
+            make_room(modules, &policy, size);
             modules
                 .put_with_weight(
                     *checksum,
-                    CachedModule {
-                        engine: entry.0,
-                        module: entry.1,
-                        size,
+                    CacheEntry {
+                        module: CachedModule {
+                            engine: entry.0,
+                            module: entry.1,
+                            size,
+                        },
+                        stored_at: Instant::now(),
+                        hits: Cell::new(0),
                     },
                 )
                 .map_err(|e| VmError::cache_err(format!("{:?}", e)))?;
@@ -73,8 +127,19 @@ impl InMemoryCache {
     /// Looks up a module in the cache and creates a new module
     pub fn load(&mut self, checksum: &Checksum) -> VmResult<Option<CachedModule>> {
         if let Some(modules) = &mut self.modules {
+            let expired = match modules.peek(checksum) {
+                Some(cached) => is_expired(cached, &self.policy),
+                None => return Ok(None),
+            };
+            if expired {
+                modules.pop(checksum);
+                return Ok(None);
+            }
             match modules.get(checksum) {
-                Some(cached) => Ok(Some(cached.clone())),
+                Some(cached) => {
+                    cached.hits.set(cached.hits.get().saturating_add(1));
+                    Ok(Some(cached.module.clone()))
+                }
                 None => Ok(None),
             }
         } else {
@@ -82,6 +147,36 @@ impl InMemoryCache {
         }
     }
 
+    /// Returns true if and only if this cache has an unexpired entry identified by the given checksum.
+    ///
+    /// In contrast to `load`, this does not count as a use of the module.
+    pub fn has(&self, checksum: &Checksum) -> bool {
+        match &self.modules {
+            Some(modules) => match modules.peek(checksum) {
+                Some(cached) => !is_expired(cached, &self.policy),
+                None => false,
+            },
+            None => false,
+        }
+    }
+
+    /// Removes the module identified by the given checksum.
+    /// Returns true if the module was in the cache.
+    pub fn remove(&mut self, checksum: &Checksum) -> bool {
+        match &mut self.modules {
+            Some(modules) => modules.pop(checksum).is_some(),
+            None => false,
+        }
+    }
+
+    /// Returns the checksums of all modules in the cache, including expired ones
+    pub fn checksums(&self) -> Vec<Checksum> {
+        match &self.modules {
+            Some(modules) => modules.iter().map(|(checksum, _)| *checksum).collect(),
+            None => Vec::new(),
+        }
+    }
+
     /// Returns the number of elements in the cache.
     pub fn len(&self) -> usize {
         self.modules
@@ -100,6 +195,77 @@ impl InMemoryCache {
             .map(|modules| modules.weight())
             .unwrap_or_default()
     }
+
+    /// Returns the maximum cumulative size of all elements in the cache,
+    /// i.e. the size the cache was created with.
+    pub fn capacity(&self) -> usize {
+        self.modules
+            .as_ref()
+            .map(|modules| modules.capacity())
+            .unwrap_or_default()
+    }
+}
+
+fn is_expired(entry: &CacheEntry, policy: &MemoryCachePolicy) -> bool {
+    match policy.ttl {
+        Some(ttl) => entry.stored_at.elapsed() >= ttl,
+        None => false,
+    }
+}
+
+/// Evicts modules according to the policy until a new module of the given size can be
+/// stored without exceeding the entry limit or triggering the size based LRU eviction
+/// of the underlying cache.
+fn make_room(
+    modules: &mut CLruCache<Checksum, CacheEntry, RandomState, SizeScale>,
+    policy: &MemoryCachePolicy,
+    size: usize,
+) {
+    if policy.ttl.is_some() {
+        // Not using `retain` since it does not update the cache's weight
+        let expired: Vec<Checksum> = modules
+            .iter()
+            .filter(|(_, entry)| is_expired(entry, policy))
+            .map(|(checksum, _)| *checksum)
+            .collect();
+        for checksum in expired {
+            modules.pop(&checksum);
+        }
+    }
+
+    loop {
+        let too_many = match policy.max_entries {
+            Some(max_entries) => modules.len() >= max_entries,
+            None => false,
+        };
+        // Mirrors the eviction condition of `CLruCache::put_with_weight`
+        let too_large = modules.len() + modules.weight() + size >= modules.capacity();
+        if !too_many && !too_large {
+            break;
+        }
+
+        let victim = match policy.eviction {
+            EvictionStrategy::LeastRecentlyUsed => modules.back().map(|(checksum, _)| *checksum),
+            // Iterates from most to least recently used, such that ties are resolved
+            // in favour of evicting the least recently used module
+            EvictionStrategy::LeastFrequentlyUsed => modules
+                .iter()
+                .fold(None::<(&Checksum, u64)>, |min, (checksum, entry)| {
+                    let hits = entry.hits.get();
+                    match min {
+                        Some((_, min_hits)) if min_hits < hits => min,
+                        _ => Some((checksum, hits)),
+                    }
+                })
+                .map(|(checksum, _)| *checksum),
+        };
+        match victim {
+            Some(checksum) => {
+                modules.pop(&checksum);
+            }
+            None => break,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -130,7 +296,7 @@ mod tests {
 
     #[test]
     fn in_memory_cache_run() {
-        let mut cache = InMemoryCache::new(Size::mebi(200));
+        let mut cache = InMemoryCache::new(Size::mebi(200), MemoryCachePolicy::default());
 
         // Create module
         let wasm = wat::parse_str(
@@ -181,7 +347,7 @@ mod tests {
 
     #[test]
     fn len_works() {
-        let mut cache = InMemoryCache::new(Size::mebi(2));
+        let mut cache = InMemoryCache::new(Size::mebi(2), MemoryCachePolicy::default());
 
         // Create module
         let wasm1 = wat::parse_str(
@@ -241,7 +407,7 @@ mod tests {
 
     #[test]
     fn size_works() {
-        let mut cache = InMemoryCache::new(Size::mebi(2));
+        let mut cache = InMemoryCache::new(Size::mebi(2), MemoryCachePolicy::default());
 
         // Create module
         let wasm1 = wat::parse_str(
@@ -298,4 +464,181 @@ mod tests {
             .unwrap();
         assert_eq!(cache.size(), 1_500_000);
     }
+
+    fn make_testing_modules(count: u8) -> Vec<(Checksum, Vec<u8>)> {
+        (0..count)
+            .map(|i| {
+                let wasm = wat::parse_str(format!(
+                    r#"(module
+                    (type $t0 (func (param i32) (result i32)))
+                    (func $add (export "add") (type $t0) (param $p0 i32) (result i32)
+                        get_local $p0
+                        i32.const {}
+                        i32.add)
+                    )"#,
+                    i
+                ))
+                .unwrap();
+                (Checksum::generate(&wasm), wasm)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut cache = InMemoryCache::new(Size::mebi(200), MemoryCachePolicy::default());
+        let modules = make_testing_modules(2);
+        for (checksum, wasm) in &modules {
+            cache
+                .store(checksum, compile(wasm, &[]).unwrap(), 100)
+                .unwrap();
+        }
+        let (checksum1, _) = &modules[0];
+        let (checksum2, _) = &modules[1];
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size(), 200);
+
+        assert!(cache.remove(checksum1));
+        assert!(!cache.has(checksum1));
+        assert_eq!(cache.checksums(), vec![*checksum2]);
+        assert_eq!(cache.size(), 100);
+
+        // Removing again does nothing
+        assert!(!cache.remove(checksum1));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn max_entries_works() {
+        let policy = MemoryCachePolicy {
+            max_entries: Some(2),
+            ..MemoryCachePolicy::default()
+        };
+        let mut cache = InMemoryCache::new(Size::mebi(200), policy);
+        let modules = make_testing_modules(3);
+
+        for (checksum, wasm) in &modules {
+            cache
+                .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+                .unwrap();
+        }
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size(), 200_000);
+        assert!(cache.load(&modules[0].0).unwrap().is_none());
+        assert!(cache.load(&modules[1].0).unwrap().is_some());
+        assert!(cache.load(&modules[2].0).unwrap().is_some());
+
+        // Storing an existing module does not evict anything
+        let (checksum, wasm) = &modules[2];
+        cache
+            .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+            .unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.load(&modules[1].0).unwrap().is_some());
+
+        // Zero entries disables the cache
+        let policy = MemoryCachePolicy {
+            max_entries: Some(0),
+            ..MemoryCachePolicy::default()
+        };
+        let mut cache = InMemoryCache::new(Size::mebi(200), policy);
+        cache
+            .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+            .unwrap();
+        assert_eq!(cache.len(), 0);
+        assert!(cache.load(checksum).unwrap().is_none());
+    }
+
+    #[test]
+    fn ttl_works() {
+        let policy = MemoryCachePolicy {
+            ttl: Some(Duration::from_millis(50)),
+            ..MemoryCachePolicy::default()
+        };
+        let mut cache = InMemoryCache::new(Size::mebi(200), policy);
+        let modules = make_testing_modules(2);
+
+        let (checksum, wasm) = &modules[0];
+        cache
+            .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+            .unwrap();
+        assert!(cache.load(checksum).unwrap().is_some());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(cache.load(checksum).unwrap().is_none());
+        assert_eq!(cache.len(), 0);
+
+        // Expired modules are removed when storing a new one
+        cache
+            .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(60));
+        let (checksum, wasm) = &modules[1];
+        cache
+            .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+            .unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.size(), 100_000);
+    }
+
+    #[test]
+    fn least_frequently_used_eviction_works() {
+        let policy = MemoryCachePolicy {
+            max_entries: Some(3),
+            eviction: EvictionStrategy::LeastFrequentlyUsed,
+            ..MemoryCachePolicy::default()
+        };
+        let mut cache = InMemoryCache::new(Size::mebi(200), policy);
+        let modules = make_testing_modules(5);
+
+        for (checksum, wasm) in &modules[0..3] {
+            cache
+                .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+                .unwrap();
+        }
+        // Module 0 is used often but not recently, module 1 once and module 2 never
+        for _ in 0..3 {
+            cache.load(&modules[0].0).unwrap().unwrap();
+        }
+        cache.load(&modules[1].0).unwrap().unwrap();
+
+        // Evicts module 2, which LRU would keep
+        let (checksum, wasm) = &modules[3];
+        cache
+            .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+            .unwrap();
+        assert_eq!(cache.len(), 3);
+        assert!(cache.load(&modules[2].0).unwrap().is_none());
+
+        // Module 1 and 3 have one hit each now, so the less recently used module 1 is evicted
+        cache.load(&modules[3].0).unwrap().unwrap();
+        let (checksum, wasm) = &modules[4];
+        cache
+            .store(checksum, compile(wasm, &[]).unwrap(), 100_000)
+            .unwrap();
+        assert!(cache.load(&modules[1].0).unwrap().is_none());
+        assert!(cache.load(&modules[0].0).unwrap().is_some());
+        assert!(cache.load(&modules[3].0).unwrap().is_some());
+        assert!(cache.load(&modules[4].0).unwrap().is_some());
+
+        // Size based eviction uses the same strategy
+        let policy = MemoryCachePolicy {
+            eviction: EvictionStrategy::LeastFrequentlyUsed,
+            ..MemoryCachePolicy::default()
+        };
+        let mut cache = InMemoryCache::new(Size::mebi(2), policy);
+        for (checksum, wasm) in &modules[0..2] {
+            cache
+                .store(checksum, compile(wasm, &[]).unwrap(), 900_000)
+                .unwrap();
+        }
+        cache.load(&modules[0].0).unwrap().unwrap();
+        let (checksum, wasm) = &modules[2];
+        cache
+            .store(checksum, compile(wasm, &[]).unwrap(), 900_000)
+            .unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.load(&modules[0].0).unwrap().is_some());
+        assert!(cache.load(&modules[1].0).unwrap().is_none());
+    }
 }
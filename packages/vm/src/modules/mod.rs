@@ -6,6 +6,6 @@ mod versioning;
 
 pub use cached_module::CachedModule;
 pub use file_system_cache::{FileSystemCache, NewFileSystemCacheError};
-pub use in_memory_cache::InMemoryCache;
+pub use in_memory_cache::{EvictionStrategy, InMemoryCache, MemoryCachePolicy};
 pub use pinned_memory_cache::PinnedMemoryCache;
 pub use versioning::current_wasmer_module_version;
@@ -54,6 +54,13 @@ impl PinnedMemoryCache {
         self.modules.contains_key(checksum)
     }
 
+    /// Returns the checksums of all elements in the cache, sorted by their hex representation
+    pub fn checksums(&self) -> Vec<Checksum> {
+        let mut checksums: Vec<Checksum> = self.modules.keys().copied().collect();
+        checksums.sort_by_cached_key(|checksum| checksum.to_hex());
+        checksums
+    }
+
     /// Returns the number of elements in the cache.
     pub fn len(&self) -> usize {
         self.modules.len()
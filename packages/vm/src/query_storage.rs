@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+#[cfg(feature = "iterator")]
+use cosmwasm_std::{Order, Record};
+
+use crate::backend::{BackendError, BackendResult, GasInfo, ReadonlyStorage, Storage};
+
+#[cfg(feature = "iterator")]
+struct Cursor {
+    start: Option<Vec<u8>>,
+    end: Option<Vec<u8>>,
+    order: Order,
+    done: bool,
+}
+
+/// A [`Storage`] for queries that reads from a shared [`ReadonlyStorage`] snapshot.
+///
+/// Writing is not possible, so instances using this storage can run concurrently against
+/// the same snapshot without cloning it. Iterators are kept per `QueryStorage`, such that
+/// iterator IDs are independent of other instances.
+pub struct QueryStorage<T: ReadonlyStorage> {
+    snapshot: Arc<T>,
+    #[cfg(feature = "iterator")]
+    iterators: Vec<Cursor>,
+}
+
+impl<T: ReadonlyStorage> QueryStorage<T> {
+    pub fn new(snapshot: Arc<T>) -> Self {
+        QueryStorage {
+            snapshot,
+            #[cfg(feature = "iterator")]
+            iterators: Vec::new(),
+        }
+    }
+
+    /// Returns the shared snapshot
+    pub fn snapshot(&self) -> &Arc<T> {
+        &self.snapshot
+    }
+}
+
+impl<T: ReadonlyStorage> Storage for QueryStorage<T> {
+    const READONLY: bool = true;
+
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        self.snapshot.get(key)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn scan(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<u32> {
+        self.iterators.push(Cursor {
+            start: start.map(|start| start.to_vec()),
+            end: end.map(|end| end.to_vec()),
+            order,
+            done: false,
+        });
+        // Starting at 1 like MockStorage
+        let id = self
+            .iterators
+            .len()
+            .try_into()
+            .expect("Found more iterator IDs than supported");
+        (Ok(id), GasInfo::free())
+    }
+
+    #[cfg(feature = "iterator")]
+    fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+        let cursor = match (iterator_id as usize)
+            .checked_sub(1)
+            .and_then(|index| self.iterators.get_mut(index))
+        {
+            Some(cursor) => cursor,
+            None => {
+                return (
+                    Err(BackendError::iterator_does_not_exist(iterator_id)),
+                    GasInfo::free(),
+                )
+            }
+        };
+        if cursor.done {
+            return (Ok(None), GasInfo::free());
+        }
+
+        let (result, gas_info) =
+            self.snapshot
+                .first(cursor.start.as_deref(), cursor.end.as_deref(), cursor.order);
+        match &result {
+            // Narrow the range such that the next call returns the following record
+            Ok(Some((key, _))) => match cursor.order {
+                Order::Ascending => {
+                    let mut successor = key.clone();
+                    successor.push(0);
+                    cursor.start = Some(successor);
+                }
+                Order::Descending => cursor.end = Some(key.clone()),
+            },
+            Ok(None) => cursor.done = true,
+            Err(_) => {}
+        }
+        (result, gas_info)
+    }
+
+    fn set(&mut self, _key: &[u8], _value: &[u8]) -> BackendResult<()> {
+        (Err(read_only_error()), GasInfo::free())
+    }
+
+    fn remove(&mut self, _key: &[u8]) -> BackendResult<()> {
+        (Err(read_only_error()), GasInfo::free())
+    }
+}
+
+fn read_only_error() -> BackendError {
+    BackendError::unknown("Query storage is read-only")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockStorage;
+
+    fn make_snapshot() -> Arc<MockStorage> {
+        let mut storage = MockStorage::new();
+        storage.set(b"foo", b"bar").0.unwrap();
+        storage.set(b"food", b"bank").0.unwrap();
+        storage.set(b"zoo", b"zebra").0.unwrap();
+        Arc::new(storage)
+    }
+
+    #[test]
+    fn get_works() {
+        let storage = QueryStorage::new(make_snapshot());
+        assert_eq!(storage.get(b"foo").0.unwrap(), Some(b"bar".to_vec()));
+        assert_eq!(storage.get(b"fo").0.unwrap(), None);
+    }
+
+    #[test]
+    fn set_and_remove_fail() {
+        let mut storage = QueryStorage::new(make_snapshot());
+        match storage.set(b"foo", b"baz").0.unwrap_err() {
+            BackendError::Unknown { msg } => assert_eq!(msg, "Query storage is read-only"),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        match storage.remove(b"foo").0.unwrap_err() {
+            BackendError::Unknown { msg } => assert_eq!(msg, "Query storage is read-only"),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        assert_eq!(storage.get(b"foo").0.unwrap(), Some(b"bar".to_vec()));
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn iteration_works() {
+        let snapshot = make_snapshot();
+        let mut storage = QueryStorage::new(snapshot.clone());
+
+        let ascending = storage.scan(None, None, Order::Ascending).0.unwrap();
+        let descending = storage
+            .scan(Some(b"foo"), Some(b"zoo"), Order::Descending)
+            .0
+            .unwrap();
+        assert_eq!(ascending, 1);
+        assert_eq!(descending, 2);
+
+        // Iterators are independent of each other and of other storages using the same snapshot
+        let mut other = QueryStorage::new(snapshot);
+        assert_eq!(other.scan(None, None, Order::Ascending).0.unwrap(), 1);
+        assert_eq!(
+            other.next(1).0.unwrap(),
+            Some((b"foo".to_vec(), b"bar".to_vec()))
+        );
+
+        assert_eq!(
+            storage.next(ascending).0.unwrap(),
+            Some((b"foo".to_vec(), b"bar".to_vec()))
+        );
+        assert_eq!(
+            storage.next(descending).0.unwrap(),
+            Some((b"food".to_vec(), b"bank".to_vec()))
+        );
+        assert_eq!(
+            storage.next(ascending).0.unwrap(),
+            Some((b"food".to_vec(), b"bank".to_vec()))
+        );
+        assert_eq!(
+            storage.next(ascending).0.unwrap(),
+            Some((b"zoo".to_vec(), b"zebra".to_vec()))
+        );
+        assert_eq!(storage.next(ascending).0.unwrap(), None);
+        assert_eq!(storage.next(ascending).0.unwrap(), None);
+        assert_eq!(
+            storage.next(descending).0.unwrap(),
+            Some((b"foo".to_vec(), b"bar".to_vec()))
+        );
+        assert_eq!(storage.next(descending).0.unwrap(), None);
+
+        match storage.next(3).0.unwrap_err() {
+            BackendError::IteratorDoesNotExist { id } => assert_eq!(id, 3),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+        match storage.next(0).0.unwrap_err() {
+            BackendError::IteratorDoesNotExist { id } => assert_eq!(id, 0),
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+}
@@ -1,5 +1,8 @@
-use parity_wasm::elements::{deserialize_buffer, Internal, Module};
-use std::collections::HashSet;
+use parity_wasm::elements::{
+    deserialize_buffer, External, Instruction, Internal, MemoryType, Module,
+};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
 
 use crate::errors::{VmError, VmResult};
 
@@ -12,6 +15,24 @@ pub const REQUIRED_IBC_EXPORTS: &[&str] = &[
     "ibc_packet_timeout",
 ];
 
+/// All entry points the VM can call, i.e. exported functions with a special meaning.
+pub const ENTRY_POINTS: &[&str] = &[
+    "instantiate",
+    "execute",
+    "migrate",
+    "sudo",
+    "reply",
+    "query",
+    "ibc_channel_open",
+    "ibc_channel_connect",
+    "ibc_channel_close",
+    "ibc_packet_receive",
+    "ibc_packet_ack",
+    "ibc_packet_timeout",
+    "ibc_source_callback",
+    "ibc_destination_callback",
+];
+
 pub fn deserialize_wasm(wasm_code: &[u8]) -> VmResult<Module> {
     deserialize_buffer(wasm_code).map_err(|err| {
         VmError::static_validation_err(format!(
@@ -82,6 +103,206 @@ pub fn has_ibc_entry_points(module: &impl ExportInfo) -> bool {
         .all(|required| available_exports.contains(*required))
 }
 
+/// A category of Wasm constructs that can lead to non-deterministic execution
+/// and are therefore not allowed in contracts.
+///
+/// Float operations are not part of this list since they are handled by the gatekeeper
+/// during compilation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NonDeterministicConstruct {
+    /// Operations of the "Threads and atomics" proposal, such as
+    /// `i32.atomic.rmw.add` or `memory.atomic.wait32`.
+    ThreadsAtomics,
+    /// Operations of the "Bulk memory operations" proposal as well as passive
+    /// data and element segments, which can only be used through those operations.
+    BulkMemory,
+    /// A defined or imported memory with the shared flag set.
+    SharedMemory,
+    /// An imported global. Its value is provided by the host at instantiation time.
+    ImportedGlobal,
+}
+
+impl fmt::Display for NonDeterministicConstruct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonDeterministicConstruct::ThreadsAtomics => f.write_str("threads/atomics operations"),
+            NonDeterministicConstruct::BulkMemory => {
+                f.write_str("bulk memory operations or passive segments")
+            }
+            NonDeterministicConstruct::SharedMemory => f.write_str("shared memory"),
+            NonDeterministicConstruct::ImportedGlobal => f.write_str("imported globals"),
+        }
+    }
+}
+
+/// Returns all categories of non-deterministic constructs found in the module.
+/// An empty set means none of the checked constructs are used.
+pub fn non_deterministic_constructs(module: &Module) -> BTreeSet<NonDeterministicConstruct> {
+    let mut out = BTreeSet::new();
+
+    let is_shared = |memory: &MemoryType| memory.limits().shared();
+    if module
+        .memory_section()
+        .is_some_and(|section| section.entries().iter().any(is_shared))
+    {
+        out.insert(NonDeterministicConstruct::SharedMemory);
+    }
+
+    if let Some(import_section) = module.import_section() {
+        for entry in import_section.entries() {
+            match entry.external() {
+                External::Global(_) => {
+                    out.insert(NonDeterministicConstruct::ImportedGlobal);
+                }
+                External::Memory(memory) if is_shared(memory) => {
+                    out.insert(NonDeterministicConstruct::SharedMemory);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let has_passive_data = module
+        .data_section()
+        .is_some_and(|section| section.entries().iter().any(|s| s.passive()));
+    let has_passive_elements = module
+        .elements_section()
+        .is_some_and(|section| section.entries().iter().any(|s| s.passive()));
+    if has_passive_data || has_passive_elements {
+        out.insert(NonDeterministicConstruct::BulkMemory);
+    }
+
+    if let Some(code_section) = module.code_section() {
+        for body in code_section.bodies() {
+            for instruction in body.code().elements() {
+                match instruction {
+                    Instruction::Atomics(_) => {
+                        out.insert(NonDeterministicConstruct::ThreadsAtomics);
+                    }
+                    Instruction::Bulk(_) => {
+                        out.insert(NonDeterministicConstruct::BulkMemory);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Returns all entry points ([`ENTRY_POINTS`]) that exist as exported functions
+pub fn entry_points(module: &impl ExportInfo) -> BTreeSet<String> {
+    let available_exports = module.exported_function_names(None);
+    ENTRY_POINTS
+        .iter()
+        .filter(|entry_point| available_exports.contains(**entry_point))
+        .map(|entry_point| entry_point.to_string())
+        .collect()
+}
+
+/// Returns all imports of the module in the format `<module>.<field>`, e.g. `env.db_read`
+pub fn required_imports(module: &Module) -> BTreeSet<String> {
+    module
+        .import_section()
+        .map_or(BTreeSet::default(), |import_section| {
+            import_section
+                .entries()
+                .iter()
+                .map(|entry| format!("{}.{}", entry.module(), entry.field()))
+                .collect()
+        })
+}
+
+/// Returns true if and only if the code contains at least one float operation.
+///
+/// Such contracts are rejected by the gatekeeper during compilation. This allows
+/// detecting them without compiling.
+pub fn has_float_operations(module: &Module) -> bool {
+    module.code_section().is_some_and(|code_section| {
+        code_section
+            .bodies()
+            .iter()
+            .flat_map(|body| body.code().elements())
+            .any(is_float_instruction)
+    })
+}
+
+fn is_float_instruction(instruction: &Instruction) -> bool {
+    use Instruction::*;
+    matches!(
+        instruction,
+        F32Load(..)
+            | F64Load(..)
+            | F32Store(..)
+            | F64Store(..)
+            | F32Const(_)
+            | F64Const(_)
+            | F32Eq
+            | F32Ne
+            | F32Lt
+            | F32Gt
+            | F32Le
+            | F32Ge
+            | F64Eq
+            | F64Ne
+            | F64Lt
+            | F64Gt
+            | F64Le
+            | F64Ge
+            | F32Abs
+            | F32Neg
+            | F32Ceil
+            | F32Floor
+            | F32Trunc
+            | F32Nearest
+            | F32Sqrt
+            | F32Add
+            | F32Sub
+            | F32Mul
+            | F32Div
+            | F32Min
+            | F32Max
+            | F32Copysign
+            | F64Abs
+            | F64Neg
+            | F64Ceil
+            | F64Floor
+            | F64Trunc
+            | F64Nearest
+            | F64Sqrt
+            | F64Add
+            | F64Sub
+            | F64Mul
+            | F64Div
+            | F64Min
+            | F64Max
+            | F64Copysign
+            | I32TruncSF32
+            | I32TruncUF32
+            | I32TruncSF64
+            | I32TruncUF64
+            | I64TruncSF32
+            | I64TruncUF32
+            | I64TruncSF64
+            | I64TruncUF64
+            | F32ConvertSI32
+            | F32ConvertUI32
+            | F32ConvertSI64
+            | F32ConvertUI64
+            | F32DemoteF64
+            | F64ConvertSI32
+            | F64ConvertUI32
+            | F64ConvertSI64
+            | F64ConvertUI64
+            | F64PromoteF32
+            | I32ReinterpretF32
+            | I64ReinterpretF64
+            | F32ReinterpretI32
+            | F64ReinterpretI64
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,6 +311,7 @@ mod tests {
 
     static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
     static CORRUPTED: &[u8] = include_bytes!("../testdata/corrupted.wasm");
+    static FLOATY: &[u8] = include_bytes!("../testdata/floaty.wasm");
 
     #[test]
     fn deserialize_wasm_works() {
@@ -310,4 +532,193 @@ mod tests {
         let module = deserialize_wasm(&wasm).unwrap();
         assert!(!has_ibc_entry_points(&module));
     }
+
+    #[test]
+    fn non_deterministic_constructs_works() {
+        // No constructs
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 3)
+                (func (param i32) (result i32) local.get 0)
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(non_deterministic_constructs(&module), BTreeSet::new());
+
+        // Latest contract
+        let module = deserialize_wasm(CONTRACT).unwrap();
+        assert_eq!(non_deterministic_constructs(&module), BTreeSet::new());
+
+        // Threads/atomics operations
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 3)
+                (func (param i32) (result i32) local.get 0 i32.atomic.load)
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            non_deterministic_constructs(&module),
+            BTreeSet::from([NonDeterministicConstruct::ThreadsAtomics])
+        );
+
+        // Bulk memory operations
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 3)
+                (func (param i32 i32 i32) local.get 0 local.get 1 local.get 2 memory.copy)
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            non_deterministic_constructs(&module),
+            BTreeSet::from([NonDeterministicConstruct::BulkMemory])
+        );
+
+        // Passive data segment
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 3)
+                (data "passive")
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            non_deterministic_constructs(&module),
+            BTreeSet::from([NonDeterministicConstruct::BulkMemory])
+        );
+
+        // Shared memory
+        let wasm = wat::parse_str(r#"(module (memory 3 5 shared))"#).unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            non_deterministic_constructs(&module),
+            BTreeSet::from([NonDeterministicConstruct::SharedMemory])
+        );
+
+        // Imported shared memory
+        let wasm =
+            wat::parse_str(r#"(module (import "env" "memory" (memory 3 5 shared)))"#).unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            non_deterministic_constructs(&module),
+            BTreeSet::from([NonDeterministicConstruct::SharedMemory])
+        );
+
+        // Imported global
+        let wasm = wat::parse_str(r#"(module (import "env" "seed" (global i32)))"#).unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            non_deterministic_constructs(&module),
+            BTreeSet::from([NonDeterministicConstruct::ImportedGlobal])
+        );
+
+        // Multiple categories are reported separately
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "seed" (global i32))
+                (memory 3 5 shared)
+                (func (param i32) (result i32) local.get 0 i32.atomic.load)
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            non_deterministic_constructs(&module),
+            BTreeSet::from([
+                NonDeterministicConstruct::ThreadsAtomics,
+                NonDeterministicConstruct::SharedMemory,
+                NonDeterministicConstruct::ImportedGlobal,
+            ])
+        );
+    }
+
+    #[test]
+    fn entry_points_works() {
+        let wasm = wat::parse_str(r#"(module)"#).unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(entry_points(&module), BTreeSet::new());
+
+        let module = deserialize_wasm(CONTRACT).unwrap();
+        assert_eq!(
+            entry_points(&module),
+            BTreeSet::from(
+                ["execute", "instantiate", "migrate", "query", "sudo"].map(String::from)
+            )
+        );
+
+        // Only exported functions count
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 3)
+                (export "query" (memory 0))
+
+                (type (func))
+                (func (type 0) nop)
+                (export "reply" (func 0))
+                (export "allocate" (func 0))
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(entry_points(&module), BTreeSet::from(["reply".to_string()]));
+    }
+
+    #[test]
+    fn required_imports_works() {
+        let wasm = wat::parse_str(r#"(module)"#).unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(required_imports(&module), BTreeSet::new());
+
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "db_read" (func (param i32) (result i32)))
+                (import "env" "debug" (func (param i32)))
+                (import "env" "memory" (memory 1))
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            required_imports(&module),
+            BTreeSet::from(["env.db_read", "env.debug", "env.memory"].map(String::from))
+        );
+
+        let module = deserialize_wasm(CONTRACT).unwrap();
+        let imports = required_imports(&module);
+        assert!(imports.contains("env.db_read"));
+        assert!(imports.contains("env.addr_validate"));
+        assert!(imports.iter().all(|import| import.starts_with("env.")));
+    }
+
+    #[test]
+    fn has_float_operations_works() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (param i32) (result i32) local.get 0)
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert!(!has_float_operations(&module));
+
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (param i32) (result f32) local.get 0 f32.convert_i32_u)
+            )"#,
+        )
+        .unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert!(has_float_operations(&module));
+
+        let module = deserialize_wasm(CONTRACT).unwrap();
+        assert!(!has_float_operations(&module));
+
+        let module = deserialize_wasm(FLOATY).unwrap();
+        assert!(has_float_operations(&module));
+    }
 }
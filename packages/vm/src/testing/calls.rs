@@ -4,6 +4,8 @@
 use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Serialize};
 
+#[cfg(feature = "cosmwasm_1_5")]
+use cosmwasm_std::MigrateInfo;
 use cosmwasm_std::{ContractResult, CustomMsg, Env, MessageInfo, QueryResponse, Reply, Response};
 #[cfg(feature = "stargate")]
 use cosmwasm_std::{
@@ -11,7 +13,11 @@ use cosmwasm_std::{
     IbcChannelOpenMsg, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
     IbcReceiveResponse,
 };
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+use cosmwasm_std::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
 
+#[cfg(feature = "cosmwasm_1_5")]
+use crate::calls::call_migrate_with_info;
 use crate::calls::{
     call_execute, call_instantiate, call_migrate, call_query, call_reply, call_sudo,
 };
@@ -20,7 +26,9 @@ use crate::calls::{
     call_ibc_channel_close, call_ibc_channel_connect, call_ibc_channel_open, call_ibc_packet_ack,
     call_ibc_packet_receive, call_ibc_packet_timeout,
 };
-use crate::instance::Instance;
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+use crate::calls::{call_ibc_destination_callback, call_ibc_source_callback};
+use crate::instance::{GasReport, Instance};
 use crate::serde::to_vec;
 use crate::{BackendApi, Querier, Storage};
 
@@ -83,9 +91,48 @@ where
     call_migrate(instance, &env, &serialized_msg).expect("VM error")
 }
 
-// sudo mimicks the call signature of the smart contracts.
+// migrate_with_info mimicks the call signature of the smart contracts.
 // thus it moves env and msg rather than take them as reference.
 // this is inefficient here, but only used in test code
+#[cfg(feature = "cosmwasm_1_5")]
+pub fn migrate_with_info<A, S, Q, M, U>(
+    instance: &mut Instance<A, S, Q>,
+    env: Env,
+    msg: M,
+    migrate_info: MigrateInfo,
+) -> ContractResult<Response<U>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+    M: Serialize + JsonSchema,
+    U: DeserializeOwned + CustomMsg,
+{
+    let serialized_msg = to_vec(&msg).expect("Testing error: Could not seralize request message");
+    call_migrate_with_info(instance, &env, &serialized_msg, &migrate_info).expect("VM error")
+}
+
+/// Calls the `sudo` entry point, which chain modules use to call contracts
+/// without a sender.
+///
+/// This mimicks the call signature of the smart contracts.
+/// Thus it moves env and msg rather than take them as reference.
+/// This is inefficient here, but only used in test code.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{coins, Response};
+/// # use cosmwasm_vm::testing::{instantiate, mock_env, mock_info, mock_instance, sudo};
+/// # static WASM: &[u8] = include_bytes!("../../testdata/hackatom.wasm");
+/// let mut instance = mock_instance(WASM, &[]);
+/// let msg = serde_json::json!({"verifier": "verifies", "beneficiary": "benefits"});
+/// let _: Response = instantiate(&mut instance, mock_env(), mock_info("creator", &[]), msg).unwrap();
+///
+/// let msg = serde_json::json!({"steal_funds": {"recipient": "community-pool", "amount": coins(700, "gold")}});
+/// let res: Response = sudo(&mut instance, mock_env(), msg).unwrap();
+/// assert_eq!(res.messages.len(), 1);
+/// ```
 pub fn sudo<A, S, Q, M, U>(
     instance: &mut Instance<A, S, Q>,
     env: Env,
@@ -102,6 +149,36 @@ where
     call_sudo(instance, &env, &serialized_msg).expect("VM error")
 }
 
+/// Like [`sudo`], but also returns the gas used by this call.
+///
+/// Chain modules often call `sudo` in begin or end blockers with a tight gas budget,
+/// so it can be useful to assert the gas consumption in tests.
+/// In contrast to [`Instance::create_gas_report`], the report only contains the gas used
+/// by this call and not by previous calls on the same instance.
+pub fn sudo_with_gas_report<A, S, Q, M, U>(
+    instance: &mut Instance<A, S, Q>,
+    env: Env,
+    msg: M,
+) -> (ContractResult<Response<U>>, GasReport)
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+    M: Serialize + JsonSchema,
+    U: DeserializeOwned + CustomMsg,
+{
+    let before = instance.create_gas_report();
+    let result = sudo(instance, env, msg);
+    let after = instance.create_gas_report();
+    let report = GasReport {
+        limit: after.limit,
+        remaining: after.remaining,
+        used_externally: after.used_externally - before.used_externally,
+        used_internally: after.used_internally - before.used_internally,
+    };
+    (result, report)
+}
+
 // reply mimicks the call signature of the smart contracts.
 // thus it moves env and msg rather than take them as reference.
 // this is inefficient here, but only used in test code
@@ -243,3 +320,91 @@ where
 {
     call_ibc_packet_timeout(instance, &env, &msg).expect("VM error")
 }
+
+// ibc_source_callback mimicks the call signature of the smart contracts.
+// thus it moves env and msg rather than take them as reference.
+// this is inefficient here, but only used in test code
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub fn ibc_source_callback<A, S, Q, U>(
+    instance: &mut Instance<A, S, Q>,
+    env: Env,
+    msg: IbcSourceCallbackMsg,
+) -> ContractResult<IbcBasicResponse<U>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+    U: DeserializeOwned + CustomMsg,
+{
+    call_ibc_source_callback(instance, &env, &msg).expect("VM error")
+}
+
+// ibc_destination_callback mimicks the call signature of the smart contracts.
+// thus it moves env and msg rather than take them as reference.
+// this is inefficient here, but only used in test code
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub fn ibc_destination_callback<A, S, Q, U>(
+    instance: &mut Instance<A, S, Q>,
+    env: Env,
+    msg: IbcDestinationCallbackMsg,
+) -> ContractResult<IbcBasicResponse<U>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+    U: DeserializeOwned + CustomMsg,
+{
+    call_ibc_destination_callback(instance, &env, &msg).expect("VM error")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_env, mock_info, mock_instance};
+    use cosmwasm_std::coins;
+
+    static CONTRACT: &[u8] = include_bytes!("../../testdata/hackatom.wasm");
+
+    #[test]
+    fn sudo_with_gas_report_works() {
+        let init_msg = serde_json::json!({"verifier": "verifies", "beneficiary": "benefits"});
+        let sudo_msg = serde_json::json!({
+            "steal_funds": {"recipient": "community-pool", "amount": coins(700, "gold")}
+        });
+
+        // measure the same sudo call without the helper
+        let mut plain = mock_instance(CONTRACT, &[]);
+        let _: Response = instantiate(
+            &mut plain,
+            mock_env(),
+            mock_info("creator", &[]),
+            init_msg.clone(),
+        )
+        .unwrap();
+        let before = plain.create_gas_report();
+        let _: Response = sudo(&mut plain, mock_env(), sudo_msg.clone()).unwrap();
+        let after = plain.create_gas_report();
+        let expected_internally = after.used_internally - before.used_internally;
+        let expected_externally = after.used_externally - before.used_externally;
+
+        let mut instance = mock_instance(CONTRACT, &[]);
+        let _: Response = instantiate(
+            &mut instance,
+            mock_env(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
+        let (res, report) = sudo_with_gas_report(&mut instance, mock_env(), sudo_msg);
+        let res: Response = res.unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // only the gas of the sudo call is reported, not the one of the instantiation
+        let total = instance.create_gas_report();
+        assert!(report.used_internally > 0);
+        assert!(report.used_internally < total.used_internally);
+        assert_eq!(report.used_internally, expected_internally);
+        assert_eq!(report.used_externally, expected_externally);
+        assert_eq!(report.remaining, total.remaining);
+    }
+}
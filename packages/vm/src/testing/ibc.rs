@@ -0,0 +1,300 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use cosmwasm_std::{
+    to_binary, Addr, CustomMsg, IbcAcknowledgement, IbcBasicResponse, IbcChannel,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, IbcTimeout, IbcTimeoutBlock, StdResult,
+};
+
+use super::calls::{ibc_channel_connect, ibc_channel_open};
+use super::mock::mock_env;
+use crate::instance::Instance;
+use crate::{BackendApi, Querier, Storage};
+
+/// The progress of an [`IbcHandshake`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IbcHandshakeState {
+    /// No entry point was called yet
+    New,
+    /// `ibc_channel_open` succeeded
+    Opened,
+    /// `ibc_channel_connect` succeeded, i.e. the channel is established
+    Connected,
+}
+
+/// Drives a contract through an IBC channel handshake the way a relayer would,
+/// such that integration tests do not need to assemble the handshake messages by hand.
+///
+/// The counterparty is simulated to accept whatever the contract proposes: if the contract
+/// returns a version from `ibc_channel_open`, this version is used for the rest of the handshake.
+///
+/// Once connected, [`IbcHandshake::packet_recv`], [`IbcHandshake::packet_ack`] and
+/// [`IbcHandshake::packet_timeout`] create packets on the established channel.
+#[derive(Clone, Debug)]
+pub struct IbcHandshake {
+    channel: IbcChannel,
+    initiator: bool,
+    state: IbcHandshakeState,
+    next_recv_sequence: u64,
+    next_send_sequence: u64,
+}
+
+impl IbcHandshake {
+    /// A handshake started on the contract's chain (`OpenInit`, then `OpenAck`).
+    /// `channel.version` is the version proposed to the counterparty.
+    pub fn init(channel: IbcChannel) -> Self {
+        Self::new(channel, true)
+    }
+
+    /// A handshake started on the counterparty chain (`OpenTry`, then `OpenConfirm`).
+    /// `channel.version` is the version proposed by the counterparty.
+    pub fn try_open(channel: IbcChannel) -> Self {
+        Self::new(channel, false)
+    }
+
+    fn new(channel: IbcChannel, initiator: bool) -> Self {
+        IbcHandshake {
+            channel,
+            initiator,
+            state: IbcHandshakeState::New,
+            next_recv_sequence: 1,
+            next_send_sequence: 1,
+        }
+    }
+
+    pub fn state(&self) -> IbcHandshakeState {
+        self.state
+    }
+
+    /// The channel with the version negotiated so far
+    pub fn channel(&self) -> &IbcChannel {
+        &self.channel
+    }
+
+    /// Calls `ibc_channel_open` with `OpenInit` or `OpenTry`.
+    ///
+    /// Returns the contract error if the contract rejects the channel.
+    pub fn open<A, S, Q>(&mut self, instance: &mut Instance<A, S, Q>) -> Result<(), String>
+    where
+        A: BackendApi + 'static,
+        S: Storage + 'static,
+        Q: Querier + 'static,
+    {
+        assert_eq!(
+            self.state,
+            IbcHandshakeState::New,
+            "channel was already opened"
+        );
+        let msg = if self.initiator {
+            IbcChannelOpenMsg::new_init(self.channel.clone())
+        } else {
+            IbcChannelOpenMsg::new_try(self.channel.clone(), self.channel.version.clone())
+        };
+        let res = ibc_channel_open(instance, mock_env(), msg).into_result()?;
+        if let Some(res) = res {
+            self.channel.version = res.version;
+        }
+        self.state = IbcHandshakeState::Opened;
+        Ok(())
+    }
+
+    /// Calls `ibc_channel_connect` with `OpenAck` or `OpenConfirm`.
+    ///
+    /// Returns the contract error if the contract rejects the channel.
+    pub fn connect<A, S, Q, U>(
+        &mut self,
+        instance: &mut Instance<A, S, Q>,
+    ) -> Result<IbcBasicResponse<U>, String>
+    where
+        A: BackendApi + 'static,
+        S: Storage + 'static,
+        Q: Querier + 'static,
+        U: DeserializeOwned + CustomMsg,
+    {
+        assert_eq!(
+            self.state,
+            IbcHandshakeState::Opened,
+            "channel must be opened before connecting"
+        );
+        let msg = if self.initiator {
+            IbcChannelConnectMsg::new_ack(self.channel.clone(), self.channel.version.clone())
+        } else {
+            IbcChannelConnectMsg::new_confirm(self.channel.clone())
+        };
+        let res = ibc_channel_connect(instance, mock_env(), msg).into_result()?;
+        self.state = IbcHandshakeState::Connected;
+        Ok(res)
+    }
+
+    /// Performs the full handshake, returning the response of `ibc_channel_connect`
+    pub fn run<A, S, Q, U>(
+        &mut self,
+        instance: &mut Instance<A, S, Q>,
+    ) -> Result<IbcBasicResponse<U>, String>
+    where
+        A: BackendApi + 'static,
+        S: Storage + 'static,
+        Q: Querier + 'static,
+        U: DeserializeOwned + CustomMsg,
+    {
+        self.open(instance)?;
+        self.connect(instance)
+    }
+
+    /// Creates a IbcPacketReceiveMsg for a packet sent by the counterparty to the contract.
+    /// Sequence numbers are counted up starting at 1.
+    pub fn packet_recv(&mut self, data: &impl Serialize) -> StdResult<IbcPacketReceiveMsg> {
+        let packet = IbcPacket::new(
+            to_binary(data)?,
+            self.channel.counterparty_endpoint.clone(),
+            self.channel.endpoint.clone(),
+            self.next_recv_sequence,
+            mock_timeout(),
+        );
+        self.next_recv_sequence += 1;
+        Ok(IbcPacketReceiveMsg::new(packet, Addr::unchecked("relayer")))
+    }
+
+    /// Creates a IbcPacketAckMsg for the next packet sent by the contract.
+    /// Sequence numbers are counted up starting at 1, shared with [`IbcHandshake::packet_timeout`].
+    pub fn packet_ack(
+        &mut self,
+        data: &impl Serialize,
+        ack: IbcAcknowledgement,
+    ) -> StdResult<IbcPacketAckMsg> {
+        let packet = self.sent_packet(data)?;
+        Ok(IbcPacketAckMsg::new(
+            ack,
+            packet,
+            Addr::unchecked("relayer"),
+        ))
+    }
+
+    /// Creates a IbcPacketTimeoutMsg for the next packet sent by the contract.
+    /// Sequence numbers are counted up starting at 1, shared with [`IbcHandshake::packet_ack`].
+    pub fn packet_timeout(&mut self, data: &impl Serialize) -> StdResult<IbcPacketTimeoutMsg> {
+        let packet = self.sent_packet(data)?;
+        Ok(IbcPacketTimeoutMsg::new(packet, Addr::unchecked("relayer")))
+    }
+
+    fn sent_packet(&mut self, data: &impl Serialize) -> StdResult<IbcPacket> {
+        let packet = IbcPacket::new(
+            to_binary(data)?,
+            self.channel.endpoint.clone(),
+            self.channel.counterparty_endpoint.clone(),
+            self.next_send_sequence,
+            mock_timeout(),
+        );
+        self.next_send_sequence += 1;
+        Ok(packet)
+    }
+}
+
+fn mock_timeout() -> IbcTimeout {
+    IbcTimeoutBlock {
+        revision: 1,
+        height: 12345678,
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calls::call_instantiate;
+    use crate::testing::{
+        ibc_packet_receive, mock_ibc_channel, mock_info, mock_instance, MockApi, MockQuerier,
+        MockStorage,
+    };
+    use cosmwasm_std::{Empty, IbcOrder};
+
+    static CONTRACT: &[u8] = include_bytes!("../../testdata/ibc_reflect.wasm");
+    const IBC_VERSION: &str = "ibc-reflect-v1";
+
+    fn setup() -> Instance<MockApi, MockStorage, MockQuerier> {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        let info = mock_info("creator", &[]);
+        let msg = br#"{"reflect_code_id":77}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        instance
+    }
+
+    #[test]
+    fn handshake_works() {
+        let handshakes: [fn(IbcChannel) -> IbcHandshake; 2] =
+            [IbcHandshake::init, IbcHandshake::try_open];
+        for new_handshake in handshakes {
+            let mut instance = setup();
+            let channel = mock_ibc_channel("channel-0", IbcOrder::Ordered, IBC_VERSION);
+            let mut handshake = new_handshake(channel);
+            assert_eq!(handshake.state(), IbcHandshakeState::New);
+
+            handshake.open(&mut instance).unwrap();
+            assert_eq!(handshake.state(), IbcHandshakeState::Opened);
+            assert_eq!(handshake.channel().version, IBC_VERSION);
+
+            let res: IbcBasicResponse = handshake.connect(&mut instance).unwrap();
+            assert_eq!(handshake.state(), IbcHandshakeState::Connected);
+            // ibc_reflect instantiates a reflect contract for every channel
+            assert_eq!(res.messages.len(), 1);
+        }
+    }
+
+    #[test]
+    fn handshake_returns_contract_errors() {
+        let mut instance = setup();
+        let channel = mock_ibc_channel("channel-0", IbcOrder::Unordered, IBC_VERSION);
+        let mut handshake = IbcHandshake::init(channel);
+        let err = handshake.run::<_, _, _, Empty>(&mut instance).unwrap_err();
+        assert!(err.contains("Only supports ordered channels"), "{}", err);
+        assert_eq!(handshake.state(), IbcHandshakeState::New);
+    }
+
+    #[test]
+    #[should_panic(expected = "channel must be opened before connecting")]
+    fn connect_panics_for_unopened_channel() {
+        let mut instance = setup();
+        let channel = mock_ibc_channel("channel-0", IbcOrder::Ordered, IBC_VERSION);
+        let _ = IbcHandshake::init(channel).connect::<_, _, _, Empty>(&mut instance);
+    }
+
+    #[test]
+    fn packet_builders_work() {
+        let channel = mock_ibc_channel("channel-0", IbcOrder::Ordered, IBC_VERSION);
+        let mut handshake = IbcHandshake::init(channel.clone());
+
+        let msg = handshake.packet_recv(&"foo").unwrap();
+        assert_eq!(msg.packet.src, channel.counterparty_endpoint);
+        assert_eq!(msg.packet.dest, channel.endpoint);
+        assert_eq!(msg.packet.sequence, 1);
+        assert_eq!(handshake.packet_recv(&"foo").unwrap().packet.sequence, 2);
+
+        let msg = handshake
+            .packet_ack(&"bar", IbcAcknowledgement::new(b"ok"))
+            .unwrap();
+        assert_eq!(msg.original_packet.src, channel.endpoint);
+        assert_eq!(msg.original_packet.dest, channel.counterparty_endpoint);
+        assert_eq!(msg.original_packet.sequence, 1);
+        let msg = handshake.packet_timeout(&"bar").unwrap();
+        assert_eq!(msg.packet.sequence, 2);
+    }
+
+    #[test]
+    fn packets_can_be_sent_to_connected_channel() {
+        let mut instance = setup();
+        let channel = mock_ibc_channel("channel-0", IbcOrder::Ordered, IBC_VERSION);
+        let mut handshake = IbcHandshake::init(channel);
+        handshake.run::<_, _, _, Empty>(&mut instance).unwrap();
+
+        // no account was registered for the channel (we skipped the reply), so the contract
+        // responds with an error ack
+        let msg = handshake
+            .packet_recv(&serde_json::json!({"who_am_i":{}}))
+            .unwrap();
+        let res = ibc_packet_receive::<_, _, _, Empty>(&mut instance, mock_env(), msg).unwrap();
+        let ack = String::from_utf8(res.acknowledgement.to_vec()).unwrap();
+        assert!(ack.contains("error"), "{}", ack);
+    }
+}
@@ -3,10 +3,12 @@
 //! use cosmwasm_vm::testing::X
 use cosmwasm_std::Coin;
 use std::collections::HashSet;
+use std::time::Duration;
 
 use crate::capabilities::capabilities_from_csv;
 use crate::compatibility::check_wasm;
-use crate::instance::{Instance, InstanceOptions};
+use crate::environment::{GasSchedule, DEFAULT_MAX_QUERY_DEPTH};
+use crate::instance::{Instance, InstanceOptions, SharedDebugHandler};
 use crate::size::Size;
 use crate::{Backend, BackendApi, Querier, Storage};
 
@@ -91,6 +93,14 @@ pub struct MockInstanceOptions<'a> {
     /// Gas limit measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
     pub gas_limit: u64,
     pub print_debug: bool,
+    /// See [`InstanceOptions::debug_handler`]
+    pub debug_handler: Option<SharedDebugHandler>,
+    /// See [`InstanceOptions::readonly_ibc_channel_open`]
+    pub readonly_ibc_channel_open: bool,
+    /// See [`InstanceOptions::max_query_depth`]
+    pub max_query_depth: usize,
+    /// See [`InstanceOptions::max_execution_time`]
+    pub max_execution_time: Option<Duration>,
     /// Memory limit in bytes. Use a value that is divisible by the Wasm page size 65536, e.g. full MiBs.
     pub memory_limit: Option<Size>,
 }
@@ -98,9 +108,15 @@ pub struct MockInstanceOptions<'a> {
 impl MockInstanceOptions<'_> {
     fn default_capabilities() -> HashSet<String> {
         #[allow(unused_mut)]
-        let mut out = capabilities_from_csv("iterator,staking,cosmwasm_1_1,cosmwasm_1_2");
+        let mut out = capabilities_from_csv(
+            "iterator,staking,feegrant,authz,zk,sr25519,cosmwasm_1_1,cosmwasm_1_2,cosmwasm_1_3",
+        );
         #[cfg(feature = "stargate")]
         out.insert("stargate".to_string());
+        #[cfg(feature = "cosmwasm_1_4")]
+        out.insert("cosmwasm_1_4".to_string());
+        #[cfg(feature = "cosmwasm_1_5")]
+        out.insert("cosmwasm_1_5".to_string());
         out
     }
 }
@@ -117,6 +133,10 @@ impl Default for MockInstanceOptions<'_> {
             available_capabilities: Self::default_capabilities(),
             gas_limit: DEFAULT_GAS_LIMIT,
             print_debug: DEFAULT_PRINT_DEBUG,
+            debug_handler: None,
+            readonly_ibc_channel_open: false,
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            max_execution_time: None,
             memory_limit: DEFAULT_MEMORY_LIMIT,
         }
     }
@@ -154,6 +174,11 @@ pub fn mock_instance_with_options(
     let options = InstanceOptions {
         gas_limit: options.gas_limit,
         print_debug: options.print_debug,
+        debug_handler: options.debug_handler,
+        readonly_ibc_channel_open: options.readonly_ibc_channel_open,
+        gas_schedule: GasSchedule::default(),
+        max_query_depth: options.max_query_depth,
+        max_execution_time: options.max_execution_time,
     };
     Instance::from_code(wasm, backend, options, memory_limit).unwrap()
 }
@@ -164,6 +189,11 @@ pub fn mock_instance_options() -> (InstanceOptions, Option<Size>) {
         InstanceOptions {
             gas_limit: DEFAULT_GAS_LIMIT,
             print_debug: DEFAULT_PRINT_DEBUG,
+            debug_handler: None,
+            readonly_ibc_channel_open: false,
+            gas_schedule: GasSchedule::default(),
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            max_execution_time: None,
         },
         DEFAULT_MEMORY_LIMIT,
     )
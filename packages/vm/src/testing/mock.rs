@@ -1,3 +1,4 @@
+use bech32::{FromBase32, ToBase32, Variant};
 use cosmwasm_std::testing::{digit_sum, riffle_shuffle};
 use cosmwasm_std::{
     Addr, BlockInfo, Coin, ContractInfo, Env, MessageInfo, Timestamp, TransactionInfo,
@@ -53,6 +54,8 @@ pub struct MockApi {
     canonical_length: usize,
     /// When set, all calls to the API fail with BackendError::Unknown containing this message
     backend_error: Option<&'static str>,
+    /// When set, addresses are bech32 encoded with this prefix like on real chains
+    bech32_prefix: Option<&'static str>,
 }
 
 impl MockApi {
@@ -67,6 +70,18 @@ impl MockApi {
             ..MockApi::default()
         }
     }
+
+    /// Creates a MockApi that converts between bech32 addresses with the given prefix
+    /// (e.g. "osmo") and their raw data, like real chains do.
+    ///
+    /// Human addresses must be valid bech32 strings with this prefix. Canonical addresses
+    /// must be between 1 and 255 bytes long, which is the limit of the Cosmos SDK.
+    pub fn with_prefix(prefix: &'static str) -> Self {
+        MockApi {
+            bech32_prefix: Some(prefix),
+            ..MockApi::default()
+        }
+    }
 }
 
 impl Default for MockApi {
@@ -74,6 +89,7 @@ impl Default for MockApi {
         MockApi {
             canonical_length: CANONICAL_LENGTH,
             backend_error: None,
+            bech32_prefix: None,
         }
     }
 }
@@ -89,6 +105,10 @@ impl BackendApi for MockApi {
             return (Err(BackendError::unknown(backend_error)), gas_info);
         }
 
+        if let Some(prefix) = self.bech32_prefix {
+            return (bech32_canonicalize(prefix, input), gas_info);
+        }
+
         // Dummy input validation. This is more sophisticated for formats like bech32, where format and checksum are validated.
         let min_length = 3;
         let max_length = self.canonical_length;
@@ -129,6 +149,10 @@ impl BackendApi for MockApi {
             return (Err(BackendError::unknown(backend_error)), gas_info);
         }
 
+        if let Some(prefix) = self.bech32_prefix {
+            return (bech32_humanize(prefix, canonical), gas_info);
+        }
+
         if canonical.len() != self.canonical_length {
             return (
                 Err(BackendError::user_err(
@@ -157,6 +181,44 @@ impl BackendApi for MockApi {
     }
 }
 
+/// The maximum length of an address in the Cosmos SDK
+const MAX_BECH32_ADDRESS_DATA_LENGTH: usize = 255;
+
+fn bech32_canonicalize(prefix: &str, input: &str) -> Result<Vec<u8>, BackendError> {
+    let (decoded_prefix, data, variant) = bech32::decode(input)
+        .map_err(|e| BackendError::user_err(format!("Invalid input: {}", e)))?;
+    if decoded_prefix != prefix {
+        return Err(BackendError::user_err(format!(
+            "Invalid input: wrong address prefix, expected {}",
+            prefix
+        )));
+    }
+    if variant != Variant::Bech32 {
+        return Err(BackendError::user_err(
+            "Invalid input: expected bech32 but got bech32m",
+        ));
+    }
+    let data = Vec::<u8>::from_base32(&data)
+        .map_err(|e| BackendError::user_err(format!("Invalid input: {}", e)))?;
+    validate_bech32_data_length(&data)?;
+    Ok(data)
+}
+
+fn bech32_humanize(prefix: &str, canonical: &[u8]) -> Result<String, BackendError> {
+    validate_bech32_data_length(canonical)?;
+    bech32::encode(prefix, canonical.to_base32(), Variant::Bech32)
+        .map_err(|e| BackendError::user_err(format!("Invalid input: {}", e)))
+}
+
+fn validate_bech32_data_length(data: &[u8]) -> Result<(), BackendError> {
+    if data.is_empty() || data.len() > MAX_BECH32_ADDRESS_DATA_LENGTH {
+        return Err(BackendError::user_err(
+            "Invalid input: canonical address length not correct",
+        ));
+    }
+    Ok(())
+}
+
 /// Returns a default enviroment with height, time, chain_id, and contract address
 /// You can submit as is to most contracts, or modify height/time if you want to
 /// test for expiration.
@@ -272,4 +334,61 @@ mod tests {
             err => panic!("Unexpected error: {:?}", err),
         }
     }
+
+    #[test]
+    fn with_prefix_canonical_and_human_address_round_trip() {
+        let api = MockApi::with_prefix("osmo");
+
+        for data in [vec![0xAA; 20], vec![0x11; 32]] {
+            let (result, gas_info) = api.human_address(&data);
+            assert_eq!(gas_info.cost, GAS_COST_HUMANIZE);
+            let human = result.unwrap();
+            assert!(human.starts_with("osmo1"));
+            let (result, gas_info) = api.canonical_address(&human);
+            assert_eq!(gas_info.cost, GAS_COST_CANONICALIZE);
+            assert_eq!(result.unwrap(), data);
+        }
+
+        // Test vector from BIP-173 with custom prefix
+        let api = MockApi::with_prefix("abcdef");
+        let canonical = api
+            .canonical_address("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw")
+            .0
+            .unwrap();
+        assert_eq!(
+            canonical,
+            hex::decode("00443214c74254b635cf84653a56d7c675be77df").unwrap()
+        );
+    }
+
+    #[test]
+    fn with_prefix_canonical_address_fails_for_invalid_input() {
+        let api = MockApi::with_prefix("osmo");
+
+        let juno = MockApi::with_prefix("juno")
+            .human_address(&[0xAA; 20])
+            .0
+            .unwrap();
+        match api.canonical_address(&juno).0.unwrap_err() {
+            BackendError::UserErr { msg } => assert!(msg.contains("wrong address prefix")),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        match api.canonical_address("foobar123").0.unwrap_err() {
+            BackendError::UserErr { .. } => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn with_prefix_validates_canonical_length() {
+        let api = MockApi::with_prefix("osmo");
+        for data in [vec![], vec![0x11; 256]] {
+            match api.human_address(&data).0.unwrap_err() {
+                BackendError::UserErr { msg } => assert!(msg.contains("length not correct")),
+                err => panic!("Unexpected error: {:?}", err),
+            }
+        }
+        api.human_address(&[0x11; 255]).0.unwrap();
+    }
 }
@@ -1,17 +1,34 @@
 // The external interface is `use cosmwasm_vm::testing::X` for all integration testing symbols, no matter where they live internally.
 
 mod calls;
+#[cfg(feature = "stargate")]
+mod ibc;
 mod instance;
 mod mock;
 mod querier;
 mod storage;
+mod submessages;
 
-pub use calls::{execute, instantiate, migrate, query, reply, sudo};
+#[cfg(feature = "cosmwasm_1_5")]
+pub use calls::migrate_with_info;
+pub use calls::{execute, instantiate, migrate, query, reply, sudo, sudo_with_gas_report};
 #[cfg(feature = "stargate")]
 pub use calls::{
     ibc_channel_close, ibc_channel_connect, ibc_channel_open, ibc_packet_ack, ibc_packet_receive,
     ibc_packet_timeout,
 };
+#[cfg(all(feature = "stargate", feature = "cosmwasm_1_4"))]
+pub use calls::{ibc_destination_callback, ibc_source_callback};
+// mock_env in this crate and in cosmwasm-std return the same values, so the builder can be shared
+pub use cosmwasm_std::testing::EnvBuilder;
+#[cfg(feature = "stargate")]
+pub use cosmwasm_std::testing::{
+    mock_ibc_channel, mock_ibc_channel_close_confirm, mock_ibc_channel_close_init,
+    mock_ibc_channel_connect_ack, mock_ibc_channel_connect_confirm, mock_ibc_channel_open_init,
+    mock_ibc_channel_open_try, mock_ibc_packet_ack, mock_ibc_packet_recv, mock_ibc_packet_timeout,
+};
+#[cfg(feature = "stargate")]
+pub use ibc::{IbcHandshake, IbcHandshakeState};
 pub use instance::{
     mock_instance, mock_instance_options, mock_instance_with_balances,
     mock_instance_with_failing_api, mock_instance_with_gas_limit, mock_instance_with_options,
@@ -21,4 +38,5 @@ pub use mock::{
     mock_backend, mock_backend_with_balances, mock_env, mock_info, MockApi, MOCK_CONTRACT_ADDR,
 };
 pub use querier::MockQuerier;
-pub use storage::MockStorage;
+pub use storage::{MockStorage, MockStorageSnapshot};
+pub use submessages::{DispatchedMessages, SubMsgDispatcher};
@@ -45,6 +45,53 @@ impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
         self.querier.update_staking(denom, validators, delegations);
     }
 
+    /// Adds a validator or replaces the one with the same address
+    #[cfg(feature = "staking")]
+    pub fn update_validator(&mut self, validator: cosmwasm_std::Validator) {
+        self.querier.update_validator(validator);
+    }
+
+    /// Adds a delegation or replaces the one of the same delegator to the same validator.
+    /// Rewards set via [`MockQuerier::update_delegation_rewards`] are used as its `accumulated_rewards`.
+    #[cfg(feature = "staking")]
+    pub fn update_delegation(&mut self, delegation: cosmwasm_std::FullDelegation) {
+        self.querier.update_delegation(delegation);
+    }
+
+    /// Removes the delegation of `delegator` to `validator` and returns it
+    #[cfg(feature = "staking")]
+    pub fn remove_delegation(
+        &mut self,
+        delegator: &str,
+        validator: &str,
+    ) -> Option<cosmwasm_std::FullDelegation> {
+        self.querier.remove_delegation(delegator, validator)
+    }
+
+    /// Sets the pending unbondings of the delegation of `delegator` to `validator`
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    pub fn update_unbonding_delegations(
+        &mut self,
+        delegator: impl Into<String>,
+        validator: impl Into<String>,
+        entries: &[cosmwasm_std::UnbondingDelegationEntry],
+    ) {
+        self.querier
+            .update_unbonding_delegations(delegator, validator, entries);
+    }
+
+    /// Sets the rewards accrued by the delegation of `delegator` to `validator`
+    #[cfg(feature = "cosmwasm_1_4")]
+    pub fn update_delegation_rewards(
+        &mut self,
+        delegator: impl Into<String>,
+        validator: impl Into<String>,
+        rewards: Vec<cosmwasm_std::DecCoin>,
+    ) {
+        self.querier
+            .update_delegation_rewards(delegator, validator, rewards);
+    }
+
     pub fn update_wasm<WH: 'static>(&mut self, handler: WH)
     where
         WH: Fn(&cosmwasm_std::WasmQuery) -> cosmwasm_std::QuerierResult,
@@ -59,6 +106,17 @@ impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
         self.querier = self.querier.with_custom_handler(handler);
         self
     }
+
+    /// Registers a handler for the Stargate and gRPC queries with the given path.
+    /// See [`cosmwasm_std::testing::MockQuerier::with_grpc_handler`].
+    #[cfg(any(feature = "stargate", feature = "cosmwasm_1_4"))]
+    pub fn with_grpc_handler<GH>(mut self, path: impl Into<String>, handler: GH) -> Self
+    where
+        GH: Fn(&Binary) -> MockQuerierCustomHandlerResult + 'static,
+    {
+        self.querier = self.querier.with_grpc_handler(path, handler);
+        self
+    }
 }
 
 impl<C: CustomQuery + DeserializeOwned> Querier for MockQuerier<C> {
@@ -131,6 +189,108 @@ mod tests {
         }
     }
 
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    #[test]
+    fn staking_querier_unbonding_delegations() {
+        use cosmwasm_std::{
+            StakingQuery, Timestamp, UnbondingDelegationEntry, UnbondingDelegationsResponse,
+        };
+
+        let entry = UnbondingDelegationEntry {
+            creation_height: 100,
+            completion_time: Timestamp::from_seconds(2000),
+            initial_balance: coin(100, "ustake"),
+            balance: coin(100, "ustake"),
+        };
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.update_unbonding_delegations("investor", "validator", std::slice::from_ref(&entry));
+
+        let raw = querier
+            .query::<Empty>(
+                &StakingQuery::UnbondingDelegations {
+                    delegator: "investor".to_string(),
+                    validator: "validator".to_string(),
+                }
+                .into(),
+                DEFAULT_QUERY_GAS_LIMIT,
+            )
+            .0
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let res: UnbondingDelegationsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.entries, vec![entry]);
+    }
+
+    #[cfg(all(feature = "staking", feature = "cosmwasm_1_4"))]
+    #[test]
+    fn staking_querier_delegation_with_rewards() {
+        use cosmwasm_std::{
+            Addr, DecCoin, Decimal256, DelegationResponse, FullDelegation, StakingQuery,
+        };
+
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.update_delegation(FullDelegation {
+            delegator: Addr::unchecked("investor"),
+            validator: "validator".to_string(),
+            amount: coin(100, "ustake"),
+            can_redelegate: coin(100, "ustake"),
+            accumulated_rewards: vec![],
+        });
+        querier.update_delegation_rewards(
+            "investor",
+            "validator",
+            vec![DecCoin::new(Decimal256::percent(350), "ustake")],
+        );
+
+        let raw = querier
+            .query::<Empty>(
+                &StakingQuery::Delegation {
+                    delegator: "investor".to_string(),
+                    validator: "validator".to_string(),
+                }
+                .into(),
+                DEFAULT_QUERY_GAS_LIMIT,
+            )
+            .0
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let res: DelegationResponse = from_binary(&raw).unwrap();
+        assert_eq!(
+            res.delegation.unwrap().accumulated_rewards,
+            vec![coin(3, "ustake")]
+        );
+
+        assert!(querier.remove_delegation("investor", "validator").is_some());
+        assert_eq!(querier.remove_delegation("investor", "validator"), None);
+    }
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn grpc_handler_works() {
+        use cosmwasm_std::GrpcQuery;
+
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[])
+            .with_grpc_handler("/cosmos.bank.v1beta1.Query/DenomOwners", |data: &Binary| {
+                SystemResult::Ok(ContractResult::Ok(data.clone()))
+            });
+
+        let res = querier
+            .query::<Empty>(
+                &GrpcQuery {
+                    path: "/cosmos.bank.v1beta1.Query/DenomOwners".to_string(),
+                    data: Binary::from(b"\x0a\x03foo"),
+                }
+                .into(),
+                DEFAULT_QUERY_GAS_LIMIT,
+            )
+            .0
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.as_slice(), b"\x0a\x03foo");
+    }
+
     #[test]
     fn bank_querier_all_balances() {
         let addr = String::from("foobar");
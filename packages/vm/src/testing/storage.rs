@@ -6,6 +6,7 @@ use std::ops::{Bound, RangeBounds};
 
 #[cfg(feature = "iterator")]
 use cosmwasm_std::{Order, Record};
+use cosmwasm_std::{SnapshotJournal, StorageSnapshot};
 
 #[cfg(feature = "iterator")]
 use crate::BackendError;
@@ -29,13 +30,46 @@ pub struct MockStorage {
     data: BTreeMap<Vec<u8>, Vec<u8>>,
     #[cfg(feature = "iterator")]
     iterators: HashMap<u32, Iter>,
+    journal: SnapshotJournal,
 }
 
+/// A handle to the state of a [`MockStorage`] at the time [`MockStorage::snapshot`] was called
+pub type MockStorageSnapshot = StorageSnapshot;
+
 impl MockStorage {
     pub fn new() -> Self {
         MockStorage::default()
     }
 
+    /// Takes a snapshot of the current state, which can be restored later using [`MockStorage::restore`].
+    ///
+    /// This does not copy the storage. Instead, the previous values of all keys written after the
+    /// snapshot are recorded, so restoring a snapshot is cheap when few keys were written.
+    pub fn snapshot(&mut self) -> MockStorageSnapshot {
+        self.journal.snapshot()
+    }
+
+    /// Restores the state at the time the snapshot was taken. Open iterators are not affected.
+    ///
+    /// The snapshot stays valid and can be restored again, e.g. after every contract call of
+    /// a test harness. All snapshots taken after this one are discarded.
+    ///
+    /// Panics if the snapshot was discarded or was taken from another storage.
+    pub fn restore(&mut self, snapshot: MockStorageSnapshot) {
+        self.journal.restore(snapshot, &mut self.data);
+    }
+
+    /// Returns the keys whose values changed since the snapshot was taken, along with their
+    /// current value. The value is `None` if the key was removed.
+    ///
+    /// Panics if the snapshot was discarded or was taken from another storage.
+    pub fn changes_since(
+        &self,
+        snapshot: MockStorageSnapshot,
+    ) -> BTreeMap<Vec<u8>, Option<Vec<u8>>> {
+        self.journal.changes_since(snapshot, &self.data)
+    }
+
     #[cfg(feature = "iterator")]
     pub fn all(&mut self, iterator_id: u32) -> BackendResult<Vec<Record>> {
         let mut out: Vec<Record> = Vec::new();
@@ -124,17 +158,117 @@ impl Storage for MockStorage {
         (Ok(value), gas_info)
     }
 
+    #[cfg(feature = "iterator")]
+    fn scan_bounded(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+        limit: usize,
+        _gas_limit: u64,
+    ) -> BackendResult<Vec<Record>> {
+        let bounds = range_bounds(start, end);
+
+        let records: Vec<Record> = match (bounds.start_bound(), bounds.end_bound()) {
+            // See scan. Start > end describes an empty range.
+            (Bound::Included(start), Bound::Excluded(end)) if start > end => Vec::new(),
+            _ => match order {
+                Order::Ascending => self
+                    .data
+                    .range(bounds)
+                    .take(limit)
+                    .map(clone_item)
+                    .collect(),
+                Order::Descending => self
+                    .data
+                    .range(bounds)
+                    .rev()
+                    .take(limit)
+                    .map(clone_item)
+                    .collect(),
+            },
+        };
+
+        let mut gas_info = GasInfo::with_externally_used(GAS_COST_RANGE);
+        for (key, value) in &records {
+            gas_info += GasInfo::with_cost((key.len() + value.len()) as u64);
+        }
+        (Ok(records), gas_info)
+    }
+
     fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
-        self.data.insert(key.to_vec(), value.to_vec());
+        let previous = self.data.insert(key.to_vec(), value.to_vec());
+        self.journal.record(key, previous);
         let gas_info = GasInfo::with_externally_used((key.len() + value.len()) as u64);
         (Ok(()), gas_info)
     }
 
     fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
-        self.data.remove(key);
+        if let Some(previous) = self.data.remove(key) {
+            self.journal.record(key, Some(previous));
+        }
         let gas_info = GasInfo::with_externally_used(key.len() as u64);
         (Ok(()), gas_info)
     }
+
+    #[cfg(feature = "iterator")]
+    fn remove_range(
+        &mut self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        limit: usize,
+    ) -> BackendResult<usize> {
+        let bounds = range_bounds(start, end);
+
+        let keys: Vec<Vec<u8>> = match (bounds.start_bound(), bounds.end_bound()) {
+            // See scan. Start > end describes an empty range.
+            (Bound::Included(start), Bound::Excluded(end)) if start > end => Vec::new(),
+            _ => self
+                .data
+                .range(bounds)
+                .take(limit)
+                .map(|(key, _)| key.clone())
+                .collect(),
+        };
+
+        let mut gas_info = GasInfo::with_externally_used(GAS_COST_RANGE);
+        for key in &keys {
+            if let Some(previous) = self.data.remove(key) {
+                self.journal.record(key, Some(previous));
+            }
+            gas_info += GasInfo::with_externally_used(key.len() as u64);
+        }
+        (Ok(keys.len()), gas_info)
+    }
+}
+
+impl crate::ReadonlyStorage for MockStorage {
+    fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+        Storage::get(self, key)
+    }
+
+    #[cfg(feature = "iterator")]
+    fn first(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> BackendResult<Option<Record>> {
+        let bounds = range_bounds(start, end);
+        let item = match (bounds.start_bound(), bounds.end_bound()) {
+            // See scan. Start > end describes an empty range.
+            (Bound::Included(start), Bound::Excluded(end)) if start > end => None,
+            _ => match order {
+                Order::Ascending => self.data.range(bounds).next().map(clone_item),
+                Order::Descending => self.data.range(bounds).next_back().map(clone_item),
+            },
+        };
+        let gas_info = match &item {
+            Some((key, value)) => GasInfo::with_cost((key.len() + value.len()) as u64),
+            None => GasInfo::with_externally_used(GAS_COST_LAST_ITERATION),
+        };
+        (Ok(item), gas_info)
+    }
 }
 
 #[cfg(feature = "iterator")]
@@ -339,4 +473,222 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut store = MockStorage::new();
+        store.set(b"foo", b"bar").0.unwrap();
+        store.set(b"food", b"bank").0.unwrap();
+
+        let snapshot = store.snapshot();
+        store.set(b"foo", b"baz").0.unwrap();
+        store.remove(b"food").0.unwrap();
+        store.remove(b"nope").0.unwrap();
+        store.set(b"ze", b"bra").0.unwrap();
+        assert_eq!(
+            store.changes_since(snapshot),
+            BTreeMap::from([
+                (b"foo".to_vec(), Some(b"baz".to_vec())),
+                (b"food".to_vec(), None),
+                (b"ze".to_vec(), Some(b"bra".to_vec())),
+            ])
+        );
+
+        store.restore(snapshot);
+        assert_eq!(store.get(b"foo").0.unwrap(), Some(b"bar".to_vec()));
+        assert_eq!(store.get(b"food").0.unwrap(), Some(b"bank".to_vec()));
+        assert_eq!(store.get(b"ze").0.unwrap(), None);
+        assert_eq!(store.changes_since(snapshot), BTreeMap::new());
+
+        // the snapshot can be restored again, later snapshots are discarded
+        store.set(b"foo", b"other").0.unwrap();
+        let later = store.snapshot();
+        store.set(b"foo", b"last").0.unwrap();
+        store.restore(later);
+        assert_eq!(store.get(b"foo").0.unwrap(), Some(b"other".to_vec()));
+        store.restore(snapshot);
+        assert_eq!(store.get(b"foo").0.unwrap(), Some(b"bar".to_vec()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot does not exist")]
+    fn restore_panics_for_discarded_snapshot() {
+        let mut store = MockStorage::new();
+        let first = store.snapshot();
+        let second = store.snapshot();
+        store.restore(first);
+        store.restore(second);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn snapshot_restores_remove_range() {
+        let mut store = MockStorage::new();
+        store.set(b"ant", b"hill").0.unwrap();
+        store.set(b"foo", b"bar").0.unwrap();
+        let snapshot = store.snapshot();
+
+        assert_eq!(store.remove_range(None, None, 10).0.unwrap(), 2);
+        store.restore(snapshot);
+        assert_eq!(store.get(b"ant").0.unwrap(), Some(b"hill".to_vec()));
+        assert_eq!(store.get(b"foo").0.unwrap(), Some(b"bar".to_vec()));
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn remove_range_works() {
+        let mut store = MockStorage::new();
+        store.set(b"ant", b"hill").0.unwrap();
+        store.set(b"foo", b"bar").0.unwrap();
+        store.set(b"food", b"bank").0.unwrap();
+        store.set(b"ze", b"bra").0.unwrap();
+
+        // limited
+        let (result, gas_info) = store.remove_range(Some(b"f"), None, 1);
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(gas_info.externally_used, GAS_COST_RANGE + 3);
+        assert_eq!(store.get(b"foo").0.unwrap(), None);
+        assert_eq!(store.get(b"food").0.unwrap(), Some(b"bank".to_vec()));
+
+        // empty range (start > end)
+        let removed = store.remove_range(Some(b"z"), Some(b"a"), 10).0.unwrap();
+        assert_eq!(removed, 0);
+
+        // end is exclusive
+        let removed = store.remove_range(None, Some(b"ze"), 10).0.unwrap();
+        assert_eq!(removed, 2);
+        let iter_id = store.scan(None, None, Order::Ascending).0.unwrap();
+        let elements = store.all(iter_id).0.unwrap();
+        assert_eq!(elements, vec![(b"ze".to_vec(), b"bra".to_vec())]);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn scan_bounded_works() {
+        let mut store = MockStorage::new();
+        store.set(b"ant", b"hill").0.unwrap();
+        store.set(b"foo", b"bar").0.unwrap();
+        store.set(b"food", b"bank").0.unwrap();
+        store.set(b"ze", b"bra").0.unwrap();
+
+        // limited
+        let (result, gas_info) =
+            store.scan_bounded(Some(b"f"), None, Order::Ascending, 2, u64::MAX);
+        assert_eq!(
+            result.unwrap(),
+            vec![
+                (b"foo".to_vec(), b"bar".to_vec()),
+                (b"food".to_vec(), b"bank".to_vec())
+            ]
+        );
+        assert_eq!(gas_info.cost, 6 + 8);
+        assert_eq!(gas_info.externally_used, GAS_COST_RANGE);
+
+        // descending, end is exclusive
+        let records = store
+            .scan_bounded(None, Some(b"ze"), Order::Descending, 10, u64::MAX)
+            .0
+            .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                (b"food".to_vec(), b"bank".to_vec()),
+                (b"foo".to_vec(), b"bar".to_vec()),
+                (b"ant".to_vec(), b"hill".to_vec())
+            ]
+        );
+
+        // empty range (start > end)
+        let records = store
+            .scan_bounded(Some(b"z"), Some(b"a"), Order::Ascending, 10, u64::MAX)
+            .0
+            .unwrap();
+        assert_eq!(records, vec![]);
+
+        // zero limit
+        let records = store
+            .scan_bounded(None, None, Order::Ascending, 0, u64::MAX)
+            .0
+            .unwrap();
+        assert_eq!(records, vec![]);
+    }
+
+    /// A storage that uses the default implementation of remove_range and scan_bounded
+    #[cfg(feature = "iterator")]
+    struct DefaultImplementationStorage(MockStorage);
+
+    #[cfg(feature = "iterator")]
+    impl Storage for DefaultImplementationStorage {
+        fn get(&self, key: &[u8]) -> BackendResult<Option<Vec<u8>>> {
+            self.0.get(key)
+        }
+
+        fn scan(
+            &mut self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> BackendResult<u32> {
+            self.0.scan(start, end, order)
+        }
+
+        fn next(&mut self, iterator_id: u32) -> BackendResult<Option<Record>> {
+            self.0.next(iterator_id)
+        }
+
+        fn set(&mut self, key: &[u8], value: &[u8]) -> BackendResult<()> {
+            self.0.set(key, value)
+        }
+
+        fn remove(&mut self, key: &[u8]) -> BackendResult<()> {
+            self.0.remove(key)
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn remove_range_default_implementation_works() {
+        let mut store = DefaultImplementationStorage(MockStorage::new());
+        store.set(b"ant", b"hill").0.unwrap();
+        store.set(b"foo", b"bar").0.unwrap();
+        store.set(b"food", b"bank").0.unwrap();
+        store.set(b"ze", b"bra").0.unwrap();
+
+        let removed = store.remove_range(Some(b"f"), None, 2).0.unwrap();
+        assert_eq!(removed, 2);
+        let removed = store.remove_range(Some(b"f"), None, 2).0.unwrap();
+        assert_eq!(removed, 1);
+        let removed = store.remove_range(Some(b"f"), None, 2).0.unwrap();
+        assert_eq!(removed, 0);
+
+        let iter_id = store.scan(None, None, Order::Ascending).0.unwrap();
+        let elements = store.0.all(iter_id).0.unwrap();
+        assert_eq!(elements, vec![(b"ant".to_vec(), b"hill".to_vec())]);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn scan_bounded_default_implementation_works() {
+        let mut store = DefaultImplementationStorage(MockStorage::new());
+        store.set(b"ant", b"hill").0.unwrap();
+        store.set(b"foo", b"bar").0.unwrap();
+        store.set(b"food", b"bank").0.unwrap();
+
+        let records = store
+            .scan_bounded(Some(b"f"), None, Order::Descending, 1, u64::MAX)
+            .0
+            .unwrap();
+        assert_eq!(records, vec![(b"food".to_vec(), b"bank".to_vec())]);
+        let records = store
+            .scan_bounded(None, None, Order::Ascending, 10, u64::MAX)
+            .0
+            .unwrap();
+        assert_eq!(records.len(), 3);
+        // stops once the gas limit is reached
+        let (result, gas_info) = store.scan_bounded(None, None, Order::Ascending, 10, 0);
+        assert_eq!(result.unwrap().len(), 0);
+        assert_eq!(gas_info.externally_used, GAS_COST_RANGE);
+        let (result, _) = store.scan_bounded(None, None, Order::Ascending, 10, GAS_COST_RANGE);
+        assert_eq!(result.unwrap().len(), 1);
+    }
 }
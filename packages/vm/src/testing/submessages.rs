@@ -0,0 +1,290 @@
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "cosmwasm_1_4")]
+use cosmwasm_std::ReplyOn;
+use cosmwasm_std::{
+    Binary, CosmosMsg, CustomMsg, Empty, Env, Reply, SubMsg, SubMsgResponse, SubMsgResult,
+};
+
+use super::calls::reply;
+use super::mock::mock_env;
+use crate::calls::reply_for_sub_msg;
+use crate::instance::Instance;
+use crate::{BackendApi, Querier, Storage};
+
+type MsgHandler<U> = Box<dyn FnMut(&CosmosMsg<U>) -> Option<Result<SubMsgResponse, String>>>;
+
+/// The outcome of [`SubMsgDispatcher::dispatch`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DispatchedMessages<U = Empty> {
+    /// All messages passed to the handlers, including the ones emitted by `reply`
+    pub messages: Vec<CosmosMsg<U>>,
+    /// All replies sent to the contract, in order
+    pub replies: Vec<Reply>,
+    /// The data set by the last `reply` or forwarded by a `ReplyOn::ForwardData` submessage.
+    /// If this is set, it replaces the data of the original response.
+    pub data: Option<Binary>,
+}
+
+/// Executes the submessages returned by a contract and sends the replies back to it,
+/// such that submessage flows can be tested at the Wasm level.
+///
+/// The execution of the messages is simulated by handlers registered via
+/// [`SubMsgDispatcher::with_handler`]. They are tried in the order of registration and the
+/// first one returning `Some` determines the result of the message. Messages that are not
+/// handled by any handler fail.
+///
+/// Messages returned by `reply` are dispatched as well. Unlike on a chain, contract state
+/// is not reverted when a submessage or `reply` fails.
+pub struct SubMsgDispatcher<U = Empty> {
+    handlers: Vec<MsgHandler<U>>,
+    env: Env,
+}
+
+impl<U> Default for SubMsgDispatcher<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U> SubMsgDispatcher<U> {
+    /// Creates a dispatcher without handlers that calls `reply` with [`mock_env`]
+    pub fn new() -> Self {
+        SubMsgDispatcher {
+            handlers: Vec::new(),
+            env: mock_env(),
+        }
+    }
+
+    /// Registers a handler simulating the execution of messages. It returns `None` for
+    /// messages it does not handle, the response for successfully executed messages and
+    /// an error message for failing ones.
+    pub fn with_handler<H>(mut self, handler: H) -> Self
+    where
+        H: FnMut(&CosmosMsg<U>) -> Option<Result<SubMsgResponse, String>> + 'static,
+    {
+        self.handlers.push(Box::new(handler));
+        self
+    }
+
+    /// Sets the environment used for `reply` calls
+    pub fn with_env(mut self, env: Env) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Executes the messages, e.g. `Response::messages`, and calls `reply` as requested
+    /// by their `reply_on`.
+    ///
+    /// Returns an error if a message without error reply fails or if `reply` returns an error.
+    pub fn dispatch<A, S, Q>(
+        &mut self,
+        instance: &mut Instance<A, S, Q>,
+        messages: Vec<SubMsg<U>>,
+    ) -> Result<DispatchedMessages<U>, String>
+    where
+        A: BackendApi + 'static,
+        S: Storage + 'static,
+        Q: Querier + 'static,
+        U: DeserializeOwned + CustomMsg,
+    {
+        let mut dispatched = DispatchedMessages {
+            messages: Vec::new(),
+            replies: Vec::new(),
+            data: None,
+        };
+        self.dispatch_into(instance, messages, &mut dispatched)?;
+        Ok(dispatched)
+    }
+
+    fn dispatch_into<A, S, Q>(
+        &mut self,
+        instance: &mut Instance<A, S, Q>,
+        messages: Vec<SubMsg<U>>,
+        dispatched: &mut DispatchedMessages<U>,
+    ) -> Result<(), String>
+    where
+        A: BackendApi + 'static,
+        S: Storage + 'static,
+        Q: Querier + 'static,
+        U: DeserializeOwned + CustomMsg,
+    {
+        for sub_msg in messages {
+            let result: SubMsgResult = self.handle(&sub_msg.msg).into();
+            let reply_msg = reply_for_sub_msg(&sub_msg, result.clone());
+            dispatched.messages.push(sub_msg.msg);
+
+            #[cfg(feature = "cosmwasm_1_4")]
+            if sub_msg.reply_on == ReplyOn::ForwardData {
+                if let SubMsgResult::Ok(response) = &result {
+                    dispatched.data = response.data.clone();
+                }
+            }
+            let msg = match reply_msg {
+                Some(msg) => msg,
+                None => {
+                    if let SubMsgResult::Err(err) = result {
+                        return Err(err);
+                    }
+                    continue;
+                }
+            };
+            dispatched.replies.push(msg.clone());
+            let response = reply::<_, _, _, U>(instance, self.env.clone(), msg).into_result()?;
+            if response.data.is_some() {
+                dispatched.data = response.data;
+            }
+            self.dispatch_into(instance, response.messages, dispatched)?;
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, msg: &CosmosMsg<U>) -> Result<SubMsgResponse, String>
+    where
+        U: CustomMsg,
+    {
+        self.handlers
+            .iter_mut()
+            .find_map(|handler| handler(msg))
+            .unwrap_or_else(|| Err(format!("No handler for message: {:?}", msg)))
+    }
+}
+
+#[cfg(all(test, feature = "stargate"))]
+mod tests {
+    use super::*;
+    use crate::calls::call_instantiate;
+    use crate::testing::{
+        ibc_packet_receive, mock_ibc_channel, mock_info, mock_instance, IbcHandshake, MockApi,
+        MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{Event, IbcBasicResponse, IbcOrder, WasmMsg};
+
+    static CONTRACT: &[u8] = include_bytes!("../../testdata/ibc_reflect.wasm");
+    const IBC_VERSION: &str = "ibc-reflect-v1";
+    const REFLECT_ADDR: &str = "reflect-acct-1";
+
+    /// Creates a channel, which makes ibc_reflect instantiate a reflect contract
+    /// with reply_on success
+    fn setup() -> (
+        Instance<MockApi, MockStorage, MockQuerier>,
+        IbcHandshake,
+        Vec<SubMsg>,
+    ) {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        let info = mock_info("creator", &[]);
+        let msg = br#"{"reflect_code_id":77}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+        let channel = mock_ibc_channel("channel-0", IbcOrder::Ordered, IBC_VERSION);
+        let mut handshake = IbcHandshake::init(channel);
+        let res: IbcBasicResponse = handshake.run(&mut instance).unwrap();
+        (instance, handshake, res.messages)
+    }
+
+    fn instantiate_handler(msg: &CosmosMsg) -> Option<Result<SubMsgResponse, String>> {
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Instantiate { code_id: 77, .. }) => Some(Ok(SubMsgResponse {
+                events: vec![Event::new("instantiate")
+                    .add_attribute("code_id", "77")
+                    .add_attribute("_contract_address", REFLECT_ADDR)],
+                data: None,
+                msg_responses: vec![],
+            })),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn dispatch_sends_replies() {
+        let (mut instance, mut handshake, messages) = setup();
+        assert_eq!(messages.len(), 1);
+
+        let mut dispatcher = SubMsgDispatcher::new().with_handler(instantiate_handler);
+        let dispatched = dispatcher.dispatch(&mut instance, messages).unwrap();
+        assert_eq!(dispatched.messages.len(), 1);
+        assert_eq!(dispatched.replies.len(), 1);
+        assert!(dispatched.replies[0].result.is_ok());
+        assert_eq!(dispatched.data, None);
+
+        // the reply registered the reflect account for the channel
+        let msg = handshake
+            .packet_recv(&serde_json::json!({"who_am_i":{}}))
+            .unwrap();
+        let res = ibc_packet_receive::<_, _, _, Empty>(&mut instance, mock_env(), msg).unwrap();
+        let ack = String::from_utf8(res.acknowledgement.to_vec()).unwrap();
+        assert!(ack.contains(REFLECT_ADDR), "{}", ack);
+    }
+
+    #[test]
+    fn dispatch_tries_handlers_in_order() {
+        let (mut instance, _, messages) = setup();
+
+        let mut dispatcher = SubMsgDispatcher::new()
+            .with_handler(|_| None)
+            .with_handler(instantiate_handler)
+            .with_handler(|_| Some(Err("must not be called".to_string())));
+        let dispatched = dispatcher.dispatch(&mut instance, messages).unwrap();
+        assert!(dispatched.replies[0].result.is_ok());
+    }
+
+    #[test]
+    fn dispatch_fails_for_failing_message_without_error_reply() {
+        let (mut instance, _, messages) = setup();
+
+        // reply_on success does not catch the error
+        let mut dispatcher =
+            SubMsgDispatcher::new().with_handler(|_| Some(Err("instantiation failed".to_string())));
+        let err = dispatcher
+            .dispatch(&mut instance, messages.clone())
+            .unwrap_err();
+        assert_eq!(err, "instantiation failed");
+
+        // unhandled messages fail
+        let err = SubMsgDispatcher::new()
+            .dispatch(&mut instance, messages)
+            .unwrap_err();
+        assert!(
+            err.starts_with("No handler for message: Wasm(Instantiate"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn dispatch_returns_reply_errors() {
+        let (mut instance, _, messages) = setup();
+
+        // the first reply consumes the pending channel, so replaying the submessage fails
+        let mut dispatcher = SubMsgDispatcher::new().with_handler(instantiate_handler);
+        dispatcher
+            .dispatch(&mut instance, messages.clone())
+            .unwrap();
+        let err = dispatcher.dispatch(&mut instance, messages).unwrap_err();
+        assert!(err.contains("not found"), "{}", err);
+    }
+
+    #[test]
+    #[cfg(feature = "cosmwasm_1_4")]
+    fn dispatch_forwards_data() {
+        let (mut instance, _, _) = setup();
+
+        let msg: SubMsg = SubMsg {
+            reply_on: ReplyOn::ForwardData,
+            ..SubMsg::new(WasmMsg::ClearAdmin {
+                contract_addr: REFLECT_ADDR.to_string(),
+            })
+        };
+        let mut dispatcher = SubMsgDispatcher::new().with_handler(|_| {
+            Some(Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(Binary::from(b"forwarded")),
+                msg_responses: vec![],
+            }))
+        });
+        let dispatched = dispatcher.dispatch(&mut instance, vec![msg]).unwrap();
+        assert_eq!(dispatched.replies, vec![]);
+        assert_eq!(dispatched.data, Some(Binary::from(b"forwarded")));
+    }
+}
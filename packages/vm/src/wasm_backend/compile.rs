@@ -3,7 +3,7 @@ use std::sync::Arc;
 use wasmer::{Engine, Module, ModuleMiddleware};
 
 use crate::errors::VmResult;
-use crate::wasm_backend::make_engine;
+use crate::wasm_backend::{make_engine, make_engine_with_operator_cost};
 
 /// Compiles a given Wasm bytecode into a module.
 pub fn compile(
@@ -15,6 +15,18 @@ pub fn compile(
     Ok((engine, module))
 }
 
+/// Compiles a given Wasm bytecode into a module that charges `wasm_operator_cost`
+/// for each executed Wasm operator.
+pub fn compile_with_operator_cost(
+    code: &[u8],
+    middlewares: &[Arc<dyn ModuleMiddleware>],
+    wasm_operator_cost: u64,
+) -> VmResult<(Engine, Module)> {
+    let engine = make_engine_with_operator_cost(middlewares, wasm_operator_cost);
+    let module = Module::new(&engine, code)?;
+    Ok((engine, module))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
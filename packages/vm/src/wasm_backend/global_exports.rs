@@ -0,0 +1,50 @@
+use wasmer::{ExportIndex, FunctionMiddleware, LocalFunctionIndex, ModuleMiddleware, Mutability};
+use wasmer_types::ModuleInfo;
+
+/// The prefix of the export names added by [`GlobalExports`]
+const GLOBAL_EXPORT_PREFIX: &str = "cosmwasm_global_";
+
+/// A middleware that exports all mutable globals of a module which are not exported yet.
+///
+/// Globals which are not exported cannot be accessed from the host. Exporting them allows
+/// restoring the globals of an instance to their initial values before it is reused.
+/// The export names are [`GLOBAL_EXPORT_PREFIX`] followed by the global index.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct GlobalExports {}
+
+impl ModuleMiddleware for GlobalExports {
+    fn generate_function_middleware(&self, _: LocalFunctionIndex) -> Box<dyn FunctionMiddleware> {
+        Box::new(NoopFunctionMiddleware {})
+    }
+
+    fn transform_module_info(&self, module_info: &mut ModuleInfo) {
+        let exported: Vec<_> = module_info
+            .exports
+            .values()
+            .filter_map(|export| match export {
+                ExportIndex::Global(index) => Some(*index),
+                _ => None,
+            })
+            .collect();
+        let unexported: Vec<_> = module_info
+            .globals
+            .iter()
+            .filter(|(index, global)| {
+                global.mutability == Mutability::Var && !exported.contains(index)
+            })
+            .map(|(index, _)| index)
+            .collect();
+        for index in unexported {
+            module_info.exports.insert(
+                format!("{}{}", GLOBAL_EXPORT_PREFIX, index.as_u32()),
+                ExportIndex::Global(index),
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NoopFunctionMiddleware {}
+
+impl FunctionMiddleware for NoopFunctionMiddleware {}
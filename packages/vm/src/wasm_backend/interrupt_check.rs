@@ -0,0 +1,186 @@
+use std::sync::Mutex;
+
+use wasmer::wasmparser::{BlockType, Operator};
+use wasmer::{
+    ExportIndex, FunctionMiddleware, GlobalInit, GlobalType, LocalFunctionIndex, MiddlewareError,
+    MiddlewareReaderState, ModuleMiddleware, Mutability, Type,
+};
+use wasmer_types::{GlobalIndex, ModuleInfo};
+
+/// The export name of the interrupt flag added by [`InterruptCheck`]
+pub const INTERRUPT_FLAG_EXPORT: &str = "cosmwasm_interrupted";
+
+/// A middleware that traps at the start of functions and loop iterations once the host sets
+/// an interrupt flag, such that the host can stop code running longer than allowed.
+///
+/// The flag is a mutable i32 global added to the module and exported as [`INTERRUPT_FLAG_EXPORT`].
+/// Similar to the gas metering, the inserted code only reads this global, so no call into the
+/// host is needed and the function indices of the module stay the same.
+///
+/// This must be the last middleware, such that the inserted code is not charged gas and the
+/// global is not exported a second time by [`GlobalExports`](super::global_exports::GlobalExports).
+#[derive(Debug, Default)]
+pub struct InterruptCheck {
+    /// The index of the interrupt flag global
+    flag: Mutex<Option<GlobalIndex>>,
+}
+
+impl ModuleMiddleware for InterruptCheck {
+    fn generate_function_middleware(
+        &self,
+        _local_function_index: LocalFunctionIndex,
+    ) -> Box<dyn FunctionMiddleware> {
+        Box::new(FunctionInterruptCheck {
+            flag: self.flag.lock().unwrap().expect(
+                "InterruptCheck::generate_function_middleware: module info not transformed",
+            ),
+            started: false,
+        })
+    }
+
+    fn transform_module_info(&self, module_info: &mut ModuleInfo) {
+        let mut flag = self.flag.lock().unwrap();
+        if flag.is_some() {
+            panic!("InterruptCheck::transform_module_info: Attempting to use an `InterruptCheck` middleware from multiple modules.");
+        }
+        // Overwriting a contract export would break the contract and hand its global to the watchdog.
+        // Such contracts are rejected by `check_wasm` before they are compiled.
+        if module_info.exports.contains_key(INTERRUPT_FLAG_EXPORT) {
+            panic!("InterruptCheck::transform_module_info: The module exports `{INTERRUPT_FLAG_EXPORT}` already.");
+        }
+
+        let index = module_info
+            .globals
+            .push(GlobalType::new(Type::I32, Mutability::Var));
+        module_info
+            .global_initializers
+            .push(GlobalInit::I32Const(0));
+        module_info.exports.insert(
+            INTERRUPT_FLAG_EXPORT.to_string(),
+            ExportIndex::Global(index),
+        );
+
+        *flag = Some(index);
+    }
+}
+
+#[derive(Debug)]
+struct FunctionInterruptCheck {
+    flag: GlobalIndex,
+    /// Whether the check at the start of the function was inserted already
+    started: bool,
+}
+
+impl FunctionInterruptCheck {
+    fn insert_check(&self, state: &mut MiddlewareReaderState<'_>) {
+        state.extend(&[
+            // if flag != 0 { unreachable }
+            Operator::GlobalGet {
+                global_index: self.flag.as_u32(),
+            },
+            Operator::If {
+                blockty: BlockType::Empty,
+            },
+            Operator::Unreachable,
+            Operator::End,
+        ]);
+    }
+}
+
+impl FunctionMiddleware for FunctionInterruptCheck {
+    fn feed<'a>(
+        &mut self,
+        operator: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        if !self.started {
+            self.started = true;
+            self.insert_check(state);
+        }
+        let is_loop = matches!(operator, Operator::Loop { .. });
+        state.push_operator(operator);
+        if is_loop {
+            self.insert_check(state);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmer::{
+        imports, Function, FunctionEnv, FunctionEnvMut, Global, Instance, Module, Store, Value,
+    };
+    use wasmer_middlewares::metering::set_remaining_points;
+
+    use crate::wasm_backend::make_engine;
+
+    #[derive(Default)]
+    struct Counter {
+        calls: u32,
+        /// The number of the call setting the flag, never set if zero
+        interrupt_after: u32,
+        flag: Option<Global>,
+    }
+
+    #[test]
+    fn interrupt_check_traps_once_flag_is_set() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "count" (func $count (result i32)))
+                (func $double (param i32) (result i32)
+                    local.get 0
+                    i32.const 2
+                    i32.mul)
+                (func (export "run") (param $n i32) (result i32)
+                    (local $sum i32)
+                    (loop $continue
+                        (local.set $sum (i32.add (local.get $sum) (call $double (call $count))))
+                        (local.tee $n (i32.sub (local.get $n) (i32.const 1)))
+                        br_if $continue)
+                    local.get $sum)
+            )"#,
+        )
+        .unwrap();
+
+        let mut store = Store::new(make_engine(&[]));
+        let module = Module::new(&store, wasm).unwrap();
+        let env = FunctionEnv::new(&mut store, Counter::default());
+        let imports = imports! {
+            "env" => {
+                "count" => Function::new_typed_with_env(&mut store, &env, |mut env: FunctionEnvMut<Counter>| {
+                    let (counter, mut store) = env.data_and_store_mut();
+                    counter.calls += 1;
+                    if counter.calls == counter.interrupt_after {
+                        counter.flag.as_ref().unwrap().set(&mut store, Value::I32(1)).unwrap();
+                    }
+                    1
+                }),
+            },
+        };
+        let instance = Instance::new(&mut store, &module, &imports).unwrap();
+        set_remaining_points(&mut store, &instance, u64::MAX);
+        let run = instance.exports.get_function("run").unwrap();
+        let flag = instance.exports.get_global(INTERRUPT_FLAG_EXPORT).unwrap();
+
+        // runs normally while the flag is not set
+        let result = run.call(&mut store, &[1000.into()]).unwrap();
+        assert_eq!(result[0].unwrap_i32(), 2000);
+        assert_eq!(env.as_ref(&store).calls, 1000);
+
+        // traps in the middle of the loop once the flag is set
+        let counter = env.as_mut(&mut store);
+        counter.calls = 0;
+        counter.interrupt_after = 10;
+        counter.flag = Some(flag.clone());
+        let err = run.call(&mut store, &[1000.into()]).unwrap_err();
+        assert!(err.message().contains("unreachable"), "{err}");
+        assert_eq!(env.as_ref(&store).calls, 10);
+
+        // traps right at the start while the flag is set
+        let err = run.call(&mut store, &[1000.into()]).unwrap_err();
+        assert!(err.message().contains("unreachable"), "{err}");
+        assert_eq!(env.as_ref(&store).calls, 10);
+    }
+}
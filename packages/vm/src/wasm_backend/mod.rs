@@ -1,8 +1,13 @@
 mod compile;
 mod gatekeeper;
+mod global_exports;
+mod interrupt_check;
 mod limiting_tunables;
 mod store;
 
-pub use compile::compile;
+pub use compile::{compile, compile_with_operator_cost};
+pub(crate) use interrupt_check::INTERRUPT_FLAG_EXPORT;
 pub use limiting_tunables::LimitingTunables;
-pub use store::{make_engine, make_runtime_store, make_store_with_engine};
+pub use store::{
+    make_engine, make_engine_with_operator_cost, make_runtime_store, make_store_with_engine,
+};
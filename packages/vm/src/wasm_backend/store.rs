@@ -9,9 +9,12 @@ use wasmer::{
 };
 use wasmer_middlewares::Metering;
 
+use crate::environment::GasSchedule;
 use crate::size::Size;
 
 use super::gatekeeper::Gatekeeper;
+use super::global_exports::GlobalExports;
+use super::interrupt_check::InterruptCheck;
 use super::limiting_tunables::LimitingTunables;
 
 /// WebAssembly linear memory objects have sizes measured in pages. Each page
@@ -20,21 +23,25 @@ use super::limiting_tunables::LimitingTunables;
 /// https://github.com/WebAssembly/memory64/blob/master/proposals/memory64/Overview.md
 const MAX_WASM_PAGES: u32 = 65536;
 
-fn cost(_operator: &Operator) -> u64 {
-    // A flat fee for each operation
-    // The target is 1 Teragas per millisecond (see GAS.md).
-    //
-    // In https://github.com/CosmWasm/cosmwasm/pull/1042 a profiler is developed to
-    // identify runtime differences between different Wasm operation, but this is not yet
-    // precise enough to derive insights from it.
-    150_000
+/// Creates an engine with the default compiler, metering Wasm operators with
+/// the cost of the default [`GasSchedule`].
+pub fn make_engine(middlewares: &[Arc<dyn ModuleMiddleware>]) -> Engine {
+    make_engine_with_operator_cost(middlewares, GasSchedule::default().wasm_operator_cost)
 }
 
-/// Creates an engine with the default compiler.
-pub fn make_engine(middlewares: &[Arc<dyn ModuleMiddleware>]) -> Engine {
+/// Creates an engine with the default compiler, charging `wasm_operator_cost`
+/// for each executed Wasm operator.
+pub fn make_engine_with_operator_cost(
+    middlewares: &[Arc<dyn ModuleMiddleware>],
+    wasm_operator_cost: u64,
+) -> Engine {
     let gas_limit = 0;
     let deterministic = Arc::new(Gatekeeper::default());
-    let metering = Arc::new(Metering::new(gas_limit, cost));
+    let global_exports = Arc::new(GlobalExports::default());
+    let metering = Arc::new(Metering::new(gas_limit, move |_operator: &Operator| {
+        wasm_operator_cost
+    }));
+    let interrupt_check = Arc::new(InterruptCheck::default());
 
     #[cfg(feature = "cranelift")]
     let mut compiler = Cranelift::default();
@@ -46,7 +53,9 @@ pub fn make_engine(middlewares: &[Arc<dyn ModuleMiddleware>]) -> Engine {
         compiler.push_middleware(middleware.clone());
     }
     compiler.push_middleware(deterministic);
+    compiler.push_middleware(global_exports);
     compiler.push_middleware(metering);
+    compiler.push_middleware(interrupt_check);
     compiler.into()
 }
 
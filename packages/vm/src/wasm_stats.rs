@@ -0,0 +1,203 @@
+use crate::errors::{VmError, VmResult};
+use crate::static_analysis::deserialize_wasm;
+
+/// The size of a section of a Wasm blob
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SectionSize {
+    /// The name of the section, e.g. "code". Custom sections have the
+    /// form `custom:<name>`.
+    pub name: String,
+    /// The size of the section content in bytes, excluding the section header
+    pub size: usize,
+}
+
+/// Size statistics of a Wasm blob, which can be compared between releases
+/// of a contract to track size regressions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WasmStats {
+    /// The size of the Wasm blob in bytes
+    pub size: usize,
+    /// All sections in the order they appear in the Wasm blob
+    pub sections: Vec<SectionSize>,
+    /// The number of imports, including functions, memories, tables and globals
+    pub imports: usize,
+    /// The number of functions defined in the contract, excluding imports
+    pub functions: usize,
+    /// The number of exports
+    pub exports: usize,
+    /// The number of data segments
+    pub data_segments: usize,
+    /// The total size of all data segments in bytes. This data is copied into the
+    /// memory of every instance.
+    pub data_size: usize,
+    /// The initial size of the memory in pages of 64 KiB
+    pub initial_memory_pages: u32,
+}
+
+/// Collects size statistics of a Wasm blob. This does not check if the blob is a
+/// valid contract, see [`validate_wasm`](crate::validate_wasm) for that.
+pub fn wasm_stats(wasm_code: &[u8]) -> VmResult<WasmStats> {
+    let module = deserialize_wasm(wasm_code)?;
+    let data_segments = module
+        .data_section()
+        .map_or(&[][..], |section| section.entries());
+    Ok(WasmStats {
+        size: wasm_code.len(),
+        sections: section_sizes(wasm_code)?,
+        imports: module
+            .import_section()
+            .map_or(0, |section| section.entries().len()),
+        functions: module
+            .function_section()
+            .map_or(0, |section| section.entries().len()),
+        exports: module
+            .export_section()
+            .map_or(0, |section| section.entries().len()),
+        data_segments: data_segments.len(),
+        data_size: data_segments
+            .iter()
+            .map(|segment| segment.value().len())
+            .sum(),
+        initial_memory_pages: module
+            .memory_section()
+            .and_then(|section| section.entries().first())
+            .map_or(0, |memory| memory.limits().initial()),
+    })
+}
+
+/// Reads the section headers of the binary format
+fn section_sizes(wasm_code: &[u8]) -> VmResult<Vec<SectionSize>> {
+    let invalid = || VmError::static_validation_err("Wasm bytecode has an invalid section header");
+
+    // skip magic bytes and version
+    let mut offset = 8;
+    let mut sections = vec![];
+    while offset < wasm_code.len() {
+        let id = wasm_code[offset];
+        offset += 1;
+        let size = read_leb128_u32(wasm_code, &mut offset).ok_or_else(invalid)? as usize;
+        let end = offset.checked_add(size).ok_or_else(invalid)?;
+        let content = wasm_code.get(offset..end).ok_or_else(invalid)?;
+        let name = match id {
+            0 => {
+                let mut name_offset = 0;
+                let name_len =
+                    read_leb128_u32(content, &mut name_offset).ok_or_else(invalid)? as usize;
+                let name = content
+                    .get(name_offset..name_offset + name_len)
+                    .ok_or_else(invalid)?;
+                format!("custom:{}", String::from_utf8_lossy(name))
+            }
+            1 => "type".to_string(),
+            2 => "import".to_string(),
+            3 => "function".to_string(),
+            4 => "table".to_string(),
+            5 => "memory".to_string(),
+            6 => "global".to_string(),
+            7 => "export".to_string(),
+            8 => "start".to_string(),
+            9 => "element".to_string(),
+            10 => "code".to_string(),
+            11 => "data".to_string(),
+            12 => "datacount".to_string(),
+            _ => format!("unknown:{}", id),
+        };
+        sections.push(SectionSize { name, size });
+        offset = end;
+    }
+    Ok(sections)
+}
+
+fn read_leb128_u32(data: &[u8], offset: &mut usize) -> Option<u32> {
+    let mut result: u32 = 0;
+    for shift in (0..35).step_by(7) {
+        let byte = *data.get(*offset)?;
+        *offset += 1;
+        result |= u32::from(byte & 0x7f).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+
+    #[test]
+    fn wasm_stats_works() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "abort" (func (param i32)))
+                (memory 3)
+                (export "memory" (memory 0))
+                (func (export "allocate") (param i32) (result i32) local.get 0)
+                (func (export "deallocate") (param i32))
+                (data (i32.const 1) "hello")
+                (data (i32.const 16) "world!")
+                (@custom "my_section" "foo")
+            )"#,
+        )
+        .unwrap();
+        let stats = wasm_stats(&wasm).unwrap();
+        assert_eq!(stats.size, wasm.len());
+        assert_eq!(stats.imports, 1);
+        assert_eq!(stats.functions, 2);
+        assert_eq!(stats.exports, 3);
+        assert_eq!(stats.data_segments, 2);
+        assert_eq!(stats.data_size, 11);
+        assert_eq!(stats.initial_memory_pages, 3);
+
+        let names: Vec<_> = stats
+            .sections
+            .iter()
+            .map(|section| section.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "type",
+                "import",
+                "function",
+                "memory",
+                "export",
+                "code",
+                "data",
+                "custom:my_section"
+            ]
+        );
+        // the custom section contains the name and the payload
+        assert_eq!(stats.sections[7].size, 1 + 10 + 3);
+        // section contents plus a header of at least 2 bytes per section
+        let sections_size: usize = stats.sections.iter().map(|section| section.size).sum();
+        assert!(sections_size + 8 + 2 * stats.sections.len() <= wasm.len());
+    }
+
+    #[test]
+    fn wasm_stats_works_for_contract() {
+        let stats = wasm_stats(CONTRACT).unwrap();
+        assert_eq!(stats.size, CONTRACT.len());
+        assert!(stats.functions > 0);
+        assert!(stats.imports > 0);
+        assert!(stats.initial_memory_pages > 0);
+        let code = stats
+            .sections
+            .iter()
+            .find(|section| section.name == "code")
+            .unwrap();
+        assert!(code.size > 0);
+    }
+
+    #[test]
+    fn wasm_stats_fails_for_invalid_wasm() {
+        match wasm_stats(&[0x00, 0x61, 0x73, 0x6d, 0x01, 0x00]).unwrap_err() {
+            VmError::StaticValidationErr { msg, .. } => {
+                assert!(msg.contains("could not be deserialized"))
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Interruption of contract calls that exceed their maximum execution time
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI32, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use wasmer::{AsStoreMut, Extern, Instance as WasmerInstance};
+use wasmer_vm::VMExtern;
+
+use crate::errors::{VmError, VmResult};
+use crate::wasm_backend::INTERRUPT_FLAG_EXPORT;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Running,
+    /// The time is up but the call is still running
+    Interrupted,
+    Finished,
+}
+
+/// A pointer to the interrupt flag of an instance
+#[derive(Clone, Copy)]
+struct InterruptFlag(*const AtomicI32);
+
+// SAFETY: the pointer is only dereferenced while the call is running, see `Watch`
+unsafe impl Send for InterruptFlag {}
+unsafe impl Sync for InterruptFlag {}
+
+impl InterruptFlag {
+    /// # Safety
+    ///
+    /// The instance must be alive.
+    unsafe fn set(&self, interrupted: bool) {
+        (*self.0).store(interrupted.into(), Ordering::SeqCst)
+    }
+}
+
+/// A watched call, shared between the calling thread and the timer thread
+struct Watch {
+    flag: InterruptFlag,
+    /// Once this is finished, the flag must not be accessed anymore
+    state: Mutex<State>,
+}
+
+impl Watch {
+    /// Sets the interrupt flag if the call is still running. Called by the timer thread.
+    fn interrupt(&self) {
+        let mut state = self.state.lock().unwrap();
+        if *state == State::Running {
+            *state = State::Interrupted;
+            // SAFETY: the state is not finished and we hold its lock, so the call is still
+            // running and `Watchdog::stop` waits for us before the call can return.
+            unsafe { self.flag.set(true) };
+        }
+    }
+}
+
+/// The deadlines of all watched calls, ordered by time. The second part of the key tells
+/// apart calls with the same deadline.
+type Deadlines = BTreeMap<(Instant, u64), Arc<Watch>>;
+
+/// A single thread interrupting the watched calls of all instances once their time is up
+struct Timer {
+    deadlines: Mutex<Deadlines>,
+    /// Wakes up the thread when a deadline was added
+    condvar: Condvar,
+    next_id: AtomicU64,
+}
+
+impl Timer {
+    /// Returns the timer shared by the process, starting its thread on first use
+    fn shared() -> VmResult<Arc<Timer>> {
+        static TIMER: OnceLock<Result<Arc<Timer>, String>> = OnceLock::new();
+        TIMER
+            .get_or_init(|| {
+                let timer = Arc::new(Timer {
+                    deadlines: Mutex::new(BTreeMap::new()),
+                    condvar: Condvar::new(),
+                    next_id: AtomicU64::new(0),
+                });
+                let thread_timer = Arc::clone(&timer);
+                thread::Builder::new()
+                    .name("cosmwasm-watchdog".to_string())
+                    .spawn(move || thread_timer.run())
+                    .map(|_| timer)
+                    .map_err(|err| err.to_string())
+            })
+            .clone()
+            .map_err(|err| VmError::generic_err(format!("Could not start watchdog: {err}")))
+    }
+
+    fn add(&self, deadline: Instant, watch: Arc<Watch>) -> (Instant, u64) {
+        let key = (deadline, self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.deadlines.lock().unwrap().insert(key, watch);
+        self.condvar.notify_one();
+        key
+    }
+
+    fn remove(&self, key: &(Instant, u64)) {
+        self.deadlines.lock().unwrap().remove(key);
+    }
+
+    fn run(&self) {
+        let mut deadlines = self.deadlines.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            while let Some(entry) = deadlines.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+                entry.remove().interrupt();
+            }
+            deadlines = match deadlines.keys().next() {
+                Some((next, _)) => {
+                    let timeout = next.saturating_duration_since(now);
+                    self.condvar.wait_timeout(deadlines, timeout).unwrap().0
+                }
+                None => self.condvar.wait(deadlines).unwrap(),
+            };
+        }
+    }
+}
+
+/// Interrupts a running contract call from another thread once its time is up.
+///
+/// Running Wasm code cannot be stopped directly. Instead, the watchdog sets the interrupt flag
+/// of the instance, which is checked by code inserted at the start of functions and loop
+/// iterations (see `InterruptCheck`). This works no matter if the contract calls imports or not.
+/// All watchdogs share a single timer thread, which is started on first use.
+pub(crate) struct Watchdog {
+    timer: Arc<Timer>,
+    key: (Instant, u64),
+    /// Taken once the call finished
+    watch: Option<Arc<Watch>>,
+}
+
+impl Watchdog {
+    /// Starts watching a call into the given instance, which must belong to `store`.
+    ///
+    /// The watchdog must be finished or dropped before the call returns, see [`Watch`].
+    pub fn start(
+        store: &mut impl AsStoreMut,
+        instance: &WasmerInstance,
+        timeout: Duration,
+    ) -> VmResult<Self> {
+        let flag = match instance.exports.get_extern(INTERRUPT_FLAG_EXPORT) {
+            Some(global @ Extern::Global(_)) => match global.to_vm_extern() {
+                VMExtern::Global(handle) => handle.get(store.objects_mut()).vmglobal(),
+                _ => unreachable!("Extern::Global always converts into VMExtern::Global"),
+            },
+            _ => {
+                return Err(VmError::generic_err(
+                    "Instance does not export the interrupt flag",
+                ))
+            }
+        };
+        // SAFETY: the global is a boxed definition owned by the objects of the store. Those are
+        // only freed when the store is dropped, which cannot happen while the store is borrowed
+        // by the running call. The wasmer instance only holds handles into those objects, so
+        // dropping it does not free the global either. Thus the pointer stays valid as long as
+        // the call runs. The flag is stored as i32 at the start of the 16 byte aligned global.
+        let flag = InterruptFlag(flag.as_ptr() as *const AtomicI32);
+
+        let timer = Timer::shared()?;
+        let watch = Arc::new(Watch {
+            flag,
+            state: Mutex::new(State::Running),
+        });
+        let key = timer.add(Instant::now() + timeout, Arc::clone(&watch));
+        Ok(Watchdog {
+            timer,
+            key,
+            watch: Some(watch),
+        })
+    }
+
+    /// Marks the end of the watched call and returns true iff the call was interrupted.
+    ///
+    /// This clears the interrupt flag, such that the instance can be called again.
+    /// After this returns, the flag of the instance is not accessed anymore.
+    pub fn finish(mut self) -> bool {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> bool {
+        let Some(watch) = self.watch.take() else {
+            return false;
+        };
+        // The timer thread sets the flag while holding this lock only, so it does not
+        // access the flag anymore once the state is finished
+        let interrupted = {
+            let mut state = watch.state.lock().unwrap();
+            let interrupted = *state == State::Interrupted;
+            *state = State::Finished;
+            interrupted
+        };
+        self.timer.remove(&self.key);
+        if interrupted {
+            // SAFETY: the call just finished, so the store and the flag are still alive
+            unsafe { watch.flag.set(false) };
+        }
+        interrupted
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}